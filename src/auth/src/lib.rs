@@ -31,12 +31,17 @@ pub use common::{
 };
 pub use permission::{
     ALL_ACTIONS, AccessMode, DASHBOARD_DELETE, DASHBOARD_QUERY, DASHBOARD_SAVE,
-    DefaultPermissionChecker, INFLUXDB_WRITE, JAEGER_QUERY, LOG_QUERY, LOG_WRITE, OPENTSDB_WRITE,
-    OTLP_WRITE, PIPELINE_DELETE, PIPELINE_INSERT, PIPELINE_QUERY, PROM_STORE_READ,
-    PROM_STORE_WRITE, PROMQL_QUERY, PermissionAction, PermissionChecker, PermissionReq,
-    PermissionResp, PermissionTableTarget, PermissionTableTargets, SEMANTIC_GRAPH_QUERY,
+    DefaultPermissionChecker, INFLUXDB_QUERY, INFLUXDB_WRITE, JAEGER_QUERY, LOG_QUERY, LOG_WRITE,
+    ManagedPermissionChecker, OPENTSDB_QUERY, OPENTSDB_WRITE, OTLP_WRITE, PIPELINE_DELETE,
+    PIPELINE_INSERT, PIPELINE_QUERY, PROM_STORE_READ, PROM_STORE_WRITE, PROMQL_QUERY,
+    PermissionAction, PermissionChecker, PermissionReq, PermissionResp, PermissionTableTarget,
+    PermissionTableTargets, SEMANTIC_GRAPH_QUERY, TEMPO_QUERY, TableGrant, TablePrivilege,
+};
+pub use user_info::{ManagedUserInfo, UserInfo, refresh_user_grants};
+pub use user_provider::managed_user_provider::{
+    ManagedUser, ManagedUserProvider, ManagedUserSource, ManagedUserSourceRef,
+    managed_user_password_verifiers,
 };
-pub use user_info::UserInfo;
 pub use user_provider::static_user_provider::StaticUserProvider;
 pub use user_provider::{PgAuthInfo, UserProvider};
 
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashSet;
 use std::fmt::Debug;
 use std::sync::Arc;
 
@@ -19,10 +20,11 @@ use api::v1::RowInsertRequests;
 use api::v1::greptime_request::Request;
 use api::v1::query_request::Query;
 use common_telemetry::debug;
+use sql::statements::copy::{Copy, CopyTable};
 use sql::statements::statement::Statement;
 
 use crate::error::{PermissionDeniedSnafu, Result};
-use crate::user_info::DefaultUserInfo;
+use crate::user_info::{DefaultUserInfo, ManagedUserInfo};
 use crate::{PermissionCheckerRef, UserInfo, UserInfoRef};
 
 /// A user-visible table target for permission checks.
@@ -93,6 +95,52 @@ impl PermissionTableTargets {
     }
 }
 
+/// A table privilege that can be granted to users created by `CREATE USER`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TablePrivilege {
+    /// Reads the table.
+    Select,
+    /// Writes the table.
+    Insert,
+    /// Deletes rows of the table, or truncates it.
+    Delete,
+    /// Alters the table.
+    Alter,
+    /// Drops the table.
+    Drop,
+}
+
+/// Every [TablePrivilege], as granted by `GRANT ALL`.
+const ALL_TABLE_PRIVILEGES: &[TablePrivilege] = &[
+    TablePrivilege::Select,
+    TablePrivilege::Insert,
+    TablePrivilege::Delete,
+    TablePrivilege::Alter,
+    TablePrivilege::Drop,
+];
+
+/// Privileges granted on a table, or on every table of a schema if `table` is `None`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableGrant {
+    pub catalog: String,
+    pub schema: String,
+    pub table: Option<String>,
+    pub privileges: Vec<TablePrivilege>,
+}
+
+impl TableGrant {
+    /// Returns true if the grant covers `target` and contains `privilege`.
+    pub fn allows(&self, target: &PermissionTableTarget, privilege: TablePrivilege) -> bool {
+        self.catalog == target.catalog
+            && self.schema == target.schema
+            && self
+                .table
+                .as_ref()
+                .is_none_or(|table| *table == target.table)
+            && self.privileges.contains(&privilege)
+    }
+}
+
 /// The access mode of a named permission action.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum AccessMode {
@@ -138,12 +186,15 @@ impl PermissionAction {
 pub const PROMQL_QUERY: PermissionAction = PermissionAction::read("promql.query");
 pub const LOG_QUERY: PermissionAction = PermissionAction::read("log.query");
 pub const OPENTSDB_WRITE: PermissionAction = PermissionAction::write("opentsdb.write");
+pub const OPENTSDB_QUERY: PermissionAction = PermissionAction::read("opentsdb.query");
 pub const INFLUXDB_WRITE: PermissionAction = PermissionAction::write("influxdb.write");
+pub const INFLUXDB_QUERY: PermissionAction = PermissionAction::read("influxdb.query");
 pub const PROM_STORE_WRITE: PermissionAction = PermissionAction::write("prom_store.write");
 pub const PROM_STORE_READ: PermissionAction = PermissionAction::read("prom_store.read");
 pub const OTLP_WRITE: PermissionAction = PermissionAction::write("otlp.write");
 pub const LOG_WRITE: PermissionAction = PermissionAction::write("log.write");
 pub const JAEGER_QUERY: PermissionAction = PermissionAction::read("jaeger.query");
+pub const TEMPO_QUERY: PermissionAction = PermissionAction::read("tempo.query");
 pub const PIPELINE_QUERY: PermissionAction = PermissionAction::read("pipeline.query");
 pub const PIPELINE_INSERT: PermissionAction = PermissionAction::write("pipeline.insert");
 pub const PIPELINE_DELETE: PermissionAction = PermissionAction::write("pipeline.delete");
@@ -163,12 +214,15 @@ pub const ALL_ACTIONS: &[PermissionAction] = &[
     PROMQL_QUERY,
     LOG_QUERY,
     OPENTSDB_WRITE,
+    OPENTSDB_QUERY,
     INFLUXDB_WRITE,
+    INFLUXDB_QUERY,
     PROM_STORE_WRITE,
     PROM_STORE_READ,
     OTLP_WRITE,
     LOG_WRITE,
     JAEGER_QUERY,
+    TEMPO_QUERY,
     PIPELINE_QUERY,
     PIPELINE_INSERT,
     PIPELINE_DELETE,
@@ -367,14 +421,218 @@ impl PermissionChecker for DefaultPermissionChecker {
         self.check_permission(user_info, req)
     }
 }
+/// Write actions whose table targets are checked by the frontend.
+const TABLE_TARGETED_WRITE_ACTIONS: &[PermissionAction] = &[
+    OPENTSDB_WRITE,
+    INFLUXDB_WRITE,
+    PROM_STORE_WRITE,
+    OTLP_WRITE,
+    LOG_WRITE,
+];
+
+/// Returns true if `stmt` manages users or their grants.
+fn is_user_management(stmt: &Statement) -> bool {
+    matches!(
+        stmt,
+        Statement::CreateUser(_)
+            | Statement::AlterUser(_)
+            | Statement::DropUser(_)
+            | Statement::Grant(_)
+            | Statement::Revoke(_)
+    )
+}
+
+/// Returns the table privileges a write statement requires on every target.
+///
+/// Statements without a privilege of their own, like `CREATE TABLE`, require all
+/// of them.
+fn write_statement_privileges(stmt: &Statement) -> &'static [TablePrivilege] {
+    match stmt {
+        Statement::Insert(_) | Statement::Copy(Copy::CopyTable(CopyTable::From(_))) => {
+            &[TablePrivilege::Insert]
+        }
+        // `UPDATE` deletes the rows whose fields are set to NULL.
+        Statement::Update(_) => &[TablePrivilege::Insert, TablePrivilege::Delete],
+        Statement::Delete(_) | Statement::TruncateTable(_) => &[TablePrivilege::Delete],
+        Statement::AlterTable(_) => &[TablePrivilege::Alter],
+        Statement::DropTable(_) => &[TablePrivilege::Drop],
+        Statement::Explain(explain) => write_statement_privileges(&explain.statement),
+        _ => ALL_TABLE_PRIVILEGES,
+    }
+}
+
+/// The permission checker for users created by `CREATE USER`.
+///
+/// Requests of [ManagedUserInfo] users are checked against their table grants:
+/// reads require `SELECT`, and writes require the privilege of the statement,
+/// like `INSERT` or `DROP`, on every target. Requests of other users are
+/// delegated to `fallback`.
+///
+/// Only admins and the configured `admin_users` can manage users and their
+/// grants.
+pub struct ManagedPermissionChecker {
+    fallback: PermissionCheckerRef,
+    admin_users: HashSet<String>,
+}
+
+impl ManagedPermissionChecker {
+    /// Returns a new [PermissionCheckerRef] wrapping `fallback`.
+    pub fn arc(fallback: PermissionCheckerRef, admin_users: Vec<String>) -> PermissionCheckerRef {
+        Arc::new(Self {
+            fallback,
+            admin_users: admin_users.into_iter().collect(),
+        })
+    }
+
+    /// Returns true if the user can manage users and their grants.
+    ///
+    /// Users created by `CREATE USER` are never admins, even if their names are
+    /// in `admin_users`.
+    fn is_user_admin(&self, user_info: &UserInfoRef) -> bool {
+        !user_info.as_any().is::<ManagedUserInfo>()
+            && (user_info.is_admin() || self.admin_users.contains(user_info.username()))
+    }
+
+    /// Rejects user management statements of non-admin users.
+    fn check_user_management(&self, user_info: &UserInfoRef, req: &PermissionReq) -> bool {
+        match req {
+            PermissionReq::SqlStatement(stmt) if is_user_management(stmt) => {
+                let allowed = self.is_user_admin(user_info);
+                if !allowed {
+                    debug!(
+                        "Permission denied: user management not allowed, user = {}",
+                        user_info.username()
+                    );
+                }
+                allowed
+            }
+            _ => true,
+        }
+    }
+}
+
+impl PermissionChecker for ManagedPermissionChecker {
+    fn check_permission(
+        &self,
+        user_info: UserInfoRef,
+        req: PermissionReq,
+    ) -> Result<PermissionResp> {
+        self.check_permission_with_context(user_info, req, None)
+    }
+
+    fn check_permission_with_context(
+        &self,
+        user_info: UserInfoRef,
+        req: PermissionReq,
+        current_schema: Option<&str>,
+    ) -> Result<PermissionResp> {
+        if !self.check_user_management(&user_info, &req) {
+            return Ok(PermissionResp::Reject);
+        }
+        let Some(managed_user) = user_info.as_any().downcast_ref::<ManagedUserInfo>() else {
+            return self
+                .fallback
+                .check_permission_with_context(user_info, req, current_schema);
+        };
+
+        let allowed = match req {
+            PermissionReq::SqlStatement(_) => true,
+            PermissionReq::Action(action) => {
+                action.access_mode() == AccessMode::Read
+                    || TABLE_TARGETED_WRITE_ACTIONS.contains(&action)
+            }
+            PermissionReq::BulkInsert {
+                catalog,
+                schema,
+                table,
+            } => managed_user.is_allowed(
+                &PermissionTableTarget::new(catalog, schema, table),
+                TablePrivilege::Insert,
+            ),
+            // Other gRPC requests don't carry resolved table targets.
+            PermissionReq::GrpcRequest(_) => false,
+        };
+        if !allowed {
+            debug!(
+                "Permission denied: request not allowed for managed user, user = {}",
+                managed_user.username()
+            );
+            return Ok(PermissionResp::Reject);
+        }
+
+        Ok(PermissionResp::Allow)
+    }
+
+    fn check_permission_with_table_targets(
+        &self,
+        user_info: UserInfoRef,
+        req: PermissionReq,
+        targets: PermissionTableTargets,
+    ) -> Result<PermissionResp> {
+        if !self.check_user_management(&user_info, &req) {
+            return Ok(PermissionResp::Reject);
+        }
+        let Some(managed_user) = user_info.as_any().downcast_ref::<ManagedUserInfo>() else {
+            return self
+                .fallback
+                .check_permission_with_table_targets(user_info, req, targets);
+        };
+
+        let PermissionTableTargets::Resolved(targets) = targets else {
+            return Ok(PermissionResp::Reject);
+        };
+        let privileges: &[TablePrivilege] = match req {
+            _ if req.is_readonly() => &[TablePrivilege::Select],
+            PermissionReq::SqlStatement(stmt) => write_statement_privileges(stmt),
+            _ => &[TablePrivilege::Insert],
+        };
+        // Statements that write without a table target change other metadata, like
+        // databases, which table grants don't cover.
+        if targets.is_empty() && req.is_write() {
+            return Ok(if matches!(req, PermissionReq::SqlStatement(_)) {
+                PermissionResp::Reject
+            } else {
+                PermissionResp::Allow
+            });
+        }
+
+        Ok(
+            if targets.iter().all(|target| {
+                privileges
+                    .iter()
+                    .all(|privilege| managed_user.is_allowed(target, *privilege))
+            }) {
+                PermissionResp::Allow
+            } else {
+                debug!(
+                    "Permission denied: missing {:?} privileges, user = {}",
+                    privileges,
+                    managed_user.username()
+                );
+                PermissionResp::Reject
+            },
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashSet;
 
+    use sql::dialect::GreptimeDbDialect;
+    use sql::parser::{ParseOptions, ParserContext};
+
     use super::*;
     use crate::error::{Error, InternalStateSnafu};
     use crate::user_info::PermissionMode;
 
+    fn parse(sql: &str) -> Statement {
+        ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+            .unwrap()
+            .pop()
+            .unwrap()
+    }
+
     struct TargetAwarePermissionChecker;
 
     impl PermissionChecker for TargetAwarePermissionChecker {
@@ -703,4 +961,158 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_managed_permission_checker() {
+        let checker = ManagedPermissionChecker::arc(DefaultPermissionChecker::arc(), vec![]);
+        let user = ManagedUserInfo::with_grants(
+            "reader",
+            vec![
+                TableGrant {
+                    catalog: "greptime".to_string(),
+                    schema: "public".to_string(),
+                    table: None,
+                    privileges: vec![TablePrivilege::Select],
+                },
+                TableGrant {
+                    catalog: "greptime".to_string(),
+                    schema: "public".to_string(),
+                    table: Some("allowed".to_string()),
+                    privileges: vec![TablePrivilege::Select, TablePrivilege::Insert],
+                },
+            ],
+        );
+
+        let check = |req: PermissionReq, targets: PermissionTableTargets| {
+            matches!(
+                checker
+                    .check_permission_with_table_targets(user.clone(), req, targets)
+                    .unwrap(),
+                PermissionResp::Allow
+            )
+        };
+        assert!(check(
+            PermissionReq::Action(PROMQL_QUERY),
+            resolved_targets("metrics")
+        ));
+        assert!(check(
+            PermissionReq::Action(PROM_STORE_WRITE),
+            resolved_targets("allowed")
+        ));
+        assert!(!check(
+            PermissionReq::Action(PROM_STORE_WRITE),
+            resolved_targets("metrics")
+        ));
+        assert!(!check(
+            PermissionReq::Action(PROMQL_QUERY),
+            PermissionTableTargets::resolved(vec![PermissionTableTarget::new(
+                "greptime", "other", "metrics",
+            )])
+        ));
+        assert!(!check(
+            PermissionReq::Action(PROMQL_QUERY),
+            PermissionTableTargets::Unresolved
+        ));
+
+        assert!(matches!(
+            checker
+                .check_permission(user.clone(), PermissionReq::Action(PIPELINE_INSERT))
+                .unwrap(),
+            PermissionResp::Reject
+        ));
+        assert!(matches!(
+            checker
+                .check_permission(
+                    user.clone(),
+                    PermissionReq::BulkInsert {
+                        catalog: "greptime",
+                        schema: "public",
+                        table: "allowed",
+                    },
+                )
+                .unwrap(),
+            PermissionResp::Allow
+        ));
+
+        // Other users are checked by the fallback checker.
+        let readonly = DefaultUserInfo::with_name_and_permission("ro", PermissionMode::ReadOnly);
+        assert!(matches!(
+            checker
+                .check_permission(readonly, PermissionReq::Action(PROM_STORE_WRITE))
+                .unwrap(),
+            PermissionResp::Reject
+        ));
+    }
+
+    #[test]
+    fn test_managed_permission_checker_statement_privileges() {
+        let checker = ManagedPermissionChecker::arc(DefaultPermissionChecker::arc(), vec![]);
+        let user = ManagedUserInfo::with_grants(
+            "writer",
+            vec![TableGrant {
+                catalog: "greptime".to_string(),
+                schema: "public".to_string(),
+                table: Some("cpu".to_string()),
+                privileges: vec![TablePrivilege::Insert, TablePrivilege::Delete],
+            }],
+        );
+
+        for (sql, allowed) in [
+            ("INSERT INTO cpu VALUES (1)", true),
+            ("DELETE FROM cpu", true),
+            ("TRUNCATE TABLE cpu", true),
+            ("ALTER TABLE cpu ADD COLUMN c INT", false),
+            ("DROP TABLE cpu", false),
+            ("CREATE TABLE cpu (ts TIMESTAMP TIME INDEX)", false),
+        ] {
+            let stmt = parse(sql);
+            let result = checker
+                .check_permission_with_table_targets(
+                    user.clone(),
+                    PermissionReq::SqlStatement(&stmt),
+                    resolved_targets("cpu"),
+                )
+                .unwrap();
+            assert_eq!(allowed, matches!(result, PermissionResp::Allow), "{sql}");
+        }
+    }
+
+    #[test]
+    fn test_managed_permission_checker_user_management() {
+        let checker = ManagedPermissionChecker::arc(
+            DefaultPermissionChecker::arc(),
+            vec!["root".to_string(), "writer".to_string()],
+        );
+        let root = DefaultUserInfo::with_name_and_permission("root", PermissionMode::ReadWrite);
+        let writeonly =
+            DefaultUserInfo::with_name_and_permission("writeonly_user", PermissionMode::WriteOnly);
+        // A managed user named like an admin isn't an admin.
+        let managed = ManagedUserInfo::with_grants("writer", vec![]);
+
+        for sql in [
+            "CREATE USER alice IDENTIFIED BY 'secret'",
+            "ALTER USER alice IDENTIFIED BY 'secret'",
+            "DROP USER alice",
+            "GRANT ALL ON public.* TO alice",
+            "REVOKE INSERT ON public.* FROM alice",
+        ] {
+            let stmt = parse(sql);
+            let check = |user: &UserInfoRef| {
+                let direct = checker
+                    .check_permission(user.clone(), PermissionReq::SqlStatement(&stmt))
+                    .unwrap();
+                let targeted = checker
+                    .check_permission_with_table_targets(
+                        user.clone(),
+                        PermissionReq::SqlStatement(&stmt),
+                        PermissionTableTargets::resolved(Vec::new()),
+                    )
+                    .unwrap();
+                matches!(direct, PermissionResp::Allow) && matches!(targeted, PermissionResp::Allow)
+            };
+            assert!(check(&root), "{sql}");
+            assert!(!check(&writeonly), "{sql}");
+            assert!(!check(&managed), "{sql}");
+        }
+    }
 }
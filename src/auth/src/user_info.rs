@@ -14,9 +14,12 @@
 
 use std::any::Any;
 use std::fmt::Debug;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use crate::UserInfoRef;
+use crate::error::Result;
+use crate::permission::{PermissionTableTarget, TableGrant, TablePrivilege};
+use crate::user_provider::managed_user_provider::ManagedUserSourceRef;
 
 pub trait UserInfo: Debug + Sync + Send {
     fn as_any(&self) -> &dyn Any;
@@ -119,6 +122,97 @@ impl UserInfo for DefaultUserInfo {
         self.username.as_str()
     }
 }
+/// The [UserInfo] of a user created by `CREATE USER`.
+///
+/// It carries the user's table grants. [ManagedUserInfo::refresh_grants] reloads
+/// them from the user source, so `GRANT` and `REVOKE` reach live sessions.
+pub struct ManagedUserInfo {
+    username: String,
+    grants: RwLock<Arc<Vec<TableGrant>>>,
+    source: Option<ManagedUserSourceRef>,
+}
+
+impl ManagedUserInfo {
+    pub fn with_grants(username: impl Into<String>, grants: Vec<TableGrant>) -> UserInfoRef {
+        Arc::new(Self {
+            username: username.into(),
+            grants: RwLock::new(Arc::new(grants)),
+            source: None,
+        })
+    }
+
+    /// Creates a user whose grants are reloaded from `source`.
+    pub fn with_source(
+        username: impl Into<String>,
+        grants: Vec<TableGrant>,
+        source: ManagedUserSourceRef,
+    ) -> UserInfoRef {
+        Arc::new(Self {
+            username: username.into(),
+            grants: RwLock::new(Arc::new(grants)),
+            source: Some(source),
+        })
+    }
+
+    /// Returns the table grants of the user.
+    pub fn grants(&self) -> Arc<Vec<TableGrant>> {
+        self.grants.read().unwrap().clone()
+    }
+
+    /// Returns true if any grant allows `privilege` on `target`.
+    pub fn is_allowed(&self, target: &PermissionTableTarget, privilege: TablePrivilege) -> bool {
+        self.grants
+            .read()
+            .unwrap()
+            .iter()
+            .any(|grant| grant.allows(target, privilege))
+    }
+
+    /// Reloads the table grants from the user source. A dropped user has no grants.
+    pub async fn refresh_grants(&self) -> Result<()> {
+        let Some(source) = &self.source else {
+            return Ok(());
+        };
+        let grants = source
+            .get_user(&self.username)
+            .await?
+            .map(|user| user.grants)
+            .unwrap_or_default();
+        *self.grants.write().unwrap() = Arc::new(grants);
+        Ok(())
+    }
+}
+
+impl Debug for ManagedUserInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ManagedUserInfo")
+            .field("username", &self.username)
+            .field("grants", &self.grants())
+            .finish()
+    }
+}
+
+/// Reloads the table grants of `user_info` if it's a [ManagedUserInfo].
+///
+/// Call it before checking permissions of a session that outlives a single
+/// request, like a MySQL or PostgreSQL connection.
+pub async fn refresh_user_grants(user_info: &UserInfoRef) -> Result<()> {
+    match user_info.as_any().downcast_ref::<ManagedUserInfo>() {
+        Some(managed_user) => managed_user.refresh_grants().await,
+        None => Ok(()),
+    }
+}
+
+impl UserInfo for ManagedUserInfo {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn username(&self) -> &str {
+        self.username.as_str()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+pub(crate) mod managed_user_provider;
 pub(crate) mod static_user_provider;
 pub(crate) mod watch_file_user_provider;
 
@@ -99,6 +100,12 @@ pub trait UserProvider: Send + Sync {
     fn external(&self) -> bool {
         false
     }
+
+    /// Returns true if `username` is a user configured in this provider, like the
+    /// users of a static user provider.
+    fn has_configured_user(&self, _username: &str) -> bool {
+        false
+    }
 }
 
 pub enum PgAuthInfo {
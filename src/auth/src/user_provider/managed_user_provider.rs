@@ -0,0 +1,353 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use common_base::secrets::ExposeSecret;
+use snafu::{OptionExt, ensure};
+
+use crate::common::{
+    DEFAULT_PBKDF2_SHA256_ITERATIONS, DEFAULT_PBKDF2_SHA256_SALT_LEN,
+    format_mysql_native_password_verifier, format_pg_scram_sha256_password_verifier,
+};
+use crate::error::{
+    IllegalParamSnafu, Result, UnsupportedPasswordTypeSnafu, UserPasswordMismatchSnafu,
+};
+use crate::permission::TableGrant;
+use crate::user_info::ManagedUserInfo;
+use crate::user_provider::{PasswordVerifier, PgAuthInfo};
+use crate::{Identity, Password, UserInfoRef, UserProvider, UserProviderRef};
+
+pub(crate) const MANAGED_USER_PROVIDER: &str = "managed_user_provider";
+
+/// A user created by `CREATE USER`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ManagedUser {
+    pub username: String,
+    /// Password verifiers built by [managed_user_password_verifiers].
+    pub password_verifiers: Vec<String>,
+    pub grants: Vec<TableGrant>,
+}
+
+/// The source of users created by `CREATE USER`.
+#[async_trait]
+pub trait ManagedUserSource: Send + Sync {
+    /// Returns the user named `username`, or `None` if there is no such user.
+    async fn get_user(&self, username: &str) -> Result<Option<ManagedUser>>;
+}
+
+pub type ManagedUserSourceRef = Arc<dyn ManagedUserSource>;
+
+/// Builds the password verifiers stored for a managed user.
+///
+/// The plaintext password is never stored: a Postgres SCRAM verifier serves
+/// plaintext and SCRAM authentication, and a `mysql_native_password` verifier
+/// serves the MySQL handshake.
+pub fn managed_user_password_verifiers(password: &str) -> Result<Vec<String>> {
+    let salt = rand::random::<[u8; DEFAULT_PBKDF2_SHA256_SALT_LEN]>();
+    Ok(vec![
+        format_pg_scram_sha256_password_verifier(
+            password.as_bytes(),
+            &salt,
+            DEFAULT_PBKDF2_SHA256_ITERATIONS,
+        )?,
+        format_mysql_native_password_verifier(password.as_bytes()),
+    ])
+}
+
+/// A [UserProvider] that authenticates users created by `CREATE USER`.
+///
+/// Users unknown to the [ManagedUserSource] are handled by `fallback`, so the
+/// users configured at startup keep working.
+pub struct ManagedUserProvider {
+    source: ManagedUserSourceRef,
+    fallback: UserProviderRef,
+}
+
+impl ManagedUserProvider {
+    pub fn new(source: ManagedUserSourceRef, fallback: UserProviderRef) -> Self {
+        Self { source, fallback }
+    }
+
+    async fn get_user(
+        &self,
+        id: &Identity<'_>,
+    ) -> Result<Option<(ManagedUser, Vec<PasswordVerifier>)>> {
+        let Identity::UserId(username, _) = id;
+        // Configured users take precedence, so a managed user can't shadow them.
+        if username.is_empty() || self.fallback.has_configured_user(username) {
+            return Ok(None);
+        }
+
+        Ok(self.source.get_user(username).await?.map(|user| {
+            let verifiers = user
+                .password_verifiers
+                .iter()
+                .filter_map(|verifier| PasswordVerifier::parse(verifier))
+                .collect();
+            (user, verifiers)
+        }))
+    }
+}
+
+#[async_trait]
+impl UserProvider for ManagedUserProvider {
+    fn name(&self) -> &str {
+        MANAGED_USER_PROVIDER
+    }
+
+    async fn authenticate(&self, id: Identity<'_>, password: Password<'_>) -> Result<UserInfoRef> {
+        let Some((user, verifiers)) = self.get_user(&id).await? else {
+            return self.fallback.authenticate(id, password).await;
+        };
+
+        let matched = match password {
+            Password::PlainText(pwd) => {
+                ensure!(
+                    !pwd.expose_secret().is_empty(),
+                    IllegalParamSnafu {
+                        msg: "blank password"
+                    }
+                );
+                verifiers
+                    .iter()
+                    .any(|verifier| verifier.verify_plain_text(pwd.expose_secret()))
+            }
+            Password::MysqlNativePassword(auth_data, salt) => {
+                let verifier = verifiers
+                    .iter()
+                    .find(|verifier| {
+                        matches!(verifier, PasswordVerifier::MysqlNativePassword { .. })
+                    })
+                    .context(UnsupportedPasswordTypeSnafu {
+                        password_type: "mysql_native_password",
+                    })?;
+                verifier
+                    .verify_mysql_native_password(auth_data, salt, &user.username)
+                    .is_ok()
+            }
+            Password::PgMD5(_, _) => {
+                return UnsupportedPasswordTypeSnafu {
+                    password_type: "pg_md5",
+                }
+                .fail();
+            }
+        };
+        ensure!(
+            matched,
+            UserPasswordMismatchSnafu {
+                username: user.username,
+            }
+        );
+
+        Ok(ManagedUserInfo::with_source(
+            user.username,
+            user.grants,
+            self.source.clone(),
+        ))
+    }
+
+    async fn authorize(&self, catalog: &str, schema: &str, user_info: &UserInfoRef) -> Result<()> {
+        if user_info.as_any().is::<ManagedUserInfo>() {
+            // Managed users are checked against their table grants per request.
+            return Ok(());
+        }
+        self.fallback.authorize(catalog, schema, user_info).await
+    }
+
+    async fn postgres_auth_info(&self, id: Identity<'_>) -> Result<PgAuthInfo> {
+        let Some((user, verifiers)) = self.get_user(&id).await? else {
+            return self.fallback.postgres_auth_info(id).await;
+        };
+
+        Ok(
+            match verifiers
+                .iter()
+                .find_map(|verifier| verifier.to_pg_scram_sha256_verifier())
+            {
+                Some(verifier) => PgAuthInfo::ScramSha256 {
+                    verifier,
+                    user_info: Some(ManagedUserInfo::with_source(
+                        user.username,
+                        user.grants,
+                        self.source.clone(),
+                    )),
+                },
+                None => PgAuthInfo::Cleartext,
+            },
+        )
+    }
+
+    fn external(&self) -> bool {
+        self.fallback.external()
+    }
+
+    fn has_configured_user(&self, username: &str) -> bool {
+        self.fallback.has_configured_user(username)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::StaticUserProvider;
+    use crate::common::mysql_native_password_hash;
+    use crate::permission::{PermissionTableTarget, TablePrivilege};
+    use crate::user_info::DefaultUserInfo;
+
+    struct MockUserSource {
+        users: HashMap<String, ManagedUser>,
+    }
+
+    #[async_trait]
+    impl ManagedUserSource for MockUserSource {
+        async fn get_user(&self, username: &str) -> Result<Option<ManagedUser>> {
+            Ok(self.users.get(username).cloned())
+        }
+    }
+
+    fn new_source() -> ManagedUserSourceRef {
+        let users = ["alice", "root"].map(|username| ManagedUser {
+            username: username.to_string(),
+            password_verifiers: managed_user_password_verifiers("secret").unwrap(),
+            grants: vec![TableGrant {
+                catalog: "greptime".to_string(),
+                schema: "public".to_string(),
+                table: None,
+                privileges: vec![TablePrivilege::Select],
+            }],
+        });
+        Arc::new(MockUserSource {
+            users: users
+                .into_iter()
+                .map(|user| (user.username.clone(), user))
+                .collect(),
+        })
+    }
+
+    fn new_provider() -> ManagedUserProvider {
+        let source = new_source();
+        let fallback = Arc::new(StaticUserProvider::new("cmd:root=123456").unwrap());
+        ManagedUserProvider::new(source, fallback)
+    }
+
+    async fn authenticate(
+        provider: &ManagedUserProvider,
+        username: &str,
+        password: &str,
+    ) -> Result<UserInfoRef> {
+        provider
+            .authenticate(
+                Identity::UserId(username, None),
+                Password::PlainText(password.to_string().into()),
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_managed_user() {
+        let provider = new_provider();
+
+        let user_info = authenticate(&provider, "alice", "secret").await.unwrap();
+        let managed = user_info
+            .as_any()
+            .downcast_ref::<ManagedUserInfo>()
+            .unwrap();
+        assert_eq!(managed.username(), "alice");
+        assert_eq!(managed.grants().len(), 1);
+        assert!(authenticate(&provider, "alice", "wrong").await.is_err());
+
+        // Unknown users fall back to the configured provider.
+        let user_info = authenticate(&provider, "root", "123456").await.unwrap();
+        assert!(user_info.as_any().is::<DefaultUserInfo>());
+        assert!(authenticate(&provider, "bob", "secret").await.is_err());
+
+        // A managed user can't shadow a configured user of the same name.
+        assert!(provider.has_configured_user("root"));
+        assert!(authenticate(&provider, "root", "secret").await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_managed_user_grants() {
+        let source = new_source();
+        let user_info = ManagedUserInfo::with_source("alice", vec![], source);
+        let target = PermissionTableTarget::new("greptime", "public", "metrics");
+        let managed = user_info
+            .as_any()
+            .downcast_ref::<ManagedUserInfo>()
+            .unwrap();
+        assert!(!managed.is_allowed(&target, TablePrivilege::Select));
+
+        crate::refresh_user_grants(&user_info).await.unwrap();
+        assert!(managed.is_allowed(&target, TablePrivilege::Select));
+
+        // A dropped user loses every grant.
+        let dropped = ManagedUserInfo::with_source(
+            "bob",
+            managed.grants().to_vec(),
+            Arc::new(MockUserSource {
+                users: HashMap::new(),
+            }),
+        );
+        crate::refresh_user_grants(&dropped).await.unwrap();
+        let dropped = dropped.as_any().downcast_ref::<ManagedUserInfo>().unwrap();
+        assert!(dropped.grants().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_managed_user_mysql_native_password() {
+        use digest::Digest;
+        use sha1::Sha1;
+
+        let provider = new_provider();
+        let salt = b"12345678901234567890";
+        let hash_stage_1 = Sha1::digest(b"secret");
+        let mut hasher = Sha1::new();
+        hasher.update(salt);
+        hasher.update(mysql_native_password_hash(b"secret"));
+        let auth_data = hash_stage_1
+            .iter()
+            .zip(hasher.finalize().iter())
+            .map(|(lhs, rhs)| lhs ^ rhs)
+            .collect::<Vec<_>>();
+
+        provider
+            .authenticate(
+                Identity::UserId("alice", None),
+                Password::MysqlNativePassword(&auth_data, salt),
+            )
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_managed_user_postgres_auth_info() {
+        let provider = new_provider();
+        let PgAuthInfo::ScramSha256 {
+            verifier,
+            user_info,
+        } = provider
+            .postgres_auth_info(Identity::UserId("alice", None))
+            .await
+            .unwrap()
+        else {
+            panic!("expected SCRAM auth info");
+        };
+        assert!(verifier.verify_plain_password(b"secret").unwrap());
+        assert_eq!(user_info.unwrap().username(), "alice");
+    }
+}
@@ -84,6 +84,10 @@ impl UserProvider for StaticUserProvider {
         // default allow all
         Ok(())
     }
+
+    fn has_configured_user(&self, username: &str) -> bool {
+        self.users.contains_key(username)
+    }
 }
 
 #[cfg(test)]
@@ -95,6 +95,11 @@ impl UserProvider for WatchFileUserProvider {
         // default allow all
         Ok(())
     }
+
+    fn has_configured_user(&self, username: &str) -> bool {
+        let users = self.users.lock().expect("users credential must be valid");
+        users.contains_key(username)
+    }
 }
 
 #[cfg(test)]
@@ -22,7 +22,7 @@ use catalog::kvbackend::new_table_cache;
 use common_meta::cache::{
     CacheRegistry, CacheRegistryBuilder, LayeredCacheRegistryBuilder, new_schema_cache,
     new_table_flownode_set_cache, new_table_info_cache, new_table_name_cache,
-    new_table_route_cache, new_table_schema_cache, new_view_info_cache,
+    new_table_route_cache, new_table_schema_cache, new_user_cache, new_view_info_cache,
 };
 use common_meta::kv_backend::KvBackendRef;
 use moka::future::{Cache, CacheBuilder};
@@ -52,6 +52,7 @@ pub const TABLE_SCHEMA_NAME_CACHE_NAME: &str = "table_schema_name_cache";
 pub const TABLE_FLOWNODE_SET_CACHE_NAME: &str = "table_flownode_set_cache";
 pub const TABLE_ROUTE_CACHE_NAME: &str = "table_route_cache";
 pub const PARTITION_INFO_CACHE_NAME: &str = "partition_info_cache";
+pub const USER_CACHE_NAME: &str = "user_cache";
 
 /// Builds cache registry for datanode, including:
 /// - Schema cache.
@@ -86,6 +87,7 @@ pub fn build_datanode_cache_registry(kv_backend: KvBackendRef) -> CacheRegistry
 /// - Table flow node cache
 /// - View cache
 /// - Schema cache
+/// - User cache
 pub fn build_fundamental_cache_registry(kv_backend: KvBackendRef) -> CacheRegistry {
     // Builds table info cache
     let cache = default_cache();
@@ -134,6 +136,14 @@ pub fn build_fundamental_cache_registry(kv_backend: KvBackendRef) -> CacheRegist
         kv_backend.clone(),
     ));
 
+    // Builds user cache
+    let cache = default_cache();
+    let user_cache = Arc::new(new_user_cache(
+        USER_CACHE_NAME.to_string(),
+        cache,
+        kv_backend.clone(),
+    ));
+
     let table_id_schema_cache = Arc::new(new_table_schema_cache(
         TABLE_SCHEMA_NAME_CACHE_NAME.to_string(),
         CacheBuilder::new(DEFAULT_CACHE_MAX_CAPACITY).build(),
@@ -147,6 +157,7 @@ pub fn build_fundamental_cache_registry(kv_backend: KvBackendRef) -> CacheRegist
         .add_cache(table_flownode_set_cache)
         .add_cache(schema_cache)
         .add_cache(table_id_schema_cache)
+        .add_cache(user_cache)
         .build()
 }
 
@@ -19,7 +19,7 @@ use clap::{Parser, Subcommand};
 use cmd::datanode::builder::InstanceBuilder;
 use cmd::error::{InitTlsProviderSnafu, Result};
 use cmd::options::GlobalOptions;
-use cmd::{App, cli, datanode, flownode, frontend, metasrv, standalone, user};
+use cmd::{App, cli, compactor, datanode, flownode, frontend, metasrv, standalone, user};
 use common_base::Plugins;
 use common_version::{product_name, verbose_version, version};
 use servers::install_default_crypto_provider;
@@ -45,6 +45,10 @@ enum SubCommand {
     #[clap(name = "flownode")]
     Flownode(flownode::Command),
 
+    /// Start compaction worker service.
+    #[clap(name = "compactor")]
+    Compactor(compactor::Command),
+
     /// Start frontend service.
     #[clap(name = "frontend")]
     Frontend(frontend::Command),
@@ -125,6 +129,12 @@ async fn start(cli: Command) -> Result<()> {
                 .run()
                 .await
         }
+        SubCommand::Compactor(cmd) => {
+            cmd.build(cmd.load_options(&cli.global_options)?)
+                .await?
+                .run()
+                .await
+        }
         SubCommand::Frontend(cmd) => {
             cmd.build(cmd.load_options(&cli.global_options)?)
                 .await?
@@ -0,0 +1,214 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::path::Path;
+
+use clap::Parser;
+use common_base::Plugins;
+use common_config::Configurable;
+use common_telemetry::info;
+use common_telemetry::logging::{DEFAULT_LOGGING_DIR, TracingOptions};
+use common_version::{short_version, verbose_version};
+use datanode::compactor::{Compactor, CompactorOptions as CompactorComponentOptions};
+use snafu::ResultExt;
+use tracing_appender::non_blocking::WorkerGuard;
+
+use crate::error::{LoadLayeredConfigSnafu, Result, ShutdownCompactorSnafu, StartCompactorSnafu};
+use crate::options::{GlobalOptions, GreptimeOptions};
+use crate::{App, create_resource_limit_metrics, log_versions, maybe_activate_heap_profile};
+
+pub const APP_NAME: &str = "greptime-compactor";
+
+type CompactorOptions = GreptimeOptions<CompactorComponentOptions>;
+
+pub struct Instance {
+    compactor: Compactor,
+    // Keep the logging guard to prevent the worker from being dropped.
+    _guard: Vec<WorkerGuard>,
+}
+
+impl Instance {
+    pub fn new(compactor: Compactor, guard: Vec<WorkerGuard>) -> Self {
+        Self {
+            compactor,
+            _guard: guard,
+        }
+    }
+
+    pub fn compactor(&self) -> &Compactor {
+        &self.compactor
+    }
+}
+
+#[async_trait::async_trait]
+impl App for Instance {
+    fn name(&self) -> &str {
+        APP_NAME
+    }
+
+    async fn start(&mut self) -> Result<()> {
+        self.compactor.start().await.context(StartCompactorSnafu)
+    }
+
+    async fn stop(&mut self) -> Result<()> {
+        self.compactor
+            .shutdown()
+            .await
+            .context(ShutdownCompactorSnafu)
+    }
+}
+
+#[derive(Parser)]
+pub struct Command {
+    #[clap(subcommand)]
+    subcmd: SubCommand,
+}
+
+impl Command {
+    pub async fn build(&self, opts: CompactorOptions) -> Result<Instance> {
+        self.subcmd.build(opts).await
+    }
+
+    pub fn load_options(&self, global_options: &GlobalOptions) -> Result<CompactorOptions> {
+        match &self.subcmd {
+            SubCommand::Start(cmd) => cmd.load_options(global_options),
+        }
+    }
+}
+
+#[derive(Parser)]
+enum SubCommand {
+    Start(StartCommand),
+}
+
+impl SubCommand {
+    async fn build(&self, opts: CompactorOptions) -> Result<Instance> {
+        match self {
+            SubCommand::Start(cmd) => cmd.build(opts).await,
+        }
+    }
+}
+
+#[derive(Debug, Parser, Default)]
+struct StartCommand {
+    /// Bind address for the gRPC server.
+    #[clap(long = "grpc-bind-addr")]
+    grpc_bind_addr: Option<String>,
+    /// The working home directory, must share the object storage with the datanodes.
+    #[clap(long)]
+    data_home: Option<String>,
+    /// The configuration file for compactor
+    #[clap(short, long)]
+    config_file: Option<String>,
+    /// The prefix of environment variables, default is `GREPTIMEDB_COMPACTOR`;
+    #[clap(long, default_value = "GREPTIMEDB_COMPACTOR")]
+    env_prefix: String,
+}
+
+impl StartCommand {
+    fn load_options(&self, global_options: &GlobalOptions) -> Result<CompactorOptions> {
+        let mut opts = CompactorOptions::load_layered_options(
+            self.config_file.as_deref(),
+            self.env_prefix.as_ref(),
+        )
+        .context(LoadLayeredConfigSnafu)?;
+
+        self.merge_with_cli_options(global_options, &mut opts);
+
+        Ok(opts)
+    }
+
+    // The precedence order is: cli > config file > environment variables > default values.
+    fn merge_with_cli_options(&self, global_options: &GlobalOptions, opts: &mut CompactorOptions) {
+        let opts = &mut opts.component;
+
+        if let Some(dir) = &global_options.log_dir {
+            opts.logging.dir.clone_from(dir);
+        }
+
+        if global_options.log_level.is_some() {
+            opts.logging.level.clone_from(&global_options.log_level);
+        }
+
+        opts.tracing = TracingOptions {
+            #[cfg(feature = "tokio-console")]
+            tokio_console_addr: global_options.tokio_console_addr.clone(),
+        };
+
+        if let Some(addr) = &self.grpc_bind_addr {
+            opts.grpc.bind_addr.clone_from(addr);
+        }
+
+        if let Some(data_home) = &self.data_home {
+            opts.storage.data_home.clone_from(data_home);
+        }
+
+        // If the logging dir is not set, use the default logs dir in the data home.
+        if opts.logging.dir.is_empty() {
+            opts.logging.dir = Path::new(&opts.storage.data_home)
+                .join(DEFAULT_LOGGING_DIR)
+                .to_string_lossy()
+                .to_string();
+        }
+    }
+
+    async fn build(&self, opts: CompactorOptions) -> Result<Instance> {
+        let guard = common_telemetry::init_global_logging(
+            APP_NAME,
+            &opts.component.logging,
+            &opts.component.tracing,
+            None,
+            None,
+        );
+
+        common_runtime::init_global_runtimes(&opts.runtime);
+
+        crate::options::flush_dropped_plugin_warnings();
+        log_versions(verbose_version(), short_version(), APP_NAME);
+        maybe_activate_heap_profile(&opts.component.memory);
+        create_resource_limit_metrics(APP_NAME);
+
+        info!("Compactor start command: {:#?}", self);
+        info!("Compactor options: {:#?}", opts);
+
+        let compactor = Compactor::try_new(&opts.component, Plugins::new())
+            .await
+            .context(StartCompactorSnafu)?;
+
+        Ok(Instance::new(compactor, guard))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_with_cli_options() {
+        let command = StartCommand::try_parse_from([
+            "compactor",
+            "--grpc-bind-addr",
+            "127.0.0.1:13010",
+            "--data-home",
+            "/tmp/greptimedb_compactor/",
+        ])
+        .unwrap();
+
+        let options = command.load_options(&GlobalOptions::default()).unwrap();
+        let options = options.component;
+        assert_eq!("127.0.0.1:13010", options.grpc.bind_addr);
+        assert_eq!("/tmp/greptimedb_compactor/", options.storage.data_home);
+        assert_eq!("/tmp/greptimedb_compactor/logs", options.logging.dir);
+    }
+}
@@ -324,6 +324,8 @@ impl ParquetbenchCommand {
                 num_series: 0,
                 primary_key_min: None,
                 primary_key_max: None,
+                downsample_interval: None,
+                storage: None,
             },
             Arc::new(NoopFilePurger),
         );
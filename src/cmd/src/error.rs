@@ -85,6 +85,20 @@ pub enum Error {
         source: datanode::error::Error,
     },
 
+    #[snafu(display("Failed to start compactor"))]
+    StartCompactor {
+        #[snafu(implicit)]
+        location: Location,
+        source: datanode::error::Error,
+    },
+
+    #[snafu(display("Failed to shutdown compactor"))]
+    ShutdownCompactor {
+        #[snafu(implicit)]
+        location: Location,
+        source: datanode::error::Error,
+    },
+
     #[snafu(display("Failed to start flownode"))]
     StartFlownode {
         #[snafu(implicit)]
@@ -326,6 +340,9 @@ impl ErrorExt for Error {
             Error::StartDatanode { source, .. } => source.status_code(),
             Error::StartFrontend { source, .. } => source.status_code(),
             Error::ShutdownDatanode { source, .. } => source.status_code(),
+            Error::StartCompactor { source, .. } | Error::ShutdownCompactor { source, .. } => {
+                source.status_code()
+            }
             Error::ShutdownFrontend { source, .. } => source.status_code(),
             Error::StartMetaServer { source, .. } => source.status_code(),
             Error::ShutdownMetaServer { source, .. } => source.status_code(),
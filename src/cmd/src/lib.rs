@@ -24,6 +24,7 @@ use common_telemetry::{error, info, warn};
 use crate::error::Result;
 
 pub mod cli;
+pub mod compactor;
 pub mod datanode;
 pub mod error;
 pub mod flownode;
@@ -132,6 +132,21 @@ impl HllState {
     }
 }
 
+/// Merges two serialized HyperLogLog states generated by `hll` and returns
+/// the serialized result.
+pub fn merge_hll_states(lhs: &[u8], rhs: &[u8]) -> DfResult<Vec<u8>> {
+    let deserialize = |raw: &[u8]| {
+        bincode::deserialize::<HllStateType>(raw)
+            .map_err(|e| DataFusionError::Plan(format!("Failed to deserialize HyperLogLog: {}", e)))
+    };
+    let mut merged = deserialize(lhs)?;
+    merged
+        .merge(&deserialize(rhs)?)
+        .map_err(|e| DataFusionError::Plan(format!("Failed to merge HyperLogLog: {}", e)))?;
+    bincode::serialize(&merged)
+        .map_err(|e| DataFusionError::Internal(format!("Failed to serialize HyperLogLog: {}", e)))
+}
+
 impl DfAccumulator for HllState {
     fn update_batch(&mut self, values: &[ArrayRef]) -> DfResult<()> {
         let array = &values[0];
@@ -197,6 +212,24 @@ mod tests {
 
     use super::*;
 
+    #[test]
+    fn test_merge_hll_states() {
+        let mut lhs = HllState::new();
+        lhs.update("a");
+        lhs.update("b");
+        let mut rhs = HllState::new();
+        rhs.update("b");
+        rhs.update("c");
+        let lhs = bincode::serialize(&lhs.hll).unwrap();
+        let rhs = bincode::serialize(&rhs.hll).unwrap();
+
+        let merged = merge_hll_states(&lhs, &rhs).unwrap();
+        let mut hll: HllStateType = bincode::deserialize(&merged).unwrap();
+        assert_eq!(hll.count().trunc() as u32, 3);
+
+        assert!(merge_hll_states(&lhs, b"invalid").is_err());
+    }
+
     #[test]
     fn test_hll_basic() {
         let mut state = HllState::new();
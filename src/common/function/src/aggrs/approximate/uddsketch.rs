@@ -121,6 +121,41 @@ impl UddSketchState {
     }
 }
 
+/// Merges two serialized UDDSketch states generated by `uddsketch_state` and
+/// returns the serialized result.
+///
+/// Both states must be created with the same bucket size and error rate.
+pub fn merge_uddsketch_states(lhs: &[u8], rhs: &[u8]) -> DfResult<Vec<u8>> {
+    let decode = |raw: &[u8]| {
+        uddsketch_compat::decode(raw).map_err(|e| {
+            common_telemetry::trace!("Failed to deserialize UDDSketch: {}", e);
+            DataFusionError::Plan("Failed to deserialize UDDSketch from binary".to_string())
+        })
+    };
+    let mut merged = decode(lhs)?;
+    let other = decode(rhs)?;
+    if other.count() > 0 {
+        if merged.count() == 0 {
+            merged = other;
+        } else if merged.max_buckets() != other.max_buckets()
+            || merged.initial_error().to_bits() != other.initial_error().to_bits()
+        {
+            return Err(DataFusionError::Plan(format!(
+                "Merging UDDSketch with different parameters: {:?} vs {:?}",
+                (merged.max_buckets(), merged.initial_error()),
+                (other.max_buckets(), other.initial_error())
+            )));
+        } else {
+            merged
+                .merge(&other)
+                .map_err(|e| DataFusionError::Plan(e.to_string()))?;
+        }
+    }
+    merged
+        .encode()
+        .map_err(|e| DataFusionError::Internal(format!("Failed to serialize UDDSketch: {}", e)))
+}
+
 fn downcast_accumulator_args(args: AccumulatorArgs) -> DfResult<(u32, f64)> {
     let bucket_size = match args.exprs[0]
         .as_any()
@@ -242,6 +277,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_merge_uddsketch_states() {
+        let mut lhs = UddSketchState::new(10, 0.01).unwrap();
+        lhs.uddsketch.add(1.0).unwrap();
+        let mut rhs = UddSketchState::new(10, 0.01).unwrap();
+        rhs.uddsketch.add(2.0).unwrap();
+        rhs.uddsketch.add(3.0).unwrap();
+        let empty = UddSketchState::new(10, 0.01).unwrap();
+        let lhs = lhs.uddsketch.encode().unwrap();
+        let rhs = rhs.uddsketch.encode().unwrap();
+        let empty = empty.uddsketch.encode().unwrap();
+
+        let merged = merge_uddsketch_states(&lhs, &rhs).unwrap();
+        let merged = UddSketchRef::parse(&merged).unwrap();
+        assert_eq!(merged.count(), 3);
+        assert_eq!(merged.sum(), 6.0);
+
+        let merged = merge_uddsketch_states(&empty, &rhs).unwrap();
+        assert_eq!(UddSketchRef::parse(&merged).unwrap().count(), 2);
+
+        let mut other = UddSketchState::new(20, 0.01).unwrap();
+        other.uddsketch.add(1.0).unwrap();
+        let other = other.uddsketch.encode().unwrap();
+        assert!(merge_uddsketch_states(&lhs, &other).is_err());
+    }
+
     #[test]
     fn test_uddsketch_state_roundtrip() {
         let mut state = UddSketchState::new(10, 0.01).unwrap();
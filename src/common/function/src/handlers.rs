@@ -38,6 +38,10 @@ pub trait TableMutationHandler: Send + Sync {
     /// Inserts rows into the table.
     async fn insert(&self, request: InsertRequest, ctx: QueryContextRef) -> Result<Output>;
 
+    /// Inserts rows into the table, replacing the existing rows with the same key
+    /// instead of merging with them.
+    async fn replace(&self, request: InsertRequest, ctx: QueryContextRef) -> Result<Output>;
+
     /// Delete rows from the table.
     async fn delete(&self, request: DeleteRequest, ctx: QueryContextRef) -> Result<AffectedRows>;
 
@@ -19,6 +19,7 @@ mod field;
 mod format;
 mod insert;
 mod locate;
+mod log_field;
 mod regexp_extract;
 mod space;
 
@@ -27,6 +28,7 @@ pub(crate) use field::FieldFunction;
 pub(crate) use format::FormatFunction;
 pub(crate) use insert::InsertFunction;
 pub(crate) use locate::LocateFunction;
+pub(crate) use log_field::{JsonFieldFunction, LogfmtFieldFunction};
 pub(crate) use regexp_extract::RegexpExtractFunction;
 pub(crate) use space::SpaceFunction;
 
@@ -39,6 +41,8 @@ pub fn register_string_functions(registry: &FunctionRegistry) {
     FormatFunction::register(registry);
     InsertFunction::register(registry);
     LocateFunction::register(registry);
+    JsonFieldFunction::register(registry);
+    LogfmtFieldFunction::register(registry);
     RegexpExtractFunction::register(registry);
     SpaceFunction::register(registry);
 }
@@ -0,0 +1,304 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Functions extracting a field from a structured log line.
+//!
+//! Unlike `json_get_string(parse_json(..))`, these functions never fail on a
+//! malformed line: a line that can't be parsed or doesn't contain the field
+//! yields NULL, so a query over mixed logs keeps working. They back the
+//! `| json` and `| logfmt` parsers of LogQL.
+
+use std::fmt;
+use std::sync::Arc;
+
+use datafusion_common::DataFusionError;
+use datafusion_common::arrow::array::{Array, AsArray, LargeStringBuilder};
+use datafusion_common::arrow::compute::cast;
+use datafusion_common::arrow::datatypes::DataType;
+use datafusion_expr::{ColumnarValue, ScalarFunctionArgs, Signature, Volatility};
+use serde_json::Value;
+
+use crate::function::Function;
+use crate::function_registry::FunctionRegistry;
+
+/// JSON_FIELD(line, key) returns the top-level field `key` of the JSON object
+/// in `line` as a string.
+///
+/// String values are returned unquoted, other values in their JSON text form.
+#[derive(Debug)]
+pub struct JsonFieldFunction {
+    signature: Signature,
+}
+
+impl JsonFieldFunction {
+    pub const NAME: &'static str = "json_field";
+
+    pub fn register(registry: &FunctionRegistry) {
+        registry.register_scalar(JsonFieldFunction::default());
+    }
+}
+
+impl Default for JsonFieldFunction {
+    fn default() -> Self {
+        Self {
+            signature: Signature::string(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl fmt::Display for JsonFieldFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", Self::NAME.to_ascii_uppercase())
+    }
+}
+
+impl Function for JsonFieldFunction {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn return_type(&self, _: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::LargeUtf8)
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: ScalarFunctionArgs,
+    ) -> datafusion_common::Result<ColumnarValue> {
+        extract_field(Self::NAME, args, |line, key| {
+            let Ok(Value::Object(mut object)) = serde_json::from_str::<Value>(line) else {
+                return None;
+            };
+            match object.remove(key)? {
+                Value::Null => None,
+                Value::String(s) => Some(s),
+                other => Some(other.to_string()),
+            }
+        })
+    }
+}
+
+/// LOGFMT_FIELD(line, key) returns the value of `key` in the logfmt encoded
+/// `line`, e.g. `level` of `level=info msg="hello world"`.
+///
+/// Quoted values are unescaped. A key without a value yields an empty string.
+#[derive(Debug)]
+pub struct LogfmtFieldFunction {
+    signature: Signature,
+}
+
+impl LogfmtFieldFunction {
+    pub const NAME: &'static str = "logfmt_field";
+
+    pub fn register(registry: &FunctionRegistry) {
+        registry.register_scalar(LogfmtFieldFunction::default());
+    }
+}
+
+impl Default for LogfmtFieldFunction {
+    fn default() -> Self {
+        Self {
+            signature: Signature::string(2, Volatility::Immutable),
+        }
+    }
+}
+
+impl fmt::Display for LogfmtFieldFunction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", Self::NAME.to_ascii_uppercase())
+    }
+}
+
+impl Function for LogfmtFieldFunction {
+    fn name(&self) -> &str {
+        Self::NAME
+    }
+
+    fn return_type(&self, _: &[DataType]) -> datafusion_common::Result<DataType> {
+        Ok(DataType::LargeUtf8)
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn invoke_with_args(
+        &self,
+        args: ScalarFunctionArgs,
+    ) -> datafusion_common::Result<ColumnarValue> {
+        extract_field(Self::NAME, args, logfmt_value)
+    }
+}
+
+fn extract_field(
+    name: &str,
+    args: ScalarFunctionArgs,
+    extract: impl Fn(&str, &str) -> Option<String>,
+) -> datafusion_common::Result<ColumnarValue> {
+    if args.args.len() != 2 {
+        return Err(DataFusionError::Execution(format!(
+            "{} requires exactly two arguments (line, key)",
+            name.to_ascii_uppercase()
+        )));
+    }
+
+    let arrays = ColumnarValue::values_to_arrays(&args.args)?;
+    let lines = cast(arrays[0].as_ref(), &DataType::LargeUtf8)?;
+    let keys = cast(arrays[1].as_ref(), &DataType::LargeUtf8)?;
+    let lines = lines.as_string::<i64>();
+    let keys = keys.as_string::<i64>();
+
+    let mut builder = LargeStringBuilder::with_capacity(lines.len(), 0);
+    for i in 0..lines.len() {
+        if lines.is_null(i) || keys.is_null(i) {
+            builder.append_null();
+            continue;
+        }
+        builder.append_option(extract(lines.value(i), keys.value(i)));
+    }
+
+    Ok(ColumnarValue::Array(Arc::new(builder.finish())))
+}
+
+/// Returns the value of the first `key` pair in a logfmt line.
+fn logfmt_value(line: &str, key: &str) -> Option<String> {
+    let mut chars = line.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        chars.peek()?;
+
+        let mut current_key = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '=') {
+            current_key.push(c);
+        }
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'=').is_some() {
+            if chars.next_if_eq(&'"').is_some() {
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => value.push('\n'),
+                            Some('t') => value.push('\t'),
+                            Some('r') => value.push('\r'),
+                            Some(c) => value.push(c),
+                            None => break,
+                        },
+                        c => value.push(c),
+                    }
+                }
+            } else {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    value.push(c);
+                }
+            }
+        }
+
+        if current_key == key {
+            return Some(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion_common::arrow::array::StringArray;
+    use datafusion_common::arrow::datatypes::Field;
+
+    use super::*;
+
+    fn invoke(function: &dyn Function, lines: Vec<Option<&str>>, key: &str) -> Vec<Option<String>> {
+        let number_rows = lines.len();
+        let args = ScalarFunctionArgs {
+            args: vec![
+                ColumnarValue::Array(Arc::new(StringArray::from(lines))),
+                ColumnarValue::Scalar(key.into()),
+            ],
+            arg_fields: vec![
+                Arc::new(Field::new("arg_0", DataType::Utf8, true)),
+                Arc::new(Field::new("arg_1", DataType::Utf8, false)),
+            ],
+            return_field: Arc::new(Field::new("result", DataType::LargeUtf8, true)),
+            number_rows,
+            config_options: Arc::new(datafusion_common::config::ConfigOptions::default()),
+        };
+
+        let ColumnarValue::Array(array) = function.invoke_with_args(args).unwrap() else {
+            panic!("expected array");
+        };
+        array
+            .as_string::<i64>()
+            .iter()
+            .map(|v| v.map(ToString::to_string))
+            .collect()
+    }
+
+    #[test]
+    fn test_json_field() {
+        let lines = vec![
+            Some(r#"{"level":"error","code":500,"nested":{"a":1}}"#),
+            Some(r#"{"code":200}"#),
+            Some("not json"),
+            None,
+        ];
+        let function = JsonFieldFunction::default();
+
+        assert_eq!(
+            invoke(&function, lines.clone(), "level"),
+            vec![Some("error".to_string()), None, None, None]
+        );
+        assert_eq!(
+            invoke(&function, lines.clone(), "code"),
+            vec![Some("500".to_string()), Some("200".to_string()), None, None]
+        );
+        assert_eq!(
+            invoke(&function, lines, "nested"),
+            vec![Some(r#"{"a":1}"#.to_string()), None, None, None]
+        );
+    }
+
+    #[test]
+    fn test_logfmt_field() {
+        let lines = vec![
+            Some(r#"level=info msg="hello \"world\"" duration=12ms"#),
+            Some("  debug level=warn"),
+            Some("no pairs here"),
+            None,
+        ];
+        let function = LogfmtFieldFunction::default();
+
+        assert_eq!(
+            invoke(&function, lines.clone(), "level"),
+            vec![
+                Some("info".to_string()),
+                Some("warn".to_string()),
+                None,
+                None
+            ]
+        );
+        assert_eq!(
+            invoke(&function, lines.clone(), "msg"),
+            vec![Some(r#"hello "world""#.to_string()), None, None, None]
+        );
+        assert_eq!(
+            invoke(&function, lines, "debug"),
+            vec![None, Some(String::new()), None, None]
+        );
+    }
+}
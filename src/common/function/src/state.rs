@@ -134,6 +134,14 @@ impl FunctionState {
                 Ok(Output::new_with_affected_rows(ROWS))
             }
 
+            async fn replace(
+                &self,
+                _request: InsertRequest,
+                _ctx: QueryContextRef,
+            ) -> Result<Output> {
+                Ok(Output::new_with_affected_rows(ROWS))
+            }
+
             async fn delete(
                 &self,
                 _request: DeleteRequest,
@@ -16,6 +16,7 @@ mod container;
 mod flow;
 mod registry;
 mod table;
+mod user;
 
 pub use container::{CacheContainer, InitStrategy, Initializer, Invalidator, TokenFilter};
 pub use flow::{TableFlownodeSetCache, TableFlownodeSetCacheRef, new_table_flownode_set_cache};
@@ -29,3 +30,4 @@ pub use table::{
     TableSchemaCacheRef, ViewInfoCache, ViewInfoCacheRef, new_schema_cache, new_table_info_cache,
     new_table_name_cache, new_table_route_cache, new_table_schema_cache, new_view_info_cache,
 };
+pub use user::{UserCache, UserCacheRef, new_user_cache};
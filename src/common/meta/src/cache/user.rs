@@ -0,0 +1,105 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+
+use futures::future::BoxFuture;
+use moka::future::Cache;
+use snafu::OptionExt;
+
+use crate::cache::{CacheContainer, Initializer};
+use crate::error;
+use crate::error::Result;
+use crate::instruction::CacheIdent;
+use crate::key::user::{UserManager, UserManagerRef, UserValue};
+use crate::kv_backend::KvBackendRef;
+
+/// [UserCache] caches the username to [UserValue] mapping.
+pub type UserCache = CacheContainer<String, Arc<UserValue>, CacheIdent>;
+
+pub type UserCacheRef = Arc<UserCache>;
+
+/// Constructs a [UserCache].
+pub fn new_user_cache(
+    name: String,
+    cache: Cache<String, Arc<UserValue>>,
+    kv_backend: KvBackendRef,
+) -> UserCache {
+    let user_manager = Arc::new(UserManager::new(kv_backend));
+    let init = init_factory(user_manager);
+
+    CacheContainer::new(name, cache, Box::new(invalidator), init, filter)
+}
+
+fn init_factory(user_manager: UserManagerRef) -> Initializer<String, Arc<UserValue>> {
+    Arc::new(move |username| {
+        let user_manager = user_manager.clone();
+        Box::pin(async move {
+            let user = user_manager
+                .get(username)
+                .await?
+                .context(error::ValueNotExistSnafu {})?
+                .into_inner();
+
+            Ok(Some(Arc::new(user)))
+        })
+    })
+}
+
+fn invalidator<'a>(
+    cache: &'a Cache<String, Arc<UserValue>>,
+    idents: &'a [&CacheIdent],
+) -> BoxFuture<'a, Result<()>> {
+    Box::pin(async move {
+        for ident in idents {
+            if let CacheIdent::ManagedUser(username) = ident {
+                cache.invalidate(username).await
+            }
+        }
+        Ok(())
+    })
+}
+
+fn filter(ident: &CacheIdent) -> bool {
+    matches!(ident, CacheIdent::ManagedUser(_))
+}
+
+#[cfg(test)]
+mod tests {
+    use moka::future::CacheBuilder;
+
+    use super::*;
+    use crate::kv_backend::memory::MemoryKvBackend;
+
+    #[tokio::test]
+    async fn test_user_cache() {
+        let mem_kv = Arc::new(MemoryKvBackend::default());
+        let user_manager = UserManager::new(mem_kv.clone());
+        let cache = CacheBuilder::new(128).build();
+        let cache = new_user_cache("test".to_string(), cache, mem_kv);
+
+        assert!(cache.get_by_ref("alice").await.unwrap().is_none());
+        let value = UserValue::new(vec!["verifier".to_string()]);
+        user_manager.create("alice", &value).await.unwrap();
+        let user = cache.get_by_ref("alice").await.unwrap().unwrap();
+        assert_eq!(*user, value);
+
+        assert!(cache.contains_key("alice"));
+        cache
+            .invalidate(&[CacheIdent::ManagedUser("alice".to_string())])
+            .await
+            .unwrap();
+        assert!(!cache.contains_key("alice"));
+    }
+}
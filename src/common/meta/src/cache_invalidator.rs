@@ -28,6 +28,7 @@ use crate::key::table_info::TableInfoKey;
 use crate::key::table_name::TableNameKey;
 use crate::key::table_route::TableRouteKey;
 use crate::key::tombstone::to_tombstone_key;
+use crate::key::user::UserKey;
 use crate::key::view_info::ViewInfoKey;
 use crate::key::{
     MetadataKey, drop_generation_key, dropped_at_key, purging_key, retention_expires_at_key,
@@ -177,6 +178,10 @@ where
                     // User cache invalidation is handled by external
                     // CacheInvalidator implementations.
                 }
+                CacheIdent::ManagedUser(username) => {
+                    let key = UserKey::new(username);
+                    self.invalidate_key(&key.to_bytes()).await;
+                }
             }
         }
         Ok(())
@@ -403,6 +403,10 @@ pub enum CacheIdent {
     DropFlow(DropFlow),
     /// Indicate change of user metadata.
     User(UserCacheIdent),
+    /// Indicate change of a user created by `CREATE USER`.
+    ///
+    /// These users are global, so only the username identifies them.
+    ManagedUser(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
@@ -65,6 +65,10 @@
 //! 13. Topic name to region map key `__topic_region/{topic_name}/{region_id}`
 //!     - Mapping {topic_name} to {region_id}
 //!
+//! 14. User key: `__user/{username}`
+//!     - The value is a [UserValue](crate::key::user::UserValue) struct; it contains the password
+//!       verifiers and table grants of a user created by `CREATE USER`.
+//!
 //! All keys have related managers. The managers take care of the serialization and deserialization
 //! of keys and values, and the interaction with the underlying KV store backend.
 //!
@@ -114,6 +118,7 @@ pub mod tombstone;
 pub mod topic_name;
 pub mod topic_region;
 pub mod txn_helper;
+pub mod user;
 pub mod view_info;
 
 use std::collections::{BTreeMap, HashMap, HashSet};
@@ -163,6 +168,7 @@ use crate::key::table_repart::{TableRepartKey, TableRepartManager};
 use crate::key::table_route::TableRouteKey;
 use crate::key::topic_region::TopicRegionValue;
 use crate::key::txn_helper::TxnOpGetResponseSet;
+use crate::key::user::UserValue;
 use crate::kv_backend::KvBackendRef;
 use crate::kv_backend::txn::{Txn, TxnOp};
 use crate::rpc::KeyValue;
@@ -191,6 +197,7 @@ pub const KAFKA_TOPIC_KEY_PREFIX: &str = "__topic_name/kafka";
 // The legacy topic key prefix is used to store the topic name in previous versions.
 pub const LEGACY_TOPIC_KEY_PREFIX: &str = "__created_wal_topics/kafka";
 pub const TOPIC_REGION_PREFIX: &str = "__topic_region";
+pub const USER_KEY_PREFIX: &str = "__user";
 
 /// The election key.
 pub const ELECTION_KEY: &str = "__metasrv_election";
@@ -339,6 +346,12 @@ lazy_static! {
         Regex::new(&format!("^{KAFKA_TOPIC_KEY_PREFIX}/(.*)$")).unwrap();
 }
 
+lazy_static! {
+    /// USER_KEY: {USER_KEY_PREFIX}/{username}
+    static ref USER_KEY_PATTERN: Regex =
+        Regex::new(&format!("^{USER_KEY_PREFIX}/(.+)$")).unwrap();
+}
+
 lazy_static! {
     pub static ref TOPIC_REGION_PATTERN: Regex = Regex::new(&format!(
         "^{TOPIC_REGION_PREFIX}/({TOPIC_NAME_PATTERN})/([0-9]+)$"
@@ -1886,7 +1899,8 @@ impl_metadata_value! {
     SchemaNameValue,
     FlowStateValue,
     PoisonValue,
-    TopicRegionValue
+    TopicRegionValue,
+    UserValue
 }
 
 impl_optional_metadata_value! {
@@ -0,0 +1,313 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ensure};
+
+use crate::error::{self, Error, InvalidMetadataSnafu, Result};
+use crate::key::{
+    DeserializedValueWithBytes, MetadataKey, MetadataValue, USER_KEY_PATTERN, USER_KEY_PREFIX,
+};
+use crate::kv_backend::KvBackendRef;
+use crate::kv_backend::txn::Txn;
+
+/// The user key, stores a user created by `CREATE USER`.
+///
+/// The layout: `__user/{username}`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UserKey<'a> {
+    pub username: &'a str,
+}
+
+impl<'a> UserKey<'a> {
+    pub fn new(username: &'a str) -> Self {
+        Self { username }
+    }
+}
+
+impl<'a> MetadataKey<'a, UserKey<'a>> for UserKey<'_> {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.to_string().into_bytes()
+    }
+
+    fn from_bytes(bytes: &'a [u8]) -> Result<UserKey<'a>> {
+        let key = std::str::from_utf8(bytes).map_err(|e| {
+            InvalidMetadataSnafu {
+                err_msg: format!(
+                    "UserKey '{}' is not a valid UTF8 string: {e}",
+                    String::from_utf8_lossy(bytes)
+                ),
+            }
+            .build()
+        })?;
+        UserKey::try_from(key)
+    }
+}
+
+impl Display for UserKey<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", USER_KEY_PREFIX, self.username)
+    }
+}
+
+impl<'a> TryFrom<&'a str> for UserKey<'a> {
+    type Error = Error;
+
+    fn try_from(s: &'a str) -> Result<Self> {
+        let captures = USER_KEY_PATTERN.captures(s).context(InvalidMetadataSnafu {
+            err_msg: format!("Illegal UserKey format: '{s}'"),
+        })?;
+
+        // Safety: pass the regex check above
+        Ok(Self {
+            username: captures.get(1).unwrap().as_str(),
+        })
+    }
+}
+
+/// A privilege granted to a user.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UserPrivilege {
+    Select,
+    Insert,
+    Delete,
+    Alter,
+    Drop,
+}
+
+/// Privileges granted on a table, or on every table of a schema if `table` is `None`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserGrant {
+    pub catalog: String,
+    pub schema: String,
+    pub table: Option<String>,
+    pub privileges: Vec<UserPrivilege>,
+}
+
+impl UserGrant {
+    fn is_target(&self, catalog: &str, schema: &str, table: Option<&str>) -> bool {
+        self.catalog == catalog && self.schema == schema && self.table.as_deref() == table
+    }
+}
+
+/// The value of [UserKey].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UserValue {
+    /// Password verifiers, the plaintext password is never stored.
+    pub password_verifiers: Vec<String>,
+    #[serde(default)]
+    pub grants: Vec<UserGrant>,
+}
+
+impl UserValue {
+    pub fn new(password_verifiers: Vec<String>) -> Self {
+        Self {
+            password_verifiers,
+            grants: vec![],
+        }
+    }
+
+    /// Adds `privileges` on the target to the grants.
+    pub fn grant(
+        &mut self,
+        catalog: &str,
+        schema: &str,
+        table: Option<&str>,
+        privileges: &[UserPrivilege],
+    ) {
+        let grant = match self
+            .grants
+            .iter()
+            .position(|grant| grant.is_target(catalog, schema, table))
+        {
+            Some(index) => &mut self.grants[index],
+            None => {
+                self.grants.push(UserGrant {
+                    catalog: catalog.to_string(),
+                    schema: schema.to_string(),
+                    table: table.map(|table| table.to_string()),
+                    privileges: vec![],
+                });
+                self.grants.last_mut().unwrap()
+            }
+        };
+        for privilege in privileges {
+            if !grant.privileges.contains(privilege) {
+                grant.privileges.push(*privilege);
+            }
+        }
+    }
+
+    /// Removes `privileges` from the grant on the target.
+    ///
+    /// Only the grant on exactly the same target is changed, e.g., revoking on
+    /// `db.*` doesn't change the grant on `db.table`.
+    pub fn revoke(
+        &mut self,
+        catalog: &str,
+        schema: &str,
+        table: Option<&str>,
+        privileges: &[UserPrivilege],
+    ) {
+        for grant in &mut self.grants {
+            if grant.is_target(catalog, schema, table) {
+                grant
+                    .privileges
+                    .retain(|privilege| !privileges.contains(privilege));
+            }
+        }
+        self.grants.retain(|grant| !grant.privileges.is_empty());
+    }
+}
+
+pub type UserManagerRef = Arc<UserManager>;
+
+/// The manager of [UserKey].
+#[derive(Clone)]
+pub struct UserManager {
+    kv_backend: KvBackendRef,
+}
+
+impl UserManager {
+    pub fn new(kv_backend: KvBackendRef) -> Self {
+        Self { kv_backend }
+    }
+
+    /// Creates a user, returns false if the user already exists.
+    pub async fn create(&self, username: &str, value: &UserValue) -> Result<bool> {
+        let raw_key = UserKey::new(username).to_bytes();
+        let raw_value = value.try_as_raw_value()?;
+
+        self.kv_backend
+            .put_conditionally(raw_key, raw_value, true)
+            .await
+    }
+
+    pub async fn get(
+        &self,
+        username: &str,
+    ) -> Result<Option<DeserializedValueWithBytes<UserValue>>> {
+        let raw_key = UserKey::new(username).to_bytes();
+        self.kv_backend
+            .get(&raw_key)
+            .await?
+            .map(|x| DeserializedValueWithBytes::from_inner_slice(&x.value))
+            .transpose()
+    }
+
+    /// Updates a user if it's still `current_value`.
+    pub async fn update(
+        &self,
+        username: &str,
+        current_value: &DeserializedValueWithBytes<UserValue>,
+        new_value: &UserValue,
+    ) -> Result<()> {
+        let raw_key = UserKey::new(username).to_bytes();
+        let txn = Txn::compare_and_put(
+            raw_key,
+            current_value.get_raw_bytes(),
+            new_value.try_as_raw_value()?,
+        );
+        let r = self.kv_backend.txn(txn).await?;
+
+        // Don't print the values, they contain password verifiers.
+        ensure!(
+            r.succeeded,
+            error::UnexpectedSnafu {
+                err_msg: format!("User '{username}' was modified concurrently"),
+            }
+        );
+
+        Ok(())
+    }
+
+    /// Deletes a user, returns false if the user doesn't exist.
+    pub async fn delete(&self, username: &str) -> Result<bool> {
+        let raw_key = UserKey::new(username).to_bytes();
+        let prev = self.kv_backend.delete(&raw_key, true).await?;
+
+        Ok(prev.is_some())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kv_backend::memory::MemoryKvBackend;
+
+    #[test]
+    fn test_serialization() {
+        let key = UserKey::new("Alice");
+        assert_eq!(key.to_string(), "__user/Alice");
+
+        let parsed = UserKey::from_bytes(b"__user/Alice").unwrap();
+        assert_eq!(key, parsed);
+        assert!(UserKey::from_bytes(b"__user/").is_err());
+    }
+
+    #[test]
+    fn test_grant_and_revoke() {
+        let mut value = UserValue::new(vec!["verifier".to_string()]);
+        value.grant("greptime", "public", None, &[UserPrivilege::Select]);
+        value.grant(
+            "greptime",
+            "public",
+            None,
+            &[UserPrivilege::Select, UserPrivilege::Insert],
+        );
+        value.grant("greptime", "public", Some("cpu"), &[UserPrivilege::Insert]);
+        assert_eq!(value.grants.len(), 2);
+        assert_eq!(
+            value.grants[0].privileges,
+            vec![UserPrivilege::Select, UserPrivilege::Insert]
+        );
+
+        value.revoke("greptime", "public", None, &[UserPrivilege::Insert]);
+        assert_eq!(value.grants[0].privileges, vec![UserPrivilege::Select]);
+        // Only the exact target is revoked.
+        assert_eq!(value.grants[1].privileges, vec![UserPrivilege::Insert]);
+
+        value.revoke("greptime", "public", Some("cpu"), &[UserPrivilege::Insert]);
+        assert_eq!(value.grants.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_user_manager() {
+        let manager = UserManager::new(Arc::new(MemoryKvBackend::default()));
+        let value = UserValue::new(vec!["verifier".to_string()]);
+
+        assert!(manager.create("alice", &value).await.unwrap());
+        assert!(!manager.create("alice", &value).await.unwrap());
+
+        let current = manager.get("alice").await.unwrap().unwrap();
+        assert_eq!(*current, value);
+        let mut new_value = value.clone();
+        new_value.grant("greptime", "public", None, &[UserPrivilege::Select]);
+        manager.update("alice", &current, &new_value).await.unwrap();
+        // The stale value can't be used to update the user.
+        assert!(manager.update("alice", &current, &value).await.is_err());
+        assert_eq!(
+            manager.get("alice").await.unwrap().unwrap().into_inner(),
+            new_value
+        );
+
+        assert!(manager.delete("alice").await.unwrap());
+        assert!(!manager.delete("alice").await.unwrap());
+        assert!(manager.get("alice").await.unwrap().is_none());
+    }
+}
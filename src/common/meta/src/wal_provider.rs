@@ -229,6 +229,7 @@ pub enum WalProvider {
     #[default]
     RaftEngine,
     Kafka(KafkaTopicPool),
+    ObjectStore,
 }
 
 /// Arc wrapper of WalProvider.
@@ -251,7 +252,7 @@ impl WalProvider {
     /// Tries to start the provider.
     pub async fn start(&self) -> Result<()> {
         match self {
-            Self::RaftEngine => Ok(()),
+            Self::RaftEngine | Self::ObjectStore => Ok(()),
             Self::Kafka(kafka_topic_manager) => kafka_topic_manager.activate().await,
         }
     }
@@ -272,12 +273,13 @@ impl WalProvider {
                     .collect();
                 Ok(options_batch)
             }
+            WalProvider::ObjectStore => Ok(vec![WalOptions::ObjectStore; num_regions]),
         }
     }
 
     /// Returns true if it's the remote WAL.
     pub fn is_remote_wal(&self) -> bool {
-        matches!(&self, WalProvider::Kafka(_))
+        matches!(&self, WalProvider::Kafka(_) | WalProvider::ObjectStore)
     }
 }
 
@@ -315,6 +317,7 @@ pub async fn build_wal_provider(
             let topic_pool = KafkaTopicPool::new(kafka_config, kv_backend, topic_creator);
             Ok(WalProvider::Kafka(topic_pool))
         }
+        MetasrvWalConfig::ObjectStore => Ok(WalProvider::ObjectStore),
     }
 }
 
@@ -359,6 +362,28 @@ mod tests {
     use crate::test_util::test_kafka_topic_pool;
     use crate::wal_provider::selector::RoundRobinTopicSelector;
 
+    // Tests that the wal provider could successfully allocate object store wal options.
+    #[tokio::test]
+    async fn test_provider_with_object_store() {
+        let kv_backend = Arc::new(MemoryKvBackend::new()) as KvBackendRef;
+        let wal_config = MetasrvWalConfig::ObjectStore;
+        let provider = build_wal_provider(&wal_config, kv_backend).await.unwrap();
+        provider.start().await.unwrap();
+        assert!(provider.is_remote_wal());
+
+        let regions = (0..8).collect::<Vec<_>>();
+        let got = provider.allocate(&regions, false).await.unwrap();
+        let expected = regions
+            .iter()
+            .copied()
+            .zip(vec![WalOptions::ObjectStore; regions.len()])
+            .collect();
+        assert_eq!(got, expected);
+
+        let got = provider.allocate(&regions, true).await.unwrap();
+        assert!(got.values().all(|options| *options == WalOptions::Noop));
+    }
+
     // Tests that the wal provider could successfully allocate raft-engine wal options.
     #[tokio::test]
     async fn test_provider_with_raft_engine() {
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub mod kafka;
+pub mod object_store;
 pub mod raft_engine;
 
 use std::time::Duration;
@@ -25,6 +26,7 @@ use crate::config::kafka::common::{
     DEFAULT_PERIODIC_CHECKPOINT_PERSIST_INTERVAL, DEFAULT_REGION_FLUSH_TRIGGER_INTERVAL,
 };
 use crate::config::kafka::{DatanodeKafkaConfig, MetasrvKafkaConfig};
+use crate::config::object_store::ObjectStoreWalConfig;
 use crate::config::raft_engine::RaftEngineConfig;
 use crate::error::{Error, UnsupportedWalProviderSnafu};
 
@@ -36,6 +38,7 @@ pub enum MetasrvWalConfig {
     #[default]
     RaftEngine,
     Kafka(MetasrvKafkaConfig),
+    ObjectStore,
 }
 
 #[allow(clippy::large_enum_variant)]
@@ -45,6 +48,7 @@ pub enum MetasrvWalConfig {
 pub enum DatanodeWalConfig {
     RaftEngine(RaftEngineConfig),
     Kafka(DatanodeKafkaConfig),
+    ObjectStore(ObjectStoreWalConfig),
     Noop,
 }
 
@@ -79,6 +83,7 @@ impl TryFrom<DatanodeWalConfig> for MetasrvWalConfig {
                 // This field won't be used in standalone mode
                 periodic_checkpoint_persist_interval: DEFAULT_PERIODIC_CHECKPOINT_PERSIST_INTERVAL,
             })),
+            DatanodeWalConfig::ObjectStore(_) => Ok(Self::ObjectStore),
             DatanodeWalConfig::Noop => UnsupportedWalProviderSnafu {
                 provider: "noop".to_string(),
             }
@@ -91,7 +96,11 @@ impl MetasrvWalConfig {
     /// Returns if active wal pruning is enabled.
     pub fn enable_active_wal_pruning(&self) -> bool {
         match self {
-            MetasrvWalConfig::RaftEngine => false,
+            // Object store wal segments live in the namespace of their region, so the datanode
+            // deletes them once the region is flushed, like the raft engine wal. Regions without
+            // writes are still flushed by the periodic flush of the datanode, so no prune
+            // procedure or flush trigger is required.
+            MetasrvWalConfig::RaftEngine | MetasrvWalConfig::ObjectStore => false,
             MetasrvWalConfig::Kafka(config) => config.auto_prune_interval > Duration::ZERO,
         }
     }
@@ -99,7 +108,7 @@ impl MetasrvWalConfig {
     /// Gets the kafka connection config.
     pub fn remote_wal_options(&self) -> Option<&MetasrvKafkaConfig> {
         match self {
-            MetasrvWalConfig::RaftEngine => None,
+            MetasrvWalConfig::RaftEngine | MetasrvWalConfig::ObjectStore => None,
             MetasrvWalConfig::Kafka(config) => Some(config),
         }
     }
@@ -114,6 +123,7 @@ impl From<MetasrvWalConfig> for DatanodeWalConfig {
                 kafka_topic: config.kafka_topic,
                 ..Default::default()
             }),
+            MetasrvWalConfig::ObjectStore => Self::ObjectStore(ObjectStoreWalConfig::default()),
         }
     }
 }
@@ -176,6 +186,43 @@ mod tests {
         assert_eq!(datanode_wal_config, DatanodeWalConfig::RaftEngine(expected));
     }
 
+    #[test]
+    fn test_toml_object_store() {
+        let toml_str = r#"
+            provider = "object_store"
+        "#;
+        let metasrv_wal_config: MetasrvWalConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(metasrv_wal_config, MetasrvWalConfig::ObjectStore);
+
+        let datanode_wal_config: DatanodeWalConfig = toml::from_str(toml_str).unwrap();
+        assert_eq!(
+            datanode_wal_config,
+            DatanodeWalConfig::ObjectStore(ObjectStoreWalConfig::default())
+        );
+
+        let toml_str = r#"
+            provider = "object_store"
+            segment_dir = "shared_wal/"
+            read_batch_size = 64
+            group_commit_interval = "10ms"
+        "#;
+        let datanode_wal_config: DatanodeWalConfig = toml::from_str(toml_str).unwrap();
+        let expected = ObjectStoreWalConfig {
+            segment_dir: "shared_wal/".to_string(),
+            read_batch_size: 64,
+            group_commit_interval: Duration::from_millis(10),
+            ..Default::default()
+        };
+        assert_eq!(
+            datanode_wal_config,
+            DatanodeWalConfig::ObjectStore(expected.clone())
+        );
+        assert_eq!(
+            MetasrvWalConfig::try_from(DatanodeWalConfig::ObjectStore(expected)).unwrap(),
+            MetasrvWalConfig::ObjectStore
+        );
+    }
+
     #[test]
     fn test_toml_kafka() {
         let toml_str = r#"
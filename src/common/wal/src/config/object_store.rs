@@ -0,0 +1,53 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::time::Duration;
+
+use common_base::readable_size::ReadableSize;
+use serde::{Deserialize, Serialize};
+
+/// The default directory of wal segments in the object store.
+pub const DEFAULT_OBJECT_STORE_WAL_DIR: &str = "wal/";
+
+/// Configurations for object storage backed wal.
+///
+/// Wal segments are written to the object store of the datanode, so the wal
+/// can be shared by all datanodes accessing the same object store.
+///
+/// Segments of a region are deleted by the datanode once the region is flushed,
+/// like the raft engine wal, so the metasrv doesn't prune this wal.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ObjectStoreWalConfig {
+    /// Directory of wal segments, relative to the root of the object store.
+    pub segment_dir: String,
+    /// Max number of entries to yield in a batch when reading the wal.
+    pub read_batch_size: usize,
+    /// Max time to wait for more entries before committing the buffered entries.
+    #[serde(with = "humantime_serde")]
+    pub group_commit_interval: Duration,
+    /// Buffered entries are committed once their size reaches this limit.
+    pub group_commit_size: ReadableSize,
+}
+
+impl Default for ObjectStoreWalConfig {
+    fn default() -> Self {
+        Self {
+            segment_dir: DEFAULT_OBJECT_STORE_WAL_DIR.to_string(),
+            read_batch_size: 128,
+            group_commit_interval: Duration::from_millis(5),
+            group_commit_size: ReadableSize::mb(4),
+        }
+    }
+}
@@ -33,6 +33,7 @@ pub enum WalOptions {
     RaftEngine,
     #[serde(with = "kafka_prefix")]
     Kafka(KafkaWalOptions),
+    ObjectStore,
     Noop,
 }
 
@@ -81,6 +82,15 @@ mod tests {
             WalOptions::Kafka(KafkaWalOptions::new("test_topic".to_string()))
         );
 
+        // Test serde object store wal options.
+        let wal_options = WalOptions::ObjectStore;
+        let encoded = serde_json::to_string(&wal_options).unwrap();
+        let expected = r#"{"wal.provider":"object_store"}"#;
+        assert_eq!(&encoded, expected);
+
+        let decoded: WalOptions = serde_json::from_str(&encoded).unwrap();
+        assert_eq!(decoded, wal_options);
+
         // Test serde noop wal options.
         let wal_options = WalOptions::Noop;
         let encoded = serde_json::to_string(&wal_options).unwrap();
@@ -0,0 +1,166 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Remote compaction.
+//!
+//! A compaction worker started by `greptime compactor` serves compaction jobs over the
+//! Arrow Flight `DoAction` RPC. It reads and writes SSTs directly against the object
+//! store shared with the datanodes. Datanodes send jobs to the workers through the
+//! [RemoteCompactionScheduler].
+//!
+//! Workers serve over TLS if `grpc.tls` is configured and authenticate the datanodes
+//! by the `user_provider` like the gRPC service of frontends. A worker refuses to start
+//! without a `user_provider` unless `disable_auth` is set.
+
+mod scheduler;
+mod service;
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use common_base::Plugins;
+use common_config::Configurable;
+use common_options::memory::MemoryOptions;
+use common_telemetry::logging::{LoggingOptions, TracingOptions};
+use common_telemetry::{info, warn};
+use mito2::config::MitoConfig;
+pub use scheduler::RemoteCompactionScheduler;
+use serde::{Deserialize, Serialize};
+use servers::grpc::GrpcOptions;
+use servers::grpc::builder::GrpcServerBuilder;
+use servers::server::{ServerHandler, ServerHandlers};
+pub use service::CompactorService;
+use snafu::{ResultExt, ensure};
+
+use crate::config::StorageConfig;
+use crate::datanode::DatanodeBuilder;
+use crate::error::{
+    InvalidCompactorAuthConfigSnafu, InvalidCompactorMitoConfigSnafu,
+    InvalidCompactorTlsConfigSnafu, MissingCompactorUserProviderSnafu, ParseAddrSnafu, Result,
+    ShutdownServerSnafu, StartServerSnafu,
+};
+
+/// The action type of a compaction job.
+const COMPACT_ACTION: &str = "compact";
+
+/// Options of a compaction worker.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct CompactorOptions {
+    pub grpc: GrpcOptions,
+    /// The user provider to authenticate the datanodes sending compaction jobs.
+    pub user_provider: Option<String>,
+    /// Accepts compaction jobs from anyone if there is no `user_provider`.
+    pub disable_auth: bool,
+    /// The object storage shared with the datanodes.
+    pub storage: StorageConfig,
+    /// The mito engine options used to read and write SSTs.
+    pub mito: MitoConfig,
+    pub logging: LoggingOptions,
+    pub tracing: TracingOptions,
+    pub memory: MemoryOptions,
+}
+
+impl Default for CompactorOptions {
+    fn default() -> Self {
+        Self {
+            grpc: GrpcOptions::default().with_bind_addr("127.0.0.1:3010"),
+            user_provider: None,
+            disable_auth: false,
+            storage: StorageConfig::default(),
+            mito: MitoConfig::default(),
+            logging: LoggingOptions::default(),
+            tracing: TracingOptions::default(),
+            memory: MemoryOptions::default(),
+        }
+    }
+}
+
+impl Configurable for CompactorOptions {}
+
+/// A compaction worker.
+pub struct Compactor {
+    services: ServerHandlers,
+}
+
+impl Compactor {
+    pub async fn try_new(opts: &CompactorOptions, plugins: Plugins) -> Result<Self> {
+        let user_provider = opts
+            .user_provider
+            .as_deref()
+            .map(auth::user_provider_from_option)
+            .transpose()
+            .context(InvalidCompactorAuthConfigSnafu)?;
+        if user_provider.is_none() {
+            ensure!(opts.disable_auth, MissingCompactorUserProviderSnafu);
+            warn!("Authentication of the compactor is disabled, all compaction jobs are accepted");
+        }
+
+        let mut mito_config = opts.mito.clone();
+        mito_config
+            .sanitize(&opts.storage.data_home)
+            .context(InvalidCompactorMitoConfigSnafu)?;
+        let object_store_manager =
+            DatanodeBuilder::build_object_store_manager(&opts.storage).await?;
+
+        let service =
+            CompactorService::new(mito_config, object_store_manager, user_provider, plugins);
+        let grpc_server =
+            GrpcServerBuilder::new(opts.grpc.as_config(), common_runtime::global_runtime())
+                .with_tls_config(opts.grpc.tls.clone())
+                .context(InvalidCompactorTlsConfigSnafu)?
+                .flight_handler(Arc::new(service))
+                .build();
+        let addr: SocketAddr = opts.grpc.bind_addr.parse().context(ParseAddrSnafu {
+            addr: &opts.grpc.bind_addr,
+        })?;
+
+        let services = ServerHandlers::default();
+        let handler: ServerHandler = (Box::new(grpc_server), addr);
+        services.insert(handler);
+
+        Ok(Self { services })
+    }
+
+    pub async fn start(&mut self) -> Result<()> {
+        info!("Starting compactor instance...");
+        self.services.start_all().await.context(StartServerSnafu)
+    }
+
+    pub fn server_handlers(&self) -> &ServerHandlers {
+        &self.services
+    }
+
+    pub async fn shutdown(&mut self) -> Result<()> {
+        self.services
+            .shutdown_all()
+            .await
+            .context(ShutdownServerSnafu)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    #[tokio::test]
+    async fn test_compactor_requires_user_provider() {
+        let result = Compactor::try_new(&CompactorOptions::default(), Plugins::default()).await;
+        assert!(matches!(
+            result,
+            Err(Error::MissingCompactorUserProvider { .. })
+        ));
+    }
+}
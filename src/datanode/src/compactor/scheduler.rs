@@ -0,0 +1,239 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+use arrow_flight::Action;
+use arrow_flight::flight_service_client::FlightServiceClient;
+use async_trait::async_trait;
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use common_grpc::reloadable_tls::TlsConfigLoader;
+use common_telemetry::{info, warn};
+use mito2::compaction::remote::{RemoteCompactionRequest, commit_remote_compaction};
+use mito2::error::RemoteCompactionSnafu;
+use mito2::manifest::action::RegionEdit;
+use mito2::schedule::remote_job_scheduler::{
+    CompactionJobResult, JobId, Notifier, RemoteJob, RemoteJobResult, RemoteJobScheduler,
+    RemoteJobSchedulerError,
+};
+use servers::http::AUTHORIZATION_HEADER;
+use snafu::{ResultExt, location};
+use tonic::metadata::AsciiMetadataValue;
+use tonic::transport::{Channel, ClientTlsConfig, Endpoint};
+
+use crate::compactor::COMPACT_ACTION;
+use crate::config::RemoteCompactionConfig;
+use crate::error::{InvalidCompactorTlsConfigSnafu, Result};
+
+/// A [RemoteJobScheduler] that sends compaction jobs to compaction workers.
+///
+/// Workers are tried in round-robin order. If none of them is reachable, the job is
+/// handed back to the mito engine, which falls back to local compaction.
+pub struct RemoteCompactionScheduler {
+    worker_addrs: Vec<String>,
+    connect_timeout: Duration,
+    timeout: Duration,
+    /// Connects to the workers over TLS if present.
+    tls_config: Option<ClientTlsConfig>,
+    /// The basic authorization sent to the workers.
+    authorization: Option<AsciiMetadataValue>,
+    next_worker: AtomicUsize,
+}
+
+impl RemoteCompactionScheduler {
+    pub fn try_new(config: &RemoteCompactionConfig) -> Result<Self> {
+        let tls_config = config.tls.load().context(InvalidCompactorTlsConfigSnafu)?;
+        let authorization = config.username.as_ref().and_then(|username| {
+            let password = config.password.as_deref().unwrap_or_default();
+            let encoded = BASE64_STANDARD.encode(format!("{username}:{password}"));
+            // Base64 encoded credentials are always a valid metadata value.
+            format!("Basic {encoded}").parse().ok()
+        });
+        Ok(Self {
+            worker_addrs: config.worker_addrs.clone(),
+            connect_timeout: config.connect_timeout,
+            timeout: config.timeout,
+            tls_config,
+            authorization,
+            next_worker: AtomicUsize::new(0),
+        })
+    }
+
+    fn endpoint(&self, addr: &str) -> std::result::Result<Endpoint, tonic::transport::Error> {
+        let scheme = if self.tls_config.is_some() {
+            "https"
+        } else {
+            "http"
+        };
+        let endpoint = Endpoint::from_shared(format!("{scheme}://{addr}"))?
+            .connect_timeout(self.connect_timeout)
+            .timeout(self.timeout);
+        match &self.tls_config {
+            Some(tls_config) => endpoint.tls_config(tls_config.clone()),
+            None => Ok(endpoint),
+        }
+    }
+
+    /// Connects to the next reachable compaction worker.
+    async fn connect(&self) -> Option<(String, Channel)> {
+        let start = self.next_worker.fetch_add(1, Ordering::Relaxed);
+        for i in 0..self.worker_addrs.len() {
+            let addr = &self.worker_addrs[(start + i) % self.worker_addrs.len()];
+            let endpoint = match self.endpoint(addr) {
+                Ok(endpoint) => endpoint,
+                Err(e) => {
+                    warn!(e; "Invalid compaction worker address {}", addr);
+                    continue;
+                }
+            };
+            match endpoint.connect().await {
+                Ok(channel) => return Some((addr.clone(), channel)),
+                Err(e) => warn!(e; "Failed to connect to compaction worker {}", addr),
+            }
+        }
+        None
+    }
+}
+
+/// Runs the encoded compaction job on the worker and returns the [RegionEdit] of it.
+async fn execute(
+    channel: Channel,
+    body: Vec<u8>,
+    authorization: Option<AsciiMetadataValue>,
+) -> std::result::Result<RegionEdit, String> {
+    let mut client = FlightServiceClient::new(channel);
+    let mut request = tonic::Request::new(Action {
+        r#type: COMPACT_ACTION.to_string(),
+        body: body.into(),
+    });
+    if let Some(authorization) = authorization {
+        request
+            .metadata_mut()
+            .insert(AUTHORIZATION_HEADER, authorization);
+    }
+    let mut stream = client
+        .do_action(request)
+        .await
+        .map_err(|e| e.message().to_string())?
+        .into_inner();
+    let result = stream
+        .message()
+        .await
+        .map_err(|e| e.message().to_string())?
+        .ok_or_else(|| "compaction worker returned no result".to_string())?;
+
+    serde_json::from_slice(&result.body).map_err(|e| format!("invalid region edit: {e}"))
+}
+
+#[async_trait]
+impl RemoteJobScheduler for RemoteCompactionScheduler {
+    async fn schedule(
+        &self,
+        job: RemoteJob,
+        notifier: Box<dyn Notifier>,
+    ) -> std::result::Result<JobId, RemoteJobSchedulerError> {
+        let RemoteJob::CompactionJob(mut job) = job;
+        let region_id = job.compaction_region.region_id;
+        let body = match serde_json::to_vec(&RemoteCompactionRequest::from(&job)) {
+            Ok(body) => body,
+            Err(e) => {
+                return Err(RemoteJobSchedulerError {
+                    location: location!(),
+                    reason: format!("failed to encode compaction job: {e}"),
+                    waiters: job.waiters,
+                });
+            }
+        };
+        let Some((addr, channel)) = self.connect().await else {
+            return Err(RemoteJobSchedulerError {
+                location: location!(),
+                reason: "no compaction worker is reachable".to_string(),
+                waiters: job.waiters,
+            });
+        };
+
+        let job_id = JobId::random();
+        info!(
+            "Sending compaction job {} of region {} to worker {}",
+            job_id, region_id, addr
+        );
+        let waiters = std::mem::take(&mut job.waiters);
+        let authorization = self.authorization.clone();
+        common_runtime::spawn_global(async move {
+            let region_edit = match execute(channel, body, authorization).await {
+                Ok(edit) => {
+                    commit_remote_compaction(&job.compaction_region, &job.picker_output, edit).await
+                }
+                Err(reason) => RemoteCompactionSnafu {
+                    region_id,
+                    job_id: Some(job_id),
+                    reason,
+                }
+                .fail(),
+            };
+            let result = CompactionJobResult {
+                job_id,
+                region_id,
+                start_time: job.start_time,
+                region_edit,
+            };
+            notifier
+                .notify(RemoteJobResult::CompactionJobResult(result), waiters)
+                .await;
+        });
+
+        Ok(job_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_connect_unreachable_workers() {
+        let scheduler = RemoteCompactionScheduler::try_new(&RemoteCompactionConfig {
+            worker_addrs: vec!["127.0.0.1:1".to_string(), "not an address".to_string()],
+            connect_timeout: Duration::from_millis(100),
+            ..Default::default()
+        })
+        .unwrap();
+        assert!(scheduler.connect().await.is_none());
+
+        let scheduler =
+            RemoteCompactionScheduler::try_new(&RemoteCompactionConfig::default()).unwrap();
+        assert!(scheduler.connect().await.is_none());
+    }
+
+    #[test]
+    fn test_authorization() {
+        let scheduler = RemoteCompactionScheduler::try_new(&RemoteCompactionConfig {
+            username: Some("greptime".to_string()),
+            password: Some("secret".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(
+            "Basic Z3JlcHRpbWU6c2VjcmV0",
+            scheduler.authorization.unwrap().to_str().unwrap()
+        );
+
+        let scheduler =
+            RemoteCompactionScheduler::try_new(&RemoteCompactionConfig::default()).unwrap();
+        assert!(scheduler.authorization.is_none());
+        assert!(scheduler.tls_config.is_none());
+    }
+}
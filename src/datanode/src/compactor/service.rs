@@ -0,0 +1,115 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use arrow_flight::{Action, FlightData, Ticket};
+use async_trait::async_trait;
+use auth::UserProviderRef;
+use common_base::Plugins;
+use common_error::ext::ErrorExt;
+use common_telemetry::{error, info};
+use mito2::compaction::remote::{RemoteCompactionRequest, execute_remote_compaction};
+use mito2::config::MitoConfig;
+use object_store::manager::ObjectStoreManagerRef;
+use servers::grpc::context_auth::{check_auth, create_query_context_from_grpc_metadata};
+use servers::grpc::flight::{FlightCraft, TonicStream};
+use tonic::{Request, Response, Result as TonicResult, Status};
+
+use crate::compactor::COMPACT_ACTION;
+
+/// Serves the compaction jobs sent by the [RemoteCompactionScheduler](crate::compactor::RemoteCompactionScheduler).
+pub struct CompactorService {
+    mito_config: Arc<MitoConfig>,
+    object_store_manager: ObjectStoreManagerRef,
+    /// Authenticates the compaction jobs. It's only `None` if the authentication is
+    /// explicitly disabled by `disable_auth`, then all jobs are accepted.
+    user_provider: Option<UserProviderRef>,
+    plugins: Plugins,
+}
+
+impl CompactorService {
+    pub fn new(
+        mito_config: MitoConfig,
+        object_store_manager: ObjectStoreManagerRef,
+        user_provider: Option<UserProviderRef>,
+        plugins: Plugins,
+    ) -> Self {
+        Self {
+            mito_config: Arc::new(mito_config),
+            object_store_manager,
+            user_provider,
+            plugins,
+        }
+    }
+}
+
+#[async_trait]
+impl FlightCraft for CompactorService {
+    async fn do_get(
+        &self,
+        _request: Request<Ticket>,
+    ) -> TonicResult<Response<TonicStream<FlightData>>> {
+        Err(Status::unimplemented(
+            "Compactor only serves compaction actions",
+        ))
+    }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<TonicStream<arrow_flight::Result>>> {
+        let query_ctx = create_query_context_from_grpc_metadata(request.metadata())?;
+        check_auth(self.user_provider.clone(), request.metadata(), query_ctx).await?;
+
+        let action = request.into_inner();
+        if action.r#type != COMPACT_ACTION {
+            return Err(Status::invalid_argument(format!(
+                "Unknown action: {}",
+                action.r#type
+            )));
+        }
+        let request: RemoteCompactionRequest = serde_json::from_slice(&action.body)
+            .map_err(|e| Status::invalid_argument(format!("Invalid compaction job: {e}")))?;
+
+        let region_id = request.region_id;
+        let start = Instant::now();
+        info!("Received compaction job for region {}", region_id);
+        let edit = execute_remote_compaction(
+            request,
+            &self.mito_config,
+            self.object_store_manager.clone(),
+            self.plugins.clone(),
+        )
+        .await
+        .map_err(|e| {
+            error!(e; "Failed to compact region {}", region_id);
+            Status::internal(e.output_msg())
+        })?;
+        info!(
+            "Compacted region {}, files to add: {}, files to remove: {}, elapsed: {:?}",
+            region_id,
+            edit.files_to_add.len(),
+            edit.files_to_remove.len(),
+            start.elapsed()
+        );
+
+        let body = serde_json::to_vec(&edit)
+            .map_err(|e| Status::internal(format!("Failed to encode region edit: {e}")))?;
+        let stream =
+            futures::stream::once(async move { Ok(arrow_flight::Result { body: body.into() }) });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
@@ -18,6 +18,7 @@ use std::time::Duration;
 
 use common_base::readable_size::ReadableSize;
 use common_config::{Configurable, DEFAULT_DATA_HOME};
+use common_grpc::channel_manager::ClientTlsOption;
 use common_options::memory::MemoryOptions;
 pub use common_procedure::options::ProcedureConfig;
 use common_telemetry::logging::{LoggingOptions, TracingOptions};
@@ -94,6 +95,8 @@ pub struct DatanodeOptions {
     pub tracing: TracingOptions,
     pub query: QueryOptions,
     pub memory: MemoryOptions,
+    /// Options to offload compactions to remote compaction workers.
+    pub remote_compaction: RemoteCompactionConfig,
 
     /// Environment variable keys to read and report in heartbeat messages.
     /// The values of these env vars at startup will be sent to metasrv.
@@ -153,6 +156,7 @@ impl Default for DatanodeOptions {
             tracing: TracingOptions::default(),
             query: QueryOptions::default(),
             memory: MemoryOptions::default(),
+            remote_compaction: RemoteCompactionConfig::default(),
             heartbeat_env_vars: vec![],
 
             // Deprecated options
@@ -170,11 +174,52 @@ impl Configurable for DatanodeOptions {
         Some(&[
             "heartbeat_env_vars",
             "meta_client.metasrv_addrs",
+            "remote_compaction.worker_addrs",
             "wal.broker_endpoints",
         ])
     }
 }
 
+/// Remote compaction client config.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct RemoteCompactionConfig {
+    /// Addresses of the compaction workers. Remote compaction is disabled if it's empty.
+    pub worker_addrs: Vec<String>,
+    /// Timeout to connect to a compaction worker.
+    #[serde(with = "humantime_serde")]
+    pub connect_timeout: Duration,
+    /// Timeout of a single compaction job.
+    #[serde(with = "humantime_serde")]
+    pub timeout: Duration,
+    /// The username to authenticate to the compaction workers.
+    pub username: Option<String>,
+    /// The password to authenticate to the compaction workers.
+    pub password: Option<String>,
+    /// TLS options to connect to the compaction workers.
+    pub tls: ClientTlsOption,
+}
+
+impl Default for RemoteCompactionConfig {
+    fn default() -> Self {
+        Self {
+            worker_addrs: vec![],
+            connect_timeout: Duration::from_secs(3),
+            timeout: Duration::from_secs(60 * 60),
+            username: None,
+            password: None,
+            tls: ClientTlsOption::default(),
+        }
+    }
+}
+
+impl RemoteCompactionConfig {
+    /// Returns true if any compaction worker is configured.
+    pub fn is_enabled(&self) -> bool {
+        !self.worker_addrs.is_empty()
+    }
+}
+
 #[allow(clippy::large_enum_variant)]
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub enum RegionEngineConfig {
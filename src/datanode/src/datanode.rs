@@ -34,11 +34,13 @@ use common_stat::ResourceStatImpl;
 use common_telemetry::{error, info, warn};
 use common_wal::config::DatanodeWalConfig;
 use common_wal::config::kafka::DatanodeKafkaConfig;
+use common_wal::config::object_store::ObjectStoreWalConfig;
 use common_wal::config::raft_engine::RaftEngineConfig;
 use file_engine::engine::FileRegionEngine;
 use log_store::kafka::log_store::KafkaLogStore;
 use log_store::kafka::{GlobalIndexCollector, default_index_file};
 use log_store::noop::log_store::NoopLogStore;
+use log_store::object_store::log_store::ObjectStoreLogStore;
 use log_store::raft_engine::log_store::RaftEngineLogStore;
 use meta_client::MetaClientRef;
 use metric_engine::engine::MetricEngine;
@@ -59,7 +61,7 @@ use store_api::region_engine::{
 use tokio::fs;
 use tokio::sync::Notify;
 
-use crate::config::{DatanodeOptions, RegionEngineConfig, StorageConfig};
+use crate::config::{DatanodeOptions, ObjectStoreConfig, RegionEngineConfig, StorageConfig};
 use crate::error::{
     self, BuildDatanodeSnafu, BuildMetricEngineSnafu, BuildMitoEngineSnafu, CreateDirSnafu,
     DataFusionSnafu, GetMetadataSnafu, MissingCacheSnafu, MissingNodeIdSnafu, OpenLogStoreSnafu,
@@ -630,6 +632,32 @@ impl DatanodeBuilder {
 
                 builder.try_build().await.context(BuildMitoEngineSnafu)?
             }
+            DatanodeWalConfig::ObjectStore(object_store_config) => {
+                let log_store = Self::build_object_store_log_store(
+                    &opts.storage.store,
+                    &opts.storage.data_home,
+                    object_store_config,
+                )
+                .await?;
+
+                let builder = MitoEngineBuilder::new(
+                    &opts.storage.data_home,
+                    config,
+                    log_store,
+                    object_store_manager,
+                    schema_metadata_manager,
+                    file_ref_manager,
+                    partition_expr_fetcher.clone(),
+                    plugins,
+                );
+
+                #[cfg(feature = "enterprise")]
+                let builder = builder.with_extension_range_provider_factory(
+                    self.extension_range_provider_factory.take(),
+                );
+
+                builder.try_build().await.context(BuildMitoEngineSnafu)?
+            }
             DatanodeWalConfig::Noop => {
                 let log_store = Arc::new(NoopLogStore);
 
@@ -694,6 +722,22 @@ impl DatanodeBuilder {
             .map(Arc::new)
     }
 
+    /// Builds [`ObjectStoreLogStore`] on the default storage of the datanode.
+    async fn build_object_store_log_store(
+        store: &ObjectStoreConfig,
+        data_home: &str,
+        config: &ObjectStoreWalConfig,
+    ) -> Result<Arc<ObjectStoreLogStore>> {
+        // Wal segments are written once and read on replay, so caching them is useless.
+        let object_store = new_object_store_without_cache(store, data_home).await?;
+        info!(
+            "Creating object store logstore with config: {:?} and storage: {}",
+            config,
+            store.provider_name()
+        );
+        Ok(Arc::new(ObjectStoreLogStore::new(object_store, config)))
+    }
+
     /// Builds [`GlobalIndexCollector`]
     fn build_global_index_collector(
         dump_index_interval: Duration,
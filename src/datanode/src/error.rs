@@ -348,6 +348,35 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid mito config for compactor"))]
+    InvalidCompactorMitoConfig {
+        source: mito2::error::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Invalid TLS config for remote compaction"))]
+    InvalidCompactorTlsConfig {
+        source: common_grpc::error::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Invalid user provider config for compactor"))]
+    InvalidCompactorAuthConfig {
+        source: auth::error::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display(
+        "Compactor requires a user_provider to authenticate datanodes, set disable_auth to accept all compaction jobs"
+    ))]
+    MissingCompactorUserProvider {
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display("Failed to build metric engine"))]
     BuildMetricEngine {
         source: metric_engine::error::Error,
@@ -503,7 +532,12 @@ impl ErrorExt for Error {
             StopRegionEngine { source, .. } => source.status_code(),
 
             FindLogicalRegions { source, .. } => source.status_code(),
-            BuildMitoEngine { source, .. } | GcMitoEngine { source, .. } => source.status_code(),
+            BuildMitoEngine { source, .. }
+            | GcMitoEngine { source, .. }
+            | InvalidCompactorMitoConfig { source, .. } => source.status_code(),
+            InvalidCompactorTlsConfig { .. }
+            | InvalidCompactorAuthConfig { .. }
+            | MissingCompactorUserProvider { .. } => StatusCode::InvalidArguments,
             BuildMetricEngine { source, .. } => source.status_code(),
             ListStorageSsts { source, .. } => source.status_code(),
             ConcurrentQueryLimiterClosed { .. } | ConcurrentQueryLimiterTimeout { .. } => {
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub mod alive_keeper;
+pub mod compactor;
 pub mod config;
 pub mod datanode;
 pub mod error;
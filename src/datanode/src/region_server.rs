@@ -29,7 +29,8 @@ use api::region::RegionResponse;
 use api::v1::meta::TopicStat;
 use api::v1::region::sync_request::ManifestInfo;
 use api::v1::region::{
-    ListMetadataRequest, RegionResponse as RegionResponseV1, SyncRequest, region_request,
+    ListMetadataRequest, RegionRequestHeader, RegionResponse as RegionResponseV1, SyncRequest,
+    region_request,
 };
 use api::v1::{ResponseHeader, Status};
 use arrow_flight::{FlightData, Ticket};
@@ -82,7 +83,7 @@ use store_api::region_engine::{
 };
 use store_api::region_request::{
     AffectedRows, BatchRegionDdlRequest, RegionCatchupRequest, RegionCloseRequest,
-    RegionOpenRequest, RegionRequest,
+    RegionOpenRequest, RegionRequest, is_replacing_existing_rows,
 };
 use store_api::storage::RegionId;
 use tokio::sync::{OwnedSemaphorePermit, Semaphore, mpsc};
@@ -513,12 +514,24 @@ impl RegionServer {
             .await
     }
 
+    /// Handles the insert or delete requests in parallel.
+    ///
+    /// The put requests replace the existing rows with the same key if
+    /// `replace_existing` is true.
     async fn handle_requests_in_parallel(
         &self,
         request: region_request::Body,
+        replace_existing: bool,
     ) -> Result<RegionResponse> {
-        let requests =
+        let mut requests =
             RegionRequest::try_from_request_body(request).context(BuildRegionRequestsSnafu)?;
+        if replace_existing {
+            for (_, request) in &mut requests {
+                if let RegionRequest::Put(put) = request {
+                    put.replace_existing = true;
+                }
+            }
+        }
 
         // Try to optimize batch Put requests for metric engine
         // Returns either Some(response) or None(requests_back)
@@ -911,7 +924,11 @@ impl Stream for RegionWatermarkStream {
 
 #[async_trait]
 impl RegionServerHandler for RegionServer {
-    async fn handle(&self, request: region_request::Body) -> ServerResult<RegionResponseV1> {
+    async fn handle(
+        &self,
+        header: RegionRequestHeader,
+        request: region_request::Body,
+    ) -> ServerResult<RegionResponseV1> {
         let failed_requests_cnt = crate::metrics::REGION_SERVER_REQUEST_FAILURE_COUNT
             .with_label_values(&[request.as_ref()]);
         let response = match &request {
@@ -919,7 +936,8 @@ impl RegionServerHandler for RegionServer {
             | region_request::Body::Drops(_)
             | region_request::Body::Alters(_) => self.handle_batch_ddl_requests(request).await,
             region_request::Body::Inserts(_) | region_request::Body::Deletes(_) => {
-                self.handle_requests_in_parallel(request).await
+                self.handle_requests_in_parallel(request, is_replacing_existing_rows(&header))
+                    .await
             }
             region_request::Body::Sync(sync_request) => {
                 self.handle_sync_region_request(sync_request).await
@@ -2011,6 +2029,7 @@ mod tests {
                 rows: rows(),
                 hint: None,
                 partition_expr_version: None,
+                replace_existing: false,
             },
         )));
         assert!(RegionServerInner::is_ingest_request(
@@ -31,6 +31,7 @@ use crate::expr::{self, Batch, GlobalId, LocalId};
 use crate::plan::{Plan, TypedPlan};
 use crate::repr::{self, DiffRow, RelationType};
 
+mod join;
 mod map;
 mod reduce;
 mod src_sink;
@@ -157,10 +158,7 @@ impl Context<'_, '_> {
                 key_val_plan,
                 reduce_plan,
             } => self.render_reduce(input, key_val_plan, reduce_plan, plan.schema.typ),
-            Plan::Join { .. } => NotImplementedSnafu {
-                reason: "Join is still WIP",
-            }
-            .fail(),
+            Plan::Join { inputs, plan } => self.render_join(inputs, plan),
             Plan::Union { .. } => NotImplementedSnafu {
                 reason: "Union is still WIP",
             }
@@ -399,7 +397,7 @@ mod test {
         mut bundle: CollectionBundle,
     ) -> Rc<RefCell<Vec<DiffRow>>> {
         let collection = bundle.collection;
-        let _arranged = bundle.arranged.pop_first().unwrap().1;
+        let _arranged = bundle.arranged.pop_first();
         let output = Rc::new(RefCell::new(vec![]));
         let output_inner = output.clone();
         let _subgraph = ctx.df.add_subgraph_sink(
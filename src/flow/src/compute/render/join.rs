@@ -0,0 +1,587 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Render `Plan::Join` into an incremental equi-join.
+//!
+//! Each stage of a linear join keeps a full arrangement for both of its inputs, with rows
+//! arranged by their join key followed by the row itself. For updates `dS` and `dL` arriving
+//! at the stream and lookup side, the stage emits `dS x L + (S + dS) x dL`, so both
+//! insertions and retractions on either side are reflected in the output.
+
+use datatypes::value::Value;
+use dfir_rs::scheduled::graph_ext::GraphExt;
+use itertools::Itertools;
+use snafu::OptionExt;
+
+use crate::compute::render::{Context, SubgraphArg};
+use crate::compute::types::{Collection, CollectionBundle, ErrCollector, Toff};
+use crate::error::{Error, InvalidQuerySnafu, PlanSnafu};
+use crate::expr::error::DataAlreadyExpiredSnafu;
+use crate::expr::{EvalError, ScalarExpr};
+use crate::plan::{JoinFilter, JoinPlan, LinearJoinPlan, LinearStagePlan, TypedPlan};
+use crate::repr::{self, DiffRow, Row};
+use crate::utils::{ArrangeHandler, Arrangement, KeyExpiryManager};
+
+/// An update to one side of a join, split into `(join key, value, diff)`
+type JoinUpdate = (Row, Row, repr::Diff);
+
+impl Context<'_, '_> {
+    const JOIN: &'static str = "join";
+    const JOIN_FILTER: &'static str = "join_filter";
+
+    /// render `Plan::Join` into executable dataflow
+    pub fn render_join(
+        &mut self,
+        inputs: Vec<TypedPlan>,
+        plan: JoinPlan,
+    ) -> Result<CollectionBundle, Error> {
+        match plan {
+            JoinPlan::Linear(linear) => self.render_linear_join(inputs, linear),
+        }
+    }
+
+    fn render_linear_join(
+        &mut self,
+        inputs: Vec<TypedPlan>,
+        plan: LinearJoinPlan,
+    ) -> Result<CollectionBundle, Error> {
+        let mut inputs = inputs
+            .into_iter()
+            .map(|input| {
+                let time_index = input.schema.typ().time_index;
+                self.render_plan(input)
+                    .map(|bundle| Some((bundle.collection, time_index)))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut take_input = |idx: usize| {
+            inputs
+                .get_mut(idx)
+                .and_then(Option::take)
+                .with_context(|| InvalidQuerySnafu {
+                    reason: format!("Join input {idx} not found or used more than once"),
+                })
+        };
+
+        let (mut stream, mut stream_time_index) = take_input(plan.source_relation)?;
+        if let Some(closure) = plan.initial_closure {
+            stream = self.render_join_filter(stream, closure);
+            stream_time_index = None;
+        }
+        for stage in plan.stage_plans {
+            let (lookup, lookup_time_index) = take_input(stage.lookup_relation)?;
+            stream = self.render_join_stage(
+                stream,
+                stream_time_index,
+                lookup,
+                lookup_time_index,
+                stage,
+            )?;
+            // the time index of joined rows is not tracked for later stages
+            stream_time_index = None;
+        }
+        if let Some(closure) = plan.final_closure {
+            stream = self.render_join_filter(stream, closure);
+        }
+
+        for (unused, _) in inputs.into_iter().flatten() {
+            unused.into_inner().drop(self.df);
+        }
+
+        Ok(CollectionBundle::from_collection(stream))
+    }
+
+    /// Render a binary join between the accumulated `stream` and a new `lookup` collection.
+    ///
+    /// If `expire_after` is set for this dataflow, rows on either side with a time index are
+    /// removed from the join state once expired, which bounds the state of stream-stream joins.
+    fn render_join_stage(
+        &mut self,
+        stream: Collection<DiffRow>,
+        stream_time_index: Option<usize>,
+        lookup: Collection<DiffRow>,
+        lookup_time_index: Option<usize>,
+        stage: LinearStagePlan,
+    ) -> Result<Collection<DiffRow>, Error> {
+        let key_arity = stage.stream_key.len();
+        // arranged rows are the join key followed by the value, so shift the time index by key arity
+        let stream_arrange = self.new_join_arrange(
+            stream_time_index
+                .and_then(|ts| stage.stream_thinning.iter().position(|c| *c == ts))
+                .map(|ts| key_arity + ts),
+        )?;
+        let lookup_arrange = self.new_join_arrange(lookup_time_index.map(|ts| key_arity + ts))?;
+        let join_arrange = JoinArrange {
+            stream: stream_arrange,
+            lookup: lookup_arrange,
+        };
+
+        let now = self.compute_state.current_time_ref();
+        let err_collector = self.err_collector.clone();
+        let scheduler = self.compute_state.get_scheduler();
+        let scheduler_inner = scheduler.clone();
+
+        let (out_send_port, out_recv_port) = self.df.make_edge::<_, Toff>(Self::JOIN);
+
+        let subgraph = self.df.add_subgraph_2in_out(
+            Self::JOIN,
+            stream.into_inner(),
+            lookup.into_inner(),
+            out_send_port,
+            move |_ctx, stream_recv, lookup_recv, send| {
+                let stream_data = stream_recv
+                    .take_inner()
+                    .into_iter()
+                    .flat_map(|v| v.into_iter())
+                    .collect_vec();
+                let lookup_data = lookup_recv
+                    .take_inner()
+                    .into_iter()
+                    .flat_map(|v| v.into_iter())
+                    .collect_vec();
+
+                join_stage_subgraph(
+                    &join_arrange,
+                    stream_data,
+                    lookup_data,
+                    &stage,
+                    SubgraphArg {
+                        now: *now.borrow(),
+                        err_collector: &err_collector,
+                        scheduler: &scheduler_inner,
+                        send,
+                    },
+                );
+            },
+        );
+
+        scheduler.set_cur_subgraph(subgraph);
+
+        Ok(Collection::from_port(out_recv_port))
+    }
+
+    /// Create a full arrangement for one side of a join, expiring rows by the event time
+    /// in `time_index` column of the arranged row if `expire_after` is set.
+    fn new_join_arrange(&mut self, time_index: Option<usize>) -> Result<ArrangeHandler, Error> {
+        let arrange_handler = self.compute_state.new_arrange(None);
+
+        if let (Some(time_index), Some(expire_after)) =
+            (time_index, self.compute_state.expire_after())
+        {
+            let expire_man =
+                KeyExpiryManager::new(Some(expire_after), Some(ScalarExpr::Column(time_index)));
+            arrange_handler.write().set_expire_state(expire_man);
+        }
+
+        // join need full arrangement to be able to look up all rows of a key
+        arrange_handler.clone_full_arrange().context(PlanSnafu {
+            reason: "No write is expected at this point",
+        })
+    }
+
+    /// Apply a [`JoinFilter`] to each row of `input`, used for closures before and after the join stages.
+    fn render_join_filter(
+        &mut self,
+        input: Collection<DiffRow>,
+        filter: JoinFilter,
+    ) -> Collection<DiffRow> {
+        let (out_send_port, out_recv_port) = self.df.make_edge::<_, Toff>(Self::JOIN_FILTER);
+        let err_collector = self.err_collector.clone();
+        let scheduler = self.compute_state.get_scheduler();
+
+        let subgraph = self.df.add_subgraph_in_out(
+            Self::JOIN_FILTER,
+            input.into_inner(),
+            out_send_port,
+            move |_ctx, recv, send| {
+                let output = recv
+                    .take_inner()
+                    .into_iter()
+                    .flat_map(|v| v.into_iter())
+                    .filter_map(|(row, ts, diff)| {
+                        err_collector
+                            .run(|| eval_join_filter(&filter, row.inner))
+                            .flatten()
+                            .map(|row| (row, ts, diff))
+                    })
+                    .collect_vec();
+                send.give(output);
+            },
+        );
+        scheduler.set_cur_subgraph(subgraph);
+
+        Collection::from_port(out_recv_port)
+    }
+}
+
+/// The state of both sides of a join stage
+struct JoinArrange {
+    stream: ArrangeHandler,
+    lookup: ArrangeHandler,
+}
+
+fn join_stage_subgraph(
+    JoinArrange { stream, lookup }: &JoinArrange,
+    stream_data: Vec<DiffRow>,
+    lookup_data: Vec<DiffRow>,
+    stage: &LinearStagePlan,
+    SubgraphArg {
+        now,
+        err_collector,
+        scheduler: _,
+        send,
+    }: SubgraphArg,
+) {
+    if stream_data.is_empty() && lookup_data.is_empty() {
+        return;
+    }
+
+    let stream_updates = split_rows_to_join_updates(
+        stream_data,
+        &stage.stream_key,
+        Some(&stage.stream_thinning),
+        err_collector,
+    );
+    let lookup_updates =
+        split_rows_to_join_updates(lookup_data, &stage.lookup_key, None, err_collector);
+
+    // lock both arranges for the rest of function body, since we read one side while
+    // updating the other
+    let mut stream = stream.write();
+    let mut lookup = lookup.write();
+    stream.truncate_expired_keys(now);
+    lookup.truncate_expired_keys(now);
+    let stream_updates = remove_expired(&stream, stream_updates, now, err_collector);
+    let lookup_updates = remove_expired(&lookup, lookup_updates, now, err_collector);
+
+    let mut outputs = Vec::new();
+
+    // new stream rows join with lookup rows seen before
+    for (key, stream_val, diff) in &stream_updates {
+        for (lookup_val, lookup_diff) in get_join_matches(&lookup, now, key) {
+            if let Some(row) = err_collector
+                .run(|| eval_join_closure(&stage.closure, key, stream_val, &lookup_val))
+                .flatten()
+            {
+                outputs.push((row, now, diff * lookup_diff));
+            }
+        }
+    }
+    update_join_arrange(&mut stream, stream_updates, now, err_collector);
+
+    // new lookup rows join with all stream rows, including the ones just arrived
+    for (key, lookup_val, diff) in &lookup_updates {
+        for (stream_val, stream_diff) in get_join_matches(&stream, now, key) {
+            if let Some(row) = err_collector
+                .run(|| eval_join_closure(&stage.closure, key, &stream_val, lookup_val))
+                .flatten()
+            {
+                outputs.push((row, now, stream_diff * diff));
+            }
+        }
+    }
+    update_join_arrange(&mut lookup, lookup_updates, now, err_collector);
+
+    if !outputs.is_empty() {
+        send.give(outputs);
+    }
+}
+
+/// Split rows into join key and value, the value is the columns in `thinning`
+/// or the whole row if not given.
+///
+/// Rows with null in join key are dropped, since null never equals to anything in equi-join.
+fn split_rows_to_join_updates(
+    rows: Vec<DiffRow>,
+    key_exprs: &[ScalarExpr],
+    thinning: Option<&[usize]>,
+    err_collector: &ErrCollector,
+) -> Vec<JoinUpdate> {
+    rows.into_iter()
+        .filter_map(|(row, _ts, diff)| {
+            err_collector.run(|| {
+                let key = key_exprs
+                    .iter()
+                    .map(|e| e.eval(&row.inner))
+                    .collect::<Result<Vec<_>, _>>()?;
+                if key.iter().any(Value::is_null) {
+                    return Ok(None);
+                }
+                let val = match thinning {
+                    Some(thinning) => {
+                        Row::new(thinning.iter().map(|i| row.inner[*i].clone()).collect())
+                    }
+                    None => row,
+                };
+                Ok(Some((Row::new(key), val, diff)))
+            })?
+        })
+        .collect()
+}
+
+/// Join key followed by the value, which is how rows are arranged in join state
+fn arranged_row(key: &Row, val: &Row) -> Row {
+    Row::new(key.iter().chain(val.iter()).cloned().collect())
+}
+
+/// Remove updates whose event time is already expired, expired data is ignored with a warning
+fn remove_expired(
+    arrange: &Arrangement,
+    updates: Vec<JoinUpdate>,
+    now: repr::Timestamp,
+    err_collector: &ErrCollector,
+) -> Vec<JoinUpdate> {
+    let Some(expire_man) = arrange.get_expire_state() else {
+        return updates;
+    };
+    updates
+        .into_iter()
+        .filter(|(key, val, _diff)| {
+            let expired = err_collector
+                .run(|| expire_man.get_expire_duration(now, &arranged_row(key, val)))
+                .flatten();
+            if let Some(expired_by) = expired {
+                common_telemetry::warn!(
+                    "Data already expired: {}",
+                    DataAlreadyExpiredSnafu { expired_by }.build()
+                );
+            }
+            expired.is_none()
+        })
+        .collect()
+}
+
+/// Get the values and their multiplicity of all rows in `arrange` with join key `key`
+fn get_join_matches(
+    arrange: &Arrangement,
+    now: repr::Timestamp,
+    key: &Row,
+) -> Vec<(Row, repr::Diff)> {
+    arrange
+        .get_by_prefix(now, key)
+        .into_iter()
+        .filter(|(_, (_, _, diff))| *diff != 0)
+        .map(|(row, (_, _, diff))| (Row::new(row.inner[key.len()..].to_vec()), diff))
+        .collect()
+}
+
+/// Apply updates into join state, the arranged value is empty and the diff keeps the multiplicity of each row
+fn update_join_arrange(
+    arrange: &mut Arrangement,
+    updates: Vec<JoinUpdate>,
+    now: repr::Timestamp,
+    err_collector: &ErrCollector,
+) {
+    if updates.is_empty() {
+        return;
+    }
+    let updates = updates
+        .into_iter()
+        .map(|(key, val, diff)| ((arranged_row(&key, &val), Row::empty()), now, diff))
+        .collect_vec();
+    err_collector.run(|| {
+        arrange.apply_updates(now, updates)?;
+        arrange.compact_to(now)
+    });
+}
+
+/// Evaluate the closure of a join stage on the concatenation of key, stream value and lookup value
+fn eval_join_closure(
+    closure: &JoinFilter,
+    key: &Row,
+    stream_val: &Row,
+    lookup_val: &Row,
+) -> Result<Option<Row>, EvalError> {
+    let values = key
+        .iter()
+        .chain(stream_val.iter())
+        .chain(lookup_val.iter())
+        .cloned()
+        .collect_vec();
+    eval_join_filter(closure, values)
+}
+
+/// Check all `ready_equivalences` hold, then apply the map filter project of `filter`
+fn eval_join_filter(filter: &JoinFilter, mut values: Vec<Value>) -> Result<Option<Row>, EvalError> {
+    for exprs in &filter.ready_equivalences {
+        let mut evaluated = exprs.iter().map(|e| e.eval(&values));
+        if let Some(first) = evaluated.next() {
+            let first = first?;
+            for other in evaluated {
+                if other? != first {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+    filter.before.evaluate_into(&mut values, &mut Row::empty())
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use datatypes::data_type::ConcreteDataType;
+    use dfir_rs::scheduled::graph::Dfir;
+
+    use super::*;
+    use crate::compute::render::test::{get_output_handle, harness_test_ctx, run_and_check};
+    use crate::compute::state::DataflowState;
+    use crate::expr::{self, BinaryFunc, GlobalId};
+    use crate::plan::Plan;
+    use crate::repr::{ColumnType, RelationType};
+
+    fn row(values: Vec<Value>) -> Row {
+        Row::new(values)
+    }
+
+    /// Join `left(id, val)` with `right(id, name)` on `left.id = right.id`
+    fn join_plan(left_typ: RelationType, right_typ: RelationType) -> Plan {
+        let plan = LinearJoinPlan::binary_equi_join(
+            2,
+            2,
+            vec![ScalarExpr::Column(0).call_binary(ScalarExpr::Column(2), BinaryFunc::Eq)],
+        )
+        .unwrap();
+        Plan::Join {
+            inputs: vec![
+                Plan::Get {
+                    id: expr::Id::Global(GlobalId::User(1)),
+                }
+                .with_types(left_typ.into_unnamed()),
+                Plan::Get {
+                    id: expr::Id::Global(GlobalId::User(2)),
+                }
+                .with_types(right_typ.into_unnamed()),
+            ],
+            plan: JoinPlan::Linear(plan),
+        }
+    }
+
+    /// Test join with insertions and retractions on both sides
+    #[test]
+    fn test_render_join() {
+        let mut df = Dfir::new();
+        let mut state = DataflowState::default();
+        let mut ctx = harness_test_ctx(&mut df, &mut state);
+
+        let left = vec![
+            (row(vec![1i64.into(), 10i64.into()]), 1, 1),
+            (row(vec![2i64.into(), 20i64.into()]), 2, 1),
+            (row(vec![1i64.into(), 11i64.into()]), 4, 1),
+            (row(vec![1i64.into(), 10i64.into()]), 5, -1),
+        ];
+        let right = vec![
+            (row(vec![1i64.into(), "a".into()]), 1, 1),
+            (row(vec![2i64.into(), "b".into()]), 3, 1),
+            (row(vec![Value::Null, "c".into()]), 3, 1),
+            (row(vec![2i64.into(), "b".into()]), 6, -1),
+        ];
+        let left = ctx.render_constant(left);
+        let right = ctx.render_constant(right);
+        ctx.insert_global(GlobalId::User(1), left);
+        ctx.insert_global(GlobalId::User(2), right);
+
+        let typ = |second: ConcreteDataType| {
+            RelationType::new(vec![
+                ColumnType::new_nullable(ConcreteDataType::int64_datatype()),
+                ColumnType::new_nullable(second),
+            ])
+        };
+        let Plan::Join { inputs, plan } = join_plan(
+            typ(ConcreteDataType::int64_datatype()),
+            typ(ConcreteDataType::string_datatype()),
+        ) else {
+            unreachable!()
+        };
+        let bundle = ctx.render_join(inputs, plan).unwrap();
+
+        let output = get_output_handle(&mut ctx, bundle);
+        drop(ctx);
+        let joined = |id: i64, val: i64, name: &str| {
+            row(vec![id.into(), val.into(), id.into(), name.into()])
+        };
+        let expected = BTreeMap::from([
+            (1, vec![(joined(1, 10, "a"), 1, 1)]),
+            (3, vec![(joined(2, 20, "b"), 3, 1)]),
+            (4, vec![(joined(1, 11, "a"), 4, 1)]),
+            (5, vec![(joined(1, 10, "a"), 5, -1)]),
+            (6, vec![(joined(2, 20, "b"), 6, -1)]),
+        ]);
+        run_and_check(&mut state, &mut df, 1..7, expected, output);
+    }
+
+    /// Test join with non-equi condition and state expiry by time index
+    #[test]
+    fn test_render_join_expire() {
+        let mut df = Dfir::new();
+        let mut state = DataflowState::default();
+        state.set_expire_after(Some(2));
+        let mut ctx = harness_test_ctx(&mut df, &mut state);
+
+        // (id, ts)
+        let left = vec![
+            (row(vec![1i64.into(), 1i64.into()]), 1, 1),
+            (row(vec![1i64.into(), 5i64.into()]), 5, 1),
+        ];
+        let right = vec![
+            (row(vec![1i64.into(), 2i64.into()]), 2, 1),
+            // left row with ts=1 is expired at now=5
+            (row(vec![1i64.into(), 5i64.into()]), 5, 1),
+            // expired itself
+            (row(vec![1i64.into(), 0i64.into()]), 6, 1),
+        ];
+        let left = ctx.render_constant(left);
+        let right = ctx.render_constant(right);
+        ctx.insert_global(GlobalId::User(1), left);
+        ctx.insert_global(GlobalId::User(2), right);
+
+        let typ = RelationType::new(vec![
+            ColumnType::new_nullable(ConcreteDataType::int64_datatype()),
+            ColumnType::new_nullable(ConcreteDataType::int64_datatype()),
+        ])
+        .with_time_index(Some(1));
+        let plan = LinearJoinPlan::binary_equi_join(
+            2,
+            2,
+            vec![ScalarExpr::CallVariadic {
+                func: expr::VariadicFunc::And,
+                exprs: vec![
+                    ScalarExpr::Column(2).call_binary(ScalarExpr::Column(0), BinaryFunc::Eq),
+                    ScalarExpr::Column(1).call_binary(ScalarExpr::Column(3), BinaryFunc::Lte),
+                ],
+            }],
+        )
+        .unwrap();
+        let inputs = vec![
+            Plan::Get {
+                id: expr::Id::Global(GlobalId::User(1)),
+            }
+            .with_types(typ.clone().into_unnamed()),
+            Plan::Get {
+                id: expr::Id::Global(GlobalId::User(2)),
+            }
+            .with_types(typ.into_unnamed()),
+        ];
+        let bundle = ctx.render_join(inputs, JoinPlan::Linear(plan)).unwrap();
+
+        let output = get_output_handle(&mut ctx, bundle);
+        drop(ctx);
+        let joined = |l: i64, r: i64| row(vec![1i64.into(), l.into(), 1i64.into(), r.into()]);
+        let expected = BTreeMap::from([
+            (2, vec![(joined(1, 2), 2, 1)]),
+            (5, vec![(joined(5, 5), 5, 1)]),
+        ]);
+        run_and_check(&mut state, &mut df, 1..7, expected, output);
+    }
+}
@@ -12,7 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::expr::ScalarExpr;
+use crate::error::{Error, NotImplementedSnafu};
+use crate::expr::{BinaryFunc, MapFilterProject, ScalarExpr, VariadicFunc};
 use crate::plan::SafeMfpPlan;
 
 /// TODO(discord9): consider impl more join strategies
@@ -74,3 +75,116 @@ pub struct LinearStagePlan {
     /// the stream value columns, and the lookup value columns.
     pub closure: JoinFilter,
 }
+
+impl LinearJoinPlan {
+    /// Create a plan for inner joining relation `0` (the stream) with relation `1` (the lookup).
+    ///
+    /// `predicates` are the conjunctive join conditions over the concatenation of both
+    /// relations' columns. Equalities between an expression of the left columns and one of the
+    /// right columns become the join key, all others are evaluated as a filter on the joined row.
+    ///
+    /// The output is the concatenation of both relations' columns.
+    pub fn binary_equi_join(
+        left_arity: usize,
+        right_arity: usize,
+        predicates: Vec<ScalarExpr>,
+    ) -> Result<Self, Error> {
+        let is_left = |e: &ScalarExpr| {
+            let cols = e.get_all_ref_columns();
+            !cols.is_empty() && cols.iter().all(|c| *c < left_arity)
+        };
+        let is_right = |e: &ScalarExpr| {
+            let cols = e.get_all_ref_columns();
+            !cols.is_empty() && cols.iter().all(|c| *c >= left_arity)
+        };
+
+        let mut stream_key = vec![];
+        let mut lookup_key = vec![];
+        let mut residual = vec![];
+        for predicate in predicates.into_iter().flat_map(flatten_conjunction) {
+            match predicate {
+                ScalarExpr::CallBinary {
+                    func: BinaryFunc::Eq,
+                    expr1,
+                    expr2,
+                } if is_left(&expr1) && is_right(&expr2) => {
+                    stream_key.push(*expr1);
+                    lookup_key.push(*expr2);
+                }
+                ScalarExpr::CallBinary {
+                    func: BinaryFunc::Eq,
+                    expr1,
+                    expr2,
+                } if is_right(&expr1) && is_left(&expr2) => {
+                    stream_key.push(*expr2);
+                    lookup_key.push(*expr1);
+                }
+                _ => residual.push(predicate),
+            }
+        }
+
+        if stream_key.is_empty() {
+            return NotImplementedSnafu {
+                reason: "Join without equality condition between both sides is not supported",
+            }
+            .fail();
+        }
+        if residual.iter().any(|e| e.contains_temporal()) {
+            return NotImplementedSnafu {
+                reason: "Temporal filter in join condition is not supported",
+            }
+            .fail();
+        }
+
+        // lookup key is evaluated on the lookup relation alone
+        let shift_left = (0..left_arity + right_arity)
+            .map(|i| i.saturating_sub(left_arity))
+            .collect::<Vec<_>>();
+        for key in lookup_key.iter_mut() {
+            key.permute(&shift_left)?;
+        }
+
+        // the closure's input is the join key followed by both relations' columns
+        let key_arity = stream_key.len();
+        let shift_right = (0..left_arity + right_arity)
+            .map(|i| i + key_arity)
+            .collect::<Vec<_>>();
+        for predicate in residual.iter_mut() {
+            predicate.permute(&shift_right)?;
+        }
+        let input_arity = key_arity + left_arity + right_arity;
+        let before = MapFilterProject::new(input_arity)
+            .filter(residual)?
+            .project(key_arity..input_arity)?
+            .into_safe();
+
+        Ok(LinearJoinPlan {
+            source_relation: 0,
+            source_key: Some(stream_key.clone()),
+            initial_closure: None,
+            stage_plans: vec![LinearStagePlan {
+                lookup_relation: 1,
+                stream_key,
+                stream_thinning: (0..left_arity).collect(),
+                lookup_key,
+                closure: JoinFilter {
+                    ready_equivalences: vec![],
+                    before,
+                },
+            }],
+            final_closure: None,
+        })
+    }
+}
+
+/// Split nested `AND`s into a list of predicates
+fn flatten_conjunction(expr: ScalarExpr) -> Vec<ScalarExpr> {
+    match expr {
+        ScalarExpr::CallVariadic {
+            func: VariadicFunc::And,
+            exprs,
+        } => exprs.into_iter().flat_map(flatten_conjunction).collect(),
+        expr if expr.is_literal_true() => vec![],
+        expr => vec![expr],
+    }
+}
@@ -18,14 +18,15 @@ use itertools::Itertools;
 use snafu::OptionExt;
 use substrait::substrait_proto_df::proto::{FilterRel, ReadRel};
 use substrait_proto::proto::expression::MaskExpression;
+use substrait_proto::proto::join_rel::JoinType;
 use substrait_proto::proto::read_rel::ReadType;
 use substrait_proto::proto::rel::RelType;
-use substrait_proto::proto::{Plan as SubPlan, ProjectRel, Rel, plan_rel};
+use substrait_proto::proto::{JoinRel, Plan as SubPlan, ProjectRel, Rel, plan_rel};
 
 use crate::error::{Error, InvalidQuerySnafu, NotImplementedSnafu, PlanSnafu, UnexpectedSnafu};
 use crate::expr::{MapFilterProject, TypedExpr};
-use crate::plan::{Plan, TypedPlan};
-use crate::repr::{self, RelationType};
+use crate::plan::{JoinPlan, LinearJoinPlan, Plan, TypedPlan};
+use crate::repr::{self, Key, RelationType};
 use crate::transform::{FlownodeContext, FunctionExtensions, substrait_proto};
 
 impl TypedPlan {
@@ -146,6 +147,75 @@ impl TypedPlan {
         input.filter(expr)
     }
 
+    /// Convert an inner join into a [`Plan::Join`], whose output is the concatenation of
+    /// the left and right input's columns
+    #[async_recursion::async_recursion]
+    pub async fn from_substrait_join(
+        ctx: &mut FlownodeContext,
+        join: &JoinRel,
+        extensions: &FunctionExtensions,
+    ) -> Result<TypedPlan, Error> {
+        if join.r#type() != JoinType::Inner {
+            return not_impl_err!(
+                "Only inner join is supported, found {:?} join",
+                join.r#type()
+            );
+        }
+        let (Some(left), Some(right)) = (join.left.as_ref(), join.right.as_ref()) else {
+            return not_impl_err!("Join without both inputs is not supported");
+        };
+        let left = TypedPlan::from_substrait_rel(ctx, left, extensions).await?;
+        let right = TypedPlan::from_substrait_rel(ctx, right, extensions).await?;
+        let left_arity = left.schema.typ().arity();
+        let right_arity = right.schema.typ().arity();
+
+        let schema = {
+            let left_typ = left.schema.typ();
+            let right_typ = right.schema.typ();
+            let time_index = left_typ
+                .time_index
+                .or(right_typ.time_index.map(|i| i + left_arity));
+            let auto_columns = left_typ
+                .auto_columns
+                .iter()
+                .copied()
+                .chain(right_typ.auto_columns.iter().map(|i| i + left_arity))
+                .collect_vec();
+            // a joined row is unique by the combination of both sides' keys
+            let keys = match (left_typ.keys.first(), right_typ.keys.first()) {
+                (Some(left_key), Some(right_key)) => vec![Key::from(
+                    left_key
+                        .get()
+                        .iter()
+                        .copied()
+                        .chain(right_key.get().iter().map(|i| i + left_arity))
+                        .collect(),
+                )],
+                _ => vec![],
+            };
+            let mut schema = left.schema.clone().concat(right.schema.clone());
+            schema.typ.keys = keys;
+            schema.typ.time_index = time_index;
+            schema.typ.auto_columns = auto_columns;
+            schema
+        };
+
+        let mut predicates = Vec::new();
+        for expr in join.expression.iter().chain(join.post_join_filter.iter()) {
+            let expr = TypedExpr::from_substrait_rex(expr, &schema, extensions).await?;
+            predicates.push(expr.expr);
+        }
+        let plan = LinearJoinPlan::binary_equi_join(left_arity, right_arity, predicates)?;
+
+        Ok(TypedPlan {
+            schema,
+            plan: Plan::Join {
+                inputs: vec![left, right],
+                plan: JoinPlan::Linear(plan),
+            },
+        })
+    }
+
     pub async fn from_substrait_read(
         ctx: &mut FlownodeContext,
         read: &ReadRel,
@@ -225,6 +295,9 @@ impl TypedPlan {
             Some(RelType::Aggregate(agg)) => {
                 Self::from_substrait_agg_rel(ctx, agg, extensions).await
             }
+            Some(RelType::Join(join)) => {
+                Self::from_substrait_join(ctx, join.as_ref(), extensions).await
+            }
             _ => not_impl_err!("Unsupported relation type: {:?}", rel.rel_type),
         }
     }
@@ -273,4 +346,47 @@ mod test {
 
         assert_eq!(flow_plan.unwrap(), expected);
     }
+
+    #[tokio::test]
+    async fn test_join() {
+        let engine = create_test_query_engine();
+        let sql = "SELECT numbers.number, numbers_with_ts.ts FROM numbers JOIN numbers_with_ts ON numbers.number = numbers_with_ts.number";
+        let plan = sql_to_substrait(engine.clone(), sql).await;
+
+        let mut ctx = create_test_ctx();
+        let flow_plan = TypedPlan::from_substrait_plan(&mut ctx, &plan)
+            .await
+            .unwrap();
+
+        fn find_join(plan: &Plan) -> Option<&Plan> {
+            match plan {
+                Plan::Join { .. } => Some(plan),
+                _ => plan
+                    .get_first_input_plan()
+                    .and_then(|input| find_join(&input.plan)),
+            }
+        }
+        let Some(Plan::Join {
+            inputs,
+            plan: JoinPlan::Linear(plan),
+        }) = find_join(&flow_plan.plan)
+        else {
+            panic!("Expect a join in plan: {:?}", flow_plan);
+        };
+        assert_eq!(inputs.len(), 2);
+        assert_eq!(plan.stage_plans.len(), 1);
+        assert_eq!(plan.stage_plans[0].stream_key.len(), 1);
+        assert_eq!(plan.stage_plans[0].lookup_key.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_outer_join_not_supported() {
+        let engine = create_test_query_engine();
+        let sql = "SELECT numbers.number, numbers_with_ts.ts FROM numbers LEFT JOIN numbers_with_ts ON numbers.number = numbers_with_ts.number";
+        let plan = sql_to_substrait(engine.clone(), sql).await;
+
+        let mut ctx = create_test_ctx();
+        let res = TypedPlan::from_substrait_plan(&mut ctx, &plan).await;
+        assert!(res.is_err());
+    }
 }
@@ -623,6 +623,32 @@ impl Arrangement {
         }
         final_val
     }
+
+    /// Get current state of all keys starting with `prefix`.
+    ///
+    /// Useful for join operator, which arrange rows by join key followed by the row itself,
+    /// so all rows with the same join key are adjacent in the arrangement
+    pub fn get_by_prefix(&self, now: Timestamp, prefix: &Row) -> Vec<(Row, DiffRow)> {
+        let keys: BTreeSet<&Row> = self
+            .spine
+            .range(..=now)
+            .chain(
+                self.spine
+                    .range((Bound::Excluded(now), Bound::Unbounded))
+                    .next(),
+            )
+            .flat_map(|(_, batch)| {
+                batch
+                    .range(prefix.clone()..)
+                    .take_while(|(key, _)| key.inner.starts_with(&prefix.inner))
+                    .map(|(key, _)| key)
+            })
+            .collect();
+
+        keys.into_iter()
+            .filter_map(|key| self.get(now, key).map(|val| (key.clone(), val)))
+            .collect()
+    }
 }
 
 fn compact_diff_row(old_row: Option<DiffRow>, new_row: &DiffRow) -> Option<DiffRow> {
@@ -1038,4 +1064,49 @@ mod test {
             Some((lit("y"), 1, 1)) /* fast path */
         );
     }
+
+    #[test]
+    fn test_get_by_prefix() {
+        let mut arr = Arrangement::default();
+        arr.full_arrangement = true;
+        let row = |k: &str, v: i64| Row::new(vec![k.into(), v.into()]);
+        let updates = vec![
+            (
+                (row("a", 1), Row::empty()),
+                1, /* ts */
+                1, /* diff */
+            ),
+            (
+                (row("a", 2), Row::empty()),
+                1, /* ts */
+                2, /* diff */
+            ),
+            (
+                (row("b", 1), Row::empty()),
+                1, /* ts */
+                1, /* diff */
+            ),
+            (
+                (row("a", 1), Row::empty()),
+                2,  /* ts */
+                -1, /* diff */
+            ),
+        ];
+        arr.apply_updates(0, updates).unwrap();
+
+        assert_eq!(
+            arr.get_by_prefix(1, &lit("a")),
+            vec![
+                (row("a", 1), (Row::empty(), 1, 1)),
+                (row("a", 2), (Row::empty(), 1, 2)),
+            ]
+        );
+
+        arr.compact_to(2).unwrap();
+        assert_eq!(
+            arr.get_by_prefix(2, &lit("a")),
+            vec![(row("a", 2), (Row::empty(), 1, 2))]
+        );
+        assert!(arr.get_by_prefix(2, &lit("c")).is_empty());
+    }
 }
@@ -33,9 +33,10 @@ use crate::error;
 use crate::error::Result;
 use crate::heartbeat::HeartbeatTask;
 use crate::instance::Instance;
+use crate::promql_cache::PromqlCacheOptions;
 use crate::service_config::{
     InfluxdbOptions, JaegerOptions, MysqlOptions, OpentsdbOptions, OtlpOptions, PostgresOptions,
-    PromStoreOptions,
+    PromStoreOptions, TempoOptions,
 };
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -66,14 +67,22 @@ pub struct FrontendOptions {
     pub influxdb: InfluxdbOptions,
     pub prom_store: PromStoreOptions,
     pub jaeger: JaegerOptions,
+    pub tempo: TempoOptions,
     pub otlp: OtlpOptions,
     pub meta_client: Option<MetaClientOptions>,
     pub logging: LoggingOptions,
     pub datanode: DatanodeClientOptions,
     pub user_provider: Option<String>,
+    /// Users allowed to run `CREATE USER`, `ALTER USER`, `DROP USER`, `GRANT` and `REVOKE`.
+    pub admin_users: Vec<String>,
+    /// The directory the `geoip` pipeline processor reads databases from. The
+    /// processor is disabled if it's not set.
+    pub geoip_database_dir: Option<String>,
     pub tracing: TracingOptions,
     pub query: QueryOptions,
     pub slow_query: SlowQueryOptions,
+    /// The PromQL range query results cache options.
+    pub promql_cache: PromqlCacheOptions,
     pub memory: MemoryOptions,
     /// The event recorder options.
     pub event_recorder: EventRecorderOptions,
@@ -98,15 +107,19 @@ impl Default for FrontendOptions {
             opentsdb: OpentsdbOptions::default(),
             influxdb: InfluxdbOptions::default(),
             jaeger: JaegerOptions::default(),
+            tempo: TempoOptions::default(),
             prom_store: PromStoreOptions::default(),
             otlp: OtlpOptions::default(),
             meta_client: None,
             logging: LoggingOptions::default(),
             datanode: DatanodeClientOptions::default(),
             user_provider: None,
+            admin_users: vec![],
+            geoip_database_dir: None,
             tracing: TracingOptions::default(),
             query: QueryOptions::default(),
             slow_query: SlowQueryOptions::default(),
+            promql_cache: PromqlCacheOptions::default(),
             memory: MemoryOptions::default(),
             event_recorder: EventRecorderOptions::default(),
             heartbeat_env_vars: vec![],
@@ -25,6 +25,7 @@ mod otlp;
 pub mod prom_store;
 mod promql;
 mod region_query;
+mod tempo;
 
 use std::collections::HashSet;
 use std::pin::Pin;
@@ -54,17 +55,18 @@ use common_meta::key::table_name::TableNameKey;
 use common_meta::node_manager::NodeManagerRef;
 use common_meta::procedure_executor::ProcedureExecutorRef;
 use common_query::Output;
-use common_recordbatch::RecordBatchStreamWrapper;
 use common_recordbatch::error::StreamTimeoutSnafu;
+use common_recordbatch::{RecordBatch, RecordBatchStreamWrapper};
 use common_telemetry::logging::SlowQueryOptions;
 use common_telemetry::{debug, error, tracing};
 use dashmap::DashMap;
+use datafusion::dataframe::DataFrame;
 use datafusion::physical_plan::ExecutionPlan;
 use datafusion_expr::LogicalPlan;
 use futures::{Stream, StreamExt, future};
 use lazy_static::lazy_static;
 use operator::delete::DeleterRef;
-use operator::insert::InserterRef;
+use operator::insert::{InsertListenerRef, InserterRef};
 use operator::statement::{StatementExecutor, StatementExecutorRef};
 use partition::manager::PartitionRuleManagerRef;
 use pipeline::pipeline_operator::PipelineOperator;
@@ -97,9 +99,13 @@ use sql::statements::comment::CommentObject;
 use sql::statements::copy::{CopyDatabase, CopyTable};
 use sql::statements::statement::Statement;
 use sql::statements::tql::Tql;
+use sql::statements::user::{Grant, Revoke};
 use sql::util::{extract_tables_from_prom_expr_checked, extract_tables_from_statement_checked};
 use sqlparser::ast::{AnalyzeFormat, ObjectName};
-use table::requests::{OTLP_METRIC_COMPAT_KEY, OTLP_METRIC_COMPAT_PROM};
+use table::TableRef;
+use table::requests::{
+    InsertRequest as TableInsertRequest, OTLP_METRIC_COMPAT_KEY, OTLP_METRIC_COMPAT_PROM,
+};
 use tracing::Span;
 
 use crate::error::{
@@ -107,6 +113,7 @@ use crate::error::{
     InvalidSqlSnafu, ParseSqlSnafu, PermissionSnafu, PlanStatementSnafu, Result,
     SqlExecInterceptedSnafu, StatementTimeoutSnafu, TableOperationSnafu,
 };
+use crate::promql_cache::PromqlCacheRef;
 use crate::service_config::InfluxdbMergeMode;
 use crate::stream_wrapper::CancellableStreamWrapper;
 
@@ -132,6 +139,7 @@ pub struct Instance {
     slow_query_recorder: EventRecorderRef,
     process_manager: ProcessManagerRef,
     slow_query_options: SlowQueryOptions,
+    promql_cache: Option<PromqlCacheRef>,
     influxdb_default_merge_mode: InfluxdbMergeMode,
     trace_ingest_chunk_size: usize,
     suspend: Arc<AtomicBool>,
@@ -173,6 +181,14 @@ impl Instance {
         Ok(())
     }
 
+    /// Reloads the table grants of a user created by `CREATE USER`, so grant
+    /// changes reach sessions like MySQL connections that outlive a request.
+    async fn refresh_user_grants(&self, ctx: &QueryContextRef) -> server_error::Result<()> {
+        auth::refresh_user_grants(&ctx.current_user())
+            .await
+            .context(AuthSnafu)
+    }
+
     pub fn statement_executor(&self) -> &StatementExecutorRef {
         &self.statement_executor
     }
@@ -206,6 +222,10 @@ impl Instance {
         self.inserter.table_flownode_set_cache()
     }
 
+    pub fn insert_listener(&self) -> Option<&InsertListenerRef> {
+        self.inserter.insert_listener()
+    }
+
     pub fn cache_invalidator(&self) -> &CacheInvalidatorRef {
         self.statement_executor.cache_invalidator()
     }
@@ -365,7 +385,10 @@ impl Instance {
         query_interceptor: Option<&SqlQueryInterceptorRef<Error>>,
     ) -> Result<Output> {
         match stmt {
-            Statement::Query(_) | Statement::Explain(_) | Statement::Delete(_) => {
+            Statement::Query(_)
+            | Statement::Explain(_)
+            | Statement::Delete(_)
+            | Statement::Update(_) => {
                 // TODO: remove this when format is supported in datafusion
                 if let Statement::Explain(explain) = &stmt
                     && let Some(format) = explain.format()
@@ -802,6 +825,42 @@ impl Instance {
         }
     }
 
+    /// Executes a dataframe built by a protocol query handler like the plan of a SQL query,
+    /// so the query shows in the process list, can be cancelled, is recorded as a slow query
+    /// and is bounded by the statement timeout.
+    pub(crate) async fn exec_dataframe(
+        &self,
+        dataframe: DataFrame,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
+        let plan = dataframe.into_parts().1;
+        self.do_exec_plan_inner(plan, None, query_ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(server_error::ExecutePlanSnafu)?
+            .map_dictionary_to_values()
+            .context(server_error::CollectRecordbatchSnafu)
+    }
+
+    /// Executes the dataframe by [Instance::exec_dataframe] and collects the results.
+    pub(crate) async fn collect_dataframe(
+        &self,
+        dataframe: DataFrame,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Vec<RecordBatch>> {
+        let output = self.exec_dataframe(dataframe, query_ctx).await?;
+        match output.data {
+            OutputData::Stream(stream) => common_recordbatch::util::collect(stream)
+                .await
+                .context(server_error::CollectRecordbatchSnafu),
+            OutputData::RecordBatches(batches) => Ok(batches.take()),
+            OutputData::AffectedRows(_) => UnexpectedResultSnafu {
+                reason: "expected record batches of a query",
+            }
+            .fail(),
+        }
+    }
+
     async fn exec_plan(&self, plan: LogicalPlan, query_ctx: QueryContextRef) -> Result<Output> {
         self.query_engine
             .execute(plan, query_ctx)
@@ -940,7 +999,10 @@ impl Instance {
         let is_inner_plannable = |s: &Statement| {
             matches!(
                 s,
-                Statement::Insert(_) | Statement::Query(_) | Statement::Delete(_)
+                Statement::Insert(_)
+                    | Statement::Query(_)
+                    | Statement::Delete(_)
+                    | Statement::Update(_)
             )
         };
         let plannable = is_inner_plannable(&stmt)
@@ -980,6 +1042,9 @@ impl SqlQueryHandler for Instance {
         query: &str,
         query_ctx: QueryContextRef,
     ) -> Vec<server_error::Result<Output>> {
+        if let Err(e) = self.refresh_user_grants(&query_ctx).await {
+            return vec![Err(e)];
+        }
         self.do_query_inner(query, query_ctx)
             .await
             .into_iter()
@@ -992,6 +1057,7 @@ impl SqlQueryHandler for Instance {
         query: &str,
         query_ctx: QueryContextRef,
     ) -> server_error::Result<Output> {
+        self.refresh_user_grants(&query_ctx).await?;
         self.do_analyze_stream_query_inner(query, query_ctx)
             .await
             .map_err(BoxedError::new)
@@ -1004,6 +1070,7 @@ impl SqlQueryHandler for Instance {
         stmt: Option<Statement>,
         query_ctx: QueryContextRef,
     ) -> server_error::Result<Output> {
+        self.refresh_user_grants(&query_ctx).await?;
         self.do_exec_plan_inner(plan, stmt, query_ctx)
             .await
             .map_err(BoxedError::new)
@@ -1015,6 +1082,9 @@ impl SqlQueryHandler for Instance {
         query: &PromQuery,
         query_ctx: QueryContextRef,
     ) -> Vec<server_error::Result<Output>> {
+        if let Err(e) = self.refresh_user_grants(&query_ctx).await {
+            return vec![Err(e)];
+        }
         self.do_promql_query_inner(query, query_ctx)
             .await
             .into_iter()
@@ -1027,6 +1097,7 @@ impl SqlQueryHandler for Instance {
         stmt: Statement,
         query_ctx: QueryContextRef,
     ) -> server_error::Result<Option<DescribeResult>> {
+        self.refresh_user_grants(&query_ctx).await?;
         self.do_describe_inner(stmt, query_ctx)
             .await
             .map_err(BoxedError::new)
@@ -1039,6 +1110,52 @@ impl SqlQueryHandler for Instance {
             .map_err(BoxedError::new)
             .context(server_error::CheckDatabaseValiditySnafu)
     }
+
+    async fn do_table_insert(
+        &self,
+        request: TableInsertRequest,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
+        self.refresh_user_grants(&query_ctx).await?;
+        self.check_permission(
+            &query_ctx,
+            PermissionReq::BulkInsert {
+                catalog: &request.catalog_name,
+                schema: &request.schema_name,
+                table: &request.table_name,
+            },
+        )?;
+
+        self.inserter
+            .handle_table_insert(request, query_ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(ExecuteQuerySnafu)
+    }
+
+    async fn insert_target_table(
+        &self,
+        catalog: &str,
+        schema: &str,
+        table: &str,
+        query_ctx: QueryContextRef,
+    ) -> server_error::Result<Option<TableRef>> {
+        self.refresh_user_grants(&query_ctx).await?;
+        self.check_permission(
+            &query_ctx,
+            PermissionReq::BulkInsert {
+                catalog,
+                schema,
+                table,
+            },
+        )?;
+
+        self.catalog_manager
+            .table(catalog, schema, table, Some(&query_ctx))
+            .await
+            .map_err(BoxedError::new)
+            .context(ExecuteQuerySnafu)
+    }
 }
 
 /// Expands scan-time dictionaries only when a query result leaves the frontend.
@@ -1223,9 +1340,14 @@ impl PrometheusHandler for Instance {
 
         let (query, stmt) = query.into_parts();
 
-        let QueryStatement::Promql(eval_stmt, _) = &stmt else {
+        let QueryStatement::Promql(eval_stmt, alias) = &stmt else {
             unreachable!("query is parsed from promql");
         };
+        let cacheable_query = self.promql_cache.as_ref().and_then(|cache| {
+            cache
+                .cacheable_query(eval_stmt, alias.clone(), &query_ctx)
+                .map(|query| (cache.clone(), query))
+        });
 
         let plan = self
             .statement_executor
@@ -1268,12 +1390,23 @@ impl PrometheusHandler for Instance {
             slow_query_timer,
         );
 
-        let query_fut = self.statement_executor.exec_plan(plan, query_ctx.clone());
+        let query_fut = async {
+            match cacheable_query {
+                Some((cache, query)) => cache
+                    .execute(query, plan, &self.statement_executor, query_ctx.clone())
+                    .await
+                    .map_err(BoxedError::new),
+                None => self
+                    .statement_executor
+                    .exec_plan(plan, query_ctx.clone())
+                    .await
+                    .map_err(BoxedError::new),
+            }
+        };
 
         let output = CancellableFuture::new(query_fut, ticket.cancellation_handle.clone())
             .await
             .map_err(|_| servers::error::CancelledSnafu.build())?
-            .map_err(BoxedError::new)
             .context(ExecuteQuerySnafu)?;
         let output = map_query_output(output)
             .map_err(BoxedError::new)
@@ -1457,6 +1590,7 @@ pub fn check_permission(
         | Statement::Explain(_)
         | Statement::Tql(_)
         | Statement::Delete(_)
+        | Statement::Update(_)
         | Statement::DeclareCursor(_)
         | Statement::Copy(sql::statements::copy::Copy::CopyQueryTo(_)) => {}
         // database ops won't be checked
@@ -1594,6 +1728,15 @@ pub fn check_permission(
         Statement::Kill(_) => {}
         // SHOW PROCESSLIST
         Statement::ShowProcesslist(_) => {}
+        // Users are global, the permission checker decides who can manage them.
+        Statement::CreateUser(_) | Statement::AlterUser(_) | Statement::DropUser(_) => {}
+        Statement::Grant(Grant { target, .. }) | Statement::Revoke(Revoke { target, .. }) => {
+            if let Some(database) = &target.database {
+                validate_catalog_and_schema(query_ctx.current_catalog(), database, query_ctx)
+                    .map_err(BoxedError::new)
+                    .context(SqlExecInterceptedSnafu)?;
+            }
+        }
     }
     Ok(())
 }
@@ -1682,7 +1825,8 @@ mod tests {
     use api::v1::query_request::Query;
     use auth::{
         DASHBOARD_DELETE, DASHBOARD_QUERY, DASHBOARD_SAVE, JAEGER_QUERY, PIPELINE_DELETE,
-        PIPELINE_INSERT, PIPELINE_QUERY, PermissionAction, PermissionResp, UserInfo, UserInfoRef,
+        PIPELINE_INSERT, PIPELINE_QUERY, PermissionAction, PermissionResp, TEMPO_QUERY, UserInfo,
+        UserInfoRef,
     };
     use catalog::process_manager::{ProcessManager, QueryStatement, SlowQueryTimer};
     use common_base::Plugins;
@@ -1719,7 +1863,7 @@ mod tests {
     use query::query_engine::options::QueryOptions;
     use servers::query_handler::{
         DashboardHandler, JaegerQueryHandler, LogQueryHandler, PipelineHandler, PipelineHandlerRef,
-        PromStoreProtocolHandler,
+        PromStoreProtocolHandler, TempoQueryHandler,
     };
     use session::context::{Channel, ConnInfo, QueryContext, QueryContextBuilder};
     use snafu::{Location, Snafu};
@@ -2978,6 +3122,10 @@ mod tests {
             servers::http::jaeger::JAEGER_QUERY_TABLE_NAME_KEY,
             "denied".to_string(),
         );
+        Arc::get_mut(&mut ctx).unwrap().set_extension(
+            servers::http::tempo::TEMPO_QUERY_TABLE_NAME_KEY,
+            "denied".to_string(),
+        );
         let jaeger_targets = Some(PermissionTableTargets::resolved(vec![
             PermissionTableTarget::new("greptime", "public", "denied"),
         ]));
@@ -3003,7 +3151,19 @@ mod tests {
             )
             .await,
         );
-        assert_action_checked(&checker, JAEGER_QUERY, jaeger_targets);
+        assert_action_checked(&checker, JAEGER_QUERY, jaeger_targets.clone());
+
+        assert_permission_denied(
+            TempoQueryHandler::get_trace(&instance, ctx.clone(), "trace", None, None).await,
+        );
+        assert_action_checked(&checker, TEMPO_QUERY, jaeger_targets.clone());
+        assert_permission_denied(
+            TempoQueryHandler::find_trace_ids(&instance, ctx.clone(), None, None, None, 0, 20)
+                .await,
+        );
+        assert_action_checked(&checker, TEMPO_QUERY, jaeger_targets.clone());
+        assert_permission_denied(TempoQueryHandler::tag_names(&instance, ctx.clone(), None).await);
+        assert_action_checked(&checker, TEMPO_QUERY, jaeger_targets);
 
         assert_permission_denied(
             PipelineHandler::get_pipeline_str(&instance, "pipeline", None, ctx.clone()).await,
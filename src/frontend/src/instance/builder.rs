@@ -15,7 +15,7 @@
 use std::sync::Arc;
 use std::sync::atomic::AtomicBool;
 
-use auth::PermissionCheckerRef;
+use auth::{PermissionCheckerRef, UserProviderRef};
 use cache::{PARTITION_INFO_CACHE_NAME, TABLE_FLOWNODE_SET_CACHE_NAME, TABLE_ROUTE_CACHE_NAME};
 use catalog::CatalogManagerRef;
 use catalog::kvbackend::KvBackendCatalogManager;
@@ -58,6 +58,7 @@ use crate::heartbeat::frontend_peer_addr;
 use crate::instance::Instance;
 use crate::instance::entity_graph::EntityGraphProviderImpl;
 use crate::instance::region_query::FrontendRegionQueryHandler;
+use crate::promql_cache::PromqlCache;
 
 /// The frontend [`Instance`] builder.
 pub struct FrontendBuilder {
@@ -182,6 +183,7 @@ impl FrontendBuilder {
     }
 
     pub async fn try_build(self) -> Result<Instance> {
+        pipeline::set_geoip_database_dir(self.options.geoip_database_dir.as_deref());
         let kv_backend = self.kv_backend;
         let node_manager = self.node_manager;
         let plugins = self.plugins.unwrap_or_default();
@@ -222,18 +224,31 @@ impl FrontendBuilder {
                     name: TABLE_FLOWNODE_SET_CACHE_NAME,
                 })?;
 
-        let inserter = Arc::new(Inserter::new(
+        let promql_cache = self
+            .options
+            .promql_cache
+            .enable
+            .then(|| Arc::new(PromqlCache::new(&self.options.promql_cache)));
+        let mut inserter = Inserter::new(
             self.catalog_manager.clone(),
             partition_manager.clone(),
             node_manager.clone(),
             table_flownode_cache,
             self.options.auto_create_table,
-        ));
-        let deleter = Arc::new(Deleter::new(
+        );
+        if let Some(cache) = &promql_cache {
+            inserter = inserter.with_insert_listener(cache.clone());
+        }
+        let inserter = Arc::new(inserter);
+        let mut deleter = Deleter::new(
             self.catalog_manager.clone(),
             partition_manager.clone(),
             node_manager.clone(),
-        ));
+        );
+        if let Some(cache) = &promql_cache {
+            deleter = deleter.with_table_change_listener(cache.clone());
+        }
+        let deleter = Arc::new(deleter);
         let requester = Arc::new(Requester::new(
             self.catalog_manager.clone(),
             partition_manager.clone(),
@@ -302,6 +317,16 @@ impl FrontendBuilder {
             frontend_peer_addr.clone(),
             self.local_file_access,
         );
+        let statement_executor = if let Some(user_provider) = plugins.get::<UserProviderRef>() {
+            statement_executor.with_user_provider(user_provider)
+        } else {
+            statement_executor
+        };
+        let statement_executor = if let Some(cache) = &promql_cache {
+            statement_executor.with_table_change_listener(cache.clone())
+        } else {
+            statement_executor
+        };
 
         let statement_executor =
             if let Some(configurator) = plugins.get::<StatementExecutorConfiguratorRef>() {
@@ -365,6 +390,7 @@ impl FrontendBuilder {
             process_manager,
             otlp_metrics_table_legacy_cache: DashMap::new(),
             slow_query_options: self.options.slow_query.clone(),
+            promql_cache,
             influxdb_default_merge_mode: self.options.influxdb.default_merge_mode,
             trace_ingest_chunk_size: self.options.otlp.trace_ingest_chunk_size,
             suspend: Arc::new(AtomicBool::new(false)),
@@ -17,22 +17,28 @@ use std::sync::Arc;
 use api::v1::value::ValueData;
 use api::v1::{ColumnDataType, RowInsertRequests, SemanticType};
 use async_trait::async_trait;
-use auth::{INFLUXDB_WRITE, PermissionChecker, PermissionCheckerRef, PermissionReq};
+use auth::{
+    INFLUXDB_QUERY, INFLUXDB_WRITE, PermissionChecker, PermissionCheckerRef, PermissionReq,
+    PermissionTableTarget, PermissionTableTargets,
+};
 use catalog::CatalogManagerRef;
 use client::Output;
 use common_error::ext::BoxedError;
 use common_time::Timestamp;
 use common_time::timestamp::TimeUnit;
-use servers::error::{AuthSnafu, Error, TimestampOverflowSnafu, UnexpectedResultSnafu};
+use query::influxql::ast::Statement;
+use servers::error::{
+    AuthSnafu, Error, ExecuteQuerySnafu, TimestampOverflowSnafu, UnexpectedResultSnafu,
+};
 use servers::influxdb::InfluxdbRequest;
 use servers::interceptor::{LineProtocolInterceptor, LineProtocolInterceptorRef};
-use servers::query_handler::InfluxdbLineProtocolHandler;
+use servers::query_handler::{InfluxdbLineProtocolHandler, InfluxqlOutput, InfluxqlQueryHandler};
 use session::context::QueryContextRef;
 use snafu::{OptionExt, ResultExt};
 use store_api::mito_engine_options::MERGE_MODE_KEY;
 use table::requests::{SEMANTIC_SIGNAL_TYPE, SEMANTIC_SOURCE, SIGNAL_TYPE_METRIC, SOURCE_INFLUXDB};
 
-use crate::instance::Instance;
+use crate::instance::{Instance, map_query_output};
 use crate::service_config::influxdb::InfluxdbMergeMode;
 
 fn ctx_with_default_merge_mode(
@@ -98,6 +104,58 @@ impl InfluxdbLineProtocolHandler for Instance {
     }
 }
 
+#[async_trait]
+impl InfluxqlQueryHandler for Instance {
+    async fn query(
+        &self,
+        stmt: Statement,
+        ctx: QueryContextRef,
+    ) -> servers::error::Result<InfluxqlOutput> {
+        // `SHOW MEASUREMENTS` only reads the metadata of the schema. The planner reads
+        // from the same target database.
+        let targets = match stmt.measurement() {
+            Some(measurement) => {
+                let schema = stmt
+                    .target_database()
+                    .map(ToString::to_string)
+                    .unwrap_or_else(|| ctx.current_schema());
+                PermissionTableTargets::resolved(vec![PermissionTableTarget::new(
+                    ctx.current_catalog(),
+                    &schema,
+                    &measurement.name,
+                )])
+            }
+            None => PermissionTableTargets::resolved(vec![]),
+        };
+        let targets = self.resolve_query_permission_targets(targets, &ctx).await?;
+        self.check_table_permission(&ctx, PermissionReq::Action(INFLUXDB_QUERY), targets)
+            .context(AuthSnafu)?;
+
+        let plan = self
+            .query_engine
+            .planner()
+            .plan_influxql(stmt, ctx.clone())
+            .await
+            .map_err(BoxedError::new)
+            .context(ExecuteQuerySnafu)?;
+
+        let output = self
+            .statement_executor
+            .exec_plan(plan.plan, ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(ExecuteQuerySnafu)?;
+        let output = map_query_output(output)
+            .map_err(BoxedError::new)
+            .context(ExecuteQuerySnafu)?;
+
+        Ok(InfluxqlOutput {
+            layout: plan.layout,
+            output,
+        })
+    }
+}
+
 /// Align the timestamp precisions in Influxdb lines (after they are converted to the GRPC row
 /// inserts) to the time index columns' time units of the created tables (if there are any).
 struct InfluxdbLineTimestampAligner<'a> {
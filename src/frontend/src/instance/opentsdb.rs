@@ -12,21 +12,39 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::BTreeSet;
 use std::sync::Arc;
 
 use async_trait::async_trait;
 use auth::{
-    OPENTSDB_WRITE, PermissionChecker, PermissionCheckerRef, PermissionReq, PermissionTableTarget,
-    PermissionTableTargets,
+    OPENTSDB_QUERY, OPENTSDB_WRITE, PermissionChecker, PermissionCheckerRef, PermissionReq,
+    PermissionTableTarget, PermissionTableTargets,
 };
 use common_error::ext::BoxedError;
+use common_query::Output;
+use common_query::prelude::{greptime_timestamp, greptime_value};
 use common_telemetry::tracing;
-use servers::error::{self as server_error, AuthSnafu, ExecuteGrpcQuerySnafu};
+use datafusion::arrow::array::AsArray;
+use datafusion::arrow::compute::cast as cast_array;
+use datafusion::arrow::datatypes::{DataType, TimeUnit as ArrowTimeUnit};
+use datafusion::common::ScalarValue;
+use datafusion::dataframe::DataFrame;
+use datafusion::functions_aggregate::expr_fn::{
+    avg, count, first_value, last_value, max, min, stddev_pop, sum,
+};
+use datafusion_expr::{Expr, Operator, binary_expr, cast, ident, lit};
+use futures::TryStreamExt;
+use servers::error::{
+    self as server_error, ArrowSnafu, AuthSnafu, DataFusionSnafu, ExecuteGrpcQuerySnafu,
+    ExecuteQuerySnafu, InvalidQuerySnafu, TableNotFoundSnafu,
+};
 use servers::opentsdb::codec::DataPoint;
 use servers::opentsdb::data_point_to_grpc_row_insert_requests;
-use servers::query_handler::OpentsdbProtocolHandler;
+use servers::opentsdb::query::{Aggregator, MetricQuery, SuggestKind, TagFilter, TagFilterKind};
+use servers::query_handler::{OpentsdbProtocolHandler, OpentsdbQueryHandler};
 use session::context::QueryContextRef;
 use snafu::prelude::*;
+use table::TableRef;
 use table::requests::{SEMANTIC_SIGNAL_TYPE, SEMANTIC_SOURCE, SIGNAL_TYPE_METRIC, SOURCE_OPENTSDB};
 
 use crate::instance::Instance;
@@ -95,6 +113,411 @@ impl OpentsdbProtocolHandler for Instance {
     }
 }
 
+impl Instance {
+    async fn check_opentsdb_query_permission(
+        &self,
+        tables: &[&str],
+        ctx: &QueryContextRef,
+    ) -> server_error::Result<()> {
+        let catalog = ctx.current_catalog();
+        let schema = ctx.current_schema();
+        let targets = PermissionTableTargets::resolved(
+            tables
+                .iter()
+                .map(|table| PermissionTableTarget::new(catalog, &schema, table))
+                .collect(),
+        );
+        let targets = self.resolve_query_permission_targets(targets, ctx).await?;
+        self.check_table_permission(ctx, PermissionReq::Action(OPENTSDB_QUERY), targets)
+            .context(AuthSnafu)?;
+        Ok(())
+    }
+
+    async fn opentsdb_metric_table(
+        &self,
+        metric: &str,
+        ctx: &QueryContextRef,
+    ) -> server_error::Result<TableRef> {
+        self.check_opentsdb_query_permission(&[metric], ctx).await?;
+        self.catalog_manager()
+            .table(
+                ctx.current_catalog(),
+                &ctx.current_schema(),
+                metric,
+                Some(ctx),
+            )
+            .await?
+            .with_context(|| TableNotFoundSnafu {
+                catalog: ctx.current_catalog(),
+                schema: ctx.current_schema(),
+                table: metric,
+            })
+    }
+
+    fn opentsdb_dataframe(&self, table: TableRef) -> server_error::Result<DataFrame> {
+        self.query_engine
+            .read_table(table)
+            .map_err(BoxedError::new)
+            .context(ExecuteQuerySnafu)
+    }
+}
+
+#[async_trait]
+impl OpentsdbQueryHandler for Instance {
+    async fn query(
+        &self,
+        query: &MetricQuery,
+        ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
+        let table = self.opentsdb_metric_table(&query.metric, &ctx).await?;
+        let schema = table.schema();
+        let time_index = schema
+            .timestamp_column()
+            .with_context(|| InvalidQuerySnafu {
+                reason: format!("metric {} has no time index", query.metric),
+            })?
+            .name
+            .clone();
+        let value_column = value_column(&table).with_context(|| InvalidQuerySnafu {
+            reason: format!("metric {} has no numeric field", query.metric),
+        })?;
+        let tag_columns = tag_columns(&table);
+        let mut filters = tag_filters(&query.filters, &tag_columns)?;
+        filters.push(
+            ident(&time_index).gt_eq(lit(ScalarValue::TimestampMillisecond(
+                Some(query.start_millis),
+                None,
+            ))),
+        );
+        filters.push(
+            ident(&time_index).lt_eq(lit(ScalarValue::TimestampMillisecond(
+                Some(query.end_millis),
+                None,
+            ))),
+        );
+
+        let dataframe = filters
+            .into_iter()
+            .try_fold(self.opentsdb_dataframe(table)?, |df, expr| {
+                df.filter(expr).context(DataFusionSnafu)
+            })?;
+
+        // The timestamps are returned as milliseconds, and the values as `f64`.
+        let ts_millis = cast(
+            cast(
+                ident(&time_index),
+                DataType::Timestamp(ArrowTimeUnit::Millisecond, None),
+            ),
+            DataType::Int64,
+        );
+        let value = cast(ident(&value_column), DataType::Float64);
+        let tags = tag_columns.iter().map(ident).collect::<Vec<_>>();
+
+        // It's equivalent to the following SQL query:
+        //
+        // ```
+        // SELECT
+        //   {tags},
+        //   {ts_millis} - {ts_millis} % {interval} AS greptime_timestamp,
+        //   {downsample_aggregator}({value}) AS greptime_value
+        // FROM {metric}
+        // WHERE {filters}
+        // GROUP BY {tags}, greptime_timestamp
+        // ```
+        //
+        // or selecting the raw points without downsampling.
+        let dataframe = match &query.downsample {
+            Some(downsample) => {
+                let bucket = binary_expr(
+                    ts_millis.clone(),
+                    Operator::Minus,
+                    binary_expr(ts_millis, Operator::Modulo, lit(downsample.interval_millis)),
+                );
+                let order_by = || vec![ident(&time_index).sort(true, false)];
+                let aggregate = match downsample.aggregator {
+                    Aggregator::Sum => sum(value),
+                    Aggregator::Avg => avg(value),
+                    Aggregator::Min => min(value),
+                    Aggregator::Max => max(value),
+                    Aggregator::Count => cast(count(value), DataType::Float64),
+                    Aggregator::Dev => stddev_pop(value),
+                    Aggregator::First => first_value(value, order_by()),
+                    Aggregator::Last => last_value(value, order_by()),
+                    Aggregator::None => {
+                        return InvalidQuerySnafu {
+                            reason: "aggregator none is not allowed in downsample",
+                        }
+                        .fail();
+                    }
+                };
+                let group_by = tags
+                    .into_iter()
+                    .chain([bucket.alias(greptime_timestamp())])
+                    .collect();
+                dataframe
+                    .aggregate(group_by, vec![aggregate.alias(greptime_value())])
+                    .context(DataFusionSnafu)?
+            }
+            None => {
+                let selects = tags
+                    .into_iter()
+                    .chain([
+                        ts_millis.alias(greptime_timestamp()),
+                        value.alias(greptime_value()),
+                    ])
+                    .collect::<Vec<_>>();
+                dataframe.select(selects).context(DataFusionSnafu)?
+            }
+        };
+
+        self.exec_dataframe(dataframe, ctx).await
+    }
+
+    async fn suggest(
+        &self,
+        kind: SuggestKind,
+        prefix: &str,
+        max: usize,
+        ctx: QueryContextRef,
+    ) -> server_error::Result<Vec<String>> {
+        let catalog = ctx.current_catalog();
+        let schema = ctx.current_schema();
+        let mut suggestions = BTreeSet::new();
+        match kind {
+            SuggestKind::Metrics => {
+                let mut table_names = self
+                    .catalog_manager()
+                    .table_names(catalog, &schema, Some(&ctx))
+                    .await?;
+                table_names.retain(|name| name.starts_with(prefix));
+                table_names.sort_unstable();
+                // Only suggests the metrics the user can read.
+                for table_name in table_names {
+                    if suggestions.len() >= max {
+                        break;
+                    }
+                    if self
+                        .check_opentsdb_query_permission(&[table_name.as_str()], &ctx)
+                        .await
+                        .is_ok()
+                    {
+                        suggestions.insert(table_name);
+                    }
+                }
+            }
+            SuggestKind::TagKeys => {
+                let tables = self
+                    .catalog_manager()
+                    .tables(catalog, &schema, Some(&ctx))
+                    .try_collect::<Vec<_>>()
+                    .await?;
+                for table in tables {
+                    let tag_columns = tag_columns(&table)
+                        .into_iter()
+                        .filter(|tagk| tagk.starts_with(prefix) && !suggestions.contains(tagk))
+                        .collect::<Vec<_>>();
+                    // Only suggests the tag keys of the tables the user can read.
+                    if tag_columns.is_empty()
+                        || self
+                            .check_opentsdb_query_permission(
+                                &[table.table_info().name.as_str()],
+                                &ctx,
+                            )
+                            .await
+                            .is_err()
+                    {
+                        continue;
+                    }
+                    suggestions.extend(tag_columns);
+                }
+            }
+            SuggestKind::TagValues => {
+                let tables = self
+                    .catalog_manager()
+                    .tables(catalog, &schema, Some(&ctx))
+                    .try_collect::<Vec<_>>()
+                    .await?;
+                // Tag values are read from the data, so stops scanning once `max`
+                // values are found instead of returning the first `max` values in
+                // order.
+                'tables: for table in tables {
+                    let tag_columns = tag_columns(&table);
+                    let table_name = table.table_info().name.clone();
+                    // Skips tables the user can't read.
+                    if tag_columns.is_empty()
+                        || self
+                            .check_opentsdb_query_permission(&[table_name.as_str()], &ctx)
+                            .await
+                            .is_err()
+                    {
+                        continue;
+                    }
+                    for tagk in tag_columns {
+                        let remaining = max.saturating_sub(suggestions.len());
+                        if remaining == 0 {
+                            break 'tables;
+                        }
+                        let tagv = cast(ident(&tagk), DataType::Utf8);
+                        // It's equivalent to
+                        // `SELECT DISTINCT {tagk} FROM {table} WHERE {tagk} LIKE '{prefix}%' LIMIT {remaining}`,
+                        // the limited distinct aggregation stops reading once `remaining` values are found.
+                        let dataframe = self
+                            .opentsdb_dataframe(table.clone())?
+                            .filter(tagv.clone().like(lit(format!("{}%", escape_like(prefix)))))
+                            .and_then(|df| df.select(vec![tagv.alias(&tagk)]))
+                            .and_then(|df| df.distinct())
+                            .and_then(|df| df.limit(0, Some(remaining)))
+                            .context(DataFusionSnafu)?;
+                        let batches = self.collect_dataframe(dataframe, ctx.clone()).await?;
+                        for batch in batches {
+                            let values =
+                                cast_array(batch.column(0), &DataType::Utf8).context(ArrowSnafu)?;
+                            suggestions.extend(
+                                values
+                                    .as_string::<i32>()
+                                    .iter()
+                                    .flatten()
+                                    .map(ToString::to_string),
+                            );
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(suggestions.into_iter().take(max).collect())
+    }
+
+    async fn lookup(
+        &self,
+        metric: &str,
+        filters: &[TagFilter],
+        limit: usize,
+        ctx: QueryContextRef,
+    ) -> server_error::Result<Output> {
+        let table = self.opentsdb_metric_table(metric, &ctx).await?;
+        let tag_columns = tag_columns(&table);
+        let filters = tag_filters(filters, &tag_columns)?;
+
+        // It's equivalent to the following SQL query:
+        //
+        // ```
+        // SELECT DISTINCT {tags}
+        // FROM {metric}
+        // WHERE {filters}
+        // ORDER BY {tags}
+        // LIMIT {limit}
+        // ```
+        let dataframe = filters
+            .into_iter()
+            .try_fold(self.opentsdb_dataframe(table)?, |df, expr| {
+                df.filter(expr).context(DataFusionSnafu)
+            })?;
+        let tags = tag_columns
+            .iter()
+            .map(|tag| cast(ident(tag), DataType::Utf8).alias(tag))
+            .collect::<Vec<_>>();
+        let sorts = tag_columns
+            .iter()
+            .map(|tag| ident(tag).sort(true, true))
+            .collect::<Vec<_>>();
+        let dataframe = dataframe
+            .select(tags)
+            .and_then(|df| df.distinct())
+            .and_then(|df| df.sort(sorts))
+            .and_then(|df| df.limit(0, Some(limit)))
+            .context(DataFusionSnafu)?;
+
+        self.exec_dataframe(dataframe, ctx).await
+    }
+}
+
+/// Tags of OpenTSDB metrics are the primary key columns of the table.
+fn tag_columns(table: &TableRef) -> Vec<String> {
+    table
+        .table_info()
+        .meta
+        .row_key_column_names()
+        .cloned()
+        .collect()
+}
+
+/// Returns the `greptime_value` column, or the first numeric field of tables not written by
+/// OpenTSDB.
+fn value_column(table: &TableRef) -> Option<String> {
+    let table_info = table.table_info();
+    let schema = table.schema();
+    let numeric_fields = table_info
+        .meta
+        .field_column_names()
+        .filter(|name| {
+            schema
+                .column_schema_by_name(name)
+                .is_some_and(|column| column.data_type.is_numeric())
+        })
+        .collect::<Vec<_>>();
+    numeric_fields
+        .iter()
+        .find(|name| name.as_str() == greptime_value())
+        .or(numeric_fields.first())
+        .map(|name| name.to_string())
+}
+
+fn tag_filters(filters: &[TagFilter], tag_columns: &[String]) -> server_error::Result<Vec<Expr>> {
+    filters
+        .iter()
+        .map(|filter| {
+            ensure!(
+                tag_columns.contains(&filter.tagk),
+                InvalidQuerySnafu {
+                    reason: format!("unknown tag key: {}", filter.tagk),
+                }
+            );
+            let tagv = cast(ident(&filter.tagk), DataType::Utf8);
+            let expr = match &filter.kind {
+                TagFilterKind::LiteralOr(values) => {
+                    tagv.in_list(values.iter().map(lit).collect(), false)
+                }
+                TagFilterKind::NotLiteralOr(values) => {
+                    tagv.in_list(values.iter().map(lit).collect(), true)
+                }
+                TagFilterKind::ILiteralOr(values) => values
+                    .iter()
+                    .map(|value| tagv.clone().ilike(lit(escape_like(value))))
+                    .reduce(Expr::or)
+                    .unwrap_or(lit(false)),
+                TagFilterKind::NotILiteralOr(values) => values
+                    .iter()
+                    .map(|value| tagv.clone().not_ilike(lit(escape_like(value))))
+                    .reduce(Expr::and)
+                    .unwrap_or(tagv.is_not_null()),
+                TagFilterKind::Wildcard(pattern) if pattern == "*" => tagv.is_not_null(),
+                TagFilterKind::Wildcard(pattern) => tagv.like(lit(wildcard_to_like(pattern))),
+                TagFilterKind::IWildcard(pattern) => tagv.ilike(lit(wildcard_to_like(pattern))),
+                TagFilterKind::Regexp(regex) => {
+                    binary_expr(tagv, Operator::RegexMatch, lit(regex.as_str()))
+                }
+            };
+            Ok(expr)
+        })
+        .collect()
+}
+
+fn escape_like(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
+
+/// Converts an OpenTSDB wildcard pattern, where `*` matches any characters, to a LIKE pattern.
+fn wildcard_to_like(pattern: &str) -> String {
+    pattern
+        .split('*')
+        .map(escape_like)
+        .collect::<Vec<_>>()
+        .join("%")
+}
+
 #[cfg(test)]
 mod tests {
     use session::context::QueryContext;
@@ -0,0 +1,404 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use std::collections::BTreeSet;
+
+use async_trait::async_trait;
+use auth::{PermissionReq, PermissionTableTarget, PermissionTableTargets, TEMPO_QUERY};
+use common_catalog::consts::TRACE_TABLE_NAME;
+use common_error::ext::BoxedError;
+use common_query::Output;
+use common_recordbatch::RecordBatch;
+use common_telemetry::tracing;
+use datafusion::arrow::array::AsArray;
+use datafusion::arrow::compute::cast as cast_array;
+use datafusion::arrow::datatypes::DataType;
+use datafusion::dataframe::DataFrame;
+use datafusion::functions_aggregate::expr_fn::max;
+use datafusion_expr::{Expr, Operator, binary_expr, cast, ident, lit, lit_timestamp_nano};
+use datatypes::prelude::ConcreteDataType;
+use datatypes::schema::SchemaRef;
+use servers::error::{
+    ArrowSnafu, AuthSnafu, DataFusionSnafu, ExecuteQuerySnafu, InvalidTempoQuerySnafu,
+    Result as ServerResult, TableNotFoundSnafu,
+};
+use servers::http::tempo::TEMPO_QUERY_TABLE_NAME_KEY;
+use servers::otlp::trace::{
+    DURATION_NANO_COLUMN, KEY_SERVICE_NAME, SERVICE_NAME_COLUMN, SPAN_KIND_COLUMN,
+    SPAN_NAME_COLUMN, SPAN_STATUS_CODE, SPAN_STATUS_MESSAGE_COLUMN, TIMESTAMP_COLUMN,
+    TRACE_ID_COLUMN,
+};
+use servers::query_handler::TempoQueryHandler;
+use servers::tempo::traceql::{AttributeScope, CompareOp, Field, FieldExpr, Static};
+use servers::tempo::{
+    RESOURCE_ATTRIBUTE_COLUMN_PREFIX, SPAN_ATTRIBUTE_COLUMN_PREFIX, SPAN_SET_COLUMNS,
+};
+use session::context::QueryContextRef;
+use snafu::{OptionExt, ResultExt, ensure};
+use table::TableRef;
+
+use crate::instance::Instance;
+
+const KEY_LATEST_TIMESTAMP: &str = "greptime_latest_timestamp";
+const KEY_TAG_VALUE: &str = "greptime_tag_value";
+
+impl Instance {
+    /// Gets the v1 trace table to query, checking the permission to read it.
+    async fn tempo_trace_table(&self, ctx: &QueryContextRef) -> ServerResult<TableRef> {
+        let table_name = ctx
+            .extension(TEMPO_QUERY_TABLE_NAME_KEY)
+            .unwrap_or(TRACE_TABLE_NAME);
+        let targets = PermissionTableTargets::resolved(vec![PermissionTableTarget::new(
+            ctx.current_catalog(),
+            ctx.current_schema(),
+            table_name,
+        )]);
+        let targets = self.resolve_query_permission_targets(targets, ctx).await?;
+        self.check_table_permission(ctx, PermissionReq::Action(TEMPO_QUERY), targets)
+            .context(AuthSnafu)?;
+
+        let table = self
+            .catalog_manager()
+            .table(
+                ctx.current_catalog(),
+                &ctx.current_schema(),
+                table_name,
+                Some(ctx),
+            )
+            .await?
+            .with_context(|| TableNotFoundSnafu {
+                table: table_name,
+                catalog: ctx.current_catalog(),
+                schema: ctx.current_schema(),
+            })?;
+        ensure!(
+            table::requests::is_trace_v1_table(&table.table_info()),
+            InvalidTempoQuerySnafu {
+                reason: format!("table {table_name} is not a trace table of the v1 data model"),
+            }
+        );
+        Ok(table)
+    }
+
+    fn tempo_dataframe(&self, table: TableRef) -> ServerResult<DataFrame> {
+        self.query_engine
+            .read_table(table)
+            .map_err(BoxedError::new)
+            .context(ExecuteQuerySnafu)
+    }
+}
+
+#[async_trait]
+impl TempoQueryHandler for Instance {
+    #[tracing::instrument(skip_all)]
+    async fn find_trace_ids(
+        &self,
+        ctx: QueryContextRef,
+        filter: Option<&FieldExpr>,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+        offset: usize,
+        limit: usize,
+    ) -> ServerResult<Vec<String>> {
+        let table = self.tempo_trace_table(&ctx).await?;
+        let schema = table.schema();
+        let mut filters = filter
+            .map(|filter| filter_expr(filter, &schema))
+            .into_iter()
+            .chain(time_range_filters(start_time, end_time));
+
+        // It's equivalent to the following SQL query:
+        //
+        // ```
+        // SELECT trace_id
+        // FROM {trace_table}
+        // WHERE {filter} AND timestamp >= {start_time} AND timestamp <= {end_time}
+        // GROUP BY trace_id
+        // ORDER BY max(timestamp) DESC, trace_id
+        // LIMIT {limit} OFFSET {offset}
+        // ```
+        let dataframe = filters
+            .try_fold(self.tempo_dataframe(table)?, |df, expr| {
+                df.filter(expr).context(DataFusionSnafu)
+            })?
+            .aggregate(
+                vec![ident(TRACE_ID_COLUMN)],
+                vec![max(ident(TIMESTAMP_COLUMN)).alias(KEY_LATEST_TIMESTAMP)],
+            )
+            .and_then(|df| {
+                // Sorts by trace id as well, so pages of the same query don't overlap.
+                df.sort(vec![
+                    ident(KEY_LATEST_TIMESTAMP).sort(false, false),
+                    ident(TRACE_ID_COLUMN).sort(true, false),
+                ])
+            })
+            .and_then(|df| df.limit(offset, Some(limit)))
+            .and_then(|df| df.select(vec![ident(TRACE_ID_COLUMN)]))
+            .context(DataFusionSnafu)?;
+
+        collect_strings(self.collect_dataframe(dataframe, ctx).await?)
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn find_spans(
+        &self,
+        ctx: QueryContextRef,
+        trace_ids: &[String],
+        filter: Option<&FieldExpr>,
+    ) -> ServerResult<Output> {
+        let table = self.tempo_trace_table(&ctx).await?;
+        let schema = table.schema();
+        let trace_ids = trace_ids.iter().map(|id| lit(id.as_str())).collect();
+        let mut filters = std::iter::once(ident(TRACE_ID_COLUMN).in_list(trace_ids, false))
+            .chain(filter.map(|filter| filter_expr(filter, &schema)));
+
+        let dataframe = filters
+            .try_fold(self.tempo_dataframe(table)?, |df, expr| {
+                df.filter(expr).context(DataFusionSnafu)
+            })?
+            .select(
+                SPAN_SET_COLUMNS
+                    .iter()
+                    .map(|c| ident(*c))
+                    .collect::<Vec<_>>(),
+            )
+            .context(DataFusionSnafu)?;
+
+        self.exec_dataframe(dataframe, ctx).await
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn get_trace(
+        &self,
+        ctx: QueryContextRef,
+        trace_id: &str,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+    ) -> ServerResult<Output> {
+        let table = self.tempo_trace_table(&ctx).await?;
+
+        // It's equivalent to
+        // `SELECT * FROM {trace_table} WHERE trace_id = '{trace_id}' ORDER BY timestamp`.
+        let dataframe = std::iter::once(ident(TRACE_ID_COLUMN).eq(lit(trace_id)))
+            .chain(time_range_filters(start_time, end_time))
+            .try_fold(self.tempo_dataframe(table)?, |df, expr| {
+                df.filter(expr).context(DataFusionSnafu)
+            })?
+            .sort(vec![ident(TIMESTAMP_COLUMN).sort(true, false)])
+            .context(DataFusionSnafu)?;
+
+        self.exec_dataframe(dataframe, ctx).await
+    }
+
+    async fn tag_names(
+        &self,
+        ctx: QueryContextRef,
+        scope: Option<AttributeScope>,
+    ) -> ServerResult<Vec<String>> {
+        let table = self.tempo_trace_table(&ctx).await?;
+        let schema = table.schema();
+
+        let with_span = scope != Some(AttributeScope::Resource);
+        let with_resource = scope != Some(AttributeScope::Span);
+        let mut names = BTreeSet::new();
+        for column in schema.column_schemas() {
+            if with_span && let Some(name) = column.name.strip_prefix(SPAN_ATTRIBUTE_COLUMN_PREFIX)
+            {
+                names.insert(name.to_string());
+            }
+            if with_resource
+                && let Some(name) = column.name.strip_prefix(RESOURCE_ATTRIBUTE_COLUMN_PREFIX)
+            {
+                names.insert(name.to_string());
+            }
+        }
+        // The `service.name` resource attribute is stored in the `service_name` column.
+        if with_resource {
+            names.insert(KEY_SERVICE_NAME.to_string());
+        }
+        Ok(names.into_iter().collect())
+    }
+
+    #[tracing::instrument(skip_all)]
+    async fn tag_values(
+        &self,
+        ctx: QueryContextRef,
+        field: &Field,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+        offset: usize,
+        limit: usize,
+    ) -> ServerResult<Vec<String>> {
+        let table = self.tempo_trace_table(&ctx).await?;
+        let schema = table.schema();
+        let columns = match field {
+            Field::Name => vec![SPAN_NAME_COLUMN.to_string()],
+            Field::StatusMessage => vec![SPAN_STATUS_MESSAGE_COLUMN.to_string()],
+            Field::Attribute { scope, name } => attribute_columns(*scope, name, &schema),
+            Field::Status | Field::Kind | Field::Duration => vec![],
+        };
+
+        let mut values = BTreeSet::new();
+        for column in columns {
+            // It's equivalent to
+            // `SELECT DISTINCT CAST({column} AS STRING) FROM {trace_table} WHERE {column} IS NOT NULL LIMIT {limit}`.
+            let value = cast(ident(&column), DataType::Utf8);
+            let dataframe = std::iter::once(ident(&column).is_not_null())
+                .chain(time_range_filters(start_time, end_time))
+                .try_fold(self.tempo_dataframe(table.clone())?, |df, expr| {
+                    df.filter(expr).context(DataFusionSnafu)
+                })?
+                .select(vec![value.alias(KEY_TAG_VALUE)])
+                .and_then(|df| df.distinct())
+                .and_then(|df| df.limit(0, Some(limit)))
+                .context(DataFusionSnafu)?;
+            values.extend(collect_strings(
+                self.collect_dataframe(dataframe, ctx.clone()).await?,
+            )?);
+        }
+
+        Ok(values.into_iter().take(limit).collect())
+    }
+}
+
+fn time_range_filters(start_time: Option<i64>, end_time: Option<i64>) -> Vec<Expr> {
+    let mut filters = vec![];
+    if let Some(start_time) = start_time {
+        filters.push(ident(TIMESTAMP_COLUMN).gt_eq(lit_timestamp_nano(start_time)));
+    }
+    if let Some(end_time) = end_time {
+        filters.push(ident(TIMESTAMP_COLUMN).lt_eq(lit_timestamp_nano(end_time)));
+    }
+    filters
+}
+
+/// Converts the TraceQL filter into a DataFusion expression on the v1 trace table.
+fn filter_expr(expr: &FieldExpr, schema: &SchemaRef) -> Expr {
+    match expr {
+        FieldExpr::And(left, right) => filter_expr(left, schema).and(filter_expr(right, schema)),
+        FieldExpr::Or(left, right) => filter_expr(left, schema).or(filter_expr(right, schema)),
+        FieldExpr::Compare { field, op, value } => match (field, value) {
+            (Field::Name, Static::String(name)) => {
+                compare(ident(SPAN_NAME_COLUMN), *op, string_literal(*op, name))
+            }
+            (Field::StatusMessage, Static::String(message)) => compare(
+                ident(SPAN_STATUS_MESSAGE_COLUMN),
+                *op,
+                string_literal(*op, message),
+            ),
+            (Field::Status, Static::Status(status)) => {
+                compare(ident(SPAN_STATUS_CODE), *op, lit(status.as_column_value()))
+            }
+            (Field::Kind, Static::Kind(kind)) => {
+                compare(ident(SPAN_KIND_COLUMN), *op, lit(kind.as_column_value()))
+            }
+            (Field::Duration, Static::Duration(nanos) | Static::Int(nanos)) => compare(
+                ident(DURATION_NANO_COLUMN),
+                *op,
+                lit((*nanos).max(0) as u64),
+            ),
+            (Field::Attribute { scope, name }, value) => attribute_columns(*scope, name, schema)
+                .into_iter()
+                .filter_map(|column| {
+                    let data_type = &schema.column_schema_by_name(&column)?.data_type;
+                    let value = attribute_literal(data_type, *op, value)?;
+                    Some(compare(ident(&column), *op, value))
+                })
+                .reduce(Expr::or)
+                // Spans without the attribute never match.
+                .unwrap_or(lit(false)),
+            // The parser rejects other comparisons.
+            _ => lit(false),
+        },
+    }
+}
+
+/// Returns the existing columns of the attribute.
+fn attribute_columns(scope: AttributeScope, name: &str, schema: &SchemaRef) -> Vec<String> {
+    if name == KEY_SERVICE_NAME && scope != AttributeScope::Span {
+        return vec![SERVICE_NAME_COLUMN.to_string()];
+    }
+
+    let prefixes = match scope {
+        AttributeScope::Span => vec![SPAN_ATTRIBUTE_COLUMN_PREFIX],
+        AttributeScope::Resource => vec![RESOURCE_ATTRIBUTE_COLUMN_PREFIX],
+        AttributeScope::Any => vec![
+            SPAN_ATTRIBUTE_COLUMN_PREFIX,
+            RESOURCE_ATTRIBUTE_COLUMN_PREFIX,
+        ],
+    };
+    prefixes
+        .into_iter()
+        .map(|prefix| format!("{prefix}{name}"))
+        .filter(|column| schema.contains_column(column))
+        .collect()
+}
+
+/// Converts the value into a literal comparable with the attribute column, or returns `None` if
+/// the types mismatch.
+fn attribute_literal(data_type: &ConcreteDataType, op: CompareOp, value: &Static) -> Option<Expr> {
+    let literal = match value {
+        Static::String(s) if data_type.is_string() => string_literal(op, s),
+        Static::Bool(b) if data_type.is_boolean() => lit(*b),
+        Static::Int(i) | Static::Duration(i) if data_type.is_numeric() => lit(*i),
+        Static::Float(f) if data_type.is_numeric() => lit(*f),
+        // Values in the `tags` param of the v1 search API are untyped, so also match them as
+        // strings. Only equality is meaningful for them, since strings are ordered
+        // lexicographically.
+        _ if data_type.is_string() && matches!(op, CompareOp::Eq | CompareOp::NotEq) => match value
+        {
+            Static::Int(i) => lit(i.to_string()),
+            Static::Float(f) => lit(f.to_string()),
+            Static::Bool(b) => lit(b.to_string()),
+            _ => return None,
+        },
+        _ => return None,
+    };
+    Some(literal)
+}
+
+fn compare(left: Expr, op: CompareOp, right: Expr) -> Expr {
+    match op {
+        CompareOp::Eq => left.eq(right),
+        CompareOp::NotEq => left.not_eq(right),
+        CompareOp::Gt => left.gt(right),
+        CompareOp::Gte => left.gt_eq(right),
+        CompareOp::Lt => left.lt(right),
+        CompareOp::Lte => left.lt_eq(right),
+        CompareOp::Regex => binary_expr(left, Operator::RegexMatch, right),
+        CompareOp::NotRegex => binary_expr(left, Operator::RegexNotMatch, right),
+    }
+}
+
+fn string_literal(op: CompareOp, value: &str) -> Expr {
+    match op {
+        // TraceQL regular expressions are fully anchored.
+        CompareOp::Regex | CompareOp::NotRegex => lit(format!("^(?:{value})$")),
+        _ => lit(value),
+    }
+}
+
+fn collect_strings(batches: Vec<RecordBatch>) -> ServerResult<Vec<String>> {
+    let mut values = vec![];
+    for batch in batches {
+        let column = cast_array(batch.column(0), &DataType::Utf8).context(ArrowSnafu)?;
+        values.extend(
+            column
+                .as_string::<i32>()
+                .iter()
+                .flatten()
+                .map(ToString::to_string),
+        );
+    }
+    Ok(values)
+}
@@ -17,7 +17,9 @@ pub mod events;
 pub mod frontend;
 pub mod heartbeat;
 pub mod instance;
+pub mod managed_user;
 pub(crate) mod metrics;
+pub mod promql_cache;
 pub mod server;
 pub mod service_config;
 mod stream_wrapper;
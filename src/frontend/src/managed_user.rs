@@ -0,0 +1,75 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Users created by `CREATE USER` for the frontend's user provider.
+
+use async_trait::async_trait;
+use auth::error::AuthBackendSnafu;
+use auth::{ManagedUser, ManagedUserSource, TableGrant, TablePrivilege};
+use common_error::ext::BoxedError;
+use common_meta::cache::UserCacheRef;
+use common_meta::key::user::{UserPrivilege, UserValue};
+use snafu::ResultExt;
+
+/// A [ManagedUserSource] backed by the [UserCache](common_meta::cache::UserCache).
+pub struct UserCacheSource {
+    user_cache: UserCacheRef,
+}
+
+impl UserCacheSource {
+    pub fn new(user_cache: UserCacheRef) -> Self {
+        Self { user_cache }
+    }
+}
+
+#[async_trait]
+impl ManagedUserSource for UserCacheSource {
+    async fn get_user(&self, username: &str) -> auth::error::Result<Option<ManagedUser>> {
+        let user = self
+            .user_cache
+            .get_by_ref(username)
+            .await
+            .map_err(BoxedError::new)
+            .context(AuthBackendSnafu)?;
+
+        Ok(user.map(|value| to_managed_user(username, &value)))
+    }
+}
+
+fn to_managed_user(username: &str, value: &UserValue) -> ManagedUser {
+    ManagedUser {
+        username: username.to_string(),
+        password_verifiers: value.password_verifiers.clone(),
+        grants: value
+            .grants
+            .iter()
+            .map(|grant| TableGrant {
+                catalog: grant.catalog.clone(),
+                schema: grant.schema.clone(),
+                table: grant.table.clone(),
+                privileges: grant
+                    .privileges
+                    .iter()
+                    .map(|privilege| match privilege {
+                        UserPrivilege::Select => TablePrivilege::Select,
+                        UserPrivilege::Insert => TablePrivilege::Insert,
+                        UserPrivilege::Delete => TablePrivilege::Delete,
+                        UserPrivilege::Alter => TablePrivilege::Alter,
+                        UserPrivilege::Drop => TablePrivilege::Drop,
+                    })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
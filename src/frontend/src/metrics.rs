@@ -38,6 +38,14 @@ lazy_static! {
     )
     .unwrap();
 
+    /// The number of PromQL results cache shards, labeled with hit or miss.
+    pub static ref PROMQL_CACHE_SHARDS: IntCounterVec = register_int_counter_vec!(
+        "greptime_frontend_promql_cache_shards",
+        "frontend promql results cache shards",
+        &["type"]
+    )
+    .unwrap();
+
     /// The number of OpenTelemetry metrics send by frontend node.
     pub static ref OTLP_METRICS_ROWS: IntCounter = register_int_counter!(
         "greptime_frontend_otlp_metrics_rows",
@@ -0,0 +1,729 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A results cache for PromQL range queries.
+//!
+//! A range query is split into shards of `split_interval` aligned to the query
+//! step. Shards that are fully covered by the query and older than
+//! `max_freshness` are cached, so refreshing a dashboard only recomputes the
+//! recent tail and the shards invalidated by writes.
+//!
+//! Writes are observed through the [`InsertListener`] of this frontend's
+//! inserter, which is also notified by the Prometheus remote write batcher
+//! after each flush. Deletes and DDL such as `TRUNCATE`, `DROP`, `CREATE` and `ALTER`
+//! are observed through the [`TableChangeListener`] of this frontend's deleter
+//! and statement executor. Changes handled by other frontends are not observed,
+//! so in a cluster with several frontends cached results may be stale for up to
+//! the cache `ttl`.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use common_base::readable_size::ReadableSize;
+use common_catalog::format_full_table_name;
+use common_query::{Output, OutputData};
+use common_recordbatch::{RecordBatch, RecordBatches, util};
+use common_time::util::current_time_millis;
+use datafusion::arrow::array::{AsArray, BooleanArray};
+use datafusion::arrow::compute::filter_record_batch;
+use datafusion::arrow::datatypes::{DataType, TimeUnit, TimestampMillisecondType};
+use datafusion::error::DataFusionError;
+use datafusion_expr::LogicalPlan;
+use datatypes::schema::SchemaRef;
+use moka::sync::Cache;
+use operator::insert::InsertListener;
+use operator::statement::StatementExecutor;
+use operator::table::TableChangeListener;
+use promql_parser::parser::{EvalStmt, Expr, Offset};
+use promql_parser::util::{ExprVisitor, walk_expr};
+use query::parser::QueryStatement;
+use serde::{Deserialize, Serialize};
+use session::context::QueryContextRef;
+use session::table_name::table_idents_to_full_name;
+use snafu::ResultExt;
+use sql::util::extract_tables_from_prom_expr_checked;
+use table::table_reference::TableReference;
+
+use crate::error::{CollectRecordbatchSnafu, DataFusionSnafu, NotSupportedSnafu, Result};
+use crate::metrics::PROMQL_CACHE_SHARDS;
+
+/// Options of the PromQL range query results cache.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct PromqlCacheOptions {
+    /// Whether to enable the cache.
+    pub enable: bool,
+    /// The time span of each cached shard, rounded up to a multiple of the query step.
+    #[serde(with = "humantime_serde")]
+    pub split_interval: Duration,
+    /// Shards ending within this duration before now are never cached, as samples
+    /// may still be arriving for them.
+    #[serde(with = "humantime_serde")]
+    pub max_freshness: Duration,
+    /// The maximum memory used by cached results.
+    pub capacity: ReadableSize,
+    /// The time to live of a cached shard. It bounds the staleness caused by
+    /// changes handled by other frontends, which don't invalidate cached shards.
+    #[serde(with = "humantime_serde")]
+    pub ttl: Duration,
+}
+
+impl Default for PromqlCacheOptions {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            split_interval: Duration::from_secs(60 * 60),
+            max_freshness: Duration::from_secs(10 * 60),
+            capacity: ReadableSize::mb(256),
+            ttl: Duration::from_secs(5 * 60),
+        }
+    }
+}
+
+pub type PromqlCacheRef = Arc<PromqlCache>;
+
+/// Caches the results of PromQL range queries in step-aligned time shards.
+pub struct PromqlCache {
+    split_interval: i64,
+    max_freshness: i64,
+    shards: Cache<ShardKey, Arc<CachedShard>>,
+    /// The maximal end of the cached shards of each table. Writes after it can't
+    /// affect any cached shard, which is the common case.
+    watermarks: RwLock<HashMap<String, i64>>,
+    /// Increases on each invalidation, so results computed concurrently with a
+    /// write to a cached window are not cached.
+    generation: AtomicU64,
+}
+
+/// Identifies a range query regardless of its time range.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct QueryKey {
+    catalog: String,
+    schema: String,
+    /// The normalized PromQL expression.
+    expr: String,
+    alias: Option<String>,
+    step: i64,
+    lookback: i64,
+    /// The offset of evaluation timestamps to the step, i.e. `start % step`.
+    offset: i64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+struct ShardKey {
+    query: Arc<QueryKey>,
+    /// The start of the shard in milliseconds.
+    start: i64,
+}
+
+struct CachedShard {
+    /// Full names of the tables read by the query.
+    tables: Arc<HashSet<String>>,
+    /// The exclusive end of the shard in milliseconds.
+    end: i64,
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+}
+
+/// A step-aligned part of the time range of a query. All timestamps are in milliseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Shard {
+    start: i64,
+    /// Exclusive end of the shard.
+    end: i64,
+    /// The first evaluation timestamp of the query within the shard.
+    query_start: i64,
+    /// The last evaluation timestamp of the query within the shard.
+    query_end: i64,
+    /// Whether the query covers the whole shard and the shard is old enough to be cached.
+    cacheable: bool,
+}
+
+/// A range query that can be served by the [`PromqlCache`].
+pub struct CacheableQuery {
+    key: Arc<QueryKey>,
+    eval_stmt: EvalStmt,
+    tables: Arc<HashSet<String>>,
+    shards: Vec<Shard>,
+}
+
+impl CacheableQuery {
+    fn shard_key(&self, start: i64) -> ShardKey {
+        ShardKey {
+            query: self.key.clone(),
+            start,
+        }
+    }
+}
+
+/// Consecutive shards that are either served from the cache or computed together.
+enum Segment {
+    Hit(Arc<CachedShard>),
+    Miss(Vec<Shard>),
+}
+
+impl PromqlCache {
+    pub fn new(options: &PromqlCacheOptions) -> Self {
+        let shards = Cache::builder()
+            .name("promql_results")
+            .max_capacity(options.capacity.as_bytes())
+            .weigher(|key: &ShardKey, shard: &Arc<CachedShard>| {
+                let size = key.query.expr.len()
+                    + shard
+                        .batches
+                        .iter()
+                        .map(|batch| batch.df_record_batch().get_array_memory_size())
+                        .sum::<usize>();
+                size.try_into().unwrap_or(u32::MAX)
+            })
+            .time_to_live(options.ttl)
+            .support_invalidation_closures()
+            .build();
+        Self {
+            split_interval: options.split_interval.as_millis() as i64,
+            max_freshness: options.max_freshness.as_millis() as i64,
+            shards,
+            watermarks: RwLock::new(HashMap::new()),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the [`CacheableQuery`] of the range query, or `None` if the query
+    /// can't be served from the cache.
+    pub fn cacheable_query(
+        &self,
+        eval_stmt: &EvalStmt,
+        alias: Option<String>,
+        query_ctx: &QueryContextRef,
+    ) -> Option<CacheableQuery> {
+        let start = system_time_to_millis(eval_stmt.start)?;
+        let end = system_time_to_millis(eval_stmt.end)?;
+        let step = eval_stmt.interval.as_millis() as i64;
+        // Instant queries have nothing to split.
+        if step <= 0 || end <= start || !is_cacheable_expr(&eval_stmt.expr) {
+            return None;
+        }
+        let tables = extract_tables_from_prom_expr_checked(&eval_stmt.expr)?
+            .map(|name| {
+                table_idents_to_full_name(&name, query_ctx)
+                    .ok()
+                    .map(|(catalog, schema, table)| {
+                        format_full_table_name(&catalog, &schema, &table)
+                    })
+            })
+            .collect::<Option<HashSet<_>>>()?;
+
+        let shard_len = (self.split_interval.max(step) + step - 1) / step * step;
+        let shards = split_shards(
+            start,
+            end,
+            step,
+            shard_len,
+            current_time_millis() - self.max_freshness,
+        );
+        let key = QueryKey {
+            catalog: query_ctx.current_catalog().to_string(),
+            schema: query_ctx.current_schema(),
+            expr: eval_stmt.expr.to_string(),
+            alias,
+            step,
+            lookback: eval_stmt.lookback_delta.as_millis() as i64,
+            offset: start.rem_euclid(step),
+        };
+        Some(CacheableQuery {
+            key: Arc::new(key),
+            eval_stmt: eval_stmt.clone(),
+            tables: Arc::new(tables),
+            shards,
+        })
+    }
+
+    /// Executes the query, serving cached shards and computing the rest.
+    ///
+    /// `plan` is the logical plan of the whole query, which is used as is when
+    /// no shard is cached.
+    pub async fn execute(
+        &self,
+        query: CacheableQuery,
+        plan: LogicalPlan,
+        statement_executor: &StatementExecutor,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let mut segments: Vec<Segment> = Vec::new();
+        for shard in &query.shards {
+            let cached = shard
+                .cacheable
+                .then(|| self.shards.get(&query.shard_key(shard.start)))
+                .flatten();
+            match (cached, segments.last_mut()) {
+                (Some(cached), _) => segments.push(Segment::Hit(cached)),
+                (None, Some(Segment::Miss(run))) => run.push(*shard),
+                (None, _) => segments.push(Segment::Miss(vec![*shard])),
+            }
+        }
+
+        let mut plan = match segments.as_slice() {
+            [Segment::Miss(_)] => Some(plan),
+            _ => None,
+        };
+        let mut schema = None;
+        let mut batches = Vec::new();
+        for segment in segments {
+            let (records_schema, records) = match segment {
+                Segment::Hit(cached) => {
+                    PROMQL_CACHE_SHARDS.with_label_values(&["hit"]).inc();
+                    (cached.schema.clone(), cached.batches.clone())
+                }
+                Segment::Miss(run) => {
+                    PROMQL_CACHE_SHARDS
+                        .with_label_values(&["miss"])
+                        .inc_by(run.len() as u64);
+                    // Safety: a miss run is never empty.
+                    let (start, end) = (run[0].query_start, run[run.len() - 1].query_end);
+                    // Registers the shards before computing them, so writes during the
+                    // computation bump the generation.
+                    if let Some(shard) = run.iter().rev().find(|shard| shard.cacheable) {
+                        self.update_watermarks(&query.tables, shard.end);
+                    }
+                    let generation = self.generation.load(Ordering::Acquire);
+                    let plan = match plan.take() {
+                        Some(plan) => plan,
+                        None => {
+                            plan_range(&query, start, end, statement_executor, &query_ctx).await?
+                        }
+                    };
+                    let records = execute_plan(plan, statement_executor, &query_ctx).await?;
+                    if generation == self.generation.load(Ordering::Acquire) {
+                        self.fill(&query, &run, &records)?;
+                    }
+                    (records.schema(), records.take())
+                }
+            };
+
+            let expected = schema.get_or_insert_with(|| records_schema.clone());
+            if *expected != records_schema {
+                // Results computed before and after a schema change can't be merged.
+                self.invalidate_query(&query);
+                let plan = plan_range(
+                    &query,
+                    query.shards[0].query_start,
+                    query.shards[query.shards.len() - 1].query_end,
+                    statement_executor,
+                    &query_ctx,
+                )
+                .await?;
+                return statement_executor
+                    .exec_plan(plan, query_ctx)
+                    .await
+                    .map_err(Into::into);
+            }
+            batches.extend(records);
+        }
+
+        // Safety: a range query has at least one shard.
+        let records =
+            RecordBatches::try_new(schema.unwrap(), batches).context(CollectRecordbatchSnafu)?;
+        Ok(Output::new_with_record_batches(records))
+    }
+
+    /// Caches the cacheable shards of a computed run of shards.
+    fn fill(&self, query: &CacheableQuery, run: &[Shard], records: &RecordBatches) -> Result<()> {
+        let schema = records.schema();
+        let Some(ts_index) = schema.arrow_schema().fields().iter().position(|field| {
+            matches!(
+                field.data_type(),
+                DataType::Timestamp(TimeUnit::Millisecond, _)
+            )
+        }) else {
+            return Ok(());
+        };
+
+        for shard in run.iter().filter(|shard| shard.cacheable) {
+            let mut batches = Vec::new();
+            for batch in records.iter() {
+                let batch = filter_by_time(batch, ts_index, shard.start, shard.end)?;
+                if batch.num_rows() > 0 {
+                    batches.push(batch);
+                }
+            }
+            self.shards.insert(
+                query.shard_key(shard.start),
+                Arc::new(CachedShard {
+                    tables: query.tables.clone(),
+                    end: shard.end,
+                    schema: schema.clone(),
+                    batches,
+                }),
+            );
+        }
+        Ok(())
+    }
+
+    fn update_watermarks(&self, tables: &HashSet<String>, end: i64) {
+        let mut watermarks = self.watermarks.write().unwrap();
+        for table in tables {
+            let watermark = watermarks.entry(table.clone()).or_insert(end);
+            *watermark = (*watermark).max(end);
+        }
+    }
+
+    fn invalidate_query(&self, query: &CacheableQuery) {
+        for shard in &query.shards {
+            self.shards.invalidate(&query.shard_key(shard.start));
+        }
+    }
+}
+
+impl InsertListener for PromqlCache {
+    fn on_inserted(&self, table: TableReference, min_timestamp_millis: i64) {
+        let table = table.to_string();
+        let affected = self
+            .watermarks
+            .read()
+            .unwrap()
+            .get(&table)
+            .is_some_and(|watermark| min_timestamp_millis < *watermark);
+        if !affected {
+            return;
+        }
+
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        // Evaluation timestamps before the written rows can't see them.
+        self.shards
+            .invalidate_entries_if(move |_, shard| {
+                shard.end > min_timestamp_millis && shard.tables.contains(&table)
+            })
+            .expect("cache should support invalidation closures");
+    }
+}
+
+impl TableChangeListener for PromqlCache {
+    fn on_table_changed(&self, table: TableReference) {
+        let table = table.to_string();
+        self.generation.fetch_add(1, Ordering::AcqRel);
+        // Deletes may remove rows of any time and DDL may change the results of
+        // any shard, so all shards reading the table are invalidated.
+        self.shards
+            .invalidate_entries_if(move |_, shard| shard.tables.contains(&table))
+            .expect("cache should support invalidation closures");
+    }
+}
+
+/// Splits the evaluation timestamps `start, start + step, ..., end` into shards of
+/// `shard_len`, which must be a multiple of `step`.
+///
+/// Shards are aligned to multiples of `shard_len` offset by `start % step`, so queries
+/// with the same step and offset share shards. Shards ending after `cacheable_before`
+/// are not cacheable.
+fn split_shards(
+    start: i64,
+    end: i64,
+    step: i64,
+    shard_len: i64,
+    cacheable_before: i64,
+) -> Vec<Shard> {
+    let offset = start.rem_euclid(step);
+    let first = (start - offset).div_euclid(shard_len);
+    let last = (end - offset).div_euclid(shard_len);
+    (first..=last)
+        .map(|index| {
+            let shard_start = offset + index * shard_len;
+            let shard_end = shard_start + shard_len;
+            Shard {
+                start: shard_start,
+                end: shard_end,
+                query_start: start.max(shard_start),
+                query_end: end.min(shard_end - step),
+                cacheable: start <= shard_start
+                    && shard_end - step <= end
+                    && shard_end <= cacheable_before,
+            }
+        })
+        .collect()
+}
+
+/// Returns whether the results of the expression at each evaluation timestamp only
+/// depend on that timestamp.
+///
+/// `@` modifiers and negative offsets read data relative to the query range or after
+/// the evaluation timestamp, and subqueries are evaluated relative to the query start.
+fn is_cacheable_expr(expr: &Expr) -> bool {
+    struct Checker {
+        cacheable: bool,
+    }
+
+    impl ExprVisitor for Checker {
+        type Error = ();
+
+        fn pre_visit(&mut self, expr: &Expr) -> std::result::Result<bool, Self::Error> {
+            let selector = match expr {
+                Expr::VectorSelector(selector) => Some(selector),
+                Expr::MatrixSelector(selector) => Some(&selector.vs),
+                Expr::Subquery(_) | Expr::Extension(_) => {
+                    self.cacheable = false;
+                    None
+                }
+                _ => None,
+            };
+            if let Some(selector) = selector
+                && (selector.at.is_some() || matches!(selector.offset, Some(Offset::Neg(_))))
+            {
+                self.cacheable = false;
+            }
+            Ok(self.cacheable)
+        }
+    }
+
+    let mut checker = Checker { cacheable: true };
+    let _ = walk_expr(&mut checker, expr);
+    checker.cacheable
+}
+
+async fn plan_range(
+    query: &CacheableQuery,
+    start: i64,
+    end: i64,
+    statement_executor: &StatementExecutor,
+    query_ctx: &QueryContextRef,
+) -> Result<LogicalPlan> {
+    let mut eval_stmt = query.eval_stmt.clone();
+    eval_stmt.start = millis_to_system_time(start);
+    eval_stmt.end = millis_to_system_time(end);
+    let stmt = QueryStatement::Promql(eval_stmt, query.key.alias.clone());
+    Ok(statement_executor.plan(&stmt, query_ctx.clone()).await?)
+}
+
+async fn execute_plan(
+    plan: LogicalPlan,
+    statement_executor: &StatementExecutor,
+    query_ctx: &QueryContextRef,
+) -> Result<RecordBatches> {
+    let output = statement_executor
+        .exec_plan(plan, query_ctx.clone())
+        .await?;
+    match output.data {
+        OutputData::Stream(stream) => util::collect_batches(stream)
+            .await
+            .context(CollectRecordbatchSnafu),
+        OutputData::RecordBatches(records) => Ok(records),
+        OutputData::AffectedRows(_) => NotSupportedSnafu {
+            feat: "caching PromQL results without record batches",
+        }
+        .fail(),
+    }
+}
+
+/// Returns the rows of the batch whose timestamps are within `[start, end)`.
+fn filter_by_time(
+    batch: &RecordBatch,
+    ts_index: usize,
+    start: i64,
+    end: i64,
+) -> Result<RecordBatch> {
+    let df_batch = batch.df_record_batch();
+    let timestamps = df_batch
+        .column(ts_index)
+        .as_primitive::<TimestampMillisecondType>();
+    let mask = timestamps
+        .iter()
+        .map(|ts| ts.map(|ts| ts >= start && ts < end))
+        .collect::<BooleanArray>();
+    let filtered = filter_record_batch(df_batch, &mask)
+        .map_err(DataFusionError::from)
+        .context(DataFusionSnafu)?;
+    Ok(RecordBatch::from_df_record_batch(
+        batch.schema.clone(),
+        filtered,
+    ))
+}
+
+fn system_time_to_millis(time: SystemTime) -> Option<i64> {
+    time.duration_since(UNIX_EPOCH)
+        .ok()
+        .and_then(|duration| duration.as_millis().try_into().ok())
+}
+
+fn millis_to_system_time(millis: i64) -> SystemTime {
+    if millis >= 0 {
+        UNIX_EPOCH + Duration::from_millis(millis as u64)
+    } else {
+        UNIX_EPOCH - Duration::from_millis(millis.unsigned_abs())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MINUTE: i64 = 60 * 1000;
+    const HOUR: i64 = 60 * MINUTE;
+
+    #[test]
+    fn test_split_shards() {
+        let start = 10 * HOUR + 30 * MINUTE + 15_000;
+        let end = 13 * HOUR + 5 * MINUTE + 15_000;
+        let shards = split_shards(start, end, MINUTE, HOUR, 12 * HOUR + 15_000);
+        assert_eq!(4, shards.len());
+
+        // The head shard is only partially covered.
+        assert_eq!(10 * HOUR + 15_000, shards[0].start);
+        assert_eq!(start, shards[0].query_start);
+        assert_eq!(11 * HOUR - MINUTE + 15_000, shards[0].query_end);
+        assert!(!shards[0].cacheable);
+
+        assert_eq!(11 * HOUR + 15_000, shards[1].start);
+        assert_eq!(12 * HOUR + 15_000, shards[1].end);
+        assert!(shards[1].cacheable);
+
+        // Too fresh to be cached.
+        assert_eq!(12 * HOUR + 15_000, shards[2].start);
+        assert!(!shards[2].cacheable);
+
+        assert_eq!(13 * HOUR + 15_000, shards[3].query_start);
+        assert_eq!(end, shards[3].query_end);
+        assert!(!shards[3].cacheable);
+    }
+
+    #[test]
+    fn test_split_shards_single_step() {
+        let shards = split_shards(HOUR, HOUR, MINUTE, HOUR, i64::MAX);
+        assert_eq!(1, shards.len());
+        assert_eq!(HOUR, shards[0].query_start);
+        assert_eq!(HOUR, shards[0].query_end);
+        assert!(!shards[0].cacheable);
+    }
+
+    #[test]
+    fn test_is_cacheable_expr() {
+        let cases = [
+            ("up", true),
+            ("sum by (job) (rate(http_requests_total[5m]))", true),
+            ("up offset 5m", true),
+            ("up offset -5m", false),
+            ("up @ 1000", false),
+            ("rate(http_requests_total[5m] @ end())", false),
+            ("max_over_time(rate(http_requests_total[5m])[1h:1m])", false),
+        ];
+        for (query, expected) in cases {
+            let expr = promql_parser::parser::parse(query).unwrap();
+            assert_eq!(expected, is_cacheable_expr(&expr), "{query}");
+        }
+    }
+
+    #[test]
+    fn test_invalidate_on_insert() {
+        let cache = PromqlCache::new(&PromqlCacheOptions {
+            enable: true,
+            ..Default::default()
+        });
+        let query = Arc::new(QueryKey {
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            expr: "up".to_string(),
+            alias: None,
+            step: MINUTE,
+            lookback: 5 * MINUTE,
+            offset: 0,
+        });
+        let tables = Arc::new(HashSet::from(["greptime.public.up".to_string()]));
+        cache.update_watermarks(&tables, 2 * HOUR);
+        for start in [0, HOUR] {
+            cache.shards.insert(
+                ShardKey {
+                    query: query.clone(),
+                    start,
+                },
+                Arc::new(CachedShard {
+                    tables: tables.clone(),
+                    end: start + HOUR,
+                    schema: RecordBatches::empty().schema(),
+                    batches: vec![],
+                }),
+            );
+        }
+        let contains = |start| {
+            cache.shards.contains_key(&ShardKey {
+                query: query.clone(),
+                start,
+            })
+        };
+
+        // Writes to other tables or after all shards don't invalidate anything.
+        cache.on_inserted(TableReference::full("greptime", "public", "other"), 0);
+        cache.on_inserted(TableReference::full("greptime", "public", "up"), 2 * HOUR);
+        assert!(contains(0));
+        assert!(contains(HOUR));
+
+        cache.on_inserted(
+            TableReference::full("greptime", "public", "up"),
+            HOUR + MINUTE,
+        );
+        cache.shards.run_pending_tasks();
+        assert!(contains(0));
+        assert!(!contains(HOUR));
+    }
+
+    #[test]
+    fn test_invalidate_on_table_changed() {
+        let cache = PromqlCache::new(&PromqlCacheOptions {
+            enable: true,
+            ..Default::default()
+        });
+        let query = Arc::new(QueryKey {
+            catalog: "greptime".to_string(),
+            schema: "public".to_string(),
+            expr: "up".to_string(),
+            alias: None,
+            step: MINUTE,
+            lookback: 5 * MINUTE,
+            offset: 0,
+        });
+        let tables = Arc::new(HashSet::from(["greptime.public.up".to_string()]));
+        for start in [0, HOUR] {
+            cache.shards.insert(
+                ShardKey {
+                    query: query.clone(),
+                    start,
+                },
+                Arc::new(CachedShard {
+                    tables: tables.clone(),
+                    end: start + HOUR,
+                    schema: RecordBatches::empty().schema(),
+                    batches: vec![],
+                }),
+            );
+        }
+        let contains = |start| {
+            cache.shards.contains_key(&ShardKey {
+                query: query.clone(),
+                start,
+            })
+        };
+
+        cache.on_table_changed(TableReference::full("greptime", "public", "other"));
+        cache.shards.run_pending_tasks();
+        assert!(contains(0));
+        assert!(contains(HOUR));
+
+        // Changes invalidate all shards of the table regardless of the watermark.
+        let generation = cache.generation.load(Ordering::Acquire);
+        cache.on_table_changed(TableReference::full("greptime", "public", "up"));
+        cache.shards.run_pending_tasks();
+        assert!(!contains(0));
+        assert!(!contains(HOUR));
+        assert!(cache.generation.load(Ordering::Acquire) > generation);
+    }
+}
@@ -26,7 +26,7 @@ use common_telemetry::{info, warn};
 use meta_client::MetaClientOptions;
 use servers::error::Error as ServerError;
 use servers::grpc::builder::GrpcServerBuilder;
-use servers::grpc::flight::FlightCraftRef;
+use servers::grpc::flight::{FlightCraftRef, FlightSqlCraft};
 use servers::grpc::frontend_grpc_handler::FrontendGrpcHandler;
 use servers::grpc::greptime_handler::GreptimeRequestHandler;
 use servers::grpc::{GrpcOptions, GrpcServer};
@@ -118,11 +118,15 @@ where
         }
 
         if opts.opentsdb.enable {
-            builder = builder.with_opentsdb_handler(self.instance.clone());
+            builder = builder
+                .with_opentsdb_handler(self.instance.clone())
+                .with_opentsdb_query_handler(self.instance.clone());
         }
 
         if opts.influxdb.enable {
-            builder = builder.with_influxdb_handler(self.instance.clone());
+            builder = builder
+                .with_influxdb_handler(self.instance.clone())
+                .with_influxql_handler(self.instance.clone());
         }
 
         if opts.prom_store.enable {
@@ -132,6 +136,7 @@ where
                     self.instance.node_manager().clone(),
                     self.instance.catalog_manager().clone(),
                     self.instance.table_flownode_set_cache().clone(),
+                    self.instance.insert_listener().cloned(),
                     opts.prom_store.with_metric_engine,
                     self.instance.clone(),
                     opts.prom_store.pending_rows_flush_interval,
@@ -166,6 +171,10 @@ where
             builder = builder.with_jaeger_handler(self.instance.clone());
         }
 
+        if opts.tempo.enable {
+            builder = builder.with_tempo_handler(self.instance.clone(), opts.tempo.search_limits());
+        }
+
         builder = builder.with_dashboard_handler(self.instance.clone());
 
         if let Some(configurator) = self.plugins.get::<RouterConfigurator>() {
@@ -246,6 +255,13 @@ where
             .flight_handler
             .clone()
             .unwrap_or_else(|| Arc::new(greptime_request_handler.clone()) as FlightCraftRef);
+        // Serve Arrow Flight SQL on the same Flight service.
+        let flight_handler = Arc::new(FlightSqlCraft::new(
+            flight_handler,
+            self.instance.clone(),
+            self.instance.catalog_manager().clone(),
+            user_provider.clone(),
+        )) as FlightCraftRef;
 
         let grpc_server = builder
             .name(name)
@@ -19,6 +19,7 @@ pub mod opentsdb;
 pub mod otlp;
 pub mod postgres;
 pub mod prom_store;
+pub mod tempo;
 
 pub use influxdb::{InfluxdbMergeMode, InfluxdbOptions};
 pub use jaeger::JaegerOptions;
@@ -27,3 +28,4 @@ pub use opentsdb::OpentsdbOptions;
 pub use otlp::OtlpOptions;
 pub use postgres::PostgresOptions;
 pub use prom_store::PromStoreOptions;
+pub use tempo::TempoOptions;
@@ -0,0 +1,59 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+use serde::{Deserialize, Serialize};
+use servers::http::tempo::TempoSearchLimits;
+
+/// Options for Tempo query APIs.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct TempoOptions {
+    /// Whether to enable Tempo query APIs.
+    pub enable: bool,
+    /// The max number of traces returned by a search.
+    pub max_search_limit: usize,
+    /// The max number of spans returned for each span set of a trace in a search.
+    pub max_spans_per_span_set: usize,
+}
+
+impl Default for TempoOptions {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            max_search_limit: 1000,
+            max_spans_per_span_set: 100,
+        }
+    }
+}
+
+impl TempoOptions {
+    pub fn search_limits(&self) -> TempoSearchLimits {
+        TempoSearchLimits {
+            max_search_limit: self.max_search_limit,
+            max_spans_per_span_set: self.max_spans_per_span_set,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TempoOptions;
+
+    #[test]
+    fn test_tempo_options() {
+        let default = TempoOptions::default();
+        assert!(default.enable);
+        assert_eq!(1000, default.max_search_limit);
+        assert_eq!(100, default.max_spans_per_span_set);
+    }
+}
@@ -14,5 +14,6 @@
 
 pub mod error;
 mod log_query;
+pub mod logql;
 
 pub use log_query::*;
@@ -124,9 +124,10 @@ pub enum LogExpr {
         expr: Vec<AggFunc>,
         by: Vec<LogExpr>,
     },
+    /// Extracts fields from a structured value, keeping all existing columns.
     Decompose {
         expr: Box<LogExpr>,
-        /// JSON, CSV, etc.
+        /// `json` or `logfmt`.
         schema: String,
         /// Fields with type name to extract from the decomposed value.
         fields: Vec<(String, String)>,
@@ -143,6 +144,31 @@ pub enum LogExpr {
     Filter {
         filter: ColumnFilters,
     },
+    /// Sorts rows by the given expressions.
+    ///
+    /// Consecutive sorts make up a single sort, the keys of the earlier ones
+    /// taking precedence, so keys can be sorted in different orders.
+    Sort {
+        by: Vec<LogExpr>,
+        descending: bool,
+    },
+    /// Range aggregation evaluated at fixed steps, like LogQL's `count_over_time`.
+    ///
+    /// For each step `t` in `[start, end]` the rows with `timestamp` in
+    /// `(t - range, t]` are aggregated per `by` group. The result contains the
+    /// `by` columns, the step time named after the `timestamp` column, and the
+    /// aggregated value. All times and durations are in milliseconds.
+    RangeAggr {
+        /// Function name, `count_over_time` or `rate`.
+        name: String,
+        timestamp: Box<LogExpr>,
+        by: Vec<LogExpr>,
+        start: i64,
+        end: i64,
+        step: i64,
+        range: i64,
+        alias: Option<String>,
+    },
 }
 
 impl Default for LogQuery {
@@ -0,0 +1,986 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Parser for the subset of [LogQL] served by the Loki query API, and its
+//! translation into a [`LogQuery`].
+//!
+//! Supported syntax:
+//! - stream selectors: `{app="api", env!="dev", pod=~"api-.*", ns!~"kube-.*"}`
+//! - line filters: `|= "text"`, `!= "text"`, `|~ "regex"`, `!~ "regex"`
+//! - parsers: `| json`, `| logfmt`
+//! - label filters: `| level="error"`, also with `!=`, `=~` and `!~`
+//! - range aggregations: `count_over_time(<selector> [5m])`, `rate(<selector> [5m])`
+//! - vector aggregations over a range aggregation: `sum`, `avg`, `min`, `max` and
+//!   `count`, optionally with `by (<labels>)`
+//!
+//! Parsers only extract the labels referenced later in the query, e.g. by a
+//! label filter or a `by` clause.
+//!
+//! [LogQL]: https://grafana.com/docs/loki/latest/query/
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use chrono::{DateTime, SecondsFormat};
+use table::table_name::TableName;
+
+use crate::error::{InvalidLogQlSnafu, Result, UnsupportedLogQlSnafu};
+use crate::{
+    AggFunc, BinaryOperator, ColumnFilters, ContentFilter, Context, EqualValue, Filters, Limit,
+    LogExpr, LogQuery, TimeFilter,
+};
+
+/// Name of the value column produced by metric queries.
+pub const METRIC_VALUE_COLUMN: &str = "value";
+
+/// A parsed LogQL query.
+#[derive(Debug, Clone, PartialEq)]
+pub enum LogQlExpr {
+    /// A log query returning log lines.
+    Log(LogSelector),
+    /// A metric query returning samples.
+    Metric(MetricExpr),
+}
+
+/// A stream selector followed by a log pipeline.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogSelector {
+    pub matchers: Vec<LabelMatcher>,
+    pub stages: Vec<PipelineStage>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelMatcher {
+    pub name: String,
+    pub op: MatchOp,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchOp {
+    Equal,
+    NotEqual,
+    Regex,
+    NotRegex,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum PipelineStage {
+    /// `|=`, `!=`, `|~` or `!~` on the log line.
+    LineFilter {
+        op: MatchOp,
+        value: String,
+    },
+    Parser(LogParser),
+    LabelFilter(LabelMatcher),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogParser {
+    Json,
+    Logfmt,
+}
+
+impl LogParser {
+    fn schema(&self) -> &'static str {
+        match self {
+            LogParser::Json => "json",
+            LogParser::Logfmt => "logfmt",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MetricExpr {
+    Range(RangeAggregation),
+    Vector(VectorAggregation),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RangeAggregation {
+    pub op: RangeOp,
+    pub selector: LogSelector,
+    /// Range in milliseconds.
+    pub range: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RangeOp {
+    CountOverTime,
+    Rate,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VectorAggregation {
+    pub op: VectorOp,
+    pub grouping: Vec<String>,
+    pub inner: RangeAggregation,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VectorOp {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count,
+}
+
+impl VectorOp {
+    fn aggregate_function(&self) -> &'static str {
+        match self {
+            VectorOp::Sum => "sum",
+            VectorOp::Avg => "avg",
+            VectorOp::Min => "min",
+            VectorOp::Max => "max",
+            VectorOp::Count => "count",
+        }
+    }
+}
+
+/// The table a LogQL query reads from.
+#[derive(Debug, Clone)]
+pub struct LogQlTarget {
+    pub table: TableName,
+    pub timestamp_column: String,
+    pub line_column: String,
+    /// Stream labels, i.e. the tag columns of the table.
+    pub stream_labels: Vec<String>,
+}
+
+/// Parses a LogQL query.
+pub fn parse(query: &str) -> Result<LogQlExpr> {
+    let mut parser = Parser::new(query);
+    let expr = parser.parse_expr()?;
+    parser.expect_end()?;
+    Ok(expr)
+}
+
+/// Parses a Prometheus style duration like `5m` or `1h30m` into milliseconds.
+pub fn parse_duration(input: &str) -> Result<i64> {
+    let invalid = || {
+        InvalidLogQlSnafu {
+            reason: format!("invalid duration `{input}`"),
+        }
+        .build()
+    };
+
+    let mut chars = input.chars().peekable();
+    let mut total = 0i64;
+    chars.peek().ok_or_else(invalid)?;
+    while chars.peek().is_some() {
+        let mut number = String::new();
+        while let Some(c) = chars.next_if(char::is_ascii_digit) {
+            number.push(c);
+        }
+        let mut unit = String::new();
+        while let Some(c) = chars.next_if(char::is_ascii_alphabetic) {
+            unit.push(c);
+        }
+        let number = number.parse::<i64>().map_err(|_| invalid())?;
+        let millis = match unit.as_str() {
+            "ms" => 1,
+            "s" => 1_000,
+            "m" => 60_000,
+            "h" => 3_600_000,
+            "d" => 86_400_000,
+            "w" => 604_800_000,
+            "y" => 31_536_000_000,
+            _ => return Err(invalid()),
+        };
+        total = number
+            .checked_mul(millis)
+            .and_then(|v| total.checked_add(v))
+            .ok_or_else(invalid)?;
+    }
+    Ok(total)
+}
+
+impl LogSelector {
+    /// Translates the selector into a query returning the matching lines, newest
+    /// first when `descending`, with their stream and extracted labels.
+    ///
+    /// `start` and `end` are in milliseconds; `end` is exclusive.
+    pub fn to_log_query(
+        &self,
+        target: &LogQlTarget,
+        start: i64,
+        end: i64,
+        limit: usize,
+        descending: bool,
+    ) -> Result<LogQuery> {
+        let (filters, mut exprs) = self.pipeline(target, &[])?;
+        exprs.push(LogExpr::Sort {
+            by: vec![LogExpr::NamedIdent(target.timestamp_column.clone())],
+            descending,
+        });
+
+        let mut columns = target.stream_labels.clone();
+        columns.push(target.timestamp_column.clone());
+        columns.push(target.line_column.clone());
+
+        Ok(LogQuery {
+            table: target.table.clone(),
+            time_filter: time_filter(start, end),
+            limit: Limit {
+                skip: None,
+                fetch: Some(limit),
+            },
+            columns,
+            filters,
+            context: Context::None,
+            exprs,
+        })
+    }
+
+    /// Translates the selector into a query returning the distinct label sets
+    /// of the matching streams, like the Loki series API.
+    pub fn to_series_query(&self, target: &LogQlTarget, start: i64, end: i64) -> Result<LogQuery> {
+        let (filters, _) = self.pipeline(target, &[])?;
+        Ok(LogQuery {
+            table: target.table.clone(),
+            time_filter: time_filter(start, end),
+            limit: Limit::default(),
+            columns: vec![],
+            filters,
+            context: Context::None,
+            exprs: vec![LogExpr::AggrFunc {
+                expr: vec![],
+                by: named_idents(&target.stream_labels),
+            }],
+        })
+    }
+
+    /// Builds the filters and the expressions of the log pipeline.
+    ///
+    /// Stream selectors, line filters and label filters on stream labels become
+    /// filters. A parser extracts the other labels referenced by label filters
+    /// or listed in `extra_labels`, and label filters on them are applied after
+    /// the extraction.
+    fn pipeline(
+        &self,
+        target: &LogQlTarget,
+        extra_labels: &[String],
+    ) -> Result<(Filters, Vec<LogExpr>)> {
+        let mut filters = self
+            .matchers
+            .iter()
+            .map(label_matcher_filter)
+            .collect::<Vec<_>>();
+        let mut parser = None;
+        let mut label_filters = vec![];
+
+        for stage in &self.stages {
+            match stage {
+                PipelineStage::LineFilter { op, value } => {
+                    let content = match op {
+                        MatchOp::Equal | MatchOp::NotEqual => {
+                            ContentFilter::Contains(value.clone())
+                        }
+                        MatchOp::Regex | MatchOp::NotRegex => ContentFilter::Regex(value.clone()),
+                    };
+                    let filter = Filters::Single(ColumnFilters {
+                        expr: Box::new(LogExpr::NamedIdent(target.line_column.clone())),
+                        filters: vec![content],
+                    });
+                    if matches!(op, MatchOp::NotEqual | MatchOp::NotRegex) {
+                        filters.push(Filters::Not(Box::new(filter)));
+                    } else {
+                        filters.push(filter);
+                    }
+                }
+                PipelineStage::Parser(p) => {
+                    if parser.replace(*p).is_some() {
+                        return UnsupportedLogQlSnafu {
+                            feature: "multiple parser stages",
+                        }
+                        .fail();
+                    }
+                }
+                PipelineStage::LabelFilter(matcher) => {
+                    if target.stream_labels.contains(&matcher.name) {
+                        filters.push(label_matcher_filter(matcher));
+                    } else if parser.is_some() {
+                        label_filters.push(matcher);
+                    } else {
+                        return UnsupportedLogQlSnafu {
+                            feature: format!(
+                                "label filter on `{}`, which is neither a stream label nor extracted by a parser",
+                                matcher.name
+                            ),
+                        }
+                        .fail();
+                    }
+                }
+            }
+        }
+
+        let mut exprs = vec![];
+        if let Some(parser) = parser {
+            let mut fields: Vec<(String, String)> = vec![];
+            let referenced = label_filters
+                .iter()
+                .map(|m| &m.name)
+                .chain(extra_labels.iter());
+            for name in referenced {
+                if !target.stream_labels.contains(name) && !fields.iter().any(|(f, _)| f == name) {
+                    fields.push((name.clone(), "string".to_string()));
+                }
+            }
+            if !fields.is_empty() {
+                exprs.push(LogExpr::Decompose {
+                    expr: Box::new(LogExpr::NamedIdent(target.line_column.clone())),
+                    schema: parser.schema().to_string(),
+                    fields,
+                });
+            }
+        }
+        for matcher in label_filters {
+            exprs.push(LogExpr::Filter {
+                filter: label_matcher_column_filters(matcher),
+            });
+        }
+
+        Ok((Filters::And(filters), exprs))
+    }
+}
+
+impl MetricExpr {
+    /// Translates the metric query into a query evaluating it at each step in
+    /// `[start, end]`.
+    ///
+    /// The result contains the series labels, the step time in the timestamp
+    /// column, and the sample in [`METRIC_VALUE_COLUMN`]. All times are in
+    /// milliseconds. An instant query has `start == end` and ignores `step`.
+    pub fn to_log_query(
+        &self,
+        target: &LogQlTarget,
+        start: i64,
+        end: i64,
+        step: i64,
+    ) -> Result<LogQuery> {
+        let (range, grouping) = match self {
+            MetricExpr::Range(range) => (range, None),
+            MetricExpr::Vector(vector) => (&vector.inner, Some(&vector.grouping)),
+        };
+        let step = if start == end { range.range } else { step };
+        let (filters, mut exprs) = range
+            .selector
+            .pipeline(target, grouping.map(Vec::as_slice).unwrap_or_default())?;
+
+        let mut series_labels = target.stream_labels.clone();
+        for expr in &exprs {
+            if let LogExpr::Decompose { fields, .. } = expr {
+                series_labels.extend(fields.iter().map(|(name, _)| name.clone()));
+            }
+        }
+
+        exprs.push(LogExpr::RangeAggr {
+            name: match range.op {
+                RangeOp::CountOverTime => "count_over_time",
+                RangeOp::Rate => "rate",
+            }
+            .to_string(),
+            timestamp: Box::new(LogExpr::NamedIdent(target.timestamp_column.clone())),
+            by: named_idents(&series_labels),
+            start,
+            end,
+            step,
+            range: range.range,
+            alias: Some(METRIC_VALUE_COLUMN.to_string()),
+        });
+
+        if let MetricExpr::Vector(vector) = self {
+            let mut by = named_idents(&vector.grouping);
+            by.push(LogExpr::NamedIdent(target.timestamp_column.clone()));
+            exprs.push(LogExpr::AggrFunc {
+                expr: vec![AggFunc::new(
+                    vector.op.aggregate_function().to_string(),
+                    vec![LogExpr::NamedIdent(METRIC_VALUE_COLUMN.to_string())],
+                    Some(METRIC_VALUE_COLUMN.to_string()),
+                )],
+                by,
+            });
+        }
+
+        Ok(LogQuery {
+            table: target.table.clone(),
+            // Rows in `(start - range, end]` contribute to some step.
+            time_filter: time_filter(start - range.range + 1, end + 1),
+            limit: Limit::default(),
+            columns: vec![],
+            filters,
+            context: Context::None,
+            exprs,
+        })
+    }
+}
+
+impl LogQlExpr {
+    /// Returns the stream selector of the query.
+    pub fn selector(&self) -> &LogSelector {
+        match self {
+            LogQlExpr::Log(selector) => selector,
+            LogQlExpr::Metric(MetricExpr::Range(range)) => &range.selector,
+            LogQlExpr::Metric(MetricExpr::Vector(vector)) => &vector.inner.selector,
+        }
+    }
+}
+
+fn time_filter(start: i64, end: i64) -> TimeFilter {
+    let format = |millis: i64| {
+        DateTime::from_timestamp_millis(millis)
+            .map(|dt| dt.to_rfc3339_opts(SecondsFormat::Millis, true))
+    };
+    TimeFilter {
+        start: format(start),
+        end: format(end),
+        span: None,
+    }
+}
+
+fn named_idents(names: &[String]) -> Vec<LogExpr> {
+    names.iter().cloned().map(LogExpr::NamedIdent).collect()
+}
+
+fn label_matcher_filter(matcher: &LabelMatcher) -> Filters {
+    Filters::Single(label_matcher_column_filters(matcher))
+}
+
+/// Builds the filter of a label matcher. A missing label matches like an
+/// empty value, as in Loki.
+fn label_matcher_column_filters(matcher: &LabelMatcher) -> ColumnFilters {
+    let label = LogExpr::ScalarFunc {
+        name: "coalesce".to_string(),
+        args: vec![
+            LogExpr::NamedIdent(matcher.name.clone()),
+            LogExpr::Literal(String::new()),
+        ],
+        alias: None,
+    };
+    // LogQL regexes are fully anchored.
+    let anchored = format!("^(?:{})$", matcher.value);
+
+    match matcher.op {
+        MatchOp::Equal => ColumnFilters {
+            expr: Box::new(label),
+            filters: vec![ContentFilter::Equal(EqualValue::String(
+                matcher.value.clone(),
+            ))],
+        },
+        MatchOp::NotEqual => ColumnFilters {
+            expr: Box::new(LogExpr::BinaryOp {
+                left: Box::new(label),
+                op: BinaryOperator::Ne,
+                right: Box::new(LogExpr::Literal(matcher.value.clone())),
+            }),
+            filters: vec![],
+        },
+        MatchOp::Regex => ColumnFilters {
+            expr: Box::new(label),
+            filters: vec![ContentFilter::Regex(anchored)],
+        },
+        MatchOp::NotRegex => ColumnFilters {
+            expr: Box::new(LogExpr::ScalarFunc {
+                name: "regexp_like".to_string(),
+                args: vec![label, LogExpr::Literal(anchored)],
+                alias: None,
+            }),
+            filters: vec![ContentFilter::IsFalse],
+        },
+    }
+}
+
+struct Parser<'a> {
+    query: &'a str,
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> Parser<'a> {
+    fn new(query: &'a str) -> Self {
+        Self {
+            query,
+            chars: query.chars().peekable(),
+        }
+    }
+
+    fn error<T>(&self, reason: impl Into<String>) -> Result<T> {
+        InvalidLogQlSnafu {
+            reason: format!("{} in `{}`", reason.into(), self.query),
+        }
+        .fail()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.peek().copied()
+    }
+
+    /// Consumes `token` if the remaining input starts with it.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        let mut lookahead = self.chars.clone();
+        for expected in token.chars() {
+            if lookahead.next() != Some(expected) {
+                return false;
+            }
+        }
+        self.chars = lookahead;
+        true
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if self.eat(token) {
+            Ok(())
+        } else {
+            self.error(format!("expected `{token}`"))
+        }
+    }
+
+    fn expect_end(&mut self) -> Result<()> {
+        match self.peek() {
+            None => Ok(()),
+            Some(c) => self.error(format!("unexpected `{c}`")),
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Result<String> {
+        self.skip_whitespace();
+        let mut ident = String::new();
+        while let Some(c) = self
+            .chars
+            .next_if(|c| c.is_ascii_alphanumeric() || *c == '_')
+        {
+            ident.push(c);
+        }
+        if ident.is_empty() || ident.starts_with(|c: char| c.is_ascii_digit()) {
+            return self.error("expected identifier");
+        }
+        Ok(ident)
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        let quote = match self.peek() {
+            Some(c @ ('"' | '`')) => c,
+            _ => return self.error("expected string"),
+        };
+        self.chars.next();
+
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some(c) if c == quote => return Ok(value),
+                Some('\\') if quote == '"' => match self.chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some('r') => value.push('\r'),
+                    Some(c) => value.push(c),
+                    None => break,
+                },
+                Some(c) => value.push(c),
+                None => break,
+            }
+        }
+        self.error("unterminated string")
+    }
+
+    fn parse_match_op(&mut self) -> Result<MatchOp> {
+        if self.eat("=~") {
+            Ok(MatchOp::Regex)
+        } else if self.eat("!~") {
+            Ok(MatchOp::NotRegex)
+        } else if self.eat("!=") {
+            Ok(MatchOp::NotEqual)
+        } else if self.eat("=") {
+            Ok(MatchOp::Equal)
+        } else {
+            self.error("expected one of `=`, `!=`, `=~`, `!~`")
+        }
+    }
+
+    fn parse_label_matcher(&mut self) -> Result<LabelMatcher> {
+        let name = self.parse_identifier()?;
+        let op = self.parse_match_op()?;
+        let value = self.parse_string()?;
+        Ok(LabelMatcher { name, op, value })
+    }
+
+    fn parse_expr(&mut self) -> Result<LogQlExpr> {
+        if self.peek() == Some('{') {
+            return Ok(LogQlExpr::Log(self.parse_selector()?));
+        }
+
+        let name = self.parse_identifier()?;
+        if let Some(op) = vector_op(&name) {
+            let mut grouping = self.parse_grouping()?;
+            self.expect("(")?;
+            let inner_name = self.parse_identifier()?;
+            let inner = self.parse_range_aggregation(&inner_name)?;
+            self.expect(")")?;
+            if grouping.is_empty() {
+                grouping = self.parse_grouping()?;
+            }
+            Ok(LogQlExpr::Metric(MetricExpr::Vector(VectorAggregation {
+                op,
+                grouping,
+                inner,
+            })))
+        } else {
+            Ok(LogQlExpr::Metric(MetricExpr::Range(
+                self.parse_range_aggregation(&name)?,
+            )))
+        }
+    }
+
+    /// Parses an optional `by (<labels>)` clause.
+    fn parse_grouping(&mut self) -> Result<Vec<String>> {
+        if self.eat("without") {
+            return UnsupportedLogQlSnafu {
+                feature: "`without` grouping",
+            }
+            .fail();
+        }
+        if !self.eat("by") {
+            return Ok(vec![]);
+        }
+        self.expect("(")?;
+        let mut labels = vec![];
+        if !self.eat(")") {
+            loop {
+                labels.push(self.parse_identifier()?);
+                if self.eat(")") {
+                    break;
+                }
+                self.expect(",")?;
+            }
+        }
+        Ok(labels)
+    }
+
+    fn parse_range_aggregation(&mut self, name: &str) -> Result<RangeAggregation> {
+        let op = match name {
+            "count_over_time" => RangeOp::CountOverTime,
+            "rate" => RangeOp::Rate,
+            _ => {
+                return UnsupportedLogQlSnafu {
+                    feature: format!("function `{name}`"),
+                }
+                .fail();
+            }
+        };
+        self.expect("(")?;
+        let selector = self.parse_selector()?;
+        self.expect("[")?;
+        let mut duration = String::new();
+        while let Some(c) = self.chars.next_if(|c| *c != ']') {
+            duration.push(c);
+        }
+        self.expect("]")?;
+        let range = parse_duration(duration.trim())?;
+        if range <= 0 {
+            return self.error("range must be positive");
+        }
+        self.expect(")")?;
+        Ok(RangeAggregation {
+            op,
+            selector,
+            range,
+        })
+    }
+
+    fn parse_selector(&mut self) -> Result<LogSelector> {
+        self.expect("{")?;
+        let mut matchers = vec![];
+        if !self.eat("}") {
+            loop {
+                matchers.push(self.parse_label_matcher()?);
+                if self.eat("}") {
+                    break;
+                }
+                self.expect(",")?;
+            }
+        }
+        if matchers.is_empty() {
+            return self.error("stream selector must contain at least one label matcher");
+        }
+
+        let mut stages = vec![];
+        loop {
+            let op = if self.eat("|=") {
+                MatchOp::Equal
+            } else if self.eat("|~") {
+                MatchOp::Regex
+            } else if self.eat("!=") {
+                MatchOp::NotEqual
+            } else if self.eat("!~") {
+                MatchOp::NotRegex
+            } else if self.eat("|") {
+                stages.push(self.parse_pipeline_stage()?);
+                continue;
+            } else {
+                break;
+            };
+            let value = self.parse_string()?;
+            stages.push(PipelineStage::LineFilter { op, value });
+        }
+
+        Ok(LogSelector { matchers, stages })
+    }
+
+    fn parse_pipeline_stage(&mut self) -> Result<PipelineStage> {
+        let name = self.parse_identifier()?;
+        match name.as_str() {
+            "json" => Ok(PipelineStage::Parser(LogParser::Json)),
+            "logfmt" => Ok(PipelineStage::Parser(LogParser::Logfmt)),
+            _ if matches!(self.peek(), Some('=' | '!')) => {
+                let op = self.parse_match_op()?;
+                let value = self.parse_string()?;
+                Ok(PipelineStage::LabelFilter(LabelMatcher { name, op, value }))
+            }
+            _ => UnsupportedLogQlSnafu {
+                feature: format!("pipeline stage `{name}`"),
+            }
+            .fail(),
+        }
+    }
+}
+
+fn vector_op(name: &str) -> Option<VectorOp> {
+    match name {
+        "sum" => Some(VectorOp::Sum),
+        "avg" => Some(VectorOp::Avg),
+        "min" => Some(VectorOp::Min),
+        "max" => Some(VectorOp::Max),
+        "count" => Some(VectorOp::Count),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::Error;
+
+    fn matcher(name: &str, op: MatchOp, value: &str) -> LabelMatcher {
+        LabelMatcher {
+            name: name.to_string(),
+            op,
+            value: value.to_string(),
+        }
+    }
+
+    fn target() -> LogQlTarget {
+        LogQlTarget {
+            table: TableName::new("greptime", "public", "loki_logs"),
+            timestamp_column: "greptime_timestamp".to_string(),
+            line_column: "line".to_string(),
+            stream_labels: vec!["app".to_string(), "env".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_parse_log_query() {
+        let expr = parse(
+            r#"{app="api", env!="dev"} |= "error" != `timeout` |~ "code=5.." | json | level=~"err|warn""#,
+        )
+        .unwrap();
+        assert_eq!(
+            expr,
+            LogQlExpr::Log(LogSelector {
+                matchers: vec![
+                    matcher("app", MatchOp::Equal, "api"),
+                    matcher("env", MatchOp::NotEqual, "dev"),
+                ],
+                stages: vec![
+                    PipelineStage::LineFilter {
+                        op: MatchOp::Equal,
+                        value: "error".to_string()
+                    },
+                    PipelineStage::LineFilter {
+                        op: MatchOp::NotEqual,
+                        value: "timeout".to_string()
+                    },
+                    PipelineStage::LineFilter {
+                        op: MatchOp::Regex,
+                        value: "code=5..".to_string()
+                    },
+                    PipelineStage::Parser(LogParser::Json),
+                    PipelineStage::LabelFilter(matcher("level", MatchOp::Regex, "err|warn")),
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_metric_query() {
+        let expr = parse(r#"sum by (level) (count_over_time({app="api"} | logfmt [5m]))"#).unwrap();
+        let LogQlExpr::Metric(MetricExpr::Vector(vector)) = expr else {
+            panic!("expected vector aggregation");
+        };
+        assert_eq!(vector.op, VectorOp::Sum);
+        assert_eq!(vector.grouping, vec!["level".to_string()]);
+        assert_eq!(vector.inner.op, RangeOp::CountOverTime);
+        assert_eq!(vector.inner.range, 300_000);
+        assert_eq!(
+            vector.inner.selector.stages,
+            vec![PipelineStage::Parser(LogParser::Logfmt)]
+        );
+
+        let expr = parse(r#"max(rate({app="api"}[1m30s])) by (env)"#).unwrap();
+        let LogQlExpr::Metric(MetricExpr::Vector(vector)) = expr else {
+            panic!("expected vector aggregation");
+        };
+        assert_eq!(vector.op, VectorOp::Max);
+        assert_eq!(vector.grouping, vec!["env".to_string()]);
+        assert_eq!(vector.inner.op, RangeOp::Rate);
+        assert_eq!(vector.inner.range, 90_000);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for (query, message) in [
+            (r#"{}"#, "at least one label matcher"),
+            (r#"{app="api""#, "expected `,`"),
+            (r#"{app="api} "#, "unterminated string"),
+            (r#"count_over_time({app="api"})"#, "expected `[`"),
+            (r#"{app="api"} extra"#, "unexpected `e`"),
+        ] {
+            let err = parse(query).unwrap_err();
+            assert!(matches!(err, Error::InvalidLogQl { .. }), "{query}");
+            assert!(err.to_string().contains(message), "{query}: {err}");
+        }
+
+        for query in [
+            r#"bytes_over_time({app="api"}[5m])"#,
+            r#"sum without (app) (rate({app="api"}[5m]))"#,
+            r#"{app="api"} | pattern "<ip>""#,
+        ] {
+            assert!(
+                matches!(parse(query), Err(Error::UnsupportedLogQl { .. })),
+                "{query}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("500ms").unwrap(), 500);
+        assert_eq!(parse_duration("1h30m").unwrap(), 5_400_000);
+        assert_eq!(parse_duration("2d").unwrap(), 172_800_000);
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("5").is_err());
+        assert!(parse_duration("5x").is_err());
+    }
+
+    #[test]
+    fn test_log_selector_to_log_query() {
+        let LogQlExpr::Log(selector) =
+            parse(r#"{app="api"} |= "error" | json | level="warn" | env="prod""#).unwrap()
+        else {
+            panic!("expected log query");
+        };
+        let query = selector
+            .to_log_query(&target(), 0, 60_000, 100, true)
+            .unwrap();
+
+        assert_eq!(
+            query.time_filter.start.as_deref(),
+            Some("1970-01-01T00:00:00.000Z")
+        );
+        assert_eq!(
+            query.time_filter.end.as_deref(),
+            Some("1970-01-01T00:01:00.000Z")
+        );
+        assert_eq!(query.limit.fetch, Some(100));
+        assert_eq!(
+            query.columns,
+            vec!["app", "env", "greptime_timestamp", "line"]
+        );
+        // The stream selector, the line filter and the filter on the `env` stream label.
+        let Filters::And(filters) = &query.filters else {
+            panic!("expected conjunction");
+        };
+        assert_eq!(filters.len(), 3);
+        // Only `level` is extracted, `env` is a stream label.
+        assert!(matches!(
+            &query.exprs[..],
+            [
+                LogExpr::Decompose { schema, fields, .. },
+                LogExpr::Filter { .. },
+                LogExpr::Sort { descending: true, .. },
+            ] if schema == "json" && fields == &[("level".to_string(), "string".to_string())]
+        ));
+    }
+
+    #[test]
+    fn test_label_filter_requires_parser() {
+        let LogQlExpr::Log(selector) = parse(r#"{app="api"} | level="warn""#).unwrap() else {
+            panic!("expected log query");
+        };
+        assert!(matches!(
+            selector.to_log_query(&target(), 0, 60_000, 100, true),
+            Err(Error::UnsupportedLogQl { .. })
+        ));
+    }
+
+    #[test]
+    fn test_metric_expr_to_log_query() {
+        let LogQlExpr::Metric(metric) =
+            parse(r#"sum by (level) (rate({app="api"} | json [1m]))"#).unwrap()
+        else {
+            panic!("expected metric query");
+        };
+        let query = metric
+            .to_log_query(&target(), 120_000, 240_000, 60_000)
+            .unwrap();
+
+        assert_eq!(
+            query.time_filter.start.as_deref(),
+            Some("1970-01-01T00:01:00.001Z")
+        );
+        assert_eq!(
+            query.time_filter.end.as_deref(),
+            Some("1970-01-01T00:04:00.001Z")
+        );
+        let [
+            LogExpr::Decompose { fields, .. },
+            LogExpr::RangeAggr {
+                name,
+                by,
+                start,
+                end,
+                step,
+                range,
+                ..
+            },
+            LogExpr::AggrFunc { expr, by: grouping },
+        ] = &query.exprs[..]
+        else {
+            panic!("unexpected exprs: {:?}", query.exprs);
+        };
+        assert_eq!(fields, &[("level".to_string(), "string".to_string())]);
+        assert_eq!(name, "rate");
+        assert_eq!(by.len(), 3);
+        assert_eq!(
+            (*start, *end, *step, *range),
+            (120_000, 240_000, 60_000, 60_000)
+        );
+        assert_eq!(expr[0].name, "sum");
+        assert_eq!(grouping.len(), 2);
+    }
+}
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 use std::any::Any;
+use std::sync::Arc;
 
 use common_error::ext::{ErrorExt, RetryHint, retry_hint_from_io_error};
 use common_error::status_code::StatusCode;
@@ -308,6 +309,72 @@ pub enum Error {
         #[snafu(implicit)]
         location: Location,
     },
+
+    #[snafu(display("Failed to write wal segment, path: {path}"))]
+    WriteSegment {
+        #[snafu(implicit)]
+        location: Location,
+        #[snafu(source)]
+        error: object_store::Error,
+        path: String,
+    },
+
+    #[snafu(display("Failed to read wal segment, path: {path}"))]
+    ReadSegment {
+        #[snafu(implicit)]
+        location: Location,
+        #[snafu(source)]
+        error: object_store::Error,
+        path: String,
+    },
+
+    #[snafu(display("Failed to list wal segments, path: {path}"))]
+    ListSegments {
+        #[snafu(implicit)]
+        location: Location,
+        #[snafu(source)]
+        error: object_store::Error,
+        path: String,
+    },
+
+    #[snafu(display("Failed to delete wal segments, path: {path}"))]
+    DeleteSegments {
+        #[snafu(implicit)]
+        location: Location,
+        #[snafu(source)]
+        error: object_store::Error,
+        path: String,
+    },
+
+    #[snafu(display("Corrupted wal segment, path: {path}, reason: {reason}"))]
+    CorruptedSegment {
+        #[snafu(implicit)]
+        location: Location,
+        path: String,
+        reason: String,
+    },
+
+    #[snafu(display("The wal segment writer is stopped"))]
+    SegmentWriterStopped {
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    // Shared error for each append in the same group commit.
+    #[snafu(display("Failed to commit wal segments"))]
+    GroupCommit {
+        #[snafu(implicit)]
+        location: Location,
+        source: Arc<Error>,
+    },
+
+    #[snafu(display("Conflicting wal writer of namespace {namespace_id}, reason: {reason}"))]
+    WalWriterConflict {
+        #[snafu(implicit)]
+        location: Location,
+        namespace_id: u64,
+        reason: String,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -351,12 +418,21 @@ impl ErrorExt for Error {
             | OrderedBatchProducerStopped { .. }
             | WaitProduceResultReceiver { .. }
             | WaitDumpIndex { .. }
-            | MetaLengthExceededLimit { .. } => StatusCode::Internal,
+            | MetaLengthExceededLimit { .. }
+            | CorruptedSegment { .. }
+            | SegmentWriterStopped { .. } => StatusCode::Internal,
+            GroupCommit { source, .. } => source.status_code(),
+            WalWriterConflict { .. } => StatusCode::RegionNotReady,
 
             // Object store related errors
-            CreateWriter { .. } | WriteIndex { .. } | ReadIndex { .. } | Io { .. } => {
-                StatusCode::StorageUnavailable
-            }
+            CreateWriter { .. }
+            | WriteIndex { .. }
+            | ReadIndex { .. }
+            | Io { .. }
+            | WriteSegment { .. }
+            | ReadSegment { .. }
+            | ListSegments { .. }
+            | DeleteSegments { .. } => StatusCode::StorageUnavailable,
             // Raft engine
             FetchEntry { .. } | RaftEngine { .. } | AddEntryLogBatch { .. } => {
                 StatusCode::StorageUnavailable
@@ -382,10 +458,15 @@ impl ErrorExt for Error {
         use Error::*;
 
         match self {
-            CreateWriter { error, .. } | WriteIndex { error, .. } | ReadIndex { error, .. } => {
-                retry_hint_from_opendal_error(error)
-            }
+            CreateWriter { error, .. }
+            | WriteIndex { error, .. }
+            | ReadIndex { error, .. }
+            | WriteSegment { error, .. }
+            | ReadSegment { error, .. }
+            | ListSegments { error, .. }
+            | DeleteSegments { error, .. } => retry_hint_from_opendal_error(error),
             Io { error, .. } => retry_hint_from_io_error(error),
+            GroupCommit { source, .. } => source.retry_hint(),
             FetchEntry { .. } | RaftEngine { .. } | AddEntryLogBatch { .. } => RetryHint::Retryable,
             ProduceRecord { error, .. } => match error {
                 rskafka::client::producer::Error::Client(error) => {
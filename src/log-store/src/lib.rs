@@ -16,5 +16,6 @@ pub mod error;
 pub mod kafka;
 pub mod metrics;
 pub mod noop;
+pub mod object_store;
 pub mod raft_engine;
 pub mod test_util;
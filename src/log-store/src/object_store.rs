@@ -0,0 +1,37 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A log store that batches entries into segment objects on the object store.
+//!
+//! Each region owns a namespace directory under the wal directory. Appends are
+//! committed in groups, and each group commit writes one segment per region,
+//! named after the first and the last entry id it contains and the epoch of
+//! its writer:
+//!
+//! ```text
+//! {wal_dir}/{region_id}/{start_entry_id}_{end_entry_id}_{epoch}.seg
+//! {wal_dir}/{region_id}/{epoch}.epoch
+//! ```
+//!
+//! A writer claims a new epoch before writing to a region, so segments written by
+//! a stale writer, e.g., the datanode a region is migrated from, are fenced out.
+//! Segments are deleted by the datanode once the region is flushed, so the
+//! metasrv doesn't prune this wal.
+
+pub mod log_store;
+mod namespace;
+mod segment;
+mod writer;
+
+pub use log_store::ObjectStoreLogStore;
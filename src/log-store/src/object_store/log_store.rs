@@ -0,0 +1,573 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashMap;
+use std::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+use async_stream::try_stream;
+use common_telemetry::info;
+use common_wal::config::object_store::ObjectStoreWalConfig;
+use dashmap::DashMap;
+use object_store::util::normalize_dir;
+use object_store::{ErrorKind, ObjectStore};
+use snafu::{OptionExt, ResultExt};
+use store_api::logstore::entry::{Entry, Id as EntryId, NaiveEntry};
+use store_api::logstore::provider::{ObjectStoreProvider, Provider};
+use store_api::logstore::{AppendBatchResponse, LogStore, SendableEntryStream, WalIndex};
+use store_api::storage::RegionId;
+
+use crate::error::{DeleteSegmentsSnafu, Error, InvalidProviderSnafu, ListSegmentsSnafu, Result};
+use crate::metrics;
+use crate::object_store::namespace::SegmentStore;
+use crate::object_store::writer::SegmentWriter;
+
+/// A [LogStore] that persists entries as segment objects on an [ObjectStore].
+///
+/// Entries of a region are only stored under the region's own namespace, so
+/// any datanode that can access the object store is able to replay them.
+pub struct ObjectStoreLogStore {
+    store: SegmentStore,
+    writer: SegmentWriter,
+    read_batch_size: usize,
+    /// The latest entry id of each namespace seen by this log store.
+    latest_entry_ids: DashMap<u64, EntryId>,
+    /// The epoch claimed by this log store of each namespace it writes.
+    epochs: Arc<DashMap<u64, u64>>,
+}
+
+impl ObjectStoreLogStore {
+    pub fn new(object_store: ObjectStore, config: &ObjectStoreWalConfig) -> Self {
+        let store = SegmentStore::new(object_store, normalize_dir(&config.segment_dir));
+        let epochs = Arc::new(DashMap::new());
+        let writer = SegmentWriter::new(
+            store.clone(),
+            epochs.clone(),
+            config.group_commit_interval,
+            config.group_commit_size.as_bytes() as usize,
+        );
+        Self {
+            store,
+            writer,
+            read_batch_size: config.read_batch_size.max(1),
+            latest_entry_ids: DashMap::new(),
+            epochs,
+        }
+    }
+
+    fn update_latest_entry_id(&self, namespace_id: u64, entry_id: EntryId) {
+        self.latest_entry_ids
+            .entry(namespace_id)
+            .and_modify(|latest| *latest = (*latest).max(entry_id))
+            .or_insert(entry_id);
+    }
+}
+
+impl Debug for ObjectStoreLogStore {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ObjectStoreLogStore")
+            .field("dir", &self.store.dir())
+            .field("read_batch_size", &self.read_batch_size)
+            .finish()
+    }
+}
+
+fn object_store_provider(provider: &Provider) -> Result<&ObjectStoreProvider> {
+    provider
+        .as_object_store_provider()
+        .with_context(|| InvalidProviderSnafu {
+            expected: ObjectStoreProvider::type_name(),
+            actual: provider.type_name(),
+        })
+}
+
+#[async_trait::async_trait]
+impl LogStore for ObjectStoreLogStore {
+    type Error = Error;
+
+    async fn stop(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Appends a batch of entries. The batch is committed together with concurrent
+    /// appends, and entries of each region are written into a single segment.
+    async fn append_batch(&self, entries: Vec<Entry>) -> Result<AppendBatchResponse> {
+        metrics::METRIC_OBJECT_STORE_APPEND_BATCH_BYTES_TOTAL.inc_by(
+            entries
+                .iter()
+                .map(|entry| entry.estimated_size())
+                .sum::<usize>() as u64,
+        );
+        let _timer = metrics::METRIC_OBJECT_STORE_APPEND_BATCH_ELAPSED.start_timer();
+
+        if entries.is_empty() {
+            return Ok(AppendBatchResponse::default());
+        }
+
+        let mut last_entry_ids = HashMap::new();
+        for entry in &entries {
+            let _ = object_store_provider(entry.provider())?;
+            last_entry_ids
+                .entry(entry.region_id())
+                .and_modify(|last: &mut EntryId| *last = (*last).max(entry.entry_id()))
+                .or_insert(entry.entry_id());
+        }
+        self.writer.append(entries).await?;
+
+        for (region_id, entry_id) in &last_entry_ids {
+            self.update_latest_entry_id(region_id.as_u64(), *entry_id);
+        }
+        Ok(AppendBatchResponse { last_entry_ids })
+    }
+
+    /// Creates a stream of entries with ids starting from `entry_id`. The end of stream is
+    /// determined by the segments listed when the stream is created.
+    async fn read(
+        &self,
+        provider: &Provider,
+        entry_id: EntryId,
+        _index: Option<WalIndex>,
+    ) -> Result<SendableEntryStream<'static, Entry, Self::Error>> {
+        let namespace_id = object_store_provider(provider)?.id;
+        // The region is opened or caught up, so another writer may have written the
+        // namespace since this log store claimed its epoch. Claims a new one on next append.
+        self.epochs.remove(&namespace_id);
+        let segments = {
+            let _timer = metrics::METRIC_OBJECT_STORE_READ_ELAPSED.start_timer();
+            self.store.list(namespace_id).await?.valid_segments()
+        };
+        if let Some(end) = segments.iter().map(|segment| segment.end).max() {
+            self.update_latest_entry_id(namespace_id, end);
+        }
+        let segments = segments
+            .into_iter()
+            .filter(|segment| segment.end >= entry_id)
+            .collect::<Vec<_>>();
+        info!(
+            "Read logstore, namespace: {}, start: {}, segments: {}",
+            namespace_id,
+            entry_id,
+            segments.len()
+        );
+
+        let store = self.store.clone();
+        let read_batch_size = self.read_batch_size;
+        let provider = provider.clone();
+        let region_id = RegionId::from_u64(namespace_id);
+        let s = try_stream!({
+            // Skips entries already yielded in case segments overlap.
+            let mut next_entry_id = entry_id;
+            for segment in segments {
+                let mut entries = store
+                    .read_segment(&segment.path)
+                    .await?
+                    .into_iter()
+                    .filter(|(id, _)| *id >= next_entry_id)
+                    .map(|(id, data)| {
+                        Entry::Naive(NaiveEntry {
+                            provider: provider.clone(),
+                            region_id,
+                            entry_id: id,
+                            data,
+                        })
+                    })
+                    .peekable();
+                while entries.peek().is_some() {
+                    let batch = entries.by_ref().take(read_batch_size).collect::<Vec<_>>();
+                    // Safety: the batch is not empty.
+                    next_entry_id = batch.last().unwrap().entry_id() + 1;
+                    yield batch;
+                }
+            }
+        });
+        Ok(Box::pin(s))
+    }
+
+    /// Segments are created on demand, so there is nothing to do.
+    async fn create_namespace(&self, ns: &Provider) -> Result<()> {
+        let _ = object_store_provider(ns)?;
+        Ok(())
+    }
+
+    async fn delete_namespace(&self, ns: &Provider) -> Result<()> {
+        let namespace_id = object_store_provider(ns)?.id;
+        let dir = self.store.namespace_dir(namespace_id);
+        self.store
+            .object_store()
+            .delete_with(&dir)
+            .recursive(true)
+            .await
+            .context(DeleteSegmentsSnafu { path: &dir })?;
+        self.latest_entry_ids.remove(&namespace_id);
+        self.epochs.remove(&namespace_id);
+        info!(
+            "Deleted namespace {} of logstore, dir: {}",
+            namespace_id, dir
+        );
+        Ok(())
+    }
+
+    async fn list_namespaces(&self) -> Result<Vec<Provider>> {
+        let dir = self.store.dir();
+        let entries = match self.store.object_store().list(dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(vec![]),
+            Err(e) => {
+                return Err(e).context(ListSegmentsSnafu { path: dir });
+            }
+        };
+        Ok(entries
+            .into_iter()
+            .filter(|entry| entry.metadata().is_dir())
+            .filter_map(|entry| entry.name().trim_end_matches('/').parse::<u64>().ok())
+            .map(Provider::object_store_provider)
+            .collect())
+    }
+
+    fn entry(
+        &self,
+        data: Vec<u8>,
+        entry_id: EntryId,
+        region_id: RegionId,
+        provider: &Provider,
+    ) -> Result<Entry> {
+        debug_assert_eq!(
+            provider.as_object_store_provider().unwrap().id,
+            region_id.as_u64()
+        );
+        Ok(Entry::Naive(NaiveEntry {
+            provider: provider.clone(),
+            region_id,
+            entry_id,
+            data,
+        }))
+    }
+
+    /// Deletes segments whose entries are all obsolete, and epochs no longer fencing
+    /// any segment except the latest one.
+    async fn obsolete(
+        &self,
+        provider: &Provider,
+        _region_id: RegionId,
+        entry_id: EntryId,
+    ) -> Result<()> {
+        let namespace_id = object_store_provider(provider)?.id;
+        let files = self.store.list(namespace_id).await?;
+        let (obsolete, remaining): (Vec<_>, Vec<_>) = files
+            .segments
+            .iter()
+            .partition(|segment| segment.end <= entry_id);
+        if obsolete.is_empty() {
+            return Ok(());
+        }
+
+        let num_segments = obsolete.len();
+        let latest_epoch = files.latest_epoch();
+        let mut paths = obsolete
+            .into_iter()
+            .map(|segment| segment.path.clone())
+            .collect::<Vec<_>>();
+        paths.extend(
+            files
+                .epochs
+                .iter()
+                .filter(|epoch| {
+                    Some(epoch.epoch) != latest_epoch
+                        && remaining.iter().all(|segment| segment.epoch >= epoch.epoch)
+                })
+                .map(|epoch| epoch.path.clone()),
+        );
+        self.store.delete(namespace_id, paths).await?;
+        info!(
+            "Namespace {} obsoleted {} segments, obsoleted entry id: {}",
+            namespace_id, num_segments, entry_id
+        );
+        Ok(())
+    }
+
+    async fn obsolete_all(&self, provider: &Provider, _region_id: RegionId) -> Result<()> {
+        self.delete_namespace(provider).await
+    }
+
+    fn latest_entry_id(&self, provider: &Provider) -> Result<EntryId> {
+        let namespace_id = object_store_provider(provider)?.id;
+        Ok(self
+            .latest_entry_ids
+            .get(&namespace_id)
+            .map(|entry_id| *entry_id)
+            .unwrap_or(0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use common_test_util::temp_dir::{TempDir, create_temp_dir};
+    use futures_util::StreamExt;
+    use object_store::services::Fs;
+
+    use super::*;
+
+    fn new_test_log_store(dir: &TempDir) -> ObjectStoreLogStore {
+        new_test_log_store_with_config(
+            dir,
+            ObjectStoreWalConfig {
+                read_batch_size: 2,
+                ..Default::default()
+            },
+        )
+    }
+
+    fn new_test_log_store_with_config(
+        dir: &TempDir,
+        config: ObjectStoreWalConfig,
+    ) -> ObjectStoreLogStore {
+        let object_store = ObjectStore::new(Fs::default().root(dir.path().to_str().unwrap()))
+            .unwrap()
+            .finish();
+        ObjectStoreLogStore::new(object_store, &config)
+    }
+
+    fn new_entries(
+        logstore: &ObjectStoreLogStore,
+        region_id: RegionId,
+        entry_ids: impl IntoIterator<Item = EntryId>,
+    ) -> Vec<Entry> {
+        let provider = Provider::object_store_provider(region_id.as_u64());
+        entry_ids
+            .into_iter()
+            .map(|id| {
+                logstore
+                    .entry(id.to_string().into_bytes(), id, region_id, &provider)
+                    .unwrap()
+            })
+            .collect()
+    }
+
+    async fn read_entry_ids(
+        logstore: &ObjectStoreLogStore,
+        region_id: RegionId,
+        entry_id: EntryId,
+    ) -> Vec<EntryId> {
+        let provider = Provider::object_store_provider(region_id.as_u64());
+        let mut stream = logstore.read(&provider, entry_id, None).await.unwrap();
+        let mut entry_ids = vec![];
+        while let Some(entries) = stream.next().await {
+            let entries = entries.unwrap();
+            assert!(entries.len() <= 2);
+            for entry in entries {
+                assert_eq!(entry.region_id(), region_id);
+                let id = entry.entry_id();
+                assert_eq!(entry.into_bytes(), id.to_string().into_bytes());
+                entry_ids.push(id);
+            }
+        }
+        entry_ids
+    }
+
+    #[tokio::test]
+    async fn test_append_and_read() {
+        let dir = create_temp_dir("wal");
+        let logstore = new_test_log_store(&dir);
+        let region_1 = RegionId::new(1, 1);
+        let region_2 = RegionId::new(1, 2);
+
+        let mut entries = new_entries(&logstore, region_1, 1..=3);
+        entries.extend(new_entries(&logstore, region_2, 1..=2));
+        let response = logstore.append_batch(entries).await.unwrap();
+        assert_eq!(response.last_entry_ids[&region_1], 3);
+        assert_eq!(response.last_entry_ids[&region_2], 2);
+        let response = logstore
+            .append_batch(new_entries(&logstore, region_1, 4..=6))
+            .await
+            .unwrap();
+        assert_eq!(response.last_entry_ids[&region_1], 6);
+
+        assert_eq!(
+            read_entry_ids(&logstore, region_1, 0).await,
+            (1..=6).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            read_entry_ids(&logstore, region_1, 3).await,
+            (3..=6).collect::<Vec<_>>()
+        );
+        assert!(read_entry_ids(&logstore, region_1, 7).await.is_empty());
+        assert_eq!(read_entry_ids(&logstore, region_2, 0).await, vec![1, 2]);
+        assert!(
+            read_entry_ids(&logstore, RegionId::new(1, 3), 0)
+                .await
+                .is_empty()
+        );
+
+        let mut namespaces = logstore.list_namespaces().await.unwrap();
+        namespaces.sort_unstable_by_key(|ns| ns.as_object_store_provider().unwrap().id);
+        assert_eq!(
+            namespaces,
+            vec![
+                Provider::object_store_provider(region_1.as_u64()),
+                Provider::object_store_provider(region_2.as_u64()),
+            ]
+        );
+
+        let err = logstore
+            .read(&Provider::raft_engine_provider(region_1.as_u64()), 0, None)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::InvalidProvider { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_obsolete() {
+        let dir = create_temp_dir("wal");
+        let logstore = new_test_log_store(&dir);
+        let region_id = RegionId::new(1, 1);
+        let provider = Provider::object_store_provider(region_id.as_u64());
+
+        for range in [1..=3, 4..=6, 7..=9] {
+            logstore
+                .append_batch(new_entries(&logstore, region_id, range))
+                .await
+                .unwrap();
+        }
+
+        // Entry 5 is still required, so the segment [4, 6] must be kept.
+        logstore.obsolete(&provider, region_id, 4).await.unwrap();
+        assert_eq!(
+            read_entry_ids(&logstore, region_id, 0).await,
+            (4..=9).collect::<Vec<_>>()
+        );
+        logstore.obsolete(&provider, region_id, 6).await.unwrap();
+        assert_eq!(
+            read_entry_ids(&logstore, region_id, 0).await,
+            (7..=9).collect::<Vec<_>>()
+        );
+        assert_eq!(logstore.latest_entry_id(&provider).unwrap(), 9);
+
+        logstore.obsolete_all(&provider, region_id).await.unwrap();
+        assert!(read_entry_ids(&logstore, region_id, 0).await.is_empty());
+        assert!(logstore.list_namespaces().await.unwrap().is_empty());
+        assert_eq!(logstore.latest_entry_id(&provider).unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_replay_from_another_logstore() {
+        let dir = create_temp_dir("wal");
+        let region_id = RegionId::new(1, 1);
+        let provider = Provider::object_store_provider(region_id.as_u64());
+        {
+            let logstore = new_test_log_store(&dir);
+            logstore
+                .append_batch(new_entries(&logstore, region_id, 1..=5))
+                .await
+                .unwrap();
+        }
+
+        // Another log store on the same object store, e.g., on the datanode
+        // the region fails over to.
+        let logstore = new_test_log_store(&dir);
+        assert_eq!(logstore.latest_entry_id(&provider).unwrap(), 0);
+        assert_eq!(
+            read_entry_ids(&logstore, region_id, 2).await,
+            (2..=5).collect::<Vec<_>>()
+        );
+        assert_eq!(logstore.latest_entry_id(&provider).unwrap(), 5);
+
+        logstore
+            .append_batch(new_entries(&logstore, region_id, 6..=6))
+            .await
+            .unwrap();
+        assert_eq!(
+            read_entry_ids(&logstore, region_id, 0).await,
+            (1..=6).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_group_commit() {
+        let dir = create_temp_dir("wal");
+        let logstore = new_test_log_store_with_config(
+            &dir,
+            ObjectStoreWalConfig {
+                read_batch_size: 2,
+                group_commit_interval: Duration::from_secs(1),
+                ..Default::default()
+            },
+        );
+        let region_id = RegionId::new(1, 1);
+
+        let (first, second) = futures::join!(
+            logstore.append_batch(new_entries(&logstore, region_id, 1..=2)),
+            logstore.append_batch(new_entries(&logstore, region_id, 3..=4)),
+        );
+        assert_eq!(first.unwrap().last_entry_ids[&region_id], 2);
+        assert_eq!(second.unwrap().last_entry_ids[&region_id], 4);
+
+        // Both appends are committed into one segment.
+        let files = logstore.store.list(region_id.as_u64()).await.unwrap();
+        assert_eq!(files.segments.len(), 1);
+        assert_eq!((files.segments[0].start, files.segments[0].end), (1, 4));
+        assert_eq!(
+            read_entry_ids(&logstore, region_id, 0).await,
+            (1..=4).collect::<Vec<_>>()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fence_stale_writer() {
+        let dir = create_temp_dir("wal");
+        let region_id = RegionId::new(1, 1);
+        let stale = new_test_log_store(&dir);
+        stale
+            .append_batch(new_entries(&stale, region_id, 1..=3))
+            .await
+            .unwrap();
+
+        // The region is migrated to another datanode, which replays and writes the wal.
+        let logstore = new_test_log_store(&dir);
+        assert_eq!(
+            read_entry_ids(&logstore, region_id, 0).await,
+            (1..=3).collect::<Vec<_>>()
+        );
+        logstore
+            .append_batch(new_entries(&logstore, region_id, 4..=5))
+            .await
+            .unwrap();
+
+        // Entries written by the stale writer afterwards are ignored.
+        stale
+            .append_batch(new_entries(&stale, region_id, 4..=6))
+            .await
+            .unwrap();
+        let files = logstore.store.list(region_id.as_u64()).await.unwrap();
+        assert_eq!(files.segments.len(), 3);
+        assert_eq!(files.valid_segments().len(), 2);
+        assert_eq!(
+            read_entry_ids(&new_test_log_store(&dir), region_id, 0).await,
+            (1..=5).collect::<Vec<_>>()
+        );
+
+        // A writer doesn't claim an epoch if the entries it writes already exist.
+        let another = new_test_log_store(&dir);
+        let err = another
+            .append_batch(new_entries(&another, region_id, 5..=5))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::GroupCommit { .. }));
+        assert_eq!(
+            read_entry_ids(&another, region_id, 0).await,
+            (1..=5).collect::<Vec<_>>()
+        );
+    }
+}
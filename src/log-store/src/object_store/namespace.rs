@@ -0,0 +1,266 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use common_telemetry::warn;
+use futures::future::try_join_all;
+use object_store::util::{join_dir, join_path};
+use object_store::{ErrorKind, ObjectStore};
+use snafu::ResultExt;
+use store_api::logstore::entry::Id as EntryId;
+
+use crate::error::{
+    DeleteSegmentsSnafu, Error, ListSegmentsSnafu, ReadSegmentSnafu, Result,
+    WalWriterConflictSnafu, WriteSegmentSnafu,
+};
+use crate::metrics;
+use crate::object_store::segment::{
+    decode_epoch, decode_segment, encode_epoch, encode_segment, epoch_file_name,
+    parse_epoch_file_name, parse_segment_file_name, segment_file_name,
+};
+
+/// Metadata of a segment in the object store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct SegmentMeta {
+    /// The first entry id in the segment.
+    pub(crate) start: EntryId,
+    /// The last entry id in the segment.
+    pub(crate) end: EntryId,
+    /// The epoch of the writer of the segment.
+    pub(crate) epoch: u64,
+    /// Path of the segment.
+    pub(crate) path: String,
+}
+
+/// Metadata of an epoch claimed by a writer of a namespace.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct EpochMeta {
+    pub(crate) epoch: u64,
+    /// The last entry id of the namespace when the epoch is claimed.
+    pub(crate) fence: EntryId,
+    /// Path of the epoch file.
+    pub(crate) path: String,
+}
+
+/// Files of a namespace.
+#[derive(Debug, Default)]
+pub(crate) struct NamespaceFiles {
+    /// Segments ordered by entry ids, including fenced ones.
+    pub(crate) segments: Vec<SegmentMeta>,
+    /// Epochs ordered by epoch.
+    pub(crate) epochs: Vec<EpochMeta>,
+}
+
+impl NamespaceFiles {
+    /// Returns true if the segment contains entries written by a stale writer after a
+    /// newer epoch is claimed. These entries must be ignored.
+    pub(crate) fn is_fenced(&self, segment: &SegmentMeta) -> bool {
+        self.epochs
+            .iter()
+            .any(|epoch| epoch.epoch > segment.epoch && segment.end > epoch.fence)
+    }
+
+    /// Returns segments that aren't fenced, ordered by entry ids.
+    pub(crate) fn valid_segments(&self) -> Vec<SegmentMeta> {
+        self.segments
+            .iter()
+            .filter(|segment| !self.is_fenced(segment))
+            .cloned()
+            .collect()
+    }
+
+    pub(crate) fn latest_epoch(&self) -> Option<u64> {
+        self.epochs.last().map(|epoch| epoch.epoch)
+    }
+}
+
+/// Accesses the segments and epochs of namespaces under a directory of the object store.
+///
+/// Each writer of a namespace claims a new epoch before writing its first segment. The
+/// epoch file records the last entry id written so far, so segments of older epochs
+/// ending after it are written by a stale writer, e.g., the datanode the region was
+/// migrated from, and are ignored on replay.
+#[derive(Clone)]
+pub(crate) struct SegmentStore {
+    object_store: ObjectStore,
+    /// Root directory of all namespaces.
+    dir: String,
+    /// Whether the object store supports creating files exclusively. Two writers can't
+    /// claim the same epoch if it does.
+    exclusive_create: bool,
+}
+
+impl SegmentStore {
+    pub(crate) fn new(object_store: ObjectStore, dir: String) -> Self {
+        let exclusive_create = object_store
+            .info()
+            .full_capability()
+            .write_with_if_not_exists;
+        if !exclusive_create {
+            warn!(
+                "Object store {:?} doesn't support conditional writes, concurrent writers of a wal namespace may claim the same epoch",
+                object_store.info().scheme()
+            );
+        }
+        Self {
+            object_store,
+            dir,
+            exclusive_create,
+        }
+    }
+
+    pub(crate) fn object_store(&self) -> &ObjectStore {
+        &self.object_store
+    }
+
+    pub(crate) fn dir(&self) -> &str {
+        &self.dir
+    }
+
+    pub(crate) fn namespace_dir(&self, namespace_id: u64) -> String {
+        join_dir(&self.dir, &namespace_id.to_string())
+    }
+
+    /// Lists segments and epochs of the namespace.
+    pub(crate) async fn list(&self, namespace_id: u64) -> Result<NamespaceFiles> {
+        let dir = self.namespace_dir(namespace_id);
+        let entries = match self.object_store.list(&dir).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(NamespaceFiles::default()),
+            Err(e) => return Err(e).context(ListSegmentsSnafu { path: dir }),
+        };
+
+        let mut segments = Vec::new();
+        let mut epoch_reads = Vec::new();
+        for entry in entries
+            .into_iter()
+            .filter(|entry| entry.metadata().is_file())
+        {
+            if let Some((start, end, epoch)) = parse_segment_file_name(entry.name()) {
+                segments.push(SegmentMeta {
+                    start,
+                    end,
+                    epoch,
+                    path: entry.path().to_string(),
+                });
+            } else if let Some(epoch) = parse_epoch_file_name(entry.name()) {
+                let path = entry.path().to_string();
+                epoch_reads.push(async move {
+                    let bytes = self
+                        .object_store
+                        .read(&path)
+                        .await
+                        .context(ReadSegmentSnafu { path: &path })?
+                        .to_vec();
+                    let fence = decode_epoch(&path, &bytes)?;
+                    Ok::<_, Error>(EpochMeta { epoch, fence, path })
+                });
+            }
+        }
+        let mut epochs = try_join_all(epoch_reads).await?;
+        segments.sort_unstable_by_key(|segment| (segment.start, segment.end, segment.epoch));
+        epochs.sort_unstable_by_key(|epoch| epoch.epoch);
+        Ok(NamespaceFiles { segments, epochs })
+    }
+
+    /// Claims a new epoch for writing entries starting from `first_entry_id` to the namespace.
+    ///
+    /// Fails if the namespace already contains entries not before `first_entry_id`, which
+    /// means another writer is writing the namespace.
+    pub(crate) async fn claim_epoch(
+        &self,
+        namespace_id: u64,
+        first_entry_id: EntryId,
+    ) -> Result<u64> {
+        let files = self.list(namespace_id).await?;
+        if let Some(segment) = files
+            .valid_segments()
+            .into_iter()
+            .find(|segment| segment.end >= first_entry_id)
+        {
+            return WalWriterConflictSnafu {
+                namespace_id,
+                reason: format!(
+                    "segment {} already contains entries not before {}",
+                    segment.path, first_entry_id
+                ),
+            }
+            .fail();
+        }
+
+        let epoch = files.latest_epoch().map_or(1, |epoch| epoch + 1);
+        let path = join_path(&self.namespace_dir(namespace_id), &epoch_file_name(epoch));
+        let bytes = encode_epoch(first_entry_id.saturating_sub(1));
+        let result = if self.exclusive_create {
+            self.object_store
+                .write_with(&path, bytes)
+                .if_not_exists(true)
+                .await
+        } else {
+            self.object_store.write(&path, bytes).await
+        };
+        match result {
+            Ok(_) => Ok(epoch),
+            Err(e) if e.kind() == ErrorKind::ConditionNotMatch => WalWriterConflictSnafu {
+                namespace_id,
+                reason: format!("epoch {epoch} is claimed by another writer"),
+            }
+            .fail(),
+            Err(e) => Err(e).context(WriteSegmentSnafu { path }),
+        }
+    }
+
+    /// Writes entries into a segment of the namespace. Entries must be ordered by entry ids.
+    pub(crate) async fn write_segment(
+        &self,
+        namespace_id: u64,
+        epoch: u64,
+        entries: &[(EntryId, Vec<u8>)],
+    ) -> Result<()> {
+        // Safety: entries of a segment are never empty.
+        let start = entries.first().unwrap().0;
+        let end = entries.last().unwrap().0;
+        let path = join_path(
+            &self.namespace_dir(namespace_id),
+            &segment_file_name(start, end, epoch),
+        );
+        let bytes = encode_segment(entries.iter().map(|(id, data)| (*id, data.as_slice())));
+        self.object_store
+            .write(&path, bytes)
+            .await
+            .context(WriteSegmentSnafu { path })?;
+        Ok(())
+    }
+
+    /// Reads entries of the segment at `path`.
+    pub(crate) async fn read_segment(&self, path: &str) -> Result<Vec<(EntryId, Vec<u8>)>> {
+        let bytes = self
+            .object_store
+            .read(path)
+            .await
+            .context(ReadSegmentSnafu { path })?
+            .to_vec();
+        metrics::METRIC_OBJECT_STORE_READ_BYTES_TOTAL.inc_by(bytes.len() as u64);
+        decode_segment(path, &bytes)
+    }
+
+    /// Deletes files at `paths` of the namespace.
+    pub(crate) async fn delete(&self, namespace_id: u64, paths: Vec<String>) -> Result<()> {
+        self.object_store
+            .delete_iter(paths)
+            .await
+            .context(DeleteSegmentsSnafu {
+                path: self.namespace_dir(namespace_id),
+            })
+    }
+}
@@ -0,0 +1,171 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use bytes::{Buf, BufMut};
+use snafu::{OptionExt, ensure};
+use store_api::logstore::EntryId;
+
+use crate::error::{CorruptedSegmentSnafu, Result};
+
+/// Magic bytes at the beginning of each segment.
+const SEGMENT_MAGIC: &[u8; 4] = b"GWAL";
+/// Extension of segment files.
+const SEGMENT_EXTENSION: &str = ".seg";
+/// Extension of epoch files.
+const EPOCH_EXTENSION: &str = ".epoch";
+/// Size of the header of an entry: entry id (u64) and data length (u32).
+const ENTRY_HEADER_SIZE: usize = size_of::<u64>() + size_of::<u32>();
+
+/// Returns the file name of the segment containing entries in `[start, end]`, written
+/// by the writer of `epoch`.
+///
+/// Entry ids are zero padded so segments are listed in the order of entry ids.
+pub(crate) fn segment_file_name(start: EntryId, end: EntryId, epoch: u64) -> String {
+    format!("{start:020}_{end:020}_{epoch:020}{SEGMENT_EXTENSION}")
+}
+
+/// Parses the entry id range `[start, end]` and the epoch from a segment file name.
+pub(crate) fn parse_segment_file_name(name: &str) -> Option<(EntryId, EntryId, u64)> {
+    let mut parts = name.strip_suffix(SEGMENT_EXTENSION)?.split('_');
+    let start = parts.next()?.parse().ok()?;
+    let end = parts.next()?.parse().ok()?;
+    let epoch = parts.next()?.parse().ok()?;
+    if parts.next().is_some() {
+        return None;
+    }
+    Some((start, end, epoch))
+}
+
+/// Returns the file name of the epoch file of `epoch`.
+pub(crate) fn epoch_file_name(epoch: u64) -> String {
+    format!("{epoch:020}{EPOCH_EXTENSION}")
+}
+
+/// Parses the epoch from an epoch file name.
+pub(crate) fn parse_epoch_file_name(name: &str) -> Option<u64> {
+    name.strip_suffix(EPOCH_EXTENSION)?.parse().ok()
+}
+
+/// Encodes the fence of an epoch, i.e., the last entry id written before the epoch.
+pub(crate) fn encode_epoch(fence: EntryId) -> Vec<u8> {
+    fence.to_le_bytes().to_vec()
+}
+
+/// Decodes the fence of the epoch file at `path`.
+pub(crate) fn decode_epoch(path: &str, bytes: &[u8]) -> Result<EntryId> {
+    let bytes: [u8; size_of::<EntryId>()] =
+        bytes
+            .try_into()
+            .ok()
+            .with_context(|| CorruptedSegmentSnafu {
+                path,
+                reason: format!("invalid epoch file length {}", bytes.len()),
+            })?;
+    Ok(EntryId::from_le_bytes(bytes))
+}
+
+/// Encodes entries into a segment.
+pub(crate) fn encode_segment<'a>(
+    entries: impl IntoIterator<Item = (EntryId, &'a [u8])>,
+) -> Vec<u8> {
+    let mut buf = SEGMENT_MAGIC.to_vec();
+    for (entry_id, data) in entries {
+        buf.reserve(ENTRY_HEADER_SIZE + data.len());
+        buf.put_u64_le(entry_id);
+        buf.put_u32_le(data.len() as u32);
+        buf.put_slice(data);
+    }
+    buf
+}
+
+/// Decodes entries from a segment at `path`.
+pub(crate) fn decode_segment(path: &str, mut bytes: &[u8]) -> Result<Vec<(EntryId, Vec<u8>)>> {
+    ensure!(
+        bytes.starts_with(SEGMENT_MAGIC),
+        CorruptedSegmentSnafu {
+            path,
+            reason: "invalid magic",
+        }
+    );
+    bytes.advance(SEGMENT_MAGIC.len());
+
+    let mut entries = Vec::new();
+    while bytes.has_remaining() {
+        ensure!(
+            bytes.remaining() >= ENTRY_HEADER_SIZE,
+            CorruptedSegmentSnafu {
+                path,
+                reason: format!("truncated entry header after {} entries", entries.len()),
+            }
+        );
+        let entry_id = bytes.get_u64_le();
+        let len = bytes.get_u32_le() as usize;
+        ensure!(
+            bytes.remaining() >= len,
+            CorruptedSegmentSnafu {
+                path,
+                reason: format!("truncated data of entry {entry_id}"),
+            }
+        );
+        entries.push((entry_id, bytes[..len].to_vec()));
+        bytes.advance(len);
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_segment_file_name() {
+        let name = segment_file_name(3, 42, 1);
+        assert_eq!(
+            name,
+            "00000000000000000003_00000000000000000042_00000000000000000001.seg"
+        );
+        assert_eq!(parse_segment_file_name(&name), Some((3, 42, 1)));
+
+        assert!(segment_file_name(9, 9, 2) < segment_file_name(10, 10, 1));
+        assert_eq!(parse_segment_file_name("3_42_1"), None);
+        assert_eq!(parse_segment_file_name("3_42.seg"), None);
+        assert_eq!(parse_segment_file_name("3-42-1.seg"), None);
+        assert_eq!(parse_segment_file_name("a_42_1.seg"), None);
+        assert_eq!(parse_segment_file_name("3_42_1_1.seg"), None);
+        assert_eq!(parse_segment_file_name(&epoch_file_name(1)), None);
+    }
+
+    #[test]
+    fn test_epoch_file() {
+        let name = epoch_file_name(7);
+        assert_eq!(name, "00000000000000000007.epoch");
+        assert_eq!(parse_epoch_file_name(&name), Some(7));
+        assert_eq!(parse_epoch_file_name(&segment_file_name(1, 2, 3)), None);
+
+        assert_eq!(decode_epoch("test", &encode_epoch(42)).unwrap(), 42);
+        assert!(decode_epoch("test", b"42").is_err());
+    }
+
+    #[test]
+    fn test_encode_decode_segment() {
+        let entries = vec![(1, b"hello".to_vec()), (2, vec![]), (5, b"world".to_vec())];
+        let bytes = encode_segment(entries.iter().map(|(id, data)| (*id, data.as_slice())));
+        let decoded = decode_segment("test", &bytes).unwrap();
+        assert_eq!(decoded, entries);
+
+        assert!(decode_segment("test", b"XWAL").is_err());
+        assert!(decode_segment("test", &bytes[..bytes.len() - 1]).is_err());
+        assert!(decode_segment("test", &bytes[..SEGMENT_MAGIC.len() + 3]).is_err());
+    }
+}
@@ -0,0 +1,186 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+
+use common_telemetry::{debug, warn};
+use dashmap::DashMap;
+use futures::future::join_all;
+use snafu::{OptionExt, ResultExt};
+use store_api::logstore::entry::{Entry, Id as EntryId};
+use tokio::sync::mpsc::{self, Receiver, Sender};
+use tokio::sync::oneshot;
+use tokio::time::{Instant, timeout_at};
+
+use crate::error::{Error, GroupCommitSnafu, Result, SegmentWriterStoppedSnafu};
+use crate::object_store::namespace::SegmentStore;
+
+/// Capacity of the channel of append requests.
+const APPEND_CHANNEL_SIZE: usize = 128;
+
+struct AppendRequest {
+    entries: Vec<Entry>,
+    size: usize,
+    sender: oneshot::Sender<Result<()>>,
+}
+
+/// Commits entries appended concurrently together.
+///
+/// Appends are buffered until `group_commit_size` is reached or `group_commit_interval`
+/// elapses since the first buffered append. Buffered entries of each namespace are then
+/// written into a single segment, and all appends are acknowledged once their segments
+/// are written.
+pub(crate) struct SegmentWriter {
+    sender: Sender<AppendRequest>,
+}
+
+impl SegmentWriter {
+    /// Creates a [SegmentWriter] and spawns its background worker.
+    ///
+    /// `epochs` are the epochs claimed by this writer for each namespace.
+    pub(crate) fn new(
+        store: SegmentStore,
+        epochs: Arc<DashMap<u64, u64>>,
+        group_commit_interval: Duration,
+        group_commit_size: usize,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel(APPEND_CHANNEL_SIZE);
+        let mut worker = GroupCommitWorker {
+            store,
+            epochs,
+            receiver,
+            group_commit_interval,
+            group_commit_size,
+        };
+        tokio::spawn(async move { worker.run().await });
+        Self { sender }
+    }
+
+    /// Appends entries and waits until they are committed.
+    pub(crate) async fn append(&self, entries: Vec<Entry>) -> Result<()> {
+        let size = entries.iter().map(|entry| entry.estimated_size()).sum();
+        let (sender, receiver) = oneshot::channel();
+        let request = AppendRequest {
+            entries,
+            size,
+            sender,
+        };
+        if self.sender.send(request).await.is_err() {
+            warn!("The GroupCommitWorker is already exited");
+            return SegmentWriterStoppedSnafu {}.fail();
+        }
+        receiver.await.ok().context(SegmentWriterStoppedSnafu)?
+    }
+}
+
+struct GroupCommitWorker {
+    store: SegmentStore,
+    epochs: Arc<DashMap<u64, u64>>,
+    receiver: Receiver<AppendRequest>,
+    group_commit_interval: Duration,
+    group_commit_size: usize,
+}
+
+impl GroupCommitWorker {
+    async fn run(&mut self) {
+        while let Some(request) = self.receiver.recv().await {
+            let deadline = Instant::now() + self.group_commit_interval;
+            let mut size = request.size;
+            let mut requests = vec![request];
+            while size < self.group_commit_size {
+                match timeout_at(deadline, self.receiver.recv()).await {
+                    Ok(Some(request)) => {
+                        size += request.size;
+                        requests.push(request);
+                    }
+                    // The interval elapses or the sender is dropped.
+                    Ok(None) | Err(_) => break,
+                }
+            }
+            self.commit(requests).await;
+        }
+        debug!("The sender is dropped, GroupCommitWorker exited");
+    }
+
+    async fn commit(&self, requests: Vec<AppendRequest>) {
+        let mut namespace_entries: BTreeMap<u64, Vec<(EntryId, Vec<u8>)>> = BTreeMap::new();
+        let mut senders = Vec::with_capacity(requests.len());
+        for AppendRequest {
+            entries, sender, ..
+        } in requests
+        {
+            let mut namespace_ids = Vec::new();
+            for entry in entries {
+                let namespace_id = entry.region_id().as_u64();
+                namespace_ids.push(namespace_id);
+                namespace_entries
+                    .entry(namespace_id)
+                    .or_default()
+                    .push((entry.entry_id(), entry.into_bytes()));
+            }
+            namespace_ids.sort_unstable();
+            namespace_ids.dedup();
+            senders.push((namespace_ids, sender));
+        }
+
+        let results = join_all(namespace_entries.into_iter().map(
+            |(namespace_id, mut entries)| async move {
+                entries.sort_by_key(|(entry_id, _)| *entry_id);
+                let result = self
+                    .commit_namespace(namespace_id, &entries)
+                    .await
+                    .map_err(Arc::new);
+                (namespace_id, result)
+            },
+        ))
+        .await
+        .into_iter()
+        .collect::<HashMap<_, _>>();
+
+        for (namespace_ids, sender) in senders {
+            let result = match namespace_ids
+                .iter()
+                .find_map(|namespace_id| results.get(namespace_id)?.as_ref().err())
+            {
+                Some(error) => Err::<(), Arc<Error>>(error.clone()).context(GroupCommitSnafu),
+                None => Ok(()),
+            };
+            if sender.send(result).is_err() {
+                warn!("The receiver of the append request is dropped");
+            }
+        }
+    }
+
+    /// Writes entries of the namespace into a segment, claiming an epoch for the namespace
+    /// if this writer hasn't claimed one.
+    async fn commit_namespace(
+        &self,
+        namespace_id: u64,
+        entries: &[(EntryId, Vec<u8>)],
+    ) -> Result<()> {
+        let claimed = self.epochs.get(&namespace_id).map(|epoch| *epoch);
+        let epoch = match claimed {
+            Some(epoch) => epoch,
+            None => {
+                // Safety: entries of a namespace are never empty.
+                let epoch = self.store.claim_epoch(namespace_id, entries[0].0).await?;
+                self.epochs.insert(namespace_id, epoch);
+                epoch
+            }
+        };
+        self.store.write_segment(namespace_id, epoch, entries).await
+    }
+}
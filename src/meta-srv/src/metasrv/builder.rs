@@ -475,8 +475,10 @@ impl MetasrvBuilder {
 
         let ddl_manager = Arc::new(ddl_manager);
 
-        let region_flush_ticker = if is_remote_wal {
-            let remote_wal_options = options.wal.remote_wal_options().unwrap();
+        // The object store WAL keeps a namespace per region, so regions are flushed and
+        // pruned by the datanode itself without the help of topic statistics.
+        let region_flush_ticker = if let Some(remote_wal_options) = options.wal.remote_wal_options()
+        {
             let (region_flush_trigger, region_flush_ticker) = RegionFlushTrigger::new(
                 table_metadata_manager.clone(),
                 leader_region_registry.clone(),
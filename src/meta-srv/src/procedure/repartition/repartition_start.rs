@@ -19,6 +19,7 @@ use common_procedure::{Context as ProcedureContext, Status};
 use common_telemetry::debug;
 use partition::collider::Collider;
 use partition::expr::PartitionExpr;
+use partition::hash::contains_hash_bucket;
 use partition::subtask::{self, RepartitionSubtask};
 use serde::{Deserialize, Deserializer, Serialize};
 use snafu::{OptionExt, ResultExt, ensure};
@@ -390,7 +391,10 @@ impl RepartitionStart {
             }
         );
 
-        Collider::new(to_exprs).context(error::RepartitionCreateSubtasksSnafu)?;
+        // Hash partition exprs are validated by `HashPartitionRule` instead.
+        if !contains_hash_bucket(to_exprs) {
+            Collider::new(to_exprs).context(error::RepartitionCreateSubtasksSnafu)?;
+        }
 
         let to_expr_indices = (0..to_exprs.len()).collect::<Vec<_>>();
         Ok(vec![RepartitionSubtask {
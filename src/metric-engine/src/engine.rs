@@ -642,6 +642,7 @@ mod test {
                     },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 }),
             )
             .await
@@ -602,6 +602,7 @@ mod tests {
                     rows: api::v1::Rows { schema, rows },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 }),
             )
             .await
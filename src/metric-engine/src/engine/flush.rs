@@ -93,6 +93,7 @@ mod tests {
                     rows: Rows { schema, rows },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 });
                 engine
                     .handle_request(*logi_region_id, request)
@@ -241,6 +241,7 @@ impl MetricEngineInner {
                 primary_key_encoding: PrimaryKeyEncodingProto::Sparse.into(),
             }),
             partition_expr_version: merged_version,
+            replace_existing: false,
         };
 
         Ok((merged_request, total_affected_rows))
@@ -294,6 +295,7 @@ impl MetricEngineInner {
             rows: final_rows,
             hint: None,
             partition_expr_version: merged_version,
+            replace_existing: false,
         };
 
         Ok((merged_request, table_ids.len() as AffectedRows))
@@ -927,6 +929,7 @@ mod tests {
                         },
                         hint: None,
                         partition_expr_version: None,
+                        replace_existing: false,
                     },
                 ),
                 (
@@ -938,6 +941,7 @@ mod tests {
                         },
                         hint: None,
                         partition_expr_version: None,
+                        replace_existing: false,
                     },
                 ),
             ]
@@ -1098,6 +1102,7 @@ mod tests {
             rows: Rows { schema, rows },
             hint: None,
             partition_expr_version: None,
+            replace_existing: false,
         });
 
         // write data
@@ -1173,6 +1178,7 @@ mod tests {
             rows: Rows { schema, rows },
             hint: None,
             partition_expr_version: None,
+            replace_existing: false,
         });
 
         // write data
@@ -1196,6 +1202,7 @@ mod tests {
             rows: Rows { schema, rows },
             hint: None,
             partition_expr_version: None,
+            replace_existing: false,
         });
 
         engine
@@ -1217,6 +1224,7 @@ mod tests {
             rows: Rows { schema, rows },
             hint: None,
             partition_expr_version: None,
+            replace_existing: false,
         });
 
         engine
@@ -1280,6 +1288,7 @@ mod tests {
                     },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 },
             ),
             (
@@ -1291,6 +1300,7 @@ mod tests {
                     },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 },
             ),
             (
@@ -1302,6 +1312,7 @@ mod tests {
                     },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 },
             ),
         ];
@@ -1353,6 +1364,7 @@ mod tests {
                     },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 },
             ),
             (
@@ -1364,6 +1376,7 @@ mod tests {
                     },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 },
             ),
             (
@@ -1375,6 +1388,7 @@ mod tests {
                     },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 },
             ),
         ];
@@ -1413,6 +1427,7 @@ mod tests {
                 },
                 hint: None,
                 partition_expr_version: None,
+                replace_existing: false,
             },
         )];
 
@@ -1447,6 +1462,7 @@ mod tests {
                     },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 },
             ),
             (
@@ -1458,6 +1474,7 @@ mod tests {
                     },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 },
             ),
         ];
@@ -1493,6 +1510,7 @@ mod tests {
                 },
                 hint: None,
                 partition_expr_version: None,
+                replace_existing: false,
             },
         )];
 
@@ -1568,6 +1586,7 @@ mod tests {
                     rows,
                     hint: None,
                     partition_expr_version: Some(1),
+                    replace_existing: false,
                 }),
             )
             .await
@@ -1610,6 +1629,7 @@ mod tests {
                     rows: rows.clone(),
                     hint: None,
                     partition_expr_version: Some(expected_version.wrapping_add(1)),
+                    replace_existing: false,
                 }),
             )
             .await
@@ -1624,6 +1644,7 @@ mod tests {
                     rows: rows.clone(),
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 }),
             )
             .await
@@ -1638,6 +1659,7 @@ mod tests {
                     rows,
                     hint: None,
                     partition_expr_version: Some(expected_version),
+                    replace_existing: false,
                 }),
             )
             .await
@@ -1706,6 +1728,7 @@ mod tests {
                     rows: Rows { schema, rows },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 }),
             )
             .await
@@ -1762,6 +1785,7 @@ mod tests {
                     rows: Rows { schema, rows },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 }),
             )
             .await
@@ -1816,6 +1840,7 @@ mod tests {
                     rows: Rows { schema, rows },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 }),
             )
             .await
@@ -501,6 +501,7 @@ mod test {
                 },
                 hint: None,
                 partition_expr_version: None,
+                replace_existing: false,
             })
         };
         env.metric()
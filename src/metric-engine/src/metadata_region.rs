@@ -588,6 +588,7 @@ impl MetadataRegion {
             rows,
             hint: None,
             partition_expr_version: None,
+            replace_existing: false,
         }
     }
 
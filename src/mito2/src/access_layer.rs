@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
@@ -24,7 +25,7 @@ use object_store::util::{join_dir, with_instrument_layers};
 use object_store::{ATOMIC_WRITE_DIR, ErrorKind, OLD_ATOMIC_WRITE_DIR, ObjectStore};
 use parquet::file::metadata::PageIndexPolicy;
 use smallvec::SmallVec;
-use snafu::ResultExt;
+use snafu::{OptionExt, ResultExt};
 use store_api::metadata::RegionMetadataRef;
 use store_api::region_request::PathType;
 use store_api::sst_entry::StorageSstEntry;
@@ -35,7 +36,8 @@ use crate::cache::write_cache::SstUploadRequest;
 use crate::cache::{CacheManagerRef, SstMetaPreparation, prepare_sst_meta_sync};
 use crate::config::{BloomFilterConfig, FulltextIndexConfig, IndexConfig, InvertedIndexConfig};
 use crate::error::{
-    CleanDirSnafu, DeleteIndexSnafu, DeleteIndexesSnafu, DeleteSstsSnafu, OpenDalSnafu, Result,
+    CleanDirSnafu, DeleteIndexSnafu, DeleteIndexesSnafu, DeleteSstsSnafu, ObjectStoreNotFoundSnafu,
+    OpenDalSnafu, Result,
 };
 use crate::metrics::{COMPACTION_STAGE_ELAPSED, FLUSH_ELAPSED};
 use crate::read::FlatSource;
@@ -150,6 +152,8 @@ pub struct AccessLayer {
     path_type: PathType,
     /// Target object store.
     object_store: ObjectStore,
+    /// Tiered object stores that hold SSTs moved out of the target object store, keyed by name.
+    tiered_stores: HashMap<String, ObjectStore>,
     /// Puffin manager factory for index.
     puffin_manager_factory: PuffinManagerFactory,
     /// Intermediate manager for inverted index.
@@ -177,11 +181,21 @@ impl AccessLayer {
             table_dir: table_dir.into(),
             path_type,
             object_store,
+            tiered_stores: HashMap::new(),
             puffin_manager_factory,
             intermediate_manager,
         }
     }
 
+    /// Attaches tiered object stores to the layer.
+    pub fn with_tiered_stores(
+        mut self,
+        tiered_stores: impl IntoIterator<Item = (String, ObjectStore)>,
+    ) -> AccessLayer {
+        self.tiered_stores.extend(tiered_stores);
+        self
+    }
+
     /// Returns the directory of the table.
     pub fn table_dir(&self) -> &str {
         &self.table_dir
@@ -192,6 +206,25 @@ impl AccessLayer {
         &self.object_store
     }
 
+    /// Returns the object store named `storage`, or the object store of the layer
+    /// if `storage` is `None`.
+    ///
+    /// Returns an error if `storage` doesn't name a tiered store of the layer.
+    pub fn object_store_of(&self, storage: Option<&str>) -> Result<&ObjectStore> {
+        let Some(name) = storage else {
+            return Ok(&self.object_store);
+        };
+        self.tiered_stores
+            .get(name)
+            .context(ObjectStoreNotFoundSnafu { object_store: name })
+    }
+
+    /// Returns all object stores of the layer, starting with the object store
+    /// of the layer and followed by the tiered stores.
+    pub fn object_stores(&self) -> impl Iterator<Item = &ObjectStore> {
+        std::iter::once(&self.object_store).chain(self.tiered_stores.values())
+    }
+
     /// Returns the path type of the layer.
     pub fn path_type(&self) -> PathType {
         self.path_type
@@ -207,12 +240,12 @@ impl AccessLayer {
         &self.intermediate_manager
     }
 
-    /// Build the puffin manager.
-    pub(crate) fn build_puffin_manager(&self) -> SstPuffinManager {
-        let store = self.object_store.clone();
+    /// Build the puffin manager of the object store named `storage`.
+    pub(crate) fn build_puffin_manager(&self, storage: Option<&str>) -> Result<SstPuffinManager> {
+        let store = self.object_store_of(storage)?.clone();
         let path_provider =
             RegionFilePathFactory::new(self.table_dir().to_string(), self.path_type());
-        self.puffin_manager_factory.build(store, path_provider)
+        Ok(self.puffin_manager_factory.build(store, path_provider))
     }
 
     pub(crate) async fn delete_index(
@@ -224,12 +257,12 @@ impl AccessLayer {
             RegionIndexId::new(index_file_id.file_id, index_file_id.version),
             self.path_type,
         );
-        self.object_store
-            .delete(&path)
-            .await
-            .context(DeleteIndexSnafu {
+        // The index file lives in the same store as its SST file.
+        for object_store in self.object_stores() {
+            object_store.delete(&path).await.context(DeleteIndexSnafu {
                 file_id: index_file_id.file_id(),
             })?;
+        }
         Ok(())
     }
 
@@ -254,25 +287,28 @@ impl AccessLayer {
             })
             .collect();
 
-        let mut deleter = self
-            .object_store
-            .deleter()
-            .await
-            .with_context(|_| DeleteSstsSnafu {
-                region_id,
-                file_ids: attempted_files.clone(),
-            })?;
-        deleter
-            .delete_iter(paths.iter().map(String::as_str))
-            .await
-            .with_context(|_| DeleteSstsSnafu {
+        // A file may live in any tiered store. Deleting a missing path is a no-op, so we
+        // delete the files from all stores instead of resolving their storage.
+        for object_store in self.object_stores() {
+            let mut deleter = object_store
+                .deleter()
+                .await
+                .with_context(|_| DeleteSstsSnafu {
+                    region_id,
+                    file_ids: attempted_files.clone(),
+                })?;
+            deleter
+                .delete_iter(paths.iter().map(String::as_str))
+                .await
+                .with_context(|_| DeleteSstsSnafu {
+                    region_id,
+                    file_ids: attempted_files.clone(),
+                })?;
+            deleter.close().await.with_context(|_| DeleteSstsSnafu {
                 region_id,
                 file_ids: attempted_files.clone(),
             })?;
-        deleter.close().await.with_context(|_| DeleteSstsSnafu {
-            region_id,
-            file_ids: attempted_files,
-        })?;
+        }
 
         Ok(())
     }
@@ -294,23 +330,21 @@ impl AccessLayer {
             .map(|index_id| location::index_file_path(&self.table_dir, *index_id, self.path_type))
             .collect();
 
-        let mut deleter = self
-            .object_store
-            .deleter()
-            .await
-            .context(DeleteIndexesSnafu {
+        // Like SSTs, indexes are deleted from all stores.
+        for object_store in self.object_stores() {
+            let mut deleter = object_store.deleter().await.context(DeleteIndexesSnafu {
                 file_ids: file_ids.clone(),
             })?;
-        deleter
-            .delete_iter(paths.iter().map(String::as_str))
-            .await
-            .context(DeleteIndexesSnafu {
+            deleter
+                .delete_iter(paths.iter().map(String::as_str))
+                .await
+                .context(DeleteIndexesSnafu {
+                    file_ids: file_ids.clone(),
+                })?;
+            deleter.close().await.context(DeleteIndexesSnafu {
                 file_ids: file_ids.clone(),
             })?;
-        deleter
-            .close()
-            .await
-            .context(DeleteIndexesSnafu { file_ids })?;
+        }
 
         Ok(())
     }
@@ -321,13 +355,16 @@ impl AccessLayer {
     }
 
     /// Returns a reader builder for specific `file`.
-    pub(crate) fn read_sst(&self, file: FileHandle) -> ParquetReaderBuilder {
-        ParquetReaderBuilder::new(
+    pub(crate) fn read_sst(&self, file: FileHandle) -> Result<ParquetReaderBuilder> {
+        let object_store = self
+            .object_store_of(file.meta_ref().storage.as_deref())?
+            .clone();
+        Ok(ParquetReaderBuilder::new(
             self.table_dir.clone(),
             self.path_type,
             file,
-            self.object_store.clone(),
-        )
+            object_store,
+        ))
     }
 
     /// Writes a SST with specific `file_id` and `metadata` to the layer.
@@ -342,6 +379,7 @@ impl AccessLayer {
         let region_id = request.metadata.region_id;
         let region_metadata = request.metadata.clone();
         let cache_manager = request.cache_manager.clone();
+        let object_store = self.object_store_of(request.storage.as_deref())?.clone();
 
         let sst_info = if let Some(write_cache) = cache_manager.write_cache() {
             // Write to the write cache.
@@ -353,7 +391,7 @@ impl AccessLayer {
                             self.table_dir.clone(),
                             self.path_type,
                         ),
-                        remote_store: self.object_store.clone(),
+                        remote_store: object_store,
                     },
                     write_opts,
                     metrics,
@@ -361,7 +399,7 @@ impl AccessLayer {
                 .await?
         } else {
             // Write cache is disabled.
-            let store = self.object_store.clone();
+            let store = object_store.clone();
             let path_provider = RegionFilePathFactory::new(self.table_dir.clone(), self.path_type);
             let indexer_builder = IndexerBuilderImpl {
                 build_type: request.op_type.into(),
@@ -381,9 +419,9 @@ impl AccessLayer {
             // We disable write cache on file system but we still use atomic write.
             // TODO(yingwen): If we support other non-fs stores without the write cache, then
             // we may have find a way to check whether we need the cleaner.
-            let cleaner = TempFileCleaner::new(region_id, self.object_store.clone());
+            let cleaner = TempFileCleaner::new(region_id, object_store.clone());
             let mut writer = ParquetWriter::new_with_object_store(
-                self.object_store.clone(),
+                object_store,
                 request.metadata,
                 request.index_config,
                 indexer_builder,
@@ -560,7 +598,8 @@ pub struct SstWriteRequest {
     pub metadata: RegionMetadataRef,
     pub source: FlatSource,
     pub cache_manager: CacheManagerRef,
-    #[allow(dead_code)]
+    /// Name of the tiered store to write the SST to. `None` writes the SST to the
+    /// object store of the layer.
     pub storage: Option<String>,
     pub max_sequence: Option<SequenceNumber>,
     pub sst_write_format: FormatType,
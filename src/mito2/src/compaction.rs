@@ -14,11 +14,14 @@
 
 mod buckets;
 pub mod compactor;
+mod downsample;
 pub mod memory_manager;
 pub mod picker;
 mod reader;
+pub mod remote;
 pub mod run;
 mod scheduler;
+mod size_tiered;
 mod task;
 #[cfg(test)]
 mod test_util;
@@ -40,6 +43,7 @@ use snafu::ResultExt;
 use store_api::storage::RegionId;
 
 use crate::error::{GetSchemaMetadataSnafu, Result, TimeoutSnafu};
+use crate::region::options::DownsampleOptions;
 use crate::sst::file::{FileHandle, FileMeta, Level};
 
 /// Finds compaction options and TTL together with a single metadata fetch to reduce RTT.
@@ -134,6 +138,11 @@ pub struct CompactionOutput {
     pub filter_deleted: bool,
     /// Compaction output time range. Only windowed compaction specifies output time range.
     pub output_time_range: Option<TimestampRange>,
+    /// Downsamples rows of the inputs if present.
+    pub downsample: Option<DownsampleOptions>,
+    /// Name of the tiered object store to write the output to. `None` writes the
+    /// output to the object store of the region.
+    pub storage: Option<String>,
 }
 
 /// SerializedCompactionOutput is a serialized version of [CompactionOutput] by replacing [FileHandle] with [FileMeta].
@@ -143,4 +152,8 @@ pub struct SerializedCompactionOutput {
     inputs: Vec<FileMeta>,
     filter_deleted: bool,
     output_time_range: Option<TimestampRange>,
+    #[serde(default)]
+    downsample: Option<DownsampleOptions>,
+    #[serde(default)]
+    storage: Option<String>,
 }
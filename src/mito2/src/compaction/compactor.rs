@@ -47,6 +47,7 @@ use crate::error::{
 };
 use crate::manifest::action::{RegionEdit, RegionMetaAction, RegionMetaActionList};
 use crate::manifest::manager::{RegionManifestManager, RegionManifestOptions};
+use crate::region::opener::get_tiered_stores;
 use crate::region::options::RegionOptions;
 use crate::region::version::VersionRef;
 use crate::region::{ManifestContext, RegionLeaderState, RegionRoleState};
@@ -147,26 +148,6 @@ pub async fn open_compaction_region(
         }
     };
 
-    let access_layer = {
-        let puffin_manager_factory = PuffinManagerFactory::new(
-            &mito_config.index.aux_path,
-            mito_config.index.staging_size.as_bytes(),
-            Some(mito_config.index.write_buffer_size.as_bytes() as _),
-            mito_config.index.staging_ttl,
-        )
-        .await?;
-        let intermediate_manager =
-            IntermediateManager::init_fs(mito_config.index.aux_path.clone()).await?;
-
-        Arc::new(AccessLayer::new(
-            &req.table_dir,
-            req.path_type,
-            object_store.clone(),
-            puffin_manager_factory,
-            intermediate_manager,
-        ))
-    };
-
     let manifest_manager = {
         let region_dir = region_dir_from_table_dir(&req.table_dir, req.region_id, req.path_type);
         let region_manifest_options =
@@ -182,6 +163,36 @@ pub async fn open_compaction_region(
 
     let manifest = manifest_manager.manifest();
     let region_metadata = manifest.metadata.clone();
+
+    let access_layer = {
+        let puffin_manager_factory = PuffinManagerFactory::new(
+            &mito_config.index.aux_path,
+            mito_config.index.staging_size.as_bytes(),
+            Some(mito_config.index.write_buffer_size.as_bytes() as _),
+            mito_config.index.staging_ttl,
+        )
+        .await?;
+        let intermediate_manager =
+            IntermediateManager::init_fs(mito_config.index.aux_path.clone()).await?;
+
+        let tiered_stores = get_tiered_stores(
+            &req.region_options,
+            manifest.files.values(),
+            &object_store_manager,
+        )?;
+
+        Arc::new(
+            AccessLayer::new(
+                &req.table_dir,
+                req.path_type,
+                object_store.clone(),
+                puffin_manager_factory,
+                intermediate_manager,
+            )
+            .with_tiered_stores(tiered_stores),
+        )
+    };
+
     let hook: Option<RegionHookRef> = req.plugins.get();
     let manifest_ctx = Arc::new(ManifestContext::new(
         manifest_manager,
@@ -366,6 +377,22 @@ impl MergeOutput {
     pub fn output_file_size(&self) -> u64 {
         self.files_to_add.iter().map(|f| f.file_size).sum()
     }
+
+    /// Converts the output into the [RegionEdit] to write to the manifest.
+    pub(crate) fn into_region_edit(self) -> RegionEdit {
+        RegionEdit {
+            files_to_add: self.files_to_add,
+            files_to_remove: self.files_to_remove,
+            // Use current timestamp as the edit timestamp.
+            timestamp_ms: Some(chrono::Utc::now().timestamp_millis()),
+            compaction_time_window: self
+                .compaction_time_window
+                .map(|seconds| Duration::from_secs(seconds as u64)),
+            flushed_entry_id: None,
+            flushed_sequence: None,
+            committed_sequence: None,
+        }
+    }
 }
 
 /// Compactor is the trait that defines the compaction logic.
@@ -413,7 +440,15 @@ impl SstMerger for DefaultSstMerger {
         write_opts: WriteOptions,
     ) -> Result<(Vec<FileMeta>, Vec<SstInfo>)> {
         let region_id = compaction_region.region_id;
-        let storage = compaction_region.region_options.storage.clone();
+        // Outputs stay in the tiered store of the inputs if all of them are in the same store.
+        let tiered_storage = output.storage.clone().or_else(|| {
+            let storage = output.inputs.first()?.meta_ref().storage.clone()?;
+            output
+                .inputs
+                .iter()
+                .all(|file| file.meta_ref().storage.as_ref() == Some(&storage))
+                .then_some(storage)
+        });
         let index_options = compaction_region
             .current_version
             .options
@@ -421,6 +456,7 @@ impl SstMerger for DefaultSstMerger {
             .clone();
         let append_mode = compaction_region.current_version.options.append_mode;
         let merge_mode = compaction_region.current_version.options.merge_mode();
+        let merge_functions = compaction_region.current_version.options.merge_functions();
         let flat_format = compaction_region
             .region_options
             .sst_format
@@ -454,6 +490,8 @@ impl SstMerger for DefaultSstMerger {
             filter_deleted: output.filter_deleted,
             time_range: output.output_time_range,
             merge_mode,
+            merge_functions,
+            downsample: output.downsample.clone(),
         };
         let source = builder.build_flat_sst_reader().await?;
 
@@ -467,7 +505,7 @@ impl SstMerger for DefaultSstMerger {
                     metadata: region_metadata.clone(),
                     source,
                     cache_manager: compaction_region.cache_manager.clone(),
-                    storage,
+                    storage: tiered_storage.clone(),
                     max_sequence: max_sequence.map(NonZero::get),
                     sst_write_format: if flat_format {
                         FormatType::Flat
@@ -497,6 +535,21 @@ impl SstMerger for DefaultSstMerger {
             })?,
         };
 
+        // Outputs keep the resolution of the inputs if all of them are downsampled at the same interval.
+        let downsample_interval = match &output.downsample {
+            Some(downsample) => Some(downsample.interval),
+            None => output
+                .inputs
+                .first()
+                .and_then(|file| file.meta_ref().downsample_interval)
+                .filter(|interval| {
+                    output
+                        .inputs
+                        .iter()
+                        .all(|file| file.meta_ref().downsample_interval == Some(*interval))
+                }),
+        };
+
         let output_files = sst_infos
             .iter()
             .map(|sst_info| {
@@ -527,6 +580,8 @@ impl SstMerger for DefaultSstMerger {
                     num_series: sst_info.num_series,
                     primary_key_min,
                     primary_key_max,
+                    downsample_interval,
+                    storage: tiered_storage.clone(),
                 }
             })
             .collect::<Vec<_>>();
@@ -735,18 +790,7 @@ where
         merge_output: MergeOutput,
     ) -> Result<(RegionEdit, ManifestVersion)> {
         // Write region edit to manifest.
-        let edit = RegionEdit {
-            files_to_add: merge_output.files_to_add,
-            files_to_remove: merge_output.files_to_remove,
-            // Use current timestamp as the edit timestamp.
-            timestamp_ms: Some(chrono::Utc::now().timestamp_millis()),
-            compaction_time_window: merge_output
-                .compaction_time_window
-                .map(|seconds| Duration::from_secs(seconds as u64)),
-            flushed_entry_id: None,
-            flushed_sequence: None,
-            committed_sequence: None,
-        };
+        let edit = merge_output.into_region_edit();
 
         let action_list = RegionMetaActionList::with_action(RegionMetaAction::Edit(edit.clone()));
         // TODO: We might leak files if we fail to update manifest. We can add a cleanup task to remove them later.
@@ -887,18 +931,24 @@ mod tests {
                     inputs: vec![new_file_handle(input_meta_0.clone())],
                     filter_deleted: false,
                     output_time_range: None,
+                    downsample: None,
+                    storage: None,
                 },
                 CompactionOutput {
                     output_level: 1,
                     inputs: vec![new_file_handle(input_meta_1.clone())],
                     filter_deleted: false,
                     output_time_range: None,
+                    downsample: None,
+                    storage: None,
                 },
                 CompactionOutput {
                     output_level: 1,
                     inputs: vec![new_file_handle(input_meta_2.clone())],
                     filter_deleted: false,
                     output_time_range: None,
+                    downsample: None,
+                    storage: None,
                 },
             ],
             expired_ssts: vec![],
@@ -944,6 +994,8 @@ mod tests {
                 inputs: vec![new_file_handle(input_meta.clone())],
                 filter_deleted: false,
                 output_time_range: None,
+                downsample: None,
+                storage: None,
             }],
             expired_ssts: vec![],
             time_window_size: 3600,
@@ -982,6 +1034,8 @@ mod tests {
                 inputs: vec![new_file_handle(input_meta.clone())],
                 filter_deleted: false,
                 output_time_range: None,
+                downsample: None,
+                storage: None,
             }],
             expired_ssts: vec![new_file_handle(expired_meta.clone())],
             time_window_size: 3600,
@@ -1045,18 +1099,24 @@ mod tests {
                     inputs: vec![new_file_handle(dummy_file_meta())],
                     filter_deleted: false,
                     output_time_range: None,
+                    downsample: None,
+                    storage: None,
                 },
                 CompactionOutput {
                     output_level: 1,
                     inputs: vec![new_file_handle(dummy_file_meta())],
                     filter_deleted: false,
                     output_time_range: None,
+                    downsample: None,
+                    storage: None,
                 },
                 CompactionOutput {
                     output_level: 1,
                     inputs: vec![new_file_handle(dummy_file_meta())],
                     filter_deleted: false,
                     output_time_range: None,
+                    downsample: None,
+                    storage: None,
                 },
             ],
             expired_ssts: vec![],
@@ -0,0 +1,456 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Downsampling of compaction outputs.
+
+use std::ops::Range;
+use std::time::Duration;
+
+use api::v1::{OpType, SemanticType};
+use async_stream::try_stream;
+use common_time::timestamp::TimeUnit;
+use datatypes::arrow::array::{Array, ArrayRef, AsArray, UInt8Array, UInt64Array};
+use datatypes::arrow::compute::kernels::cmp::neq;
+use datatypes::arrow::compute::kernels::partition::partition;
+use datatypes::arrow::compute::kernels::take::take;
+use datatypes::arrow::compute::{cast, concat_batches, filter_record_batch};
+use datatypes::arrow::datatypes::{DataType as ArrowDataType, Int64Type, SchemaRef};
+use datatypes::arrow::record_batch::RecordBatch;
+use datatypes::data_type::DataType;
+use datatypes::vectors::Helper;
+use futures::TryStreamExt;
+use snafu::ResultExt;
+use store_api::metadata::RegionMetadata;
+
+use crate::error::{ComputeArrowSnafu, ConvertVectorSnafu, NewRecordBatchSnafu, Result};
+use crate::read::BoxedRecordBatchStream;
+use crate::read::dedup::merge_field_value;
+use crate::region::options::{DownsampleOptions, MergeFunction};
+use crate::sst::parquet::flat_format::{
+    op_type_column_index, primary_key_column_index, time_index_column_index,
+};
+use crate::sst::parquet::format::FIXED_POS_COLUMN_NUM;
+
+/// Merges rows of the same series in the same downsample bucket.
+///
+/// Input batches must be in flat format and sorted by primary key, time index and
+/// sequence desc, like the output of the compaction reader. Rows are labelled by
+/// the start of their buckets so merged rows with the same timestamp, like late rows
+/// merged into an already downsampled bucket, are merged again.
+pub(crate) struct FlatDownsampler {
+    /// Bucket size in the unit of the time index.
+    interval: i64,
+    /// Names, indices and merge functions of field columns.
+    fields: Vec<(String, usize, MergeFunction)>,
+    /// Rows of the last bucket, which may continue in the next batch.
+    pending: Option<RecordBatch>,
+}
+
+impl FlatDownsampler {
+    /// Creates a downsampler for batches of `schema` in the flat format of the region.
+    pub(crate) fn new(
+        metadata: &RegionMetadata,
+        schema: &SchemaRef,
+        downsample: &DownsampleOptions,
+    ) -> Self {
+        let field_column_end = schema.fields().len() - FIXED_POS_COLUMN_NUM;
+        let fields = schema.fields()[..field_column_end]
+            .iter()
+            .enumerate()
+            .filter(|(_, field)| {
+                metadata
+                    .column_by_name(field.name())
+                    .is_some_and(|column| column.semantic_type == SemanticType::Field)
+            })
+            .map(|(idx, field)| {
+                (
+                    field.name().clone(),
+                    idx,
+                    downsample.functions.get(field.name()),
+                )
+            })
+            .collect();
+        let unit = metadata
+            .time_index_column()
+            .column_schema
+            .data_type
+            .as_timestamp()
+            .map(|ts| ts.unit())
+            .unwrap_or(TimeUnit::Millisecond);
+
+        Self {
+            interval: interval_in_unit(downsample.interval, unit),
+            fields,
+            pending: None,
+        }
+    }
+
+    /// Pushes a batch and returns merged rows of buckets that are complete.
+    pub(crate) fn push_batch(&mut self, batch: RecordBatch) -> Result<Option<RecordBatch>> {
+        let batch = self.align_time_index(batch)?;
+        if batch.num_rows() == 0 {
+            return Ok(None);
+        }
+        let batch = match self.pending.take() {
+            Some(pending) => {
+                concat_batches(&batch.schema(), &[pending, batch]).context(ComputeArrowSnafu)?
+            }
+            None => batch,
+        };
+
+        let ranges = bucket_ranges(&batch)?;
+        // The last bucket may continue in the next batch.
+        let (last, completed) = ranges.split_last().unwrap();
+        self.pending = Some(batch.slice(last.start, last.len()));
+        if completed.is_empty() {
+            return Ok(None);
+        }
+        self.merge_buckets(&batch, completed).map(Some)
+    }
+
+    /// Returns merged rows of the last bucket.
+    pub(crate) fn finish(&mut self) -> Result<Option<RecordBatch>> {
+        let Some(pending) = self.pending.take() else {
+            return Ok(None);
+        };
+        let range = 0..pending.num_rows();
+        self.merge_buckets(&pending, &[range]).map(Some)
+    }
+
+    /// Removes deleted rows and moves timestamps to the start of their buckets.
+    ///
+    /// The downsampled rows replace all rows in the time window so deletion markers
+    /// have nothing to mask anymore.
+    fn align_time_index(&self, batch: RecordBatch) -> Result<RecordBatch> {
+        let num_columns = batch.num_columns();
+        let op_types = batch
+            .column(op_type_column_index(num_columns))
+            .as_any()
+            .downcast_ref::<UInt8Array>()
+            .unwrap();
+        let puts = neq(op_types, &UInt8Array::new_scalar(OpType::Delete as u8))
+            .context(ComputeArrowSnafu)?;
+        let batch = filter_record_batch(&batch, &puts).context(ComputeArrowSnafu)?;
+
+        let ts_index = time_index_column_index(num_columns);
+        let timestamps = batch.column(ts_index);
+        let values = cast(timestamps, &ArrowDataType::Int64).context(ComputeArrowSnafu)?;
+        let interval = self.interval;
+        let aligned = values
+            .as_primitive::<Int64Type>()
+            .unary::<_, Int64Type>(|v| align_to_bucket_start(v, interval));
+        let aligned = cast(&aligned, timestamps.data_type()).context(ComputeArrowSnafu)?;
+
+        let mut columns = batch.columns().to_vec();
+        columns[ts_index] = aligned;
+        RecordBatch::try_new(batch.schema(), columns).context(NewRecordBatchSnafu)
+    }
+
+    /// Merges rows in each range into one row.
+    ///
+    /// Non-field columns come from the latest row in the range. Fields are merged from the
+    /// latest row to the oldest one so the last non-null value is the latest one. Rows with
+    /// the same timestamp are in sequence desc order, so an already downsampled row is
+    /// considered later than the late rows merged into it.
+    fn merge_buckets(&self, batch: &RecordBatch, ranges: &[Range<usize>]) -> Result<RecordBatch> {
+        let last_indices: UInt64Array = ranges.iter().map(|r| Some(r.end as u64 - 1)).collect();
+        let mut columns = batch
+            .columns()
+            .iter()
+            .map(|column| take(column, &last_indices, None).context(ComputeArrowSnafu))
+            .collect::<Result<Vec<ArrayRef>>>()?;
+
+        for (name, idx, function) in &self.fields {
+            let vector = Helper::try_into_vector(batch.column(*idx)).context(ConvertVectorSnafu)?;
+            let mut builder = vector.data_type().create_mutable_vector(ranges.len());
+            for range in ranges {
+                let mut merged = vector.get(range.end - 1);
+                for i in (range.start..range.end - 1).rev() {
+                    merged = merge_field_value(*function, name, merged, vector.get(i))?;
+                }
+                builder.push_value_ref(&merged.as_value_ref());
+            }
+            columns[*idx] = builder.to_vector().to_arrow_array();
+        }
+
+        RecordBatch::try_new(batch.schema(), columns).context(NewRecordBatchSnafu)
+    }
+}
+
+/// Wraps the sorted `stream` to downsample its rows.
+pub(crate) fn downsample_stream(
+    mut stream: BoxedRecordBatchStream,
+    mut downsampler: FlatDownsampler,
+) -> BoxedRecordBatchStream {
+    Box::pin(try_stream! {
+        while let Some(batch) = stream.try_next().await? {
+            if let Some(batch) = downsampler.push_batch(batch)? {
+                yield batch;
+            }
+        }
+        if let Some(batch) = downsampler.finish()? {
+            yield batch;
+        }
+    })
+}
+
+/// Returns ranges of rows with the same primary key and timestamp.
+fn bucket_ranges(batch: &RecordBatch) -> Result<Vec<Range<usize>>> {
+    let num_columns = batch.num_columns();
+    let columns = [
+        batch.column(primary_key_column_index(num_columns)).clone(),
+        batch.column(time_index_column_index(num_columns)).clone(),
+    ];
+    let partitions = partition(&columns).context(ComputeArrowSnafu)?;
+    Ok(partitions.ranges())
+}
+
+/// Aligns the timestamp `value` to the start of its bucket `[start, start + interval)`.
+fn align_to_bucket_start(value: i64, interval: i64) -> i64 {
+    value.saturating_sub(value.rem_euclid(interval))
+}
+
+/// Converts the `interval` to a positive value in the time `unit`.
+fn interval_in_unit(interval: Duration, unit: TimeUnit) -> i64 {
+    let value = match unit {
+        TimeUnit::Second => interval.as_secs() as u128,
+        TimeUnit::Millisecond => interval.as_millis(),
+        TimeUnit::Microsecond => interval.as_micros(),
+        TimeUnit::Nanosecond => interval.as_nanos(),
+    };
+    value.clamp(1, i64::MAX as u128) as i64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use datatypes::arrow::array::{BinaryDictionaryBuilder, Int64Array, TimestampMillisecondArray};
+    use datatypes::arrow::datatypes::{Field, Schema, TimeUnit as ArrowTimeUnit, UInt32Type};
+
+    use super::*;
+
+    fn test_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("field0", ArrowDataType::Int64, true),
+            Field::new("field1", ArrowDataType::Int64, true),
+            Field::new(
+                "ts",
+                ArrowDataType::Timestamp(ArrowTimeUnit::Millisecond, None),
+                false,
+            ),
+            Field::new(
+                "__primary_key",
+                ArrowDataType::Dictionary(
+                    Box::new(ArrowDataType::UInt32),
+                    Box::new(ArrowDataType::Binary),
+                ),
+                false,
+            ),
+            Field::new("__sequence", ArrowDataType::UInt64, false),
+            Field::new("__op_type", ArrowDataType::UInt8, false),
+        ]))
+    }
+
+    fn new_batch(
+        primary_keys: &[&[u8]],
+        timestamps: &[i64],
+        op_types: &[OpType],
+        fields: &[(Option<i64>, Option<i64>)],
+    ) -> RecordBatch {
+        let mut pk_builder = BinaryDictionaryBuilder::<UInt32Type>::new();
+        for pk in primary_keys {
+            pk_builder.append(pk).unwrap();
+        }
+        let columns: Vec<ArrayRef> = vec![
+            Arc::new(Int64Array::from_iter(fields.iter().map(|f| f.0))),
+            Arc::new(Int64Array::from_iter(fields.iter().map(|f| f.1))),
+            Arc::new(TimestampMillisecondArray::from_iter_values(
+                timestamps.iter().copied(),
+            )),
+            Arc::new(pk_builder.finish()),
+            Arc::new(UInt64Array::from_iter_values(
+                timestamps.iter().map(|ts| *ts as u64),
+            )),
+            Arc::new(UInt8Array::from_iter_values(
+                op_types.iter().map(|op| *op as u8),
+            )),
+        ];
+        RecordBatch::try_new(test_schema(), columns).unwrap()
+    }
+
+    #[test]
+    fn test_align_to_bucket_start() {
+        assert_eq!(0, align_to_bucket_start(0, 60));
+        assert_eq!(0, align_to_bucket_start(59, 60));
+        assert_eq!(60, align_to_bucket_start(60, 60));
+        assert_eq!(60, align_to_bucket_start(61, 60));
+        assert_eq!(-120, align_to_bucket_start(-61, 60));
+        assert_eq!(-60, align_to_bucket_start(-60, 60));
+        assert_eq!(i64::MIN, align_to_bucket_start(i64::MIN, 60));
+    }
+
+    #[test]
+    fn test_interval_in_unit() {
+        let interval = Duration::from_secs(300);
+        assert_eq!(300, interval_in_unit(interval, TimeUnit::Second));
+        assert_eq!(300_000, interval_in_unit(interval, TimeUnit::Millisecond));
+        assert_eq!(
+            300_000_000_000,
+            interval_in_unit(interval, TimeUnit::Nanosecond)
+        );
+        assert_eq!(
+            1,
+            interval_in_unit(Duration::from_millis(10), TimeUnit::Second)
+        );
+    }
+
+    #[test]
+    fn test_flat_downsampler() {
+        let mut downsampler = FlatDownsampler {
+            interval: 60_000,
+            fields: vec![
+                ("field0".to_string(), 0, MergeFunction::Sum),
+                ("field1".to_string(), 1, MergeFunction::LastNonNull),
+            ],
+            pending: None,
+        };
+
+        let mut outputs = vec![];
+        let inputs = [
+            new_batch(
+                &[b"k1", b"k1", b"k1", b"k1"],
+                &[1_000, 30_000, 60_000, 61_000],
+                &[OpType::Put, OpType::Put, OpType::Put, OpType::Put],
+                &[
+                    (Some(1), Some(10)),
+                    (Some(2), Some(20)),
+                    (Some(3), None),
+                    (Some(4), Some(40)),
+                ],
+            ),
+            // The bucket [60s, 120s) continues in this batch.
+            new_batch(
+                &[b"k1", b"k1", b"k2"],
+                &[90_000, 100_000, 1_000],
+                &[OpType::Put, OpType::Delete, OpType::Put],
+                &[(Some(5), None), (Some(100), Some(100)), (Some(6), None)],
+            ),
+        ];
+        for input in inputs {
+            outputs.extend(downsampler.push_batch(input).unwrap());
+        }
+        outputs.extend(downsampler.finish().unwrap());
+        let output = concat_batches(&test_schema(), &outputs).unwrap();
+
+        let expected = new_batch(
+            &[b"k1", b"k1", b"k2"],
+            &[0, 60_000, 0],
+            &[OpType::Put, OpType::Put, OpType::Put],
+            &[(Some(3), Some(20)), (Some(12), Some(40)), (Some(6), None)],
+        );
+        // Non-field columns come from the latest row of each bucket.
+        let sequences: ArrayRef = Arc::new(UInt64Array::from(vec![30_000, 90_000, 1_000]));
+        let mut columns = expected.columns().to_vec();
+        columns[4] = sequences;
+        let expected = RecordBatch::try_new(test_schema(), columns).unwrap();
+        assert_eq!(expected, output);
+    }
+
+    fn with_sequences(batch: RecordBatch, sequences: Vec<u64>) -> RecordBatch {
+        let mut columns = batch.columns().to_vec();
+        columns[4] = Arc::new(UInt64Array::from(sequences));
+        RecordBatch::try_new(test_schema(), columns).unwrap()
+    }
+
+    #[test]
+    fn test_flat_downsampler_late_rows() {
+        let new_downsampler = || FlatDownsampler {
+            interval: 60_000,
+            fields: vec![
+                ("field0".to_string(), 0, MergeFunction::Sum),
+                ("field1".to_string(), 1, MergeFunction::LastNonNull),
+            ],
+            pending: None,
+        };
+
+        // Late rows arrive in buckets that are already downsampled, one of them at the
+        // timestamp of the downsampled row.
+        let mut downsampler = new_downsampler();
+        let late = new_batch(
+            &[b"k1", b"k1", b"k1"],
+            &[0, 30_000, 60_000],
+            &[OpType::Put, OpType::Put, OpType::Put],
+            &[(Some(1), Some(10)), (Some(2), None), (Some(4), Some(40))],
+        );
+        let late = with_sequences(late, vec![101, 102, 103]);
+        let mut outputs = vec![];
+        outputs.extend(downsampler.push_batch(late).unwrap());
+        outputs.extend(downsampler.finish().unwrap());
+        let late = concat_batches(&test_schema(), &outputs).unwrap();
+        assert_eq!(
+            with_sequences(
+                new_batch(
+                    &[b"k1", b"k1"],
+                    &[0, 60_000],
+                    &[OpType::Put, OpType::Put],
+                    &[(Some(3), Some(10)), (Some(4), Some(40))],
+                ),
+                vec![102, 103],
+            ),
+            late
+        );
+
+        // Merges late rows with downsampled rows in the order of the merge reader.
+        let downsampled = with_sequences(
+            new_batch(
+                &[b"k1", b"k1"],
+                &[0, 120_000],
+                &[OpType::Put, OpType::Put],
+                &[(Some(6), None), (Some(7), Some(70))],
+            ),
+            vec![50, 60],
+        );
+        let merged = concat_batches(
+            &test_schema(),
+            &[
+                late.slice(0, 1),
+                downsampled.slice(0, 1),
+                late.slice(1, 1),
+                downsampled.slice(1, 1),
+            ],
+        )
+        .unwrap();
+        let mut downsampler = new_downsampler();
+        let mut outputs = vec![];
+        outputs.extend(downsampler.push_batch(merged).unwrap());
+        outputs.extend(downsampler.finish().unwrap());
+        let output = concat_batches(&test_schema(), &outputs).unwrap();
+
+        let expected = with_sequences(
+            new_batch(
+                &[b"k1", b"k1", b"k1"],
+                &[0, 60_000, 120_000],
+                &[OpType::Put, OpType::Put, OpType::Put],
+                &[
+                    (Some(9), Some(10)),
+                    (Some(4), Some(40)),
+                    (Some(7), Some(70)),
+                ],
+            ),
+            vec![50, 103, 60],
+        );
+        assert_eq!(expected, output);
+    }
+}
@@ -21,11 +21,12 @@ use common_time::{TimeToLive, Timestamp};
 use serde::{Deserialize, Serialize};
 
 use crate::compaction::compactor::CompactionRegion;
+use crate::compaction::size_tiered::SizeTieredPicker;
 use crate::compaction::twcs::TwcsPicker;
 use crate::compaction::window::WindowedCompactionPicker;
 use crate::compaction::{CompactionOutput, SerializedCompactionOutput};
 use crate::error::Result;
-use crate::region::options::CompactionOptions;
+use crate::region::options::{CompactionOptions, TieredStorageOptions};
 use crate::sst::file::{FileHandle, FileMeta};
 use crate::sst::file_purger::FilePurger;
 use crate::sst::version::LevelMeta;
@@ -73,6 +74,8 @@ impl From<&PickerOutput> for SerializedPickerOutput {
                 inputs: output.inputs.iter().map(|s| s.meta_ref().clone()).collect(),
                 filter_deleted: output.filter_deleted,
                 output_time_range: output.output_time_range,
+                downsample: output.downsample.clone(),
+                storage: output.storage.clone(),
             })
             .collect();
         let expired_ssts = input
@@ -107,6 +110,8 @@ impl PickerOutput {
                     .collect(),
                 filter_deleted: output.filter_deleted,
                 output_time_range: output.output_time_range,
+                downsample: output.downsample,
+                storage: output.storage,
             })
             .collect();
 
@@ -130,6 +135,7 @@ pub fn new_picker(
     compact_request_options: &compact_request::Options,
     compaction_options: &CompactionOptions,
     append_mode: bool,
+    tiered_storage: Option<TieredStorageOptions>,
     max_background_tasks: Option<usize>,
     time_range: Option<TimestampRange>,
 ) -> Arc<dyn Picker> {
@@ -149,6 +155,15 @@ pub fn new_picker(
                 append_mode,
                 max_background_tasks,
                 time_range,
+                downsample: twcs_opts.downsample(),
+                tiered_storage,
+            }) as Arc<_>,
+            CompactionOptions::SizeTiered(size_tiered_opts) => Arc::new(SizeTieredPicker {
+                trigger_file_num: size_tiered_opts.trigger_file_num,
+                min_file_size: size_tiered_opts.min_file_size.as_bytes(),
+                max_output_file_size: size_tiered_opts.max_output_file_size.map(|r| r.as_bytes()),
+                append_mode,
+                max_background_tasks,
             }) as Arc<_>,
         }
     }
@@ -172,10 +187,13 @@ pub(super) fn get_expired_ssts(
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use store_api::storage::FileId;
 
     use super::*;
     use crate::compaction::test_util::new_file_handle;
+    use crate::region::options::DownsampleOptions;
     use crate::test_util::new_noop_file_purger;
 
     #[test]
@@ -197,12 +215,20 @@ mod tests {
                     inputs: inputs_file_handle.clone(),
                     filter_deleted: false,
                     output_time_range: None,
+                    downsample: None,
+                    storage: None,
                 },
                 CompactionOutput {
                     output_level: 0,
                     inputs: inputs_file_handle.clone(),
                     filter_deleted: false,
                     output_time_range: None,
+                    downsample: Some(DownsampleOptions {
+                        after: Duration::from_secs(3600),
+                        interval: Duration::from_secs(60),
+                        functions: "value:sum".parse().unwrap(),
+                    }),
+                    storage: Some("archive_s3".to_string()),
                 },
             ],
             expired_ssts: expired_ssts_file_handle.clone(),
@@ -240,6 +266,8 @@ mod tests {
                     });
                 assert_eq!(expected.filter_deleted, actual.filter_deleted);
                 assert_eq!(expected.output_time_range, actual.output_time_range);
+                assert_eq!(expected.downsample, actual.downsample);
+                assert_eq!(expected.storage, actual.storage);
             });
     }
 }
@@ -20,6 +20,7 @@ use common_time::range::TimestampRange;
 use common_time::timestamp::TimeUnit;
 use datafusion_common::ScalarValue;
 use datafusion_expr::Expr;
+use datatypes::arrow::datatypes::SchemaRef;
 use datatypes::extension::json::is_json2_extension_type;
 use datatypes::types::json_type::JsonNativeType;
 use parquet::arrow::parquet_to_arrow_schema;
@@ -29,16 +30,19 @@ use store_api::metadata::RegionMetadataRef;
 
 use crate::access_layer::AccessLayerRef;
 use crate::cache::{CacheManagerRef, CacheStrategy};
+use crate::compaction::downsample::{FlatDownsampler, downsample_stream};
 use crate::error::{
     DataTypeMismatchSnafu, ParquetToArrowSchemaSnafu, Result, TimeRangePredicateOverflowSnafu,
 };
-use crate::read::FlatSource;
+use crate::read::flat_merge::FlatMergeReader;
 use crate::read::flat_projection::FlatProjectionMapper;
 use crate::read::read_columns::ReadColumns;
 use crate::read::scan_region::{PredicateGroup, ScanInput};
 use crate::read::seq_scan::SeqScan;
-use crate::region::options::MergeMode;
+use crate::read::{BoxedRecordBatchStream, FlatSource};
+use crate::region::options::{DownsampleOptions, MergeFunctions, MergeMode};
 use crate::sst::file::FileHandle;
+use crate::sst::parquet::DEFAULT_READ_BATCH_SIZE;
 use crate::sst::parquet::reader::MetadataCacheMetrics;
 
 /// Builders to create [BoxedRecordBatchStream] for compaction.
@@ -51,26 +55,70 @@ pub(crate) struct CompactionSstReaderBuilder<'a> {
     pub(crate) filter_deleted: bool,
     pub(crate) time_range: Option<TimestampRange>,
     pub(crate) merge_mode: MergeMode,
+    pub(crate) merge_functions: MergeFunctions,
+    pub(crate) downsample: Option<DownsampleOptions>,
 }
 
 impl CompactionSstReaderBuilder<'_> {
     /// Build a [FlatSource] that yields Arrow `RecordBatch`s from reading all the input SST files,
     /// for compaction. The schema of the [FlatSource] is unified.
     pub(crate) async fn build_flat_sst_reader(self) -> Result<FlatSource> {
-        let scan_input = self.build_scan_input().await?;
+        let Some(downsample) = self.downsample.clone() else {
+            let (schema, stream) = self.build_flat_stream(self.inputs).await?;
+            return Ok(FlatSource::new_stream(schema, stream));
+        };
+
+        // Rows in downsampled inputs are already merged per bucket. Late rows in the same
+        // bucket must be merged into them instead of replacing them during dedup, so we
+        // downsample other inputs separately and merge rows of both sides again.
+        let (downsampled, others): (Vec<_>, Vec<_>) = self
+            .inputs
+            .iter()
+            .cloned()
+            .partition(|file| file.meta_ref().downsample_interval == Some(downsample.interval));
+        if downsampled.is_empty() || others.is_empty() {
+            let (schema, stream) = self.build_flat_stream(self.inputs).await?;
+            let downsampler = FlatDownsampler::new(&self.metadata, &schema, &downsample);
+            return Ok(FlatSource::new_stream(
+                schema,
+                downsample_stream(stream, downsampler),
+            ));
+        }
 
-        let schema = scan_input.mapper.output_schema();
-        let schema = schema.arrow_schema();
+        let (schema, others) = self.build_flat_stream(&others).await?;
+        let (_, downsampled) = self.build_flat_stream(&downsampled).await?;
+        let others = downsample_stream(
+            others,
+            FlatDownsampler::new(&self.metadata, &schema, &downsample),
+        );
+        let reader = FlatMergeReader::new(
+            schema.clone(),
+            vec![others, downsampled],
+            DEFAULT_READ_BATCH_SIZE,
+            None,
+        )
+        .await?;
+        let downsampler = FlatDownsampler::new(&self.metadata, &schema, &downsample);
+        let stream = downsample_stream(Box::pin(reader.into_stream()), downsampler);
+        Ok(FlatSource::new_stream(schema, stream))
+    }
 
+    /// Builds a sorted and deduplicated stream that reads `inputs`.
+    async fn build_flat_stream(
+        &self,
+        inputs: &[FileHandle],
+    ) -> Result<(SchemaRef, BoxedRecordBatchStream)> {
+        let scan_input = self.build_scan_input(inputs).await?;
+        let schema = scan_input.mapper.output_schema().arrow_schema().clone();
         let stream = SeqScan::new(scan_input)
             .build_flat_reader_for_compaction()
             .await?;
-        Ok(FlatSource::new_stream(schema.clone(), stream))
+        Ok((schema, stream))
     }
 
-    async fn build_scan_input(self) -> Result<ScanInput> {
+    async fn build_scan_input(&self, inputs: &[FileHandle]) -> Result<ScanInput> {
         let schema = self.metadata.schema.arrow_schema();
-        let parquet_metadata = self.collect_parquet_metadata().await?;
+        let parquet_metadata = self.collect_parquet_metadata(inputs).await?;
         let batch_size = crate::batch_size::estimate_batch_size(
             parquet_metadata
                 .iter()
@@ -141,17 +189,18 @@ impl CompactionSstReaderBuilder<'_> {
         let mapper =
             FlatProjectionMapper::new_with_read_columns(&self.metadata, projection, read_columns)?;
 
-        let mut scan_input = ScanInput::new(self.sst_layer, mapper)
-            .with_files(self.inputs.to_vec())
+        let mut scan_input = ScanInput::new(self.sst_layer.clone(), mapper)
+            .with_files(inputs.to_vec())
             .with_compaction(true)
             .with_batch_size(batch_size)
             .with_append_mode(self.append_mode)
             // We use special cache strategy for compaction.
-            .with_cache(CacheStrategy::Compaction(self.cache))
+            .with_cache(CacheStrategy::Compaction(self.cache.clone()))
             .with_filter_deleted(self.filter_deleted)
             // We ignore file not found error during compaction.
             .with_ignore_file_not_found(true)
-            .with_merge_mode(self.merge_mode);
+            .with_merge_mode(self.merge_mode)
+            .with_merge_functions(self.merge_functions.clone());
 
         // This serves as a workaround of https://github.com/GreptimeTeam/greptimedb/issues/3944
         // by converting time ranges into predicate.
@@ -163,16 +212,19 @@ impl CompactionSstReaderBuilder<'_> {
         Ok(scan_input)
     }
 
-    async fn collect_parquet_metadata(&self) -> Result<Vec<Arc<ParquetMetaData>>> {
-        let mut metadata = Vec::with_capacity(self.inputs.len());
+    async fn collect_parquet_metadata(
+        &self,
+        inputs: &[FileHandle],
+    ) -> Result<Vec<Arc<ParquetMetaData>>> {
+        let mut metadata = Vec::with_capacity(inputs.len());
 
-        for file_handle in self.inputs {
+        for file_handle in inputs {
             let file_path =
                 file_handle.file_path(self.sst_layer.table_dir(), self.sst_layer.path_type());
             let file_size = file_handle.meta_ref().file_size;
             let parquet_metadata = match self
                 .sst_layer
-                .read_sst(file_handle.clone())
+                .read_sst(file_handle.clone())?
                 .cache(CacheStrategy::Compaction(self.cache.clone()))
                 .read_parquet_metadata(
                     &file_path,
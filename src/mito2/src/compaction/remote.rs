@@ -0,0 +1,335 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Compaction jobs executed by a remote compaction worker.
+//!
+//! The datanode sends a [RemoteCompactionRequest] to a worker. The worker merges the
+//! input SSTs against the shared object store and replies with the [RegionEdit] of
+//! the compaction. The datanode still owns the region manifest, so it commits the
+//! edit itself by [commit_remote_compaction].
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use common_base::Plugins;
+use common_base::cancellation::CancellationHandle;
+use common_telemetry::info;
+use common_time::TimeToLive;
+use either::Either;
+use object_store::manager::ObjectStoreManagerRef;
+use serde::{Deserialize, Serialize};
+use snafu::ensure;
+use store_api::region_request::PathType;
+use store_api::storage::RegionId;
+
+use crate::compaction::compactor::{
+    CompactionRegion, Compactor, DefaultCompactor, MergeOutput, OpenCompactionRegionRequest,
+    open_compaction_region,
+};
+use crate::compaction::picker::{PickerOutput, SerializedPickerOutput};
+use crate::config::MitoConfig;
+use crate::error::{RemoteCompactionSnafu, Result};
+use crate::manifest::action::{RegionEdit, RegionMetaAction, RegionMetaActionList};
+use crate::region::options::RegionOptions;
+use crate::schedule::remote_job_scheduler::CompactionJob;
+use crate::sst::file::UncommittedSsts;
+use crate::sst::file_purger::{FilePurgerRef, NoopFilePurger};
+
+/// The serializable form of a [CompactionJob].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteCompactionRequest {
+    pub region_id: RegionId,
+    pub table_dir: String,
+    pub path_type: PathType,
+    pub region_options: RegionOptions,
+    pub picker_output: SerializedPickerOutput,
+    pub ttl: TimeToLive,
+    pub max_parallelism: usize,
+}
+
+impl From<&CompactionJob> for RemoteCompactionRequest {
+    fn from(job: &CompactionJob) -> Self {
+        let compaction_region = &job.compaction_region;
+        Self {
+            region_id: compaction_region.region_id,
+            table_dir: compaction_region.access_layer.table_dir().to_string(),
+            path_type: compaction_region.access_layer.path_type(),
+            region_options: compaction_region.region_options.clone(),
+            picker_output: SerializedPickerOutput::from(&job.picker_output),
+            ttl: job.ttl,
+            max_parallelism: compaction_region.max_parallelism,
+        }
+    }
+}
+
+/// Executes the compaction described by `request` on a compaction worker.
+///
+/// Only the output SSTs are written, the returned [RegionEdit] is not committed
+/// to the region manifest.
+pub async fn execute_remote_compaction(
+    request: RemoteCompactionRequest,
+    mito_config: &MitoConfig,
+    object_store_manager: ObjectStoreManagerRef,
+    plugins: Plugins,
+) -> Result<RegionEdit> {
+    let open_request = OpenCompactionRegionRequest {
+        region_id: request.region_id,
+        table_dir: request.table_dir,
+        path_type: request.path_type,
+        region_options: request.region_options,
+        max_parallelism: request.max_parallelism,
+        plugins,
+    };
+    let compaction_region = open_compaction_region(
+        &open_request,
+        mito_config,
+        object_store_manager,
+        Either::Left(request.ttl),
+    )
+    .await?;
+
+    let file_purger: FilePurgerRef = match compaction_region.file_purger() {
+        Some(file_purger) => file_purger,
+        None => Arc::new(NoopFilePurger),
+    };
+    let picker_output = PickerOutput::from_serialized(request.picker_output, file_purger);
+    let compactor =
+        DefaultCompactor::new_with_cancel_handle(Arc::new(CancellationHandle::default()));
+    let merge_output = compactor
+        .merge_ssts(&compaction_region, picker_output)
+        .await;
+    compaction_region.stop_purger_scheduler().await?;
+
+    Ok(merge_output?.into_region_edit())
+}
+
+/// Commits the [RegionEdit] returned by a compaction worker to the manifest of
+/// the region being compacted.
+///
+/// The edit is validated against the `picker_output` of the job before committing.
+/// The new SSTs of the edit are removed if the manifest update is not persisted.
+pub async fn commit_remote_compaction(
+    compaction_region: &CompactionRegion,
+    picker_output: &PickerOutput,
+    edit: RegionEdit,
+) -> Result<RegionEdit> {
+    validate_remote_edit(compaction_region.region_id, picker_output, &edit)?;
+
+    let merge_output = MergeOutput {
+        files_to_add: edit.files_to_add.clone(),
+        files_to_remove: edit.files_to_remove.clone(),
+        compaction_time_window: edit
+            .compaction_time_window
+            .map(|window| window.as_secs() as i64),
+        sst_infos: vec![],
+    };
+    compaction_region.invoke_sst_hook(&merge_output).await;
+
+    // The worker has uploaded the new SSTs, so they must be removed if the edit is never
+    // committed.
+    let uncommitted = UncommittedSsts::new(
+        compaction_region.region_id,
+        compaction_region.access_layer.clone(),
+        Some(compaction_region.cache_manager.clone()),
+    );
+    uncommitted.track_files(&edit.files_to_add);
+
+    let action_list = RegionMetaActionList::with_action(RegionMetaAction::Edit(edit.clone()));
+    if let Err(e) = compaction_region
+        .manifest_ctx
+        .update_manifest_for_compaction(action_list)
+        .await
+    {
+        if e.may_have_persisted_manifest_update() {
+            uncommitted.disarm_cleanup();
+        } else {
+            info!(
+                "Cleaning uncommitted SSTs because the manifest update was not persisted, region: {}, job: remote compaction, error: {:?}",
+                compaction_region.region_id, e
+            );
+            uncommitted.cleanup().await;
+        }
+        return Err(e);
+    }
+    uncommitted.disarm_cleanup();
+
+    Ok(edit)
+}
+
+/// Ensures the [RegionEdit] returned by a compaction worker only removes the input
+/// files of the job and only adds new files of the region.
+fn validate_remote_edit(
+    region_id: RegionId,
+    picker_output: &PickerOutput,
+    edit: &RegionEdit,
+) -> Result<()> {
+    let inputs = picker_output
+        .outputs
+        .iter()
+        .flat_map(|output| output.inputs.iter())
+        .chain(picker_output.expired_ssts.iter())
+        .map(|file| file.file_id().file_id())
+        .collect::<HashSet<_>>();
+
+    for file in &edit.files_to_remove {
+        ensure!(
+            file.region_id == region_id && inputs.contains(&file.file_id),
+            RemoteCompactionSnafu {
+                region_id,
+                job_id: None,
+                reason: format!(
+                    "edit removes file {} of region {} which is not an input of the job",
+                    file.file_id, file.region_id
+                ),
+            }
+        );
+    }
+    for file in &edit.files_to_add {
+        ensure!(
+            file.region_id == region_id && !inputs.contains(&file.file_id),
+            RemoteCompactionSnafu {
+                region_id,
+                job_id: None,
+                reason: format!(
+                    "edit adds file {} of region {} which is not a new file of the region",
+                    file.file_id, file.region_id
+                ),
+            }
+        );
+    }
+    ensure!(
+        edit.flushed_entry_id.is_none()
+            && edit.flushed_sequence.is_none()
+            && edit.committed_sequence.is_none(),
+        RemoteCompactionSnafu {
+            region_id,
+            job_id: None,
+            reason: "edit of a compaction must not change flushed or committed positions",
+        }
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use store_api::storage::FileId;
+
+    use super::*;
+    use crate::compaction::CompactionOutput;
+    use crate::sst::file::{FileHandle, FileMeta};
+
+    #[test]
+    fn test_remote_compaction_request_serde() {
+        let region_id = RegionId::new(1024, 1);
+        let request = RemoteCompactionRequest {
+            region_id,
+            table_dir: "data/greptime/public/1024/".to_string(),
+            path_type: PathType::Bare,
+            region_options: RegionOptions::default(),
+            picker_output: SerializedPickerOutput {
+                outputs: vec![],
+                expired_ssts: vec![FileMeta {
+                    region_id,
+                    ..Default::default()
+                }],
+                time_window_size: 3600,
+                max_file_size: None,
+            },
+            ttl: TimeToLive::Duration(Duration::from_secs(86400)),
+            max_parallelism: 2,
+        };
+
+        let json = serde_json::to_string(&request).unwrap();
+        let decoded: RemoteCompactionRequest = serde_json::from_str(&json).unwrap();
+        assert_eq!(region_id, decoded.region_id);
+        assert_eq!(request.table_dir, decoded.table_dir);
+        assert_eq!(PathType::Bare, decoded.path_type);
+        assert_eq!(request.region_options, decoded.region_options);
+        assert_eq!(1, decoded.picker_output.expired_ssts.len());
+        assert_eq!(3600, decoded.picker_output.time_window_size);
+        assert_eq!(request.ttl, decoded.ttl);
+        assert_eq!(2, decoded.max_parallelism);
+    }
+
+    #[test]
+    fn test_validate_remote_edit() {
+        let region_id = RegionId::new(1024, 1);
+        let file_meta = |region_id, file_id| FileMeta {
+            region_id,
+            file_id,
+            ..Default::default()
+        };
+        let input = FileId::random();
+        let expired = FileId::random();
+        let picker_output = PickerOutput {
+            outputs: vec![CompactionOutput {
+                output_level: 1,
+                inputs: vec![FileHandle::new(
+                    file_meta(region_id, input),
+                    Arc::new(NoopFilePurger),
+                )],
+                filter_deleted: false,
+                output_time_range: None,
+                downsample: None,
+                storage: None,
+            }],
+            expired_ssts: vec![FileHandle::new(
+                file_meta(region_id, expired),
+                Arc::new(NoopFilePurger),
+            )],
+            time_window_size: 3600,
+            max_file_size: None,
+        };
+        let edit = |files_to_add, files_to_remove| RegionEdit {
+            files_to_add,
+            files_to_remove,
+            timestamp_ms: None,
+            compaction_time_window: None,
+            flushed_entry_id: None,
+            flushed_sequence: None,
+            committed_sequence: None,
+        };
+
+        let valid = edit(
+            vec![file_meta(region_id, FileId::random())],
+            vec![file_meta(region_id, input), file_meta(region_id, expired)],
+        );
+        validate_remote_edit(region_id, &picker_output, &valid).unwrap();
+
+        let invalid_edits = [
+            // Removes a file that is not an input.
+            edit(vec![], vec![file_meta(region_id, FileId::random())]),
+            // Removes an input of another region.
+            edit(vec![], vec![file_meta(RegionId::new(1024, 2), input)]),
+            // Adds a file of another region.
+            edit(
+                vec![file_meta(RegionId::new(1024, 2), FileId::random())],
+                vec![],
+            ),
+            // Adds an input file again.
+            edit(vec![file_meta(region_id, input)], vec![]),
+            // Changes the flushed entry id.
+            RegionEdit {
+                flushed_entry_id: Some(100),
+                ..edit(vec![], vec![])
+            },
+        ];
+        for invalid in invalid_edits {
+            validate_remote_edit(region_id, &picker_output, &invalid).unwrap_err();
+        }
+    }
+}
@@ -16,7 +16,7 @@
 //! along with the best way to merge these items to satisfy the desired run count.
 
 use std::cmp::Ordering;
-use std::collections::BinaryHeap;
+use std::collections::{BinaryHeap, HashSet};
 
 use bytes::{Buf, Bytes};
 use common_base::BitVec;
@@ -198,7 +198,6 @@ impl FileGroup {
         self.files.len()
     }
 
-    #[cfg(test)]
     pub(crate) fn files(&self) -> &[FileHandle] {
         &self.files[..]
     }
@@ -252,6 +251,23 @@ impl Item for FileGroup {
     }
 }
 
+/// Returns whether any file group of `candidate_files` that is not part of `inputs` overlaps
+/// `inputs`. Such a group keeps rows a deletion marker in `inputs` masks, so the compaction
+/// must not filter deleted rows out.
+///
+/// Overlapping is inclusive on both boundaries here: two files that only share a boundary
+/// timestamp can still hold the same row.
+pub(crate) fn overlaps_files_left_behind(
+    inputs: &[FileGroup],
+    candidate_files: &[FileGroup],
+) -> bool {
+    let picked: HashSet<_> = inputs.iter().flat_map(|fg| fg.file_ids()).collect();
+    candidate_files
+        .iter()
+        .filter(|fg| !fg.file_ids().iter().any(|id| picked.contains(id)))
+        .any(|fg| inputs.iter().any(|input| input.overlap_inclusive(fg)))
+}
+
 /// A set of files with non-overlapping time ranges.
 #[derive(Debug, Clone)]
 pub struct SortedRun<T: Item> {
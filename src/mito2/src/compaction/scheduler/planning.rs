@@ -207,6 +207,7 @@ impl CompactionScheduler {
             &options,
             &dynamic_compaction_opts,
             request.current_version.options.append_mode,
+            request.current_version.options.tiered_storage.clone(),
             Some(max_background_compactions),
             time_range,
         );
@@ -133,7 +133,10 @@ fn use_remote_compaction(finished: &mut CompactionPickFinished, fallback_to_loca
         panic!("expected prepared compaction");
     };
     let crate::region::options::CompactionOptions::Twcs(options) =
-        &mut prepared.compaction_region.region_options.compaction;
+        &mut prepared.compaction_region.region_options.compaction
+    else {
+        unreachable!()
+    };
     options.remote_compaction = true;
     options.fallback_to_local = fallback_to_local;
 }
@@ -148,6 +151,8 @@ fn picker_output_with_files(
             inputs: output_files,
             filter_deleted: false,
             output_time_range: None,
+            downsample: None,
+            storage: None,
         }],
         expired_ssts,
         ..Default::default()
@@ -227,6 +232,7 @@ async fn test_find_compaction_options_db_level() {
         crate::region::options::CompactionOptions::Twcs(t) => {
             assert_eq!(t.time_window_seconds(), Some(2 * 3600));
         }
+        _ => unreachable!(),
     }
 }
 
@@ -291,7 +297,10 @@ async fn test_find_compaction_options_priority() {
         let mut region_opts = version_control.current().version.options.clone();
         region_opts.compaction_override = override_set;
         if let Some(window) = table_window {
-            let crate::region::options::CompactionOptions::Twcs(twcs) = &mut region_opts.compaction;
+            let crate::region::options::CompactionOptions::Twcs(twcs) = &mut region_opts.compaction
+            else {
+                unreachable!()
+            };
             twcs.time_window = Some(window);
         }
 
@@ -302,6 +311,7 @@ async fn test_find_compaction_options_priority() {
             crate::region::options::CompactionOptions::Twcs(t) => {
                 assert_eq!(t.time_window_seconds(), expected_window, "{case_name}");
             }
+            _ => unreachable!(),
         }
     }
 }
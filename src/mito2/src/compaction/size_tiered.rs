@@ -0,0 +1,425 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::num::NonZeroU64;
+
+use common_base::readable_size::ReadableSize;
+use common_telemetry::{debug, info};
+use common_time::Timestamp;
+use snafu::ResultExt;
+use store_api::storage::RegionId;
+
+use crate::compaction::CompactionOutput;
+use crate::compaction::buckets::infer_time_bucket;
+use crate::compaction::compactor::CompactionRegion;
+use crate::compaction::picker::{Picker, PickerOutput, get_expired_ssts};
+use crate::compaction::run::{FileGroup, Item, find_sorted_runs, overlaps_files_left_behind};
+use crate::error::{JoinSnafu, Result};
+use crate::sst::file::{FileHandle, Level};
+use crate::sst::version::LevelMeta;
+
+const LEVEL_COMPACTED: Level = 1;
+
+/// Default value for max compaction input file num.
+const DEFAULT_MAX_INPUT_FILE_NUM: usize = 32;
+
+/// `SizeTieredPicker` picks files of similar sizes as compaction candidates, regardless of
+/// their time ranges.
+///
+/// Unlike `TwcsPicker`, it doesn't assign files to
+/// time windows, so files written out of order don't spread a compaction over many windows.
+#[derive(Clone, Debug)]
+pub struct SizeTieredPicker {
+    /// Minimum file num in a tier to trigger a compaction.
+    pub trigger_file_num: usize,
+    /// Files smaller than this size are always in the lowest tier.
+    pub min_file_size: u64,
+    /// Max allowed compaction output file size.
+    pub max_output_file_size: Option<u64>,
+    /// Whether the target region is in append mode.
+    pub append_mode: bool,
+    /// Max background compaction tasks.
+    pub max_background_tasks: Option<usize>,
+}
+
+impl SizeTieredPicker {
+    /// Builds compaction outputs from `files`. Each tier that has enough file groups
+    /// becomes one output, starting from the tier of the smallest files.
+    fn build_outputs<'a>(
+        &self,
+        region_id: RegionId,
+        files: impl Iterator<Item = &'a FileHandle>,
+    ) -> Vec<CompactionOutput> {
+        let all_groups = group_files(files);
+        let mut candidates = all_groups
+            .iter()
+            .filter(|fg| fg.files().iter().all(|file| !file.compacting()))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        // Filter out large files in append mode - they won't benefit from compaction
+        if self.append_mode
+            && let Some(max_size) = self.max_output_file_size
+        {
+            candidates.retain(|fg| fg.size() <= max_size as usize);
+        }
+
+        let mut outputs = vec![];
+        for tier in self.assign_to_tiers(candidates) {
+            if tier.len() < self.trigger_file_num {
+                continue;
+            }
+            let inputs = limit_input_files(tier);
+            if inputs.len() < 2 {
+                continue;
+            }
+
+            // Files out of the tier may hold rows masked by deletion markers in the inputs.
+            let filter_deleted =
+                !self.append_mode && !overlaps_files_left_behind(&inputs, &all_groups);
+            let mut input_files = inputs.clone();
+            let found_runs = find_sorted_runs(&mut input_files).len();
+            log_pick_result(
+                region_id,
+                found_runs,
+                self.max_output_file_size,
+                filter_deleted,
+                &inputs,
+            );
+            outputs.push(CompactionOutput {
+                output_level: LEVEL_COMPACTED,
+                inputs: inputs.into_iter().flat_map(|fg| fg.into_files()).collect(),
+                filter_deleted,
+                output_time_range: None,
+                downsample: None,
+                storage: None,
+            });
+
+            if let Some(max_background_tasks) = self.max_background_tasks
+                && outputs.len() >= max_background_tasks
+            {
+                debug!(
+                    "Region ({:?}) compaction task size larger than max background tasks({}), remaining tasks discarded",
+                    region_id, max_background_tasks
+                );
+                break;
+            }
+        }
+        outputs
+    }
+
+    /// Assigns file groups to tiers by size, from the smallest to the largest.
+    ///
+    /// A file group joins the current tier if it is smaller than `min_file_size` or at most
+    /// 1.5 times the average size of the tier. Otherwise it starts a new tier.
+    fn assign_to_tiers(&self, mut groups: Vec<FileGroup>) -> Vec<Vec<FileGroup>> {
+        groups.sort_unstable_by_key(|fg| fg.size());
+
+        let mut tiers: Vec<Vec<FileGroup>> = vec![];
+        let mut tier_size = 0;
+        for group in groups {
+            let size = group.size();
+            match tiers.last_mut() {
+                Some(tier)
+                    if size < self.min_file_size as usize
+                        || size * 2 * tier.len() <= tier_size * 3 =>
+                {
+                    tier_size += size;
+                    tier.push(group);
+                }
+                _ => {
+                    tier_size = size;
+                    tiers.push(vec![group]);
+                }
+            }
+        }
+        tiers
+    }
+}
+
+/// Groups files by their sequences. Files with the same sequence are considered
+/// created from the same compaction task.
+fn group_files<'a>(files: impl Iterator<Item = &'a FileHandle>) -> Vec<FileGroup> {
+    let mut groups: HashMap<Option<NonZeroU64>, FileGroup> = HashMap::new();
+    for file in files {
+        match groups.entry(file.meta_ref().sequence) {
+            Entry::Occupied(mut o) => {
+                o.get_mut().add_file(file.clone());
+            }
+            Entry::Vacant(v) => {
+                v.insert(FileGroup::new_with_file(file.clone()));
+            }
+        }
+    }
+    groups.into_values().collect()
+}
+
+/// Takes the smallest file groups of a tier until the number of input files reaches
+/// [DEFAULT_MAX_INPUT_FILE_NUM]. `tier` must be sorted by size.
+fn limit_input_files(tier: Vec<FileGroup>) -> Vec<FileGroup> {
+    let mut num_picked_files = 0;
+    tier.into_iter()
+        .take_while(|fg| {
+            let current_group_file_num = fg.num_files();
+            if current_group_file_num + num_picked_files <= DEFAULT_MAX_INPUT_FILE_NUM {
+                num_picked_files += current_group_file_num;
+                true
+            } else {
+                false
+            }
+        })
+        .collect()
+}
+
+fn log_pick_result(
+    region_id: RegionId,
+    found_runs: usize,
+    max_output_file_size: Option<u64>,
+    filter_deleted: bool,
+    inputs: &[FileGroup],
+) {
+    let input_file_str: Vec<String> = inputs
+        .iter()
+        .map(|f| {
+            format!(
+                "FileGroup{{id: {:?}, size: {}, num rows: {} }}",
+                f.file_ids(),
+                ReadableSize(f.size() as u64),
+                f.num_rows()
+            )
+        })
+        .collect();
+    let max_output_file_size = max_output_file_size.map(|size| ReadableSize(size).to_string());
+    info!(
+        "Region ({:?}) size-tiered compaction pick result: found runs: {}, \
+            max output file size: {:?}, filter deleted: {}, input files: {:?}",
+        region_id, found_runs, max_output_file_size, filter_deleted, input_file_str
+    );
+}
+
+#[async_trait::async_trait]
+impl Picker for SizeTieredPicker {
+    async fn pick(&self, compaction_region: &CompactionRegion) -> Result<Option<PickerOutput>> {
+        let region_id = compaction_region.region_id;
+        let picker = self.clone();
+        let compaction_region = compaction_region.clone();
+        let (expired_ssts, time_window_size, outputs) =
+            common_runtime::spawn_blocking_compact(move || {
+                let levels = compaction_region.current_version.ssts.levels();
+                let now = Timestamp::current_millis();
+                let expired_ssts = get_expired_ssts(levels, compaction_region.ttl, now);
+                if !expired_ssts.is_empty() {
+                    info!("Expired SSTs in region {}: {:?}", region_id, expired_ssts);
+                }
+                let expired_file_ids = expired_ssts
+                    .iter()
+                    .map(|file| file.file_id())
+                    .collect::<HashSet<_>>();
+
+                // Size-tiered compaction doesn't depend on time windows, but the region still
+                // records one for later compactions with time windows.
+                let time_window_size = compaction_region
+                    .current_version
+                    .compaction_time_window
+                    .map(|window| window.as_secs() as i64)
+                    .unwrap_or_else(|| infer_time_bucket(levels.iter().flat_map(LevelMeta::files)));
+
+                let outputs = picker.build_outputs(
+                    region_id,
+                    levels
+                        .iter()
+                        .flat_map(LevelMeta::files)
+                        .filter(|file| !expired_file_ids.contains(&file.file_id())),
+                );
+                (expired_ssts, time_window_size, outputs)
+            })
+            .await
+            .context(JoinSnafu)?;
+
+        if outputs.is_empty() && expired_ssts.is_empty() {
+            return Ok(None);
+        }
+
+        let max_file_size = self.max_output_file_size.map(|v| v as usize);
+        Ok(Some(PickerOutput {
+            outputs,
+            expired_ssts,
+            time_window_size,
+            max_file_size,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use store_api::storage::FileId;
+
+    use super::*;
+    use crate::compaction::test_util::new_file_handle_with_size_and_sequence;
+
+    fn new_picker(trigger_file_num: usize) -> SizeTieredPicker {
+        SizeTieredPicker {
+            trigger_file_num,
+            min_file_size: 10,
+            max_output_file_size: None,
+            append_mode: false,
+            max_background_tasks: None,
+        }
+    }
+
+    /// Creates files of the given (start, end, size) with distinct sequences.
+    fn new_files(specs: &[(i64, i64, u64)]) -> Vec<FileHandle> {
+        specs
+            .iter()
+            .enumerate()
+            .map(|(idx, (start, end, size))| {
+                new_file_handle_with_size_and_sequence(
+                    FileId::random(),
+                    *start,
+                    *end,
+                    0,
+                    idx as u64 + 1,
+                    *size,
+                )
+            })
+            .collect()
+    }
+
+    fn input_sizes(output: &CompactionOutput) -> Vec<u64> {
+        let mut sizes = output
+            .inputs
+            .iter()
+            .map(|file| file.size())
+            .collect::<Vec<_>>();
+        sizes.sort_unstable();
+        sizes
+    }
+
+    #[test]
+    fn test_assign_to_tiers() {
+        let picker = new_picker(4);
+        let files = new_files(&[
+            (0, 10, 1),
+            (0, 10, 5),
+            (0, 10, 100),
+            (0, 10, 120),
+            (0, 10, 140),
+            (0, 10, 1000),
+        ]);
+        let tiers = picker.assign_to_tiers(group_files(files.iter()));
+        let sizes = tiers
+            .iter()
+            .map(|tier| tier.iter().map(|fg| fg.size()).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        assert_eq!(vec![vec![1, 5], vec![100, 120, 140], vec![1000]], sizes);
+    }
+
+    #[test]
+    fn test_pick_tier_regardless_of_time_range() {
+        let picker = new_picker(3);
+        // Files overlap many time windows but have similar sizes.
+        let files = new_files(&[
+            (0, 86_400_000, 100),
+            (3_600_000, 7_200_000, 110),
+            (-86_400_000, 0, 120),
+            (0, 10, 1000),
+        ]);
+        let outputs = picker.build_outputs(RegionId::new(1, 1), files.iter());
+        assert_eq!(1, outputs.len());
+        assert_eq!(vec![100, 110, 120], input_sizes(&outputs[0]));
+        assert_eq!(LEVEL_COMPACTED, outputs[0].output_level);
+        // The large file overlaps the inputs so deletion markers are kept.
+        assert!(!outputs[0].filter_deleted);
+    }
+
+    #[test]
+    fn test_not_enough_files_in_tier() {
+        let picker = new_picker(3);
+        let files = new_files(&[(0, 10, 1), (0, 10, 100), (0, 10, 1000), (0, 10, 10000)]);
+        assert!(
+            picker
+                .build_outputs(RegionId::new(1, 1), files.iter())
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_filter_deleted_without_overlapping_files() {
+        let picker = new_picker(2);
+        let files = new_files(&[(0, 10, 100), (5, 20, 110), (100, 200, 1000)]);
+        let outputs = picker.build_outputs(RegionId::new(1, 1), files.iter());
+        assert_eq!(1, outputs.len());
+        assert_eq!(vec![100, 110], input_sizes(&outputs[0]));
+        assert!(outputs[0].filter_deleted);
+    }
+
+    #[test]
+    fn test_skip_compacting_files() {
+        let picker = new_picker(2);
+        let files = new_files(&[(0, 10, 100), (0, 10, 110), (0, 10, 120)]);
+        files[0].set_compacting(true);
+        let outputs = picker.build_outputs(RegionId::new(1, 1), files.iter());
+        assert_eq!(1, outputs.len());
+        assert_eq!(vec![110, 120], input_sizes(&outputs[0]));
+        // The compacting file still holds rows the inputs may mask.
+        assert!(!outputs[0].filter_deleted);
+    }
+
+    #[test]
+    fn test_append_mode_skips_large_files() {
+        let mut picker = new_picker(2);
+        picker.append_mode = true;
+        picker.max_output_file_size = Some(500);
+        let files = new_files(&[(0, 10, 100), (0, 10, 110), (0, 10, 1000), (0, 10, 1100)]);
+        let outputs = picker.build_outputs(RegionId::new(1, 1), files.iter());
+        assert_eq!(1, outputs.len());
+        assert_eq!(vec![100, 110], input_sizes(&outputs[0]));
+        assert!(!outputs[0].filter_deleted);
+    }
+
+    #[test]
+    fn test_limit_max_input_files() {
+        let picker = new_picker(4);
+        let specs = (0..40).map(|i| (i, i + 1, 100)).collect::<Vec<_>>();
+        let files = new_files(&specs);
+        let outputs = picker.build_outputs(RegionId::new(1, 1), files.iter());
+        assert_eq!(1, outputs.len());
+        assert_eq!(DEFAULT_MAX_INPUT_FILE_NUM, outputs[0].inputs.len());
+        let picked = outputs[0]
+            .inputs
+            .iter()
+            .map(|file| file.file_id())
+            .collect::<HashSet<_>>();
+        assert_eq!(DEFAULT_MAX_INPUT_FILE_NUM, picked.len());
+    }
+
+    #[test]
+    fn test_max_background_tasks() {
+        let mut picker = new_picker(2);
+        let files = new_files(&[(0, 10, 1), (0, 10, 2), (0, 10, 100), (0, 10, 110)]);
+        let outputs = picker.build_outputs(RegionId::new(1, 1), files.iter());
+        assert_eq!(2, outputs.len());
+        assert_eq!(vec![1, 2], input_sizes(&outputs[0]));
+        assert_eq!(vec![100, 110], input_sizes(&outputs[1]));
+
+        picker.max_background_tasks = Some(1);
+        let outputs = picker.build_outputs(RegionId::new(1, 1), files.iter());
+        assert_eq!(1, outputs.len());
+        assert_eq!(vec![1, 2], input_sizes(&outputs[0]));
+    }
+}
@@ -33,9 +33,11 @@ use crate::compaction::compactor::CompactionRegion;
 use crate::compaction::picker::{Picker, PickerOutput, get_expired_ssts};
 use crate::compaction::run::{
     FileGroup, Item, Ranged, find_sorted_runs, find_sorted_runs_by_time_range,
-    merge_primary_key_ranges, merge_seq_files, primary_key_ranges_overlap, reduce_runs,
+    merge_primary_key_ranges, merge_seq_files, overlaps_files_left_behind,
+    primary_key_ranges_overlap, reduce_runs,
 };
 use crate::error::{JoinSnafu, Result};
+use crate::region::options::{DownsampleOptions, TieredStorageOptions};
 use crate::sst::file::{FileHandle, Level, overlaps};
 use crate::sst::version::LevelMeta;
 
@@ -60,6 +62,10 @@ pub struct TwcsPicker {
     pub max_background_tasks: Option<usize>,
     /// Optional time range that constrains candidate compaction windows.
     pub(crate) time_range: Option<TimestampRange>,
+    /// Policy to downsample old time windows.
+    pub(crate) downsample: Option<DownsampleOptions>,
+    /// Policy to move files of old time windows to the cold store.
+    pub(crate) tiered_storage: Option<TieredStorageOptions>,
 }
 
 impl TwcsPicker {
@@ -116,6 +122,8 @@ impl TwcsPicker {
                     inputs: inputs.into_iter().flat_map(|fg| fg.into_files()).collect(),
                     filter_deleted,
                     output_time_range: None, // we do not enforce output time range in twcs compactions.
+                    downsample: None,
+                    storage: None,
                 });
 
                 if let Some(max_background_tasks) = self.max_background_tasks
@@ -132,6 +140,177 @@ impl TwcsPicker {
         Ok(output)
     }
 
+    /// Picks windows that are older than the downsample age and still have files
+    /// not downsampled at the policy interval. Picked windows are removed from `windows`
+    /// so the regular compaction doesn't touch them in the same round.
+    fn pick_downsample_outputs(
+        &self,
+        region_id: RegionId,
+        windows: &mut BTreeMap<i64, Window>,
+        time_window_size: i64,
+        now: Timestamp,
+    ) -> Vec<CompactionOutput> {
+        let Some(downsample) = &self.downsample else {
+            return vec![];
+        };
+        let Some(now) = now.convert_to(TimeUnit::Second) else {
+            return vec![];
+        };
+        let Some(downsample_before) = now.value().checked_sub(downsample.after.as_secs() as i64)
+        else {
+            return vec![];
+        };
+
+        let all_windows: &BTreeMap<i64, Window> = windows;
+        let candidates = all_windows
+            .values()
+            .filter(|window| {
+                window.time_window <= downsample_before
+                    && window.files().flat_map(|fg| fg.files()).any(|file| {
+                        file.meta_ref().downsample_interval != Some(downsample.interval)
+                    })
+                    && self.time_range.as_ref().is_none_or(|time_range| {
+                        time_window_intersects_range(
+                            window.time_window,
+                            time_window_size,
+                            time_range,
+                        )
+                    })
+            })
+            .filter(|window| {
+                // Rows of an overlapping window may share buckets with this window, so we
+                // leave them to the regular compaction until the overlap is resolved.
+                let overlap = window_has_overlap(window, all_windows);
+                if overlap {
+                    debug!(
+                        "Skip downsampling overlapping window {} in region {}",
+                        window.time_window, region_id
+                    );
+                }
+                !overlap
+            })
+            .map(|window| window.time_window)
+            .take(self.max_background_tasks.unwrap_or(usize::MAX))
+            .collect::<Vec<_>>();
+
+        candidates
+            .into_iter()
+            .filter_map(|time_window| windows.remove(&time_window))
+            .map(|window| {
+                let inputs = window
+                    .files
+                    .into_values()
+                    .flat_map(|fg| fg.into_files())
+                    .collect::<Vec<_>>();
+                info!(
+                    "Region ({:?}) picks {} files in window {} to downsample, interval: {:?}",
+                    region_id,
+                    inputs.len(),
+                    Timestamp::new_second(window.time_window).to_iso8601_string(),
+                    downsample.interval
+                );
+                CompactionOutput {
+                    output_level: LEVEL_COMPACTED,
+                    inputs,
+                    // All files in the window are rewritten and no other window overlaps it.
+                    filter_deleted: !self.append_mode,
+                    output_time_range: None,
+                    downsample: Some(downsample.clone()),
+                    storage: self.cold_storage_of(window.time_window, now),
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the start (in seconds) of the latest time window whose files should
+    /// be in the cold store.
+    fn cold_before(&self, now: Timestamp) -> Option<(&TieredStorageOptions, i64)> {
+        let tiered_storage = self.tiered_storage.as_ref()?;
+        let now = now.convert_to(TimeUnit::Second)?;
+        let cold_before = now
+            .value()
+            .checked_sub(tiered_storage.cold_after.as_secs() as i64)?;
+        Some((tiered_storage, cold_before))
+    }
+
+    /// Returns the name of the cold store if files of `time_window` should be in it.
+    fn cold_storage_of(&self, time_window: i64, now: Timestamp) -> Option<String> {
+        self.cold_before(now)
+            .filter(|(_, cold_before)| time_window <= *cold_before)
+            .map(|(tiered_storage, _)| tiered_storage.cold.clone())
+    }
+
+    /// Picks windows that are older than the cold age and still have files outside
+    /// the cold store, and rewrites all their files into the cold store. Picked windows are
+    /// removed from `windows` so the regular compaction doesn't touch them in the same round.
+    fn pick_cold_outputs(
+        &self,
+        region_id: RegionId,
+        windows: &mut BTreeMap<i64, Window>,
+        time_window_size: i64,
+        now: Timestamp,
+    ) -> Vec<CompactionOutput> {
+        let Some((tiered_storage, cold_before)) = self.cold_before(now) else {
+            return vec![];
+        };
+
+        let all_windows: &BTreeMap<i64, Window> = windows;
+        let candidates = all_windows
+            .values()
+            .filter(|window| {
+                window.time_window <= cold_before
+                    && window
+                        .files()
+                        .flat_map(|fg| fg.files())
+                        .any(|file| file.meta_ref().storage.as_ref() != Some(&tiered_storage.cold))
+                    && self.time_range.as_ref().is_none_or(|time_range| {
+                        time_window_intersects_range(
+                            window.time_window,
+                            time_window_size,
+                            time_range,
+                        )
+                    })
+            })
+            .map(|window| {
+                // Deletion markers may mask rows of overlapping windows.
+                let filter_deleted = !self.append_mode && !window_has_overlap(window, all_windows);
+                (window.time_window, filter_deleted)
+            })
+            .take(self.max_background_tasks.unwrap_or(usize::MAX))
+            .collect::<Vec<_>>();
+
+        candidates
+            .into_iter()
+            .filter_map(|(time_window, filter_deleted)| {
+                windows
+                    .remove(&time_window)
+                    .map(|window| (window, filter_deleted))
+            })
+            .map(|(window, filter_deleted)| {
+                let inputs = window
+                    .files
+                    .into_values()
+                    .flat_map(|fg| fg.into_files())
+                    .collect::<Vec<_>>();
+                info!(
+                    "Region ({:?}) picks {} files in window {} to move to storage {}",
+                    region_id,
+                    inputs.len(),
+                    Timestamp::new_second(window.time_window).to_iso8601_string(),
+                    tiered_storage.cold
+                );
+                CompactionOutput {
+                    output_level: LEVEL_COMPACTED,
+                    inputs,
+                    filter_deleted,
+                    output_time_range: None,
+                    downsample: None,
+                    storage: Some(tiered_storage.cold.clone()),
+                }
+            })
+            .collect()
+    }
+
     fn find_inputs(
         &self,
         region_id: RegionId,
@@ -235,20 +414,6 @@ impl TwcsPicker {
     }
 }
 
-/// Returns whether any file group of `window_files` that is not part of `inputs` overlaps
-/// `inputs`. Such a group keeps rows a deletion marker in `inputs` masks, so the compaction
-/// must not filter deleted rows out.
-///
-/// Overlapping is inclusive on both boundaries here: two files that only share a boundary
-/// timestamp can still hold the same row.
-fn overlaps_files_left_behind(inputs: &[FileGroup], window_files: &[FileGroup]) -> bool {
-    let picked: HashSet<_> = inputs.iter().flat_map(|fg| fg.file_ids()).collect();
-    window_files
-        .iter()
-        .filter(|fg| !fg.file_ids().iter().any(|id| picked.contains(id)))
-        .any(|fg| inputs.iter().any(|input| input.overlap_inclusive(fg)))
-}
-
 #[allow(clippy::too_many_arguments)]
 fn log_pick_result(
     region_id: RegionId,
@@ -301,14 +466,11 @@ impl Picker for TwcsPicker {
         let region_id = compaction_region.region_id;
         let picker = self.clone();
         let compaction_region = compaction_region.clone();
-        let (expired_ssts, time_window_size, active_window, windows) =
+        let (expired_ssts, time_window_size, active_window, windows, tiered_outputs) =
             common_runtime::spawn_blocking_compact(move || {
                 let levels = compaction_region.current_version.ssts.levels();
-                let expired_ssts = get_expired_ssts(
-                    levels,
-                    compaction_region.ttl,
-                    Timestamp::current_millis(),
-                );
+                let now = Timestamp::current_millis();
+                let expired_ssts = get_expired_ssts(levels, compaction_region.ttl, now);
                 if !expired_ssts.is_empty() {
                     info!("Expired SSTs in region {}: {:?}", region_id, expired_ssts);
                 }
@@ -334,22 +496,43 @@ impl Picker for TwcsPicker {
 
                 let active_window =
                     find_latest_window_in_seconds(levels[0].files(), time_window_size);
-                let windows = assign_to_windows(
+                let mut windows = assign_to_windows(
                     levels
                         .iter()
                         .flat_map(LevelMeta::files)
                         .filter(|file| !expired_file_ids.contains(&file.file_id())),
                     time_window_size,
                 );
+                let mut tiered_outputs =
+                    picker.pick_downsample_outputs(region_id, &mut windows, time_window_size, now);
+                tiered_outputs.extend(picker.pick_cold_outputs(
+                    region_id,
+                    &mut windows,
+                    time_window_size,
+                    now,
+                ));
 
-                (expired_ssts, time_window_size, active_window, windows)
+                (
+                    expired_ssts,
+                    time_window_size,
+                    active_window,
+                    windows,
+                    tiered_outputs,
+                )
             })
             .await
             .context(JoinSnafu)?;
 
-        let outputs = self
-            .build_output_with_time_range(region_id, windows, active_window, Some(time_window_size))
-            .await?;
+        let mut outputs = tiered_outputs;
+        outputs.extend(
+            self.build_output_with_time_range(
+                region_id,
+                windows,
+                active_window,
+                Some(time_window_size),
+            )
+            .await?,
+        );
 
         if outputs.is_empty() && expired_ssts.is_empty() {
             return Ok(None);
@@ -545,6 +728,7 @@ mod tests {
     use crate::sst::file::{FileMeta, Level};
     use crate::sst::version::SstVersion;
     use crate::test_util::memtable_util::metadata_for_test;
+    use crate::test_util::new_noop_file_purger;
     use crate::test_util::scheduler_util::SchedulerEnv;
 
     async fn compaction_region_with_expired_sst() -> CompactionRegion {
@@ -596,6 +780,8 @@ mod tests {
             append_mode: false,
             max_background_tasks: None,
             time_range: None,
+            downsample: None,
+            tiered_storage: None,
         };
         let compaction_region = compaction_region_with_expired_sst().await;
 
@@ -994,6 +1180,8 @@ mod tests {
                 append_mode: false,
                 max_background_tasks: None,
                 time_range: None,
+                downsample: None,
+                tiered_storage: None,
             }
             .build_output_with_time_range(RegionId::from_u64(0), windows, active_window, None)
             .await
@@ -1141,6 +1329,8 @@ mod tests {
             append_mode: false,
             max_background_tasks: None,
             time_range: None,
+            downsample: None,
+            tiered_storage: None,
         }
         .build_output_with_time_range(RegionId::from_u64(0), windows, active_window, None)
         .await
@@ -1214,6 +1404,8 @@ mod tests {
             append_mode: false,
             max_background_tasks: None,
             time_range: None,
+            downsample: None,
+            tiered_storage: None,
         };
 
         let active_window = find_latest_window_in_seconds(files.iter(), 3);
@@ -1259,6 +1451,8 @@ mod tests {
             append_mode: true,
             max_background_tasks: None,
             time_range: None,
+            downsample: None,
+            tiered_storage: None,
         };
 
         let active_window = find_latest_window_in_seconds(files.iter(), 3);
@@ -1305,6 +1499,8 @@ mod tests {
             append_mode: false,
             max_background_tasks: Some(max_background_tasks),
             time_range: None,
+            downsample: None,
+            tiered_storage: None,
         };
 
         let active_window = find_latest_window_in_seconds(files.iter(), 3);
@@ -1329,6 +1525,8 @@ mod tests {
             append_mode: false,
             max_background_tasks: None,
             time_range: None,
+            downsample: None,
+            tiered_storage: None,
         };
 
         let windows_no_limit = assign_to_windows(files.iter(), 3);
@@ -1373,6 +1571,8 @@ mod tests {
             append_mode: false,
             max_background_tasks: Some(max_background_tasks),
             time_range: None,
+            downsample: None,
+            tiered_storage: None,
         };
 
         let active_window = find_latest_window_in_seconds(files.iter(), 3);
@@ -1422,6 +1622,8 @@ mod tests {
             append_mode: false,
             max_background_tasks: None,
             time_range: None,
+            downsample: None,
+            tiered_storage: None,
         };
 
         let active_window = find_latest_window_in_seconds(files.iter(), 3);
@@ -1466,6 +1668,8 @@ mod tests {
             append_mode: false,
             max_background_tasks: None,
             time_range: None,
+            downsample: None,
+            tiered_storage: None,
         };
 
         let active_window = find_latest_window_in_seconds(files.iter(), 3);
@@ -1512,6 +1716,8 @@ mod tests {
             append_mode: false,
             max_background_tasks: None,
             time_range: None,
+            downsample: None,
+            tiered_storage: None,
         };
 
         let active_window = find_latest_window_in_seconds(files.iter(), 3600);
@@ -1558,6 +1764,8 @@ mod tests {
             append_mode: false,
             max_background_tasks: None,
             time_range: None,
+            downsample: None,
+            tiered_storage: None,
         };
 
         let active_window = find_latest_window_in_seconds(files.iter(), 3600);
@@ -1589,6 +1797,8 @@ mod tests {
             append_mode: false,
             max_background_tasks: Some(1),
             time_range: None,
+            downsample: None,
+            tiered_storage: None,
         };
 
         let output = picker
@@ -1653,6 +1863,8 @@ mod tests {
                 Timestamp::new_millisecond(7_200),
                 Timestamp::new_millisecond(7_800),
             ),
+            downsample: None,
+            tiered_storage: None,
         };
 
         let output = picker
@@ -1671,5 +1883,134 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_pick_downsample_outputs() {
+        let hour_millis = 3600 * 1000;
+        let downsample = DownsampleOptions {
+            after: Duration::from_secs(3600),
+            interval: Duration::from_secs(300),
+            functions: Default::default(),
+        };
+        let picker = TwcsPicker {
+            trigger_file_num: 4,
+            time_window_seconds: Some(3600),
+            max_output_file_size: None,
+            append_mode: false,
+            max_background_tasks: None,
+            time_range: None,
+            downsample: Some(downsample.clone()),
+            tiered_storage: None,
+        };
+        let downsampled = FileHandle::new(
+            FileMeta {
+                file_id: FileId::random(),
+                time_range: (
+                    Timestamp::new_millisecond(hour_millis + 1),
+                    Timestamp::new_millisecond(2 * hour_millis),
+                ),
+                sequence: NonZeroU64::new(10),
+                downsample_interval: Some(downsample.interval),
+                ..Default::default()
+            },
+            new_noop_file_purger(),
+        );
+        let files = [
+            // Window 1h needs downsampling.
+            new_file_handle(FileId::random(), 0, hour_millis - 1, 0),
+            new_file_handle(FileId::random(), 1, hour_millis, 0),
+            // Window 2h is already downsampled.
+            downsampled,
+            // Window 10h is not old enough.
+            new_file_handle(FileId::random(), 9 * hour_millis + 1, 10 * hour_millis, 0),
+        ];
+        let mut windows = assign_to_windows(files.iter(), 3600);
+        let now = Timestamp::new_millisecond(10 * hour_millis + 1);
+
+        let outputs = picker.pick_downsample_outputs(RegionId::new(1, 1), &mut windows, 3600, now);
+        assert_eq!(1, outputs.len());
+        assert_eq!(
+            files[..2]
+                .iter()
+                .map(|file| file.file_id())
+                .collect::<HashSet<_>>(),
+            outputs[0]
+                .inputs
+                .iter()
+                .map(|file| file.file_id())
+                .collect::<HashSet<_>>()
+        );
+        assert_eq!(Some(downsample), outputs[0].downsample);
+        assert!(outputs[0].filter_deleted);
+        // The picked window is left out of the regular compaction.
+        assert_eq!(
+            vec![7200, 36000],
+            windows.keys().copied().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_pick_cold_outputs() {
+        let hour_millis = 3600 * 1000;
+        let picker = TwcsPicker {
+            trigger_file_num: 4,
+            time_window_seconds: Some(3600),
+            max_output_file_size: None,
+            append_mode: false,
+            max_background_tasks: None,
+            time_range: None,
+            downsample: None,
+            tiered_storage: Some(TieredStorageOptions {
+                cold: "archive".to_string(),
+                cold_after: Duration::from_secs(3600),
+            }),
+        };
+        let cold = FileHandle::new(
+            FileMeta {
+                file_id: FileId::random(),
+                time_range: (
+                    Timestamp::new_millisecond(hour_millis + 1),
+                    Timestamp::new_millisecond(2 * hour_millis),
+                ),
+                sequence: NonZeroU64::new(10),
+                storage: Some("archive".to_string()),
+                ..Default::default()
+            },
+            new_noop_file_purger(),
+        );
+        let files = [
+            // Window 1h needs to move to the cold store.
+            new_file_handle(FileId::random(), 0, hour_millis - 1, 0),
+            new_file_handle(FileId::random(), 1, hour_millis, 0),
+            // Window 2h is already in the cold store.
+            cold,
+            // Window 10h is not old enough.
+            new_file_handle(FileId::random(), 9 * hour_millis + 1, 10 * hour_millis, 0),
+        ];
+        let mut windows = assign_to_windows(files.iter(), 3600);
+        let now = Timestamp::new_millisecond(10 * hour_millis + 1);
+
+        let outputs = picker.pick_cold_outputs(RegionId::new(1, 1), &mut windows, 3600, now);
+        assert_eq!(1, outputs.len());
+        assert_eq!(
+            files[..2]
+                .iter()
+                .map(|file| file.file_id())
+                .collect::<HashSet<_>>(),
+            outputs[0]
+                .inputs
+                .iter()
+                .map(|file| file.file_id())
+                .collect::<HashSet<_>>()
+        );
+        assert_eq!(Some("archive"), outputs[0].storage.as_deref());
+        assert!(outputs[0].downsample.is_none());
+        assert!(outputs[0].filter_deleted);
+        // The picked window is left out of the regular compaction.
+        assert_eq!(
+            vec![7200, 36000],
+            windows.keys().copied().collect::<Vec<_>>()
+        );
+    }
+
     // TODO(hl): TTL tester that checks if get_expired_ssts function works as expected.
 }
@@ -216,6 +216,8 @@ fn build_output(windows: BTreeMap<i64, (i64, Vec<FileHandle>)>) -> Vec<Compactio
             inputs: files,
             filter_deleted: false,
             output_time_range,
+            downsample: None,
+            storage: None,
         };
         outputs.push(output);
     }
@@ -326,12 +328,14 @@ mod tests {
                 compaction: Default::default(),
                 compaction_override: false,
                 storage: None,
+                tiered_storage: None,
                 append_mode: false,
                 skip_wal: false,
                 wal_options: Default::default(),
                 index_options: Default::default(),
                 memtable: None,
                 merge_mode: None,
+                merge_functions: None,
                 sst_format: None,
                 max_row_group_row_count: None,
                 primary_key_encoding: None,
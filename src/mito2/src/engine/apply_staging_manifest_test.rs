@@ -650,6 +650,7 @@ async fn test_apply_staging_manifest_preserves_unflushed_memtable_with_format(fl
                 rows: unflushed_rows,
                 hint: None,
                 partition_expr_version: Some(expected_version),
+                replace_existing: false,
             }),
         )
         .await
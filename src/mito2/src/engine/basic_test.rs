@@ -649,6 +649,7 @@ async fn test_absent_and_invalid_columns_with_format(flat_format: bool) {
                 rows,
                 hint: None,
                 partition_expr_version: None,
+                replace_existing: false,
             }),
         )
         .await
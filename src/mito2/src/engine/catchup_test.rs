@@ -34,8 +34,8 @@ use crate::engine::MitoEngine;
 use crate::error::Error;
 use crate::test_util::{
     CreateRequestBuilder, LogStoreFactory, TestEnv, build_rows, flush_region,
-    kafka_log_store_factory, prepare_test_for_kafka_log_store, put_rows,
-    raft_engine_log_store_factory, rows_schema, single_kafka_log_store_factory,
+    kafka_log_store_factory, object_store_log_store_factory, prepare_test_for_kafka_log_store,
+    put_rows, raft_engine_log_store_factory, rows_schema, single_kafka_log_store_factory,
     single_raft_engine_log_store_factory,
 };
 use crate::wal::EntryId;
@@ -496,6 +496,109 @@ async fn test_catchup_with_manifest_update(factory: Option<LogStoreFactory>) {
     assert!(region.is_writable());
 }
 
+#[tokio::test]
+async fn test_object_store_catchup() {
+    use futures::StreamExt;
+    use store_api::logstore::LogStore;
+    use store_api::logstore::provider::Provider;
+    use store_api::region_engine::SettableRegionRoleState;
+
+    use crate::test_util::LogStoreImpl;
+
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::with_prefix("object_store_catchup")
+        .await
+        .with_log_store_factory(object_store_log_store_factory().unwrap());
+    let leader_engine = env.create_engine(MitoConfig::default()).await;
+    let follower_engine = env.create_follower_engine(MitoConfig::default()).await;
+    let Some(LogStoreImpl::ObjectStore(log_store)) = env.get_log_store() else {
+        unreachable!()
+    };
+
+    let region_id = RegionId::new(1, 1);
+    let wal_options = serde_json::to_string(&WalOptions::ObjectStore).unwrap();
+    let request = CreateRequestBuilder::new()
+        .insert_option(WAL_OPTIONS_KEY, &wal_options)
+        .build();
+    let table_dir = request.table_dir.clone();
+    let column_schemas = rows_schema(&request);
+    leader_engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    follower_engine
+        .handle_request(
+            region_id,
+            RegionRequest::Open(RegionOpenRequest {
+                engine: String::new(),
+                table_dir,
+                path_type: PathType::Bare,
+                options: HashMap::from([(WAL_OPTIONS_KEY.to_string(), wal_options)]),
+                skip_wal_replay: false,
+                checkpoint: None,
+                requirements: Default::default(),
+            }),
+        )
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows(0, 3),
+    };
+    put_rows(&leader_engine, region_id, rows).await;
+    flush_region(&leader_engine, region_id, None).await;
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows(3, 5),
+    };
+    put_rows(&leader_engine, region_id, rows).await;
+
+    // Segments before the flushed entry id are removed from the object store.
+    let provider = Provider::object_store_provider(region_id.as_u64());
+    let mut stream = log_store.read(&provider, 0, None).await.unwrap();
+    let mut entry_ids = vec![];
+    while let Some(entries) = stream.next().await {
+        entry_ids.extend(entries.unwrap().iter().map(|entry| entry.entry_id()));
+    }
+    assert_eq!(entry_ids, vec![2]);
+
+    let resp = leader_engine
+        .set_region_role_state_gracefully(region_id, SettableRegionRoleState::Follower)
+        .await
+        .unwrap();
+    let last_entry_id = get_last_entry_id(resp);
+    assert_eq!(last_entry_id, Some(2));
+
+    // The follower replays entries written by the leader from the object store.
+    follower_engine
+        .handle_request(
+            region_id,
+            RegionRequest::Catchup(RegionCatchupRequest {
+                set_writable: true,
+                entry_id: last_entry_id,
+                ..Default::default()
+            }),
+        )
+        .await
+        .unwrap();
+    let region = follower_engine.get_region(region_id).unwrap();
+    assert!(region.is_writable());
+
+    let batches = scan_region(&follower_engine, region_id).await;
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| 0     | 0.0     | 1970-01-01T00:00:00 |
+| 1     | 1.0     | 1970-01-01T00:00:01 |
+| 2     | 2.0     | 1970-01-01T00:00:02 |
+| 3     | 3.0     | 1970-01-01T00:00:03 |
+| 4     | 4.0     | 1970-01-01T00:00:04 |
++-------+---------+---------------------+";
+    assert_eq!(expected, batches.pretty_print().unwrap());
+}
+
 async fn close_region(engine: &MitoEngine, region_id: RegionId) {
     engine
         .handle_request(
@@ -24,6 +24,7 @@ use common_error::ext::ErrorExt;
 use common_error::status_code::StatusCode;
 use common_recordbatch::{RecordBatches, SendableRecordBatchStream};
 use common_time::Timestamp;
+use datafusion_expr::{col, lit};
 use datatypes::arrow::array::AsArray;
 use datatypes::arrow::datatypes::TimestampMillisecondType;
 use store_api::region_engine::{RegionEngine, RegionRole};
@@ -40,8 +41,11 @@ use crate::config::MitoConfig;
 use crate::engine::MitoEngine;
 use crate::engine::flush_test::MockTimeProvider;
 use crate::engine::listener::{CompactionListener, EventListener};
+use crate::sst::location;
+use crate::test_util::batch_util::sort_batches_and_print;
 use crate::test_util::{
     CreateRequestBuilder, TestEnv, build_rows_for_key, column_metadata_to_column_schema, put_rows,
+    reopen_region,
 };
 
 pub(crate) async fn put_and_flush(
@@ -997,6 +1001,282 @@ async fn test_compaction_region_with_format(flat_format: bool) {
     assert_eq!((0..25).map(|v| v * 1000).collect::<Vec<_>>(), vec);
 }
 
+#[tokio::test]
+async fn test_compaction_downsample() {
+    test_compaction_downsample_with_format(false).await;
+    test_compaction_downsample_with_format(true).await;
+}
+
+async fn test_compaction_downsample_with_format(flat_format: bool) {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new().await;
+    let engine = env
+        .create_engine(MitoConfig {
+            default_flat_format: flat_format,
+            ..Default::default()
+        })
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    env.get_schema_metadata_manager()
+        .register_region_table_info(
+            region_id.table_id(),
+            "test_table",
+            "test_catalog",
+            "test_schema",
+            None,
+            env.get_kv_backend(),
+        )
+        .await;
+
+    let request = CreateRequestBuilder::new()
+        .insert_option("compaction.type", "twcs")
+        .insert_option("compaction.twcs.time_window", "1h")
+        .insert_option("compaction.twcs.downsample_after", "1d")
+        .insert_option("compaction.twcs.downsample_interval", "10s")
+        .insert_option("compaction.twcs.downsample_functions", "field_0:sum")
+        .build();
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    // Values are 0..10 in both files.
+    put_and_flush(&engine, region_id, &column_schemas, 0..10).await;
+    put_and_flush(&engine, region_id, &column_schemas, 10..20).await;
+
+    compact(&engine, region_id).await;
+
+    let scanner = engine
+        .scanner(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(1, scanner.num_files());
+    let stream = scanner.scan().await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    // Buckets are labelled by their start: [0s, 10s) is labelled 0s.
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 45.0    | 1970-01-01T00:00:00 |
+| a     | 45.0    | 1970-01-01T00:00:10 |
++-------+---------+---------------------+";
+    assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+
+    // Downsampled files are not downsampled again.
+    compact(&engine, region_id).await;
+    let scanner = engine
+        .scanner(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(1, scanner.num_files());
+    let stream = scanner.scan().await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+
+    // Late rows are merged into downsampled rows, even if they have the same timestamp.
+    let mut rows = build_rows_for_key("a", 3, 4, 7);
+    rows.extend(build_rows_for_key("a", 10, 11, 5));
+    put_rows(
+        &engine,
+        region_id,
+        Rows {
+            schema: column_schemas.clone(),
+            rows,
+        },
+    )
+    .await;
+    flush(&engine, region_id).await;
+    compact(&engine, region_id).await;
+    let scanner = engine
+        .scanner(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(1, scanner.num_files());
+    let stream = scanner.scan().await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 52.0    | 1970-01-01T00:00:00 |
+| a     | 50.0    | 1970-01-01T00:00:10 |
++-------+---------+---------------------+";
+    assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+}
+
+#[tokio::test]
+async fn test_compaction_tiered_storage() {
+    test_compaction_tiered_storage_with_format(false).await;
+    test_compaction_tiered_storage_with_format(true).await;
+}
+
+async fn test_compaction_tiered_storage_with_format(flat_format: bool) {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::new().await;
+    let engine = env
+        .create_engine_with_multiple_object_stores(
+            MitoConfig {
+                default_flat_format: flat_format,
+                ..Default::default()
+            },
+            None,
+            None,
+            &["archive"],
+        )
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    env.get_schema_metadata_manager()
+        .register_region_table_info(
+            region_id.table_id(),
+            "test_table",
+            "test_catalog",
+            "test_schema",
+            None,
+            env.get_kv_backend(),
+        )
+        .await;
+
+    let request = CreateRequestBuilder::new()
+        .insert_option("compaction.type", "twcs")
+        .insert_option("compaction.twcs.time_window", "1h")
+        .insert_option("storage.cold", "archive")
+        .insert_option("storage.cold_after", "1d")
+        .build();
+    let table_dir = request.table_dir.clone();
+    let column_schemas = request
+        .column_metadatas
+        .iter()
+        .map(column_metadata_to_column_schema)
+        .collect::<Vec<_>>();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    put_and_flush(&engine, region_id, &column_schemas, 0..10).await;
+    put_and_flush(&engine, region_id, &column_schemas, 10..20).await;
+
+    compact(&engine, region_id).await;
+
+    let region = engine.get_region(region_id).unwrap();
+    let files = region
+        .version()
+        .ssts
+        .levels()
+        .iter()
+        .flat_map(|level| level.files.values().cloned())
+        .collect::<Vec<_>>();
+    assert_eq!(1, files.len());
+    assert_eq!(Some("archive"), files[0].meta_ref().storage.as_deref());
+    let path = location::sst_file_path(
+        region.access_layer.table_dir(),
+        files[0].file_id(),
+        region.access_layer.path_type(),
+    );
+    let object_store_manager = env.get_object_store_manager().unwrap();
+    assert!(
+        object_store_manager
+            .find("archive")
+            .unwrap()
+            .exists(&path)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !object_store_manager
+            .default_object_store()
+            .exists(&path)
+            .await
+            .unwrap()
+    );
+    // The index of the output is built in the cold store as well.
+    assert!(files[0].meta_ref().exists_index());
+    let index_path = location::index_file_path(
+        region.access_layer.table_dir(),
+        files[0].index_id(),
+        region.access_layer.path_type(),
+    );
+    assert!(
+        object_store_manager
+            .find("archive")
+            .unwrap()
+            .exists(&index_path)
+            .await
+            .unwrap()
+    );
+    assert!(
+        !object_store_manager
+            .default_object_store()
+            .exists(&index_path)
+            .await
+            .unwrap()
+    );
+
+    let expected = "\
++-------+---------+---------------------+
+| tag_0 | field_0 | ts                  |
++-------+---------+---------------------+
+| a     | 0.0     | 1970-01-01T00:00:00 |
+| a     | 1.0     | 1970-01-01T00:00:01 |
+| a     | 2.0     | 1970-01-01T00:00:02 |
+| a     | 3.0     | 1970-01-01T00:00:03 |
+| a     | 4.0     | 1970-01-01T00:00:04 |
+| a     | 5.0     | 1970-01-01T00:00:05 |
+| a     | 6.0     | 1970-01-01T00:00:06 |
+| a     | 7.0     | 1970-01-01T00:00:07 |
+| a     | 8.0     | 1970-01-01T00:00:08 |
+| a     | 9.0     | 1970-01-01T00:00:09 |
+| a     | 10.0    | 1970-01-01T00:00:10 |
+| a     | 11.0    | 1970-01-01T00:00:11 |
+| a     | 12.0    | 1970-01-01T00:00:12 |
+| a     | 13.0    | 1970-01-01T00:00:13 |
+| a     | 14.0    | 1970-01-01T00:00:14 |
+| a     | 15.0    | 1970-01-01T00:00:15 |
+| a     | 16.0    | 1970-01-01T00:00:16 |
+| a     | 17.0    | 1970-01-01T00:00:17 |
+| a     | 18.0    | 1970-01-01T00:00:18 |
+| a     | 19.0    | 1970-01-01T00:00:19 |
++-------+---------+---------------------+";
+    let scanner = engine
+        .scanner(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let stream = scanner.scan().await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+
+    // Filters on tags apply the index in the cold store.
+    let scanner = engine
+        .scanner(
+            region_id,
+            ScanRequest {
+                filters: vec![col("tag_0").eq(lit("a"))],
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+    let stream = scanner.scan().await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+
+    // The region resolves the cold store from its files even without the policy.
+    reopen_region(&engine, region_id, table_dir, true, HashMap::new()).await;
+    let scanner = engine
+        .scanner(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let stream = scanner.scan().await.unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+}
+
 #[tokio::test]
 async fn test_infer_compaction_time_window() {
     test_infer_compaction_time_window_with_format(false).await;
@@ -31,6 +31,7 @@ use store_api::storage::RegionId;
 
 use crate::config::MitoConfig;
 use crate::engine::MitoEngine;
+use crate::engine::compaction_test::compact;
 use crate::engine::flush_test::MockRegionHook;
 use crate::engine::listener::{DropListener, FlushCancellationListener};
 use crate::engine::region_hook::RegionHookRef;
@@ -455,6 +456,85 @@ async fn test_engine_drop_region_for_custom_store_with_format(flat_format: bool)
     );
 }
 
+#[tokio::test]
+async fn test_engine_drop_region_with_tiered_storage() {
+    common_telemetry::init_default_ut_logging();
+    let mut env = TestEnv::with_prefix("drop-tiered").await;
+    let listener = Arc::new(DropListener::new(Duration::from_millis(100)));
+    let engine = env
+        .create_engine_with_multiple_object_stores(
+            MitoConfig::default(),
+            None,
+            Some(listener.clone()),
+            &["archive"],
+        )
+        .await;
+
+    let region_id = RegionId::new(1, 1);
+    env.get_schema_metadata_manager()
+        .register_region_table_info(
+            region_id.table_id(),
+            "test_table",
+            "test_catalog",
+            "test_schema",
+            None,
+            env.get_kv_backend(),
+        )
+        .await;
+
+    let request = CreateRequestBuilder::new()
+        .insert_option("compaction.type", "twcs")
+        .insert_option("compaction.twcs.time_window", "1h")
+        .insert_option("storage.cold", "archive")
+        .insert_option("storage.cold_after", "1d")
+        .build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+    for start in [0, 10] {
+        put_rows(
+            &engine,
+            region_id,
+            Rows {
+                schema: column_schemas.clone(),
+                rows: build_rows_for_key("a", start, start + 10, 0),
+            },
+        )
+        .await;
+        flush_region(&engine, region_id, None).await;
+    }
+    compact(&engine, region_id).await;
+
+    let region = engine.get_region(region_id).unwrap();
+    let region_dir = region.access_layer.build_region_dir(region_id);
+    let object_store_manager = env.get_object_store_manager().unwrap();
+    let archive_store = object_store_manager.find("archive").unwrap();
+    let default_store = object_store_manager.default_object_store();
+    // The compacted file is moved to the archive store.
+    assert!(archive_store.exists(&region_dir).await.unwrap());
+    assert!(default_store.exists(&region_dir).await.unwrap());
+
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Drop(RegionDropRequest {
+                fast_path: false,
+                force: false,
+                partial_drop: false,
+            }),
+        )
+        .await
+        .unwrap();
+    // Wait for drop task.
+    listener.wait().await;
+
+    // The region dir is removed from every store.
+    assert!(!archive_store.exists(&region_dir).await.unwrap());
+    assert!(!default_store.exists(&region_dir).await.unwrap());
+}
+
 #[tokio::test]
 async fn test_region_hook_on_drop() {
     common_telemetry::init_default_ut_logging();
@@ -289,6 +289,7 @@ async fn test_write_during_region_editing_is_queued() {
                     rows,
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 }),
             )
             .await
@@ -403,6 +404,7 @@ async fn test_stalled_write_fails_fast_if_region_closed_during_editing() {
                     rows,
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 }),
             )
             .await
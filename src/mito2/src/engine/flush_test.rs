@@ -746,6 +746,7 @@ async fn test_region_write_buffer_does_not_stall_follower_write() {
                 },
                 hint: None,
                 partition_expr_version: None,
+                replace_existing: false,
             }),
         ),
     )
@@ -941,6 +942,7 @@ async fn test_region_write_buffer_rejects_only_full_region_queue() {
                     },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 }),
             )
             .await
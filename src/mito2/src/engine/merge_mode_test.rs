@@ -234,3 +234,129 @@ async fn test_merge_mode_compaction_with_format(flat_format: bool) {
     let batches = RecordBatches::try_collect(stream).await.unwrap();
     assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
 }
+
+#[tokio::test]
+async fn test_aggregate_merge_mode() {
+    test_aggregate_merge_mode_with_format(false).await;
+    test_aggregate_merge_mode_with_format(true).await;
+}
+
+async fn test_aggregate_merge_mode_with_format(flat_format: bool) {
+    common_telemetry::init_default_ut_logging();
+
+    let mut env = TestEnv::new().await;
+    let engine = env
+        .create_engine(MitoConfig {
+            default_flat_format: flat_format,
+            ..Default::default()
+        })
+        .await;
+    let region_id = RegionId::new(1, 1);
+
+    env.get_schema_metadata_manager()
+        .register_region_table_info(
+            region_id.table_id(),
+            "test_table",
+            "test_catalog",
+            "test_schema",
+            None,
+            env.get_kv_backend(),
+        )
+        .await;
+
+    let request = CreateRequestBuilder::new()
+        .field_num(2)
+        .insert_option("compaction.type", "twcs")
+        .insert_option("merge_mode", "aggregate")
+        .insert_option("merge_functions", "field_0:sum,field_1:max")
+        .build();
+    let column_schemas = rows_schema(&request);
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap();
+
+    // Rows in the memtable are merged while scanning.
+    for fields in [
+        [(Some(1), Some(1)), (Some(2), None)],
+        [(Some(10), Some(5)), (Some(20), Some(7))],
+    ] {
+        let rows = Rows {
+            schema: column_schemas.clone(),
+            rows: build_rows_with_fields("a", &[1, 2], &fields),
+        };
+        put_rows(&engine, region_id, rows).await;
+    }
+    let expected = "\
++-------+---------+---------+---------------------+
+| tag_0 | field_0 | field_1 | ts                  |
++-------+---------+---------+---------------------+
+| a     | 11.0    | 5.0     | 1970-01-01T00:00:01 |
+| a     | 22.0    | 7.0     | 1970-01-01T00:00:02 |
++-------+---------+---------+---------------------+";
+    let stream = engine
+        .scan_to_stream(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+
+    // Merges rows at flush and compaction.
+    flush_region(&engine, region_id, None).await;
+    let rows = Rows {
+        schema: column_schemas.clone(),
+        rows: build_rows_with_fields("a", &[1], &[(Some(100), None)]),
+    };
+    put_rows(&engine, region_id, rows).await;
+    flush_region(&engine, region_id, None).await;
+    engine
+        .handle_request(
+            region_id,
+            RegionRequest::Compact(RegionCompactRequest::default()),
+        )
+        .await
+        .unwrap();
+
+    let rows = Rows {
+        schema: column_schemas,
+        rows: build_rows_with_fields("a", &[2], &[(Some(1000), Some(1))]),
+    };
+    put_rows(&engine, region_id, rows).await;
+
+    let expected = "\
++-------+---------+---------+---------------------+
+| tag_0 | field_0 | field_1 | ts                  |
++-------+---------+---------+---------------------+
+| a     | 111.0   | 5.0     | 1970-01-01T00:00:01 |
+| a     | 1022.0  | 7.0     | 1970-01-01T00:00:02 |
++-------+---------+---------+---------------------+";
+    let scanner = engine
+        .scanner(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    assert_eq!(1, scanner.num_files());
+    assert_eq!(1, scanner.num_memtables());
+    let stream = engine
+        .scan_to_stream(region_id, ScanRequest::default())
+        .await
+        .unwrap();
+    let batches = RecordBatches::try_collect(stream).await.unwrap();
+    assert_eq!(expected, sort_batches_and_print(&batches, &["tag_0", "ts"]));
+}
+
+#[tokio::test]
+async fn test_aggregate_merge_mode_unknown_field() {
+    let mut env = TestEnv::new().await;
+    let engine = env.create_engine(MitoConfig::default()).await;
+
+    let region_id = RegionId::new(1, 1);
+    let request = CreateRequestBuilder::new()
+        .field_num(2)
+        .insert_option("merge_mode", "aggregate")
+        .insert_option("merge_functions", "unknown:sum")
+        .build();
+    engine
+        .handle_request(region_id, RegionRequest::Create(request))
+        .await
+        .unwrap_err();
+}
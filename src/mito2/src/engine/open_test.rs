@@ -481,6 +481,7 @@ async fn test_engine_open_readonly_with_format(flat_format: bool) {
                 rows: rows.clone(),
                 hint: None,
                 partition_expr_version: None,
+                replace_existing: false,
             }),
         )
         .await
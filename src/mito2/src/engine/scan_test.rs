@@ -863,6 +863,7 @@ async fn test_two_phase_series_scan() {
                 primary_key_encoding: api::v1::PrimaryKeyEncoding::Sparse.into(),
             }),
             partition_expr_version: None,
+            replace_existing: false,
         })
     };
 
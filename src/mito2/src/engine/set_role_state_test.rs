@@ -111,6 +111,7 @@ async fn test_set_role_state_gracefully_with_format(flat_format: bool) {
                     rows: rows.clone(),
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 }),
             )
             .await
@@ -205,6 +206,7 @@ async fn test_write_downgrading_region_with_format(flat_format: bool) {
                 rows: rows.clone(),
                 hint: None,
                 partition_expr_version: None,
+                replace_existing: false,
             }),
         )
         .await
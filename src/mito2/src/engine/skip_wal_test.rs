@@ -528,6 +528,7 @@ async fn test_close_region_skip_wal_rejects_writes_queued_after_close() {
                 },
                 hint: None,
                 partition_expr_version: None,
+                replace_existing: false,
             }),
         )
         .await
@@ -559,6 +560,7 @@ async fn test_close_region_skip_wal_rejects_writes_queued_after_close() {
                     },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 }),
             )
             .await
@@ -585,6 +587,7 @@ async fn test_close_region_skip_wal_rejects_writes_queued_after_close() {
                     },
                     hint: None,
                     partition_expr_version: None,
+                    replace_existing: false,
                 }),
             )
             .await
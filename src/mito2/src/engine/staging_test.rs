@@ -286,6 +286,7 @@ async fn test_staging_reject_all_writes_rejects_put() {
                 rows,
                 hint: None,
                 partition_expr_version: None,
+                replace_existing: false,
             }),
         )
         .await
@@ -356,6 +357,7 @@ async fn test_staging_write_partition_expr_version_with_format(flat_format: bool
                 rows: bad_rows,
                 hint: None,
                 partition_expr_version: Some(origin_version),
+                replace_existing: false,
             }),
         )
         .await
@@ -377,6 +379,7 @@ async fn test_staging_write_partition_expr_version_with_format(flat_format: bool
                 rows: compat_rows,
                 hint: None,
                 partition_expr_version: None,
+                replace_existing: false,
             }),
         )
         .await
@@ -394,6 +397,7 @@ async fn test_staging_write_partition_expr_version_with_format(flat_format: bool
                 rows: ok_rows,
                 hint: None,
                 partition_expr_version: Some(expected_version),
+                replace_existing: false,
             }),
         )
         .await
@@ -441,6 +445,7 @@ async fn test_staging_write_partition_expr_version_with_format(flat_format: bool
                 rows: exit_rows,
                 hint: None,
                 partition_expr_version: Some(origin_version),
+                replace_existing: false,
             }),
         )
         .await
@@ -458,6 +463,7 @@ async fn test_staging_write_partition_expr_version_with_format(flat_format: bool
                 rows: compat_rows,
                 hint: None,
                 partition_expr_version: None,
+                replace_existing: false,
             }),
         )
         .await
@@ -484,6 +490,7 @@ async fn test_staging_write_partition_expr_version_with_format(flat_format: bool
                 rows: commit_rows,
                 hint: None,
                 partition_expr_version: Some(expected_version),
+                replace_existing: false,
             }),
         )
         .await
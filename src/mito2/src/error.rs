@@ -428,6 +428,20 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display(
+        "Failed to merge values of field {} by {}, reason: {}",
+        column,
+        function,
+        reason
+    ))]
+    MergeFieldValues {
+        column: String,
+        function: String,
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display("Invalid arrow record batch, {}", reason))]
     InvalidRecordBatch {
         reason: String,
@@ -1476,6 +1490,7 @@ impl ErrorExt for Error {
             WriteParquet { .. } => StatusCode::StorageUnavailable,
             WriteGroup { source, .. } => source.status_code(),
             InvalidBatch { .. } => StatusCode::InvalidArguments,
+            MergeFieldValues { .. } => StatusCode::InvalidArguments,
             InvalidRecordBatch { .. } => StatusCode::InvalidArguments,
             ConvertVector { source, .. } => source.status_code(),
 
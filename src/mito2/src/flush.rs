@@ -52,9 +52,9 @@ use crate::metrics::{
     INFLIGHT_FLUSH_COUNT,
 };
 use crate::read::FlatSource;
-use crate::read::flat_dedup::{FlatDedupIterator, FlatLastNonNull, FlatLastRow};
+use crate::read::flat_dedup::{FlatAggregate, FlatDedupIterator, FlatLastNonNull, FlatLastRow};
 use crate::read::flat_merge::FlatMergeIterator;
-use crate::region::options::{IndexOptions, MergeMode, RegionOptions};
+use crate::region::options::{IndexOptions, MergeFunctions, MergeMode, RegionOptions};
 use crate::region::version::{VersionControlData, VersionControlRef, VersionRef};
 use crate::region::{ManifestContextRef, RegionLeaderState, RegionRoleState, parse_partition_expr};
 use crate::request::{
@@ -806,6 +806,8 @@ impl RegionFlushTask {
             num_series: sst_info.num_series,
             primary_key_min,
             primary_key_max,
+            downsample_interval: None,
+            storage: None,
         }
     }
 
@@ -825,7 +827,7 @@ impl RegionFlushTask {
             metadata: version.metadata.clone(),
             source,
             cache_manager: self.cache_manager.clone(),
-            storage: version.options.storage.clone(),
+            storage: None,
             max_sequence: Some(max_sequence),
             sst_write_format: if flat_format {
                 FormatType::Flat
@@ -905,6 +907,9 @@ fn memtable_flat_sources(
     field_column_start: usize,
 ) -> Result<FlatSources> {
     let MemtableRanges { ranges } = mem_ranges;
+    // Memtables keep duplicate rows in the aggregate mode, so we also need to
+    // merge encoded ranges instead of writing them directly.
+    let merge_encoded = !options.append_mode && options.merge_mode() == MergeMode::Aggregate;
     let mut flat_sources = FlatSources {
         sources: SmallVec::new(),
         encoded: SmallVec::new(),
@@ -915,7 +920,7 @@ fn memtable_flat_sources(
 
         let only_range = ranges.into_values().next().unwrap();
         let max_sequence = only_range.stats().max_sequence();
-        if let Some(encoded) = only_range.encoded() {
+        if !merge_encoded && let Some(encoded) = only_range.encoded() {
             flat_sources.encoded.push((encoded, max_sequence));
         } else {
             let iter = only_range.build_record_batch_iter(None, None)?;
@@ -924,6 +929,7 @@ fn memtable_flat_sources(
             let iter = maybe_dedup_one(
                 options.append_mode,
                 options.merge_mode(),
+                &options.merge_functions(),
                 field_column_start,
                 iter,
             );
@@ -936,7 +942,7 @@ fn memtable_flat_sources(
         // Calculate total rows from non-encoded ranges.
         let total_rows: usize = ranges
             .values()
-            .filter(|r| r.encoded().is_none())
+            .filter(|r| merge_encoded || r.encoded().is_none())
             .map(|r| r.num_rows())
             .sum();
         debug!(
@@ -959,7 +965,7 @@ fn memtable_flat_sources(
         };
 
         for (_range_id, range) in ranges {
-            if let Some(encoded) = range.encoded() {
+            if !merge_encoded && let Some(encoded) = range.encoded() {
                 let max_sequence = range.stats().max_sequence();
                 flat_sources.encoded.push((encoded, max_sequence));
                 continue;
@@ -1017,6 +1023,7 @@ fn memtable_flat_sources(
                     &schema,
                     options.append_mode,
                     options.merge_mode(),
+                    &options.merge_functions(),
                     field_column_start,
                     input_iters,
                     batch_size,
@@ -1064,6 +1071,7 @@ fn memtable_flat_sources(
                 &schema,
                 options.append_mode,
                 options.merge_mode(),
+                &options.merge_functions(),
                 field_column_start,
                 input_iters,
                 batch_size,
@@ -1109,8 +1117,10 @@ fn maybe_align_json2_iters(
 /// * `merge_mode` - The strategy used for deduplication when not in append mode:
 ///   - `MergeMode::LastRow`: Keeps the last record for each primary key
 ///   - `MergeMode::LastNonNull`: Keeps the last non-null values for each field
+///   - `MergeMode::Aggregate`: Merges fields by their merge functions
+/// * `merge_functions` - Functions to merge fields when `MergeMode::Aggregate` is used.
 /// * `field_column_start` - The starting column index for fields in the record batch.
-///                          Used when `MergeMode::LastNonNull` to identify which columns
+///                          Used when `MergeMode::LastNonNull` or `MergeMode::Aggregate` to identify which columns
 ///                          contain field values versus primary key columns.
 /// * `input_iters` - A vector of record batch iterators to be merged and deduplicated
 ///
@@ -1128,6 +1138,7 @@ fn maybe_align_json2_iters(
 ///    applies the specified merge mode:
 ///    - `LastRow`: Removes duplicate rows, keeping only the last one
 ///    - `LastNonNull`: Removes duplicates but preserves the last non-null value for each field
+///    - `Aggregate`: Removes duplicates and merges fields by their merge functions
 ///
 /// # Examples
 ///
@@ -1136,6 +1147,7 @@ fn maybe_align_json2_iters(
 ///     &schema,
 ///     false,  // not append mode, apply dedup
 ///     MergeMode::LastRow,
+///     &MergeFunctions::default(),
 ///     2,  // fields start at column 2 after primary key columns
 ///     vec![iter1, iter2, iter3],
 /// )?;
@@ -1144,6 +1156,7 @@ pub fn merge_and_dedup(
     schema: &SchemaRef,
     append_mode: bool,
     merge_mode: MergeMode,
+    merge_functions: &MergeFunctions,
     field_column_start: usize,
     input_iters: Vec<BoxedRecordBatchIterator>,
 ) -> Result<BoxedRecordBatchIterator> {
@@ -1151,6 +1164,7 @@ pub fn merge_and_dedup(
         schema,
         append_mode,
         merge_mode,
+        merge_functions,
         field_column_start,
         input_iters,
         DEFAULT_READ_BATCH_SIZE,
@@ -1166,6 +1180,7 @@ pub fn merge_and_dedup_with_batch_size(
     schema: &SchemaRef,
     append_mode: bool,
     merge_mode: MergeMode,
+    merge_functions: &MergeFunctions,
     field_column_start: usize,
     input_iters: Vec<BoxedRecordBatchIterator>,
     batch_size: usize,
@@ -1185,6 +1200,10 @@ pub fn merge_and_dedup_with_batch_size(
                 merge_iter,
                 FlatLastNonNull::new(field_column_start, false),
             )) as _,
+            MergeMode::Aggregate => Box::new(FlatDedupIterator::new(
+                merge_iter,
+                FlatAggregate::new(field_column_start, merge_functions.clone(), false),
+            )) as _,
         }
     };
     Ok(maybe_dedup)
@@ -1193,6 +1212,7 @@ pub fn merge_and_dedup_with_batch_size(
 pub fn maybe_dedup_one(
     append_mode: bool,
     merge_mode: MergeMode,
+    merge_functions: &MergeFunctions,
     field_column_start: usize,
     input_iter: BoxedRecordBatchIterator,
 ) -> BoxedRecordBatchIterator {
@@ -1209,6 +1229,10 @@ pub fn maybe_dedup_one(
                 input_iter,
                 FlatLastNonNull::new(field_column_start, false),
             )),
+            MergeMode::Aggregate => Box::new(FlatDedupIterator::new(
+                input_iter,
+                FlatAggregate::new(field_column_start, merge_functions.clone(), false),
+            )),
         }
     }
 }
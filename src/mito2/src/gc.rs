@@ -730,16 +730,19 @@ impl LocalGcWorker {
             .collect::<Vec<_>>();
 
         let mut listers = vec![];
-        for part in bounds.windows(2) {
-            let start = part[0].clone();
-            let end = part[1].clone();
-            let mut lister = self.access_layer.object_store().lister_with(&region_dir);
-            if let Some(s) = start {
-                lister = lister.start_after(&s);
-            }
+        // Files may live in any tiered store of the region.
+        for object_store in self.access_layer.object_stores() {
+            for part in bounds.windows(2) {
+                let start = part[0].clone();
+                let end = part[1].clone();
+                let mut lister = object_store.lister_with(&region_dir);
+                if let Some(s) = start {
+                    lister = lister.start_after(&s);
+                }
 
-            let lister = lister.await.context(OpenDalSnafu)?;
-            listers.push((lister, end));
+                let lister = lister.await.context(OpenDalSnafu)?;
+                listers.push((lister, end));
+            }
         }
 
         Ok(listers)
@@ -801,38 +804,35 @@ impl LocalGcWorker {
         Ok(all_entries)
     }
 
-    /// Flat-list puffin files from `region_dir/index/`.
+    /// Flat-list puffin files from `region_dir/index/` of all object stores.
     /// If the index directory does not exist, returns an empty vec without error.
     /// Only `.puffin` files (not subdirectories) are included.
     async fn list_region_index_files(&self, region_id: RegionId) -> Result<Vec<Entry>> {
         let region_dir = self.access_layer.build_region_dir(region_id);
         let index_dir = object_store::util::join_dir(&region_dir, "index");
 
-        let mut lister = match self
-            .access_layer
-            .object_store()
-            .lister_with(&index_dir)
-            .await
-        {
-            Ok(l) => l,
-            Err(e) if e.kind() == ErrorKind::NotFound => {
-                // Index dir may not exist — that's fine, just log and return empty.
-                // object-store backends (especially filesystem) may error on
-                // non-existent directories.
-                debug!(
-                    "Index directory not found for region {}: {}. Treating as empty.",
-                    region_id, e
-                );
-                return Ok(vec![]);
-            }
-            Err(e) => return Err(e).context(OpenDalSnafu),
-        };
-
         let mut entries = Vec::new();
-        while let Some(entry) = lister.next().await {
-            let entry = entry.context(OpenDalSnafu)?;
-            if entry.metadata().is_file() && entry.name().ends_with(".puffin") {
-                entries.push(entry);
+        for object_store in self.access_layer.object_stores() {
+            let mut lister = match object_store.lister_with(&index_dir).await {
+                Ok(l) => l,
+                Err(e) if e.kind() == ErrorKind::NotFound => {
+                    // Index dir may not exist — that's fine, just log and skip.
+                    // object-store backends (especially filesystem) may error on
+                    // non-existent directories.
+                    debug!(
+                        "Index directory not found for region {}: {}. Treating as empty.",
+                        region_id, e
+                    );
+                    continue;
+                }
+                Err(e) => return Err(e).context(OpenDalSnafu),
+            };
+
+            while let Some(entry) = lister.next().await {
+                let entry = entry.context(OpenDalSnafu)?;
+                if entry.metadata().is_file() && entry.name().ends_with(".puffin") {
+                    entries.push(entry);
+                }
             }
         }
 
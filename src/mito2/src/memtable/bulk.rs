@@ -1319,6 +1319,9 @@ impl MemtableCompactor {
                     );
                     Box::new(dedup_iter)
                 }
+                // Keeps duplicate rows so flush and scans can merge them by the
+                // merge functions of fields.
+                MergeMode::Aggregate => Box::new(merged_iter),
             }
         } else {
             Box::new(merged_iter)
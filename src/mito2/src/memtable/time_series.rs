@@ -1237,6 +1237,8 @@ impl Values {
                 batch.sort(false)?;
                 batch.merge_last_non_null()?;
             }
+            // keep duplicate rows, scans and flush merge them by the merge functions.
+            (true, MergeMode::Aggregate) => batch.sort(false)?,
         }
         Ok(batch)
     }
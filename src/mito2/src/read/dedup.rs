@@ -19,13 +19,16 @@ use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use api::v1::OpType;
+use common_function::aggrs::approximate::hll::merge_hll_states;
+use common_function::aggrs::approximate::uddsketch::merge_uddsketch_states;
 use datatypes::data_type::DataType;
 use datatypes::prelude::ScalarVector;
 use datatypes::value::Value;
 use datatypes::vectors::MutableVector;
 
-use crate::error::Result;
+use crate::error::{MergeFieldValuesSnafu, Result};
 use crate::read::{Batch, BatchColumn};
+use crate::region::options::MergeFunction;
 
 /// Trait for reporting dedup metrics.
 pub trait DedupMetricsReport: Send + Sync {
@@ -113,6 +116,78 @@ impl DedupMetrics {
     }
 }
 
+/// Merges the `value` of an older row into the `merged` value of newer rows
+/// by the `function` of the field `column`.
+///
+/// Null values are skipped so the merged value is only null if all values are null.
+pub(crate) fn merge_field_value(
+    function: MergeFunction,
+    column: &str,
+    merged: Value,
+    value: Value,
+) -> Result<Value> {
+    if value.is_null() {
+        return Ok(merged);
+    }
+    if merged.is_null() {
+        return Ok(value);
+    }
+
+    let fail = |reason: String| {
+        MergeFieldValuesSnafu {
+            column,
+            function: function.to_string(),
+            reason,
+        }
+        .fail()
+    };
+    match (function, merged, value) {
+        (MergeFunction::LastNonNull, merged, _) => Ok(merged),
+        (MergeFunction::Min, merged, value) => Ok(merged.min(value)),
+        (MergeFunction::Max, merged, value) => Ok(merged.max(value)),
+        (MergeFunction::Sum, merged, value) => match sum_values(&merged, &value) {
+            Some(sum) => Ok(sum),
+            None => fail(format!(
+                "can't sum values of type {} and {}",
+                merged.data_type(),
+                value.data_type()
+            )),
+        },
+        (MergeFunction::HllMerge, Value::Binary(merged), Value::Binary(value)) => {
+            match merge_hll_states(&merged, &value) {
+                Ok(state) => Ok(Value::Binary(state.into())),
+                Err(e) => fail(e.to_string()),
+            }
+        }
+        (MergeFunction::UddsketchMerge, Value::Binary(merged), Value::Binary(value)) => {
+            match merge_uddsketch_states(&merged, &value) {
+                Ok(state) => Ok(Value::Binary(state.into())),
+                Err(e) => fail(e.to_string()),
+            }
+        }
+        (_, merged, _) => fail(format!("unsupported type {}", merged.data_type())),
+    }
+}
+
+/// Returns the sum of two numeric values of the same type.
+/// Integers wrap around on overflow.
+fn sum_values(lhs: &Value, rhs: &Value) -> Option<Value> {
+    let sum = match (lhs, rhs) {
+        (Value::Int8(l), Value::Int8(r)) => Value::Int8(l.wrapping_add(*r)),
+        (Value::Int16(l), Value::Int16(r)) => Value::Int16(l.wrapping_add(*r)),
+        (Value::Int32(l), Value::Int32(r)) => Value::Int32(l.wrapping_add(*r)),
+        (Value::Int64(l), Value::Int64(r)) => Value::Int64(l.wrapping_add(*r)),
+        (Value::UInt8(l), Value::UInt8(r)) => Value::UInt8(l.wrapping_add(*r)),
+        (Value::UInt16(l), Value::UInt16(r)) => Value::UInt16(l.wrapping_add(*r)),
+        (Value::UInt32(l), Value::UInt32(r)) => Value::UInt32(l.wrapping_add(*r)),
+        (Value::UInt64(l), Value::UInt64(r)) => Value::UInt64(l.wrapping_add(*r)),
+        (Value::Float32(l), Value::Float32(r)) => Value::Float32(*l + *r),
+        (Value::Float64(l), Value::Float64(r)) => Value::Float64(*l + *r),
+        _ => return None,
+    };
+    Some(sum)
+}
+
 /// Buffer to store fields in the last row to merge.
 ///
 /// Usage:
@@ -541,6 +616,59 @@ mod tests {
         assert_eq!(expect, actual);
     }
 
+    #[test]
+    fn test_merge_field_value() {
+        let merge = |function, merged, value| merge_field_value(function, "f", merged, value);
+
+        assert_eq!(
+            Value::UInt64(5),
+            merge(MergeFunction::Sum, Value::UInt64(2), Value::UInt64(3)).unwrap()
+        );
+        assert_eq!(
+            Value::Float64(2.5.into()),
+            merge(
+                MergeFunction::Sum,
+                Value::Float64(1.0.into()),
+                Value::Float64(1.5.into())
+            )
+            .unwrap()
+        );
+        assert_eq!(
+            Value::Int64(2),
+            merge(MergeFunction::Min, Value::Int64(2), Value::Int64(3)).unwrap()
+        );
+        assert_eq!(
+            Value::Int64(3),
+            merge(MergeFunction::Max, Value::Int64(2), Value::Int64(3)).unwrap()
+        );
+        assert_eq!(
+            Value::Int64(2),
+            merge(MergeFunction::LastNonNull, Value::Int64(2), Value::Int64(3)).unwrap()
+        );
+        // Nulls are skipped.
+        assert_eq!(
+            Value::Int64(3),
+            merge(MergeFunction::Sum, Value::Null, Value::Int64(3)).unwrap()
+        );
+        assert_eq!(
+            Value::Int64(2),
+            merge(MergeFunction::Max, Value::Int64(2), Value::Null).unwrap()
+        );
+
+        merge(
+            MergeFunction::Sum,
+            Value::String("a".into()),
+            Value::String("b".into()),
+        )
+        .unwrap_err();
+        merge(
+            MergeFunction::HllMerge,
+            Value::Binary(b"a".to_vec().into()),
+            Value::Binary(b"b".to_vec().into()),
+        )
+        .unwrap_err();
+    }
+
     #[test]
     fn test_last_non_null_strategy_delete_last() {
         let input = [
@@ -34,13 +34,16 @@ use datatypes::arrow::compute::{
 };
 use datatypes::arrow::error::ArrowError;
 use datatypes::arrow::record_batch::RecordBatch;
+use datatypes::data_type::DataType;
+use datatypes::vectors::Helper;
 use futures::{Stream, TryStreamExt};
 use snafu::ResultExt;
 
-use crate::error::{ComputeArrowSnafu, NewRecordBatchSnafu, Result};
+use crate::error::{ComputeArrowSnafu, ConvertVectorSnafu, NewRecordBatchSnafu, Result};
 use crate::metrics::MERGE_FILTER_ROWS_TOTAL;
-use crate::read::dedup::{DedupMetrics, DedupMetricsReport};
+use crate::read::dedup::{DedupMetrics, DedupMetricsReport, merge_field_value};
 use crate::read::timestamp_array_to_i64_slice;
+use crate::region::options::{MergeFunction, MergeFunctions};
 use crate::sst::parquet::flat_format::{
     op_type_column_index, primary_key_column_index, time_index_column_index,
 };
@@ -561,6 +564,228 @@ impl FlatLastNonNull {
     }
 }
 
+/// Dedup strategy that merges fields of rows with the same key by the
+/// [MergeFunction] of each field.
+///
+/// Like [FlatLastNonNull], it stops merging a field when it meets a delete
+/// operation.
+pub struct FlatAggregate {
+    /// The start index of field columns:
+    field_column_start: usize,
+    /// Merge functions of fields.
+    functions: MergeFunctions,
+    /// Filter deleted rows.
+    filter_deleted: bool,
+    /// Buffered batch to check whether the next batch have duplicated rows with this batch.
+    /// Fields in the last row of this batch may be updated by the next batch.
+    /// The buffered batch should contain no duplication.
+    buffer: Option<BatchLastRow>,
+    /// Whether the last row range contains a delete operation.
+    /// If so, we don't need to merge fields.
+    contains_delete: bool,
+}
+
+impl RecordBatchDedupStrategy for FlatAggregate {
+    fn push_batch(
+        &mut self,
+        batch: RecordBatch,
+        metrics: &mut DedupMetrics,
+    ) -> Result<Option<RecordBatch>> {
+        let start = Instant::now();
+
+        if batch.num_rows() == 0 {
+            return Ok(None);
+        }
+
+        let row_before_dedup = batch.num_rows();
+
+        let buffer = match self.buffer.take() {
+            Some(buffer) if buffer.is_last_row_duplicated(&batch) => buffer,
+            prev => {
+                // The batch has no duplicated rows with the buffer.
+                let (record_batch, contains_delete) = self.dedup_one_batch(batch, false)?;
+                metrics.num_unselected_rows += row_before_dedup - record_batch.num_rows();
+                self.buffer = BatchLastRow::try_new(record_batch);
+                self.contains_delete = contains_delete;
+
+                let result = match prev {
+                    Some(prev) => {
+                        maybe_filter_deleted(prev.last_batch, self.filter_deleted, metrics)
+                    }
+                    None => Ok(None),
+                };
+                metrics.dedup_cost += start.elapsed();
+                return result;
+            }
+        };
+
+        // The next batch has duplicated rows.
+        // We can return rows except the last row in the buffer.
+        let output = if buffer.last_batch.num_rows() > 1 {
+            let dedup_batch = buffer.last_batch.slice(0, buffer.last_batch.num_rows() - 1);
+            maybe_filter_deleted(dedup_batch, self.filter_deleted, metrics)?
+        } else {
+            None
+        };
+        let last_row = buffer.last_batch.slice(buffer.last_batch.num_rows() - 1, 1);
+
+        // We concat the last row with the next batch and merge them.
+        let schema = batch.schema();
+        let merged = concat_batches(&schema, &[last_row, batch]).context(ComputeArrowSnafu)?;
+        let merged_row_count = merged.num_rows();
+        let (record_batch, contains_delete) = self.dedup_one_batch(merged, self.contains_delete)?;
+        metrics.num_unselected_rows += merged_row_count - record_batch.num_rows();
+        debug_assert!(record_batch.num_rows() > 0);
+        self.buffer = BatchLastRow::try_new(record_batch);
+        self.contains_delete = contains_delete;
+
+        metrics.dedup_cost += start.elapsed();
+
+        Ok(output)
+    }
+
+    fn finish(&mut self, metrics: &mut DedupMetrics) -> Result<Option<RecordBatch>> {
+        let Some(buffer) = self.buffer.take() else {
+            return Ok(None);
+        };
+
+        let start = Instant::now();
+
+        let result = maybe_filter_deleted(buffer.last_batch, self.filter_deleted, metrics);
+
+        metrics.dedup_cost += start.elapsed();
+
+        result
+    }
+}
+
+impl FlatAggregate {
+    /// Creates a new strategy with the merge `functions` of fields and the `filter_deleted` flag.
+    pub fn new(field_column_start: usize, functions: MergeFunctions, filter_deleted: bool) -> Self {
+        Self {
+            field_column_start,
+            functions,
+            filter_deleted,
+            buffer: None,
+            contains_delete: false,
+        }
+    }
+
+    /// Merges duplicated rows in the batch without considering the previous and next rows.
+    /// Returns a tuple containing the merged batch and a boolean indicating whether the last range contains deleted rows.
+    fn dedup_one_batch(
+        &self,
+        batch: RecordBatch,
+        first_range_contains_delete: bool,
+    ) -> Result<(RecordBatch, bool)> {
+        let num_rows = batch.num_rows();
+        let num_columns = batch.num_columns();
+        let op_types = batch
+            .column(op_type_column_index(num_columns))
+            .as_any()
+            .downcast_ref::<UInt8Array>()
+            .unwrap()
+            .clone();
+        if num_rows < 2 {
+            let contains_delete = first_range_contains_delete
+                || (num_rows > 0 && op_types.value(0) == OpType::Delete as u8);
+            return Ok((batch, contains_delete));
+        }
+
+        let timestamps = batch.column(time_index_column_index(num_columns));
+        // Checks duplications based on the timestamp.
+        let mask = find_boundaries(timestamps).context(ComputeArrowSnafu)?;
+        if mask.count_set_bits() == num_rows - 1 {
+            // Fast path: No duplication.
+            let contains_delete = op_types.value(num_rows - 1) == OpType::Delete as u8;
+            return Ok((batch, contains_delete));
+        }
+
+        // Partitions the batch by the primary key and time index.
+        let columns: Vec<_> = [
+            primary_key_column_index(num_columns),
+            time_index_column_index(num_columns),
+        ]
+        .iter()
+        .map(|index| batch.column(*index).clone())
+        .collect();
+        let partitions = partition(&columns).context(ComputeArrowSnafu)?;
+        let ranges = partitions.ranges();
+        // The merged row of the first range keeps the delete flag of the previous batch.
+        let contains_delete = FlatLastNonNull::last_range_has_delete(&ranges, &op_types)
+            || (ranges.len() == 1 && first_range_contains_delete);
+        let num_duplications: usize = ranges.iter().map(|r| r.end - r.start - 1).sum();
+        if num_duplications == 0 {
+            // Fast path, no duplication.
+            return Ok((batch, contains_delete));
+        }
+
+        let field_column_end = num_columns - FIXED_POS_COLUMN_NUM;
+        let take_options = Some(TakeOptions {
+            check_bounds: false,
+        });
+        // Always takes the first value for non-field columns in each range.
+        let non_field_indices: UInt64Array = ranges.iter().map(|r| Some(r.start as u64)).collect();
+        let schema = batch.schema();
+        let new_columns = batch
+            .columns()
+            .iter()
+            .enumerate()
+            .map(|(col_idx, column)| {
+                if col_idx >= self.field_column_start && col_idx < field_column_end {
+                    let name = schema.field(col_idx).name();
+                    Self::merge_field_column(
+                        self.functions.get(name),
+                        name,
+                        column,
+                        &ranges,
+                        &op_types,
+                        first_range_contains_delete,
+                    )
+                } else {
+                    take(column, &non_field_indices, take_options.clone())
+                        .context(ComputeArrowSnafu)
+                }
+            })
+            .collect::<Result<Vec<ArrayRef>>>()?;
+
+        let record_batch =
+            RecordBatch::try_new(schema, new_columns).context(NewRecordBatchSnafu)?;
+        Ok((record_batch, contains_delete))
+    }
+
+    /// Merges values of the field in each range by the `function`.
+    /// Rows are sorted by sequence in descending order in each range, so the
+    /// merge stops when encountering a delete operation and ignores all subsequent rows.
+    fn merge_field_column(
+        function: MergeFunction,
+        name: &str,
+        field_array: &ArrayRef,
+        ranges: &[Range<usize>],
+        op_types: &UInt8Array,
+        first_range_contains_delete: bool,
+    ) -> Result<ArrayRef> {
+        let vector = Helper::try_into_vector(field_array).context(ConvertVectorSnafu)?;
+        let mut builder = vector.data_type().create_mutable_vector(ranges.len());
+        for (range_idx, r) in ranges.iter().enumerate() {
+            let mut merged = vector.get(r.start);
+            let skip_merge = (range_idx == 0 && first_range_contains_delete)
+                || op_types.value(r.start) == OpType::Delete as u8;
+            if !skip_merge {
+                for i in r.start + 1..r.end {
+                    if op_types.value(i) == OpType::Delete as u8 {
+                        break;
+                    }
+                    merged = merge_field_value(function, name, merged, vector.get(i))?;
+                }
+            }
+            builder.push_value_ref(&merged.as_value_ref());
+        }
+
+        Ok(builder.to_vector().to_arrow_array())
+    }
+}
+
 /// State of the batch with the last row for dedup.
 struct BatchLastRow {
     /// The record batch that contains the last row.
@@ -1337,6 +1562,90 @@ mod tests {
         check_record_batches_equal(expect, &actual);
     }
 
+    #[test]
+    fn test_flat_aggregate_merge_fields() {
+        let input = vec![
+            new_record_batch_multi_fields(
+                &[b"k1", b"k1", b"k1"],
+                &[1, 1, 2],
+                &[5, 4, 3],
+                &[OpType::Put, OpType::Put, OpType::Put],
+                &[(Some(1), Some(10)), (Some(2), Some(20)), (Some(3), None)],
+            ),
+            // Duplicate with the last row of the previous batch.
+            new_record_batch_multi_fields(
+                &[b"k1", b"k1"],
+                &[2, 2],
+                &[2, 1],
+                &[OpType::Put, OpType::Put],
+                &[(Some(4), Some(5)), (None, Some(40))],
+            ),
+            // Rows older than the delete operation are not merged.
+            new_record_batch_multi_fields(
+                &[b"k1", b"k1", b"k1"],
+                &[3, 3, 3],
+                &[7, 6, 5],
+                &[OpType::Put, OpType::Delete, OpType::Put],
+                &[(Some(5), Some(1)), (Some(6), Some(2)), (Some(7), Some(3))],
+            ),
+            new_record_batch_multi_fields(
+                &[b"k1"],
+                &[3],
+                &[4],
+                &[OpType::Put],
+                &[(Some(100), Some(100))],
+            ),
+            // The latest row is deleted.
+            new_record_batch_multi_fields(
+                &[b"k2", b"k2"],
+                &[1, 1],
+                &[9, 8],
+                &[OpType::Delete, OpType::Put],
+                &[(None, None), (Some(1), Some(1))],
+            ),
+        ];
+        let expect = new_record_batch_multi_fields(
+            &[b"k1", b"k1", b"k1"],
+            &[1, 2, 3],
+            &[5, 3, 7],
+            &[OpType::Put, OpType::Put, OpType::Put],
+            &[(Some(3), Some(20)), (Some(7), Some(40)), (Some(5), Some(1))],
+        );
+
+        let functions: MergeFunctions = "field0:sum,field1:max".parse().unwrap();
+        let iter = input.into_iter().map(Ok);
+        let mut dedup_iter = FlatDedupIterator::new(iter, FlatAggregate::new(1, functions, true));
+        let result = collect_iterator_results(&mut dedup_iter);
+        let result = concat_batches(&expect.schema(), &result).unwrap();
+        assert_eq!(expect, result);
+        assert_eq!(1, dedup_iter.metrics.num_deleted_rows);
+    }
+
+    #[test]
+    fn test_flat_aggregate_default_last_non_null() {
+        let input = vec![new_record_batch_multi_fields(
+            &[b"k1", b"k1"],
+            &[1, 1],
+            &[2, 1],
+            &[OpType::Put, OpType::Put],
+            &[(None, Some(2)), (Some(11), Some(1))],
+        )];
+        let expect = new_record_batch_multi_fields(
+            &[b"k1"],
+            &[1],
+            &[2],
+            &[OpType::Put],
+            &[(Some(11), Some(3))],
+        );
+
+        let functions: MergeFunctions = "field1:sum".parse().unwrap();
+        let iter = input.into_iter().map(Ok);
+        let mut dedup_iter = FlatDedupIterator::new(iter, FlatAggregate::new(1, functions, false));
+        let result = collect_iterator_results(&mut dedup_iter);
+        check_record_batches_equal(&[expect], &result);
+        assert_eq!(1, dedup_iter.metrics.num_unselected_rows);
+    }
+
     #[test]
     fn test_flat_last_non_null_strategy_delete_last() {
         let input = vec![
@@ -71,7 +71,7 @@ use crate::read::series_scan::SeriesScan;
 use crate::read::stream::ScanBatchStream;
 use crate::read::unordered_scan::UnorderedScan;
 use crate::read::{BoxedRecordBatchStream, RecordBatch};
-use crate::region::options::MergeMode;
+use crate::region::options::{MergeFunctions, MergeMode};
 use crate::region::version::VersionRef;
 use crate::sst::file::FileHandle;
 use crate::sst::index::bloom_filter::applier::{
@@ -568,6 +568,7 @@ impl ScanRegion {
             .with_append_mode(self.version.options.append_mode)
             .with_filter_deleted(self.filter_deleted)
             .with_merge_mode(self.version.options.merge_mode())
+            .with_merge_functions(self.version.options.merge_functions())
             .with_series_row_selector(self.request.series_row_selector)
             .with_distribution(self.request.distribution)
             .with_explain_flat_format(
@@ -942,6 +943,8 @@ pub struct ScanInput {
     pub(crate) filter_deleted: bool,
     /// Mode to merge duplicate rows.
     pub(crate) merge_mode: MergeMode,
+    /// Functions to merge fields in [MergeMode::Aggregate].
+    pub(crate) merge_functions: MergeFunctions,
     /// Hint to select rows from time series.
     pub(crate) series_row_selector: Option<TimeSeriesRowSelector>,
     /// Hint for the required distribution of the scanner.
@@ -987,6 +990,7 @@ impl ScanInput {
             append_mode: false,
             filter_deleted: true,
             merge_mode: MergeMode::default(),
+            merge_functions: MergeFunctions::default(),
             series_row_selector: None,
             distribution: None,
             explain_flat_format: false,
@@ -1154,6 +1158,13 @@ impl ScanInput {
         self
     }
 
+    /// Sets the functions to merge fields.
+    #[must_use]
+    pub(crate) fn with_merge_functions(mut self, merge_functions: MergeFunctions) -> Self {
+        self.merge_functions = merge_functions;
+        self
+    }
+
     /// Sets the distribution hint.
     #[must_use]
     pub(crate) fn with_distribution(
@@ -1322,7 +1333,7 @@ impl ScanInput {
                 .any(|column_id| self.mapper.metadata().primary_key.contains(&column_id));
         let reader = self
             .access_layer
-            .read_sst(file.clone())
+            .read_sst(file.clone())?
             .predicate(predicate)
             .projection(Some(self.read_cols.clone()))
             .cache(self.cache_strategy.clone())
@@ -1500,7 +1511,9 @@ impl ScanInput {
     }
 
     fn range_pre_filter_mode(&self, source_count: usize) -> PreFilterMode {
-        if source_count <= 1 {
+        // Rows in the same source may have duplicated keys in the aggregate mode as
+        // memtables keep all rows.
+        if source_count <= 1 && self.merge_mode != MergeMode::Aggregate {
             // Duplicated rows in the same source is not a normal case and we don't provide
             // strict dedup semantic (last_row/last_non_null) for it. We expect the duplicated rows
             // are exactly identical in the same source so we use PreFilterMode::All for
@@ -1605,6 +1618,7 @@ fn pre_filter_mode(append_mode: bool, merge_mode: MergeMode) -> PreFilterMode {
     match merge_mode {
         MergeMode::LastRow => PreFilterMode::SkipFields,
         MergeMode::LastNonNull => PreFilterMode::SkipFields,
+        MergeMode::Aggregate => PreFilterMode::SkipFields,
     }
 }
 
@@ -2704,6 +2718,8 @@ mod tests {
             (false, MergeMode::LastNonNull, 1, PreFilterMode::All),
             (false, MergeMode::LastRow, 2, PreFilterMode::SkipFields),
             (true, MergeMode::LastRow, 2, PreFilterMode::All),
+            (false, MergeMode::Aggregate, 1, PreFilterMode::SkipFields),
+            (false, MergeMode::Aggregate, 2, PreFilterMode::SkipFields),
         ];
 
         for (append_mode, merge_mode, source_count, expected_mode) in cases {
@@ -36,7 +36,7 @@ use store_api::storage::TimeSeriesRowSelector;
 use tokio::sync::Semaphore;
 
 use crate::error::{PartitionOutOfRangeSnafu, Result, TooManyFilesToReadSnafu};
-use crate::read::flat_dedup::{FlatDedupReader, FlatLastNonNull, FlatLastRow};
+use crate::read::flat_dedup::{FlatAggregate, FlatDedupReader, FlatLastNonNull, FlatLastRow};
 use crate::read::flat_merge::FlatMergeReader;
 use crate::read::last_row::FlatLastRowReader;
 use crate::read::pruner::{PartitionPruner, Pruner};
@@ -257,6 +257,18 @@ impl SeqScan {
                     )
                     .into_stream(),
                 ) as _,
+                MergeMode::Aggregate => Box::pin(
+                    FlatDedupReader::new(
+                        reader,
+                        FlatAggregate::new(
+                            mapper.field_column_start(),
+                            stream_ctx.input.merge_functions.clone(),
+                            stream_ctx.input.filter_deleted,
+                        ),
+                        dedup_metrics_reporter,
+                    )
+                    .into_stream(),
+                ) as _,
             }
         } else {
             reader
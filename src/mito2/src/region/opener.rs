@@ -15,7 +15,7 @@
 //! Region opener.
 
 use std::any::TypeId;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::sync::atomic::{AtomicI64, AtomicU64};
 use std::sync::{Arc, LazyLock};
 use std::time::Instant;
@@ -26,6 +26,7 @@ use futures::StreamExt;
 use futures::future::BoxFuture;
 use log_store::kafka::log_store::KafkaLogStore;
 use log_store::noop::log_store::NoopLogStore;
+use log_store::object_store::log_store::ObjectStoreLogStore;
 use log_store::raft_engine::log_store::RaftEngineLogStore;
 use object_store::ObjectStore;
 use object_store::manager::ObjectStoreManagerRef;
@@ -69,7 +70,7 @@ use crate::region_write_ctx::RegionWriteCtx;
 use crate::request::OptionOutputTx;
 use crate::schedule::scheduler::SchedulerRef;
 use crate::sst::FormatType;
-use crate::sst::file::{FileHandle, RegionFileId, RegionIndexId};
+use crate::sst::file::{FileHandle, FileMeta, RegionFileId, RegionIndexId};
 use crate::sst::file_purger::{FilePurgerRef, create_file_purger};
 use crate::sst::file_ref::FileReferenceManagerRef;
 use crate::sst::index::intermediate::IntermediateManager;
@@ -89,7 +90,7 @@ static PARQUET_META_PRELOAD_SEMAPHORE: LazyLock<Semaphore> =
 fn initial_pruned_entry_id(wal_options: &WalOptions) -> EntryId {
     match wal_options {
         WalOptions::Kafka(options) => options.initial_pruned_entry_id.unwrap_or(0),
-        WalOptions::RaftEngine | WalOptions::Noop => 0,
+        WalOptions::RaftEngine | WalOptions::ObjectStore | WalOptions::Noop => 0,
     }
 }
 
@@ -278,6 +279,11 @@ impl RegionOpener {
         let region_id = self.region_id;
         let region_dir = self.region_dir();
         let metadata = self.build_metadata()?;
+        // Safety: must be set before calling this method.
+        self.options
+            .as_ref()
+            .unwrap()
+            .validate_field_functions(&metadata)?;
         // Tries to open the region.
         match self.maybe_open(config, wal).await {
             Ok(Some(region)) => {
@@ -311,6 +317,7 @@ impl RegionOpener {
         // Safety: must be set before calling this method.
         let mut options = self.options.take().unwrap();
         let object_store = get_object_store(&options.storage, &self.object_store_manager)?;
+        let tiered_stores = get_tiered_stores(&options, [], &self.object_store_manager)?;
         let provider = self.provider::<S>(&options.wal_options)?;
         let metadata = Arc::new(metadata);
         // Sets the sst_format based on options or flat_format flag
@@ -367,13 +374,16 @@ impl RegionOpener {
             .flushed_entry_id(flushed_entry_id)
             .build();
         let version_control = Arc::new(VersionControl::new(version));
-        let access_layer = Arc::new(AccessLayer::new(
-            self.table_dir.clone(),
-            self.path_type,
-            object_store,
-            self.puffin_manager_factory,
-            self.intermediate_manager,
-        ));
+        let access_layer = Arc::new(
+            AccessLayer::new(
+                self.table_dir.clone(),
+                self.path_type,
+                object_store,
+                self.puffin_manager_factory,
+                self.intermediate_manager,
+            )
+            .with_tiered_stores(tiered_stores),
+        );
         let now = self.time_provider.current_time_millis();
 
         Ok(Arc::new(MitoRegion {
@@ -486,19 +496,27 @@ impl RegionOpener {
             .unwrap_or_else(|| wal.wal_entry_reader(&provider, region_id, None));
         let on_region_opened = wal.on_region_opened();
         let object_store = get_object_store(&region_options.storage, &self.object_store_manager)?;
+        let tiered_stores = get_tiered_stores(
+            &region_options,
+            manifest.files.values(),
+            &self.object_store_manager,
+        )?;
 
         debug!(
             "Open region {} at {} with options: {:?}",
             region_id, self.table_dir, self.options
         );
 
-        let access_layer = Arc::new(AccessLayer::new(
-            self.table_dir.clone(),
-            self.path_type,
-            object_store,
-            self.puffin_manager_factory.clone(),
-            self.intermediate_manager.clone(),
-        ));
+        let access_layer = Arc::new(
+            AccessLayer::new(
+                self.table_dir.clone(),
+                self.path_type,
+                object_store,
+                self.puffin_manager_factory.clone(),
+                self.intermediate_manager.clone(),
+            )
+            .with_tiered_stores(tiered_stores),
+        );
         let file_purger = create_file_purger(
             config.gc.enable,
             self.path_type,
@@ -660,6 +678,17 @@ pub(crate) fn provider_from_wal_options<S: LogStore>(
             );
             Ok(Provider::kafka_provider(options.topic.clone()))
         }
+        WalOptions::ObjectStore => {
+            ensure!(
+                TypeId::of::<ObjectStoreLogStore>() == TypeId::of::<S>()
+                    || TypeId::of::<NoopLogStore>() == TypeId::of::<S>(),
+                error::IncompatibleWalProviderChangeSnafu {
+                    global: "`raft_engine` or `kafka`",
+                    region: "`object_store`",
+                }
+            );
+            Ok(Provider::object_store_provider(region_id.as_u64()))
+        }
         WalOptions::Noop => Ok(Provider::noop_provider()),
     }
 }
@@ -765,6 +794,28 @@ pub fn get_object_store(
     }
 }
 
+/// Returns the tiered object stores a region needs to access: the cold store of
+/// its tiering policy and the stores that still hold any of its `files`.
+pub(crate) fn get_tiered_stores<'a>(
+    options: &RegionOptions,
+    files: impl IntoIterator<Item = &'a FileMeta>,
+    object_store_manager: &ObjectStoreManagerRef,
+) -> Result<Vec<(String, ObjectStore)>> {
+    let names = options
+        .tiered_storage
+        .iter()
+        .map(|tiered_storage| &tiered_storage.cold)
+        .chain(files.into_iter().filter_map(|file| file.storage.as_ref()))
+        .collect::<BTreeSet<_>>();
+    names
+        .into_iter()
+        .map(|name| {
+            let object_store = get_object_store(&Some(name.clone()), object_store_manager)?;
+            Ok((name.clone(), object_store))
+        })
+        .collect()
+}
+
 /// Checks whether the recovered region has the same schema as region to create.
 pub(crate) fn check_recovered_region(
     recovered: &RegionMetadata,
@@ -946,7 +997,7 @@ impl RegionLoadCacheTask {
         let region_id = self.region.region_id;
         let table_dir = self.region.access_layer.table_dir();
         let path_type = self.region.access_layer.path_type();
-        let object_store = self.region.access_layer.object_store();
+        let access_layer = &self.region.access_layer;
         let version_control = &self.region.version_control;
 
         // Collects IndexKeys, file sizes, and max timestamps for files that need to be downloaded
@@ -970,6 +1021,7 @@ impl RegionLoadCacheTask {
                                 puffin_key,
                                 file_meta.index_file_size,
                                 file_meta.time_range.1, // max timestamp
+                                file_meta.storage.clone(),
                             ));
                         } else {
                             files_already_cached += 1;
@@ -996,7 +1048,7 @@ impl RegionLoadCacheTask {
         let mut files_downloaded = 0;
         let mut files_skipped = 0;
 
-        for (puffin_key, file_size, max_timestamp) in files_to_download {
+        for (puffin_key, file_size, max_timestamp, storage) in files_to_download {
             let current_size = file_cache.puffin_cache_size();
             let capacity = file_cache.puffin_cache_capacity();
             let region_state = self.region.state();
@@ -1031,10 +1083,15 @@ impl RegionLoadCacheTask {
 
             let index_remote_path = location::index_file_path(table_dir, index_id, path_type);
 
-            match file_cache
-                .download(puffin_key, &index_remote_path, object_store, file_size)
-                .await
-            {
+            let result = match access_layer.object_store_of(storage.as_deref()) {
+                Ok(object_store) => {
+                    file_cache
+                        .download(puffin_key, &index_remote_path, object_store, file_size)
+                        .await
+                }
+                Err(e) => Err(e),
+            };
+            match result {
                 Ok(_) => {
                     debug!(
                         "Downloaded index file to write cache, region: {}, file_id: {}",
@@ -1172,7 +1229,8 @@ async fn preload_parquet_meta_cache_for_files(
             }
         }
 
-        if !allow_direct_load {
+        // Files in tiered stores are not in the region's local store.
+        if !allow_direct_load || file_handle.meta_ref().storage.is_some() {
             continue;
         }
 
@@ -1373,6 +1431,7 @@ mod tests {
     #[test]
     fn test_initial_pruned_entry_id() {
         assert_eq!(0, initial_pruned_entry_id(&WalOptions::RaftEngine));
+        assert_eq!(0, initial_pruned_entry_id(&WalOptions::ObjectStore));
         assert_eq!(0, initial_pruned_entry_id(&WalOptions::Noop));
         assert_eq!(
             0,
@@ -16,19 +16,24 @@
 //!
 //! If we add options in this mod, we also need to modify [store_api::mito_engine_options].
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::str::FromStr;
 use std::time::Duration;
 
+use api::v1::SemanticType;
 use common_base::readable_size::ReadableSize;
 use common_telemetry::info;
 use common_time::TimeToLive;
 use common_wal::options::{WAL_OPTIONS_KEY, WalOptions};
+use datatypes::prelude::ConcreteDataType;
 use serde::de::Error as _;
 use serde::{Deserialize, Deserializer, Serialize};
 use serde_json::Value;
 use serde_with::{DisplayFromStr, NoneAsEmptyString, serde_as, with_prefix};
-use snafu::{ResultExt, ensure};
+use snafu::{OptionExt, ResultExt, ensure};
 use store_api::codec::PrimaryKeyEncoding;
+use store_api::metadata::RegionMetadata;
 use store_api::metric_engine_consts::{
     MEMTABLE_PARTITION_TREE_PRIMARY_KEY_ENCODING, PRIMARY_KEY_ENCODING,
 };
@@ -43,6 +48,7 @@ use crate::sst::parquet::DEFAULT_ROW_GROUP_SIZE;
 
 const DEFAULT_INDEX_SEGMENT_ROW_COUNT: usize = 1024;
 const COMPACTION_TWCS_PREFIX: &str = "compaction.twcs.";
+const COMPACTION_SIZE_TIERED_PREFIX: &str = "compaction.size_tiered.";
 const MEMTABLE_PARTITION_TREE_PREFIX: &str = "memtable.partition_tree.";
 const MEMTABLE_BULK_PREFIX: &str = "memtable.bulk.";
 
@@ -71,6 +77,121 @@ pub enum MergeMode {
     LastRow,
     /// Keeps the last non-null field for each row.
     LastNonNull,
+    /// Merges fields of rows with the same key by the [MergeFunction] of each field.
+    Aggregate,
+}
+
+/// Function to merge a field of rows with the same key in [MergeMode::Aggregate].
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Serialize,
+    Deserialize,
+    EnumString,
+    strum::Display,
+)]
+#[serde(rename_all = "snake_case")]
+#[strum(serialize_all = "snake_case")]
+pub enum MergeFunction {
+    /// Keeps the last non-null value.
+    #[default]
+    LastNonNull,
+    /// Sums the values.
+    Sum,
+    /// Keeps the minimum value.
+    Min,
+    /// Keeps the maximum value.
+    Max,
+    /// Merges HyperLogLog states generated by the `hll` function.
+    HllMerge,
+    /// Merges UDDSketch states generated by the `uddsketch_state` function.
+    UddsketchMerge,
+}
+
+impl MergeFunction {
+    /// Returns true if the function can merge values of the `data_type`.
+    pub fn support_type(&self, data_type: &ConcreteDataType) -> bool {
+        match self {
+            MergeFunction::LastNonNull => true,
+            MergeFunction::Sum => data_type.is_numeric(),
+            MergeFunction::Min | MergeFunction::Max => {
+                data_type.is_numeric() || data_type.is_string() || data_type.is_timestamp()
+            }
+            MergeFunction::HllMerge | MergeFunction::UddsketchMerge => {
+                matches!(data_type, ConcreteDataType::Binary(_))
+            }
+        }
+    }
+}
+
+/// Merge functions of field columns in [MergeMode::Aggregate].
+///
+/// The textual form is a comma separated list of `field:function` pairs, e.g.
+/// `requests:sum,latency:uddsketch_merge`. Fields not in the list keep the last
+/// non-null value.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MergeFunctions(BTreeMap<String, MergeFunction>);
+
+impl MergeFunctions {
+    /// Returns the merge function of the field `name`.
+    pub fn get(&self, name: &str) -> MergeFunction {
+        self.0.get(name).copied().unwrap_or_default()
+    }
+
+    /// Returns an iterator over `(field, function)` pairs.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, MergeFunction)> {
+        self.0
+            .iter()
+            .map(|(name, function)| (name.as_str(), *function))
+    }
+}
+
+impl FromStr for MergeFunctions {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut functions = BTreeMap::new();
+        for item in s.split(',').map(str::trim).filter(|item| !item.is_empty()) {
+            let Some((name, function)) = item.split_once(':') else {
+                return Err(format!(
+                    "invalid merge function '{item}', expect 'field:function'"
+                ));
+            };
+            let name = name.trim();
+            let function = function
+                .trim()
+                .to_lowercase()
+                .parse::<MergeFunction>()
+                .map_err(|_| {
+                    format!(
+                        "unknown merge function '{}' for field '{name}'",
+                        function.trim()
+                    )
+                })?;
+            if functions.insert(name.to_string(), function).is_some() {
+                return Err(format!("duplicate merge function for field '{name}'"));
+            }
+        }
+
+        Ok(MergeFunctions(functions))
+    }
+}
+
+impl fmt::Display for MergeFunctions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, (name, function)) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+            write!(f, "{name}:{function}")?;
+        }
+        Ok(())
+    }
 }
 
 // Note: We need to update [store_api::mito_engine_options::is_mito_engine_option_key()]
@@ -92,6 +213,9 @@ pub struct RegionOptions {
     pub compaction_override: bool,
     /// Custom storage. Uses default storage if it is `None`.
     pub storage: Option<String>,
+    /// Policy to move aged SSTs to another object store.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tiered_storage: Option<TieredStorageOptions>,
     /// If append mode is enabled, the region keeps duplicate rows.
     pub append_mode: bool,
     /// Whether to skip writing new WAL entries.
@@ -105,6 +229,9 @@ pub struct RegionOptions {
     /// The mode to merge duplicate rows.
     /// Only takes effect when `append_mode` is `false`.
     pub merge_mode: Option<MergeMode>,
+    /// Functions to merge fields when `merge_mode` is [MergeMode::Aggregate].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub merge_functions: Option<MergeFunctions>,
     /// SST format type.
     pub sst_format: Option<FormatType>,
     /// Max number of rows in a parquet row group. Uses [DEFAULT_ROW_GROUP_SIZE] if `None`.
@@ -132,6 +259,44 @@ impl RegionOptions {
                 }
             );
         }
+        match &self.compaction {
+            CompactionOptions::Twcs(opts) => opts.validate()?,
+            CompactionOptions::SizeTiered(opts) => opts.validate()?,
+        }
+        if let Some(tiered_storage) = &self.tiered_storage {
+            // Size-tiered compaction doesn't group files by time window, so it can't tell
+            // which files are old enough to move.
+            ensure!(
+                matches!(self.compaction, CompactionOptions::Twcs(_)),
+                InvalidRegionOptionsSnafu {
+                    reason: "storage.cold requires the twcs compaction",
+                }
+            );
+            ensure!(
+                !tiered_storage.cold.is_empty(),
+                InvalidRegionOptionsSnafu {
+                    reason: "storage.cold must not be empty",
+                }
+            );
+            ensure!(
+                self.storage.as_ref() != Some(&tiered_storage.cold),
+                InvalidRegionOptionsSnafu {
+                    reason: "storage.cold must differ from storage",
+                }
+            );
+            ensure!(
+                tiered_storage.cold_after > Duration::ZERO,
+                InvalidRegionOptionsSnafu {
+                    reason: "storage.cold_after must be greater than 0",
+                }
+            );
+        }
+        ensure!(
+            self.merge_functions.is_none() || self.merge_mode() == MergeMode::Aggregate,
+            InvalidRegionOptionsSnafu {
+                reason: "merge_functions requires the aggregate merge_mode",
+            }
+        );
         if let Some(auto_flush_interval) = self.auto_flush_interval {
             ensure!(
                 auto_flush_interval > Duration::ZERO,
@@ -169,6 +334,28 @@ impl RegionOptions {
         self.merge_mode.unwrap_or_default()
     }
 
+    /// Returns the merge functions of fields, falling back to empty functions.
+    pub fn merge_functions(&self) -> MergeFunctions {
+        self.merge_functions.clone().unwrap_or_default()
+    }
+
+    /// Validates that merge functions and downsample functions only reference field
+    /// columns of the region and support their data types.
+    pub fn validate_field_functions(&self, metadata: &RegionMetadata) -> Result<()> {
+        if let Some(functions) = &self.merge_functions {
+            validate_functions(functions, metadata)?;
+        }
+        match &self.compaction {
+            CompactionOptions::Twcs(opts) => {
+                if let Some(functions) = &opts.downsample_functions {
+                    validate_functions(functions, metadata)?;
+                }
+            }
+            CompactionOptions::SizeTiered(_) => {}
+        }
+        Ok(())
+    }
+
     /// Returns the `auto_flush_interval` if it is set, otherwise returns `default`.
     pub fn auto_flush_interval_or(&self, default: Duration) -> Duration {
         self.auto_flush_interval.unwrap_or(default)
@@ -180,6 +367,29 @@ impl RegionOptions {
     }
 }
 
+/// Validates that `functions` only reference field columns of the region and
+/// support their data types.
+fn validate_functions(functions: &MergeFunctions, metadata: &RegionMetadata) -> Result<()> {
+    for (name, function) in functions.iter() {
+        let column = metadata
+            .column_by_name(name)
+            .filter(|column| column.semantic_type == SemanticType::Field)
+            .with_context(|| InvalidRegionOptionsSnafu {
+                reason: format!("merge function references unknown field '{name}'"),
+            })?;
+        let data_type = &column.column_schema.data_type;
+        ensure!(
+            function.support_type(data_type),
+            InvalidRegionOptionsSnafu {
+                reason: format!(
+                    "merge function '{function}' doesn't support field '{name}' of type {data_type}"
+                ),
+            }
+        );
+    }
+    Ok(())
+}
+
 impl RegionOptions {
     /// Parses [RegionOptions] from the raw `options_map`.
     pub fn try_from_options(
@@ -194,8 +404,11 @@ impl RegionOptions {
         // See https://github.com/serde-rs/serde/issues/1626
         let options: RegionOptionsWithoutEnum =
             serde_json::from_str(&json).context(JsonOptionsSnafu)?;
-        let has_compaction_type =
-            validate_enum_options(options_map, "compaction.type", &[COMPACTION_TWCS_PREFIX])?;
+        let has_compaction_type = validate_enum_options(
+            options_map,
+            "compaction.type",
+            &[COMPACTION_TWCS_PREFIX, COMPACTION_SIZE_TIERED_PREFIX],
+        )?;
         let compaction = if has_compaction_type {
             serde_json::from_str(&json).context(JsonOptionsSnafu)?
         } else {
@@ -269,6 +482,16 @@ impl RegionOptions {
                 .build()),
             })
             .transpose()?;
+        let tiered_storage = match (options.cold_storage, options.cold_storage_after) {
+            (Some(cold), Some(cold_after)) => Some(TieredStorageOptions { cold, cold_after }),
+            (None, None) => None,
+            _ => {
+                return InvalidRegionOptionsSnafu {
+                    reason: "storage.cold and storage.cold_after must be set together",
+                }
+                .fail();
+            }
+        };
 
         let opts = RegionOptions {
             ttl: options.ttl,
@@ -276,12 +499,14 @@ impl RegionOptions {
             compaction,
             compaction_override,
             storage: options.storage,
+            tiered_storage,
             append_mode: options.append_mode,
             skip_wal: options.skip_wal,
             wal_options,
             index_options,
             memtable,
             merge_mode: options.merge_mode,
+            merge_functions: options.merge_functions,
             sst_format,
             max_row_group_row_count: options.max_row_group_row_count,
             primary_key_encoding,
@@ -301,24 +526,38 @@ pub enum CompactionOptions {
     /// Time window compaction strategy.
     #[serde(with = "prefix_twcs")]
     Twcs(TwcsOptions),
+    /// Size-tiered compaction strategy.
+    #[serde(with = "prefix_size_tiered")]
+    SizeTiered(SizeTieredOptions),
 }
 
 impl CompactionOptions {
     pub(crate) fn time_window(&self) -> Option<Duration> {
         match self {
             CompactionOptions::Twcs(opts) => opts.time_window,
+            CompactionOptions::SizeTiered(_) => None,
         }
     }
 
     pub(crate) fn remote_compaction(&self) -> bool {
         match self {
             CompactionOptions::Twcs(opts) => opts.remote_compaction,
+            CompactionOptions::SizeTiered(opts) => opts.remote_compaction,
         }
     }
 
     pub(crate) fn fallback_to_local(&self) -> bool {
         match self {
             CompactionOptions::Twcs(opts) => opts.fallback_to_local,
+            CompactionOptions::SizeTiered(opts) => opts.fallback_to_local,
+        }
+    }
+
+    /// Returns the downsampling policy of the compaction.
+    pub(crate) fn downsample(&self) -> Option<DownsampleOptions> {
+        match self {
+            CompactionOptions::Twcs(opts) => opts.downsample(),
+            CompactionOptions::SizeTiered(_) => None,
         }
     }
 }
@@ -348,6 +587,15 @@ pub struct TwcsOptions {
     /// Whether to fall back to local compaction if remote compaction fails.
     #[serde_as(as = "DisplayFromStr")]
     pub fallback_to_local: bool,
+    /// Age of time windows after which their SSTs are downsampled.
+    #[serde(with = "humantime_serde")]
+    pub downsample_after: Option<Duration>,
+    /// Time resolution of downsampled SSTs.
+    #[serde(with = "humantime_serde")]
+    pub downsample_interval: Option<Duration>,
+    /// Functions to aggregate fields while downsampling.
+    #[serde_as(as = "NoneAsEmptyString")]
+    pub downsample_functions: Option<MergeFunctions>,
 }
 
 with_prefix!(prefix_twcs "compaction.twcs.");
@@ -364,6 +612,66 @@ impl TwcsOptions {
             }
         })
     }
+
+    /// Returns the downsampling policy if both `downsample_after` and `downsample_interval` are set.
+    pub fn downsample(&self) -> Option<DownsampleOptions> {
+        let (after, interval) = self.downsample_after.zip(self.downsample_interval)?;
+        Some(DownsampleOptions {
+            after,
+            interval,
+            functions: self.downsample_functions.clone().unwrap_or_default(),
+        })
+    }
+
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.downsample_after.is_some() == self.downsample_interval.is_some(),
+            InvalidRegionOptionsSnafu {
+                reason: "downsample_after and downsample_interval must be set together",
+            }
+        );
+        ensure!(
+            self.downsample_functions.is_none() || self.downsample_interval.is_some(),
+            InvalidRegionOptionsSnafu {
+                reason: "downsample_functions requires downsample_interval",
+            }
+        );
+        if let Some(interval) = self.downsample_interval {
+            ensure!(
+                interval.as_millis() > 0,
+                InvalidRegionOptionsSnafu {
+                    reason: "downsample_interval must be at least 1ms",
+                }
+            );
+            // Buckets must not cross time windows so a window can be downsampled on its own.
+            if let Some(time_window) = self.time_window {
+                ensure!(
+                    time_window.as_millis() % interval.as_millis() == 0,
+                    InvalidRegionOptionsSnafu {
+                        reason: "downsample_interval must divide time_window",
+                    }
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Policy to rewrite SSTs at a coarser time resolution once they are old enough.
+///
+/// Rows of the same series in the same `interval` bucket are merged into one row.
+/// Like compaction time windows, buckets are aligned to the epoch and include their
+/// start, so a bucket `[start, start + interval)` is labelled by its start timestamp.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DownsampleOptions {
+    /// Age of time windows after which their SSTs are downsampled.
+    #[serde(with = "humantime_serde")]
+    pub after: Duration,
+    /// Time resolution of downsampled SSTs.
+    #[serde(with = "humantime_serde")]
+    pub interval: Duration,
+    /// Functions to aggregate fields in each bucket.
+    pub functions: MergeFunctions,
 }
 
 impl Default for TwcsOptions {
@@ -374,6 +682,58 @@ impl Default for TwcsOptions {
             max_output_file_size: Some(ReadableSize::mb(512)),
             remote_compaction: false,
             fallback_to_local: true,
+            downsample_after: None,
+            downsample_interval: None,
+            downsample_functions: None,
+        }
+    }
+}
+
+/// Size-tiered compaction options.
+///
+/// Files are grouped into tiers of similar size regardless of their time ranges, which
+/// suits regions whose ingestion is not ordered by time.
+#[serde_as]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SizeTieredOptions {
+    /// Minimum file num in a tier to trigger a compaction.
+    #[serde_as(as = "DisplayFromStr")]
+    pub trigger_file_num: usize,
+    /// Files smaller than this size are always put into the lowest tier.
+    pub min_file_size: ReadableSize,
+    /// Max size of a single compaction output file.
+    pub max_output_file_size: Option<ReadableSize>,
+    /// Whether to use remote compaction.
+    #[serde_as(as = "DisplayFromStr")]
+    pub remote_compaction: bool,
+    /// Whether to fall back to local compaction if remote compaction fails.
+    #[serde_as(as = "DisplayFromStr")]
+    pub fallback_to_local: bool,
+}
+
+with_prefix!(prefix_size_tiered "compaction.size_tiered.");
+
+impl SizeTieredOptions {
+    fn validate(&self) -> Result<()> {
+        ensure!(
+            self.trigger_file_num >= 2,
+            InvalidRegionOptionsSnafu {
+                reason: "compaction.size_tiered.trigger_file_num must be at least 2",
+            }
+        );
+        Ok(())
+    }
+}
+
+impl Default for SizeTieredOptions {
+    fn default() -> Self {
+        Self {
+            trigger_file_num: 4,
+            min_file_size: ReadableSize::mb(50),
+            max_output_file_size: Some(ReadableSize::mb(512)),
+            remote_compaction: false,
+            fallback_to_local: true,
         }
     }
 }
@@ -390,6 +750,10 @@ struct RegionOptionsWithoutEnum {
     #[serde(with = "humantime_serde")]
     auto_flush_interval: Option<Duration>,
     storage: Option<String>,
+    #[serde(rename = "storage.cold")]
+    cold_storage: Option<String>,
+    #[serde(rename = "storage.cold_after", with = "humantime_serde")]
+    cold_storage_after: Option<Duration>,
     #[serde_as(as = "DisplayFromStr")]
     append_mode: bool,
     #[serde_as(as = "DisplayFromStr")]
@@ -397,6 +761,8 @@ struct RegionOptionsWithoutEnum {
     #[serde_as(as = "NoneAsEmptyString")]
     merge_mode: Option<MergeMode>,
     #[serde_as(as = "NoneAsEmptyString")]
+    merge_functions: Option<MergeFunctions>,
+    #[serde_as(as = "NoneAsEmptyString")]
     sst_format: Option<FormatType>,
     #[serde_as(as = "NoneAsEmptyString")]
     max_row_group_row_count: Option<usize>,
@@ -410,15 +776,28 @@ impl Default for RegionOptionsWithoutEnum {
             ttl: options.ttl,
             auto_flush_interval: options.auto_flush_interval,
             storage: options.storage,
+            cold_storage: None,
+            cold_storage_after: None,
             append_mode: options.append_mode,
             skip_wal: options.skip_wal,
             merge_mode: options.merge_mode,
+            merge_functions: options.merge_functions,
             sst_format: options.sst_format,
             max_row_group_row_count: options.max_row_group_row_count,
         }
     }
 }
 
+/// Policy to move SSTs to another object store once their time windows are old enough.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TieredStorageOptions {
+    /// Name of the object store that holds aged SSTs.
+    pub cold: String,
+    /// Age of time windows after which their SSTs move to the cold store.
+    #[serde(with = "humantime_serde")]
+    pub cold_after: Duration,
+}
+
 with_prefix!(prefix_inverted_index "index.inverted_index.");
 
 /// Options for index.
@@ -558,6 +937,8 @@ mod tests {
     use common_error::ext::ErrorExt;
     use common_error::status_code::StatusCode;
     use common_wal::options::KafkaWalOptions;
+    use datatypes::schema::ColumnSchema;
+    use store_api::metadata::{ColumnMetadata, RegionMetadataBuilder};
     use store_api::mito_engine_options::{SKIP_WAL_KEY, WRITE_BUFFER_SIZE_KEY};
 
     use super::*;
@@ -648,6 +1029,41 @@ mod tests {
         assert_eq!(expect, options);
     }
 
+    #[test]
+    fn test_with_tiered_storage() {
+        let map = make_map(&[
+            ("storage", "S3"),
+            ("storage.cold", "archive_s3"),
+            ("storage.cold_after", "30d"),
+        ]);
+        let options = RegionOptions::try_from_options(RegionId::new(0, 0), &map).unwrap();
+        let expect = RegionOptions {
+            storage: Some("S3".to_string()),
+            tiered_storage: Some(TieredStorageOptions {
+                cold: "archive_s3".to_string(),
+                cold_after: Duration::from_secs(3600 * 24 * 30),
+            }),
+            ..Default::default()
+        };
+        assert_eq!(expect, options);
+
+        for invalid in [
+            &[("storage.cold", "archive_s3")][..],
+            &[("storage.cold_after", "30d")][..],
+            &[("storage.cold", ""), ("storage.cold_after", "30d")][..],
+            &[("storage.cold", "archive_s3"), ("storage.cold_after", "0s")][..],
+            &[
+                ("storage", "archive_s3"),
+                ("storage.cold", "archive_s3"),
+                ("storage.cold_after", "30d"),
+            ][..],
+        ] {
+            let err = RegionOptions::try_from_options(RegionId::new(0, 0), &make_map(invalid))
+                .unwrap_err();
+            assert_eq!(StatusCode::InvalidArguments, err.status_code());
+        }
+    }
+
     #[test]
     fn test_without_compaction_type() {
         let map = make_map(&[
@@ -678,6 +1094,118 @@ mod tests {
         assert_eq!(expect, options);
     }
 
+    #[test]
+    fn test_with_size_tiered_compaction() {
+        let map = make_map(&[
+            ("compaction.type", "size_tiered"),
+            ("compaction.size_tiered.trigger_file_num", "6"),
+            ("compaction.size_tiered.min_file_size", "16MB"),
+            ("compaction.size_tiered.max_output_file_size", "1GB"),
+            ("compaction.size_tiered.remote_compaction", "true"),
+        ]);
+        let options = RegionOptions::try_from_options(RegionId::new(0, 0), &map).unwrap();
+        let expect = RegionOptions {
+            compaction: CompactionOptions::SizeTiered(SizeTieredOptions {
+                trigger_file_num: 6,
+                min_file_size: ReadableSize::mb(16),
+                max_output_file_size: Some(ReadableSize::gb(1)),
+                remote_compaction: true,
+                fallback_to_local: true,
+            }),
+            compaction_override: true,
+            ..Default::default()
+        };
+        assert_eq!(expect, options);
+        assert!(options.compaction.remote_compaction());
+        assert_eq!(None, options.compaction.time_window());
+
+        let json = serde_json::to_string(&options).unwrap();
+        let got: RegionOptions = serde_json::from_str(&json).unwrap();
+        assert_eq!(options, got);
+    }
+
+    #[test]
+    fn test_invalid_size_tiered_compaction() {
+        for invalid in [
+            // Options of a strategy require the compaction type.
+            &[("compaction.size_tiered.trigger_file_num", "6")][..],
+            &[
+                ("compaction.type", "size_tiered"),
+                ("compaction.size_tiered.trigger_file_num", "1"),
+            ][..],
+            &[
+                ("compaction.type", "size_tiered"),
+                ("storage.cold", "archive"),
+                ("storage.cold_after", "30d"),
+            ][..],
+        ] {
+            let err = RegionOptions::try_from_options(RegionId::new(0, 0), &make_map(invalid))
+                .unwrap_err();
+            assert_eq!(StatusCode::InvalidArguments, err.status_code());
+        }
+    }
+
+    #[test]
+    fn test_with_downsample() {
+        let map = make_map(&[
+            ("compaction.type", "twcs"),
+            ("compaction.twcs.time_window", "1h"),
+            ("compaction.twcs.downsample_after", "7d"),
+            ("compaction.twcs.downsample_interval", "5m"),
+            (
+                "compaction.twcs.downsample_functions",
+                "requests:sum, latency:max",
+            ),
+        ]);
+        let options = RegionOptions::try_from_options(RegionId::new(0, 0), &map).unwrap();
+        let downsample = options.compaction.downsample().unwrap();
+        assert_eq!(Duration::from_secs(3600 * 24 * 7), downsample.after);
+        assert_eq!(Duration::from_secs(300), downsample.interval);
+        assert_eq!(MergeFunction::Sum, downsample.functions.get("requests"));
+        assert_eq!(MergeFunction::Max, downsample.functions.get("latency"));
+        assert_eq!(
+            MergeFunction::LastNonNull,
+            downsample.functions.get("other")
+        );
+
+        let map = make_map(&[
+            ("compaction.type", "twcs"),
+            ("compaction.twcs.downsample_after", "7d"),
+            ("compaction.twcs.downsample_interval", "5m"),
+        ]);
+        let options = RegionOptions::try_from_options(RegionId::new(0, 0), &map).unwrap();
+        assert!(
+            options
+                .compaction
+                .downsample()
+                .unwrap()
+                .functions
+                .iter()
+                .next()
+                .is_none()
+        );
+
+        for invalid in [
+            &[("compaction.twcs.downsample_after", "7d")][..],
+            &[("compaction.twcs.downsample_interval", "5m")][..],
+            &[
+                ("compaction.twcs.downsample_after", "7d"),
+                ("compaction.twcs.downsample_interval", "0s"),
+            ][..],
+            &[
+                ("compaction.twcs.time_window", "1h"),
+                ("compaction.twcs.downsample_after", "7d"),
+                ("compaction.twcs.downsample_interval", "7m"),
+            ][..],
+            &[("compaction.twcs.downsample_functions", "requests:sum")][..],
+        ] {
+            let mut map = make_map(invalid);
+            map.insert("compaction.type".to_string(), "twcs".to_string());
+            let err = RegionOptions::try_from_options(RegionId::new(0, 0), &map).unwrap_err();
+            assert_eq!(StatusCode::InvalidArguments, err.status_code());
+        }
+    }
+
     #[test]
     fn test_with_compaction_override_true_without_compaction_type() {
         let map = make_map(&[(COMPACTION_OVERRIDE, "true")]);
@@ -910,6 +1438,121 @@ mod tests {
         assert_eq!(StatusCode::InvalidArguments, err.status_code());
     }
 
+    #[test]
+    fn test_with_aggregate_merge_mode() {
+        let map = make_map(&[
+            ("merge_mode", "aggregate"),
+            (
+                "merge_functions",
+                "requests: sum, latency:uddsketch_merge,users:HLL_MERGE",
+            ),
+        ]);
+        let options = RegionOptions::try_from_options(RegionId::new(0, 0), &map).unwrap();
+        assert_eq!(MergeMode::Aggregate, options.merge_mode());
+        let functions = options.merge_functions();
+        assert_eq!(MergeFunction::Sum, functions.get("requests"));
+        assert_eq!(MergeFunction::UddsketchMerge, functions.get("latency"));
+        assert_eq!(MergeFunction::HllMerge, functions.get("users"));
+        assert_eq!(MergeFunction::LastNonNull, functions.get("other"));
+        assert_eq!(
+            "latency:uddsketch_merge,requests:sum,users:hll_merge",
+            functions.to_string()
+        );
+
+        // Aggregate mode without functions keeps the last non-null values.
+        let map = make_map(&[("merge_mode", "aggregate")]);
+        let options = RegionOptions::try_from_options(RegionId::new(0, 0), &map).unwrap();
+        assert_eq!(MergeFunctions::default(), options.merge_functions());
+
+        for functions in ["requests", "requests:avg", "requests:sum,requests:max"] {
+            let map = make_map(&[("merge_mode", "aggregate"), ("merge_functions", functions)]);
+            let err = RegionOptions::try_from_options(RegionId::new(0, 0), &map).unwrap_err();
+            assert_eq!(StatusCode::InvalidArguments, err.status_code());
+        }
+
+        let map = make_map(&[
+            ("merge_mode", "last_row"),
+            ("merge_functions", "requests:sum"),
+        ]);
+        let err = RegionOptions::try_from_options(RegionId::new(0, 0), &map).unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+
+        let map = make_map(&[("append_mode", "true"), ("merge_mode", "aggregate")]);
+        let err = RegionOptions::try_from_options(RegionId::new(0, 0), &map).unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
+
+    #[test]
+    fn test_validate_field_functions() {
+        let mut builder = RegionMetadataBuilder::new(RegionId::new(1, 1));
+        builder
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new("tag", ConcreteDataType::string_datatype(), true),
+                semantic_type: SemanticType::Tag,
+                column_id: 1,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "requests",
+                    ConcreteDataType::uint64_datatype(),
+                    true,
+                ),
+                semantic_type: SemanticType::Field,
+                column_id: 2,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "users",
+                    ConcreteDataType::binary_datatype(),
+                    true,
+                ),
+                semantic_type: SemanticType::Field,
+                column_id: 3,
+            })
+            .push_column_metadata(ColumnMetadata {
+                column_schema: ColumnSchema::new(
+                    "ts",
+                    ConcreteDataType::timestamp_millisecond_datatype(),
+                    false,
+                ),
+                semantic_type: SemanticType::Timestamp,
+                column_id: 4,
+            })
+            .primary_key(vec![1]);
+        let metadata = builder.build().unwrap();
+
+        let new_options = |functions: &str| {
+            let map = make_map(&[("merge_mode", "aggregate"), ("merge_functions", functions)]);
+            RegionOptions::try_from_options(RegionId::new(1, 1), &map).unwrap()
+        };
+        new_options("requests:sum,users:hll_merge")
+            .validate_field_functions(&metadata)
+            .unwrap();
+        for functions in [
+            "tag:max",
+            "unknown:sum",
+            "users:sum",
+            "requests:uddsketch_merge",
+        ] {
+            let err = new_options(functions)
+                .validate_field_functions(&metadata)
+                .unwrap_err();
+            assert_eq!(StatusCode::InvalidArguments, err.status_code());
+        }
+
+        let map = make_map(&[
+            ("compaction.type", "twcs"),
+            ("compaction.twcs.downsample_after", "7d"),
+            ("compaction.twcs.downsample_interval", "5m"),
+            ("compaction.twcs.downsample_functions", "unknown:sum"),
+        ]);
+        let err = RegionOptions::try_from_options(RegionId::new(1, 1), &map)
+            .unwrap()
+            .validate_field_functions(&metadata)
+            .unwrap_err();
+        assert_eq!(StatusCode::InvalidArguments, err.status_code());
+    }
+
     #[test]
     fn test_with_all() {
         let wal_options = WalOptions::Kafka(KafkaWalOptions::new("test_topic".to_string()));
@@ -921,7 +1564,12 @@ mod tests {
             ("compaction.type", "twcs"),
             ("compaction.twcs.remote_compaction", "false"),
             ("compaction.twcs.fallback_to_local", "true"),
+            ("compaction.twcs.downsample_after", "7d"),
+            ("compaction.twcs.downsample_interval", "5m"),
+            ("compaction.twcs.downsample_functions", "value:sum"),
             ("storage", "S3"),
+            ("storage.cold", "archive_s3"),
+            ("storage.cold_after", "30d"),
             ("append_mode", "false"),
             ("index.inverted_index.ignore_column_ids", "1,2,3"),
             ("index.inverted_index.segment_row_count", "512"),
@@ -946,9 +1594,16 @@ mod tests {
                 max_output_file_size: Some(ReadableSize::gb(1)),
                 remote_compaction: false,
                 fallback_to_local: true,
+                downsample_after: Some(Duration::from_secs(3600 * 24 * 7)),
+                downsample_interval: Some(Duration::from_secs(300)),
+                downsample_functions: Some("value:sum".parse().unwrap()),
             }),
             compaction_override: true,
             storage: Some("S3".to_string()),
+            tiered_storage: Some(TieredStorageOptions {
+                cold: "archive_s3".to_string(),
+                cold_after: Duration::from_secs(3600 * 24 * 30),
+            }),
             append_mode: false,
             skip_wal: false,
             wal_options,
@@ -965,6 +1620,7 @@ mod tests {
                 max_merge_groups: 17,
             })),
             merge_mode: Some(MergeMode::LastNonNull),
+            merge_functions: None,
             sst_format: Some(FormatType::Flat),
             max_row_group_row_count: None,
             primary_key_encoding: None,
@@ -984,9 +1640,16 @@ mod tests {
                 max_output_file_size: None,
                 remote_compaction: false,
                 fallback_to_local: true,
+                downsample_after: None,
+                downsample_interval: None,
+                downsample_functions: None,
             }),
             compaction_override: false,
             storage: Some("S3".to_string()),
+            tiered_storage: Some(TieredStorageOptions {
+                cold: "archive_s3".to_string(),
+                cold_after: Duration::from_secs(3600 * 24 * 30),
+            }),
             append_mode: false,
             skip_wal: false,
             wal_options: WalOptions::Kafka(KafkaWalOptions::new("test_topic".to_string())),
@@ -998,6 +1661,7 @@ mod tests {
             },
             memtable: Some(MemtableOptions::Bulk(BulkMemtableConfig::default())),
             merge_mode: Some(MergeMode::LastNonNull),
+            merge_functions: None,
             sst_format: None,
             max_row_group_row_count: None,
             primary_key_encoding: None,
@@ -1051,9 +1715,13 @@ mod tests {
                 max_output_file_size: Some(ReadableSize::mb(7)),
                 remote_compaction: false,
                 fallback_to_local: true,
+                downsample_after: None,
+                downsample_interval: None,
+                downsample_functions: None,
             }),
             compaction_override: false,
             storage: Some("S3".to_string()),
+            tiered_storage: None,
             append_mode: false,
             skip_wal: false,
             wal_options: WalOptions::Kafka(KafkaWalOptions::new("test_topic".to_string())),
@@ -1065,6 +1733,7 @@ mod tests {
             },
             memtable: Some(MemtableOptions::Bulk(BulkMemtableConfig::default())),
             merge_mode: Some(MergeMode::LastNonNull),
+            merge_functions: None,
             sst_format: None,
             max_row_group_row_count: None,
             primary_key_encoding: None,
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::Instant;
 
@@ -89,16 +90,23 @@ pub struct RegionFileCopier {
 }
 
 /// A descriptor for a file.
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum FileDescriptor {
     /// An index file.
     Index {
         file_id: FileId,
         version: IndexVersion,
         size: u64,
+        /// The tiered storage of the file, or `None` for the region's object store.
+        storage: Option<String>,
     },
     /// A data file.
-    Data { file_id: FileId, size: u64 },
+    Data {
+        file_id: FileId,
+        size: u64,
+        /// The tiered storage of the file, or `None` for the region's object store.
+        storage: Option<String>,
+    },
 }
 
 impl FileDescriptor {
@@ -109,6 +117,15 @@ impl FileDescriptor {
         }
     }
 
+    /// Returns the tiered storage of the file.
+    pub fn storage(&self) -> Option<&str> {
+        match self {
+            FileDescriptor::Index { storage, .. } | FileDescriptor::Data { storage, .. } => {
+                storage.as_deref()
+            }
+        }
+    }
+
     fn file_path(&self, region_id: RegionId, table_dir: &str, path_type: PathType) -> String {
         match *self {
             FileDescriptor::Index {
@@ -139,7 +156,7 @@ impl FileDescriptor {
 fn build_copy_file_paths(
     source_region_id: RegionId,
     target_region_id: RegionId,
-    file_descriptor: FileDescriptor,
+    file_descriptor: &FileDescriptor,
     table_dir: &str,
     path_type: PathType,
 ) -> (String, String) {
@@ -151,7 +168,7 @@ fn build_copy_file_paths(
 
 fn build_delete_file_path(
     target_region_id: RegionId,
-    file_descriptor: FileDescriptor,
+    file_descriptor: &FileDescriptor,
     table_dir: &str,
     path_type: PathType,
 ) -> String {
@@ -186,7 +203,7 @@ impl RegionFileCopier {
         );
         let table_dir = self.access_layer.table_dir();
         let path_type = self.access_layer.path_type();
-        let object_store = self.access_layer.object_store();
+        let access_layer = &self.access_layer;
 
         info!(
             "Copying {} files from region {} to region {}",
@@ -200,8 +217,7 @@ impl RegionFileCopier {
         );
         let mut tasks = Vec::with_capacity(parallelism);
         for skip in 0..parallelism {
-            let target_file_ids = file_ids.iter().skip(skip).step_by(parallelism).copied();
-            let object_store = object_store.clone();
+            let target_file_ids = file_ids.iter().skip(skip).step_by(parallelism);
             tasks.push(async move {
                 for file_desc in target_file_ids {
                     let (source_path, target_path) = build_copy_file_paths(
@@ -212,7 +228,8 @@ impl RegionFileCopier {
                         path_type,
                     );
                     let now = Instant::now();
-                    object_store
+                    access_layer
+                        .object_store_of(file_desc.storage())?
                         .copy(&source_path, &target_path)
                         .await
                         .inspect_err(
@@ -246,19 +263,33 @@ impl RegionFileCopier {
     async fn clean_target_region(&self, target_region_id: RegionId, file_ids: Vec<FileDescriptor>) {
         let table_dir = self.access_layer.table_dir();
         let path_type = self.access_layer.path_type();
-        let object_store = self.access_layer.object_store();
-        let delete_file_path = file_ids
-            .into_iter()
-            .map(|file_descriptor| {
-                build_delete_file_path(target_region_id, file_descriptor, table_dir, path_type)
-            })
-            .collect::<Vec<_>>();
+        let mut delete_file_paths: HashMap<Option<&str>, Vec<String>> = HashMap::new();
+        for file_descriptor in &file_ids {
+            delete_file_paths
+                .entry(file_descriptor.storage())
+                .or_default()
+                .push(build_delete_file_path(
+                    target_region_id,
+                    file_descriptor,
+                    table_dir,
+                    path_type,
+                ));
+        }
         debug!(
             "Deleting files: {:?} after failed to copy files to target region {}",
-            delete_file_path, target_region_id
+            delete_file_paths, target_region_id
         );
-        if let Err(err) = object_store.delete_iter(delete_file_path).await {
-            error!(err; "Failed to delete files from region {}", target_region_id);
+        for (storage, paths) in delete_file_paths {
+            let object_store = match self.access_layer.object_store_of(storage) {
+                Ok(object_store) => object_store,
+                Err(err) => {
+                    error!(err; "Failed to delete files from region {}", target_region_id);
+                    continue;
+                }
+            };
+            if let Err(err) = object_store.delete_iter(paths).await {
+                error!(err; "Failed to delete files from region {}", target_region_id);
+            }
         }
     }
 }
@@ -273,13 +304,17 @@ mod tests {
         let file_id = FileId::random();
         let source_region_id = RegionId::new(1, 1);
         let target_region_id = RegionId::new(1, 2);
-        let file_descriptor = FileDescriptor::Data { file_id, size: 100 };
+        let file_descriptor = FileDescriptor::Data {
+            file_id,
+            size: 100,
+            storage: None,
+        };
         let table_dir = "/table_dir";
         let path_type = PathType::Bare;
         let (source_path, target_path) = build_copy_file_paths(
             source_region_id,
             target_region_id,
-            file_descriptor,
+            &file_descriptor,
             table_dir,
             path_type,
         );
@@ -297,11 +332,12 @@ mod tests {
             file_id,
             version,
             size: 100,
+            storage: None,
         };
         let (source_path, target_path) = build_copy_file_paths(
             source_region_id,
             target_region_id,
-            file_descriptor,
+            &file_descriptor,
             table_dir,
             path_type,
         );
@@ -329,16 +365,21 @@ mod tests {
         let table_dir = "/table_dir";
         let path_type = PathType::Bare;
 
-        let file_descriptor = FileDescriptor::Data { file_id, size: 100 };
-        let path = build_delete_file_path(target_region_id, file_descriptor, table_dir, path_type);
+        let file_descriptor = FileDescriptor::Data {
+            file_id,
+            size: 100,
+            storage: None,
+        };
+        let path = build_delete_file_path(target_region_id, &file_descriptor, table_dir, path_type);
         assert_eq!(path, format!("/table_dir/1_0000000002/{}.parquet", file_id));
 
         let file_descriptor = FileDescriptor::Index {
             file_id,
             version: 1,
             size: 100,
+            storage: None,
         };
-        let path = build_delete_file_path(target_region_id, file_descriptor, table_dir, path_type);
+        let path = build_delete_file_path(target_region_id, &file_descriptor, table_dir, path_type);
         assert_eq!(
             path,
             format!("/table_dir/1_0000000002/index/{}.1.puffin", file_id)
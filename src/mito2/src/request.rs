@@ -82,6 +82,10 @@ pub struct WriteRequest {
     pub(crate) region_metadata: Option<RegionMetadataRef>,
     /// Partition expression version for the region.
     pub partition_expr_version: Option<u64>,
+    /// Whether the put rows replace the existing rows with the same key.
+    ///
+    /// These rows are deleted before putting the rows, in the same write.
+    pub(crate) replace_existing: bool,
 }
 
 impl WriteRequest {
@@ -139,6 +143,7 @@ impl WriteRequest {
             hint: None,
             region_metadata,
             partition_expr_version: None,
+            replace_existing: false,
         })
     }
 
@@ -153,6 +158,17 @@ impl WriteRequest {
         self
     }
 
+    /// Sets whether the rows replace the existing rows with the same key.
+    pub(crate) fn with_replace_existing(mut self, replace_existing: bool) -> Self {
+        self.replace_existing = replace_existing;
+        self
+    }
+
+    /// Returns the rows to delete before putting the rows, if any.
+    pub(crate) fn replaced_rows(&self) -> Option<Rows> {
+        (self.op_type == OpType::Put && self.replace_existing).then(|| self.rows.clone())
+    }
+
     /// Returns the encoding hint.
     pub fn primary_key_encoding(&self) -> PrimaryKeyEncoding {
         infer_primary_key_encoding_from_hint(self.hint.as_ref())
@@ -672,7 +688,8 @@ impl WorkerRequest {
                 let mut write_request =
                     WriteRequest::new(region_id, OpType::Put, v.rows, region_metadata.clone())?
                         .with_hint(v.hint)
-                        .with_partition_expr_version(v.partition_expr_version);
+                        .with_partition_expr_version(v.partition_expr_version)
+                        .with_replace_existing(v.replace_existing);
                 if write_request.primary_key_encoding() == PrimaryKeyEncoding::Dense
                     && let Some(region_metadata) = &region_metadata
                 {
@@ -1375,6 +1392,27 @@ mod tests {
         assert_eq!(None, request.column_index_by_name("c2"));
     }
 
+    #[test]
+    fn test_replaced_rows() {
+        let rows = Rows {
+            schema: vec![new_column_schema(
+                "c0",
+                ColumnDataType::Int64,
+                SemanticType::Tag,
+            )],
+            rows: vec![Row {
+                values: vec![i64_value(1)],
+            }],
+        };
+
+        let request =
+            WriteRequest::new(RegionId::new(1, 1), OpType::Put, rows.clone(), None).unwrap();
+        assert!(request.replaced_rows().is_none());
+
+        let request = request.with_replace_existing(true);
+        assert_eq!(rows, request.replaced_rows().unwrap());
+    }
+
     #[test]
     fn test_compaction_cancelled_sends_cancelled_error() {
         let (tx, rx) = oneshot::channel();
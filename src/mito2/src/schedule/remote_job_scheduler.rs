@@ -87,6 +87,11 @@ pub trait Notifier: Send + Sync + 'static {
 pub struct JobId(Uuid);
 
 impl JobId {
+    /// Generates a random job id.
+    pub fn random() -> JobId {
+        JobId(Uuid::new_v4())
+    }
+
     /// Parses job id from string.
     pub fn parse_str(input: &str) -> Result<JobId> {
         Uuid::parse_str(input).map(JobId).context(ParseJobIdSnafu)
@@ -20,6 +20,7 @@ use std::fmt::{Debug, Formatter};
 use std::num::NonZeroU64;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex, RwLock};
+use std::time::Duration;
 
 use base64::prelude::{BASE64_STANDARD, Engine};
 use bytes::Bytes;
@@ -282,6 +283,18 @@ pub struct FileMeta {
         deserialize_with = "deserialize_bytes_option"
     )]
     pub primary_key_max: Option<Bytes>,
+    /// Time resolution of rows in the file if the file is written by a downsampling
+    /// compaction. `None` means the file keeps the raw resolution.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "humantime_serde"
+    )]
+    pub downsample_interval: Option<Duration>,
+    /// Name of the tiered object store that holds the file. `None` means the file
+    /// is stored in the object store of the region.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub storage: Option<String>,
 }
 
 impl Debug for FileMeta {
@@ -334,6 +347,14 @@ impl Debug for FileMeta {
                     &self.primary_key_max.as_ref().map(|b| b.len()),
                 );
         }
+        if let Some(interval) = self.downsample_interval {
+            debug_struct.field_with("downsample_interval", |f| {
+                write!(f, "{}", humantime::format_duration(interval))
+            });
+        }
+        if let Some(storage) = &self.storage {
+            debug_struct.field("storage", storage);
+        }
         debug_struct.finish()
     }
 }
@@ -730,6 +751,14 @@ impl UncommittedSsts {
         }
     }
 
+    /// Tracks the new SSTs of an edit written by a remote compaction worker.
+    pub(crate) fn track_files(&self, metas: &[FileMeta]) {
+        let mut files = self.files.lock().unwrap();
+        for meta in metas {
+            files.insert(meta.file_id, (meta.index_version, meta.index_file_size > 0));
+        }
+    }
+
     /// Disarms cleanup after the manifest edit has committed or may have committed.
     ///
     /// A manifest update error does not guarantee that the edit was not persisted. Once an edit
@@ -845,7 +845,7 @@ impl IndexBuildTask {
 
         let mut parquet_reader = self
             .access_layer
-            .read_sst(self.file.clone()) // use the latest file handle instead of creating a new one
+            .read_sst(self.file.clone())? // use the latest file handle instead of creating a new one
             .build()
             .await?;
 
@@ -994,7 +994,9 @@ impl IndexBuildTask {
         if let Some(write_cache) = &self.write_cache {
             let file_id = self.source.file_meta.file_id;
             let region_id = self.source.file_meta.region_id;
-            let remote_store = self.access_layer.object_store();
+            let remote_store = self
+                .access_layer
+                .object_store_of(self.file.meta_ref().storage.as_deref())?;
             let mut upload_tracker = UploadTracker::new(region_id);
             let mut err = None;
             let puffin_key =
@@ -199,6 +199,7 @@ impl BloomFilterIndexApplier {
     /// # Arguments
     /// * `file_id` - The region file ID to apply predicates to
     /// * `file_size_hint` - Optional hint for file size to avoid extra metadata reads
+    /// * `object_store` - The object store of the SST file, defaults to the store of the applier
     /// * `row_groups` - Iterator of row group lengths and whether to search in the row group
     /// * `metrics` - Optional mutable reference to collect metrics on demand
     #[tracing::instrument(
@@ -209,6 +210,7 @@ impl BloomFilterIndexApplier {
         &self,
         file_id: RegionIndexId,
         file_size_hint: Option<u64>,
+        object_store: Option<&ObjectStore>,
         predicates: &BTreeMap<ColumnId, Vec<InListPredicate>>,
         row_groups: impl Iterator<Item = (usize, bool)>,
         mut metrics: Option<&mut BloomFilterIndexApplyMetrics>,
@@ -235,7 +237,13 @@ impl BloomFilterIndexApplier {
 
         for (column_id, predicates) in predicates {
             let blob = match self
-                .blob_reader(file_id, *column_id, file_size_hint, metrics.as_deref_mut())
+                .blob_reader(
+                    file_id,
+                    *column_id,
+                    file_size_hint,
+                    object_store,
+                    metrics.as_deref_mut(),
+                )
                 .await?
             {
                 Some(blob) => blob,
@@ -298,6 +306,7 @@ impl BloomFilterIndexApplier {
         file_id: RegionIndexId,
         column_id: ColumnId,
         file_size_hint: Option<u64>,
+        object_store: Option<&ObjectStore>,
         metrics: Option<&mut BloomFilterIndexApplyMetrics>,
     ) -> Result<Option<BlobReader>> {
         let reader = match self
@@ -317,7 +326,7 @@ impl BloomFilterIndexApplier {
                     warn!(err; "An unexpected error occurred while reading the cached index file. Fallback to remote index file.")
                 }
                 let res = self
-                    .remote_blob_reader(file_id, column_id, file_size_hint)
+                    .remote_blob_reader(file_id, column_id, file_size_hint, object_store)
                     .await;
                 if let Err(err) = res {
                     // Blob not found means no index for this column
@@ -385,7 +394,9 @@ impl BloomFilterIndexApplier {
         file_id: RegionIndexId,
         column_id: ColumnId,
         file_size_hint: Option<u64>,
+        object_store: Option<&ObjectStore>,
     ) -> Result<BlobReader> {
+        let object_store = object_store.unwrap_or(&self.object_store);
         let path_factory = RegionFilePathFactory::new(self.table_dir.clone(), self.path_type);
 
         // Trigger background download if file cache and file size are available
@@ -394,12 +405,12 @@ impl BloomFilterIndexApplier {
             &file_id,
             file_size_hint,
             &path_factory,
-            &self.object_store,
+            object_store,
         );
 
         let puffin_manager = self
             .puffin_manager_factory
-            .build(self.object_store.clone(), path_factory)
+            .build(object_store.clone(), path_factory)
             .with_puffin_metadata_cache(self.puffin_metadata_cache.clone());
 
         let blob_name = Self::column_blob_name(column_id);
@@ -650,7 +661,14 @@ mod tests {
                     .compatible_predicate_for_sst(&Arc::new(metadata.clone()))
                     .unwrap();
                 applier
-                    .apply(file_id, None, &predicates, row_groups.into_iter(), None)
+                    .apply(
+                        file_id,
+                        None,
+                        None,
+                        &predicates,
+                        row_groups.into_iter(),
+                        None,
+                    )
                     .await
                     .unwrap()
                     .into_iter()
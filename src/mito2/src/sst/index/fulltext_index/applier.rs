@@ -218,6 +218,7 @@ impl FulltextIndexApplier {
     /// # Arguments
     /// * `file_id` - The region file ID to apply predicates to
     /// * `file_size_hint` - Optional hint for file size to avoid extra metadata reads
+    /// * `object_store` - The object store of the SST file, defaults to the store of the applier
     /// * `metrics` - Optional mutable reference to collect metrics on demand
     #[tracing::instrument(
         skip_all,
@@ -227,6 +228,7 @@ impl FulltextIndexApplier {
         &self,
         file_id: RegionIndexId,
         file_size_hint: Option<u64>,
+        object_store: Option<&ObjectStore>,
         mut metrics: Option<&mut FulltextIndexApplyMetrics>,
     ) -> Result<Option<BTreeSet<RowId>>> {
         let apply_start = Instant::now();
@@ -240,6 +242,7 @@ impl FulltextIndexApplier {
             let Some(result) = self
                 .apply_fine_one_column(
                     file_size_hint,
+                    object_store,
                     file_id,
                     *column_id,
                     request,
@@ -279,6 +282,7 @@ impl FulltextIndexApplier {
     async fn apply_fine_one_column(
         &self,
         file_size_hint: Option<u64>,
+        object_store: Option<&ObjectStore>,
         file_id: RegionIndexId,
         column_id: ColumnId,
         request: &FulltextRequest,
@@ -290,7 +294,7 @@ impl FulltextIndexApplier {
         );
         let dir = self
             .index_source
-            .dir(file_id, &blob_key, file_size_hint, metrics)
+            .dir(file_id, &blob_key, file_size_hint, object_store, metrics)
             .await?;
 
         let dir = match &dir {
@@ -356,6 +360,7 @@ impl FulltextIndexApplier {
     /// # Arguments
     /// * `file_id` - The region file ID to apply predicates to
     /// * `file_size_hint` - Optional hint for file size to avoid extra metadata reads
+    /// * `object_store` - The object store of the SST file, defaults to the store of the applier
     /// * `row_groups` - Iterator of row group lengths and whether to search in the row group
     /// * `metrics` - Optional mutable reference to collect metrics on demand
     #[allow(clippy::type_complexity)]
@@ -367,6 +372,7 @@ impl FulltextIndexApplier {
         &self,
         file_id: RegionIndexId,
         file_size_hint: Option<u64>,
+        object_store: Option<&ObjectStore>,
         row_groups: impl Iterator<Item = (usize, bool)>,
         mut metrics: Option<&mut FulltextIndexApplyMetrics>,
     ) -> Result<Option<Vec<(usize, Vec<Range<usize>>)>>> {
@@ -385,6 +391,7 @@ impl FulltextIndexApplier {
                 .apply_coarse_one_column(
                     file_id,
                     file_size_hint,
+                    object_store,
                     *column_id,
                     &request.terms,
                     &mut output,
@@ -416,6 +423,7 @@ impl FulltextIndexApplier {
         &self,
         file_id: RegionIndexId,
         file_size_hint: Option<u64>,
+        object_store: Option<&ObjectStore>,
         column_id: ColumnId,
         terms: &[FulltextTerm],
         output: &mut [(usize, Vec<Range<usize>>)],
@@ -427,7 +435,13 @@ impl FulltextIndexApplier {
         );
         let Some(reader) = self
             .index_source
-            .blob(file_id, &blob_key, file_size_hint, metrics.as_deref_mut())
+            .blob(
+                file_id,
+                &blob_key,
+                file_size_hint,
+                object_store,
+                metrics.as_deref_mut(),
+            )
             .await?
         else {
             return Ok(false);
@@ -624,9 +638,12 @@ impl IndexSource {
         file_id: RegionIndexId,
         key: &str,
         file_size_hint: Option<u64>,
+        object_store: Option<&ObjectStore>,
         metrics: Option<&mut FulltextIndexApplyMetrics>,
     ) -> Result<Option<GuardWithMetadata<SstPuffinBlob>>> {
-        let (reader, fallbacked) = self.ensure_reader(file_id, file_size_hint).await?;
+        let (reader, fallbacked) = self
+            .ensure_reader(file_id, file_size_hint, object_store)
+            .await?;
 
         // Track cache miss if fallbacked to remote
         if fallbacked && let Some(m) = metrics {
@@ -642,7 +659,9 @@ impl IndexSource {
                     Err(err).context(PuffinReadBlobSnafu)
                 } else {
                     warn!(err; "An unexpected error occurred while reading the cached index file. Fallback to remote index file.");
-                    let reader = self.build_remote(file_id, file_size_hint).await?;
+                    let reader = self
+                        .build_remote(file_id, file_size_hint, object_store)
+                        .await?;
                     let res = reader.blob(key).await;
                     match res {
                         Ok(blob) => Ok(Some(blob)),
@@ -662,9 +681,12 @@ impl IndexSource {
         file_id: RegionIndexId,
         key: &str,
         file_size_hint: Option<u64>,
+        object_store: Option<&ObjectStore>,
         mut metrics: Option<&mut FulltextIndexApplyMetrics>,
     ) -> Result<Option<GuardWithMetadata<SstPuffinDir>>> {
-        let (reader, fallbacked) = self.ensure_reader(file_id, file_size_hint).await?;
+        let (reader, fallbacked) = self
+            .ensure_reader(file_id, file_size_hint, object_store)
+            .await?;
 
         // Track cache miss if fallbacked to remote
         if fallbacked && let Some(m) = &mut metrics {
@@ -687,7 +709,9 @@ impl IndexSource {
                     Err(err).context(PuffinReadBlobSnafu)
                 } else {
                     warn!(err; "An unexpected error occurred while reading the cached index file. Fallback to remote index file.");
-                    let reader = self.build_remote(file_id, file_size_hint).await?;
+                    let reader = self
+                        .build_remote(file_id, file_size_hint, object_store)
+                        .await?;
                     let start = metrics.as_ref().map(|_| Instant::now());
                     let res = reader.dir(key).await;
                     match res {
@@ -711,10 +735,15 @@ impl IndexSource {
         &self,
         file_id: RegionIndexId,
         file_size_hint: Option<u64>,
+        object_store: Option<&ObjectStore>,
     ) -> Result<(SstPuffinReader, bool)> {
         match self.build_local_cache(file_id, file_size_hint).await {
             Ok(Some(r)) => Ok((r, false)),
-            Ok(None) => Ok((self.build_remote(file_id, file_size_hint).await?, true)),
+            Ok(None) => Ok((
+                self.build_remote(file_id, file_size_hint, object_store)
+                    .await?,
+                true,
+            )),
             Err(err) => Err(err),
         }
     }
@@ -756,7 +785,9 @@ impl IndexSource {
         &self,
         file_id: RegionIndexId,
         file_size_hint: Option<u64>,
+        object_store: Option<&ObjectStore>,
     ) -> Result<SstPuffinReader> {
+        let object_store = object_store.unwrap_or(&self.remote_store);
         let path_factory = RegionFilePathFactory::new(self.table_dir.clone(), self.path_type);
 
         // Trigger background download if file cache and file size are available
@@ -765,12 +796,12 @@ impl IndexSource {
             &file_id,
             file_size_hint,
             &path_factory,
-            &self.remote_store,
+            object_store,
         );
 
         let puffin_manager = self
             .puffin_manager_factory
-            .build(self.remote_store.clone(), path_factory)
+            .build(object_store.clone(), path_factory)
             .with_puffin_metadata_cache(self.puffin_metadata_cache.clone());
 
         let reader = puffin_manager
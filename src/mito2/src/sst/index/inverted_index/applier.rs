@@ -205,6 +205,7 @@ impl InvertedIndexApplier {
     /// # Arguments
     /// * `file_id` - The region file ID to apply predicates to
     /// * `file_size_hint` - Optional hint for file size to avoid extra metadata reads
+    /// * `object_store` - The object store of the SST file, defaults to the store of the applier
     /// * `index_applier` - Inverted index applier produced by `plan_for_sst`.
     /// * `metrics` - Optional mutable reference to collect metrics on demand
     #[tracing::instrument(
@@ -215,6 +216,7 @@ impl InvertedIndexApplier {
         &self,
         file_id: RegionIndexId,
         file_size_hint: Option<u64>,
+        object_store: Option<&ObjectStore>,
         index_applier: &PredicatesIndexApplier,
         mut metrics: Option<&mut InvertedIndexApplyMetrics>,
     ) -> Result<ApplyOutput> {
@@ -233,7 +235,8 @@ impl InvertedIndexApplier {
                 if let Err(err) = other {
                     warn!(err; "An unexpected error occurred while reading the cached index file. Fallback to remote index file.")
                 }
-                self.remote_blob_reader(file_id, file_size_hint).await?
+                self.remote_blob_reader(file_id, file_size_hint, object_store)
+                    .await?
             }
         };
 
@@ -330,7 +333,9 @@ impl InvertedIndexApplier {
         &self,
         file_id: RegionIndexId,
         file_size_hint: Option<u64>,
+        object_store: Option<&ObjectStore>,
     ) -> Result<BlobReader> {
+        let object_store = object_store.unwrap_or(&self.store);
         let path_factory = RegionFilePathFactory::new(self.table_dir.clone(), self.path_type);
 
         // Trigger background download if file cache and file size are available
@@ -339,12 +344,12 @@ impl InvertedIndexApplier {
             &file_id,
             file_size_hint,
             &path_factory,
-            &self.store,
+            object_store,
         );
 
         let puffin_manager = self
             .puffin_manager_factory
-            .build(self.store.clone(), path_factory)
+            .build(object_store.clone(), path_factory)
             .with_puffin_metadata_cache(self.puffin_metadata_cache.clone());
 
         puffin_manager
@@ -546,7 +551,7 @@ mod tests {
             .unwrap()
             .unwrap();
         let res = sst_index_applier
-            .apply(index_id, None, &plan.index_applier, None)
+            .apply(index_id, None, None, &plan.index_applier, None)
             .await;
         assert!(format!("{:?}", res.unwrap_err()).contains("Blob not found"));
     }
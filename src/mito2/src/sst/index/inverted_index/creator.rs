@@ -618,7 +618,7 @@ mod tests {
             let plan = applier.plan_for_sst(&sst_metadata).unwrap().unwrap();
             Box::pin(async move {
                 applier
-                    .apply(index_id, None, &plan.index_applier, None)
+                    .apply(index_id, None, None, &plan.index_applier, None)
                     .await
                     .unwrap()
                     .matched_segment_ids
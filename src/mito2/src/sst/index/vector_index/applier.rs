@@ -111,10 +111,13 @@ impl VectorIndexApplier {
     /// a KNN search against the indexed vectors, and maps the HNSW keys back
     /// to row offsets in the SST file. It returns only row offsets; callers
     /// are responsible for any higher-level ordering or limit enforcement.
+    /// `object_store` is the object store of the SST file, which defaults to the
+    /// store of the applier.
     pub async fn apply_with_k(
         &self,
         file_id: RegionIndexId,
         file_size_hint: Option<u64>,
+        object_store: Option<&object_store::ObjectStore>,
         k: usize,
     ) -> Result<VectorIndexApplyOutput> {
         if k == 0 {
@@ -124,7 +127,9 @@ impl VectorIndexApplier {
             });
         }
 
-        let cached = self.load_or_read_index(file_id, file_size_hint).await?;
+        let cached = self
+            .load_or_read_index(file_id, file_size_hint, object_store)
+            .await?;
         let Some(cached) = cached else {
             return Ok(VectorIndexApplyOutput {
                 row_offsets: Vec::new(),
@@ -171,6 +176,7 @@ impl VectorIndexApplier {
         &self,
         file_id: RegionIndexId,
         file_size_hint: Option<u64>,
+        object_store: Option<&object_store::ObjectStore>,
     ) -> Result<Option<Arc<CachedVectorIndex>>> {
         let cache_key =
             VectorIndexCacheKey::new(file_id.file_id(), file_id.version, self.column_id);
@@ -182,13 +188,18 @@ impl VectorIndexApplier {
 
         let reader = match self.cached_blob_reader(file_id, file_size_hint).await {
             Ok(Some(reader)) => reader,
-            Ok(None) => self.remote_blob_reader(file_id, file_size_hint).await?,
+            Ok(None) => {
+                self.remote_blob_reader(file_id, file_size_hint, object_store)
+                    .await?
+            }
             Err(err) => {
                 if is_blob_not_found(&err) {
-                    self.remote_blob_reader(file_id, file_size_hint).await?
+                    self.remote_blob_reader(file_id, file_size_hint, object_store)
+                        .await?
                 } else {
                     warn!(err; "Failed to read cached vector index blob, fallback to remote");
-                    self.remote_blob_reader(file_id, file_size_hint).await?
+                    self.remote_blob_reader(file_id, file_size_hint, object_store)
+                        .await?
                 }
             }
         };
@@ -255,7 +266,9 @@ impl VectorIndexApplier {
         &self,
         file_id: RegionIndexId,
         file_size_hint: Option<u64>,
+        object_store: Option<&object_store::ObjectStore>,
     ) -> Result<BlobReader> {
+        let object_store = object_store.unwrap_or(&self.object_store);
         let path_factory = RegionFilePathFactory::new(self.table_dir.clone(), self.path_type);
 
         trigger_index_background_download(
@@ -263,12 +276,12 @@ impl VectorIndexApplier {
             &file_id,
             file_size_hint,
             &path_factory,
-            &self.object_store,
+            object_store,
         );
 
         let puffin_manager = self
             .puffin_manager_factory
-            .build(self.object_store.clone(), path_factory)
+            .build(object_store.clone(), path_factory)
             .with_puffin_metadata_cache(self.puffin_metadata_cache.clone());
 
         let blob_name = column_blob_name(self.column_id);
@@ -463,7 +476,7 @@ mod tests {
         let (_dir, applier, index_id, size_bytes) =
             build_applier_with_blob(blob, 1, vec![1.0, 0.0], VectorDistanceMetric::L2sq).await;
         let output = applier
-            .apply_with_k(index_id, Some(size_bytes), 2)
+            .apply_with_k(index_id, Some(size_bytes), None, 2)
             .await
             .unwrap();
         assert_eq!(output.row_offsets, vec![0, 2]);
@@ -476,7 +489,9 @@ mod tests {
         let blob = build_blob_with_vectors(&config, vec![(0, vec![1.0, 0.0])], &null_bitmap, 1, 1);
         let (_dir, applier, index_id, size_bytes) =
             build_applier_with_blob(blob, 1, vec![1.0, 0.0, 0.0], VectorDistanceMetric::L2sq).await;
-        let res = applier.apply_with_k(index_id, Some(size_bytes), 1).await;
+        let res = applier
+            .apply_with_k(index_id, Some(size_bytes), None, 1)
+            .await;
         assert!(res.is_err());
     }
 
@@ -495,7 +510,7 @@ mod tests {
         let (_dir, applier, index_id, size_bytes) =
             build_applier_with_blob(blob, 1, vec![1.0], VectorDistanceMetric::L2sq).await;
         let output = applier
-            .apply_with_k(index_id, Some(size_bytes), 1)
+            .apply_with_k(index_id, Some(size_bytes), None, 1)
             .await
             .unwrap();
         assert!(output.row_offsets.is_empty());
@@ -903,6 +903,7 @@ impl ParquetReaderBuilder {
                 .apply_fine(
                     self.file_handle.index_id(),
                     Some(file_size_hint),
+                    Some(&self.object_store),
                     metrics.fulltext_index_apply_metrics.as_mut(),
                 )
                 .await;
@@ -987,6 +988,7 @@ impl ParquetReaderBuilder {
                 .apply(
                     self.file_handle.index_id(),
                     Some(file_size_hint),
+                    Some(&self.object_store),
                     &plan.index_applier,
                     metrics.inverted_index_apply_metrics.as_mut(),
                 )
@@ -1089,6 +1091,7 @@ impl ParquetReaderBuilder {
                 .apply(
                     self.file_handle.index_id(),
                     Some(file_size_hint),
+                    Some(&self.object_store),
                     &compatible_predicates,
                     rgs,
                     metrics.bloom_filter_apply_metrics.as_mut(),
@@ -1143,7 +1146,12 @@ impl ParquetReaderBuilder {
 
         let file_size_hint = self.file_handle.meta_ref().index_file_size();
         let apply_res = applier
-            .apply_with_k(self.file_handle.index_id(), Some(file_size_hint), k)
+            .apply_with_k(
+                self.file_handle.index_id(),
+                Some(file_size_hint),
+                Some(&self.object_store),
+                k,
+            )
             .await;
         let row_ids = match apply_res {
             Ok(res) => res.row_offsets,
@@ -1218,6 +1226,7 @@ impl ParquetReaderBuilder {
                 .apply_coarse(
                     self.file_handle.index_id(),
                     Some(file_size_hint),
+                    Some(&self.object_store),
                     rgs,
                     metrics.fulltext_index_apply_metrics.as_mut(),
                 )
@@ -48,6 +48,7 @@ use datatypes::extension::json::{Json2ExtensionType, JsonExtensionType};
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::ColumnSchema;
 use log_store::kafka::log_store::KafkaLogStore;
+use log_store::object_store::log_store::ObjectStoreLogStore;
 use log_store::raft_engine::log_store::RaftEngineLogStore;
 use log_store::test_util::log_store_util;
 use moka::future::CacheBuilder;
@@ -93,6 +94,10 @@ pub(crate) fn raft_engine_log_store_factory() -> Option<LogStoreFactory> {
     Some(LogStoreFactory::RaftEngine(RaftEngineLogStoreFactory))
 }
 
+pub(crate) fn object_store_log_store_factory() -> Option<LogStoreFactory> {
+    Some(LogStoreFactory::ObjectStore(ObjectStoreLogStoreFactory))
+}
+
 pub fn kafka_log_store_factory() -> Option<LogStoreFactory> {
     let _ = dotenv::dotenv();
     let Ok(broker_endpoints) = std::env::var("GT_KAFKA_ENDPOINTS") else {
@@ -206,16 +211,31 @@ impl KafkaLogStoreFactory {
     }
 }
 
+#[derive(Clone)]
+pub struct ObjectStoreLogStoreFactory;
+
+impl ObjectStoreLogStoreFactory {
+    fn create_log_store<P: AsRef<Path>>(&self, wal_path: P) -> ObjectStoreLogStore {
+        let object_store =
+            ObjectStore::new(Fs::default().root(&wal_path.as_ref().display().to_string()))
+                .unwrap()
+                .finish();
+        ObjectStoreLogStore::new(object_store, &Default::default())
+    }
+}
+
 #[derive(Clone)]
 pub enum LogStoreFactory {
     RaftEngine(RaftEngineLogStoreFactory),
     Kafka(KafkaLogStoreFactory),
+    ObjectStore(ObjectStoreLogStoreFactory),
 }
 
 #[derive(Clone)]
 pub(crate) enum LogStoreImpl {
     RaftEngine(Arc<RaftEngineLogStore>),
     Kafka(Arc<KafkaLogStore>),
+    ObjectStore(Arc<ObjectStoreLogStore>),
 }
 
 /// Env to test mito engine.
@@ -332,6 +352,7 @@ impl TestEnv {
         match self.log_store.as_ref().unwrap().clone() {
             LogStoreImpl::RaftEngine(log_store) => create(self, config, log_store, plugins).await,
             LogStoreImpl::Kafka(log_store) => create(self, config, log_store, plugins).await,
+            LogStoreImpl::ObjectStore(log_store) => create(self, config, log_store, plugins).await,
         }
     }
 
@@ -415,6 +436,20 @@ impl TestEnv {
             )
             .await
             .unwrap(),
+            LogStoreImpl::ObjectStore(log_store) => MitoEngine::new_for_test(
+                &data_home,
+                config,
+                log_store,
+                object_store_manager,
+                manager,
+                listener,
+                Arc::new(StdTimeProvider),
+                self.schema_metadata_manager.clone(),
+                self.file_ref_manager.clone(),
+                self.partition_expr_fetcher.clone(),
+            )
+            .await
+            .unwrap(),
         }
     }
 
@@ -474,6 +509,20 @@ impl TestEnv {
             )
             .await
             .unwrap(),
+            LogStoreImpl::ObjectStore(log_store) => MitoEngine::new_for_test(
+                &data_home,
+                config,
+                log_store,
+                object_store_manager,
+                manager,
+                listener,
+                Arc::new(StdTimeProvider),
+                self.schema_metadata_manager.clone(),
+                self.file_ref_manager.clone(),
+                self.partition_expr_fetcher.clone(),
+            )
+            .await
+            .unwrap(),
         }
     }
 
@@ -522,6 +571,20 @@ impl TestEnv {
             )
             .await
             .unwrap(),
+            LogStoreImpl::ObjectStore(log_store) => MitoEngine::new_for_test(
+                &data_home,
+                config,
+                log_store,
+                object_store_manager,
+                manager,
+                listener,
+                time_provider.clone(),
+                self.schema_metadata_manager.clone(),
+                self.file_ref_manager.clone(),
+                self.partition_expr_fetcher.clone(),
+            )
+            .await
+            .unwrap(),
         }
     }
 
@@ -572,6 +635,17 @@ impl TestEnv {
             )
             .await
             .unwrap(),
+            LogStoreImpl::ObjectStore(log_store) => WorkerGroup::start(
+                Arc::new(config),
+                log_store,
+                Arc::new(object_store_manager),
+                self.schema_metadata_manager.clone(),
+                self.file_ref_manager.clone(),
+                self.partition_expr_fetcher.clone(),
+                Plugins::new(),
+            )
+            .await
+            .unwrap(),
         }
     }
 
@@ -598,6 +672,11 @@ impl TestEnv {
 
                 LogStoreImpl::Kafka(Arc::new(log_store))
             }
+            LogStoreFactory::ObjectStore(factory) => {
+                let log_store = factory.create_log_store(wal_path);
+
+                LogStoreImpl::ObjectStore(Arc::new(log_store))
+            }
         }
     }
 
@@ -1229,6 +1308,7 @@ pub async fn put_rows(engine: &MitoEngine, region_id: RegionId, rows: Rows) {
                 rows,
                 hint: None,
                 partition_expr_version: None,
+                replace_existing: false,
             }),
         )
         .await
@@ -112,9 +112,9 @@ impl<S: LogStore> Wal<S> {
         location_id: Option<u64>,
     ) -> Box<dyn WalEntryReader> {
         match provider {
-            Provider::RaftEngine(_) => Box::new(LogStoreEntryReader::new(
-                LogStoreRawEntryReader::new(self.store.clone()),
-            )),
+            Provider::RaftEngine(_) | Provider::ObjectStore(_) => Box::new(
+                LogStoreEntryReader::new(LogStoreRawEntryReader::new(self.store.clone())),
+            ),
             Provider::Kafka(_) => {
                 let reader = if let Some(location_id) = location_id {
                     LogStoreRawEntryReader::new(self.store.clone())
@@ -233,7 +233,13 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                     current_options.ttl = new_ttl;
                 }
                 SetRegionOption::Twsc(key, value) => {
-                    let Twcs(options) = &mut current_options.compaction;
+                    let Twcs(options) = &mut current_options.compaction else {
+                        return Err(store_api::metadata::InvalidRegionRequestSnafu {
+                            region_id: region.region_id,
+                            err: format!("{key} requires the twcs compaction"),
+                        }
+                        .build());
+                    };
                     set_twcs_options(
                         options,
                         &TwcsOptions::default(),
@@ -268,6 +274,7 @@ impl<S: LogStore> RegionWorkerLoop<S> {
                         );
                         // Clear merge_mode since it's incompatible with append_mode.
                         current_options.merge_mode = None;
+                        current_options.merge_functions = None;
                         all_options_altered = false;
                     }
                 }
@@ -363,6 +370,7 @@ fn new_region_options_on_empty_memtable(
                     // supported transition is from false to true.
                     current_options.append_mode = *new_append_mode;
                     current_options.merge_mode = None;
+                    current_options.merge_functions = None;
                 }
             }
             SetRegionOption::MaxRowGroupRowCount(new_row_count) => {
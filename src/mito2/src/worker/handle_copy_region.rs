@@ -193,11 +193,13 @@ impl<S> RegionWorkerLoop<S> {
                         FileDescriptor::Data {
                             file_id: file_meta.file_id,
                             size: file_size,
+                            storage: file_meta.storage.clone(),
                         },
                         FileDescriptor::Index {
                             file_id,
                             version,
                             size: index_file_size,
+                            storage: file_meta.storage.clone(),
                         },
                     ]
                 } else {
@@ -205,6 +207,7 @@ impl<S> RegionWorkerLoop<S> {
                     vec![FileDescriptor::Data {
                         file_id: file_meta.file_id,
                         size: file_size,
+                        storage: file_meta.storage.clone(),
                     }]
                 }
             })
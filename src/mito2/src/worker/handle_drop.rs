@@ -151,7 +151,13 @@ where
             None => None,
         };
 
-        let object_store = region.access_layer.object_store().clone();
+        // The region may have files in tiered stores, so we remove the region
+        // directory from every store of the region.
+        let object_stores = region
+            .access_layer
+            .object_stores()
+            .cloned()
+            .collect::<Vec<_>>();
         let dropping_regions = self.dropping_regions.clone();
         let listener = self.listener.clone();
         let intm_manager = self.intermediate_manager.clone();
@@ -164,7 +170,7 @@ where
                     region_id,
                     region_dir.clone(),
                     path_type,
-                    object_store,
+                    object_stores,
                     dropping_regions,
                     partial_drop,
                     hook_payload.as_ref(),
@@ -178,7 +184,7 @@ where
                 later_drop_task_without_global_gc(
                     region_id,
                     region_dir.clone(),
-                    object_store,
+                    object_stores,
                     dropping_regions,
                     gc_duration,
                     hook_payload.as_ref(),
@@ -265,7 +271,7 @@ struct DropHookPayload {
 async fn later_drop_task_without_global_gc(
     region_id: RegionId,
     region_path: String,
-    object_store: ObjectStore,
+    object_stores: Vec<ObjectStore>,
     dropping_regions: RegionMapRef,
     gc_duration: Duration,
     hook_payload: Option<&DropHookPayload>,
@@ -273,7 +279,7 @@ async fn later_drop_task_without_global_gc(
     remove_region_with_retry(
         region_id,
         region_path,
-        object_store,
+        object_stores,
         dropping_regions,
         Some(gc_duration),
         false,
@@ -285,14 +291,14 @@ async fn later_drop_task_without_global_gc(
 async fn remove_region_with_retry(
     region_id: RegionId,
     region_path: String,
-    object_store: ObjectStore,
+    object_stores: Vec<ObjectStore>,
     dropping_regions: std::sync::Arc<crate::region::RegionMap>,
     gc_duration: Option<Duration>,
     mut force: bool,
     hook_payload: Option<&DropHookPayload>,
 ) -> bool {
     for _ in 0..MAX_RETRY_TIMES {
-        let result = remove_region_dir_once(&region_path, &object_store, force).await;
+        let result = remove_region_dirs_once(&region_path, &object_stores, force).await;
         match result {
             Err(err) => {
                 warn!(
@@ -335,7 +341,7 @@ async fn later_drop_task_with_global_gc(
     region_id: RegionId,
     region_path: String,
     path_type: PathType,
-    object_store: ObjectStore,
+    object_stores: Vec<ObjectStore>,
     dropping_regions: RegionMapRef,
     partial_drop: bool,
     hook_payload: Option<&DropHookPayload>,
@@ -348,7 +354,7 @@ async fn later_drop_task_with_global_gc(
         remove_region_with_retry(
             region_id,
             region_path,
-            object_store,
+            object_stores,
             dropping_regions,
             None,
             true,
@@ -366,6 +372,31 @@ fn should_force_remove_region_dir(path_type: PathType, partial_drop: bool) -> bo
     path_type == PathType::Metadata || !partial_drop
 }
 
+/// Removes the region dir from all `object_stores`, returns whether the directory is removed
+/// from every store.
+///
+/// The first store is the object store of the region, which holds the drop marker. It's
+/// removed after the tiered stores so the drop can be retried until all of them are removed.
+async fn remove_region_dirs_once(
+    region_path: &str,
+    object_stores: &[ObjectStore],
+    force: bool,
+) -> Result<bool> {
+    let Some((object_store, tiered_stores)) = object_stores.split_first() else {
+        return Ok(true);
+    };
+
+    let mut removed = true;
+    for tiered_store in tiered_stores {
+        removed &= remove_region_dir_once(region_path, tiered_store, force).await?;
+    }
+    if !removed {
+        return Ok(false);
+    }
+
+    remove_region_dir_once(region_path, object_store, force).await
+}
+
 // TODO(ruihang): place the marker in a separate dir
 /// Removes region dir if there is no parquet files, returns whether the directory is removed.
 /// If `force = true`, always removes the dir.
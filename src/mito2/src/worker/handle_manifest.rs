@@ -682,11 +682,21 @@ async fn edit_region(
             );
 
             let file_size = file_meta.file_size;
+            let object_store = match layer.object_store_of(file_meta.storage.as_deref()) {
+                Ok(object_store) => object_store.clone(),
+                Err(e) => {
+                    warn!(
+                        e; "Failed to preload file to write cache, region: {}, file_id: {}",
+                        region_id, file_meta.file_id
+                    );
+                    continue;
+                }
+            };
             common_runtime::spawn_global(async move {
                 WRITE_CACHE_INFLIGHT_DOWNLOAD.add(1);
 
                 let parquet_cached = write_cache
-                    .download_if_absent(index_key, &remote_path, layer.object_store(), file_size)
+                    .download_if_absent(index_key, &remote_path, &object_store, file_size)
                     .await;
 
                 if parquet_cached.is_ok() {
@@ -712,7 +722,7 @@ async fn edit_region(
                         .download(
                             index_file_index_key,
                             &index_remote_path,
-                            layer.object_store(),
+                            &object_store,
                             index_file_size,
                         )
                         .await
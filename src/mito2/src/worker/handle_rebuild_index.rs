@@ -47,13 +47,14 @@ impl<S> RegionWorkerLoop<S> {
         file_meta: FileMeta,
         build_type: IndexBuildType,
         result_sender: ResultMpscSender,
-    ) -> IndexBuildTask {
+    ) -> Result<IndexBuildTask> {
         let access_layer = region.access_layer.clone();
 
         let puffin_manager = if let Some(write_cache) = self.cache_manager.write_cache() {
             write_cache.build_puffin_manager()
         } else {
-            access_layer.build_puffin_manager()
+            // Writes the index to the object store of the SST.
+            access_layer.build_puffin_manager(file_meta.storage.as_deref())?
         };
 
         let intermediate_manager = if let Some(write_cache) = self.cache_manager.write_cache() {
@@ -76,7 +77,7 @@ impl<S> RegionWorkerLoop<S> {
             write_cache_enabled: self.cache_manager.write_cache().is_some(),
         });
 
-        IndexBuildTask {
+        Ok(IndexBuildTask {
             region_id: region.region_id,
             file: file.clone(),
             source: IndexBuildSource::new(file_meta, version.metadata.schema_version),
@@ -90,7 +91,7 @@ impl<S> RegionWorkerLoop<S> {
             request_sender: self.sender.clone(),
             indexer_builder: indexer_builder_ref.clone(),
             result_sender,
-        }
+        })
     }
 
     /// Handles manual build index requests.
@@ -198,14 +199,21 @@ impl<S> RegionWorkerLoop<S> {
                 break;
             }
 
-            let task = self.new_index_build_task(
+            let task = match self.new_index_build_task(
                 &region,
                 &version,
                 file_handle.clone(),
                 file_meta,
                 request.build_type.clone(),
                 tx.clone(),
-            );
+            ) {
+                Ok(task) => task,
+                Err(e) => {
+                    // The channel has a slot for each task, so this doesn't block.
+                    let _ = tx.send(Err(e)).await;
+                    continue;
+                }
+            };
             let _ = self
                 .index_build_scheduler
                 .schedule_build(&region.version_control, task)
@@ -444,6 +444,16 @@ impl<S> RegionWorkerLoop<S> {
                 continue;
             }
 
+            // Deletes the replaced rows in the same WAL entry, before putting the rows.
+            if let Some(rows) = sender_req.request.replaced_rows() {
+                region_ctx.push_mutation(
+                    OpType::Delete as i32,
+                    Some(rows),
+                    sender_req.request.hint,
+                    OptionOutputTx::none(),
+                    None,
+                );
+            }
             // Collect requests by region.
             region_ctx.push_mutation(
                 sender_req.request.op_type as i32,
@@ -703,7 +713,7 @@ fn write_region_ids(
 fn check_op_type(append_mode: bool, request: &WriteRequest) -> Result<()> {
     if append_mode {
         ensure!(
-            request.op_type == OpType::Put,
+            request.op_type == OpType::Put && !request.replace_existing,
             InvalidRequestSnafu {
                 region_id: request.region_id,
                 reason: "DELETE is not allowed under append mode",
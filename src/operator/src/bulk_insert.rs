@@ -21,6 +21,7 @@ use api::v1::region::{
 };
 use api::v1::{ArrowIpc, PartitionExprVersion};
 use arrow::array::Array;
+use arrow::datatypes::TimeUnit;
 use arrow::record_batch::RecordBatch;
 use bytes::Bytes;
 use common_base::AffectedRows;
@@ -44,6 +45,38 @@ impl Inserter {
         raw_flight_data: FlightData,
         record_batch: RecordBatch,
         schema_bytes: Bytes,
+    ) -> error::Result<AffectedRows> {
+        let table_info = table.table_info();
+        let min_timestamp = if self.insert_listener.is_some() {
+            table_info
+                .meta
+                .schema
+                .timestamp_column()
+                .and_then(|c| min_timestamp_millis(&record_batch, &c.name))
+        } else {
+            None
+        };
+
+        let result = self
+            .do_bulk_insert(table, raw_flight_data, record_batch, schema_bytes)
+            .await;
+        if let Some(min_timestamp) = min_timestamp {
+            let table_id = table_info.table_id();
+            let table_infos = HashMap::from_iter([(table_id, table_info)]);
+            self.notify_inserted(
+                &table_infos,
+                HashMap::from_iter([(table_id, min_timestamp)]),
+            );
+        }
+        result
+    }
+
+    async fn do_bulk_insert(
+        &self,
+        table: TableRef,
+        raw_flight_data: FlightData,
+        record_batch: RecordBatch,
+        schema_bytes: Bytes,
     ) -> error::Result<AffectedRows> {
         let table_info = table.table_info();
         let table_id = table_info.table_id();
@@ -318,6 +351,19 @@ impl Inserter {
 }
 
 /// Calculate the timestamp range of record batch. Return `None` if record batch is empty.
+/// Returns the minimal value of the time index column in milliseconds.
+fn min_timestamp_millis(rb: &RecordBatch, timestamp_index_name: &str) -> Option<i64> {
+    let ts_col = rb.column_by_name(timestamp_index_name)?;
+    let (primitive, unit) = datatypes::timestamp::timestamp_array_to_primitive(ts_col)?;
+    let min = arrow::compute::min(&primitive)?;
+    Some(match unit {
+        TimeUnit::Second => min.saturating_mul(1000),
+        TimeUnit::Millisecond => min,
+        TimeUnit::Microsecond => min.div_euclid(1000),
+        TimeUnit::Nanosecond => min.div_euclid(1_000_000),
+    })
+}
+
 fn extract_timestamps(rb: &RecordBatch, timestamp_index_name: &str) -> error::Result<Vec<i64>> {
     let ts_col = rb
         .column_by_name(timestamp_index_name)
@@ -29,6 +29,7 @@ use session::context::QueryContextRef;
 use snafu::{OptionExt, ResultExt, ensure};
 use table::TableRef;
 use table::requests::DeleteRequest as TableDeleteRequest;
+use table::table_reference::TableReference;
 
 use crate::error::{
     CatalogSnafu, FindRegionLeaderSnafu, InvalidDeleteRequestSnafu, JoinTaskSnafu,
@@ -37,11 +38,14 @@ use crate::error::{
 use crate::region_req_factory::RegionRequestFactory;
 use crate::req_convert::common::preprocess_row_delete_requests;
 use crate::req_convert::delete::{ColumnToRow, RowToRegion, TableToRegion};
+use crate::table::TableChangeListenerRef;
 
 pub struct Deleter {
     catalog_manager: CatalogManagerRef,
     partition_manager: PartitionRuleManagerRef,
     node_manager: NodeManagerRef,
+    /// Listener notified after rows are deleted from tables.
+    table_change_listener: Option<TableChangeListenerRef>,
 }
 
 pub type DeleterRef = Arc<Deleter>;
@@ -56,9 +60,16 @@ impl Deleter {
             catalog_manager,
             partition_manager,
             node_manager,
+            table_change_listener: None,
         }
     }
 
+    /// Sets the listener notified after rows are deleted.
+    pub fn with_table_change_listener(mut self, listener: TableChangeListenerRef) -> Self {
+        self.table_change_listener = Some(listener);
+        self
+    }
+
     pub async fn handle_column_deletes(
         &self,
         requests: DeleteRequests,
@@ -84,6 +95,11 @@ impl Deleter {
         validate_column_count_match(&requests)?;
 
         let requests = self.trim_columns(requests, &ctx).await?;
+        let table_names = requests
+            .deletes
+            .iter()
+            .map(|req| req.table_name.clone())
+            .collect::<Vec<_>>();
         let deletes = RowToRegion::new(
             self.catalog_manager.as_ref(),
             self.partition_manager.as_ref(),
@@ -93,6 +109,13 @@ impl Deleter {
         .await?;
 
         let affected_rows = self.do_request(deletes, &ctx).await?;
+        if let Some(listener) = &self.table_change_listener {
+            let catalog = ctx.current_catalog();
+            let schema = ctx.current_schema();
+            for table_name in &table_names {
+                listener.on_table_changed(TableReference::full(catalog, &schema, table_name));
+            }
+        }
 
         Ok(Output::new_with_affected_rows(affected_rows))
     }
@@ -113,6 +136,13 @@ impl Deleter {
             .await?;
 
         let affected_rows = self.do_request(deletes, &ctx).await?;
+        if let Some(listener) = &self.table_change_listener {
+            listener.on_table_changed(TableReference::full(
+                &table_info.catalog_name,
+                &table_info.schema_name,
+                &table_info.name,
+            ));
+        }
         Ok(affected_rows as _)
     }
 }
@@ -938,6 +938,28 @@ pub enum Error {
         #[snafu(implicit)]
         location: Location,
     },
+
+    #[snafu(display("User {} already exists", name))]
+    UserExists {
+        name: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("User {} not found", name))]
+    UserNotFound {
+        name: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Failed to encode password of user {}", name))]
+    EncodeUserPassword {
+        name: String,
+        source: auth::error::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -983,7 +1005,10 @@ impl ErrorExt for Error {
             | Error::PartitionExprToPb { .. }
             | Error::CursorNotFound { .. }
             | Error::CursorExists { .. }
+            | Error::UserExists { .. }
             | Error::CreatePartitionRules { .. } => StatusCode::InvalidArguments,
+            Error::UserNotFound { .. } => StatusCode::UserNotFound,
+            Error::EncodeUserPassword { source, .. } => source.status_code(),
             Error::TableAlreadyExists { .. } | Error::ViewAlreadyExists { .. } => {
                 StatusCode::TableAlreadyExists
             }
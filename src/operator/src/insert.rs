@@ -22,9 +22,10 @@ use api::v1::region::{
     InsertRequest as RegionInsertRequest, InsertRequests as RegionInsertRequests,
     RegionRequestHeader,
 };
+use api::v1::value::ValueData;
 use api::v1::{
     AlterTableExpr, ColumnDataType, ColumnSchema, CreateTableExpr, InsertRequests,
-    RowInsertRequest, RowInsertRequests, SemanticType,
+    RowInsertRequest, RowInsertRequests, Rows, SemanticType,
 };
 use catalog::CatalogManagerRef;
 use client::{OutputData, OutputMeta};
@@ -60,6 +61,8 @@ use store_api::mito_engine_options::{
     APPEND_MODE_KEY, COMPACTION_TYPE, COMPACTION_TYPE_TWCS, MERGE_MODE_KEY, TTL_KEY,
     TWCS_TIME_WINDOW,
 };
+use store_api::region_request::replace_existing_rows_context;
+use store_api::storage::consts::is_internal_column;
 use store_api::storage::{RegionId, TableId};
 use table::TableRef;
 use table::metadata::TableInfo;
@@ -91,10 +94,21 @@ pub struct Inserter {
     /// When `false`, missing tables are never auto-created regardless of the
     /// per-request `auto_create_table` hint. When `true`, the hint still applies.
     auto_create_table: bool,
+    /// Listener notified after rows are written to tables.
+    pub(crate) insert_listener: Option<InsertListenerRef>,
 }
 
 pub type InserterRef = Arc<Inserter>;
 
+/// Observes the rows written through the [`Inserter`].
+pub trait InsertListener: Send + Sync {
+    /// Called after rows are written to `table`, with the minimal time index value
+    /// of the written rows in milliseconds.
+    fn on_inserted(&self, table: TableReference, min_timestamp_millis: i64);
+}
+
+pub type InsertListenerRef = Arc<dyn InsertListener>;
+
 /// Hint for the table type to create automatically.
 #[derive(Clone)]
 pub enum AutoCreateTableType {
@@ -160,6 +174,32 @@ impl Inserter {
             node_manager,
             table_flownode_set_cache,
             auto_create_table,
+            insert_listener: None,
+        }
+    }
+
+    /// Sets the listener notified after rows are written.
+    pub fn with_insert_listener(mut self, listener: InsertListenerRef) -> Self {
+        self.insert_listener = Some(listener);
+        self
+    }
+
+    /// Notifies the insert listener of the minimal timestamp written to each table.
+    pub(crate) fn notify_inserted(
+        &self,
+        table_infos: &HashMap<TableId, Arc<TableInfo>>,
+        min_timestamps: HashMap<TableId, i64>,
+    ) {
+        let Some(listener) = &self.insert_listener else {
+            return;
+        };
+        for (table_id, min_timestamp) in min_timestamps {
+            if let Some(info) = table_infos.get(&table_id) {
+                listener.on_inserted(
+                    TableReference::full(&info.catalog_name, &info.schema_name, &info.name),
+                    min_timestamp,
+                );
+            }
         }
     }
 
@@ -270,6 +310,7 @@ impl Inserter {
                 .unwrap_or_default()
         });
         validate_column_count_match(&requests)?;
+        validate_no_internal_columns(&requests)?;
 
         let CreateAlterTableResult {
             instant_table_ids,
@@ -297,7 +338,7 @@ impl Inserter {
         .convert(requests)
         .await?;
 
-        self.do_request(inserts, &table_infos, &ctx).await
+        self.do_request(inserts, &table_infos, &ctx, false).await
     }
 
     /// Handles row inserts request with metric engine.
@@ -316,6 +357,7 @@ impl Inserter {
                 .unwrap_or_default()
         });
         validate_column_count_match(&requests)?;
+        validate_no_internal_columns(&requests)?;
 
         // check and create physical table
         self.create_physical_table_on_demand(&ctx, physical_table.clone(), statement_executor)
@@ -343,13 +385,32 @@ impl Inserter {
             .convert(requests)
             .await?;
 
-        self.do_request(inserts, &table_infos, &ctx).await
+        self.do_request(inserts, &table_infos, &ctx, false).await
     }
 
     pub async fn handle_table_insert(
         &self,
         request: TableInsertRequest,
         ctx: QueryContextRef,
+    ) -> Result<Output> {
+        self.handle_table_request(request, ctx, false).await
+    }
+
+    /// Handles a table insert request whose rows replace the existing rows with the
+    /// same key instead of being merged with them.
+    pub async fn handle_table_replace(
+        &self,
+        request: TableInsertRequest,
+        ctx: QueryContextRef,
+    ) -> Result<Output> {
+        self.handle_table_request(request, ctx, true).await
+    }
+
+    async fn handle_table_request(
+        &self,
+        request: TableInsertRequest,
+        ctx: QueryContextRef,
+        replace_existing: bool,
     ) -> Result<Output> {
         let catalog = request.catalog_name.as_str();
         let schema = request.schema_name.as_str();
@@ -366,7 +427,8 @@ impl Inserter {
 
         let table_infos = HashMap::from_iter([(table_info.table_id(), table_info.clone())]);
 
-        self.do_request(inserts, &table_infos, &ctx).await
+        self.do_request(inserts, &table_infos, &ctx, replace_existing)
+            .await
     }
 
     pub async fn handle_statement_insert(
@@ -381,16 +443,50 @@ impl Inserter {
 
         let table_infos = HashMap::from_iter([(table_info.table_id(), table_info.clone())]);
 
-        self.do_request(inserts, &table_infos, ctx).await
+        self.do_request(inserts, &table_infos, ctx, false).await
     }
 }
 
+/// Returns the minimal time index value in milliseconds of the rows written to each table.
+fn min_timestamps_by_table(requests: &RegionInsertRequests) -> HashMap<TableId, i64> {
+    let mut min_timestamps = HashMap::new();
+    for request in &requests.requests {
+        let Some(min_timestamp) = request.rows.as_ref().and_then(min_timestamp_millis) else {
+            continue;
+        };
+        let table_id = RegionId::from_u64(request.region_id).table_id();
+        min_timestamps
+            .entry(table_id)
+            .and_modify(|ts: &mut i64| *ts = (*ts).min(min_timestamp))
+            .or_insert(min_timestamp);
+    }
+    min_timestamps
+}
+
+fn min_timestamp_millis(rows: &Rows) -> Option<i64> {
+    let index = rows
+        .schema
+        .iter()
+        .position(|c| c.semantic_type == SemanticType::Timestamp as i32)?;
+    rows.rows
+        .iter()
+        .filter_map(|row| match row.values.get(index)?.value_data.as_ref()? {
+            ValueData::TimestampSecondValue(v) => Some(v.saturating_mul(1000)),
+            ValueData::TimestampMillisecondValue(v) => Some(*v),
+            ValueData::TimestampMicrosecondValue(v) => Some(v.div_euclid(1000)),
+            ValueData::TimestampNanosecondValue(v) => Some(v.div_euclid(1_000_000)),
+            _ => None,
+        })
+        .min()
+}
+
 impl Inserter {
     async fn do_request(
         &self,
         requests: InstantAndNormalInsertRequests,
         table_infos: &HashMap<TableId, Arc<TableInfo>>,
         ctx: &QueryContextRef,
+        replace_existing: bool,
     ) -> Result<Output> {
         // Fill impure default values in the request
         let requests = fill_reqs_with_impure_default(table_infos, requests)?;
@@ -401,17 +497,27 @@ impl Inserter {
             requests,
             ctx.channel() as u8
         );
-        let request_factory = RegionRequestFactory::new(RegionRequestHeader {
+        let mut header = RegionRequestHeader {
             tracing_context: TracingContext::from_current_span().to_w3c(),
             dbname: ctx.get_db_string(),
             ..Default::default()
-        });
+        };
+        if replace_existing {
+            header.query_context = Some(replace_existing_rows_context());
+        }
+        let request_factory = RegionRequestFactory::new(header);
 
         let InstantAndNormalInsertRequests {
             normal_requests,
             instant_requests,
         } = requests;
 
+        let min_timestamps = if self.insert_listener.is_some() {
+            min_timestamps_by_table(&normal_requests)
+        } else {
+            HashMap::new()
+        };
+
         // Mirror requests for source table to flownode asynchronously
         let flow_mirror_task = FlowMirrorTask::new(
             &self.table_flownode_set_cache,
@@ -440,10 +546,11 @@ impl Inserter {
                         .context(RequestInsertsSnafu)
                 })
             });
-        let results = future::try_join_all(write_tasks)
-            .await
-            .context(JoinTaskSnafu)?;
+        let results = future::try_join_all(write_tasks).await;
+        // Notify even if some writes failed, as others may have landed.
+        self.notify_inserted(table_infos, min_timestamps);
         let affected_rows = results
+            .context(JoinTaskSnafu)?
             .into_iter()
             .map(|resp| resp.map(|r| r.affected_rows))
             .sum::<Result<AffectedRows>>()?;
@@ -1185,6 +1292,10 @@ impl Inserter {
     pub fn table_flownode_set_cache(&self) -> &TableFlownodeSetCacheRef {
         &self.table_flownode_set_cache
     }
+
+    pub fn insert_listener(&self) -> Option<&InsertListenerRef> {
+        self.insert_listener.as_ref()
+    }
 }
 
 fn request_is_native_histogram(request_schema: &[ColumnSchema]) -> bool {
@@ -1233,6 +1344,30 @@ fn validate_column_count_match(requests: &RowInsertRequests) -> Result<()> {
     Ok(())
 }
 
+/// Rejects the requests writing the internal columns of the storage engine, e.g.
+/// [OP_TYPE_COLUMN_NAME](store_api::storage::consts::OP_TYPE_COLUMN_NAME).
+fn validate_no_internal_columns(requests: &RowInsertRequests) -> Result<()> {
+    for request in &requests.inserts {
+        let Some(rows) = &request.rows else {
+            continue;
+        };
+        if let Some(column) = rows
+            .schema
+            .iter()
+            .find(|column| is_internal_column(&column.column_name))
+        {
+            return InvalidInsertRequestSnafu {
+                reason: format!(
+                    "column {} of table {} is reserved for internal use",
+                    column.column_name, request.table_name
+                ),
+            }
+            .fail();
+        }
+    }
+    Ok(())
+}
+
 /// Fill table options for a new table by create type.
 pub fn fill_table_options_for_create(
     table_options: &mut std::collections::HashMap<String, String>,
@@ -1891,4 +2026,29 @@ mod tests {
             table_options.get(MERGE_MODE_KEY).map(String::as_str)
         );
     }
+
+    #[test]
+    fn test_reject_internal_columns() {
+        let request = |field: &str| RowInsertRequests {
+            inserts: vec![RowInsertRequest {
+                table_name: "demo".to_string(),
+                rows: Some(Rows {
+                    schema: vec![
+                        time_index_column_schema("ts", ColumnDataType::TimestampMillisecond),
+                        field_column_schema(field, ColumnDataType::Uint8),
+                    ],
+                    rows: vec![],
+                }),
+            }],
+        };
+
+        validate_no_internal_columns(&request("op_type")).unwrap();
+        let err =
+            validate_no_internal_columns(&request(store_api::storage::consts::OP_TYPE_COLUMN_NAME))
+                .unwrap_err();
+        assert!(
+            matches!(err, crate::error::Error::InvalidInsertRequest { .. }),
+            "{err}"
+        );
+    }
 }
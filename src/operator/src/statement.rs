@@ -27,11 +27,13 @@ pub mod semantic_graph;
 mod set;
 mod show;
 mod tql;
+mod user;
 
 use std::collections::HashMap;
 use std::sync::Arc;
 
 use api::v1::RowInsertRequests;
+use auth::UserProviderRef;
 use catalog::CatalogManagerRef;
 use catalog::kvbackend::KvBackendCatalogManager;
 use catalog::process_manager::ProcessManagerRef;
@@ -97,6 +99,7 @@ use crate::error::{
 use crate::insert::InserterRef;
 use crate::statement::copy_database::{COPY_DATABASE_TIME_END_KEY, COPY_DATABASE_TIME_START_KEY};
 use crate::statement::set::set_allow_query_fallback;
+use crate::table::TableChangeListenerRef;
 
 /// A configurator that customizes or enhances a [`StatementExecutor`].
 #[async_trait::async_trait]
@@ -146,6 +149,10 @@ pub struct StatementExecutor {
     origin_frontend_addr: String,
     admin_function_service: AdminFunctionServiceRef,
     pub(crate) local_file_access: LocalFileAccess,
+    /// The user provider for authentication, used to reject `CREATE USER` of configured users.
+    user_provider: Option<UserProviderRef>,
+    /// Listener notified after tables are created, altered, truncated or dropped.
+    table_change_listener: Option<TableChangeListenerRef>,
     #[cfg(feature = "enterprise")]
     create_database_handler: Option<CreateDatabaseHandlerRef>,
     #[cfg(feature = "enterprise")]
@@ -201,6 +208,8 @@ impl StatementExecutor {
             origin_frontend_addr,
             admin_function_service,
             local_file_access,
+            user_provider: None,
+            table_change_listener: None,
             #[cfg(feature = "enterprise")]
             create_database_handler: None,
             #[cfg(feature = "enterprise")]
@@ -216,6 +225,23 @@ impl StatementExecutor {
         self
     }
 
+    pub fn with_user_provider(mut self, user_provider: UserProviderRef) -> Self {
+        self.user_provider = Some(user_provider);
+        self
+    }
+
+    pub fn with_table_change_listener(mut self, listener: TableChangeListenerRef) -> Self {
+        self.table_change_listener = Some(listener);
+        self
+    }
+
+    /// Notifies the table change listener that the table is changed.
+    pub(crate) fn notify_table_changed(&self, catalog: &str, schema: &str, table: &str) {
+        if let Some(listener) = &self.table_change_listener {
+            listener.on_table_changed(TableReference::full(catalog, schema, table));
+        }
+    }
+
     #[cfg(feature = "enterprise")]
     pub fn with_trigger_querier(mut self, querier: TriggerQuerierRef) -> Self {
         self.trigger_querier = Some(querier);
@@ -243,9 +269,10 @@ impl StatementExecutor {
     #[tracing::instrument(skip_all)]
     pub async fn execute_sql(&self, stmt: Statement, query_ctx: QueryContextRef) -> Result<Output> {
         match stmt {
-            Statement::Query(_) | Statement::Explain(_) | Statement::Delete(_) => {
-                self.plan_exec(QueryStatement::Sql(stmt), query_ctx).await
-            }
+            Statement::Query(_)
+            | Statement::Explain(_)
+            | Statement::Delete(_)
+            | Statement::Update(_) => self.plan_exec(QueryStatement::Sql(stmt), query_ctx).await,
 
             Statement::DeclareCursor(declare_cursor) => {
                 self.declare_cursor(declare_cursor, query_ctx).await
@@ -504,6 +531,11 @@ impl StatementExecutor {
             Statement::Admin(admin) => self.execute_admin_command(admin, query_ctx).await,
             Statement::Kill(kill) => self.execute_kill(query_ctx, kill).await,
             Statement::ShowProcesslist(show) => self.show_processlist(show, query_ctx).await,
+            Statement::CreateUser(stmt) => self.create_user(stmt).await,
+            Statement::AlterUser(stmt) => self.alter_user(stmt).await,
+            Statement::DropUser(stmt) => self.drop_user(stmt).await,
+            Statement::Grant(stmt) => self.grant(stmt, query_ctx).await,
+            Statement::Revoke(stmt) => self.revoke(stmt, query_ctx).await,
         }
     }
 
@@ -70,6 +70,9 @@ use datatypes::value::Value;
 use datatypes::vectors::{StringVector, VectorRef};
 use humantime::parse_duration;
 use partition::expr::{Operand, PartitionExpr, RestrictedOp};
+use partition::hash::{
+    HASH_BUCKET_FUNCTION, HashBucket, HashPartitionRule, as_hash_partition, contains_hash_bucket,
+};
 use partition::multi_dim::MultiDimPartitionRule;
 use query::parser::QueryStatement;
 use query::plan::extract_and_rewrite_full_table_names;
@@ -90,7 +93,10 @@ use sql::statements::create::{
     CreateExternalTable, CreateFlow, CreateTable, CreateTableLike, CreateView, Partitions,
 };
 use sql::statements::statement::Statement;
-use sqlparser::ast::{Expr, Ident, UnaryOperator, Value as ParserValue};
+use sqlparser::ast::{
+    Expr, Function, FunctionArg, FunctionArgExpr, FunctionArguments, Ident, UnaryOperator,
+    Value as ParserValue,
+};
 use store_api::metric_engine_consts::{LOGICAL_TABLE_METADATA_KEY, METRIC_ENGINE_NAME};
 use store_api::mito_engine_options::APPEND_MODE_KEY;
 use substrait::{DFLogicalSubstraitConvertor, SubstraitPlan};
@@ -516,6 +522,12 @@ impl StatementExecutor {
                 violated: "expected table_id",
             })?;
         info!("Successfully created table '{table_name}' with table id {table_id}");
+        // Results of queries against the table before it exists are stale now.
+        self.notify_table_changed(
+            &table_name.catalog_name,
+            &table_name.schema_name,
+            &table_name.table_name,
+        );
 
         table_info.ident.table_id = table_id;
 
@@ -578,6 +590,13 @@ impl StatementExecutor {
             }
         );
         info!("Successfully created logical tables: {:?}", table_ids);
+        for create_table in create_table_exprs {
+            self.notify_table_changed(
+                &create_table.catalog_name,
+                &create_table.schema_name,
+                &create_table.table_name,
+            );
+        }
 
         // Reacquire table infos from catalog so logical tables inherit the latest partition
         // metadata (e.g. partition_key_indices) from their physical tables.
@@ -667,16 +686,21 @@ impl StatementExecutor {
                 table_name: physical_table_name.clone(),
             })?;
 
-        let multi_dim_rule = partition_rule
-            .as_ref()
-            .as_any()
-            .downcast_ref::<MultiDimPartitionRule>()
-            .context(InvalidPartitionRuleSnafu {
-                reason: "physical table partition rule is not range-based",
-            })?;
+        let partition_rule = partition_rule.as_ref().as_any();
+        let physical_partition_exprs =
+            if let Some(rule) = partition_rule.downcast_ref::<MultiDimPartitionRule>() {
+                rule.exprs()
+            } else if let Some(rule) = partition_rule.downcast_ref::<HashPartitionRule>() {
+                rule.exprs()
+            } else {
+                return InvalidPartitionRuleSnafu {
+                    reason: "physical table partition rule is neither range-based nor hash-based",
+                }
+                .fail();
+            };
 
         // TODO(ruihang): project physical partition exprs to logical partition column
-        let mut physical_partition_exprs = multi_dim_rule.exprs().to_vec();
+        let mut physical_partition_exprs = physical_partition_exprs.to_vec();
         logical_partition_exprs.sort_unstable();
         physical_partition_exprs.sort_unstable();
 
@@ -1354,6 +1378,24 @@ impl StatementExecutor {
             groups.entry(physical_table_id).or_default().push(expr);
         }
 
+        let table_names = groups
+            .values()
+            .flatten()
+            .map(|expr| {
+                let catalog = if expr.catalog_name.is_empty() {
+                    query_context.current_catalog().to_string()
+                } else {
+                    expr.catalog_name.clone()
+                };
+                let schema = if expr.schema_name.is_empty() {
+                    query_context.current_schema()
+                } else {
+                    expr.schema_name.clone()
+                };
+                TableName::new(catalog, schema, &expr.table_name)
+            })
+            .collect::<Vec<_>>();
+
         // Submit procedure for each physical table
         let mut handles = Vec::with_capacity(groups.len());
         for (_physical_table_id, exprs) in groups {
@@ -1362,6 +1404,13 @@ impl StatementExecutor {
             handles.push(fut);
         }
         let _results = futures::future::try_join_all(handles).await?;
+        for table_name in &table_names {
+            self.notify_table_changed(
+                &table_name.catalog_name,
+                &table_name.schema_name,
+                &table_name.table_name,
+            );
+        }
 
         Ok(Output::new_with_affected_rows(0))
     }
@@ -1427,6 +1476,11 @@ impl StatementExecutor {
                 )
                 .await
                 .context(error::InvalidateTableCacheSnafu)?;
+            self.notify_table_changed(
+                &table_name.catalog_name,
+                &table_name.schema_name,
+                &table_name.table_name,
+            );
         }
         Ok(Output::new_with_affected_rows(0))
     }
@@ -1511,6 +1565,11 @@ impl StatementExecutor {
         let table_id = table.table_info().table_id();
         self.truncate_table_procedure(&table_name, table_id, time_ranges, query_context)
             .await?;
+        self.notify_table_changed(
+            &table_name.catalog_name,
+            &table_name.schema_name,
+            &table_name.table_name,
+        );
 
         Ok(Output::new_with_affected_rows(0))
     }
@@ -1537,7 +1596,7 @@ impl StatementExecutor {
     #[tracing::instrument(skip_all)]
     pub async fn repartition_table(
         &self,
-        request: RepartitionRequest,
+        mut request: RepartitionRequest,
         query_context: &QueryContextRef,
     ) -> Result<Output> {
         // Check if the schema is read-only.
@@ -1581,6 +1640,45 @@ impl StatementExecutor {
         let table_info = table.table_info();
         let existing_partition_columns = table_info.meta.partition_columns().collect::<Vec<_>>();
         let column_schemas = table_info.meta.schema.column_schemas();
+
+        // Parse existing partition expressions from region routes.
+        let mut existing_partition_exprs =
+            Vec::with_capacity(physical_table_route.region_routes.len());
+        for route in &physical_table_route.region_routes {
+            let expr_json = route.region.partition_expr();
+            if !expr_json.is_empty() {
+                match PartitionExpr::from_json_str(&expr_json) {
+                    Ok(Some(expr)) => existing_partition_exprs.push(expr),
+                    Ok(None) => {
+                        // Empty
+                    }
+                    Err(e) => {
+                        return Err(e).context(DeserializePartitionExprSnafu);
+                    }
+                }
+            }
+        }
+
+        // `PARTITION BY HASH` on a hash partitioned table re-buckets all existing
+        // partitions, so it is submitted as a repartition from all of them.
+        if let RepartitionSource::Unpartitioned { partition_columns } = &request.source
+            && as_hash_partition(&existing_partition_exprs).is_some()
+            && !request.into_exprs.is_empty()
+            && request.into_exprs.iter().all(is_hash_bucket_expr)
+        {
+            let target_partition_columns = (!partition_columns
+                .iter()
+                .eq(existing_partition_columns.iter().map(|column| &column.name)))
+            .then(|| partition_columns.clone());
+            request.source = RepartitionSource::Partitions {
+                from_exprs: existing_partition_exprs
+                    .iter()
+                    .map(|expr| expr.to_parser_expr())
+                    .collect(),
+                target_partition_columns,
+            };
+        }
+
         // `REPARTITION ... ON COLUMNS` uses overwrite semantics: the provided
         // columns are the full target partition columns, not an extension of the
         // current ones. Therefore source expressions are converted with the
@@ -1668,29 +1766,12 @@ impl StatementExecutor {
         if matches!(&request.source, RepartitionSource::Partitions { .. })
             && from_partition_exprs.len() > 1
             && into_partition_exprs.len() == 1
+            && !contains_hash_bucket(&into_partition_exprs)
             && let Some(expr) = into_partition_exprs.pop()
         {
             into_partition_exprs.push(partition::simplify::simplify_merged_partition_expr(expr));
         }
 
-        // Parse existing partition expressions from region routes.
-        let mut existing_partition_exprs =
-            Vec::with_capacity(physical_table_route.region_routes.len());
-        for route in &physical_table_route.region_routes {
-            let expr_json = route.region.partition_expr();
-            if !expr_json.is_empty() {
-                match PartitionExpr::from_json_str(&expr_json) {
-                    Ok(Some(expr)) => existing_partition_exprs.push(expr),
-                    Ok(None) => {
-                        // Empty
-                    }
-                    Err(e) => {
-                        return Err(e).context(DeserializePartitionExprSnafu);
-                    }
-                }
-            }
-        }
-
         // Validate that from_partition_exprs are a subset of existing partition exprs.
         // We compare PartitionExpr directly since it implements Eq.
         if matches!(&request.source, RepartitionSource::Partitions { .. }) {
@@ -1724,14 +1805,8 @@ impl StatementExecutor {
         let new_partition_exprs_len = new_partition_exprs.len();
         let from_partition_exprs_len = from_partition_exprs.len();
 
-        // Validate the new partition expressions using MultiDimPartitionRule and PartitionChecker.
-        let _ = MultiDimPartitionRule::try_new(
-            target_partition_column_names,
-            vec![],
-            new_partition_exprs,
-            true,
-        )
-        .context(InvalidPartitionSnafu)?;
+        // Validate the new partition expressions using the partition rule and PartitionChecker.
+        validate_partition_rule(target_partition_column_names, new_partition_exprs)?;
 
         let ddl_options = parse_ddl_options(&request.options)?;
         let serialize_exprs = |exprs: Vec<PartitionExpr>| -> Result<Vec<String>> {
@@ -1835,6 +1910,7 @@ impl StatementExecutor {
         };
 
         let table_name = expr.table_name.clone();
+        let changed_table = TableName::new(&catalog_name, &schema_name, &table_name);
 
         let table = self
             .catalog_manager
@@ -1931,6 +2007,11 @@ impl StatementExecutor {
             .invalidate(&Context::default(), &invalidate_keys)
             .await
             .context(error::InvalidateTableCacheSnafu)?;
+        self.notify_table_changed(
+            &changed_table.catalog_name,
+            &changed_table.schema_name,
+            &changed_table.table_name,
+        );
 
         Ok(Output::new_with_affected_rows(0))
     }
@@ -2243,13 +2324,39 @@ pub fn parse_partitions(
         find_partition_entries(create_table, &partitions, &partition_columns, query_ctx)?;
 
     // Validates partition
-    let exprs = partition_exprs.clone();
-    MultiDimPartitionRule::try_new(partition_columns.clone(), vec![], exprs, true)
-        .context(InvalidPartitionSnafu)?;
+    validate_partition_rule(partition_columns.clone(), partition_exprs.clone())?;
 
     Ok((partition_exprs, partition_columns))
 }
 
+/// Validates the partition exprs by building the partition rule they describe.
+fn validate_partition_rule(
+    partition_columns: Vec<String>,
+    partition_exprs: Vec<PartitionExpr>,
+) -> Result<()> {
+    if contains_hash_bucket(&partition_exprs) {
+        HashPartitionRule::try_new(partition_columns, vec![], partition_exprs)
+            .context(InvalidPartitionSnafu)?;
+    } else {
+        MultiDimPartitionRule::try_new(partition_columns, vec![], partition_exprs, true)
+            .context(InvalidPartitionSnafu)?;
+    }
+    Ok(())
+}
+
+/// Returns true if the SQL expr is a `hash_bucket(..) = i` partition expr.
+fn is_hash_bucket_expr(expr: &Expr) -> bool {
+    matches!(
+        expr,
+        Expr::BinaryOp { left, .. }
+            if matches!(
+                left.as_ref(),
+                Expr::Function(func)
+                    if func.name.to_string().eq_ignore_ascii_case(HASH_BUCKET_FUNCTION)
+            )
+    )
+}
+
 fn parse_partitions_for_logical_validation(
     create_table: &CreateTableExpr,
     partitions: &Partitions,
@@ -2284,13 +2391,7 @@ fn parse_partitions_for_logical_validation(
         partition_exprs.push(partition_expr);
     }
 
-    MultiDimPartitionRule::try_new(
-        partition_columns.clone(),
-        vec![],
-        partition_exprs.clone(),
-        true,
-    )
-    .context(InvalidPartitionSnafu)?;
+    validate_partition_rule(partition_columns.clone(), partition_exprs.clone())?;
 
     Ok((partition_columns, partition_exprs))
 }
@@ -2641,6 +2742,14 @@ fn ensure_partition_operand_columns_in_target(
             ensure_partition_operand_columns_in_target(&expr.lhs, target_partition_columns)?;
             ensure_partition_operand_columns_in_target(&expr.rhs, target_partition_columns)?;
         }
+        Operand::HashBucket(hash) => {
+            for column in &hash.columns {
+                ensure_partition_operand_columns_in_target(
+                    &Operand::Column(column.clone()),
+                    target_partition_columns,
+                )?;
+            }
+        }
         Operand::Value(_) => {}
     }
 
@@ -2692,6 +2801,17 @@ fn convert_one_expr(
             let value = convert_value(&v.value, data_type, timezone, Some(*unary_op))?;
             (Operand::Value(value), op, Operand::Column(column_name))
         }
+        // hash_bucket(cols.., n), bucket
+        (Expr::Function(func), Expr::Value(value)) => {
+            let hash = convert_hash_bucket(func, column_name_and_type)?;
+            let value = convert_value(
+                &value.value,
+                ConcreteDataType::uint32_datatype(),
+                timezone,
+                None,
+            )?;
+            (Operand::HashBucket(hash), op, Operand::Value(value))
+        }
         (Expr::BinaryOp { .. }, Expr::BinaryOp { .. }) => {
             // sub-expr must against another sub-expr
             let lhs = convert_one_expr(left, column_name_and_type, timezone)?;
@@ -2709,6 +2829,54 @@ fn convert_one_expr(
     Ok(PartitionExpr::new(lhs, op, rhs))
 }
 
+/// Converts `hash_bucket(col1, col2, .., num_buckets)` to [HashBucket].
+fn convert_hash_bucket(
+    func: &Function,
+    column_name_and_type: &HashMap<&String, ConcreteDataType>,
+) -> Result<HashBucket> {
+    let invalid = || InvalidPartitionRuleSnafu {
+        reason: format!("invalid partition expr {func}"),
+    };
+    ensure!(
+        func.name
+            .to_string()
+            .eq_ignore_ascii_case(HASH_BUCKET_FUNCTION),
+        invalid()
+    );
+    let FunctionArguments::List(arg_list) = &func.args else {
+        return invalid().fail();
+    };
+    let args = arg_list
+        .args
+        .iter()
+        .map(|arg| match arg {
+            FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)) => Some(expr),
+            _ => None,
+        })
+        .collect::<Option<Vec<_>>>()
+        .with_context(invalid)?;
+    let Some((Expr::Value(num_buckets), columns)) = args.split_last() else {
+        return invalid().fail();
+    };
+    let num_buckets = match &num_buckets.value {
+        ParserValue::Number(n, _) => n.parse::<u32>().ok(),
+        _ => None,
+    }
+    .with_context(invalid)?;
+    let columns = columns
+        .iter()
+        .map(|expr| match expr {
+            Expr::Identifier(ident) => {
+                convert_identifier(ident, column_name_and_type).map(|(column, _)| column)
+            }
+            _ => invalid().fail(),
+        })
+        .collect::<Result<Vec<_>>>()?;
+    ensure!(!columns.is_empty(), invalid());
+
+    Ok(HashBucket::new(columns, num_buckets))
+}
+
 fn convert_identifier(
     ident: &Ident,
     column_name_and_type: &HashMap<&String, ConcreteDataType>,
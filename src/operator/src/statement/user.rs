@@ -0,0 +1,240 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use auth::managed_user_password_verifiers;
+use common_meta::cache_invalidator::Context;
+use common_meta::instruction::CacheIdent;
+use common_meta::key::user::{UserManager, UserPrivilege, UserValue};
+use common_query::Output;
+use session::context::QueryContextRef;
+use snafu::{OptionExt, ResultExt, ensure};
+use sql::statements::user::{
+    AlterUser, CreateUser, DropUser, Grant, GrantTarget, Privilege, Revoke,
+};
+
+use crate::error::{
+    CatalogSnafu, EncodeUserPasswordSnafu, InvalidateTableCacheSnafu, Result, SchemaNotFoundSnafu,
+    TableMetadataManagerSnafu, TableNotFoundSnafu, UserExistsSnafu, UserNotFoundSnafu,
+};
+use crate::statement::StatementExecutor;
+
+/// A resolved [GrantTarget].
+struct GrantTargetRef<'a> {
+    catalog: &'a str,
+    schema: String,
+    table: Option<&'a str>,
+}
+
+fn to_user_privileges(privileges: &[Privilege]) -> Vec<UserPrivilege> {
+    let mut result = Vec::with_capacity(privileges.len());
+    for privilege in privileges {
+        let expanded: &[UserPrivilege] = match privilege {
+            Privilege::Select => &[UserPrivilege::Select],
+            Privilege::Insert => &[UserPrivilege::Insert],
+            Privilege::Delete => &[UserPrivilege::Delete],
+            Privilege::Alter => &[UserPrivilege::Alter],
+            Privilege::Drop => &[UserPrivilege::Drop],
+            Privilege::All => &[
+                UserPrivilege::Select,
+                UserPrivilege::Insert,
+                UserPrivilege::Delete,
+                UserPrivilege::Alter,
+                UserPrivilege::Drop,
+            ],
+        };
+        for privilege in expanded {
+            if !result.contains(privilege) {
+                result.push(*privilege);
+            }
+        }
+    }
+    result
+}
+
+impl StatementExecutor {
+    fn user_manager(&self) -> UserManager {
+        UserManager::new(self.table_metadata_manager.kv_backend().clone())
+    }
+
+    async fn invalidate_user_cache(&self, username: &str) -> Result<()> {
+        self.cache_invalidator
+            .invalidate(
+                &Context::default(),
+                &[CacheIdent::ManagedUser(username.to_string())],
+            )
+            .await
+            .context(InvalidateTableCacheSnafu)
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn create_user(&self, stmt: CreateUser) -> Result<Output> {
+        // A managed user must not share its name with a configured user.
+        ensure!(
+            !self
+                .user_provider
+                .as_ref()
+                .is_some_and(|provider| provider.has_configured_user(&stmt.name)),
+            UserExistsSnafu { name: stmt.name }
+        );
+        let verifiers = managed_user_password_verifiers(stmt.password.expose())
+            .context(EncodeUserPasswordSnafu { name: &stmt.name })?;
+        let created = self
+            .user_manager()
+            .create(&stmt.name, &UserValue::new(verifiers))
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        if !created {
+            ensure!(stmt.if_not_exists, UserExistsSnafu { name: stmt.name });
+            return Ok(Output::new_with_affected_rows(0));
+        }
+
+        self.invalidate_user_cache(&stmt.name).await?;
+        Ok(Output::new_with_affected_rows(1))
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn alter_user(&self, stmt: AlterUser) -> Result<Output> {
+        let user_manager = self.user_manager();
+        let current = user_manager
+            .get(&stmt.name)
+            .await
+            .context(TableMetadataManagerSnafu)?
+            .context(UserNotFoundSnafu { name: &stmt.name })?;
+        let mut new_value = current.get_inner_ref().clone();
+        new_value.password_verifiers = managed_user_password_verifiers(stmt.password.expose())
+            .context(EncodeUserPasswordSnafu { name: &stmt.name })?;
+        user_manager
+            .update(&stmt.name, &current, &new_value)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        self.invalidate_user_cache(&stmt.name).await?;
+        Ok(Output::new_with_affected_rows(0))
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn drop_user(&self, stmt: DropUser) -> Result<Output> {
+        let deleted = self
+            .user_manager()
+            .delete(&stmt.name)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+        if !deleted {
+            ensure!(stmt.if_exists, UserNotFoundSnafu { name: stmt.name });
+            return Ok(Output::new_with_affected_rows(0));
+        }
+
+        self.invalidate_user_cache(&stmt.name).await?;
+        Ok(Output::new_with_affected_rows(1))
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn grant(&self, stmt: Grant, query_ctx: QueryContextRef) -> Result<Output> {
+        let target = resolve_grant_target(&stmt.target, &query_ctx);
+        ensure!(
+            self.catalog_manager
+                .schema_exists(target.catalog, &target.schema, Some(&query_ctx))
+                .await
+                .context(CatalogSnafu)?,
+            SchemaNotFoundSnafu {
+                schema_info: &target.schema,
+            }
+        );
+        if let Some(table) = target.table {
+            self.catalog_manager
+                .table(target.catalog, &target.schema, table, Some(&query_ctx))
+                .await
+                .context(CatalogSnafu)?
+                .context(TableNotFoundSnafu { table_name: table })?;
+        }
+
+        let privileges = to_user_privileges(&stmt.privileges);
+        self.update_user_grants(&stmt.user, |value| {
+            value.grant(target.catalog, &target.schema, target.table, &privileges)
+        })
+        .await
+    }
+
+    #[tracing::instrument(skip_all)]
+    pub async fn revoke(&self, stmt: Revoke, query_ctx: QueryContextRef) -> Result<Output> {
+        let target = resolve_grant_target(&stmt.target, &query_ctx);
+        let privileges = to_user_privileges(&stmt.privileges);
+        self.update_user_grants(&stmt.user, |value| {
+            value.revoke(target.catalog, &target.schema, target.table, &privileges)
+        })
+        .await
+    }
+
+    async fn update_user_grants(
+        &self,
+        username: &str,
+        update: impl FnOnce(&mut UserValue),
+    ) -> Result<Output> {
+        let user_manager = self.user_manager();
+        let current = user_manager
+            .get(username)
+            .await
+            .context(TableMetadataManagerSnafu)?
+            .context(UserNotFoundSnafu { name: username })?;
+        let mut new_value = current.get_inner_ref().clone();
+        update(&mut new_value);
+        if new_value == *current.get_inner_ref() {
+            return Ok(Output::new_with_affected_rows(0));
+        }
+        user_manager
+            .update(username, &current, &new_value)
+            .await
+            .context(TableMetadataManagerSnafu)?;
+
+        self.invalidate_user_cache(username).await?;
+        Ok(Output::new_with_affected_rows(0))
+    }
+}
+
+fn resolve_grant_target<'a>(
+    target: &'a GrantTarget,
+    query_ctx: &'a QueryContextRef,
+) -> GrantTargetRef<'a> {
+    GrantTargetRef {
+        catalog: query_ctx.current_catalog(),
+        schema: target
+            .database
+            .clone()
+            .unwrap_or_else(|| query_ctx.current_schema()),
+        table: target.table.as_deref(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_user_privileges() {
+        assert_eq!(
+            to_user_privileges(&[Privilege::Select]),
+            vec![UserPrivilege::Select]
+        );
+        assert_eq!(
+            to_user_privileges(&[Privilege::Insert, Privilege::All]),
+            vec![
+                UserPrivilege::Insert,
+                UserPrivilege::Select,
+                UserPrivilege::Delete,
+                UserPrivilege::Alter,
+                UserPrivilege::Drop,
+            ]
+        );
+    }
+}
@@ -12,6 +12,8 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use client::Output;
 use common_base::AffectedRows;
@@ -27,11 +29,21 @@ use table::requests::{
     FlushTableRequest, InsertRequest as TableInsertRequest,
 };
 use table::table_name::TableName;
+use table::table_reference::TableReference;
 
 use crate::delete::DeleterRef;
 use crate::insert::InserterRef;
 use crate::request::RequesterRef;
 
+/// Observes changes to the data or definition of tables other than inserts.
+pub trait TableChangeListener: Send + Sync {
+    /// Called after rows are deleted from `table`, or after `table` is created,
+    /// altered, truncated or dropped.
+    fn on_table_changed(&self, table: TableReference);
+}
+
+pub type TableChangeListenerRef = Arc<dyn TableChangeListener>;
+
 pub struct TableMutationOperator {
     inserter: InserterRef,
     deleter: DeleterRef,
@@ -62,6 +74,18 @@ impl TableMutationHandler for TableMutationOperator {
             .context(query_error::TableMutationSnafu)
     }
 
+    async fn replace(
+        &self,
+        request: TableInsertRequest,
+        ctx: QueryContextRef,
+    ) -> QueryResult<Output> {
+        self.inserter
+            .handle_table_replace(request, ctx)
+            .await
+            .map_err(BoxedError::new)
+            .context(query_error::TableMutationSnafu)
+    }
+
     async fn delete(
         &self,
         request: TableDeleteRequest,
@@ -228,6 +228,13 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid hash partition: {}", reason))]
+    InvalidHashPartition {
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display("Checkpoint `{}` is not covered", checkpoint))]
     CheckpointNotCovered {
         checkpoint: String,
@@ -253,6 +260,7 @@ impl ErrorExt for Error {
             | Error::NoExprOperand { .. }
             | Error::UndefinedColumn { .. }
             | Error::DuplicateExpr { .. }
+            | Error::InvalidHashPartition { .. }
             | Error::CheckpointNotCovered { .. }
             | Error::CheckpointOverlapped { .. } => StatusCode::InvalidArguments,
 
@@ -31,6 +31,7 @@ use sql::statements::value_to_sql_value;
 use sqlparser::ast::{BinaryOperator as ParserBinaryOperator, Expr as ParserExpr, Ident};
 
 use crate::error;
+use crate::hash::HashBucket;
 use crate::partition::PartitionBound;
 
 /// Struct for partition expression. This can be converted back to sqlparser's [Expr].
@@ -49,6 +50,8 @@ pub enum Operand {
     Column(String),
     Value(Value),
     Expr(PartitionExpr),
+    /// `hash_bucket(columns..., num_buckets)`, see [HashBucket].
+    HashBucket(HashBucket),
 }
 
 pub fn col(column_name: impl Into<String>) -> Operand {
@@ -105,6 +108,24 @@ impl Operand {
                 Ok(datafusion_expr::lit(scalar_value))
             }
             Self::Expr(e) => e.try_as_logical_expr(),
+            Self::HashBucket(h) => Ok(h.scalar_udf().call(
+                h.columns
+                    .iter()
+                    .map(|c| datafusion_expr::col(format!(r#""{}""#, c)))
+                    .collect(),
+            )),
+        }
+    }
+
+    /// Converts [Self] to sqlparser's [Expr](ParserExpr).
+    pub fn to_parser_expr(&self) -> ParserExpr {
+        // Safety: Partition rule won't contains unsupported value type.
+        // Otherwise it will be rejected by the parser.
+        match self {
+            Self::Column(c) => ParserExpr::Identifier(Ident::new(c.clone())),
+            Self::Value(v) => ParserExpr::Value(value_to_sql_value(v).unwrap().into()),
+            Self::Expr(e) => e.to_parser_expr(),
+            Self::HashBucket(h) => h.to_parser_expr(),
         }
     }
 
@@ -139,6 +160,7 @@ impl Display for Operand {
             Self::Column(v) => write!(f, "{v}"),
             Self::Value(v) => write!(f, "{v}"),
             Self::Expr(v) => write!(f, "{v}"),
+            Self::HashBucket(v) => write!(f, "{v}"),
         }
     }
 }
@@ -255,19 +277,8 @@ impl PartitionExpr {
     ///
     /// [Expr]: ParserExpr
     pub fn to_parser_expr(&self) -> ParserExpr {
-        // Safety: Partition rule won't contains unsupported value type.
-        // Otherwise it will be rejected by the parser.
-        let lhs = match &*self.lhs {
-            Operand::Column(c) => ParserExpr::Identifier(Ident::new(c.clone())),
-            Operand::Value(v) => ParserExpr::Value(value_to_sql_value(v).unwrap().into()),
-            Operand::Expr(e) => e.to_parser_expr(),
-        };
-
-        let rhs = match &*self.rhs {
-            Operand::Column(c) => ParserExpr::Identifier(Ident::new(c.clone())),
-            Operand::Value(v) => ParserExpr::Value(value_to_sql_value(v).unwrap().into()),
-            Operand::Expr(e) => e.to_parser_expr(),
-        };
+        let lhs = self.lhs.to_parser_expr();
+        let rhs = self.rhs.to_parser_expr();
 
         ParserExpr::BinaryOp {
             left: Box::new(lhs),
@@ -444,6 +455,9 @@ impl PartitionExpr {
             Operand::Expr(e) => {
                 e.collect_column_names(columns);
             }
+            Operand::HashBucket(h) => {
+                columns.extend(h.columns.iter().cloned());
+            }
             Operand::Value(_) => {}
         }
     }
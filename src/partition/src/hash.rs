@@ -0,0 +1,630 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Hash partition rule.
+//!
+//! A table created with `PARTITION BY HASH (c1, c2) PARTITIONS n` has `n` partitions.
+//! The partition expression of the i-th partition is `hash_bucket(c1, c2, n) = i`, so
+//! hash partitions are stored, displayed and repartitioned like any other partition
+//! expression.
+
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+use datafusion_common::{DataFusionError, Result as DfResult};
+use datafusion_expr::{
+    ColumnarValue, ScalarFunctionArgs, ScalarUDF, ScalarUDFImpl, Signature, Volatility,
+};
+use datatypes::arrow::array::{
+    ArrayRef, BooleanArray, BooleanBufferBuilder, RecordBatch, UInt32Array,
+};
+use datatypes::arrow::datatypes::DataType;
+use datatypes::prelude::Value;
+use datatypes::value::ValueRef;
+use datatypes::vectors::{Helper, VectorRef};
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ResultExt, ensure};
+use sql::statements::create::hash_bucket_expr;
+pub use sql::statements::create::{HASH_BUCKET_FUNCTION, MAX_HASH_PARTITIONS};
+use sqlparser::ast::{Expr as ParserExpr, Ident};
+use store_api::storage::RegionNumber;
+
+use crate::PartitionRule;
+use crate::error::{self, Result};
+use crate::expr::{Operand, PartitionExpr, RestrictedOp};
+use crate::partition::RegionMask;
+
+/// `hash_bucket(columns..., num_buckets)`, the bucket a row falls into.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct HashBucket {
+    pub columns: Vec<String>,
+    pub num_buckets: u32,
+}
+
+impl HashBucket {
+    pub fn new(columns: Vec<String>, num_buckets: u32) -> Self {
+        Self {
+            columns,
+            num_buckets,
+        }
+    }
+
+    /// Returns the partition expressions of all buckets, ordered by bucket.
+    pub fn partition_exprs(&self) -> Vec<PartitionExpr> {
+        (0..self.num_buckets)
+            .map(|bucket| {
+                PartitionExpr::new(
+                    Operand::HashBucket(self.clone()),
+                    RestrictedOp::Eq,
+                    Operand::Value(Value::UInt32(bucket)),
+                )
+            })
+            .collect()
+    }
+
+    /// Computes the bucket of the given values of the hash columns.
+    pub fn bucket_of<'a>(&self, values: impl IntoIterator<Item = ValueRef<'a>>) -> u32 {
+        let mut hasher = StableHasher::new();
+        for value in values {
+            hasher.write_value(value);
+        }
+        (hasher.finish() % self.num_buckets as u64) as u32
+    }
+
+    /// Computes the bucket of each row of the given columns.
+    pub fn buckets_of_vectors(&self, columns: &[VectorRef], num_rows: usize) -> Vec<u32> {
+        (0..num_rows)
+            .map(|row| self.bucket_of(columns.iter().map(|column| column.get_ref(row))))
+            .collect()
+    }
+
+    /// Converts [Self] to sqlparser's function call expr.
+    pub fn to_parser_expr(&self) -> ParserExpr {
+        let columns = self
+            .columns
+            .iter()
+            .map(|c| Ident::new(c.clone()))
+            .collect::<Vec<_>>();
+        hash_bucket_expr(&columns, self.num_buckets)
+    }
+
+    /// Returns the [ScalarUDF] that evaluates this hash bucket.
+    pub fn scalar_udf(&self) -> ScalarUDF {
+        ScalarUDF::new_from_impl(HashBucketFunction {
+            signature: Signature::variadic_any(Volatility::Immutable),
+            num_buckets: self.num_buckets,
+        })
+    }
+}
+
+impl Display for HashBucket {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{HASH_BUCKET_FUNCTION}({}, {})",
+            self.columns.join(", "),
+            self.num_buckets
+        )
+    }
+}
+
+/// Returns the hash bucket and the bucket number of a `hash_bucket(..) = i` expression.
+pub fn as_hash_bucket_expr(expr: &PartitionExpr) -> Option<(&HashBucket, u32)> {
+    match (expr.lhs(), expr.op(), expr.rhs()) {
+        (Operand::HashBucket(hash), RestrictedOp::Eq, Operand::Value(value)) => {
+            let bucket = value.as_u64()?;
+            Some((hash, u32::try_from(bucket).ok()?))
+        }
+        _ => None,
+    }
+}
+
+/// Returns the hash bucket if all expressions are buckets of the same hash.
+pub fn as_hash_partition(exprs: &[PartitionExpr]) -> Option<&HashBucket> {
+    let (first, _) = as_hash_bucket_expr(exprs.first()?)?;
+    exprs
+        .iter()
+        .all(|expr| as_hash_bucket_expr(expr).is_some_and(|(hash, _)| hash == first))
+        .then_some(first)
+}
+
+/// Returns true if any of the expressions references a hash bucket.
+pub fn contains_hash_bucket(exprs: &[PartitionExpr]) -> bool {
+    fn operand_contains(operand: &Operand) -> bool {
+        match operand {
+            Operand::HashBucket(_) => true,
+            Operand::Expr(expr) => operand_contains(expr.lhs()) || operand_contains(expr.rhs()),
+            Operand::Column(_) | Operand::Value(_) => false,
+        }
+    }
+
+    exprs
+        .iter()
+        .any(|expr| operand_contains(expr.lhs()) || operand_contains(expr.rhs()))
+}
+
+/// Partition rule that routes rows by the hash of the partition columns.
+#[derive(Debug)]
+pub struct HashPartitionRule {
+    partition_columns: Vec<String>,
+    hash: HashBucket,
+    exprs: Vec<PartitionExpr>,
+    /// Region of each bucket.
+    bucket_to_region: Vec<RegionNumber>,
+}
+
+impl HashPartitionRule {
+    /// Creates a new [HashPartitionRule].
+    ///
+    /// Each expression must be a `hash_bucket(..) = i` expression over the partition
+    /// columns, and every bucket must be covered exactly once. `regions` has the same
+    /// length as `exprs`, or is empty when the rule is only validated.
+    pub fn try_new(
+        partition_columns: Vec<String>,
+        regions: Vec<RegionNumber>,
+        exprs: Vec<PartitionExpr>,
+    ) -> Result<Self> {
+        let hash = as_hash_partition(&exprs)
+            .context(error::InvalidHashPartitionSnafu {
+                reason: "all partition expressions must be buckets of the same hash",
+            })?
+            .clone();
+        ensure!(
+            hash.columns == partition_columns,
+            error::InvalidHashPartitionSnafu {
+                reason: format!(
+                    "hash columns {:?} must be the partition columns {:?}",
+                    hash.columns, partition_columns
+                ),
+            }
+        );
+        ensure!(
+            (1..=MAX_HASH_PARTITIONS).contains(&hash.num_buckets),
+            error::InvalidHashPartitionSnafu {
+                reason: format!(
+                    "number of hash partitions must be between 1 and {MAX_HASH_PARTITIONS}, got {}",
+                    hash.num_buckets
+                ),
+            }
+        );
+        ensure!(
+            regions.is_empty() || regions.len() == exprs.len(),
+            error::InvalidHashPartitionSnafu {
+                reason: format!("expect {} regions, got {}", exprs.len(), regions.len()),
+            }
+        );
+
+        let mut bucket_to_region = vec![None; hash.num_buckets as usize];
+        for (index, expr) in exprs.iter().enumerate() {
+            // Safety: checked by `as_hash_partition` above.
+            let (_, bucket) = as_hash_bucket_expr(expr).unwrap();
+            let slot = bucket_to_region.get_mut(bucket as usize).context(
+                error::InvalidHashPartitionSnafu {
+                    reason: format!("bucket {bucket} is out of range"),
+                },
+            )?;
+            ensure!(
+                slot.is_none(),
+                error::DuplicateExprSnafu { expr: expr.clone() }
+            );
+            *slot = Some(regions.get(index).copied().unwrap_or_default());
+        }
+        let bucket_to_region = bucket_to_region
+            .into_iter()
+            .enumerate()
+            .map(|(bucket, region)| {
+                region.context(error::InvalidHashPartitionSnafu {
+                    reason: format!("bucket {bucket} is not covered"),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            partition_columns,
+            hash,
+            exprs,
+            bucket_to_region,
+        })
+    }
+
+    pub fn exprs(&self) -> &[PartitionExpr] {
+        &self.exprs
+    }
+
+    pub fn hash_bucket(&self) -> &HashBucket {
+        &self.hash
+    }
+
+    /// Returns the region of the given bucket.
+    pub fn region_of_bucket(&self, bucket: u32) -> RegionNumber {
+        self.bucket_to_region[bucket as usize]
+    }
+}
+
+impl PartitionRule for HashPartitionRule {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn partition_columns(&self) -> &[String] {
+        &self.partition_columns
+    }
+
+    fn find_region(&self, values: &[Value]) -> Result<RegionNumber> {
+        ensure!(
+            values.len() == self.partition_columns.len(),
+            error::RegionKeysSizeSnafu {
+                expect: self.partition_columns.len(),
+                actual: values.len(),
+            }
+        );
+
+        let bucket = self
+            .hash
+            .bucket_of(values.iter().map(|value| value.as_value_ref()));
+        Ok(self.region_of_bucket(bucket))
+    }
+
+    fn split_record_batch(
+        &self,
+        record_batch: &RecordBatch,
+    ) -> Result<HashMap<RegionNumber, RegionMask>> {
+        let num_rows = record_batch.num_rows();
+        let columns = self
+            .partition_columns
+            .iter()
+            .map(|name| {
+                let array = record_batch
+                    .column_by_name(name)
+                    .context(error::UndefinedColumnSnafu { column: name })?;
+                Helper::try_into_vector(array).context(error::ConvertToVectorSnafu)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut builders: HashMap<RegionNumber, (BooleanBufferBuilder, usize)> = HashMap::new();
+        for (row, bucket) in self
+            .hash
+            .buckets_of_vectors(&columns, num_rows)
+            .into_iter()
+            .enumerate()
+        {
+            let (builder, selected) = builders
+                .entry(self.region_of_bucket(bucket))
+                .or_insert_with(|| {
+                    let mut builder = BooleanBufferBuilder::new(num_rows);
+                    builder.append_n(num_rows, false);
+                    (builder, 0)
+                });
+            builder.set_bit(row, true);
+            *selected += 1;
+        }
+
+        Ok(builders
+            .into_iter()
+            .map(|(region, (mut builder, selected))| {
+                let array = BooleanArray::new(builder.finish(), None);
+                (region, RegionMask::new(array, selected))
+            })
+            .collect())
+    }
+}
+
+/// Returns the regions of a hash partitioned table that may contain rows whose hash
+/// columns take one of the candidate values.
+///
+/// `candidates` holds the possible values of each hash column, in the column order of
+/// `hash`. Returns `None` if there are too many combinations to enumerate.
+pub fn prune_hash_buckets(hash: &HashBucket, candidates: &[Vec<Value>]) -> Option<HashSet<u32>> {
+    const MAX_COMBINATIONS: usize = 1024;
+
+    let combinations = candidates
+        .iter()
+        .try_fold(1usize, |acc, values| acc.checked_mul(values.len()))?;
+    if combinations > MAX_COMBINATIONS || candidates.len() != hash.columns.len() {
+        return None;
+    }
+
+    let mut buckets = HashSet::new();
+    let mut indices = vec![0; candidates.len()];
+    for _ in 0..combinations {
+        buckets.insert(
+            hash.bucket_of(
+                indices
+                    .iter()
+                    .zip(candidates)
+                    .map(|(i, values)| values[*i].as_value_ref()),
+            ),
+        );
+        // Advances to the next combination.
+        for (index, values) in indices.iter_mut().zip(candidates).rev() {
+            *index += 1;
+            if *index < values.len() {
+                break;
+            }
+            *index = 0;
+        }
+    }
+    Some(buckets)
+}
+
+/// Checks whether two partition exprs may share rows when any of them is a hash bucket.
+///
+/// Returns `None` if neither expr is a hash bucket. When two hashes are over the same
+/// columns and one number of buckets is a multiple of the other, a row in bucket `b` of
+/// the larger one is in bucket `b % n` of the smaller one, so buckets can be split or
+/// merged without touching the others. Otherwise every pair of buckets may share rows.
+pub fn hash_exprs_overlap(lhs: &PartitionExpr, rhs: &PartitionExpr) -> Option<bool> {
+    match (as_hash_bucket_expr(lhs), as_hash_bucket_expr(rhs)) {
+        (Some((lhs_hash, lhs_bucket)), Some((rhs_hash, rhs_bucket)))
+            if lhs_hash.columns == rhs_hash.columns =>
+        {
+            let (n_lhs, n_rhs) = (lhs_hash.num_buckets, rhs_hash.num_buckets);
+            if n_rhs % n_lhs == 0 {
+                Some(rhs_bucket % n_lhs == lhs_bucket)
+            } else if n_lhs % n_rhs == 0 {
+                Some(lhs_bucket % n_rhs == rhs_bucket)
+            } else {
+                Some(true)
+            }
+        }
+        _ if contains_hash_bucket(&[lhs.clone(), rhs.clone()]) => Some(true),
+        _ => None,
+    }
+}
+
+/// Scalar function that evaluates `hash_bucket(columns..., num_buckets)`.
+///
+/// The number of buckets is carried by the function itself, so the arguments are
+/// only the hash columns.
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct HashBucketFunction {
+    signature: Signature,
+    num_buckets: u32,
+}
+
+impl ScalarUDFImpl for HashBucketFunction {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        HASH_BUCKET_FUNCTION
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> DfResult<DataType> {
+        Ok(DataType::UInt32)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> DfResult<ColumnarValue> {
+        let num_rows = args.number_rows;
+        let columns = args
+            .args
+            .iter()
+            .map(|arg| {
+                let array = arg.to_array(num_rows)?;
+                Helper::try_into_vector(array).map_err(|e| DataFusionError::External(Box::new(e)))
+            })
+            .collect::<DfResult<Vec<_>>>()?;
+
+        let hash = HashBucket::new(vec![], self.num_buckets);
+        let buckets = hash.buckets_of_vectors(&columns, num_rows);
+        Ok(ColumnarValue::Array(
+            Arc::new(UInt32Array::from(buckets)) as ArrayRef
+        ))
+    }
+}
+
+/// 64-bit FNV-1a with a murmur3 finalizer.
+///
+/// The hash decides which region a row is stored in, so it must never change
+/// across versions and nodes. Don't replace it with a std or third-party hasher
+/// that doesn't guarantee a stable output.
+struct StableHasher(u64);
+
+impl StableHasher {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    fn new() -> Self {
+        Self(Self::OFFSET_BASIS)
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(Self::PRIME);
+        }
+    }
+
+    /// Writes a value. Integers of different widths and signedness hash the same
+    /// if they are equal.
+    fn write_value(&mut self, value: ValueRef) {
+        match value {
+            ValueRef::Null => self.write(&[0]),
+            ValueRef::Boolean(v) => self.write(&[1, v as u8]),
+            ValueRef::UInt8(v) => self.write_integer(v as i128),
+            ValueRef::UInt16(v) => self.write_integer(v as i128),
+            ValueRef::UInt32(v) => self.write_integer(v as i128),
+            ValueRef::UInt64(v) => self.write_integer(v as i128),
+            ValueRef::Int8(v) => self.write_integer(v as i128),
+            ValueRef::Int16(v) => self.write_integer(v as i128),
+            ValueRef::Int32(v) => self.write_integer(v as i128),
+            ValueRef::Int64(v) => self.write_integer(v as i128),
+            ValueRef::Float32(v) => self.write_float(v.0 as f64),
+            ValueRef::Float64(v) => self.write_float(v.0),
+            ValueRef::String(v) => self.write_bytes(4, v.as_bytes()),
+            ValueRef::Binary(v) => self.write_bytes(5, v),
+            ValueRef::Date(v) => {
+                self.write(&[6]);
+                self.write(&v.val().to_le_bytes());
+            }
+            ValueRef::Timestamp(v) => {
+                let (secs, nanos) = v.split();
+                self.write(&[7]);
+                self.write(&secs.to_le_bytes());
+                self.write(&nanos.to_le_bytes());
+            }
+            ValueRef::Decimal128(v) => {
+                self.write(&[8]);
+                self.write(&v.val().to_le_bytes());
+                self.write(&v.scale().to_le_bytes());
+            }
+            other => {
+                let value = Value::from(other).to_string();
+                self.write_bytes(255, value.as_bytes());
+            }
+        }
+    }
+
+    fn write_integer(&mut self, v: i128) {
+        self.write(&[2]);
+        self.write(&v.to_le_bytes());
+    }
+
+    fn write_float(&mut self, v: f64) {
+        // -0.0 == 0.0
+        let v = if v == 0.0 { 0.0 } else { v };
+        self.write(&[3]);
+        self.write(&v.to_bits().to_le_bytes());
+    }
+
+    fn write_bytes(&mut self, tag: u8, bytes: &[u8]) {
+        self.write(&[tag]);
+        self.write(&(bytes.len() as u64).to_le_bytes());
+        self.write(bytes);
+    }
+
+    fn finish(&self) -> u64 {
+        let mut h = self.0;
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xff51_afd7_ed55_8ccd);
+        h ^= h >> 33;
+        h = h.wrapping_mul(0xc4ce_b9fe_1a85_ec53);
+        h ^= h >> 33;
+        h
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion_physical_expr::PhysicalExpr;
+    use datatypes::arrow::array::{Array, Int64Array, StringArray};
+    use datatypes::arrow::datatypes::{Field, Schema};
+
+    use super::*;
+
+    fn hash() -> HashBucket {
+        HashBucket::new(vec!["host".to_string(), "id".to_string()], 4)
+    }
+
+    #[test]
+    fn test_stable_bucket() {
+        // The bucket of a row must never change, otherwise existing data is routed
+        // to the wrong region.
+        let hash = HashBucket::new(vec!["host".to_string(), "id".to_string()], 16);
+        let buckets = (0..8)
+            .map(|i| hash.bucket_of([ValueRef::String(&format!("host{i}")), ValueRef::Int64(i)]))
+            .collect::<Vec<_>>();
+        assert_eq!(buckets, vec![8, 12, 13, 14, 6, 7, 8, 9]);
+
+        // Integers of different types hash the same.
+        assert_eq!(
+            hash.bucket_of([ValueRef::String("a"), ValueRef::Int64(1)]),
+            hash.bucket_of([ValueRef::String("a"), ValueRef::UInt8(1)]),
+        );
+    }
+
+    #[test]
+    fn test_hash_partition_rule() {
+        let hash = hash();
+        let exprs = hash.partition_exprs();
+        assert_eq!(exprs[1].to_string(), "hash_bucket(host, id, 4) = 1");
+        let rule =
+            HashPartitionRule::try_new(hash.columns.clone(), vec![10, 11, 12, 13], exprs.clone())
+                .unwrap();
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("host", DataType::Utf8, true),
+            Field::new("id", DataType::Int64, true),
+        ]));
+        let hosts = (0..100).map(|i| format!("host{i}")).collect::<Vec<_>>();
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(hosts.clone())),
+                Arc::new(Int64Array::from((0..100).collect::<Vec<i64>>())),
+            ],
+        )
+        .unwrap();
+        let masks = rule.split_record_batch(&batch).unwrap();
+        assert_eq!(
+            masks.values().map(|m| m.selected_rows()).sum::<usize>(),
+            100
+        );
+        // Row routing agrees with `find_region` and the scalar function.
+        for (row, host) in hosts.iter().enumerate() {
+            let region = rule
+                .find_region(&[Value::from(host.as_str()), Value::Int64(row as i64)])
+                .unwrap();
+            assert!(masks[&region].array().value(row));
+        }
+
+        let physical_expr = exprs[2].try_as_physical_expr(batch.schema_ref()).unwrap();
+        let selected = physical_expr
+            .evaluate(&batch)
+            .unwrap()
+            .into_array(100)
+            .unwrap();
+        assert_eq!(
+            selected.as_any().downcast_ref::<BooleanArray>().unwrap(),
+            masks[&12].array()
+        );
+    }
+
+    #[test]
+    fn test_invalid_hash_partition_rule() {
+        let hash = hash();
+        let columns = hash.columns.clone();
+        let mut exprs = hash.partition_exprs();
+        exprs.pop();
+        assert!(HashPartitionRule::try_new(columns.clone(), vec![], exprs.clone()).is_err());
+
+        exprs.push(exprs[0].clone());
+        assert!(HashPartitionRule::try_new(columns.clone(), vec![], exprs).is_err());
+
+        let exprs = hash.partition_exprs();
+        assert!(HashPartitionRule::try_new(vec!["host".to_string()], vec![], exprs).is_err());
+    }
+
+    #[test]
+    fn test_prune_hash_buckets() {
+        let hash = hash();
+        let buckets = prune_hash_buckets(
+            &hash,
+            &[
+                vec![Value::from("a")],
+                vec![Value::Int64(1), Value::Int64(2)],
+            ],
+        )
+        .unwrap();
+        assert!(!buckets.is_empty() && buckets.len() <= 2);
+        assert!(buckets.contains(&hash.bucket_of([ValueRef::String("a"), ValueRef::Int64(2)])));
+    }
+}
@@ -28,6 +28,7 @@ use table::metadata::{TableId, TableInfo};
 use crate::cache::{CachedPartitionInfo, PartitionInfoCacheRef, PhysicalPartitionInfo};
 use crate::error::{FindLeaderSnafu, Result};
 use crate::expr::PartitionExpr;
+use crate::hash::{HashPartitionRule, as_hash_partition};
 use crate::multi_dim::MultiDimPartitionRule;
 use crate::splitter::RowSplitter;
 use crate::{PartitionRuleRef, error};
@@ -217,12 +218,25 @@ impl PartitionRuleManager {
             .filter_map(|x| x.partition_expr.as_ref())
             .cloned()
             .collect::<Vec<_>>();
-        let partition_rule = Arc::new(MultiDimPartitionRule::try_new(
-            partition_columns,
-            regions,
-            exprs,
-            false,
-        )?) as _;
+        let partition_rule = if as_hash_partition(&exprs).is_some() {
+            let (regions, exprs) = partition_info
+                .partitions
+                .iter()
+                .filter_map(|x| Some((x.id.region_number(), x.partition_expr.clone()?)))
+                .unzip();
+            Arc::new(HashPartitionRule::try_new(
+                partition_columns,
+                regions,
+                exprs,
+            )?) as _
+        } else {
+            Arc::new(MultiDimPartitionRule::try_new(
+                partition_columns,
+                regions,
+                exprs,
+                false,
+            )?) as _
+        };
         Ok((partition_rule, partition_versions))
     }
 
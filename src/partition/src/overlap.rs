@@ -25,6 +25,7 @@ use datatypes::value::OrderedF64;
 use crate::collider::{AtomicExpr, Collider, GluonOp, NucleonExpr};
 use crate::error::Result;
 use crate::expr::PartitionExpr;
+use crate::hash::hash_exprs_overlap;
 
 /// Check if two atomic expressions can be both satisfied, i.e., whether they
 /// overlap on all common columns.
@@ -83,6 +84,10 @@ pub fn atomic_exprs_overlap(lhs: &AtomicExpr, rhs: &AtomicExpr) -> bool {
 ///
 /// Returns true if two [`PartitionExpr`]s are overlapping (any pair of atomics overlaps).
 fn expr_pair_overlap(lhs: &PartitionExpr, rhs: &PartitionExpr) -> Result<bool> {
+    if let Some(overlap) = hash_exprs_overlap(lhs, rhs) {
+        return Ok(overlap);
+    }
+
     let binding = [lhs.clone(), rhs.clone()];
     let collider = Collider::new(&binding)?;
     // Split atomic exprs by source index
@@ -220,6 +225,7 @@ mod tests {
 
     use super::*;
     use crate::expr::{Operand, PartitionExpr, RestrictedOp, col};
+    use crate::hash::HashBucket;
 
     #[test]
     fn test_pair_overlap_simple() {
@@ -263,6 +269,34 @@ mod tests {
         assert_eq!(assoc[1], vec![0, 1]);
     }
 
+    #[test]
+    fn test_associate_hash_buckets() {
+        let from = HashBucket::new(vec!["a".to_string()], 2).partition_exprs();
+        let split = HashBucket::new(vec!["a".to_string()], 4).partition_exprs();
+        assert_eq!(
+            associate_from_to(&from, &split).unwrap(),
+            vec![vec![0, 2], vec![1, 3]]
+        );
+        assert_eq!(
+            associate_from_to(&split, &from).unwrap(),
+            vec![vec![0], vec![1], vec![0], vec![1]]
+        );
+        let other = HashBucket::new(vec!["a".to_string()], 3).partition_exprs();
+        assert_eq!(
+            associate_from_to(&from, &other).unwrap(),
+            vec![vec![0, 1, 2], vec![0, 1, 2]]
+        );
+        // Range to hash repartition moves rows between all partitions.
+        let range = vec![
+            col("a").lt(Value::Int64(10)),
+            col("a").gt_eq(Value::Int64(10)),
+        ];
+        assert_eq!(
+            associate_from_to(&range, &from).unwrap(),
+            vec![vec![0, 1], vec![0, 1]]
+        );
+    }
+
     #[test]
     fn test_expr_with_or() {
         // a: (user_id = 10 OR user_id = 20)
@@ -355,6 +355,86 @@ pub enum Error {
         #[snafu(implicit)]
         location: Location,
     },
+    #[snafu(display("Grok pattern definition not found: {name}"))]
+    GrokPatternNotFound {
+        name: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Grok pattern definition {name} references itself"))]
+    GrokRecursivePattern {
+        name: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display(
+        "Invalid grok capture type: {ty}, expected one of string, int, long, float, double or boolean"
+    ))]
+    GrokInvalidCaptureType {
+        ty: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("No matching grok pattern found"))]
+    GrokNoMatchingPattern {
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display(
+        "Invalid user agent property: {property}, expected one of browser, browser_version, os, os_version or device"
+    ))]
+    UserAgentInvalidProperty {
+        property: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Database path is required for geoip processor"))]
+    GeoIpDatabaseRequired {
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Failed to read geoip database: {path}"))]
+    GeoIpReadDatabase {
+        path: String,
+        #[snafu(source)]
+        error: std::io::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Invalid geoip database {path}: {reason}"))]
+    GeoIpInvalidDatabase {
+        path: String,
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Invalid geoip database path {path}: {reason}"))]
+    GeoIpInvalidDatabasePath {
+        path: String,
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Invalid IP address: {value}"))]
+    GeoIpInvalidAddress {
+        value: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Invalid geoip property: {property}"))]
+    GeoIpInvalidProperty {
+        property: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+    #[snafu(display("Failed to convert grok capture {field}: '{value}' to {ty}"))]
+    GrokConvertCapture {
+        field: String,
+        value: String,
+        ty: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
     #[snafu(display("Invalid method: {s}"))]
     UrlEncodingInvalidMethod {
         s: String,
@@ -946,6 +1026,18 @@ impl ErrorExt for Error {
             | RegexNamedGroupNotFound { .. }
             | RegexNoValidField { .. }
             | RegexNoValidPattern { .. }
+            | GrokPatternNotFound { .. }
+            | GrokRecursivePattern { .. }
+            | GrokInvalidCaptureType { .. }
+            | GrokNoMatchingPattern { .. }
+            | GrokConvertCapture { .. }
+            | UserAgentInvalidProperty { .. }
+            | GeoIpDatabaseRequired { .. }
+            | GeoIpReadDatabase { .. }
+            | GeoIpInvalidDatabase { .. }
+            | GeoIpInvalidDatabasePath { .. }
+            | GeoIpInvalidAddress { .. }
+            | GeoIpInvalidProperty { .. }
             | UrlEncodingInvalidMethod { .. }
             | DigestPatternInvalid { .. }
             | TransformOnFailureInvalidValue { .. }
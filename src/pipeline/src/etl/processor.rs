@@ -20,15 +20,19 @@ pub mod digest;
 pub mod dissect;
 pub mod epoch;
 pub mod filter;
+pub mod geoip;
+pub mod grok;
 pub mod gsub;
 pub mod join;
 pub mod json_parse;
 pub mod json_path;
+pub mod kv;
 pub mod letter;
 pub mod regex;
 pub mod select;
 pub mod simple_extract;
 pub mod urlencoding;
+pub mod user_agent;
 pub mod vrl_processor;
 
 use std::str::FromStr;
@@ -41,13 +45,17 @@ use digest::DigestProcessor;
 use dissect::DissectProcessor;
 use enum_dispatch::enum_dispatch;
 use epoch::EpochProcessor;
+use geoip::GeoIpProcessor;
+use grok::GrokProcessor;
 use gsub::GsubProcessor;
 use join::JoinProcessor;
 use json_path::JsonPathProcessor;
+use kv::KvProcessor;
 use letter::LetterProcessor;
 use regex::RegexProcessor;
 use snafu::{OptionExt, ResultExt};
 use urlencoding::UrlEncodingProcessor;
+use user_agent::UserAgentProcessor;
 use vrl::value::Value as VrlValue;
 
 use crate::error::{
@@ -135,6 +143,7 @@ pub enum ProcessorKind {
     Csv(CsvProcessor),
     Dissect(DissectProcessor),
     Gsub(GsubProcessor),
+    Grok(GrokProcessor),
     Join(JoinProcessor),
     Letter(LetterProcessor),
     Regex(RegexProcessor),
@@ -149,6 +158,9 @@ pub enum ProcessorKind {
     Select(SelectProcessor),
     Vrl(VrlProcessor),
     Filter(FilterProcessor),
+    Kv(KvProcessor),
+    UserAgent(UserAgentProcessor),
+    GeoIp(GeoIpProcessor),
 }
 
 #[derive(Debug, Default)]
@@ -208,6 +220,7 @@ fn parse_processor(doc: &yaml_rust::Yaml) -> Result<ProcessorKind> {
         epoch::PROCESSOR_EPOCH => ProcessorKind::Epoch(EpochProcessor::try_from(value)?),
         date::PROCESSOR_DATE => ProcessorKind::Date(DateProcessor::try_from(value)?),
         gsub::PROCESSOR_GSUB => ProcessorKind::Gsub(GsubProcessor::try_from(value)?),
+        grok::PROCESSOR_GROK => ProcessorKind::Grok(GrokProcessor::try_from(value)?),
         join::PROCESSOR_JOIN => ProcessorKind::Join(JoinProcessor::try_from(value)?),
         letter::PROCESSOR_LETTER => ProcessorKind::Letter(LetterProcessor::try_from(value)?),
         regex::PROCESSOR_REGEX => ProcessorKind::Regex(RegexProcessor::try_from(value)?),
@@ -230,6 +243,11 @@ fn parse_processor(doc: &yaml_rust::Yaml) -> Result<ProcessorKind> {
         vrl_processor::PROCESSOR_VRL => ProcessorKind::Vrl(VrlProcessor::try_from(value)?),
         select::PROCESSOR_SELECT => ProcessorKind::Select(SelectProcessor::try_from(value)?),
         filter::PROCESSOR_FILTER => ProcessorKind::Filter(FilterProcessor::try_from(value)?),
+        kv::PROCESSOR_KV => ProcessorKind::Kv(KvProcessor::try_from(value)?),
+        user_agent::PROCESSOR_USER_AGENT => {
+            ProcessorKind::UserAgent(UserAgentProcessor::try_from(value)?)
+        }
+        geoip::PROCESSOR_GEOIP => ProcessorKind::GeoIp(GeoIpProcessor::try_from(value)?),
         _ => return UnsupportedProcessorSnafu { processor: str_key }.fail(),
     };
 
@@ -0,0 +1,728 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod mmdb;
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::path::{Component, Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex, OnceLock, RwLock, Weak};
+use std::time::{Duration, Instant, SystemTime};
+
+use common_telemetry::{info, warn};
+use mmdb::{MmdbReader, MmdbValue};
+use ordered_float::NotNan;
+use snafu::{OptionExt, ResultExt, ensure};
+use vrl::prelude::Bytes;
+use vrl::value::{KeyString, Value as VrlValue};
+
+use crate::error::{
+    Error, GeoIpDatabaseRequiredSnafu, GeoIpInvalidAddressSnafu, GeoIpInvalidDatabasePathSnafu,
+    GeoIpInvalidDatabaseSnafu, GeoIpInvalidPropertySnafu, GeoIpReadDatabaseSnafu,
+    KeyMustBeStringSnafu, ProcessorExpectStringSnafu, ProcessorMissingFieldSnafu,
+    RegexNoValidFieldSnafu, Result, ValueMustBeMapSnafu,
+};
+use crate::etl::field::Fields;
+use crate::etl::processor::{
+    FIELD_NAME, FIELDS_NAME, IGNORE_MISSING_NAME, Processor, yaml_bool, yaml_new_field,
+    yaml_new_fields, yaml_parse_string, yaml_string, yaml_strings,
+};
+
+pub(crate) const PROCESSOR_GEOIP: &str = "geoip";
+
+const DATABASE_NAME: &str = "database";
+const PROPERTIES_NAME: &str = "properties";
+const GEOHASH_PRECISION_NAME: &str = "geohash_precision";
+
+const DEFAULT_GEOHASH_PRECISION: usize = 12;
+const MAX_GEOHASH_PRECISION: usize = 12;
+
+/// How often the database file is checked for changes.
+const RELOAD_CHECK_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The directory geoip databases are read from.
+static DATABASE_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// The opened databases by path, shared by all the processors reading them.
+static DATABASES: LazyLock<Mutex<HashMap<PathBuf, Weak<GeoIpDatabase>>>> =
+    LazyLock::new(Default::default);
+
+/// Sets the directory geoip databases are read from. The geoip processor is
+/// disabled if it's not set.
+///
+/// Only the first call takes effect.
+pub fn set_geoip_database_dir(dir: Option<&str>) {
+    DATABASE_DIR.get_or_init(|| dir.filter(|dir| !dir.is_empty()).map(PathBuf::from));
+}
+
+/// Resolves the `database` of a processor, which must be a relative path inside
+/// the configured database directory.
+fn resolve_database_path(database: &str) -> Result<PathBuf> {
+    let dir = DATABASE_DIR
+        .get()
+        .and_then(Option::as_ref)
+        .with_context(|| GeoIpInvalidDatabasePathSnafu {
+            path: database,
+            reason: "the geoip database directory is not configured",
+        })?;
+    let relative = Path::new(database);
+    ensure!(
+        relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_))),
+        GeoIpInvalidDatabasePathSnafu {
+            path: database,
+            reason: "must be a relative path without `..`",
+        }
+    );
+
+    // Symlinks may still point outside of the directory.
+    let dir = dir.canonicalize().context(GeoIpReadDatabaseSnafu {
+        path: dir.display().to_string(),
+    })?;
+    let path = dir
+        .join(relative)
+        .canonicalize()
+        .context(GeoIpReadDatabaseSnafu { path: database })?;
+    ensure!(
+        path.starts_with(&dir),
+        GeoIpInvalidDatabasePathSnafu {
+            path: database,
+            reason: "must be inside the geoip database directory",
+        }
+    );
+    Ok(path)
+}
+
+/// The fields extracted from a GeoIP2/GeoLite2 City, Country or ASN database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Property {
+    ContinentCode,
+    ContinentName,
+    CountryIsoCode,
+    CountryName,
+    RegionIsoCode,
+    RegionName,
+    CityName,
+    PostalCode,
+    Timezone,
+    Latitude,
+    Longitude,
+    /// A WKT point, e.g. `POINT(151.2 -33.8)`, as produced by `wkt_point_from_latlng`.
+    Location,
+    Geohash,
+    Asn,
+    OrganizationName,
+}
+
+impl Property {
+    const ALL: [Property; 15] = [
+        Property::ContinentCode,
+        Property::ContinentName,
+        Property::CountryIsoCode,
+        Property::CountryName,
+        Property::RegionIsoCode,
+        Property::RegionName,
+        Property::CityName,
+        Property::PostalCode,
+        Property::Timezone,
+        Property::Latitude,
+        Property::Longitude,
+        Property::Location,
+        Property::Geohash,
+        Property::Asn,
+        Property::OrganizationName,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Property::ContinentCode => "continent_code",
+            Property::ContinentName => "continent_name",
+            Property::CountryIsoCode => "country_iso_code",
+            Property::CountryName => "country_name",
+            Property::RegionIsoCode => "region_iso_code",
+            Property::RegionName => "region_name",
+            Property::CityName => "city_name",
+            Property::PostalCode => "postal_code",
+            Property::Timezone => "timezone",
+            Property::Latitude => "latitude",
+            Property::Longitude => "longitude",
+            Property::Location => "location",
+            Property::Geohash => "geohash",
+            Property::Asn => "asn",
+            Property::OrganizationName => "organization_name",
+        }
+    }
+
+    /// The path of a string property in the record.
+    fn path(&self) -> &'static [&'static str] {
+        match self {
+            Property::ContinentCode => &["continent", "code"],
+            Property::ContinentName => &["continent", "names", "en"],
+            Property::CountryIsoCode => &["country", "iso_code"],
+            Property::CountryName => &["country", "names", "en"],
+            Property::RegionIsoCode => &["subdivisions", "0", "iso_code"],
+            Property::RegionName => &["subdivisions", "0", "names", "en"],
+            Property::CityName => &["city", "names", "en"],
+            Property::PostalCode => &["postal", "code"],
+            Property::Timezone => &["location", "time_zone"],
+            Property::OrganizationName => &["autonomous_system_organization"],
+            Property::Latitude
+            | Property::Longitude
+            | Property::Location
+            | Property::Geohash
+            | Property::Asn => &[],
+        }
+    }
+}
+
+impl std::str::FromStr for Property {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Property::ALL
+            .into_iter()
+            .find(|p| p.name() == s)
+            .context(GeoIpInvalidPropertySnafu { property: s })
+    }
+}
+
+/// Encodes a coordinate into a geohash of `precision` characters.
+fn encode_geohash(lat: f64, lon: f64, precision: usize) -> String {
+    const BASE32: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+    let (mut lat_range, mut lon_range) = ((-90.0, 90.0), (-180.0, 180.0));
+    let mut hash = String::with_capacity(precision);
+    let mut even = true;
+    let mut bits = 0;
+    let mut index = 0;
+    while hash.len() < precision {
+        let (range, value) = if even {
+            (&mut lon_range, lon)
+        } else {
+            (&mut lat_range, lat)
+        };
+        let mid = (range.0 + range.1) / 2.0;
+        index <<= 1;
+        if value >= mid {
+            index |= 1;
+            range.0 = mid;
+        } else {
+            range.1 = mid;
+        }
+        even = !even;
+        bits += 1;
+        if bits == 5 {
+            hash.push(BASE32[index] as char);
+            bits = 0;
+            index = 0;
+        }
+    }
+    hash
+}
+
+/// A database file that is reloaded when its modification time changes.
+#[derive(Debug)]
+struct GeoIpDatabase {
+    path: PathBuf,
+    state: RwLock<LoadedDatabase>,
+    last_check: Mutex<Instant>,
+    /// Whether a reload task is running.
+    reloading: AtomicBool,
+}
+
+#[derive(Debug)]
+struct LoadedDatabase {
+    reader: Arc<MmdbReader>,
+    modified: Option<SystemTime>,
+}
+
+impl GeoIpDatabase {
+    /// Opens the database at `path`, or returns the one already opened by another
+    /// processor.
+    fn open(path: PathBuf) -> Result<Arc<Self>> {
+        let mut databases = DATABASES.lock().unwrap();
+        if let Some(database) = databases.get(&path).and_then(Weak::upgrade) {
+            return Ok(database);
+        }
+
+        let state = Self::load(&path)?;
+        let database = Arc::new(GeoIpDatabase {
+            path: path.clone(),
+            state: RwLock::new(state),
+            last_check: Mutex::new(Instant::now()),
+            reloading: AtomicBool::new(false),
+        });
+        databases.retain(|_, database| database.strong_count() > 0);
+        databases.insert(path, Arc::downgrade(&database));
+        Ok(database)
+    }
+
+    fn load(path: &Path) -> Result<LoadedDatabase> {
+        let path_str = path.display().to_string();
+        let modified = std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let buf = std::fs::read(path).context(GeoIpReadDatabaseSnafu { path: &path_str })?;
+        let reader = MmdbReader::from_bytes(buf).map_err(|reason| {
+            GeoIpInvalidDatabaseSnafu {
+                path: &path_str,
+                reason,
+            }
+            .build()
+        })?;
+        Ok(LoadedDatabase {
+            reader: Arc::new(reader),
+            modified,
+        })
+    }
+
+    /// Returns the current reader.
+    ///
+    /// The file is periodically checked for changes and reloaded by a blocking task,
+    /// so the caller keeps using the current reader until the reload completes.
+    fn reader(self: &Arc<Self>) -> Arc<MmdbReader> {
+        if let Ok(mut last_check) = self.last_check.try_lock()
+            && last_check.elapsed() >= RELOAD_CHECK_INTERVAL
+            && !self.reloading.swap(true, Ordering::AcqRel)
+        {
+            *last_check = Instant::now();
+            let database = self.clone();
+            common_runtime::spawn_blocking_global(move || {
+                database.reload_if_modified();
+                database.reloading.store(false, Ordering::Release);
+            });
+        }
+        self.state.read().unwrap().reader.clone()
+    }
+
+    fn reload_if_modified(&self) {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|m| m.modified())
+            .ok();
+        if modified.is_none() || modified == self.state.read().unwrap().modified {
+            return;
+        }
+
+        match Self::load(&self.path) {
+            Ok(state) => {
+                info!("Reloaded GeoIP database {}", self.path.display());
+                *self.state.write().unwrap() = state;
+            }
+            Err(e) => {
+                // Keeps serving the previous database until a valid one is written.
+                warn!(e; "Failed to reload GeoIP database {}", self.path.display());
+            }
+        }
+    }
+}
+
+/// Enriches IP addresses with geolocation and ASN data from a local MaxMind DB (mmdb)
+/// file, e.g. GeoLite2-City or GeoLite2-ASN.
+///
+/// The output fields are named `{field}_{property}`. Properties not present in the
+/// database record are left unset, and addresses not found in the database produce
+/// no fields. The database file is reloaded when it changes.
+///
+/// The `database` is a path relative to the directory set by [set_geoip_database_dir].
+#[derive(Debug)]
+pub struct GeoIpProcessor {
+    fields: Fields,
+    database: Arc<GeoIpDatabase>,
+    properties: Vec<Property>,
+    geohash_precision: usize,
+    ignore_missing: bool,
+}
+
+impl GeoIpProcessor {
+    fn process(&self, prefix: &str, val: &str) -> Result<BTreeMap<KeyString, VrlValue>> {
+        let ip = val
+            .trim()
+            .parse::<IpAddr>()
+            .ok()
+            .context(GeoIpInvalidAddressSnafu { value: val })?;
+
+        let mut result = BTreeMap::new();
+        let record = match self.database.reader().lookup(ip) {
+            Ok(Some(record)) => record,
+            Ok(None) => return Ok(result),
+            Err(reason) => {
+                return GeoIpInvalidDatabaseSnafu {
+                    path: self.database.path.display().to_string(),
+                    reason,
+                }
+                .fail();
+            }
+        };
+
+        let lat = record
+            .get(&["location", "latitude"])
+            .and_then(MmdbValue::as_f64);
+        let lon = record
+            .get(&["location", "longitude"])
+            .and_then(MmdbValue::as_f64);
+        let float = |v: f64| NotNan::new(v).ok().map(VrlValue::Float);
+        let string = |v: String| VrlValue::Bytes(Bytes::from(v));
+
+        for property in &self.properties {
+            let value = match property {
+                Property::Latitude => lat.and_then(float),
+                Property::Longitude => lon.and_then(float),
+                Property::Location => lat
+                    .zip(lon)
+                    .map(|(lat, lon)| string(format!("POINT({lon} {lat})"))),
+                Property::Geohash => lat
+                    .zip(lon)
+                    .map(|(lat, lon)| string(encode_geohash(lat, lon, self.geohash_precision))),
+                Property::Asn => record
+                    .get(&["autonomous_system_number"])
+                    .and_then(MmdbValue::as_u64)
+                    .and_then(|v| i64::try_from(v).ok())
+                    .map(VrlValue::Integer),
+                _ => record
+                    .get(property.path())
+                    .and_then(MmdbValue::as_str)
+                    .map(|v| string(v.to_string())),
+            };
+            if let Some(value) = value {
+                result.insert(
+                    KeyString::from(format!("{prefix}_{}", property.name())),
+                    value,
+                );
+            }
+        }
+        Ok(result)
+    }
+}
+
+impl TryFrom<&yaml_rust::yaml::Hash> for GeoIpProcessor {
+    type Error = Error;
+
+    fn try_from(value: &yaml_rust::yaml::Hash) -> Result<Self> {
+        let mut fields = Fields::default();
+        let mut database = None;
+        let mut properties = Property::ALL.to_vec();
+        let mut geohash_precision = DEFAULT_GEOHASH_PRECISION;
+        let mut ignore_missing = false;
+
+        for (k, v) in value.iter() {
+            let key = k
+                .as_str()
+                .with_context(|| KeyMustBeStringSnafu { k: k.clone() })?;
+            match key {
+                FIELD_NAME => {
+                    fields = Fields::one(yaml_new_field(v, FIELD_NAME)?);
+                }
+                FIELDS_NAME => {
+                    fields = yaml_new_fields(v, FIELDS_NAME)?;
+                }
+                DATABASE_NAME => {
+                    database = Some(yaml_string(v, DATABASE_NAME)?);
+                }
+                PROPERTIES_NAME => {
+                    properties = yaml_strings(v, PROPERTIES_NAME)?
+                        .iter()
+                        .map(|p| p.parse())
+                        .collect::<Result<_>>()?;
+                }
+                GEOHASH_PRECISION_NAME => {
+                    geohash_precision = match v.as_i64() {
+                        Some(p) => p as usize,
+                        None => yaml_parse_string(v, GEOHASH_PRECISION_NAME)?,
+                    }
+                    .clamp(1, MAX_GEOHASH_PRECISION);
+                }
+                IGNORE_MISSING_NAME => {
+                    ignore_missing = yaml_bool(v, IGNORE_MISSING_NAME)?;
+                }
+                _ => {}
+            }
+        }
+
+        if fields.is_empty() {
+            return RegexNoValidFieldSnafu {
+                processor: PROCESSOR_GEOIP,
+            }
+            .fail();
+        }
+        let database = database.context(GeoIpDatabaseRequiredSnafu)?;
+        let database = GeoIpDatabase::open(resolve_database_path(&database)?)?;
+
+        Ok(GeoIpProcessor {
+            fields,
+            database,
+            properties,
+            geohash_precision,
+            ignore_missing,
+        })
+    }
+}
+
+impl Processor for GeoIpProcessor {
+    fn kind(&self) -> &str {
+        PROCESSOR_GEOIP
+    }
+
+    fn ignore_missing(&self) -> bool {
+        self.ignore_missing
+    }
+
+    fn exec_mut(&self, mut val: VrlValue) -> Result<VrlValue> {
+        for field in self.fields.iter() {
+            let index = field.input_field();
+            let prefix = field.target_or_input_field();
+            let val = val.as_object_mut().context(ValueMustBeMapSnafu)?;
+            match val.get(index) {
+                Some(VrlValue::Bytes(s)) => {
+                    let result = self.process(prefix, String::from_utf8_lossy(s).as_ref())?;
+                    val.extend(result);
+                }
+                Some(VrlValue::Null) | None => {
+                    if !self.ignore_missing {
+                        return ProcessorMissingFieldSnafu {
+                            processor: self.kind(),
+                            field: field.input_field(),
+                        }
+                        .fail();
+                    }
+                }
+                Some(v) => {
+                    return ProcessorExpectStringSnafu {
+                        processor: self.kind(),
+                        v: v.clone(),
+                    }
+                    .fail();
+                }
+            }
+        }
+
+        Ok(val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mmdb::test_util::{Value, build};
+    use super::*;
+
+    fn city_database() -> Vec<u8> {
+        build(&[
+            (
+                "81.2.69.0",
+                24,
+                Value::Map(vec![
+                    (
+                        "city",
+                        Value::Map(vec![(
+                            "names",
+                            Value::Map(vec![("en", Value::String("London"))]),
+                        )]),
+                    ),
+                    (
+                        "country",
+                        Value::Map(vec![
+                            ("iso_code", Value::String("GB")),
+                            (
+                                "names",
+                                Value::Map(vec![("en", Value::String("United Kingdom"))]),
+                            ),
+                        ]),
+                    ),
+                    (
+                        "location",
+                        Value::Map(vec![
+                            ("latitude", Value::Double(51.5142)),
+                            ("longitude", Value::Double(-0.0931)),
+                            ("time_zone", Value::String("Europe/London")),
+                        ]),
+                    ),
+                ]),
+            ),
+            (
+                "1.128.0.0",
+                11,
+                Value::Map(vec![
+                    ("autonomous_system_number", Value::Uint32(1221)),
+                    (
+                        "autonomous_system_organization",
+                        Value::String("Telstra Pty Ltd"),
+                    ),
+                ]),
+            ),
+        ])
+    }
+
+    /// The database directory shared by all tests.
+    fn database_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("test_geoip_{}", std::process::id()));
+        set_geoip_database_dir(Some(dir.to_str().unwrap()));
+        dir
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = database_dir().join(name);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn try_new_processor(yaml: &str) -> Result<GeoIpProcessor> {
+        let yaml = yaml_rust::YamlLoader::load_from_str(yaml)
+            .unwrap()
+            .pop()
+            .unwrap();
+        GeoIpProcessor::try_from(yaml.as_hash().unwrap())
+    }
+
+    fn new_processor(dir: &Path, yaml: &str) -> GeoIpProcessor {
+        std::fs::write(dir.join("test.mmdb"), city_database()).unwrap();
+        let name = dir.file_name().unwrap().to_str().unwrap();
+        try_new_processor(&format!("{yaml}\ndatabase: {name}/test.mmdb")).unwrap()
+    }
+
+    #[test]
+    fn test_geohash() {
+        assert_eq!("gcpvjcu5ywgs", encode_geohash(51.5142, -0.0931, 12));
+        assert_eq!("u4pruydqqvj", encode_geohash(57.64911, 10.40744, 11));
+    }
+
+    #[test]
+    fn test_geoip_lookup() {
+        let dir = temp_dir("test_geoip_lookup");
+        let processor = new_processor(&dir, "field: ip");
+
+        let result = processor.process("ip", "81.2.69.142").unwrap();
+        let string = |s: &str| VrlValue::Bytes(Bytes::from(s.to_string()));
+        let expected = [
+            ("ip_city_name", string("London")),
+            ("ip_country_iso_code", string("GB")),
+            ("ip_country_name", string("United Kingdom")),
+            ("ip_timezone", string("Europe/London")),
+            (
+                "ip_latitude",
+                VrlValue::Float(NotNan::new(51.5142).unwrap()),
+            ),
+            (
+                "ip_longitude",
+                VrlValue::Float(NotNan::new(-0.0931).unwrap()),
+            ),
+            ("ip_location", string("POINT(-0.0931 51.5142)")),
+            ("ip_geohash", string("gcpvjcu5ywgs")),
+        ]
+        .into_iter()
+        .map(|(k, v)| (KeyString::from(k), v))
+        .collect::<BTreeMap<_, _>>();
+        assert_eq!(expected, result);
+
+        let result = processor.process("ip", "1.128.0.1").unwrap();
+        assert_eq!(VrlValue::Integer(1221), result["ip_asn"]);
+        assert_eq!(string("Telstra Pty Ltd"), result["ip_organization_name"]);
+
+        assert!(processor.process("ip", "8.8.8.8").unwrap().is_empty());
+        assert!(processor.process("ip", "not an ip").is_err());
+    }
+
+    #[test]
+    fn test_geoip_database_path() {
+        let dir = temp_dir("test_geoip_database_path");
+        let processor = new_processor(&dir, "field: ip");
+        // Processors reading the same database share it.
+        let other = new_processor(&dir, "field: ip");
+        assert!(Arc::ptr_eq(&processor.database, &other.database));
+
+        let outside = database_dir()
+            .parent()
+            .unwrap()
+            .join("test_geoip_outside.mmdb");
+        std::fs::write(&outside, city_database()).unwrap();
+        for database in [
+            outside.to_str().unwrap().to_string(),
+            "../test_geoip_outside.mmdb".to_string(),
+            "test_geoip_database_path/../../test_geoip_outside.mmdb".to_string(),
+        ] {
+            assert!(matches!(
+                try_new_processor(&format!("field: ip\ndatabase: {database}")),
+                Err(Error::GeoIpInvalidDatabasePath { .. })
+            ));
+        }
+        #[cfg(unix)]
+        {
+            let link = dir.join("link.mmdb");
+            let _ = std::fs::remove_file(&link);
+            std::os::unix::fs::symlink(&outside, &link).unwrap();
+            assert!(matches!(
+                try_new_processor("field: ip\ndatabase: test_geoip_database_path/link.mmdb"),
+                Err(Error::GeoIpInvalidDatabasePath { .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_geoip_properties() {
+        let dir = temp_dir("test_geoip_properties");
+        let processor = new_processor(
+            &dir,
+            "field: client_ip, geo\nproperties: [country_iso_code, geohash]\ngeohash_precision: 5",
+        );
+
+        let result = processor.process("geo", "81.2.69.142").unwrap();
+        assert_eq!(2, result.len());
+        assert_eq!(VrlValue::Bytes(Bytes::from("gcpvj")), result["geo_geohash"]);
+    }
+
+    #[test]
+    fn test_geoip_reload() {
+        let dir = temp_dir("test_geoip_reload");
+        let processor = new_processor(&dir, "field: ip");
+        assert!(processor.process("ip", "8.8.8.8").unwrap().is_empty());
+
+        let path = dir.join("test.mmdb");
+        std::fs::write(
+            &path,
+            build(&[(
+                "8.8.8.0",
+                24,
+                Value::Map(vec![(
+                    "country",
+                    Value::Map(vec![("iso_code", Value::String("US"))]),
+                )]),
+            )]),
+        )
+        .unwrap();
+        let modified = SystemTime::now() + Duration::from_secs(60);
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified)
+            .unwrap();
+        processor.database.reload_if_modified();
+
+        let result = processor.process("ip", "8.8.8.8").unwrap();
+        assert_eq!(
+            VrlValue::Bytes(Bytes::from("US")),
+            result["ip_country_iso_code"]
+        );
+        assert!(processor.process("ip", "81.2.69.142").unwrap().is_empty());
+
+        // An invalid database keeps the previous one.
+        std::fs::write(&path, b"invalid").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(modified + Duration::from_secs(60))
+            .unwrap();
+        processor.database.reload_if_modified();
+        assert!(!processor.process("ip", "8.8.8.8").unwrap().is_empty());
+    }
+}
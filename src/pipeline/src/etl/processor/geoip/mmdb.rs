@@ -0,0 +1,517 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A minimal reader of the MaxMind DB (mmdb) format.
+//!
+//! See <https://maxmind.github.io/MaxMind-DB/> for the specification.
+
+use std::collections::BTreeMap;
+use std::net::IpAddr;
+
+const METADATA_MARKER: &[u8] = b"\xAB\xCD\xEFMaxMind.com";
+/// The metadata section is at most 128KiB and located at the end of the file.
+const METADATA_MAX_SIZE: usize = 128 * 1024;
+/// The size of the zero-filled separator between the search tree and the data section.
+const DATA_SECTION_SEPARATOR_SIZE: usize = 16;
+const MAX_DECODE_DEPTH: usize = 64;
+
+/// A value decoded from the data section.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum MmdbValue {
+    String(String),
+    Double(f64),
+    Float(f32),
+    Bytes(Vec<u8>),
+    Uint(u128),
+    Int(i32),
+    Bool(bool),
+    Map(BTreeMap<String, MmdbValue>),
+    Array(Vec<MmdbValue>),
+}
+
+impl MmdbValue {
+    /// Follows `path` through nested maps and arrays, array elements are addressed
+    /// by their index.
+    pub(crate) fn get(&self, path: &[&str]) -> Option<&MmdbValue> {
+        path.iter().try_fold(self, |value, key| match value {
+            MmdbValue::Map(map) => map.get(*key),
+            MmdbValue::Array(array) => array.get(key.parse::<usize>().ok()?),
+            _ => None,
+        })
+    }
+
+    pub(crate) fn as_str(&self) -> Option<&str> {
+        match self {
+            MmdbValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_f64(&self) -> Option<f64> {
+        match self {
+            MmdbValue::Double(v) => Some(*v),
+            MmdbValue::Float(v) => Some(*v as f64),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn as_u64(&self) -> Option<u64> {
+        match self {
+            MmdbValue::Uint(v) => u64::try_from(*v).ok(),
+            MmdbValue::Int(v) => u64::try_from(*v).ok(),
+            _ => None,
+        }
+    }
+}
+
+/// An in-memory mmdb database.
+#[derive(Debug)]
+pub(crate) struct MmdbReader {
+    buf: Vec<u8>,
+    node_count: usize,
+    record_size: usize,
+    ip_version: u64,
+    /// The node to start an IPv4 lookup from in an IPv6 database.
+    ipv4_start: usize,
+    data_section_start: usize,
+}
+
+impl MmdbReader {
+    pub(crate) fn from_bytes(buf: Vec<u8>) -> Result<Self, String> {
+        let search_start = buf.len().saturating_sub(METADATA_MAX_SIZE);
+        let marker = buf[search_start..]
+            .windows(METADATA_MARKER.len())
+            .rposition(|w| w == METADATA_MARKER)
+            .ok_or("metadata section not found")?;
+        let metadata_start = search_start + marker + METADATA_MARKER.len();
+
+        let metadata = Decoder {
+            buf: &buf[metadata_start..],
+        }
+        .decode(0, 0)?
+        .0;
+        let metadata_u64 = |key: &str| {
+            metadata
+                .get(&[key])
+                .and_then(|v| v.as_u64())
+                .ok_or(format!("missing metadata field {key}"))
+        };
+        let node_count = metadata_u64("node_count")? as usize;
+        let record_size = metadata_u64("record_size")? as usize;
+        let ip_version = metadata_u64("ip_version")?;
+        if !matches!(record_size, 24 | 28 | 32) {
+            return Err(format!("unsupported record size {record_size}"));
+        }
+        if !matches!(ip_version, 4 | 6) {
+            return Err(format!("unsupported ip version {ip_version}"));
+        }
+
+        let tree_size = node_count * record_size / 4;
+        let data_section_start = tree_size + DATA_SECTION_SEPARATOR_SIZE;
+        if data_section_start > metadata_start {
+            return Err("search tree exceeds the file size".to_string());
+        }
+
+        let mut reader = MmdbReader {
+            buf,
+            node_count,
+            record_size,
+            ip_version,
+            ipv4_start: 0,
+            data_section_start,
+        };
+        if ip_version == 6 {
+            // IPv4 addresses are stored in the `::/96` subtree.
+            let mut node = 0;
+            for _ in 0..96 {
+                if node >= node_count {
+                    break;
+                }
+                node = reader.read_record(node, 0)?;
+            }
+            reader.ipv4_start = node;
+        }
+        Ok(reader)
+    }
+
+    /// Looks up the record of `ip`, returns `None` if the address isn't in the database.
+    pub(crate) fn lookup(&self, ip: IpAddr) -> Result<Option<MmdbValue>, String> {
+        let (bytes, mut node) = match ip {
+            IpAddr::V4(ip) => (ip.octets().to_vec(), self.ipv4_start),
+            IpAddr::V6(_) if self.ip_version == 4 => {
+                return Err("IPv6 lookup in an IPv4 only database".to_string());
+            }
+            IpAddr::V6(ip) => (ip.octets().to_vec(), 0),
+        };
+
+        for i in 0..bytes.len() * 8 {
+            if node >= self.node_count {
+                break;
+            }
+            let bit = (bytes[i / 8] >> (7 - i % 8)) & 1;
+            node = self.read_record(node, bit as usize)?;
+        }
+
+        if node == self.node_count {
+            return Ok(None);
+        }
+        if node < self.node_count {
+            return Err("invalid search tree".to_string());
+        }
+        let offset = (node - self.node_count)
+            .checked_sub(DATA_SECTION_SEPARATOR_SIZE)
+            .ok_or("search tree record points into the data section separator")?;
+        let decoder = Decoder {
+            buf: &self.buf[self.data_section_start..],
+        };
+        decoder.decode(offset, 0).map(|(value, _)| Some(value))
+    }
+
+    fn read_record(&self, node: usize, index: usize) -> Result<usize, String> {
+        let node_size = self.record_size / 4;
+        let start = node * node_size;
+        let b = self
+            .buf
+            .get(start..start + node_size)
+            .ok_or("search tree node out of bounds")?;
+        let be = |bytes: &[u8]| bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize);
+        let record = match (self.record_size, index) {
+            (24, 0) => be(&b[0..3]),
+            (24, _) => be(&b[3..6]),
+            (28, 0) => ((b[3] as usize & 0xF0) << 20) | be(&b[0..3]),
+            (28, _) => ((b[3] as usize & 0x0F) << 24) | be(&b[4..7]),
+            (_, 0) => be(&b[0..4]),
+            (_, _) => be(&b[4..8]),
+        };
+        Ok(record)
+    }
+}
+
+struct Decoder<'a> {
+    buf: &'a [u8],
+}
+
+impl Decoder<'_> {
+    /// Decodes the value at `offset`, returns it with the offset of the next value.
+    fn decode(&self, offset: usize, depth: usize) -> Result<(MmdbValue, usize), String> {
+        if depth > MAX_DECODE_DEPTH {
+            return Err("data section nests too deep".to_string());
+        }
+        let ctrl = self.byte(offset)?;
+        let mut offset = offset + 1;
+        let mut ty = ctrl >> 5;
+
+        if ty == 1 {
+            // Pointers are followed, but the next value comes after the pointer itself.
+            let size = ((ctrl >> 3) & 0x3) as usize;
+            let vvv = (ctrl & 0x7) as usize;
+            let bytes = self.bytes(offset, size + 1)?;
+            let pointer = match size {
+                0 => (vvv << 8) | be_usize(bytes),
+                1 => ((vvv << 16) | be_usize(bytes)) + 2048,
+                2 => ((vvv << 24) | be_usize(bytes)) + 526336,
+                _ => be_usize(bytes),
+            };
+            let (value, _) = self.decode(pointer, depth + 1)?;
+            return Ok((value, offset + size + 1));
+        }
+
+        if ty == 0 {
+            ty = 7 + self.byte(offset)?;
+            offset += 1;
+        }
+
+        let mut size = (ctrl & 0x1f) as usize;
+        if size >= 29 {
+            let extra = size - 28;
+            let bytes = be_usize(self.bytes(offset, extra)?);
+            size = match extra {
+                1 => 29 + bytes,
+                2 => 285 + bytes,
+                _ => 65821 + bytes,
+            };
+            offset += extra;
+        }
+
+        match ty {
+            2 => {
+                let s =
+                    std::str::from_utf8(self.bytes(offset, size)?).map_err(|e| e.to_string())?;
+                Ok((MmdbValue::String(s.to_string()), offset + size))
+            }
+            3 => {
+                let bytes: [u8; 8] = self
+                    .bytes(offset, size)?
+                    .try_into()
+                    .map_err(|_| "invalid double size")?;
+                Ok((MmdbValue::Double(f64::from_be_bytes(bytes)), offset + size))
+            }
+            4 => Ok((
+                MmdbValue::Bytes(self.bytes(offset, size)?.to_vec()),
+                offset + size,
+            )),
+            5 | 6 | 9 | 10 => {
+                let bytes = self.bytes(offset, size)?;
+                if size > 16 {
+                    return Err("invalid unsigned integer size".to_string());
+                }
+                let value = bytes.iter().fold(0u128, |acc, b| (acc << 8) | *b as u128);
+                Ok((MmdbValue::Uint(value), offset + size))
+            }
+            7 => {
+                let mut map = BTreeMap::new();
+                for _ in 0..size {
+                    let (key, next) = self.decode(offset, depth + 1)?;
+                    let MmdbValue::String(key) = key else {
+                        return Err("map key must be a string".to_string());
+                    };
+                    let (value, next) = self.decode(next, depth + 1)?;
+                    map.insert(key, value);
+                    offset = next;
+                }
+                Ok((MmdbValue::Map(map), offset))
+            }
+            8 => {
+                let bytes = self.bytes(offset, size)?;
+                if size > 4 {
+                    return Err("invalid int32 size".to_string());
+                }
+                let value = bytes.iter().fold(0u32, |acc, b| (acc << 8) | *b as u32);
+                Ok((MmdbValue::Int(value as i32), offset + size))
+            }
+            11 => {
+                let mut array = Vec::with_capacity(size.min(1024));
+                for _ in 0..size {
+                    let (value, next) = self.decode(offset, depth + 1)?;
+                    array.push(value);
+                    offset = next;
+                }
+                Ok((MmdbValue::Array(array), offset))
+            }
+            14 => Ok((MmdbValue::Bool(size != 0), offset)),
+            15 => {
+                let bytes: [u8; 4] = self
+                    .bytes(offset, size)?
+                    .try_into()
+                    .map_err(|_| "invalid float size")?;
+                Ok((MmdbValue::Float(f32::from_be_bytes(bytes)), offset + size))
+            }
+            _ => Err(format!("unsupported data type {ty}")),
+        }
+    }
+
+    fn byte(&self, offset: usize) -> Result<u8, String> {
+        self.buf
+            .get(offset)
+            .copied()
+            .ok_or_else(|| "data section offset out of bounds".to_string())
+    }
+
+    fn bytes(&self, offset: usize, len: usize) -> Result<&[u8], String> {
+        self.buf
+            .get(offset..offset + len)
+            .ok_or_else(|| "data section offset out of bounds".to_string())
+    }
+}
+
+fn be_usize(bytes: &[u8]) -> usize {
+    bytes.iter().fold(0usize, |acc, b| (acc << 8) | *b as usize)
+}
+
+/// Builds mmdb files for tests.
+#[cfg(test)]
+pub(crate) mod test_util {
+    use std::net::IpAddr;
+
+    use super::{DATA_SECTION_SEPARATOR_SIZE, METADATA_MARKER};
+
+    /// A value to encode into the data section.
+    pub(crate) enum Value<'a> {
+        String(&'a str),
+        Double(f64),
+        Uint32(u32),
+        Map(Vec<(&'a str, Value<'a>)>),
+        Array(Vec<Value<'a>>),
+    }
+
+    fn encode_ctrl(buf: &mut Vec<u8>, ty: u8, size: usize) {
+        assert!(size < 29);
+        if ty > 7 {
+            buf.push(size as u8);
+            buf.push(ty - 7);
+        } else {
+            buf.push((ty << 5) | size as u8);
+        }
+    }
+
+    pub(crate) fn encode(buf: &mut Vec<u8>, value: &Value) {
+        match value {
+            Value::String(s) => {
+                encode_ctrl(buf, 2, s.len());
+                buf.extend_from_slice(s.as_bytes());
+            }
+            Value::Double(v) => {
+                encode_ctrl(buf, 3, 8);
+                buf.extend_from_slice(&v.to_be_bytes());
+            }
+            Value::Uint32(v) => {
+                encode_ctrl(buf, 6, 4);
+                buf.extend_from_slice(&v.to_be_bytes());
+            }
+            Value::Map(entries) => {
+                encode_ctrl(buf, 7, entries.len());
+                for (k, v) in entries {
+                    encode(buf, &Value::String(k));
+                    encode(buf, v);
+                }
+            }
+            Value::Array(values) => {
+                encode_ctrl(buf, 11, values.len());
+                for v in values {
+                    encode(buf, v);
+                }
+            }
+        }
+    }
+
+    /// Builds an IPv6 database with 24 bit records from `(network, prefix_len, record)`.
+    /// IPv4 networks are stored in the `::/96` subtree.
+    pub(crate) fn build(networks: &[(&str, usize, Value)]) -> Vec<u8> {
+        enum Record {
+            Empty,
+            Node(usize),
+            Data(usize),
+        }
+
+        let mut nodes: Vec<[Record; 2]> = vec![[Record::Empty, Record::Empty]];
+        let mut data = vec![];
+        for (network, prefix_len, value) in networks {
+            let (bytes, prefix_len) = match network.parse::<IpAddr>().unwrap() {
+                IpAddr::V4(ip) => {
+                    let mut bytes = [0u8; 16];
+                    bytes[12..].copy_from_slice(&ip.octets());
+                    (bytes, prefix_len + 96)
+                }
+                IpAddr::V6(ip) => (ip.octets(), *prefix_len),
+            };
+            let offset = data.len();
+            encode(&mut data, value);
+
+            let mut node = 0;
+            for i in 0..prefix_len {
+                let bit = ((bytes[i / 8] >> (7 - i % 8)) & 1) as usize;
+                if i == prefix_len - 1 {
+                    nodes[node][bit] = Record::Data(offset);
+                } else {
+                    node = match nodes[node][bit] {
+                        Record::Node(next) => next,
+                        _ => {
+                            nodes.push([Record::Empty, Record::Empty]);
+                            let next = nodes.len() - 1;
+                            nodes[node][bit] = Record::Node(next);
+                            next
+                        }
+                    };
+                }
+            }
+        }
+
+        let node_count = nodes.len();
+        let mut buf = vec![];
+        for node in &nodes {
+            for record in node {
+                let value = match record {
+                    Record::Empty => node_count,
+                    Record::Node(next) => *next,
+                    Record::Data(offset) => node_count + DATA_SECTION_SEPARATOR_SIZE + offset,
+                };
+                buf.extend_from_slice(&(value as u32).to_be_bytes()[1..]);
+            }
+        }
+        buf.extend_from_slice(&[0; DATA_SECTION_SEPARATOR_SIZE]);
+        buf.extend_from_slice(&data);
+        buf.extend_from_slice(METADATA_MARKER);
+        encode(
+            &mut buf,
+            &Value::Map(vec![
+                ("node_count", Value::Uint32(node_count as u32)),
+                ("record_size", Value::Uint32(24)),
+                ("ip_version", Value::Uint32(6)),
+                ("database_type", Value::String("Test")),
+            ]),
+        );
+        buf
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::test_util::{Value, build};
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let db = build(&[
+            (
+                "1.1.1.0",
+                24,
+                Value::Map(vec![
+                    ("city", Value::String("Sydney")),
+                    (
+                        "tags",
+                        Value::Array(vec![Value::String("a"), Value::String("b")]),
+                    ),
+                    ("latitude", Value::Double(-33.86)),
+                ]),
+            ),
+            ("2001:db8::", 32, Value::Uint32(42)),
+        ]);
+        let reader = MmdbReader::from_bytes(db).unwrap();
+
+        let value = reader.lookup("1.1.1.1".parse().unwrap()).unwrap().unwrap();
+        assert_eq!(
+            Some("Sydney"),
+            value.get(&["city"]).and_then(|v| v.as_str())
+        );
+        assert_eq!(
+            Some("b"),
+            value.get(&["tags", "1"]).and_then(|v| v.as_str())
+        );
+        assert_eq!(
+            Some(-33.86),
+            value.get(&["latitude"]).and_then(|v| v.as_f64())
+        );
+
+        let value = reader.lookup("2001:db8::1".parse().unwrap()).unwrap();
+        assert_eq!(Some(MmdbValue::Uint(42)), value);
+
+        assert_eq!(None, reader.lookup("1.1.2.1".parse().unwrap()).unwrap());
+        assert_eq!(None, reader.lookup("::1".parse().unwrap()).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_database() {
+        assert!(MmdbReader::from_bytes(b"not a database".to_vec()).is_err());
+    }
+
+    #[test]
+    fn test_record_in_data_section_separator() {
+        let mut db = build(&[("1.1.1.0", 24, Value::Uint32(1))]);
+        let node_count = MmdbReader::from_bytes(db.clone()).unwrap().node_count;
+        // Points the left record of the root into the data section separator.
+        db[0..3].copy_from_slice(&((node_count + 1) as u32).to_be_bytes()[1..]);
+        let reader = MmdbReader::from_bytes(db).unwrap();
+        assert!(reader.lookup("1.1.1.1".parse().unwrap()).is_err());
+    }
+}
@@ -0,0 +1,491 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod patterns;
+
+use std::collections::{BTreeMap, HashMap};
+use std::str::FromStr;
+
+use lazy_static::lazy_static;
+use ordered_float::NotNan;
+use regex::Regex;
+use snafu::{OptionExt, ResultExt, ensure};
+use vrl::prelude::Bytes;
+use vrl::value::{KeyString, Value as VrlValue};
+
+use crate::error::{
+    Error, FieldMustBeTypeSnafu, GrokConvertCaptureSnafu, GrokInvalidCaptureTypeSnafu,
+    GrokNoMatchingPatternSnafu, GrokPatternNotFoundSnafu, GrokRecursivePatternSnafu,
+    KeyMustBeStringSnafu, ProcessorExpectStringSnafu, ProcessorMissingFieldSnafu,
+    RegexNoValidFieldSnafu, RegexNoValidPatternSnafu, RegexSnafu, Result, ValueMustBeMapSnafu,
+};
+use crate::etl::field::Fields;
+use crate::etl::processor::{
+    FIELD_NAME, FIELDS_NAME, IGNORE_MISSING_NAME, PATTERN_NAME, PATTERNS_NAME, Processor,
+    yaml_bool, yaml_new_field, yaml_new_fields, yaml_string, yaml_strings,
+};
+
+pub(crate) const PROCESSOR_GROK: &str = "grok";
+
+const PATTERN_DEFINITIONS_NAME: &str = "pattern_definitions";
+
+/// Prefix of the regex group names generated for `%{NAME:field}` captures.
+/// Field names may contain characters that are not allowed in group names.
+const CAPTURE_GROUP_PREFIX: &str = "_grok_";
+
+lazy_static! {
+    /// Matches `%{NAME}`, `%{NAME:field}` and `%{NAME:field:type}`.
+    static ref GROK_REFERENCE_REGEX: Regex =
+        Regex::new(r"%\{([A-Za-z0-9_]+)(?::([^:}]+))?(?::([A-Za-z]+))?\}").unwrap();
+    static ref DEFAULT_DEFINITIONS: HashMap<String, String> = patterns::DEFAULT_PATTERNS
+        .iter()
+        .map(|(name, pattern)| (name.to_string(), pattern.to_string()))
+        .collect();
+}
+
+/// The type a captured value is converted to, e.g. `%{NUMBER:bytes:int}`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum CaptureType {
+    #[default]
+    String,
+    Int,
+    Float,
+    Boolean,
+}
+
+impl FromStr for CaptureType {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "string" => Ok(CaptureType::String),
+            "int" | "long" => Ok(CaptureType::Int),
+            "float" | "double" => Ok(CaptureType::Float),
+            "boolean" => Ok(CaptureType::Boolean),
+            _ => GrokInvalidCaptureTypeSnafu { ty: s }.fail(),
+        }
+    }
+}
+
+impl CaptureType {
+    fn name(&self) -> &'static str {
+        match self {
+            CaptureType::String => "string",
+            CaptureType::Int => "int",
+            CaptureType::Float => "float",
+            CaptureType::Boolean => "boolean",
+        }
+    }
+
+    fn convert(&self, field: &str, value: &str) -> Result<VrlValue> {
+        let converted = match self {
+            CaptureType::String => Some(VrlValue::Bytes(Bytes::from(value.to_string()))),
+            CaptureType::Int => value.parse::<i64>().ok().map(VrlValue::Integer),
+            CaptureType::Float => value
+                .parse::<f64>()
+                .ok()
+                .and_then(|v| NotNan::new(v).ok())
+                .map(VrlValue::Float),
+            CaptureType::Boolean => value.parse::<bool>().ok().map(VrlValue::Boolean),
+        };
+        converted.with_context(|| GrokConvertCaptureSnafu {
+            field,
+            value,
+            ty: self.name(),
+        })
+    }
+}
+
+#[derive(Debug)]
+struct GrokCapture {
+    /// The regex group name.
+    group: String,
+    /// The field the captured value is written to.
+    field: String,
+    ty: CaptureType,
+}
+
+/// A grok pattern compiled into a regex.
+#[derive(Debug)]
+struct GrokPattern {
+    regex: Regex,
+    captures: Vec<GrokCapture>,
+}
+
+impl GrokPattern {
+    fn compile(origin: &str, definitions: &HashMap<String, String>) -> Result<Self> {
+        let mut compiler = GrokCompiler {
+            definitions,
+            captures: vec![],
+            stack: vec![],
+        };
+        let expanded = compiler.expand(origin)?;
+        let regex = Regex::new(&expanded).context(RegexSnafu { pattern: origin })?;
+
+        let mut captures = compiler.captures;
+        // Plain named groups, e.g. `(?<queue_id>[0-9A-F]{10,11})`, are captured as strings.
+        for name in regex.capture_names().flatten() {
+            if !name.starts_with(CAPTURE_GROUP_PREFIX) {
+                captures.push(GrokCapture {
+                    group: name.to_string(),
+                    field: name.to_string(),
+                    ty: CaptureType::String,
+                });
+            }
+        }
+
+        Ok(GrokPattern { regex, captures })
+    }
+
+    /// Returns the captured fields, or `None` if the pattern doesn't match.
+    fn captures(&self, val: &str) -> Result<Option<BTreeMap<KeyString, VrlValue>>> {
+        let Some(caps) = self.regex.captures(val) else {
+            return Ok(None);
+        };
+
+        let mut result = BTreeMap::new();
+        for capture in &self.captures {
+            let Some(m) = caps.name(&capture.group) else {
+                continue;
+            };
+            let key = KeyString::from(capture.field.as_str());
+            // The same field may appear in several alternations, the first match wins.
+            if result.contains_key(&key) {
+                continue;
+            }
+            let value = capture.ty.convert(&capture.field, m.as_str())?;
+            result.insert(key, value);
+        }
+        Ok(Some(result))
+    }
+}
+
+struct GrokCompiler<'a> {
+    definitions: &'a HashMap<String, String>,
+    captures: Vec<GrokCapture>,
+    /// Names of the definitions being expanded, used to detect cycles.
+    stack: Vec<String>,
+}
+
+impl GrokCompiler<'_> {
+    /// Replaces all `%{...}` references in `pattern` with their definitions.
+    fn expand(&mut self, pattern: &str) -> Result<String> {
+        let mut expanded = String::with_capacity(pattern.len());
+        let mut last = 0;
+        for caps in GROK_REFERENCE_REGEX.captures_iter(pattern) {
+            let whole = caps.get(0).unwrap();
+            expanded.push_str(&pattern[last..whole.start()]);
+            last = whole.end();
+
+            let name = &caps[1];
+            let definitions = self.definitions;
+            let definition = definitions
+                .get(name)
+                .context(GrokPatternNotFoundSnafu { name })?;
+            ensure!(
+                !self.stack.iter().any(|n| n == name),
+                GrokRecursivePatternSnafu { name }
+            );
+            self.stack.push(name.to_string());
+            let inner = self.expand(definition)?;
+            self.stack.pop();
+
+            match caps.get(2) {
+                Some(field) => {
+                    let ty = caps
+                        .get(3)
+                        .map(|ty| ty.as_str().parse::<CaptureType>())
+                        .transpose()?
+                        .unwrap_or_default();
+                    let group = format!("{CAPTURE_GROUP_PREFIX}{}", self.captures.len());
+                    expanded.push_str(&format!("(?P<{group}>{inner})"));
+                    self.captures.push(GrokCapture {
+                        group,
+                        field: field.as_str().to_string(),
+                        ty,
+                    });
+                }
+                None => expanded.push_str(&format!("(?:{inner})")),
+            }
+        }
+        expanded.push_str(&pattern[last..]);
+        Ok(expanded)
+    }
+}
+
+/// Extracts fields from a string with grok patterns.
+///
+/// Patterns are tried in order and the captures of the first matching one are
+/// written to the document. Besides the bundled pattern library, additional
+/// definitions can be provided with `pattern_definitions`, which take precedence
+/// over the bundled ones.
+#[derive(Debug, Default)]
+pub struct GrokProcessor {
+    fields: Fields,
+    patterns: Vec<GrokPattern>,
+    ignore_missing: bool,
+}
+
+impl GrokProcessor {
+    fn check(self) -> Result<Self> {
+        if self.fields.is_empty() {
+            return RegexNoValidFieldSnafu {
+                processor: PROCESSOR_GROK,
+            }
+            .fail();
+        }
+
+        if self.patterns.is_empty() {
+            return RegexNoValidPatternSnafu {
+                processor: PROCESSOR_GROK,
+            }
+            .fail();
+        }
+
+        Ok(self)
+    }
+
+    fn process(&self, val: &str) -> Result<BTreeMap<KeyString, VrlValue>> {
+        for pattern in &self.patterns {
+            if let Some(result) = pattern.captures(val)? {
+                return Ok(result);
+            }
+        }
+        GrokNoMatchingPatternSnafu.fail()
+    }
+}
+
+impl TryFrom<&yaml_rust::yaml::Hash> for GrokProcessor {
+    type Error = Error;
+
+    fn try_from(value: &yaml_rust::yaml::Hash) -> Result<Self> {
+        let mut fields = Fields::default();
+        let mut patterns = vec![];
+        let mut definitions = DEFAULT_DEFINITIONS.clone();
+        let mut ignore_missing = false;
+
+        for (k, v) in value.iter() {
+            let key = k
+                .as_str()
+                .with_context(|| KeyMustBeStringSnafu { k: k.clone() })?;
+            match key {
+                FIELD_NAME => {
+                    fields = Fields::one(yaml_new_field(v, FIELD_NAME)?);
+                }
+                FIELDS_NAME => {
+                    fields = yaml_new_fields(v, FIELDS_NAME)?;
+                }
+                PATTERN_NAME => {
+                    patterns.push(yaml_string(v, PATTERN_NAME)?);
+                }
+                PATTERNS_NAME => {
+                    patterns.extend(yaml_strings(v, PATTERNS_NAME)?);
+                }
+                PATTERN_DEFINITIONS_NAME => {
+                    let map = v.as_hash().context(FieldMustBeTypeSnafu {
+                        field: PATTERN_DEFINITIONS_NAME,
+                        ty: "map",
+                    })?;
+                    for (name, pattern) in map {
+                        let name = yaml_string(name, PATTERN_DEFINITIONS_NAME)?;
+                        let pattern = yaml_string(pattern, PATTERN_DEFINITIONS_NAME)?;
+                        definitions.insert(name, pattern);
+                    }
+                }
+                IGNORE_MISSING_NAME => {
+                    ignore_missing = yaml_bool(v, IGNORE_MISSING_NAME)?;
+                }
+                _ => {}
+            }
+        }
+
+        // Patterns are compiled after all keys are read, since `pattern_definitions`
+        // may come after the patterns referencing them.
+        let patterns = patterns
+            .iter()
+            .map(|p| GrokPattern::compile(p, &definitions))
+            .collect::<Result<Vec<_>>>()?;
+
+        let processor = GrokProcessor {
+            fields,
+            patterns,
+            ignore_missing,
+        };
+
+        processor.check()
+    }
+}
+
+impl Processor for GrokProcessor {
+    fn kind(&self) -> &str {
+        PROCESSOR_GROK
+    }
+
+    fn ignore_missing(&self) -> bool {
+        self.ignore_missing
+    }
+
+    fn exec_mut(&self, mut val: VrlValue) -> Result<VrlValue> {
+        for field in self.fields.iter() {
+            let index = field.input_field();
+            let val = val.as_object_mut().context(ValueMustBeMapSnafu)?;
+            match val.get(index) {
+                Some(VrlValue::Bytes(s)) => {
+                    let result = self.process(String::from_utf8_lossy(s).as_ref())?;
+                    val.extend(result);
+                }
+                Some(VrlValue::Null) | None => {
+                    if !self.ignore_missing {
+                        return ProcessorMissingFieldSnafu {
+                            processor: self.kind(),
+                            field: field.input_field(),
+                        }
+                        .fail();
+                    }
+                }
+                Some(v) => {
+                    return ProcessorExpectStringSnafu {
+                        processor: self.kind(),
+                        v: v.clone(),
+                    }
+                    .fail();
+                }
+            }
+        }
+
+        Ok(val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_processor(yaml: &str) -> Result<GrokProcessor> {
+        let yaml = yaml_rust::YamlLoader::load_from_str(yaml)
+            .unwrap()
+            .pop()
+            .unwrap();
+        GrokProcessor::try_from(yaml.as_hash().unwrap())
+    }
+
+    fn string(s: &str) -> VrlValue {
+        VrlValue::Bytes(Bytes::from(s.to_string()))
+    }
+
+    #[test]
+    fn test_default_patterns_compile() {
+        for (name, _) in patterns::DEFAULT_PATTERNS {
+            let pattern = format!("%{{{name}}}");
+            GrokPattern::compile(&pattern, &DEFAULT_DEFINITIONS).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_combined_apache_log() {
+        let processor = new_processor(
+            r#"field: message
+patterns:
+  - "%{COMBINEDAPACHELOG}""#,
+        )
+        .unwrap();
+
+        let line = r#"127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] "GET /apache_pb.gif HTTP/1.0" 200 2326 "http://www.example.com/start.html" "Mozilla/4.08 [en] (Win98; I ;Nav)""#;
+        let result = processor.process(line).unwrap();
+
+        let expected = [
+            ("clientip", string("127.0.0.1")),
+            ("ident", string("-")),
+            ("auth", string("frank")),
+            ("timestamp", string("10/Oct/2000:13:55:36 -0700")),
+            ("verb", string("GET")),
+            ("request", string("/apache_pb.gif")),
+            ("httpversion", string("1.0")),
+            ("response", string("200")),
+            ("bytes", string("2326")),
+            ("referrer", string(r#""http://www.example.com/start.html""#)),
+            ("agent", string(r#""Mozilla/4.08 [en] (Win98; I ;Nav)""#)),
+        ]
+        .into_iter()
+        .map(|(k, v)| (KeyString::from(k), v))
+        .collect::<BTreeMap<_, _>>();
+        assert_eq!(expected, result);
+    }
+
+    #[test]
+    fn test_syslog_base() {
+        let processor = new_processor(
+            r#"field: message
+pattern: "%{SYSLOGBASE} %{GREEDYDATA:msg}""#,
+        )
+        .unwrap();
+
+        let result = processor
+            .process("Mar  7 00:00:01 web-1 sshd[4321]: Accepted publickey for root")
+            .unwrap();
+        assert_eq!(string("Mar  7 00:00:01"), result["timestamp"]);
+        assert_eq!(string("web-1"), result["logsource"]);
+        assert_eq!(string("sshd"), result["program"]);
+        assert_eq!(string("4321"), result["pid"]);
+        assert_eq!(string("Accepted publickey for root"), result["msg"]);
+    }
+
+    #[test]
+    fn test_typed_captures_and_definitions() {
+        let processor = new_processor(
+            r#"field: message
+patterns:
+  - "%{LEVEL:level} took %{NUMBER:took:float}ms, sent %{NUMBER:bytes:int} bytes, cached %{WORD:cached:boolean}"
+  - "%{LEVEL:level} (?<detail>.+)"
+pattern_definitions:
+  LEVEL: "(?:DEBUG|INFO|WARN|ERROR)""#,
+        )
+        .unwrap();
+
+        let result = processor
+            .process("INFO took 12.5ms, sent 1024 bytes, cached true")
+            .unwrap();
+        assert_eq!(string("INFO"), result["level"]);
+        assert_eq!(VrlValue::Float(NotNan::new(12.5).unwrap()), result["took"]);
+        assert_eq!(VrlValue::Integer(1024), result["bytes"]);
+        assert_eq!(VrlValue::Boolean(true), result["cached"]);
+
+        // falls back to the second pattern
+        let result = processor.process("ERROR connection reset").unwrap();
+        assert_eq!(string("ERROR"), result["level"]);
+        assert_eq!(string("connection reset"), result["detail"]);
+
+        assert!(processor.process("TRACE nothing").is_err());
+    }
+
+    #[test]
+    fn test_invalid_patterns() {
+        // unknown definition
+        assert!(new_processor(r#"{field: message, pattern: "%{NOT_EXISTS:a}"}"#).is_err());
+        // unknown type
+        assert!(new_processor(r#"{field: message, pattern: "%{INT:a:uint}"}"#).is_err());
+        // recursive definition
+        assert!(
+            new_processor(
+                r#"{field: message, pattern: "%{A:a}", pattern_definitions: {A: "%{B}", B: "%{A}"}}"#
+            )
+            .is_err()
+        );
+        // no pattern
+        assert!(new_processor("{field: message}").is_err());
+        // failed conversion
+        let processor = new_processor(r#"{field: message, pattern: "%{WORD:a:int}"}"#).unwrap();
+        assert!(processor.process("abc").is_err());
+    }
+}
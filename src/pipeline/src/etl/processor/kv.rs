@@ -0,0 +1,355 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use ahash::HashSet;
+use regex::Regex;
+use snafu::{OptionExt, ResultExt};
+use vrl::prelude::Bytes;
+use vrl::value::{KeyString, Value as VrlValue};
+
+use crate::error::{
+    Error, KeyMustBeStringSnafu, ProcessorExpectStringSnafu, ProcessorMissingFieldSnafu,
+    RegexNoValidFieldSnafu, RegexSnafu, Result, ValueMustBeMapSnafu,
+};
+use crate::etl::field::Fields;
+use crate::etl::processor::{
+    FIELD_NAME, FIELDS_NAME, IGNORE_MISSING_NAME, Processor, yaml_bool, yaml_new_field,
+    yaml_new_fields, yaml_string, yaml_strings,
+};
+
+pub(crate) const PROCESSOR_KV: &str = "kv";
+
+const FIELD_SPLIT_NAME: &str = "field_split";
+const VALUE_SPLIT_NAME: &str = "value_split";
+const TRIM_KEY_NAME: &str = "trim_key";
+const TRIM_VALUE_NAME: &str = "trim_value";
+const INCLUDE_KEYS_NAME: &str = "include_keys";
+const EXCLUDE_KEYS_NAME: &str = "exclude_keys";
+const PREFIX_NAME: &str = "prefix";
+const STRIP_QUOTES_NAME: &str = "strip_quotes";
+
+const DEFAULT_FIELD_SPLIT: &str = " ";
+const DEFAULT_VALUE_SPLIT: &str = "=";
+
+/// Splits a string of key-value pairs, like logfmt lines or query strings, into fields.
+///
+/// `field_split` and `value_split` are regexes separating the pairs and the key from
+/// the value. When `strip_quotes` is enabled (the default), double-quoted values may
+/// contain separators and are unquoted. Pairs without a value separator are skipped,
+/// and a key appearing more than once keeps its last value.
+#[derive(Debug)]
+pub struct KvProcessor {
+    fields: Fields,
+    field_split: Regex,
+    value_split: Regex,
+    trim_key: String,
+    trim_value: String,
+    include_keys: HashSet<String>,
+    exclude_keys: HashSet<String>,
+    prefix: String,
+    strip_quotes: bool,
+    ignore_missing: bool,
+}
+
+impl KvProcessor {
+    /// Splits `val` into pairs, ignoring field separators inside double quotes.
+    fn split_pairs<'a>(&self, val: &'a str) -> Vec<&'a str> {
+        let quoted = if self.strip_quotes {
+            quoted_ranges(val)
+        } else {
+            vec![]
+        };
+
+        let mut pairs = vec![];
+        let mut start = 0;
+        for m in self.field_split.find_iter(val) {
+            if m.is_empty() || quoted.iter().any(|(s, e)| *s < m.start() && m.start() < *e) {
+                continue;
+            }
+            pairs.push(&val[start..m.start()]);
+            start = m.end();
+        }
+        pairs.push(&val[start..]);
+        pairs.retain(|p| !p.is_empty());
+        pairs
+    }
+
+    fn process(&self, val: &str) -> BTreeMap<KeyString, VrlValue> {
+        let mut result = BTreeMap::new();
+        for pair in self.split_pairs(val) {
+            let Some(m) = self.value_split.find(pair) else {
+                continue;
+            };
+            let key = pair[..m.start()].trim_matches(|c| self.trim_key.contains(c));
+            if key.is_empty()
+                || (!self.include_keys.is_empty() && !self.include_keys.contains(key))
+                || self.exclude_keys.contains(key)
+            {
+                continue;
+            }
+
+            let value = pair[m.end()..].trim_matches(|c| self.trim_value.contains(c));
+            let value = if self.strip_quotes {
+                unquote(value)
+            } else {
+                value.to_string()
+            };
+            result.insert(
+                KeyString::from(format!("{}{key}", self.prefix)),
+                VrlValue::Bytes(Bytes::from(value)),
+            );
+        }
+        result
+    }
+}
+
+/// Returns the byte ranges of the double-quoted sections in `val`, quotes included.
+fn quoted_ranges(val: &str) -> Vec<(usize, usize)> {
+    let mut ranges = vec![];
+    let mut open = None;
+    let mut escaped = false;
+    for (i, b) in val.bytes().enumerate() {
+        match (open, b) {
+            (Some(_), _) if escaped => escaped = false,
+            (Some(_), b'\\') => escaped = true,
+            (Some(start), b'"') => {
+                ranges.push((start, i + 1));
+                open = None;
+            }
+            (None, b'"') => open = Some(i),
+            _ => {}
+        }
+    }
+    ranges
+}
+
+/// Removes the surrounding double quotes of `val` and unescapes the quoted content.
+fn unquote(val: &str) -> String {
+    let Some(inner) = val.strip_prefix('"').and_then(|v| v.strip_suffix('"')) else {
+        return val.to_string();
+    };
+
+    let mut unquoted = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some(next @ ('"' | '\\'))) => {
+                unquoted.push(next);
+                let _ = chars.next();
+            }
+            _ => unquoted.push(c),
+        }
+    }
+    unquoted
+}
+
+impl TryFrom<&yaml_rust::yaml::Hash> for KvProcessor {
+    type Error = Error;
+
+    fn try_from(value: &yaml_rust::yaml::Hash) -> Result<Self> {
+        let mut fields = Fields::default();
+        let mut field_split = DEFAULT_FIELD_SPLIT.to_string();
+        let mut value_split = DEFAULT_VALUE_SPLIT.to_string();
+        let mut trim_key = String::new();
+        let mut trim_value = String::new();
+        let mut include_keys = HashSet::default();
+        let mut exclude_keys = HashSet::default();
+        let mut prefix = String::new();
+        let mut strip_quotes = true;
+        let mut ignore_missing = false;
+
+        for (k, v) in value.iter() {
+            let key = k
+                .as_str()
+                .with_context(|| KeyMustBeStringSnafu { k: k.clone() })?;
+            match key {
+                FIELD_NAME => {
+                    fields = Fields::one(yaml_new_field(v, FIELD_NAME)?);
+                }
+                FIELDS_NAME => {
+                    fields = yaml_new_fields(v, FIELDS_NAME)?;
+                }
+                FIELD_SPLIT_NAME => {
+                    field_split = yaml_string(v, FIELD_SPLIT_NAME)?;
+                }
+                VALUE_SPLIT_NAME => {
+                    value_split = yaml_string(v, VALUE_SPLIT_NAME)?;
+                }
+                TRIM_KEY_NAME => {
+                    trim_key = yaml_string(v, TRIM_KEY_NAME)?;
+                }
+                TRIM_VALUE_NAME => {
+                    trim_value = yaml_string(v, TRIM_VALUE_NAME)?;
+                }
+                INCLUDE_KEYS_NAME => {
+                    include_keys = yaml_strings(v, INCLUDE_KEYS_NAME)?.into_iter().collect();
+                }
+                EXCLUDE_KEYS_NAME => {
+                    exclude_keys = yaml_strings(v, EXCLUDE_KEYS_NAME)?.into_iter().collect();
+                }
+                PREFIX_NAME => {
+                    prefix = yaml_string(v, PREFIX_NAME)?;
+                }
+                STRIP_QUOTES_NAME => {
+                    strip_quotes = yaml_bool(v, STRIP_QUOTES_NAME)?;
+                }
+                IGNORE_MISSING_NAME => {
+                    ignore_missing = yaml_bool(v, IGNORE_MISSING_NAME)?;
+                }
+                _ => {}
+            }
+        }
+
+        if fields.is_empty() {
+            return RegexNoValidFieldSnafu {
+                processor: PROCESSOR_KV,
+            }
+            .fail();
+        }
+
+        Ok(KvProcessor {
+            fields,
+            field_split: Regex::new(&field_split).context(RegexSnafu {
+                pattern: field_split.clone(),
+            })?,
+            value_split: Regex::new(&value_split).context(RegexSnafu {
+                pattern: value_split.clone(),
+            })?,
+            trim_key,
+            trim_value,
+            include_keys,
+            exclude_keys,
+            prefix,
+            strip_quotes,
+            ignore_missing,
+        })
+    }
+}
+
+impl Processor for KvProcessor {
+    fn kind(&self) -> &str {
+        PROCESSOR_KV
+    }
+
+    fn ignore_missing(&self) -> bool {
+        self.ignore_missing
+    }
+
+    fn exec_mut(&self, mut val: VrlValue) -> Result<VrlValue> {
+        for field in self.fields.iter() {
+            let index = field.input_field();
+            let val = val.as_object_mut().context(ValueMustBeMapSnafu)?;
+            match val.get(index) {
+                Some(VrlValue::Bytes(s)) => {
+                    let result = self.process(String::from_utf8_lossy(s).as_ref());
+                    val.extend(result);
+                }
+                Some(VrlValue::Null) | None => {
+                    if !self.ignore_missing {
+                        return ProcessorMissingFieldSnafu {
+                            processor: self.kind(),
+                            field: field.input_field(),
+                        }
+                        .fail();
+                    }
+                }
+                Some(v) => {
+                    return ProcessorExpectStringSnafu {
+                        processor: self.kind(),
+                        v: v.clone(),
+                    }
+                    .fail();
+                }
+            }
+        }
+
+        Ok(val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn new_processor(yaml: &str) -> KvProcessor {
+        let yaml = yaml_rust::YamlLoader::load_from_str(yaml)
+            .unwrap()
+            .pop()
+            .unwrap();
+        KvProcessor::try_from(yaml.as_hash().unwrap()).unwrap()
+    }
+
+    fn to_map(pairs: &[(&str, &str)]) -> BTreeMap<KeyString, VrlValue> {
+        pairs
+            .iter()
+            .map(|(k, v)| {
+                (
+                    KeyString::from(*k),
+                    VrlValue::Bytes(Bytes::from(v.to_string())),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_logfmt() {
+        let processor = new_processor("field: message");
+
+        let result = processor.process(
+            r#"level=info msg="user logged in" path=/login user="a \"quoted\" name" empty= bare"#,
+        );
+        assert_eq!(
+            to_map(&[
+                ("level", "info"),
+                ("msg", "user logged in"),
+                ("path", "/login"),
+                ("user", r#"a "quoted" name"#),
+                ("empty", ""),
+            ]),
+            result
+        );
+    }
+
+    #[test]
+    fn test_query_string() {
+        let processor = new_processor(
+            r#"field: query
+field_split: "&"
+value_split: "="
+exclude_keys: [token]
+prefix: "q_""#,
+        );
+
+        let result = processor.process("a=1&b=2&token=secret&a=3");
+        assert_eq!(to_map(&[("q_a", "3"), ("q_b", "2")]), result);
+    }
+
+    #[test]
+    fn test_trim_and_include() {
+        let processor = new_processor(
+            r#"field: message
+field_split: ",\\s*"
+value_split: ":"
+trim_key: "[]"
+trim_value: "<>"
+include_keys: [host, port]
+strip_quotes: false"#,
+        );
+
+        let result = processor.process(r#"[host]:<localhost>, [port]:<4000>, [user]:"root""#);
+        assert_eq!(to_map(&[("host", "localhost"), ("port", "4000")]), result);
+    }
+}
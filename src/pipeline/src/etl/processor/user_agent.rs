@@ -0,0 +1,500 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::BTreeMap;
+
+use lazy_static::lazy_static;
+use regex::{Captures, Regex};
+use snafu::{OptionExt, ResultExt};
+use vrl::prelude::Bytes;
+use vrl::value::{KeyString, Value as VrlValue};
+
+use crate::error::{
+    Error, FieldMustBeTypeSnafu, KeyMustBeStringSnafu, ProcessorExpectStringSnafu,
+    ProcessorMissingFieldSnafu, RegexNoValidFieldSnafu, RegexSnafu, Result,
+    UserAgentInvalidPropertySnafu, ValueMustBeMapSnafu,
+};
+use crate::etl::field::Fields;
+use crate::etl::processor::{
+    FIELD_NAME, FIELDS_NAME, IGNORE_MISSING_NAME, Processor, yaml_bool, yaml_new_field,
+    yaml_new_fields, yaml_strings,
+};
+
+pub(crate) const PROCESSOR_USER_AGENT: &str = "user_agent";
+
+const PROPERTIES_NAME: &str = "properties";
+
+const REGEXES_YAML: &str = include_str!("user_agent/regexes.yaml");
+
+const OTHER: &str = "Other";
+
+lazy_static! {
+    static ref DATABASE: UserAgentDatabase =
+        UserAgentDatabase::load(REGEXES_YAML).expect("invalid embedded user agent regexes");
+    static ref REPLACEMENT_GROUP_REGEX: Regex = Regex::new(r"\$(\d)").unwrap();
+}
+
+/// A parser entry of the regex database.
+///
+/// Each of the (up to four) extracted parts is taken from its replacement if present,
+/// otherwise from the capture group with the same position.
+#[derive(Debug)]
+struct UserAgentParser {
+    regex: Regex,
+    replacements: [Option<String>; 4],
+}
+
+impl UserAgentParser {
+    fn parse(&self, val: &str) -> Option<[Option<String>; 4]> {
+        let caps = self.regex.captures(val)?;
+        let mut parts: [Option<String>; 4] = Default::default();
+        for (i, part) in parts.iter_mut().enumerate() {
+            let value = match &self.replacements[i] {
+                Some(replacement) => substitute_groups(replacement, &caps),
+                None => caps
+                    .get(i + 1)
+                    .map(|m| m.as_str().trim().to_string())
+                    .unwrap_or_default(),
+            };
+            if !value.is_empty() {
+                *part = Some(value);
+            }
+        }
+        Some(parts)
+    }
+}
+
+/// Replaces `$N` in `replacement` with the N-th capture group.
+fn substitute_groups(replacement: &str, caps: &Captures) -> String {
+    REPLACEMENT_GROUP_REGEX
+        .replace_all(replacement, |c: &Captures| {
+            let index = c[1].parse::<usize>().unwrap_or_default();
+            caps.get(index)
+                .map(|m| m.as_str().to_string())
+                .unwrap_or_default()
+        })
+        .trim()
+        .to_string()
+}
+
+/// The embedded user agent regex database, see `user_agent/regexes.yaml`.
+#[derive(Debug)]
+struct UserAgentDatabase {
+    user_agents: Vec<UserAgentParser>,
+    os: Vec<UserAgentParser>,
+    devices: Vec<UserAgentParser>,
+}
+
+impl UserAgentDatabase {
+    fn load(yaml: &str) -> Result<Self> {
+        let docs = yaml_rust::YamlLoader::load_from_str(yaml)
+            .ok()
+            .and_then(|mut docs| docs.pop())
+            .context(FieldMustBeTypeSnafu {
+                field: PROCESSOR_USER_AGENT,
+                ty: "yaml document",
+            })?;
+
+        Ok(UserAgentDatabase {
+            user_agents: Self::load_parsers(
+                &docs,
+                "user_agent_parsers",
+                [
+                    "family_replacement",
+                    "v1_replacement",
+                    "v2_replacement",
+                    "v3_replacement",
+                ],
+            )?,
+            os: Self::load_parsers(
+                &docs,
+                "os_parsers",
+                [
+                    "os_replacement",
+                    "os_v1_replacement",
+                    "os_v2_replacement",
+                    "os_v3_replacement",
+                ],
+            )?,
+            devices: Self::load_parsers(
+                &docs,
+                "device_parsers",
+                ["device_replacement", "", "", ""],
+            )?,
+        })
+    }
+
+    fn load_parsers(
+        docs: &yaml_rust::Yaml,
+        section: &str,
+        replacement_keys: [&str; 4],
+    ) -> Result<Vec<UserAgentParser>> {
+        let entries = docs[section].as_vec().context(FieldMustBeTypeSnafu {
+            field: section,
+            ty: "list",
+        })?;
+
+        entries
+            .iter()
+            .map(|entry| {
+                let pattern = entry["regex"].as_str().context(FieldMustBeTypeSnafu {
+                    field: "regex",
+                    ty: "string",
+                })?;
+                let regex = Regex::new(pattern).context(RegexSnafu { pattern })?;
+                let replacements =
+                    replacement_keys.map(|key| entry[key].as_str().map(|s| s.to_string()));
+                Ok(UserAgentParser {
+                    regex,
+                    replacements,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The fields extracted from a user agent string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Property {
+    Browser,
+    BrowserVersion,
+    Os,
+    OsVersion,
+    Device,
+}
+
+impl Property {
+    const ALL: [Property; 5] = [
+        Property::Browser,
+        Property::BrowserVersion,
+        Property::Os,
+        Property::OsVersion,
+        Property::Device,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            Property::Browser => "browser",
+            Property::BrowserVersion => "browser_version",
+            Property::Os => "os",
+            Property::OsVersion => "os_version",
+            Property::Device => "device",
+        }
+    }
+}
+
+impl std::str::FromStr for Property {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Property::ALL
+            .into_iter()
+            .find(|p| p.name() == s)
+            .context(UserAgentInvalidPropertySnafu { property: s })
+    }
+}
+
+/// Joins the present version parts with dots, e.g. `120.0.1`.
+fn join_version(parts: &[Option<String>]) -> Option<String> {
+    let version = parts.iter().map_while(|p| p.as_deref()).collect::<Vec<_>>();
+    (!version.is_empty()).then(|| version.join("."))
+}
+
+/// Parses user agent strings into browser, OS and device fields.
+///
+/// The output fields are named `{field}_browser`, `{field}_browser_version`,
+/// `{field}_os`, `{field}_os_version` and `{field}_device`, `properties` selects a
+/// subset of them. Unrecognized browsers, operating systems and devices are reported
+/// as `Other`, and missing versions are left unset.
+#[derive(Debug)]
+pub struct UserAgentProcessor {
+    fields: Fields,
+    properties: Vec<Property>,
+    ignore_missing: bool,
+}
+
+impl UserAgentProcessor {
+    fn process(&self, prefix: &str, val: &str) -> BTreeMap<KeyString, VrlValue> {
+        let user_agent = first_match(&DATABASE.user_agents, val);
+        let os = first_match(&DATABASE.os, val);
+        let device = first_match(&DATABASE.devices, val);
+
+        let mut result = BTreeMap::new();
+        for property in &self.properties {
+            let value = match property {
+                Property::Browser => Some(family(&user_agent)),
+                Property::BrowserVersion => user_agent.as_ref().and_then(|p| join_version(&p[1..])),
+                Property::Os => Some(family(&os)),
+                Property::OsVersion => os.as_ref().and_then(|p| join_version(&p[1..])),
+                Property::Device => Some(family(&device)),
+            };
+            if let Some(value) = value {
+                result.insert(
+                    KeyString::from(format!("{prefix}_{}", property.name())),
+                    VrlValue::Bytes(Bytes::from(value)),
+                );
+            }
+        }
+        result
+    }
+}
+
+fn first_match(parsers: &[UserAgentParser], val: &str) -> Option<[Option<String>; 4]> {
+    parsers.iter().find_map(|p| p.parse(val))
+}
+
+fn family(parts: &Option<[Option<String>; 4]>) -> String {
+    parts
+        .as_ref()
+        .and_then(|p| p[0].clone())
+        .unwrap_or_else(|| OTHER.to_string())
+}
+
+impl TryFrom<&yaml_rust::yaml::Hash> for UserAgentProcessor {
+    type Error = Error;
+
+    fn try_from(value: &yaml_rust::yaml::Hash) -> Result<Self> {
+        let mut fields = Fields::default();
+        let mut properties = Property::ALL.to_vec();
+        let mut ignore_missing = false;
+
+        for (k, v) in value.iter() {
+            let key = k
+                .as_str()
+                .with_context(|| KeyMustBeStringSnafu { k: k.clone() })?;
+            match key {
+                FIELD_NAME => {
+                    fields = Fields::one(yaml_new_field(v, FIELD_NAME)?);
+                }
+                FIELDS_NAME => {
+                    fields = yaml_new_fields(v, FIELDS_NAME)?;
+                }
+                PROPERTIES_NAME => {
+                    properties = yaml_strings(v, PROPERTIES_NAME)?
+                        .iter()
+                        .map(|p| p.parse())
+                        .collect::<Result<_>>()?;
+                }
+                IGNORE_MISSING_NAME => {
+                    ignore_missing = yaml_bool(v, IGNORE_MISSING_NAME)?;
+                }
+                _ => {}
+            }
+        }
+
+        if fields.is_empty() {
+            return RegexNoValidFieldSnafu {
+                processor: PROCESSOR_USER_AGENT,
+            }
+            .fail();
+        }
+
+        Ok(UserAgentProcessor {
+            fields,
+            properties,
+            ignore_missing,
+        })
+    }
+}
+
+impl Processor for UserAgentProcessor {
+    fn kind(&self) -> &str {
+        PROCESSOR_USER_AGENT
+    }
+
+    fn ignore_missing(&self) -> bool {
+        self.ignore_missing
+    }
+
+    fn exec_mut(&self, mut val: VrlValue) -> Result<VrlValue> {
+        for field in self.fields.iter() {
+            let index = field.input_field();
+            let prefix = field.target_or_input_field();
+            let val = val.as_object_mut().context(ValueMustBeMapSnafu)?;
+            match val.get(index) {
+                Some(VrlValue::Bytes(s)) => {
+                    let result = self.process(prefix, String::from_utf8_lossy(s).as_ref());
+                    val.extend(result);
+                }
+                Some(VrlValue::Null) | None => {
+                    if !self.ignore_missing {
+                        return ProcessorMissingFieldSnafu {
+                            processor: self.kind(),
+                            field: field.input_field(),
+                        }
+                        .fail();
+                    }
+                }
+                Some(v) => {
+                    return ProcessorExpectStringSnafu {
+                        processor: self.kind(),
+                        v: v.clone(),
+                    }
+                    .fail();
+                }
+            }
+        }
+
+        Ok(val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(ua: &str) -> Vec<(String, String)> {
+        let processor = UserAgentProcessor {
+            fields: Fields::default(),
+            properties: Property::ALL.to_vec(),
+            ignore_missing: false,
+        };
+        processor
+            .process("ua", ua)
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v.to_string_lossy().to_string()))
+            .collect()
+    }
+
+    fn expected(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+        let mut pairs = pairs
+            .iter()
+            .map(|(k, v)| (format!("ua_{k}"), v.to_string()))
+            .collect::<Vec<_>>();
+        pairs.sort();
+        pairs
+    }
+
+    #[test]
+    fn test_database_loads() {
+        assert!(!DATABASE.user_agents.is_empty());
+        assert!(!DATABASE.os.is_empty());
+        assert!(!DATABASE.devices.is_empty());
+    }
+
+    #[test]
+    fn test_desktop_browsers() {
+        assert_eq!(
+            expected(&[
+                ("browser", "Chrome"),
+                ("browser_version", "120.0.0"),
+                ("os", "Windows"),
+                ("os_version", "10"),
+                ("device", "Other"),
+            ]),
+            parse(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36"
+            )
+        );
+        assert_eq!(
+            expected(&[
+                ("browser", "Edge"),
+                ("browser_version", "120.0.2210"),
+                ("os", "Windows"),
+                ("os_version", "10"),
+                ("device", "Other"),
+            ]),
+            parse(
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36 Edg/120.0.2210.91"
+            )
+        );
+        assert_eq!(
+            expected(&[
+                ("browser", "Safari"),
+                ("browser_version", "17.1"),
+                ("os", "Mac OS X"),
+                ("os_version", "10.15.7"),
+                ("device", "Mac"),
+            ]),
+            parse(
+                "Mozilla/5.0 (Macintosh; Intel Mac OS X 10_15_7) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.1 Safari/605.1.15"
+            )
+        );
+        assert_eq!(
+            expected(&[
+                ("browser", "Firefox"),
+                ("browser_version", "121.0"),
+                ("os", "Ubuntu"),
+                ("device", "Other"),
+            ]),
+            parse("Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0")
+        );
+    }
+
+    #[test]
+    fn test_mobile_and_bots() {
+        assert_eq!(
+            expected(&[
+                ("browser", "Mobile Safari"),
+                ("browser_version", "17.2"),
+                ("os", "iOS"),
+                ("os_version", "17.2"),
+                ("device", "iPhone"),
+            ]),
+            parse(
+                "Mozilla/5.0 (iPhone; CPU iPhone OS 17_2 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.2 Mobile/15E148 Safari/604.1"
+            )
+        );
+        assert_eq!(
+            expected(&[
+                ("browser", "Chrome Mobile"),
+                ("browser_version", "120.0.6099"),
+                ("os", "Android"),
+                ("os_version", "14"),
+                ("device", "Pixel 8"),
+            ]),
+            parse(
+                "Mozilla/5.0 (Linux; Android 14; Pixel 8) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.6099.144 Mobile Safari/537.36"
+            )
+        );
+        assert_eq!(
+            expected(&[
+                ("browser", "Googlebot"),
+                ("browser_version", "2.1"),
+                ("os", "Other"),
+                ("device", "Spider"),
+            ]),
+            parse("Mozilla/5.0 (compatible; Googlebot/2.1; +http://www.google.com/bot.html)")
+        );
+        assert_eq!(
+            expected(&[
+                ("browser", "curl"),
+                ("browser_version", "8.4.0"),
+                ("os", "Other"),
+                ("device", "Other"),
+            ]),
+            parse("curl/8.4.0")
+        );
+    }
+
+    #[test]
+    fn test_properties() {
+        let yaml =
+            yaml_rust::YamlLoader::load_from_str("{field: agent, properties: [browser, os]}")
+                .unwrap()
+                .pop()
+                .unwrap();
+        let processor = UserAgentProcessor::try_from(yaml.as_hash().unwrap()).unwrap();
+        let result = processor.process("agent", "curl/8.4.0");
+        assert_eq!(2, result.len());
+        assert!(result.contains_key("agent_browser"));
+        assert!(result.contains_key("agent_os"));
+
+        let yaml = yaml_rust::YamlLoader::load_from_str("{field: agent, properties: [engine]}")
+            .unwrap()
+            .pop()
+            .unwrap();
+        assert!(UserAgentProcessor::try_from(yaml.as_hash().unwrap()).is_err());
+    }
+}
@@ -23,6 +23,7 @@ mod tablesuffix;
 
 pub use etl::ctx_req::{ContextOpt, ContextReq};
 pub use etl::processor::Processor;
+pub use etl::processor::geoip::set_geoip_database_dir;
 pub use etl::transform::GreptimeTransformer;
 pub use etl::transform::transformer::greptime::{GreptimePipelineParams, SchemaInfo};
 pub use etl::transform::transformer::identity_pipeline;
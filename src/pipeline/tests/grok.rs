@@ -0,0 +1,104 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use common_query::prelude::greptime_timestamp;
+use greptime_proto::v1::value::ValueData::{I64Value, StringValue};
+use greptime_proto::v1::{ColumnDataType, SemanticType};
+
+#[test]
+fn test_grok_apache_log() {
+    let input_value_str = r#"
+    [
+      {
+        "message": "127.0.0.1 - frank [10/Oct/2000:13:55:36 -0700] \"GET /apache_pb.gif HTTP/1.0\" 200 2326"
+      }
+    ]
+"#;
+
+    let pipeline_yaml = r#"
+processors:
+  - grok:
+      field: message
+      patterns:
+        - "%{COMMONAPACHELOG_TYPED}"
+      pattern_definitions:
+        COMMONAPACHELOG_TYPED: '%{IPORHOST:clientip} %{HTTPDUSER:ident} %{HTTPDUSER:auth} \[%{HTTPDATE:timestamp}\] "%{WORD:verb} %{NOTSPACE:request} HTTP/%{NUMBER:httpversion}" %{NUMBER:response:int} %{NUMBER:bytes:int}'
+
+transform:
+  - fields:
+      - clientip
+      - verb
+      - request
+    type: string
+  - fields:
+      - response
+      - bytes
+    type: int64
+"#;
+
+    let output = common::parse_and_exec(input_value_str, pipeline_yaml);
+
+    let expected_schema = vec![
+        common::make_column_schema(
+            "clientip".to_string(),
+            ColumnDataType::String,
+            SemanticType::Field,
+        ),
+        common::make_column_schema(
+            "verb".to_string(),
+            ColumnDataType::String,
+            SemanticType::Field,
+        ),
+        common::make_column_schema(
+            "request".to_string(),
+            ColumnDataType::String,
+            SemanticType::Field,
+        ),
+        common::make_column_schema(
+            "response".to_string(),
+            ColumnDataType::Int64,
+            SemanticType::Field,
+        ),
+        common::make_column_schema(
+            "bytes".to_string(),
+            ColumnDataType::Int64,
+            SemanticType::Field,
+        ),
+        common::make_column_schema(
+            greptime_timestamp().to_string(),
+            ColumnDataType::TimestampNanosecond,
+            SemanticType::Timestamp,
+        ),
+    ];
+    assert_eq!(output.schema, expected_schema);
+
+    let values = output.rows[0]
+        .values
+        .iter()
+        .take(5)
+        .map(|v| v.value_data.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![
+            Some(StringValue("127.0.0.1".to_string())),
+            Some(StringValue("GET".to_string())),
+            Some(StringValue("/apache_pb.gif".to_string())),
+            Some(I64Value(200)),
+            Some(I64Value(2326)),
+        ]
+    );
+}
@@ -0,0 +1,85 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+mod common;
+
+use common_query::prelude::greptime_timestamp;
+use greptime_proto::v1::value::ValueData::StringValue;
+use greptime_proto::v1::{ColumnDataType, SemanticType};
+
+#[test]
+fn test_kv_logfmt() {
+    let input_value_str = r#"
+    [
+      {
+        "line": "level=warn msg=\"disk almost full\" disk=/dev/sda1",
+        "agent": "Mozilla/5.0 (X11; Ubuntu; Linux x86_64; rv:121.0) Gecko/20100101 Firefox/121.0"
+      }
+    ]
+"#;
+
+    let pipeline_yaml = r#"
+processors:
+  - kv:
+      field: line
+      exclude_keys: [disk]
+      prefix: "log_"
+  - user_agent:
+      field: agent, ua
+      properties: [browser, os]
+
+transform:
+  - fields:
+      - log_level
+      - log_msg
+      - ua_browser
+      - ua_os
+    type: string
+"#;
+
+    let output = common::parse_and_exec(input_value_str, pipeline_yaml);
+
+    let expected_schema = ["log_level", "log_msg", "ua_browser", "ua_os"]
+        .into_iter()
+        .map(|name| {
+            common::make_column_schema(
+                name.to_string(),
+                ColumnDataType::String,
+                SemanticType::Field,
+            )
+        })
+        .chain(std::iter::once(common::make_column_schema(
+            greptime_timestamp().to_string(),
+            ColumnDataType::TimestampNanosecond,
+            SemanticType::Timestamp,
+        )))
+        .collect::<Vec<_>>();
+    assert_eq!(output.schema, expected_schema);
+
+    let values = output.rows[0]
+        .values
+        .iter()
+        .take(4)
+        .map(|v| v.value_data.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        values,
+        vec![
+            Some(StringValue("warn".to_string())),
+            Some(StringValue("disk almost full".to_string())),
+            Some(StringValue("Firefox".to_string())),
+            Some(StringValue("Ubuntu".to_string())),
+        ]
+    );
+}
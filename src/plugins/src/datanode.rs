@@ -12,10 +12,14 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+use std::sync::Arc;
+
 use common_base::Plugins;
+use datanode::compactor::RemoteCompactionScheduler;
 use datanode::config::DatanodeOptions;
 use datanode::datanode::{Datanode, DatanodeBuilder};
 use datanode::error::Result;
+use mito2::schedule::remote_job_scheduler::RemoteJobSchedulerRef;
 
 use crate::options::PluginOptions;
 
@@ -27,6 +31,11 @@ pub async fn setup_datanode_plugins_pre_build(
     plugin_options: &[PluginOptions],
     dn_opts: &DatanodeOptions,
 ) -> Result<()> {
+    if dn_opts.remote_compaction.is_enabled() && plugins.get::<RemoteJobSchedulerRef>().is_none() {
+        plugins.insert::<RemoteJobSchedulerRef>(Arc::new(RemoteCompactionScheduler::try_new(
+            &dn_opts.remote_compaction,
+        )?));
+    }
     Ok(())
 }
 
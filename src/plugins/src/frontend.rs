@@ -14,14 +14,18 @@
 
 use std::sync::Arc;
 
-use auth::{DefaultPermissionChecker, PermissionCheckerRef, UserProviderRef};
+use auth::{
+    DefaultPermissionChecker, ManagedPermissionChecker, ManagedUserProvider, PermissionCheckerRef,
+    UserProviderRef,
+};
 use common_base::Plugins;
-use common_meta::cache::CacheRegistryBuilder;
+use common_meta::cache::{CacheRegistryBuilder, UserCacheRef};
 use frontend::error::{IllegalAuthConfigSnafu, Result};
 use frontend::frontend::FrontendOptions;
 use frontend::heartbeat::FrontendHeartbeatExtensions;
 use frontend::instance::Instance;
 use frontend::instance::builder::FrontendBuilder;
+use frontend::managed_user::UserCacheSource;
 use snafu::ResultExt;
 
 use crate::options::PluginOptions;
@@ -60,10 +64,25 @@ pub async fn setup_frontend_plugins_pre_build(
 /// This is where "feature plugins" are registered — plugins that consume builder context
 /// (e.g., `KvBackendRef`, `CatalogManagerRef`) to construct themselves.
 pub async fn setup_frontend_plugins_post_build(
-    _plugins: &mut Plugins,
+    plugins: &mut Plugins,
     _plugin_options: &[PluginOptions],
-    _builder: &FrontendBuilder,
+    builder: &FrontendBuilder,
 ) -> Result<()> {
+    // Users created by `CREATE USER` take effect only when authentication is enabled.
+    if let Some(user_provider) = plugins.get::<UserProviderRef>()
+        && let Some(user_cache) = builder.layered_cache_registry().get::<UserCacheRef>()
+    {
+        let source = Arc::new(UserCacheSource::new(user_cache));
+        plugins
+            .insert::<UserProviderRef>(Arc::new(ManagedUserProvider::new(source, user_provider)));
+        let permission_checker = plugins
+            .get::<PermissionCheckerRef>()
+            .unwrap_or_else(DefaultPermissionChecker::arc);
+        plugins.insert::<PermissionCheckerRef>(ManagedPermissionChecker::arc(
+            permission_checker,
+            builder.options().admin_users.clone(),
+        ));
+    }
     Ok(())
 }
 
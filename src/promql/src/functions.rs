@@ -27,6 +27,7 @@ mod resets;
 mod round;
 #[cfg(test)]
 mod test_util;
+mod vector_match;
 
 pub use aggr_over_time::{
     AbsentOverTime, AvgOverTime, CountOverTime, LastOverTime, MaxOverTime, MinOverTime,
@@ -61,6 +62,7 @@ pub use quantile::QuantileOverTime;
 pub use quantile_aggr::{QUANTILE_NAME, quantile_udaf};
 pub use resets::Resets;
 pub use round::Round;
+pub use vector_match::{MatchGroupCheck, UniqueMatchGroup};
 
 use crate::range_array::RangeArray;
 
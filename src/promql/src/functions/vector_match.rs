@@ -0,0 +1,217 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime checks for PromQL vector matching.
+
+use std::any::Any;
+use std::sync::Arc;
+
+use datafusion::arrow::array::{Array, AsArray, BooleanArray};
+use datafusion::arrow::compute::cast;
+use datafusion::arrow::datatypes::{DataType, Int64Type};
+use datafusion::common::{DataFusionError, Result as DfResult};
+use datafusion::logical_expr::{ScalarUDF, Volatility};
+use datafusion::physical_plan::ColumnarValue;
+use datafusion_common::ScalarValue;
+use datafusion_expr::{ScalarFunctionArgs, ScalarUDFImpl, Signature};
+
+/// What a [UniqueMatchGroup] asserts to be unique, which decides its error message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MatchGroupCheck {
+    /// Series on the given side (`"left"` or `"right"`) of the operation, like the
+    /// "one" side of a many-to-one or one-to-many match.
+    Side(&'static str),
+    /// Output series of a many-to-one or one-to-many match.
+    GroupOutput,
+}
+
+/// Asserts that every match group of a vector match contains at most one series.
+///
+/// The first argument is the number of rows sharing the same match group and timestamp,
+/// the remaining arguments are the values of the matching labels (in the same order as
+/// `labels`). It returns `true` for every row, or fails the query with the same error
+/// Prometheus reports when a match group is not unique.
+#[derive(Debug, PartialEq, Eq, Hash)]
+pub struct UniqueMatchGroup {
+    signature: Signature,
+    check: MatchGroupCheck,
+    labels: Vec<String>,
+}
+
+impl UniqueMatchGroup {
+    pub const fn name() -> &'static str {
+        "prom_unique_match_group"
+    }
+
+    /// Creates the `check` of the binary operation, matching on `labels`.
+    pub fn scalar_udf(check: MatchGroupCheck, labels: Vec<String>) -> ScalarUDF {
+        ScalarUDF::new_from_impl(Self {
+            signature: Signature::variadic_any(Volatility::Volatile),
+            check,
+            labels,
+        })
+    }
+
+    fn format_match_group(&self, args: &[ColumnarValue], row: usize) -> DfResult<String> {
+        let mut pairs = Vec::with_capacity(self.labels.len());
+        for (label, arg) in self.labels.iter().zip(args) {
+            let value = match arg {
+                ColumnarValue::Array(array) => ScalarValue::try_from_array(array, row)?,
+                ColumnarValue::Scalar(value) => value.clone(),
+            };
+            if value.is_null() {
+                continue;
+            }
+            let value = match value {
+                ScalarValue::Utf8(Some(v))
+                | ScalarValue::LargeUtf8(Some(v))
+                | ScalarValue::Utf8View(Some(v)) => v,
+                other => other.to_string(),
+            };
+            pairs.push(format!("{label}=\"{value}\""));
+        }
+        Ok(format!("{{{}}}", pairs.join(", ")))
+    }
+}
+
+impl ScalarUDFImpl for UniqueMatchGroup {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn name(&self) -> &str {
+        Self::name()
+    }
+
+    fn signature(&self) -> &Signature {
+        &self.signature
+    }
+
+    fn return_type(&self, _arg_types: &[DataType]) -> DfResult<DataType> {
+        Ok(DataType::Boolean)
+    }
+
+    fn invoke_with_args(&self, args: ScalarFunctionArgs) -> DfResult<ColumnarValue> {
+        let Some((count, labels)) = args.args.split_first() else {
+            return Err(DataFusionError::Plan(format!(
+                "{} function should have at least 1 input",
+                Self::name()
+            )));
+        };
+        if labels.len() != self.labels.len() {
+            return Err(DataFusionError::Plan(format!(
+                "{} function expects {} label inputs, found {}",
+                Self::name(),
+                self.labels.len(),
+                labels.len()
+            )));
+        }
+
+        let counts = count.to_array(args.number_rows)?;
+        let counts = cast(&counts, &DataType::Int64)?;
+        let counts = counts.as_primitive::<Int64Type>();
+        if let Some(row) =
+            (0..counts.len()).find(|row| counts.is_valid(*row) && counts.value(*row) > 1)
+        {
+            let message = match self.check {
+                MatchGroupCheck::Side(side) => format!(
+                    "found duplicate series for the match group {} on the {side} hand-side of the operation; many-to-many matching not allowed: matching labels must be unique on one side",
+                    self.format_match_group(labels, row)?,
+                ),
+                MatchGroupCheck::GroupOutput => {
+                    "multiple matches for labels: grouping labels must ensure unique matches"
+                        .to_string()
+                }
+            };
+            return Err(DataFusionError::Execution(message));
+        }
+
+        Ok(ColumnarValue::Array(Arc::new(BooleanArray::from(vec![
+            true;
+            args.number_rows
+        ]))))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use datafusion::arrow::array::{Int64Array, StringArray};
+    use datafusion::arrow::datatypes::Field;
+    use datafusion_common::config::ConfigOptions;
+
+    use super::*;
+
+    fn invoke(
+        check: MatchGroupCheck,
+        counts: Vec<i64>,
+        instances: Vec<&str>,
+    ) -> DfResult<ColumnarValue> {
+        let udf = UniqueMatchGroup::scalar_udf(check, vec!["instance".to_string()]);
+        let number_rows = counts.len();
+        let args = vec![
+            ColumnarValue::Array(Arc::new(Int64Array::from(counts))),
+            ColumnarValue::Array(Arc::new(StringArray::from(instances))),
+        ];
+        let arg_fields = vec![
+            Arc::new(Field::new("count", DataType::Int64, false)),
+            Arc::new(Field::new("instance", DataType::Utf8, true)),
+        ];
+        udf.invoke_with_args(ScalarFunctionArgs {
+            args,
+            arg_fields,
+            number_rows,
+            return_field: Arc::new(Field::new("x", DataType::Boolean, false)),
+            config_options: Arc::new(ConfigOptions::default()),
+        })
+    }
+
+    #[test]
+    fn unique_match_group_passes() {
+        let result = invoke(MatchGroupCheck::Side("right"), vec![1, 1], vec!["a", "b"]).unwrap();
+        let ColumnarValue::Array(array) = result else {
+            panic!("expected array");
+        };
+        assert_eq!(array.len(), 2);
+        assert_eq!(array.as_boolean(), &BooleanArray::from(vec![true, true]));
+    }
+
+    #[test]
+    fn duplicate_match_group_fails() {
+        let err = invoke(
+            MatchGroupCheck::Side("right"),
+            vec![1, 2, 2],
+            vec!["a", "b", "b"],
+        )
+        .unwrap_err();
+        let message = err.to_string();
+        assert!(
+            message.contains(
+                "found duplicate series for the match group {instance=\"b\"} on the right hand-side of the operation"
+            ),
+            "{message}"
+        );
+
+        let err = invoke(
+            MatchGroupCheck::GroupOutput,
+            vec![1, 2, 2],
+            vec!["a", "b", "b"],
+        )
+        .unwrap_err();
+        let message = err.to_string();
+        assert!(
+            message.contains("grouping labels must ensure unique matches"),
+            "{message}"
+        );
+    }
+}
@@ -39,7 +39,7 @@ use datafusion::physical_plan::analyze::AnalyzeExec;
 use datafusion::physical_plan::coalesce_partitions::CoalescePartitionsExec;
 use datafusion_common::ResolvedTableReference;
 use datafusion_expr::{
-    AggregateUDF, DmlStatement, LogicalPlan as DfLogicalPlan, LogicalPlan, WindowUDF, WriteOp,
+    AggregateUDF, DmlStatement, Expr, LogicalPlan as DfLogicalPlan, LogicalPlan, WindowUDF, WriteOp,
 };
 use datatypes::prelude::VectorRef;
 use datatypes::schema::Schema;
@@ -47,6 +47,7 @@ use futures_util::StreamExt;
 use session::context::QueryContextRef;
 use snafu::{OptionExt, ResultExt, ensure};
 use sqlparser::ast::AnalyzeFormat;
+use store_api::mito_engine_options::{APPEND_MODE_KEY, MERGE_MODE_KEY};
 use table::TableRef;
 use table::requests::{DeleteRequest, InsertRequest};
 use table::table::scan::{REGION_SCAN_EXEC_NAME, RegionScanExec};
@@ -58,7 +59,7 @@ use crate::dist_plan::{
     DistPlannerOptions, MergeScanLogicalPlan, RemoteDynFilterReceiverInjectorRef,
 };
 use crate::error::{
-    CatalogSnafu, CreateRecordBatchSnafu, MissingTableMutationHandlerSnafu,
+    CatalogSnafu, CreateRecordBatchSnafu, InvalidUpdateSnafu, MissingTableMutationHandlerSnafu,
     MissingTimestampColumnSnafu, QueryExecutionSnafu, Result, TableMutationSnafu,
     TableNotFoundSnafu, TableReadOnlySnafu, UnsupportedExprSnafu,
 };
@@ -138,6 +139,73 @@ fn query_stat_counters(plan: &Arc<dyn ExecutionPlan>) -> Option<RegionQueryStatC
     counters
 }
 
+/// Checks that an UPDATE plan can be executed as an overwriting insert into `table`.
+///
+/// Rows are identified by the primary key and time index, so these columns can't be
+/// assigned. Append-only tables don't deduplicate rows and are rejected as well.
+///
+/// Returns whether the rewritten rows must replace the existing rows. This is the
+/// case for `last_non_null` tables, where a newer row with a `NULL` field would
+/// otherwise not overwrite the old value.
+fn validate_update(
+    table_name: &ResolvedTableReference,
+    table: &TableRef,
+    input: &LogicalPlan,
+) -> Result<bool> {
+    let table_info = table.table_info();
+    let options = &table_info.meta.options.extra_options;
+    ensure!(
+        !options
+            .get(APPEND_MODE_KEY)
+            .is_some_and(|v| v.eq_ignore_ascii_case("true")),
+        InvalidUpdateSnafu {
+            table: table_name.to_string(),
+            reason: "append mode tables don't support UPDATE",
+        }
+    );
+    ensure!(
+        !options
+            .get(MERGE_MODE_KEY)
+            .is_some_and(|mode| mode.eq_ignore_ascii_case("aggregate")),
+        InvalidUpdateSnafu {
+            table: table_name.to_string(),
+            reason: "aggregate merge mode tables don't support UPDATE",
+        }
+    );
+
+    let LogicalPlan::Projection(projection) = input else {
+        return InvalidUpdateSnafu {
+            table: table_name.to_string(),
+            reason: format!("unexpected input plan: {}", input.display()),
+        }
+        .fail();
+    };
+    let schema = table.schema();
+    let mut key_columns = table_info.meta.row_key_column_names().collect::<Vec<_>>();
+    if let Some(ts) = schema.timestamp_column() {
+        key_columns.push(&ts.name);
+    }
+    for expr in &projection.expr {
+        let (name, expr) = match expr {
+            Expr::Alias(alias) => (alias.name.clone(), alias.expr.as_ref()),
+            Expr::Column(column) => (column.name.clone(), expr),
+            expr => (expr.schema_name().to_string(), expr),
+        };
+        let assigned = !matches!(expr, Expr::Column(column) if column.name == name);
+        ensure!(
+            !assigned || !key_columns.contains(&&name),
+            InvalidUpdateSnafu {
+                table: table_name.to_string(),
+                reason: format!("can't update primary key or time index column `{name}`"),
+            }
+        );
+    }
+
+    Ok(options
+        .get(MERGE_MODE_KEY)
+        .is_some_and(|mode| mode.eq_ignore_ascii_case("last_non_null")))
+}
+
 pub struct DatafusionQueryEngine {
     state: Arc<QueryEngineState>,
     plugins: Plugins,
@@ -187,7 +255,10 @@ impl DatafusionQueryEngine {
         query_ctx: QueryContextRef,
     ) -> Result<Output> {
         ensure!(
-            matches!(dml.op, WriteOp::Insert(_) | WriteOp::Delete),
+            matches!(
+                dml.op,
+                WriteOp::Insert(_) | WriteOp::Delete | WriteOp::Update
+            ),
             UnsupportedExprSnafu {
                 name: format!("DML op {}", dml.op),
             }
@@ -201,6 +272,12 @@ impl DatafusionQueryEngine {
         let default_schema = &query_ctx.current_schema();
         let table_name = dml.table_name.resolve(default_catalog, default_schema);
         let table = self.find_table(&table_name, &query_ctx).await?;
+        // Whether the rows rewritten by an UPDATE must replace the matched rows.
+        let replace_existing = if dml.op == WriteOp::Update {
+            validate_update(&table_name, &table, &dml.input)?
+        } else {
+            false
+        };
 
         let Output { data, meta } = self
             .exec_query_plan((*dml.input).clone(), query_ctx.clone())
@@ -236,6 +313,20 @@ impl DatafusionQueryEngine {
                         .delete(&table_name, &table, column_vectors, query_ctx.clone())
                         .await?;
                 }
+                WriteOp::Update => {
+                    // The rewritten rows share the primary key and time index with the
+                    // rows they replace, so the insert overwrites them.
+                    let output = if replace_existing {
+                        self.replace(&table_name, column_vectors, query_ctx.clone())
+                            .await?
+                    } else {
+                        self.insert(&table_name, column_vectors, query_ctx.clone())
+                            .await?
+                    };
+                    let (rows, cost) = output.extract_rows_and_cost();
+                    affected_rows += rows;
+                    insert_cost += cost;
+                }
                 _ => unreachable!("guarded by the 'ensure!' at the beginning"),
             }
         }
@@ -326,6 +417,39 @@ impl DatafusionQueryEngine {
             .context(TableMutationSnafu)
     }
 
+    /// Inserts rows replacing the existing rows with the same key instead of merging
+    /// with them.
+    #[tracing::instrument(skip_all)]
+    async fn replace(
+        &self,
+        table_name: &ResolvedTableReference,
+        column_vectors: HashMap<String, VectorRef>,
+        query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        let catalog_name = table_name.catalog.to_string();
+        let schema_name = table_name.schema.to_string();
+        let table_name = table_name.table.to_string();
+
+        ensure!(
+            !is_readonly_table(&schema_name, &table_name),
+            TableReadOnlySnafu { table: table_name }
+        );
+
+        let request = InsertRequest {
+            catalog_name,
+            schema_name,
+            table_name,
+            columns_values: column_vectors,
+        };
+
+        self.state
+            .table_mutation_handler()
+            .context(MissingTableMutationHandlerSnafu)?
+            .replace(request, query_ctx)
+            .await
+            .context(TableMutationSnafu)
+    }
+
     async fn find_table(
         &self,
         table_name: &ResolvedTableReference,
@@ -215,7 +215,7 @@ impl ExpressionChecker {
         result: &mut Vec<PartitionExpr>,
     ) {
         match operand {
-            Operand::Column(_) | Operand::Value(_) => {
+            Operand::Column(_) | Operand::Value(_) | Operand::HashBucket(_) => {
                 // This shouldn't happen in well-formed expressions
             }
             Operand::Expr(expr) => {
@@ -241,6 +241,8 @@ impl ExpressionChecker {
         match operand {
             Operand::Column(col) => partition_columns.contains(col),
             Operand::Value(_) => true, // Values are always safe
+            // Query predicates never contain hash buckets
+            Operand::HashBucket(_) => false,
             Operand::Expr(expr) => {
                 Self::expr_only_involves_partition_columns(expr, partition_columns)
             }
@@ -17,9 +17,11 @@
 use ahash::{HashMap, HashSet};
 use common_telemetry::debug;
 use datatypes::prelude::ConcreteDataType;
+use datatypes::types::cast::cast;
 use datatypes::value::{OrderedFloat, Value};
 use partition::collider::{AtomicExpr, Collider};
-use partition::expr::{Operand, PartitionExpr};
+use partition::expr::{Operand, PartitionExpr, RestrictedOp};
+use partition::hash::{HashBucket, as_hash_bucket_expr, as_hash_partition, prune_hash_buckets};
 use partition::manager::PartitionInfo;
 use partition::overlap::atomic_exprs_overlap;
 use store_api::storage::RegionId;
@@ -59,6 +61,23 @@ impl ConstraintPruner {
             return Ok(all_regions);
         };
 
+        if let Some(hash) = as_hash_partition(&all_partition_expressions) {
+            let candidate_regions = Self::prune_hash_regions(
+                query_expressions,
+                hash,
+                &all_partition_expressions,
+                &all_regions,
+                &column_datatypes,
+            );
+            debug!(
+                "Hash pruning (cost {}ms): {} -> {} regions",
+                start.elapsed().as_millis(),
+                partitions.len(),
+                candidate_regions.len()
+            );
+            return Ok(candidate_regions);
+        }
+
         // Create unified collider with both query and partition expressions for consistent normalization
         let mut all_expressions = query_expressions.to_vec();
         all_expressions.extend(all_partition_expressions.iter().cloned());
@@ -108,6 +127,82 @@ impl ConstraintPruner {
         Ok(candidate_regions.into_iter().collect())
     }
 
+    /// Prunes the regions of a hash partitioned table.
+    ///
+    /// Only equality constraints (`col = v`, or `col = v1 OR col = v2 ..`) on all hash
+    /// columns can prune regions, as the hash of a row is unknown otherwise.
+    fn prune_hash_regions(
+        query_expressions: &[PartitionExpr],
+        hash: &HashBucket,
+        partition_expressions: &[PartitionExpr],
+        all_regions: &[RegionId],
+        column_datatypes: &HashMap<String, ConcreteDataType>,
+    ) -> Vec<RegionId> {
+        let mut candidates = Vec::with_capacity(hash.columns.len());
+        for column in &hash.columns {
+            let Some(datatype) = column_datatypes.get(column) else {
+                debug!("Column {} not found from type set, skip pruning", column);
+                return all_regions.to_vec();
+            };
+
+            // Intersects the values allowed by each conjunct.
+            let mut column_values: Option<Vec<Value>> = None;
+            for expr in query_expressions {
+                let Some(values) = Self::collect_eq_values(expr, column) else {
+                    continue;
+                };
+                // Casts the values to the column type so they hash like the stored rows.
+                let Some(values) = values
+                    .into_iter()
+                    .map(|value| cast(value, datatype).ok())
+                    .collect::<Option<Vec<_>>>()
+                else {
+                    debug!("Values of column {} cannot be cast to {}", column, datatype);
+                    return all_regions.to_vec();
+                };
+                column_values = Some(match column_values {
+                    Some(prev) => prev.into_iter().filter(|v| values.contains(v)).collect(),
+                    None => values,
+                });
+            }
+
+            let Some(column_values) = column_values else {
+                return all_regions.to_vec();
+            };
+            candidates.push(column_values);
+        }
+
+        let Some(buckets) = prune_hash_buckets(hash, &candidates) else {
+            return all_regions.to_vec();
+        };
+        partition_expressions
+            .iter()
+            .zip(all_regions)
+            .filter_map(|(expr, region)| {
+                let (_, bucket) = as_hash_bucket_expr(expr)?;
+                buckets.contains(&bucket).then_some(*region)
+            })
+            .collect()
+    }
+
+    /// Returns the values of `column` if `expr` is `column = v` or an `OR` of them.
+    fn collect_eq_values(expr: &PartitionExpr, column: &str) -> Option<Vec<Value>> {
+        match (expr.lhs(), expr.op(), expr.rhs()) {
+            (Operand::Column(c), RestrictedOp::Eq, Operand::Value(v))
+            | (Operand::Value(v), RestrictedOp::Eq, Operand::Column(c))
+                if c == column =>
+            {
+                Some(vec![v.clone()])
+            }
+            (Operand::Expr(lhs), RestrictedOp::Or, Operand::Expr(rhs)) => {
+                let mut values = Self::collect_eq_values(lhs, column)?;
+                values.extend(Self::collect_eq_values(rhs, column)?);
+                Some(values)
+            }
+            _ => None,
+        }
+    }
+
     fn atomic_sets_overlap(query_atomics: &[&AtomicExpr], partition_atomic: &AtomicExpr) -> bool {
         query_atomics
             .iter()
@@ -584,4 +679,49 @@ mod tests {
         assert!(pruned.contains(&RegionId::new(1, 1)));
         assert!(pruned.contains(&RegionId::new(1, 2)));
     }
+
+    #[test]
+    fn test_hash_partition_pruning() {
+        let hash = HashBucket::new(vec!["host".to_string()], 4);
+        let partitions = hash
+            .partition_exprs()
+            .into_iter()
+            .enumerate()
+            .map(|(i, expr)| create_test_partition_info(i as u64, Some(expr)))
+            .collect::<Vec<_>>();
+        let column_datatypes =
+            HashMap::from_iter([("host".to_string(), ConcreteDataType::string_datatype())]);
+        let region_of = |host: &str| {
+            let bucket = hash.bucket_of([Value::from(host).as_value_ref()]);
+            RegionId::new(1, bucket)
+        };
+
+        // host = 'a'
+        let query_exprs = vec![col("host").eq(Value::from("a"))];
+        let pruned =
+            ConstraintPruner::prune_regions(&query_exprs, &partitions, column_datatypes.clone())
+                .unwrap();
+        assert_eq!(pruned, vec![region_of("a")]);
+
+        // host IN ('a', 'b')
+        let query_exprs = vec![PartitionExpr::new(
+            Operand::Expr(col("host").eq(Value::from("a"))),
+            RestrictedOp::Or,
+            Operand::Expr(col("host").eq(Value::from("b"))),
+        )];
+        let mut pruned =
+            ConstraintPruner::prune_regions(&query_exprs, &partitions, column_datatypes.clone())
+                .unwrap();
+        pruned.sort();
+        let mut expected = vec![region_of("a"), region_of("b")];
+        expected.sort();
+        expected.dedup();
+        assert_eq!(pruned, expected);
+
+        // Range constraints can't prune hash partitions.
+        let query_exprs = vec![col("host").gt(Value::from("a"))];
+        let pruned =
+            ConstraintPruner::prune_regions(&query_exprs, &partitions, column_datatypes).unwrap();
+        assert_eq!(pruned.len(), 4);
+    }
 }
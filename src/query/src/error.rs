@@ -36,6 +36,14 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid UPDATE on table {}: {}", table, reason))]
+    InvalidUpdate {
+        table: String,
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display("Unsupported show variable: {}", name))]
     UnsupportedVariable {
         name: String,
@@ -406,6 +414,7 @@ impl ErrorExt for Error {
                 StatusCode::InvalidSyntax
             }
             UnsupportedExpr { .. }
+            | InvalidUpdate { .. }
             | Unimplemented { .. }
             | UnknownTable { .. }
             | TimeIndexNotFound { .. }
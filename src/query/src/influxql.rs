@@ -0,0 +1,47 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Planning of InfluxQL queries served by the InfluxDB v1 `/query` API.
+
+pub mod ast;
+pub mod error;
+pub mod parser;
+pub mod planner;
+
+use datafusion_expr::LogicalPlan;
+
+/// A planned InfluxQL statement together with how to split its output into series.
+#[derive(Debug)]
+pub struct InfluxqlPlan {
+    pub plan: LogicalPlan,
+    pub layout: SeriesLayout,
+}
+
+/// Describes how the rows of an InfluxQL result are grouped into series.
+///
+/// Rows with the same name and tag values belong to the same series. The
+/// name column and the tag columns are not part of the series' columns.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SeriesLayout {
+    pub name: SeriesName,
+    pub tag_columns: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SeriesName {
+    /// All series share the same name.
+    Fixed(String),
+    /// The series name is read from the given column.
+    Column(String),
+}
@@ -0,0 +1,234 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The syntax tree of the supported InfluxQL statements.
+
+/// A parsed InfluxQL statement.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Statement {
+    Select(Box<SelectStatement>),
+    ShowMeasurements(ShowMeasurements),
+    ShowTagKeys(ShowTagKeys),
+    ShowTagValues(ShowTagValues),
+    ShowFieldKeys(ShowFieldKeys),
+}
+
+impl Statement {
+    /// Returns the measurement the statement reads from, if any.
+    pub fn measurement(&self) -> Option<&Measurement> {
+        match self {
+            Statement::Select(select) => Some(&select.from),
+            Statement::ShowMeasurements(_) => None,
+            Statement::ShowTagKeys(show) => show.from.as_ref(),
+            Statement::ShowTagValues(show) => show.from.as_ref(),
+            Statement::ShowFieldKeys(show) => show.from.as_ref(),
+        }
+    }
+
+    /// Returns the database specified by the `ON` clause, if any.
+    pub fn database(&self) -> Option<&str> {
+        match self {
+            Statement::Select(_) => None,
+            Statement::ShowMeasurements(show) => show.database.as_deref(),
+            Statement::ShowTagKeys(show) => show.database.as_deref(),
+            Statement::ShowTagValues(show) => show.database.as_deref(),
+            Statement::ShowFieldKeys(show) => show.database.as_deref(),
+        }
+    }
+
+    /// Returns the database the statement reads from, see [target_database].
+    pub fn target_database(&self) -> Option<&str> {
+        target_database(self.database(), self.measurement())
+    }
+}
+
+/// Returns the database a statement reads from: the database of the measurement,
+/// then the `ON` database. `None` means the current schema.
+pub fn target_database<'a>(
+    database: Option<&'a str>,
+    measurement: Option<&'a Measurement>,
+) -> Option<&'a str> {
+    measurement.and_then(|m| m.database.as_deref()).or(database)
+}
+
+/// A measurement reference like `cpu`, `"db"."rp"."cpu"` or `db..cpu`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Measurement {
+    pub database: Option<String>,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SelectStatement {
+    pub fields: Vec<Field>,
+    pub from: Measurement,
+    pub condition: Option<Expr>,
+    pub group_by: GroupBy,
+    pub fill: Fill,
+    /// Whether to order the results by time descending.
+    pub order_desc: bool,
+    pub limit: Limit,
+}
+
+/// A projected expression with an optional alias.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Field {
+    pub expr: Expr,
+    pub alias: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct GroupBy {
+    pub time: Option<TimeDimension>,
+    /// Whether to group by all tags, i.e. `GROUP BY *`.
+    pub all_tags: bool,
+    pub tags: Vec<String>,
+}
+
+/// The `time(interval[, offset])` dimension. All durations are in nanoseconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeDimension {
+    pub interval: i64,
+    pub offset: i64,
+}
+
+/// The `fill()` option of aggregate queries with `GROUP BY time()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum Fill {
+    #[default]
+    Null,
+    None,
+    Previous,
+    Linear,
+    Value(f64),
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Limit {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// `*`
+    Wildcard,
+    /// A reference to `time`, a tag or a field.
+    VarRef(String),
+    Call {
+        name: String,
+        args: Vec<Expr>,
+    },
+    Integer(i64),
+    Number(f64),
+    String(String),
+    Boolean(bool),
+    /// A duration in nanoseconds.
+    Duration(i64),
+    Regex(String),
+    Binary {
+        op: BinaryOp,
+        lhs: Box<Expr>,
+        rhs: Box<Expr>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Mod,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    RegexMatch,
+    RegexNotMatch,
+    And,
+    Or,
+}
+
+impl BinaryOp {
+    pub fn is_comparison(self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Eq
+                | BinaryOp::NotEq
+                | BinaryOp::Lt
+                | BinaryOp::LtEq
+                | BinaryOp::Gt
+                | BinaryOp::GtEq
+                | BinaryOp::RegexMatch
+                | BinaryOp::RegexNotMatch
+        )
+    }
+
+    /// Returns the operator with its operands swapped, e.g. `a < b` to `b > a`.
+    pub fn swap(self) -> Self {
+        match self {
+            BinaryOp::Lt => BinaryOp::Gt,
+            BinaryOp::LtEq => BinaryOp::GtEq,
+            BinaryOp::Gt => BinaryOp::Lt,
+            BinaryOp::GtEq => BinaryOp::LtEq,
+            op => op,
+        }
+    }
+}
+
+/// Matches tag keys or measurement names in `SHOW` statements.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum NameMatch {
+    Eq(String),
+    NotEq(String),
+    Regex(String),
+    NotRegex(String),
+    In(Vec<String>),
+}
+
+/// `SHOW MEASUREMENTS [ON db] [WITH MEASUREMENT ...] [LIMIT n] [OFFSET n]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowMeasurements {
+    pub database: Option<String>,
+    pub name: Option<NameMatch>,
+    pub limit: Limit,
+}
+
+/// `SHOW TAG KEYS [ON db] [FROM m] [LIMIT n] [OFFSET n]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowTagKeys {
+    pub database: Option<String>,
+    pub from: Option<Measurement>,
+    pub limit: Limit,
+}
+
+/// `SHOW TAG VALUES [ON db] [FROM m] WITH KEY ... [LIMIT n] [OFFSET n]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowTagValues {
+    pub database: Option<String>,
+    pub from: Option<Measurement>,
+    pub key: NameMatch,
+    pub limit: Limit,
+}
+
+/// `SHOW FIELD KEYS [ON db] [FROM m] [LIMIT n] [OFFSET n]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShowFieldKeys {
+    pub database: Option<String>,
+    pub from: Option<Measurement>,
+    pub limit: Limit,
+}
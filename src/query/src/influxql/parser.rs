@@ -0,0 +1,1069 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A hand-written parser of the InfluxQL subset understood by the `/query` API.
+
+use snafu::{OptionExt, ensure};
+
+use crate::influxql::ast::{
+    BinaryOp, Expr, Field, Fill, GroupBy, Limit, Measurement, NameMatch, SelectStatement,
+    ShowFieldKeys, ShowMeasurements, ShowTagKeys, ShowTagValues, Statement, TimeDimension,
+};
+use crate::influxql::error::{ParseSnafu, Result};
+
+const NANOS_PER_MICRO: i64 = 1_000;
+const NANOS_PER_MILLI: i64 = 1_000_000;
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+const NANOS_PER_MINUTE: i64 = 60 * NANOS_PER_SECOND;
+const NANOS_PER_HOUR: i64 = 60 * NANOS_PER_MINUTE;
+const NANOS_PER_DAY: i64 = 24 * NANOS_PER_HOUR;
+const NANOS_PER_WEEK: i64 = 7 * NANOS_PER_DAY;
+
+/// Parses `;` separated InfluxQL statements.
+pub fn parse_statements(query: &str) -> Result<Vec<Statement>> {
+    let tokens = tokenize(query)?;
+    let mut parser = Parser { tokens, pos: 0 };
+    let mut statements = vec![];
+    loop {
+        while parser.consume(&Token::Semicolon) {}
+        if parser.peek() == &Token::Eof {
+            break;
+        }
+        statements.push(parser.parse_statement()?);
+        if !parser.consume(&Token::Semicolon) {
+            parser.expect(&Token::Eof)?;
+        }
+    }
+    ensure!(
+        !statements.is_empty(),
+        ParseSnafu {
+            reason: "empty query",
+        }
+    );
+    Ok(statements)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    QuotedIdent(String),
+    Str(String),
+    Integer(i64),
+    Number(f64),
+    Duration(i64),
+    Regex(String),
+    LParen,
+    RParen,
+    Comma,
+    Dot,
+    Semicolon,
+    DoubleColon,
+    Star,
+    Plus,
+    Minus,
+    Slash,
+    Percent,
+    Eq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    EqRegex,
+    NotEqRegex,
+    Eof,
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>> {
+    let chars = query.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        if c.is_whitespace() {
+            i += 1;
+            continue;
+        }
+        // Regexes are only allowed as the right operand of `=~` and `!~`.
+        if c == '/' && matches!(tokens.last(), Some(Token::EqRegex | Token::NotEqRegex)) {
+            let (regex, next) = lex_regex(&chars, i + 1)?;
+            tokens.push(Token::Regex(regex));
+            i = next;
+            continue;
+        }
+        let next = chars.get(i + 1).copied();
+        let (token, len) = match (c, next) {
+            ('(', _) => (Token::LParen, 1),
+            (')', _) => (Token::RParen, 1),
+            (',', _) => (Token::Comma, 1),
+            ('.', _) => (Token::Dot, 1),
+            (';', _) => (Token::Semicolon, 1),
+            (':', Some(':')) => (Token::DoubleColon, 2),
+            ('*', _) => (Token::Star, 1),
+            ('+', _) => (Token::Plus, 1),
+            ('-', Some('-')) => {
+                // Skips the line comment.
+                while i < chars.len() && chars[i] != '\n' {
+                    i += 1;
+                }
+                continue;
+            }
+            ('-', _) => (Token::Minus, 1),
+            ('/', _) => (Token::Slash, 1),
+            ('%', _) => (Token::Percent, 1),
+            ('=', Some('~')) => (Token::EqRegex, 2),
+            ('=', _) => (Token::Eq, 1),
+            ('!', Some('~')) => (Token::NotEqRegex, 2),
+            ('!', Some('=')) => (Token::NotEq, 2),
+            ('<', Some('>')) => (Token::NotEq, 2),
+            ('<', Some('=')) => (Token::LtEq, 2),
+            ('<', _) => (Token::Lt, 1),
+            ('>', Some('=')) => (Token::GtEq, 2),
+            ('>', _) => (Token::Gt, 1),
+            ('"', _) => {
+                let (ident, next) = lex_quoted(&chars, i + 1, '"')?;
+                tokens.push(Token::QuotedIdent(ident));
+                i = next;
+                continue;
+            }
+            ('\'', _) => {
+                let (s, next) = lex_quoted(&chars, i + 1, '\'')?;
+                tokens.push(Token::Str(s));
+                i = next;
+                continue;
+            }
+            (c, _) if c.is_ascii_digit() => {
+                let (token, next) = lex_number(&chars, i)?;
+                tokens.push(token);
+                i = next;
+                continue;
+            }
+            (c, _) if is_ident_start(c) => {
+                let start = i;
+                while i < chars.len() && is_ident_char(chars[i]) {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+                continue;
+            }
+            (c, _) => {
+                return ParseSnafu {
+                    reason: format!("unexpected character '{c}' at position {i}"),
+                }
+                .fail();
+            }
+        };
+        tokens.push(token);
+        i += len;
+    }
+    tokens.push(Token::Eof);
+    Ok(tokens)
+}
+
+fn is_ident_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_ident_char(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Lexes a quoted string or identifier starting after the opening quote.
+fn lex_quoted(chars: &[char], mut i: usize, quote: char) -> Result<(String, usize)> {
+    let mut s = String::new();
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if i + 1 < chars.len() => {
+                match chars[i + 1] {
+                    'n' => s.push('\n'),
+                    't' => s.push('\t'),
+                    c => s.push(c),
+                }
+                i += 2;
+            }
+            c if c == quote => return Ok((s, i + 1)),
+            c => {
+                s.push(c);
+                i += 1;
+            }
+        }
+    }
+    ParseSnafu {
+        reason: format!("unterminated quoted string: {quote}{s}"),
+    }
+    .fail()
+}
+
+/// Lexes a regex starting after the opening `/`.
+fn lex_regex(chars: &[char], mut i: usize) -> Result<(String, usize)> {
+    let mut regex = String::new();
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if chars.get(i + 1) == Some(&'/') => {
+                regex.push('/');
+                i += 2;
+            }
+            '/' => return Ok((regex, i + 1)),
+            c => {
+                regex.push(c);
+                i += 1;
+            }
+        }
+    }
+    ParseSnafu {
+        reason: format!("unterminated regex: /{regex}"),
+    }
+    .fail()
+}
+
+/// Lexes an integer, a float or a duration literal like `10m`.
+fn lex_number(chars: &[char], mut i: usize) -> Result<(Token, usize)> {
+    let start = i;
+    while i < chars.len() && chars[i].is_ascii_digit() {
+        i += 1;
+    }
+    let is_float = i + 1 < chars.len() && chars[i] == '.' && chars[i + 1].is_ascii_digit();
+    if is_float {
+        i += 1;
+        while i < chars.len() && chars[i].is_ascii_digit() {
+            i += 1;
+        }
+    }
+    let literal = chars[start..i].iter().collect::<String>();
+    if is_float {
+        let number = literal.parse::<f64>().map_err(|e| {
+            ParseSnafu {
+                reason: format!("invalid number {literal}: {e}"),
+            }
+            .build()
+        })?;
+        return Ok((Token::Number(number), i));
+    }
+    let integer = literal.parse::<i64>().map_err(|e| {
+        ParseSnafu {
+            reason: format!("invalid integer {literal}: {e}"),
+        }
+        .build()
+    })?;
+
+    let unit_start = i;
+    while i < chars.len() && (is_ident_char(chars[i]) || chars[i] == 'µ') {
+        i += 1;
+    }
+    if unit_start == i {
+        return Ok((Token::Integer(integer), i));
+    }
+    let unit = chars[unit_start..i].iter().collect::<String>();
+    let multiplier = match unit.as_str() {
+        "ns" => 1,
+        "u" | "µ" => NANOS_PER_MICRO,
+        "ms" => NANOS_PER_MILLI,
+        "s" => NANOS_PER_SECOND,
+        "m" => NANOS_PER_MINUTE,
+        "h" => NANOS_PER_HOUR,
+        "d" => NANOS_PER_DAY,
+        "w" => NANOS_PER_WEEK,
+        _ => {
+            return ParseSnafu {
+                reason: format!("invalid duration {literal}{unit}"),
+            }
+            .fail();
+        }
+    };
+    let nanos = integer.checked_mul(multiplier).ok_or_else(|| {
+        ParseSnafu {
+            reason: format!("duration {literal}{unit} overflows"),
+        }
+        .build()
+    })?;
+    Ok((Token::Duration(nanos), i))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        self.peek_nth(0)
+    }
+
+    /// Returns the `n`-th next token. Positions after the end are [`Token::Eof`].
+    fn peek_nth(&self, n: usize) -> &Token {
+        self.tokens.get(self.pos + n).unwrap_or(&Token::Eof)
+    }
+
+    fn next(&mut self) -> Token {
+        let token = self.peek().clone();
+        self.pos += 1;
+        token
+    }
+
+    fn consume(&mut self, token: &Token) -> bool {
+        if self.peek() == token {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &Token) -> Result<()> {
+        if self.consume(token) {
+            Ok(())
+        } else {
+            self.unexpected(&format!("{token:?}"))
+        }
+    }
+
+    fn unexpected<T>(&self, expected: &str) -> Result<T> {
+        ParseSnafu {
+            reason: format!("found {:?}, expected {expected}", self.peek()),
+        }
+        .fail()
+    }
+
+    fn peek_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Token::Ident(ident) if ident.eq_ignore_ascii_case(keyword))
+    }
+
+    fn consume_keyword(&mut self, keyword: &str) -> bool {
+        if self.peek_keyword(keyword) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect_keyword(&mut self, keyword: &str) -> Result<()> {
+        if self.consume_keyword(keyword) {
+            Ok(())
+        } else {
+            self.unexpected(keyword)
+        }
+    }
+
+    fn parse_identifier(&mut self) -> Result<String> {
+        match self.next() {
+            Token::Ident(ident) | Token::QuotedIdent(ident) => Ok(ident),
+            _ => {
+                self.pos -= 1;
+                self.unexpected("identifier")
+            }
+        }
+    }
+
+    fn parse_unsigned(&mut self) -> Result<u64> {
+        match self.peek() {
+            Token::Integer(n) if *n >= 0 => {
+                let n = *n as u64;
+                self.pos += 1;
+                Ok(n)
+            }
+            _ => self.unexpected("non-negative integer"),
+        }
+    }
+
+    fn parse_statement(&mut self) -> Result<Statement> {
+        if self.consume_keyword("SELECT") {
+            return Ok(Statement::Select(Box::new(self.parse_select()?)));
+        }
+        if self.consume_keyword("SHOW") {
+            return self.parse_show();
+        }
+        self.unexpected("SELECT or SHOW")
+    }
+
+    fn parse_select(&mut self) -> Result<SelectStatement> {
+        let mut fields = vec![self.parse_field()?];
+        while self.consume(&Token::Comma) {
+            fields.push(self.parse_field()?);
+        }
+
+        if self.consume_keyword("INTO") {
+            return ParseSnafu {
+                reason: "SELECT INTO is not supported",
+            }
+            .fail();
+        }
+        self.expect_keyword("FROM")?;
+        let from = self.parse_measurement()?;
+        ensure!(
+            self.peek() != &Token::Comma,
+            ParseSnafu {
+                reason: "selecting from multiple measurements is not supported",
+            }
+        );
+
+        let condition = if self.consume_keyword("WHERE") {
+            Some(self.parse_expr()?)
+        } else {
+            None
+        };
+
+        let mut group_by = GroupBy::default();
+        if self.consume_keyword("GROUP") {
+            self.expect_keyword("BY")?;
+            loop {
+                self.parse_dimension(&mut group_by)?;
+                if !self.consume(&Token::Comma) {
+                    break;
+                }
+            }
+        }
+
+        let fill = if self.consume_keyword("FILL") {
+            self.parse_fill()?
+        } else {
+            Fill::default()
+        };
+
+        let mut order_desc = false;
+        if self.consume_keyword("ORDER") {
+            self.expect_keyword("BY")?;
+            self.expect_keyword("time")?;
+            if self.consume_keyword("DESC") {
+                order_desc = true;
+            } else {
+                self.consume_keyword("ASC");
+            }
+        }
+
+        let limit = self.parse_limit()?;
+        if self.peek_keyword("SLIMIT") || self.peek_keyword("SOFFSET") || self.peek_keyword("TZ") {
+            return self.unexpected("end of statement, SLIMIT, SOFFSET and tz() are not supported");
+        }
+
+        Ok(SelectStatement {
+            fields,
+            from,
+            condition,
+            group_by,
+            fill,
+            order_desc,
+            limit,
+        })
+    }
+
+    fn parse_field(&mut self) -> Result<Field> {
+        let expr = self.parse_expr()?;
+        let alias = if self.consume_keyword("AS") {
+            Some(self.parse_identifier()?)
+        } else {
+            None
+        };
+        Ok(Field { expr, alias })
+    }
+
+    /// Parses `name`, `rp.name`, `db.rp.name` or `db..name`.
+    fn parse_measurement(&mut self) -> Result<Measurement> {
+        if matches!(self.peek(), Token::Slash) {
+            return self.unexpected("measurement name, regex measurements are not supported");
+        }
+        let mut parts = vec![Some(self.parse_identifier()?)];
+        while self.consume(&Token::Dot) {
+            if self.peek() == &Token::Dot {
+                parts.push(None);
+            } else {
+                parts.push(Some(self.parse_identifier()?));
+            }
+        }
+        ensure!(
+            parts.len() <= 3,
+            ParseSnafu {
+                reason: format!("invalid measurement with {} parts", parts.len()),
+            }
+        );
+        let name = parts.pop().flatten().context(ParseSnafu {
+            reason: "missing measurement name",
+        })?;
+        let database = if parts.len() == 2 {
+            parts.remove(0)
+        } else {
+            None
+        };
+        Ok(Measurement { database, name })
+    }
+
+    fn parse_dimension(&mut self, group_by: &mut GroupBy) -> Result<()> {
+        if self.consume(&Token::Star) {
+            group_by.all_tags = true;
+            return Ok(());
+        }
+        if self.peek_keyword("time") && self.peek_nth(1) == &Token::LParen {
+            self.pos += 2;
+            let interval = self.parse_duration()?;
+            let offset = if self.consume(&Token::Comma) {
+                let negative = self.consume(&Token::Minus);
+                let offset = self.parse_duration()?;
+                if negative { -offset } else { offset }
+            } else {
+                0
+            };
+            self.expect(&Token::RParen)?;
+            ensure!(
+                interval > 0,
+                ParseSnafu {
+                    reason: "GROUP BY time() interval must be positive",
+                }
+            );
+            ensure!(
+                group_by.time.is_none(),
+                ParseSnafu {
+                    reason: "multiple GROUP BY time() dimensions",
+                }
+            );
+            group_by.time = Some(TimeDimension { interval, offset });
+            return Ok(());
+        }
+        let tag = self.parse_identifier()?;
+        // Skips the type hint like `host::tag`.
+        if self.consume(&Token::DoubleColon) {
+            self.parse_identifier()?;
+        }
+        group_by.tags.push(tag);
+        Ok(())
+    }
+
+    fn parse_duration(&mut self) -> Result<i64> {
+        match self.peek() {
+            Token::Duration(nanos) => {
+                let nanos = *nanos;
+                self.pos += 1;
+                Ok(nanos)
+            }
+            _ => self.unexpected("duration"),
+        }
+    }
+
+    fn parse_fill(&mut self) -> Result<Fill> {
+        self.expect(&Token::LParen)?;
+        let negative = self.consume(&Token::Minus);
+        let fill = match self.next() {
+            Token::Ident(ident) if !negative => match ident.to_ascii_lowercase().as_str() {
+                "null" => Fill::Null,
+                "none" => Fill::None,
+                "previous" => Fill::Previous,
+                "linear" => Fill::Linear,
+                _ => {
+                    return ParseSnafu {
+                        reason: format!("unknown fill option {ident}"),
+                    }
+                    .fail();
+                }
+            },
+            Token::Integer(n) => Fill::Value(if negative { -n as f64 } else { n as f64 }),
+            Token::Number(n) => Fill::Value(if negative { -n } else { n }),
+            _ => {
+                self.pos -= 1;
+                return self.unexpected("fill option");
+            }
+        };
+        self.expect(&Token::RParen)?;
+        Ok(fill)
+    }
+
+    fn parse_limit(&mut self) -> Result<Limit> {
+        let mut limit = Limit::default();
+        if self.consume_keyword("LIMIT") {
+            limit.limit = Some(self.parse_unsigned()?);
+        }
+        if self.consume_keyword("OFFSET") {
+            limit.offset = Some(self.parse_unsigned()?);
+        }
+        Ok(limit)
+    }
+
+    fn parse_show(&mut self) -> Result<Statement> {
+        if self.consume_keyword("MEASUREMENTS") {
+            let database = self.parse_on()?;
+            let name = if self.consume_keyword("WITH") {
+                self.expect_keyword("MEASUREMENT")?;
+                Some(self.parse_name_match()?)
+            } else {
+                None
+            };
+            self.reject_where()?;
+            let limit = self.parse_limit()?;
+            return Ok(Statement::ShowMeasurements(ShowMeasurements {
+                database,
+                name,
+                limit,
+            }));
+        }
+        if self.consume_keyword("TAG") {
+            if self.consume_keyword("KEYS") {
+                let database = self.parse_on()?;
+                let from = self.parse_from()?;
+                self.reject_where()?;
+                let limit = self.parse_limit()?;
+                return Ok(Statement::ShowTagKeys(ShowTagKeys {
+                    database,
+                    from,
+                    limit,
+                }));
+            }
+            if self.consume_keyword("VALUES") {
+                let database = self.parse_on()?;
+                let from = self.parse_from()?;
+                self.expect_keyword("WITH")?;
+                self.expect_keyword("KEY")?;
+                let key = self.parse_name_match()?;
+                self.reject_where()?;
+                let limit = self.parse_limit()?;
+                return Ok(Statement::ShowTagValues(ShowTagValues {
+                    database,
+                    from,
+                    key,
+                    limit,
+                }));
+            }
+            return self.unexpected("KEYS or VALUES");
+        }
+        if self.consume_keyword("FIELD") {
+            self.expect_keyword("KEYS")?;
+            let database = self.parse_on()?;
+            let from = self.parse_from()?;
+            let limit = self.parse_limit()?;
+            return Ok(Statement::ShowFieldKeys(ShowFieldKeys {
+                database,
+                from,
+                limit,
+            }));
+        }
+        self.unexpected("MEASUREMENTS, TAG KEYS, TAG VALUES or FIELD KEYS")
+    }
+
+    fn parse_on(&mut self) -> Result<Option<String>> {
+        if self.consume_keyword("ON") {
+            Ok(Some(self.parse_identifier()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn parse_from(&mut self) -> Result<Option<Measurement>> {
+        if self.consume_keyword("FROM") {
+            Ok(Some(self.parse_measurement()?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn reject_where(&self) -> Result<()> {
+        ensure!(
+            !self.peek_keyword("WHERE"),
+            ParseSnafu {
+                reason: "WHERE clause in SHOW statements is not supported",
+            }
+        );
+        Ok(())
+    }
+
+    fn parse_name_match(&mut self) -> Result<NameMatch> {
+        let name_match = match self.next() {
+            Token::Eq => NameMatch::Eq(self.parse_identifier_or_string()?),
+            Token::NotEq => NameMatch::NotEq(self.parse_identifier_or_string()?),
+            Token::EqRegex => NameMatch::Regex(self.parse_regex()?),
+            Token::NotEqRegex => NameMatch::NotRegex(self.parse_regex()?),
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("IN") => {
+                self.expect(&Token::LParen)?;
+                let mut names = vec![self.parse_identifier_or_string()?];
+                while self.consume(&Token::Comma) {
+                    names.push(self.parse_identifier_or_string()?);
+                }
+                self.expect(&Token::RParen)?;
+                NameMatch::In(names)
+            }
+            _ => {
+                self.pos -= 1;
+                return self.unexpected("=, !=, =~, !~ or IN");
+            }
+        };
+        Ok(name_match)
+    }
+
+    fn parse_identifier_or_string(&mut self) -> Result<String> {
+        match self.next() {
+            Token::Ident(s) | Token::QuotedIdent(s) | Token::Str(s) => Ok(s),
+            _ => {
+                self.pos -= 1;
+                self.unexpected("identifier or string")
+            }
+        }
+    }
+
+    fn parse_regex(&mut self) -> Result<String> {
+        match self.next() {
+            Token::Regex(regex) => Ok(regex),
+            _ => {
+                self.pos -= 1;
+                self.unexpected("regex")
+            }
+        }
+    }
+
+    fn parse_expr(&mut self) -> Result<Expr> {
+        self.parse_or()
+    }
+
+    fn parse_or(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_and()?;
+        while self.consume_keyword("OR") {
+            let rhs = self.parse_and()?;
+            lhs = binary(BinaryOp::Or, lhs, rhs);
+        }
+        Ok(lhs)
+    }
+
+    fn parse_and(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_comparison()?;
+        while self.consume_keyword("AND") {
+            let rhs = self.parse_comparison()?;
+            lhs = binary(BinaryOp::And, lhs, rhs);
+        }
+        Ok(lhs)
+    }
+
+    fn parse_comparison(&mut self) -> Result<Expr> {
+        let lhs = self.parse_additive()?;
+        let op = match self.peek() {
+            Token::Eq => BinaryOp::Eq,
+            Token::NotEq => BinaryOp::NotEq,
+            Token::Lt => BinaryOp::Lt,
+            Token::LtEq => BinaryOp::LtEq,
+            Token::Gt => BinaryOp::Gt,
+            Token::GtEq => BinaryOp::GtEq,
+            Token::EqRegex => BinaryOp::RegexMatch,
+            Token::NotEqRegex => BinaryOp::RegexNotMatch,
+            _ => return Ok(lhs),
+        };
+        self.pos += 1;
+        let rhs = self.parse_additive()?;
+        Ok(binary(op, lhs, rhs))
+    }
+
+    fn parse_additive(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_multiplicative()?;
+        loop {
+            let op = match self.peek() {
+                Token::Plus => BinaryOp::Add,
+                Token::Minus => BinaryOp::Sub,
+                _ => return Ok(lhs),
+            };
+            self.pos += 1;
+            let rhs = self.parse_multiplicative()?;
+            lhs = binary(op, lhs, rhs);
+        }
+    }
+
+    fn parse_multiplicative(&mut self) -> Result<Expr> {
+        let mut lhs = self.parse_unary()?;
+        loop {
+            let op = match self.peek() {
+                Token::Star => BinaryOp::Mul,
+                Token::Slash => BinaryOp::Div,
+                Token::Percent => BinaryOp::Mod,
+                _ => return Ok(lhs),
+            };
+            self.pos += 1;
+            let rhs = self.parse_unary()?;
+            lhs = binary(op, lhs, rhs);
+        }
+    }
+
+    fn parse_unary(&mut self) -> Result<Expr> {
+        if self.consume(&Token::Minus) {
+            return match self.parse_unary()? {
+                Expr::Integer(n) => Ok(Expr::Integer(-n)),
+                Expr::Number(n) => Ok(Expr::Number(-n)),
+                Expr::Duration(n) => Ok(Expr::Duration(-n)),
+                expr => Ok(binary(BinaryOp::Mul, Expr::Integer(-1), expr)),
+            };
+        }
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Expr> {
+        let expr = match self.next() {
+            Token::LParen => {
+                let expr = self.parse_expr()?;
+                self.expect(&Token::RParen)?;
+                expr
+            }
+            Token::Star => Expr::Wildcard,
+            Token::Integer(n) => Expr::Integer(n),
+            Token::Number(n) => Expr::Number(n),
+            Token::Duration(n) => Expr::Duration(n),
+            Token::Str(s) => Expr::String(s),
+            Token::Regex(regex) => Expr::Regex(regex),
+            Token::QuotedIdent(ident) => Expr::VarRef(ident),
+            Token::Ident(ident) if self.peek() == &Token::LParen => {
+                self.pos += 1;
+                let mut args = vec![];
+                if !self.consume(&Token::RParen) {
+                    args.push(self.parse_expr()?);
+                    while self.consume(&Token::Comma) {
+                        args.push(self.parse_expr()?);
+                    }
+                    self.expect(&Token::RParen)?;
+                }
+                Expr::Call {
+                    name: ident.to_ascii_lowercase(),
+                    args,
+                }
+            }
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("true") => Expr::Boolean(true),
+            Token::Ident(ident) if ident.eq_ignore_ascii_case("false") => Expr::Boolean(false),
+            Token::Ident(ident) => Expr::VarRef(ident),
+            _ => {
+                self.pos -= 1;
+                return self.unexpected("expression");
+            }
+        };
+        // Skips the type hint like `value::float`.
+        if matches!(expr, Expr::VarRef(_)) && self.consume(&Token::DoubleColon) {
+            self.parse_identifier()?;
+        }
+        Ok(expr)
+    }
+}
+
+fn binary(op: BinaryOp, lhs: Expr, rhs: Expr) -> Expr {
+    Expr::Binary {
+        op,
+        lhs: Box::new(lhs),
+        rhs: Box::new(rhs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_one(query: &str) -> Statement {
+        let mut statements = parse_statements(query).unwrap();
+        assert_eq!(1, statements.len());
+        statements.remove(0)
+    }
+
+    fn parse_select(query: &str) -> SelectStatement {
+        match parse_one(query) {
+            Statement::Select(select) => *select,
+            other => panic!("unexpected statement: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_parse_raw_select() {
+        let select = parse_select(
+            r#"select "usage", host FROM "telegraf"."autogen"."cpu" WHERE host = 'a' AND time > now() - 1h ORDER BY time DESC LIMIT 10 OFFSET 2"#,
+        );
+        assert_eq!(
+            vec![
+                Field {
+                    expr: Expr::VarRef("usage".to_string()),
+                    alias: None,
+                },
+                Field {
+                    expr: Expr::VarRef("host".to_string()),
+                    alias: None,
+                },
+            ],
+            select.fields
+        );
+        assert_eq!(
+            Measurement {
+                database: Some("telegraf".to_string()),
+                name: "cpu".to_string(),
+            },
+            select.from
+        );
+        assert_eq!(
+            Some(binary(
+                BinaryOp::And,
+                binary(
+                    BinaryOp::Eq,
+                    Expr::VarRef("host".to_string()),
+                    Expr::String("a".to_string())
+                ),
+                binary(
+                    BinaryOp::Gt,
+                    Expr::VarRef("time".to_string()),
+                    binary(
+                        BinaryOp::Sub,
+                        Expr::Call {
+                            name: "now".to_string(),
+                            args: vec![],
+                        },
+                        Expr::Duration(NANOS_PER_HOUR)
+                    )
+                )
+            )),
+            select.condition
+        );
+        assert!(select.order_desc);
+        assert_eq!(
+            Limit {
+                limit: Some(10),
+                offset: Some(2),
+            },
+            select.limit
+        );
+    }
+
+    #[test]
+    fn test_parse_aggregate_select() {
+        let select = parse_select(
+            "SELECT mean(value) AS avg_value, max(\"value\") FROM db..cpu WHERE host =~ /^web\\/[0-9]+$/ GROUP BY time(5m, 1m), host fill(previous)",
+        );
+        assert_eq!(
+            Field {
+                expr: Expr::Call {
+                    name: "mean".to_string(),
+                    args: vec![Expr::VarRef("value".to_string())],
+                },
+                alias: Some("avg_value".to_string()),
+            },
+            select.fields[0]
+        );
+        assert_eq!(Some("db".to_string()), select.from.database);
+        assert_eq!(
+            Some(binary(
+                BinaryOp::RegexMatch,
+                Expr::VarRef("host".to_string()),
+                Expr::Regex("^web/[0-9]+$".to_string())
+            )),
+            select.condition
+        );
+        assert_eq!(
+            GroupBy {
+                time: Some(TimeDimension {
+                    interval: 5 * NANOS_PER_MINUTE,
+                    offset: NANOS_PER_MINUTE,
+                }),
+                all_tags: false,
+                tags: vec!["host".to_string()],
+            },
+            select.group_by
+        );
+        assert_eq!(Fill::Previous, select.fill);
+
+        let select = parse_select("SELECT count(*) FROM cpu GROUP BY * fill(-1.5)");
+        assert!(select.group_by.all_tags);
+        assert_eq!(Fill::Value(-1.5), select.fill);
+    }
+
+    #[test]
+    fn test_parse_expr_precedence() {
+        let select = parse_select("SELECT a + b * 2 FROM m WHERE a > 1 OR b < 2 AND c = 3");
+        assert_eq!(
+            binary(
+                BinaryOp::Add,
+                Expr::VarRef("a".to_string()),
+                binary(
+                    BinaryOp::Mul,
+                    Expr::VarRef("b".to_string()),
+                    Expr::Integer(2)
+                )
+            ),
+            select.fields[0].expr
+        );
+        let Some(Expr::Binary { op, .. }) = select.condition else {
+            panic!("unexpected condition");
+        };
+        assert_eq!(BinaryOp::Or, op);
+    }
+
+    #[test]
+    fn test_target_database() {
+        for (query, database) in [
+            ("SHOW TAG VALUES ON a FROM b..m WITH KEY = x", Some("b")),
+            ("SHOW TAG VALUES ON a FROM m WITH KEY = x", Some("a")),
+            ("SHOW TAG KEYS ON a FROM \"b\".\"autogen\".m", Some("b")),
+            ("SHOW MEASUREMENTS ON a", Some("a")),
+            ("SELECT v FROM m", None),
+        ] {
+            let statement = parse_statements(query).unwrap().remove(0);
+            assert_eq!(database, statement.target_database(), "{query}");
+        }
+    }
+
+    #[test]
+    fn test_parse_show_statements() {
+        let statements = parse_statements(
+            "SHOW MEASUREMENTS ON telegraf WITH MEASUREMENT =~ /cpu.*/ LIMIT 5; \
+             show tag keys from cpu; \
+             SHOW TAG VALUES ON telegraf FROM cpu WITH KEY IN (\"host\", region); \
+             SHOW FIELD KEYS",
+        )
+        .unwrap();
+        assert_eq!(
+            vec![
+                Statement::ShowMeasurements(ShowMeasurements {
+                    database: Some("telegraf".to_string()),
+                    name: Some(NameMatch::Regex("cpu.*".to_string())),
+                    limit: Limit {
+                        limit: Some(5),
+                        offset: None,
+                    },
+                }),
+                Statement::ShowTagKeys(ShowTagKeys {
+                    database: None,
+                    from: Some(Measurement {
+                        database: None,
+                        name: "cpu".to_string(),
+                    }),
+                    limit: Limit::default(),
+                }),
+                Statement::ShowTagValues(ShowTagValues {
+                    database: Some("telegraf".to_string()),
+                    from: Some(Measurement {
+                        database: None,
+                        name: "cpu".to_string(),
+                    }),
+                    key: NameMatch::In(vec!["host".to_string(), "region".to_string()]),
+                    limit: Limit::default(),
+                }),
+                Statement::ShowFieldKeys(ShowFieldKeys {
+                    database: None,
+                    from: None,
+                    limit: Limit::default(),
+                }),
+            ],
+            statements
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        for query in [
+            "",
+            "DELETE FROM cpu",
+            "SELECT value FROM",
+            "SELECT value FROM cpu GROUP BY time(0s)",
+            "SELECT value FROM cpu fill(foo)",
+            "SELECT value FROM cpu SLIMIT 1",
+            "SELECT value INTO other FROM cpu",
+            "SELECT value FROM cpu WHERE host = 'a",
+            "SELECT value FROM cpu GROUP BY time(10x)",
+            "SHOW TAG VALUES FROM cpu",
+            "SHOW MEASUREMENTS WHERE host = 'a'",
+        ] {
+            assert!(parse_statements(query).is_err(), "{query}");
+        }
+    }
+}
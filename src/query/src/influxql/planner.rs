@@ -0,0 +1,1136 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use arrow_schema::{DataType, TimeUnit as ArrowTimeUnit};
+use catalog::information_schema::{COLUMNS, TABLES, columns, tables};
+use catalog::table_source::DfTableSourceProvider;
+use common_catalog::consts::{
+    INFORMATION_SCHEMA_NAME, SEMANTIC_TYPE_FIELD, SEMANTIC_TYPE_PRIMARY_KEY,
+};
+use common_time::Timestamp;
+use common_time::timestamp::TimeUnit;
+use datafusion::datasource::DefaultTableSource;
+use datafusion::functions_aggregate::expr_fn::{
+    avg, count, first_value, last_value, max, median, min, stddev, sum,
+};
+use datafusion::functions_window::expr_fn::row_number;
+use datafusion_common::ScalarValue;
+use datafusion_expr::expr_fn::when;
+use datafusion_expr::{
+    Expr, ExprFunctionExt, ExprSchemable, Extension, LogicalPlan, LogicalPlanBuilder, Operator,
+    SortExpr, TableSource, binary_expr, cast, ident, lit,
+};
+use datafusion_sql::TableReference;
+use datatypes::data_type::DataType as GreptimeDataType;
+use regex::Regex;
+use session::context::QueryContextRef;
+use snafu::{OptionExt, ResultExt, ensure};
+use table::table::adapter::DfTableProviderAdapter;
+
+use crate::influxql::ast::{
+    self, BinaryOp, Field, Fill, GroupBy, Limit, Measurement, NameMatch, SelectStatement,
+    ShowFieldKeys, ShowMeasurements, ShowTagKeys, ShowTagValues, Statement, target_database,
+};
+use crate::influxql::error::{
+    CatalogSnafu, DataFusionPlanningSnafu, InvalidStatementSnafu, RangeSelectSnafu, Result,
+    TimeIndexNotFoundSnafu, UnimplementedSnafu, UnknownFunctionSnafu, UnknownTableSnafu,
+};
+use crate::influxql::{InfluxqlPlan, SeriesLayout, SeriesName};
+use crate::range_select::plan::{Fill as RangeFill, RangeFn, RangeSelect};
+
+/// The name of the time column in InfluxQL results.
+const TIME_COLUMN: &str = "time";
+const ROW_NUMBER_COLUMN: &str = "__influxql_row_number";
+
+const NANOS_PER_MICRO: i64 = 1_000;
+const NANOS_PER_MILLI: i64 = 1_000_000;
+const NANOS_PER_SECOND: i64 = 1_000_000_000;
+
+/// Translates InfluxQL statements to DataFusion logical plans.
+///
+/// Measurements map to the tables written by the line protocol, whose
+/// primary key columns are tags and other non-time-index columns are fields.
+pub struct InfluxqlPlanner {
+    table_provider: DfTableSourceProvider,
+    query_ctx: QueryContextRef,
+}
+
+/// The columns of the table a `SELECT` statement reads from.
+struct TableContext {
+    time_index: String,
+    time_unit: ArrowTimeUnit,
+    /// Sorted tag column names.
+    tags: Vec<String>,
+    /// Sorted field column names.
+    fields: Vec<String>,
+}
+
+impl TableContext {
+    fn is_column(&self, name: &str) -> bool {
+        name == self.time_index
+            || self.tags.iter().any(|tag| tag == name)
+            || self.fields.iter().any(|field| field == name)
+    }
+}
+
+impl InfluxqlPlanner {
+    pub fn new(table_provider: DfTableSourceProvider, query_ctx: QueryContextRef) -> Self {
+        Self {
+            table_provider,
+            query_ctx,
+        }
+    }
+
+    pub async fn statement_to_plan(&mut self, stmt: Statement) -> Result<InfluxqlPlan> {
+        match stmt {
+            Statement::Select(select) => self.select_to_plan(*select).await,
+            Statement::ShowMeasurements(show) => self.show_measurements_to_plan(show).await,
+            Statement::ShowTagKeys(show) => self.show_tag_keys_to_plan(show).await,
+            Statement::ShowTagValues(show) => self.show_tag_values_to_plan(show).await,
+            Statement::ShowFieldKeys(show) => self.show_field_keys_to_plan(show).await,
+        }
+    }
+
+    async fn select_to_plan(&mut self, select: SelectStatement) -> Result<InfluxqlPlan> {
+        let (table_ref, table_source, table) = self.resolve_measurement(&select.from).await?;
+        let mut builder = LogicalPlanBuilder::scan(table_ref, table_source, None)
+            .context(DataFusionPlanningSnafu)?;
+        if let Some(condition) = &select.condition {
+            builder = builder
+                .filter(self.condition_to_expr(condition, &table)?)
+                .context(DataFusionPlanningSnafu)?;
+        }
+
+        let group_tags = group_tags(&select.group_by, &table);
+        let fields = expand_fields(&select.fields, &table, &group_tags);
+        ensure!(
+            !fields.is_empty(),
+            InvalidStatementSnafu {
+                reason: "at least 1 non-time field must be queried",
+            }
+        );
+        let names = output_names(&fields);
+
+        let plan = if fields.iter().any(|field| contains_call(&field.expr)) {
+            ensure!(
+                !fields
+                    .iter()
+                    .any(|field| contains_var_outside_call(&field.expr)),
+                InvalidStatementSnafu {
+                    reason: "mixing aggregate and non-aggregate queries is not supported",
+                }
+            );
+            self.plan_aggregate(builder, &select, &table, &group_tags, &fields, names)?
+        } else {
+            ensure!(
+                select.group_by.time.is_none(),
+                InvalidStatementSnafu {
+                    reason: "GROUP BY requires at least one aggregate function",
+                }
+            );
+            let mut exprs = group_tags.iter().map(ident).collect::<Vec<_>>();
+            exprs.push(ident(&table.time_index).alias(TIME_COLUMN));
+            for (field, name) in fields.iter().zip(names) {
+                exprs.push(value_expr(&field.expr)?.alias(name));
+            }
+            let builder = builder.project(exprs).context(DataFusionPlanningSnafu)?;
+            sort_and_limit(builder, &group_tags, select.order_desc, &select.limit)?
+        };
+
+        Ok(InfluxqlPlan {
+            plan,
+            layout: SeriesLayout {
+                name: SeriesName::Fixed(select.from.name),
+                tag_columns: group_tags,
+            },
+        })
+    }
+
+    /// Plans aggregations. With `GROUP BY time()` the aggregations are
+    /// evaluated per time window by a [`RangeSelect`].
+    fn plan_aggregate(
+        &self,
+        builder: LogicalPlanBuilder,
+        select: &SelectStatement,
+        table: &TableContext,
+        group_tags: &[String],
+        fields: &[Field],
+        names: Vec<String>,
+    ) -> Result<LogicalPlan> {
+        let mut aggrs = vec![];
+        let projections = fields
+            .iter()
+            .zip(names)
+            .map(|(field, name)| {
+                Ok(aggregate_projection(&field.expr, table, &mut aggrs)?.alias(name))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let tag_exprs = group_tags.iter().map(ident).collect::<Vec<_>>();
+
+        let (builder, time_expr) = match select.group_by.time {
+            Some(dimension) => {
+                let input = Arc::new(builder.build().context(DataFusionPlanningSnafu)?);
+                let interval = Duration::from_nanos(dimension.interval as u64);
+                let fill = match select.fill {
+                    Fill::Null => "NULL".to_string(),
+                    Fill::None => String::new(),
+                    Fill::Previous => "PREV".to_string(),
+                    Fill::Linear => "LINEAR".to_string(),
+                    Fill::Value(value) => value.to_string(),
+                };
+                let range_fns = aggrs
+                    .iter()
+                    .map(|(name, expr)| {
+                        let mut data_type = expr
+                            .get_type(input.schema())
+                            .context(DataFusionPlanningSnafu)?;
+                        let fill = RangeFill::try_from_str(&fill, &data_type)
+                            .context(DataFusionPlanningSnafu)?;
+                        let mut need_cast = false;
+                        if matches!(fill, Some(RangeFill::Linear)) && data_type.is_integer() {
+                            data_type = DataType::Float64;
+                            need_cast = true;
+                        }
+                        Ok(RangeFn {
+                            name: name.clone(),
+                            data_type,
+                            expr: expr.clone(),
+                            range: interval,
+                            fill,
+                            need_cast,
+                        })
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let mut projection = aggrs
+                    .iter()
+                    .map(|(name, _)| ident(name))
+                    .collect::<Vec<_>>();
+                projection.push(ident(&table.time_index));
+                projection.extend(tag_exprs.iter().cloned());
+                let range_select = RangeSelect::try_new(
+                    input,
+                    range_fns,
+                    interval,
+                    dimension.offset.div_euclid(NANOS_PER_MILLI),
+                    ident(&table.time_index),
+                    tag_exprs.clone(),
+                    &projection,
+                )
+                .context(RangeSelectSnafu)?;
+                let plan = LogicalPlan::Extension(Extension {
+                    node: Arc::new(range_select),
+                });
+                (LogicalPlanBuilder::from(plan), ident(&table.time_index))
+            }
+            None => {
+                let builder = builder
+                    .aggregate(
+                        tag_exprs.clone(),
+                        aggrs.iter().map(|(name, expr)| expr.clone().alias(name)),
+                    )
+                    .context(DataFusionPlanningSnafu)?;
+                // Like InfluxDB, uses the lower bound of the time range as the timestamp.
+                let start = match &select.condition {
+                    Some(condition) => self.time_lower_bound(condition)?,
+                    None => None,
+                };
+                let time_expr = timestamp_literal(start.unwrap_or(0), table.time_unit);
+                (builder, time_expr)
+            }
+        };
+
+        let mut exprs = tag_exprs;
+        exprs.push(time_expr.alias(TIME_COLUMN));
+        exprs.extend(projections);
+        let builder = builder.project(exprs).context(DataFusionPlanningSnafu)?;
+        sort_and_limit(builder, group_tags, select.order_desc, &select.limit)
+    }
+
+    async fn show_measurements_to_plan(&mut self, show: ShowMeasurements) -> Result<InfluxqlPlan> {
+        let schema = self.schema_name(show.database.as_deref(), None);
+        let mut builder = self.scan_information_schema(TABLES).await?;
+        let mut filter = ident(tables::TABLE_CATALOG)
+            .eq(lit(self.query_ctx.current_catalog()))
+            .and(ident(tables::TABLE_SCHEMA).eq(lit(schema)))
+            .and(ident(tables::TABLE_TYPE).eq(lit("BASE TABLE")));
+        if let Some(name) = &show.name {
+            filter = filter.and(name_match_expr(ident(tables::TABLE_NAME), name));
+        }
+        builder = builder
+            .filter(filter)
+            .context(DataFusionPlanningSnafu)?
+            .project(vec![ident(tables::TABLE_NAME).alias("name")])
+            .context(DataFusionPlanningSnafu)?;
+        let plan = sort_and_limit_by(builder, &[], &["name"], &show.limit)?;
+
+        Ok(InfluxqlPlan {
+            plan,
+            layout: SeriesLayout {
+                name: SeriesName::Fixed("measurements".to_string()),
+                tag_columns: vec![],
+            },
+        })
+    }
+
+    async fn show_tag_keys_to_plan(&mut self, show: ShowTagKeys) -> Result<InfluxqlPlan> {
+        let builder = self
+            .scan_columns(
+                show.database.as_deref(),
+                show.from.as_ref(),
+                SEMANTIC_TYPE_PRIMARY_KEY,
+            )
+            .await?
+            .project(vec![
+                ident(columns::TABLE_NAME),
+                ident(columns::COLUMN_NAME).alias("tagKey"),
+            ])
+            .context(DataFusionPlanningSnafu)?;
+        let plan = sort_and_limit_by(builder, &[columns::TABLE_NAME], &["tagKey"], &show.limit)?;
+
+        Ok(InfluxqlPlan {
+            plan,
+            layout: SeriesLayout {
+                name: SeriesName::Column(columns::TABLE_NAME.to_string()),
+                tag_columns: vec![],
+            },
+        })
+    }
+
+    async fn show_field_keys_to_plan(&mut self, show: ShowFieldKeys) -> Result<InfluxqlPlan> {
+        let data_type = ident(columns::GREPTIME_DATA_TYPE);
+        let field_type = when(data_type.clone().like(lit("Float%")), lit("float"))
+            .when(data_type.clone().like(lit("Int%")), lit("integer"))
+            .when(data_type.clone().like(lit("UInt%")), lit("unsigned"))
+            .when(data_type.eq(lit("Boolean")), lit("boolean"))
+            .otherwise(lit("string"))
+            .context(DataFusionPlanningSnafu)?;
+        let builder = self
+            .scan_columns(
+                show.database.as_deref(),
+                show.from.as_ref(),
+                SEMANTIC_TYPE_FIELD,
+            )
+            .await?
+            .project(vec![
+                ident(columns::TABLE_NAME),
+                ident(columns::COLUMN_NAME).alias("fieldKey"),
+                field_type.alias("fieldType"),
+            ])
+            .context(DataFusionPlanningSnafu)?;
+        let plan = sort_and_limit_by(builder, &[columns::TABLE_NAME], &["fieldKey"], &show.limit)?;
+
+        Ok(InfluxqlPlan {
+            plan,
+            layout: SeriesLayout {
+                name: SeriesName::Column(columns::TABLE_NAME.to_string()),
+                tag_columns: vec![],
+            },
+        })
+    }
+
+    async fn show_tag_values_to_plan(&mut self, show: ShowTagValues) -> Result<InfluxqlPlan> {
+        let database =
+            target_database(show.database.as_deref(), show.from.as_ref()).map(ToString::to_string);
+        let mut measurement = show.from.context(UnimplementedSnafu {
+            feature: "SHOW TAG VALUES without FROM clause",
+        })?;
+        measurement.database = database;
+        let (table_ref, table_source, table) = self.resolve_measurement(&measurement).await?;
+
+        let mut builder: Option<LogicalPlanBuilder> = None;
+        for tag in &table.tags {
+            if !name_matches(&show.key, tag)? {
+                continue;
+            }
+            let plan = LogicalPlanBuilder::scan(table_ref.clone(), table_source.clone(), None)
+                .and_then(|builder| builder.filter(ident(tag).is_not_null()))
+                .and_then(|builder| {
+                    builder.project(vec![
+                        lit(tag.as_str()).alias("key"),
+                        cast(ident(tag), DataType::Utf8).alias("value"),
+                    ])
+                })
+                .and_then(|builder| builder.distinct())
+                .and_then(|builder| builder.build())
+                .context(DataFusionPlanningSnafu)?;
+            builder = Some(match builder {
+                Some(builder) => builder.union(plan).context(DataFusionPlanningSnafu)?,
+                None => LogicalPlanBuilder::from(plan),
+            });
+        }
+        let builder = match builder {
+            Some(builder) => builder,
+            None => LogicalPlanBuilder::empty(false)
+                .project(vec![lit("").alias("key"), lit("").alias("value")])
+                .context(DataFusionPlanningSnafu)?,
+        };
+        let plan = sort_and_limit_by(builder, &[], &["key", "value"], &show.limit)?;
+
+        Ok(InfluxqlPlan {
+            plan,
+            layout: SeriesLayout {
+                name: SeriesName::Fixed(measurement.name),
+                tag_columns: vec![],
+            },
+        })
+    }
+
+    async fn resolve_measurement(
+        &mut self,
+        measurement: &Measurement,
+    ) -> Result<(TableReference, Arc<dyn TableSource>, TableContext)> {
+        let table_ref = match &measurement.database {
+            Some(database) => TableReference::partial(database.as_str(), measurement.name.as_str()),
+            None => TableReference::bare(measurement.name.as_str()),
+        };
+        let table_source = self
+            .table_provider
+            .resolve_table(table_ref.clone())
+            .await
+            .context(CatalogSnafu)?;
+        let table_info = table_source
+            .as_any()
+            .downcast_ref::<DefaultTableSource>()
+            .context(UnknownTableSnafu)?
+            .table_provider
+            .as_any()
+            .downcast_ref::<DfTableProviderAdapter>()
+            .context(UnknownTableSnafu)?
+            .table()
+            .table_info();
+
+        let time_index = table_info
+            .meta
+            .schema
+            .timestamp_column()
+            .context(TimeIndexNotFoundSnafu)?;
+        let DataType::Timestamp(time_unit, _) = time_index.data_type.as_arrow_type() else {
+            return TimeIndexNotFoundSnafu.fail();
+        };
+        let mut tags = table_info
+            .meta
+            .row_key_column_names()
+            .cloned()
+            .collect::<Vec<_>>();
+        tags.sort();
+        let mut fields = table_info
+            .meta
+            .field_column_names()
+            .cloned()
+            .collect::<Vec<_>>();
+        fields.sort();
+
+        Ok((
+            table_ref,
+            table_source,
+            TableContext {
+                time_index: time_index.name.clone(),
+                time_unit,
+                tags,
+                fields,
+            },
+        ))
+    }
+
+    /// Returns the schema to read from, which is the database of the measurement, the
+    /// `ON` database or the current schema in order.
+    fn schema_name(&self, database: Option<&str>, measurement: Option<&Measurement>) -> String {
+        target_database(database, measurement)
+            .map(ToString::to_string)
+            .unwrap_or_else(|| self.query_ctx.current_schema())
+    }
+
+    async fn scan_information_schema(&mut self, table: &str) -> Result<LogicalPlanBuilder> {
+        let table_ref = TableReference::full(
+            self.query_ctx.current_catalog(),
+            INFORMATION_SCHEMA_NAME,
+            table,
+        );
+        let table_source = self
+            .table_provider
+            .resolve_table(table_ref.clone())
+            .await
+            .context(CatalogSnafu)?;
+        LogicalPlanBuilder::scan(table_ref, table_source, None).context(DataFusionPlanningSnafu)
+    }
+
+    /// Scans `information_schema.columns` for columns of the given semantic type.
+    async fn scan_columns(
+        &mut self,
+        database: Option<&str>,
+        from: Option<&Measurement>,
+        semantic_type: &str,
+    ) -> Result<LogicalPlanBuilder> {
+        let schema = self.schema_name(database, from);
+        let mut filter = ident(columns::TABLE_CATALOG)
+            .eq(lit(self.query_ctx.current_catalog()))
+            .and(ident(columns::TABLE_SCHEMA).eq(lit(schema)))
+            .and(ident(columns::SEMANTIC_TYPE).eq(lit(semantic_type)));
+        if let Some(measurement) = from {
+            filter = filter.and(ident(columns::TABLE_NAME).eq(lit(measurement.name.as_str())));
+        }
+        self.scan_information_schema(COLUMNS)
+            .await?
+            .filter(filter)
+            .context(DataFusionPlanningSnafu)
+    }
+
+    fn condition_to_expr(&self, expr: &ast::Expr, table: &TableContext) -> Result<Expr> {
+        match expr {
+            ast::Expr::Binary {
+                op: op @ (BinaryOp::And | BinaryOp::Or),
+                lhs,
+                rhs,
+            } => Ok(binary_expr(
+                self.condition_to_expr(lhs, table)?,
+                to_operator(*op),
+                self.condition_to_expr(rhs, table)?,
+            )),
+            ast::Expr::Binary { op, lhs, rhs } if op.is_comparison() => {
+                if is_time_ref(lhs) {
+                    return self.time_condition(*op, rhs, table);
+                }
+                if is_time_ref(rhs) {
+                    return self.time_condition(op.swap(), lhs, table);
+                }
+                // Missing tags are empty strings in InfluxQL.
+                if let (ast::Expr::VarRef(tag), ast::Expr::String(value)) = (&**lhs, &**rhs)
+                    && value.is_empty()
+                    && table.tags.contains(tag)
+                {
+                    match op {
+                        BinaryOp::Eq => return Ok(ident(tag).is_null()),
+                        BinaryOp::NotEq => return Ok(ident(tag).is_not_null()),
+                        _ => {}
+                    }
+                }
+                Ok(binary_expr(
+                    condition_operand(lhs, table)?,
+                    to_operator(*op),
+                    condition_operand(rhs, table)?,
+                ))
+            }
+            ast::Expr::Boolean(value) => Ok(lit(*value)),
+            _ => InvalidStatementSnafu {
+                reason: format!("unsupported condition: {expr:?}"),
+            }
+            .fail(),
+        }
+    }
+
+    fn time_condition(
+        &self,
+        op: BinaryOp,
+        value: &ast::Expr,
+        table: &TableContext,
+    ) -> Result<Expr> {
+        let time = timestamp_literal(self.eval_time(value)?, table.time_unit);
+        let column = ident(&table.time_index);
+        let expr = match op {
+            BinaryOp::Eq => column.eq(time),
+            BinaryOp::NotEq => column.not_eq(time),
+            BinaryOp::Lt => column.lt(time),
+            BinaryOp::LtEq => column.lt_eq(time),
+            BinaryOp::Gt => column.gt(time),
+            BinaryOp::GtEq => column.gt_eq(time),
+            _ => {
+                return InvalidStatementSnafu {
+                    reason: format!("unsupported operator on time: {op:?}"),
+                }
+                .fail();
+            }
+        };
+        Ok(expr)
+    }
+
+    /// Evaluates a time expression to nanoseconds since the epoch.
+    fn eval_time(&self, expr: &ast::Expr) -> Result<i64> {
+        let nanos = match expr {
+            ast::Expr::Call { name, args } if name == "now" && args.is_empty() => {
+                Timestamp::current_time(TimeUnit::Nanosecond).value()
+            }
+            ast::Expr::Integer(nanos) | ast::Expr::Duration(nanos) => *nanos,
+            ast::Expr::Number(nanos) => *nanos as i64,
+            ast::Expr::String(s) => Timestamp::from_str_utc(s)
+                .ok()
+                .and_then(|ts| ts.convert_to(TimeUnit::Nanosecond))
+                .with_context(|| InvalidStatementSnafu {
+                    reason: format!("invalid time: {s}"),
+                })?
+                .value(),
+            ast::Expr::Binary {
+                op: op @ (BinaryOp::Add | BinaryOp::Sub),
+                lhs,
+                rhs,
+            } => {
+                let lhs = self.eval_time(lhs)?;
+                let rhs = self.eval_time(rhs)?;
+                let nanos = if *op == BinaryOp::Add {
+                    lhs.checked_add(rhs)
+                } else {
+                    lhs.checked_sub(rhs)
+                };
+                nanos.context(InvalidStatementSnafu {
+                    reason: "time overflows",
+                })?
+            }
+            _ => {
+                return InvalidStatementSnafu {
+                    reason: format!("invalid time expression: {expr:?}"),
+                }
+                .fail();
+            }
+        };
+        Ok(nanos)
+    }
+
+    /// Returns the lower bound of `time` in the condition in nanoseconds, if any.
+    fn time_lower_bound(&self, expr: &ast::Expr) -> Result<Option<i64>> {
+        let bound = match expr {
+            ast::Expr::Binary {
+                op: BinaryOp::And,
+                lhs,
+                rhs,
+            } => {
+                let lhs = self.time_lower_bound(lhs)?;
+                let rhs = self.time_lower_bound(rhs)?;
+                lhs.max(rhs)
+            }
+            ast::Expr::Binary { op, lhs, rhs }
+                if is_time_ref(lhs)
+                    && matches!(op, BinaryOp::Gt | BinaryOp::GtEq | BinaryOp::Eq) =>
+            {
+                Some(self.eval_time(rhs)?)
+            }
+            ast::Expr::Binary { op, lhs, rhs }
+                if is_time_ref(rhs)
+                    && matches!(op, BinaryOp::Lt | BinaryOp::LtEq | BinaryOp::Eq) =>
+            {
+                Some(self.eval_time(lhs)?)
+            }
+            _ => None,
+        };
+        Ok(bound)
+    }
+}
+
+fn is_time_ref(expr: &ast::Expr) -> bool {
+    matches!(expr, ast::Expr::VarRef(name) if name.eq_ignore_ascii_case(TIME_COLUMN))
+}
+
+fn to_operator(op: BinaryOp) -> Operator {
+    match op {
+        BinaryOp::Add => Operator::Plus,
+        BinaryOp::Sub => Operator::Minus,
+        BinaryOp::Mul => Operator::Multiply,
+        BinaryOp::Div => Operator::Divide,
+        BinaryOp::Mod => Operator::Modulo,
+        BinaryOp::Eq => Operator::Eq,
+        BinaryOp::NotEq => Operator::NotEq,
+        BinaryOp::Lt => Operator::Lt,
+        BinaryOp::LtEq => Operator::LtEq,
+        BinaryOp::Gt => Operator::Gt,
+        BinaryOp::GtEq => Operator::GtEq,
+        BinaryOp::RegexMatch => Operator::RegexMatch,
+        BinaryOp::RegexNotMatch => Operator::RegexNotMatch,
+        BinaryOp::And => Operator::And,
+        BinaryOp::Or => Operator::Or,
+    }
+}
+
+fn timestamp_literal(nanos: i64, unit: ArrowTimeUnit) -> Expr {
+    let value = match unit {
+        ArrowTimeUnit::Second => {
+            ScalarValue::TimestampSecond(Some(nanos.div_euclid(NANOS_PER_SECOND)), None)
+        }
+        ArrowTimeUnit::Millisecond => {
+            ScalarValue::TimestampMillisecond(Some(nanos.div_euclid(NANOS_PER_MILLI)), None)
+        }
+        ArrowTimeUnit::Microsecond => {
+            ScalarValue::TimestampMicrosecond(Some(nanos.div_euclid(NANOS_PER_MICRO)), None)
+        }
+        ArrowTimeUnit::Nanosecond => ScalarValue::TimestampNanosecond(Some(nanos), None),
+    };
+    lit(value)
+}
+
+/// Translates a non-aggregate expression.
+fn value_expr(expr: &ast::Expr) -> Result<Expr> {
+    let expr = match expr {
+        ast::Expr::VarRef(name) => ident(name),
+        ast::Expr::Integer(value) => lit(*value),
+        ast::Expr::Number(value) => lit(*value),
+        ast::Expr::String(value) => lit(value.as_str()),
+        ast::Expr::Boolean(value) => lit(*value),
+        ast::Expr::Regex(regex) => lit(regex.as_str()),
+        ast::Expr::Binary { op, lhs, rhs } if !op.is_comparison() => {
+            binary_expr(value_expr(lhs)?, to_operator(*op), value_expr(rhs)?)
+        }
+        _ => {
+            return InvalidStatementSnafu {
+                reason: format!("unsupported expression: {expr:?}"),
+            }
+            .fail();
+        }
+    };
+    Ok(expr)
+}
+
+/// Translates an operand of a comparison. Like InfluxDB, comparisons with
+/// unknown columns never match.
+fn condition_operand(expr: &ast::Expr, table: &TableContext) -> Result<Expr> {
+    match expr {
+        ast::Expr::VarRef(name) if !table.is_column(name) => Ok(lit(ScalarValue::Null)),
+        _ => value_expr(expr),
+    }
+}
+
+/// Translates a projection of an aggregate query. Aggregate calls are
+/// collected into `aggrs` and replaced by references to their outputs.
+fn aggregate_projection(
+    expr: &ast::Expr,
+    table: &TableContext,
+    aggrs: &mut Vec<(String, Expr)>,
+) -> Result<Expr> {
+    let expr = match expr {
+        ast::Expr::Call { name, args } => {
+            let [arg] = args.as_slice() else {
+                return InvalidStatementSnafu {
+                    reason: format!("invalid number of arguments for {name}"),
+                }
+                .fail();
+            };
+            let arg = value_expr(arg)?;
+            let order_by = || vec![ident(&table.time_index).sort(true, false)];
+            match name.as_str() {
+                "mean" => register_aggr(aggrs, avg(arg)),
+                "sum" => register_aggr(aggrs, sum(arg)),
+                "count" => register_aggr(aggrs, count(arg)),
+                "min" => register_aggr(aggrs, min(arg)),
+                "max" => register_aggr(aggrs, max(arg)),
+                "median" => register_aggr(aggrs, median(arg)),
+                "stddev" => register_aggr(aggrs, stddev(arg)),
+                "first" => register_aggr(aggrs, first_value(arg, order_by())),
+                "last" => register_aggr(aggrs, last_value(arg, order_by())),
+                "spread" => {
+                    let max_expr = register_aggr(aggrs, max(arg.clone()));
+                    let min_expr = register_aggr(aggrs, min(arg));
+                    max_expr - min_expr
+                }
+                _ => return UnknownFunctionSnafu { name }.fail(),
+            }
+        }
+        ast::Expr::Binary { op, lhs, rhs } if !op.is_comparison() => binary_expr(
+            aggregate_projection(lhs, table, aggrs)?,
+            to_operator(*op),
+            aggregate_projection(rhs, table, aggrs)?,
+        ),
+        ast::Expr::Integer(value) => lit(*value),
+        ast::Expr::Number(value) => lit(*value),
+        _ => {
+            return InvalidStatementSnafu {
+                reason: format!("unsupported expression in aggregate query: {expr:?}"),
+            }
+            .fail();
+        }
+    };
+    Ok(expr)
+}
+
+fn register_aggr(aggrs: &mut Vec<(String, Expr)>, aggr: Expr) -> Expr {
+    let name = aggr.schema_name().to_string();
+    if !aggrs.iter().any(|(existing, _)| *existing == name) {
+        aggrs.push((name.clone(), aggr));
+    }
+    ident(name)
+}
+
+/// Returns the tags to group by, ignoring unknown tags.
+fn group_tags(group_by: &GroupBy, table: &TableContext) -> Vec<String> {
+    if group_by.all_tags {
+        return table.tags.clone();
+    }
+    let mut tags = vec![];
+    for tag in &group_by.tags {
+        if table.tags.contains(tag) && !tags.contains(tag) {
+            tags.push(tag.clone());
+        }
+    }
+    tags
+}
+
+/// Expands wildcards like `*` and `count(*)`, and removes the `time` column
+/// which is always returned.
+fn expand_fields(fields: &[Field], table: &TableContext, group_tags: &[String]) -> Vec<Field> {
+    let mut expanded = vec![];
+    for field in fields {
+        match &field.expr {
+            ast::Expr::Wildcard => {
+                let mut columns = table
+                    .tags
+                    .iter()
+                    .filter(|tag| !group_tags.contains(tag))
+                    .chain(table.fields.iter())
+                    .collect::<Vec<_>>();
+                columns.sort();
+                expanded.extend(columns.into_iter().map(|column| Field {
+                    expr: ast::Expr::VarRef(column.clone()),
+                    alias: None,
+                }));
+            }
+            ast::Expr::Call { name, args } if args.as_slice() == [ast::Expr::Wildcard] => {
+                expanded.extend(table.fields.iter().map(|column| Field {
+                    expr: ast::Expr::Call {
+                        name: name.clone(),
+                        args: vec![ast::Expr::VarRef(column.clone())],
+                    },
+                    alias: Some(format!("{name}_{column}")),
+                }));
+            }
+            expr if is_time_ref(expr) => {}
+            _ => expanded.push(field.clone()),
+        }
+    }
+    expanded
+}
+
+/// Returns the output column names of the fields, named like InfluxDB does
+/// and deduplicated by `_<n>` suffixes.
+fn output_names(fields: &[Field]) -> Vec<String> {
+    let mut seen = HashSet::from([TIME_COLUMN.to_string()]);
+    fields
+        .iter()
+        .map(|field| {
+            let name = field.alias.clone().unwrap_or_else(|| {
+                let mut names = vec![];
+                collect_names(&field.expr, &mut names);
+                if names.is_empty() {
+                    "expr".to_string()
+                } else {
+                    names.join("_")
+                }
+            });
+            let mut unique = name.clone();
+            let mut suffix = 1;
+            while !seen.insert(unique.clone()) {
+                unique = format!("{name}_{suffix}");
+                suffix += 1;
+            }
+            unique
+        })
+        .collect()
+}
+
+fn collect_names(expr: &ast::Expr, names: &mut Vec<String>) {
+    match expr {
+        ast::Expr::VarRef(name) | ast::Expr::Call { name, .. } => names.push(name.clone()),
+        ast::Expr::Binary { lhs, rhs, .. } => {
+            collect_names(lhs, names);
+            collect_names(rhs, names);
+        }
+        _ => {}
+    }
+}
+
+fn contains_call(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::Call { .. } => true,
+        ast::Expr::Binary { lhs, rhs, .. } => contains_call(lhs) || contains_call(rhs),
+        _ => false,
+    }
+}
+
+fn contains_var_outside_call(expr: &ast::Expr) -> bool {
+    match expr {
+        ast::Expr::VarRef(_) | ast::Expr::Wildcard => true,
+        ast::Expr::Binary { lhs, rhs, .. } => {
+            contains_var_outside_call(lhs) || contains_var_outside_call(rhs)
+        }
+        _ => false,
+    }
+}
+
+fn name_match_expr(column: Expr, name: &NameMatch) -> Expr {
+    match name {
+        NameMatch::Eq(value) => column.eq(lit(value.as_str())),
+        NameMatch::NotEq(value) => column.not_eq(lit(value.as_str())),
+        NameMatch::Regex(regex) => binary_expr(column, Operator::RegexMatch, lit(regex.as_str())),
+        NameMatch::NotRegex(regex) => {
+            binary_expr(column, Operator::RegexNotMatch, lit(regex.as_str()))
+        }
+        NameMatch::In(values) => column.in_list(
+            values.iter().map(|value| lit(value.as_str())).collect(),
+            false,
+        ),
+    }
+}
+
+fn name_matches(name_match: &NameMatch, name: &str) -> Result<bool> {
+    let matched = match name_match {
+        NameMatch::Eq(value) => value == name,
+        NameMatch::NotEq(value) => value != name,
+        NameMatch::Regex(regex) | NameMatch::NotRegex(regex) => {
+            let is_match = Regex::new(regex)
+                .map_err(|e| {
+                    InvalidStatementSnafu {
+                        reason: format!("invalid regex {regex}: {e}"),
+                    }
+                    .build()
+                })?
+                .is_match(name);
+            is_match == matches!(name_match, NameMatch::Regex(_))
+        }
+        NameMatch::In(values) => values.iter().any(|value| value == name),
+    };
+    Ok(matched)
+}
+
+/// Sorts the result by series and time, then applies `LIMIT` and `OFFSET` to each series.
+fn sort_and_limit(
+    builder: LogicalPlanBuilder,
+    series_columns: &[String],
+    order_desc: bool,
+    limit: &Limit,
+) -> Result<LogicalPlan> {
+    let series_columns = series_columns
+        .iter()
+        .map(String::as_str)
+        .collect::<Vec<_>>();
+    let time_order = ident(TIME_COLUMN).sort(!order_desc, false);
+    sort_and_limit_with(builder, &series_columns, vec![time_order], limit)
+}
+
+fn sort_and_limit_by(
+    builder: LogicalPlanBuilder,
+    series_columns: &[&str],
+    order_columns: &[&str],
+    limit: &Limit,
+) -> Result<LogicalPlan> {
+    let order_by = order_columns
+        .iter()
+        .map(|column| ident(*column).sort(true, false))
+        .collect();
+    sort_and_limit_with(builder, series_columns, order_by, limit)
+}
+
+fn sort_and_limit_with(
+    builder: LogicalPlanBuilder,
+    series_columns: &[&str],
+    order_by: Vec<SortExpr>,
+    limit: &Limit,
+) -> Result<LogicalPlan> {
+    let mut sort_exprs = series_columns
+        .iter()
+        .map(|column| ident(*column).sort(true, false))
+        .collect::<Vec<_>>();
+    sort_exprs.extend(order_by.iter().cloned());
+
+    if limit.limit.is_none() && limit.offset.is_none() {
+        return builder
+            .sort(sort_exprs)
+            .and_then(|builder| builder.build())
+            .context(DataFusionPlanningSnafu);
+    }
+    let offset = limit.offset.unwrap_or(0);
+    if series_columns.is_empty() {
+        return builder
+            .sort(sort_exprs)
+            .and_then(|builder| {
+                builder.limit(offset as usize, limit.limit.map(|limit| limit as usize))
+            })
+            .and_then(|builder| builder.build())
+            .context(DataFusionPlanningSnafu);
+    }
+
+    let columns = builder
+        .schema()
+        .columns()
+        .into_iter()
+        .map(Expr::Column)
+        .collect::<Vec<_>>();
+    let row_number = row_number()
+        .partition_by(series_columns.iter().map(|column| ident(*column)).collect())
+        .order_by(order_by)
+        .build()
+        .context(DataFusionPlanningSnafu)?;
+    let mut filter = ident(ROW_NUMBER_COLUMN).gt(lit(offset));
+    if let Some(limit) = limit.limit {
+        filter = filter.and(ident(ROW_NUMBER_COLUMN).lt_eq(lit(offset + limit)));
+    }
+    builder
+        .window(vec![row_number.alias(ROW_NUMBER_COLUMN)])
+        .and_then(|builder| builder.filter(filter))
+        .and_then(|builder| builder.project(columns))
+        .and_then(|builder| builder.sort(sort_exprs))
+        .and_then(|builder| builder.build())
+        .context(DataFusionPlanningSnafu)
+}
+
+#[cfg(test)]
+mod tests {
+    use catalog::RegisterTableRequest;
+    use catalog::memory::MemoryCatalogManager;
+    use common_catalog::consts::DEFAULT_CATALOG_NAME;
+    use common_query::test_util::DummyDecoder;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use session::context::QueryContext;
+    use table::metadata::{TableInfoBuilder, TableMetaBuilder};
+    use table::test_util::EmptyTable;
+
+    use super::*;
+    use crate::influxql::parser::parse_statements;
+
+    /// Registers `public.cpu` with tags `host` and `region`.
+    fn build_test_table_provider() -> DfTableSourceProvider {
+        let columns = vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("region", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new("usage_idle", ConcreteDataType::float64_datatype(), true),
+            ColumnSchema::new("usage_user", ConcreteDataType::int64_datatype(), true),
+            ColumnSchema::new(
+                "greptime_timestamp",
+                ConcreteDataType::timestamp_nanosecond_datatype(),
+                false,
+            )
+            .with_time_index(true),
+        ];
+        let table_meta = TableMetaBuilder::empty()
+            .schema(Arc::new(Schema::new(columns)))
+            .primary_key_indices(vec![0, 1])
+            .value_indices(vec![2, 3])
+            .next_column_id(1024)
+            .build()
+            .unwrap();
+        let table_info = TableInfoBuilder::default()
+            .name("cpu")
+            .meta(table_meta)
+            .build()
+            .unwrap();
+
+        let catalog_list = MemoryCatalogManager::with_default_setup();
+        catalog_list
+            .register_table_sync(RegisterTableRequest {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: "public".to_string(),
+                table_name: "cpu".to_string(),
+                table_id: 1024,
+                table: EmptyTable::from_table_info(&table_info),
+            })
+            .unwrap();
+
+        DfTableSourceProvider::new(
+            catalog_list,
+            false,
+            QueryContext::arc(),
+            DummyDecoder::arc(),
+            false,
+        )
+    }
+
+    async fn plan(query: &str) -> Result<InfluxqlPlan> {
+        let stmt = parse_statements(query).unwrap().remove(0);
+        let mut planner = InfluxqlPlanner::new(build_test_table_provider(), QueryContext::arc());
+        planner.statement_to_plan(stmt).await
+    }
+
+    fn field_names(plan: &InfluxqlPlan) -> Vec<String> {
+        plan.plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_plan_raw_select() {
+        let plan = plan("SELECT usage_idle, usage_idle * 2 FROM cpu WHERE host = 'a' LIMIT 10")
+            .await
+            .unwrap();
+        assert_eq!(
+            vec!["time", "usage_idle", "usage_idle_1"],
+            field_names(&plan)
+        );
+        assert_eq!(
+            SeriesLayout {
+                name: SeriesName::Fixed("cpu".to_string()),
+                tag_columns: vec![],
+            },
+            plan.layout
+        );
+
+        let plan = plan("SELECT * FROM cpu GROUP BY region").await.unwrap();
+        assert_eq!(
+            vec!["region", "time", "host", "usage_idle", "usage_user"],
+            field_names(&plan)
+        );
+        assert_eq!(vec!["region".to_string()], plan.layout.tag_columns);
+    }
+
+    #[tokio::test]
+    async fn test_plan_group_by_time() {
+        let plan = plan(
+            "SELECT mean(usage_idle), spread(usage_user) AS s FROM cpu \
+             WHERE time > now() - 1h GROUP BY time(1m), host fill(linear) LIMIT 5",
+        )
+        .await
+        .unwrap();
+        assert_eq!(vec!["host", "time", "mean", "s"], field_names(&plan));
+        assert_eq!(vec!["host".to_string()], plan.layout.tag_columns);
+        let display = plan.plan.display_indent().to_string();
+        assert!(display.contains("RangeSelect"), "{display}");
+        assert!(display.contains(ROW_NUMBER_COLUMN), "{display}");
+    }
+
+    #[tokio::test]
+    async fn test_plan_aggregate_without_time() {
+        let plan = plan("SELECT count(*) FROM cpu WHERE time >= '2024-01-01T00:00:00Z'")
+            .await
+            .unwrap();
+        assert_eq!(
+            vec!["time", "count_usage_idle", "count_usage_user"],
+            field_names(&plan)
+        );
+        let display = plan.plan.display_indent().to_string();
+        assert!(display.contains("1704067200000000000"), "{display}");
+    }
+
+    #[tokio::test]
+    async fn test_plan_show_tag_values() {
+        let plan = plan("SHOW TAG VALUES FROM cpu WITH KEY =~ /reg/")
+            .await
+            .unwrap();
+        assert_eq!(vec!["key", "value"], field_names(&plan));
+        let display = plan.plan.display_indent().to_string();
+        assert!(display.contains("region"), "{display}");
+        assert!(!display.contains("host"), "{display}");
+    }
+
+    #[tokio::test]
+    async fn test_plan_invalid_select() {
+        for query in [
+            "SELECT mean(usage_idle), host FROM cpu",
+            "SELECT usage_idle FROM cpu GROUP BY time(1m)",
+            "SELECT foo(usage_idle) FROM cpu",
+            "SELECT usage_idle FROM not_exist",
+        ] {
+            assert!(plan(query).await.is_err(), "{query}");
+        }
+    }
+}
@@ -24,6 +24,7 @@ pub mod dist_plan;
 pub mod dummy_catalog;
 pub mod error;
 pub mod executor;
+pub mod influxql;
 pub mod log_query;
 pub mod metrics;
 pub mod optimizer;
@@ -73,6 +73,13 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid range aggregation: {reason}"))]
+    InvalidRangeAggr {
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display("Unexpected log expression: {expr:?}, expected {expected}"))]
     UnexpectedLogExpr {
         expr: LogExpr,
@@ -92,6 +99,7 @@ impl ErrorExt for Error {
             Unimplemented { .. } => StatusCode::Unsupported,
             UnknownAggregateFunction { .. }
             | UnknownScalarFunction { .. }
+            | InvalidRangeAggr { .. }
             | UnexpectedLogExpr { .. } => StatusCode::InvalidArguments,
         }
     }
@@ -12,28 +12,33 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use arrow_schema::{DataType, Schema as ArrowSchema};
+use arrow_schema::{DataType, Schema as ArrowSchema, TimeUnit};
 use catalog::table_source::DfTableSourceProvider;
 use common_function::utils::escape_like_pattern;
 use datafusion::datasource::DefaultTableSource;
 use datafusion::execution::SessionState;
+use datafusion::functions_aggregate::expr_fn::count;
 use datafusion_common::{DFSchema, ScalarValue};
 use datafusion_expr::utils::{conjunction, disjunction};
 use datafusion_expr::{
-    BinaryExpr, Expr, ExprSchemable, LogicalPlan, LogicalPlanBuilder, Operator, col, lit, not,
+    BinaryExpr, Expr, ExprSchemable, LogicalPlan, LogicalPlanBuilder, Operator, cast, col, lit,
+    not, when,
 };
 use datafusion_sql::TableReference;
 use datatypes::schema::Schema;
 use log_query::{AggFunc, BinaryOperator, EqualValue, LogExpr, LogQuery, TimeFilter};
-use snafu::{OptionExt, ResultExt};
+use snafu::{OptionExt, ResultExt, ensure};
 use table::table::adapter::DfTableProviderAdapter;
 
 use crate::log_query::error::{
-    CatalogSnafu, DataFusionPlanningSnafu, Result, TimeIndexNotFoundSnafu, UnexpectedLogExprSnafu,
-    UnimplementedSnafu, UnknownAggregateFunctionSnafu, UnknownScalarFunctionSnafu,
-    UnknownTableSnafu,
+    CatalogSnafu, DataFusionPlanningSnafu, InvalidRangeAggrSnafu, Result, TimeIndexNotFoundSnafu,
+    UnexpectedLogExprSnafu, UnimplementedSnafu, UnknownAggregateFunctionSnafu,
+    UnknownScalarFunctionSnafu, UnknownTableSnafu,
 };
 
+/// The maximum number of steps a row can be counted in by a range aggregation.
+const MAX_RANGE_AGGR_OFFSETS: i64 = 1_000;
+
 pub struct LogQueryPlanner {
     table_provider: DfTableSourceProvider,
     session_state: SessionState,
@@ -98,8 +103,18 @@ impl LogQueryPlanner {
         }
 
         // Apply log expressions
-        for expr in &query.exprs {
-            plan_builder = self.process_log_expr(plan_builder, expr)?;
+        let mut exprs = query.exprs.iter().peekable();
+        while let Some(expr) = exprs.next() {
+            if matches!(expr, LogExpr::Sort { .. }) {
+                // Consecutive sorts make up a single sort, earlier keys taking precedence.
+                let mut sorts = vec![expr];
+                while let Some(sort) = exprs.next_if(|expr| matches!(expr, LogExpr::Sort { .. })) {
+                    sorts.push(sort);
+                }
+                plan_builder = self.build_sort(plan_builder, &sorts)?;
+            } else {
+                plan_builder = self.process_log_expr(plan_builder, expr)?;
+            }
         }
 
         // Apply pagination to the final result after all log expressions.
@@ -225,10 +240,11 @@ impl LogQueryPlanner {
             log_query::ContentFilter::Contains(value) => Ok(Some(col_expr.like(lit(
                 ScalarValue::Utf8(Some(format!("%{}%", escape_like_pattern(value)))),
             )))),
-            log_query::ContentFilter::Regex(_pattern) => Err(UnimplementedSnafu {
-                feature: "regex filter",
-            }
-            .build()),
+            log_query::ContentFilter::Regex(pattern) => Ok(Some(Expr::BinaryExpr(BinaryExpr {
+                left: Box::new(col_expr),
+                op: Operator::RegexMatch,
+                right: Box::new(lit(ScalarValue::Utf8(Some(pattern.clone())))),
+            }))),
             log_query::ContentFilter::Exist => Ok(Some(col_expr.is_not_null())),
             log_query::ContentFilter::Between {
                 start,
@@ -363,13 +379,15 @@ impl LogQueryPlanner {
                 let df_expr = self.log_expr_to_df_expr(expr, schema)?;
                 Ok(df_expr.alias(alias))
             }
-            LogExpr::AggrFunc { .. } | LogExpr::Filter { .. } | LogExpr::Decompose { .. } => {
-                UnexpectedLogExprSnafu {
-                    expr: expr.clone(),
-                    expected: "not a typical expression",
-                }
-                .fail()
+            LogExpr::AggrFunc { .. }
+            | LogExpr::Filter { .. }
+            | LogExpr::Decompose { .. }
+            | LogExpr::Sort { .. }
+            | LogExpr::RangeAggr { .. } => UnexpectedLogExprSnafu {
+                expr: expr.clone(),
+                expected: "not a typical expression",
             }
+            .fail(),
         }
     }
 
@@ -542,14 +560,251 @@ impl LogQueryPlanner {
                     .project([binary_expr])
                     .context(DataFusionPlanningSnafu)?;
             }
+            LogExpr::Decompose {
+                expr,
+                schema: format,
+                fields,
+            } => {
+                plan_builder = self.build_decompose(plan_builder, expr, format, fields)?;
+            }
+            LogExpr::Sort { .. } => {
+                plan_builder = self.build_sort(plan_builder, &[expr])?;
+            }
+            LogExpr::RangeAggr { .. } => {
+                plan_builder = self.build_range_aggr(plan_builder, expr)?;
+            }
+        }
+        Ok(plan_builder)
+    }
+
+    /// Sorts by the keys of all the given [`LogExpr::Sort`], each in its own order.
+    fn build_sort(
+        &self,
+        plan_builder: LogicalPlanBuilder,
+        sorts: &[&LogExpr],
+    ) -> Result<LogicalPlanBuilder> {
+        let schema = plan_builder.schema();
+        let mut sort_exprs = vec![];
+        for sort in sorts {
+            let LogExpr::Sort { by, descending } = sort else {
+                continue;
+            };
+            for expr in by {
+                let expr = self.log_expr_to_df_expr(expr, schema)?;
+                sort_exprs.push(expr.sort(!descending, *descending));
+            }
+        }
+        plan_builder
+            .sort(sort_exprs)
+            .context(DataFusionPlanningSnafu)
+    }
+
+    /// Projects the extracted `fields` next to the existing columns. A field
+    /// replaces the column of the same name, if any.
+    fn build_decompose(
+        &self,
+        plan_builder: LogicalPlanBuilder,
+        expr: &LogExpr,
+        format: &str,
+        fields: &[(String, String)],
+    ) -> Result<LogicalPlanBuilder> {
+        let function_name = match format {
+            "json" => "json_field",
+            "logfmt" => "logfmt_field",
             _ => {
-                UnimplementedSnafu {
-                    feature: "log expression",
+                return UnimplementedSnafu {
+                    feature: format!("decomposing `{format}`"),
                 }
-                .fail()?;
+                .fail();
             }
+        };
+        let func = self
+            .session_state
+            .scalar_functions()
+            .get(function_name)
+            .context(UnknownScalarFunctionSnafu {
+                name: function_name.to_string(),
+            })?;
+
+        let schema = plan_builder.schema();
+        let value = self.log_expr_to_df_expr(expr, schema)?;
+        let mut projection = schema
+            .columns()
+            .into_iter()
+            .filter(|column| !fields.iter().any(|(name, _)| *name == column.name))
+            .map(Expr::Column)
+            .collect::<Vec<_>>();
+        for (name, data_type) in fields {
+            ensure!(
+                data_type == "string",
+                UnimplementedSnafu {
+                    feature: format!("decomposing field `{name}` as `{data_type}`"),
+                }
+            );
+            projection.push(
+                func.call(vec![
+                    value.clone(),
+                    lit(ScalarValue::Utf8(Some(name.clone()))),
+                ])
+                .alias(name),
+            );
         }
-        Ok(plan_builder)
+
+        plan_builder
+            .project(projection)
+            .context(DataFusionPlanningSnafu)
+    }
+
+    /// Plans a [`LogExpr::RangeAggr`].
+    ///
+    /// A row at `ts` belongs to every step `start + k * step` in `[ts, ts + range)`.
+    /// Each row is paired with the offsets `0..ceil(range / step)` to enumerate
+    /// those steps from the last one, `k_hi = (ts - (start - range) - 1) / step`,
+    /// and the pairs are then counted per group and step.
+    ///
+    /// A row never belongs to more steps than the query evaluates, so the offsets
+    /// are capped by the number of steps, and requests that would still pair each
+    /// row with more than [`MAX_RANGE_AGGR_OFFSETS`] offsets are rejected.
+    fn build_range_aggr(
+        &self,
+        plan_builder: LogicalPlanBuilder,
+        expr: &LogExpr,
+    ) -> Result<LogicalPlanBuilder> {
+        let LogExpr::RangeAggr {
+            name,
+            timestamp,
+            by,
+            start,
+            end,
+            step,
+            range,
+            alias,
+        } = expr
+        else {
+            unreachable!("expected range aggregation");
+        };
+        let (start, end, step, range) = (*start, *end, *step, *range);
+        ensure!(
+            step > 0 && range > 0 && end >= start,
+            InvalidRangeAggrSnafu {
+                reason: format!(
+                    "expected positive step and range, and end not before start, got start={start}, end={end}, step={step}, range={range}"
+                ),
+            }
+        );
+        let LogExpr::NamedIdent(timestamp_name) = timestamp.as_ref() else {
+            return UnexpectedLogExprSnafu {
+                expr: timestamp.as_ref().clone(),
+                expected: "timestamp column name",
+            }
+            .fail();
+        };
+        let rate = match name.as_str() {
+            "count_over_time" => false,
+            "rate" => true,
+            _ => {
+                return UnknownAggregateFunctionSnafu { name: name.clone() }.fail();
+            }
+        };
+
+        const TS_MS: &str = "__range_ts";
+        const LAST_STEP: &str = "__range_last_step";
+        const OFFSET: &str = "__range_offset";
+        const STEP: &str = "__range_step";
+        const COUNT: &str = "__range_count";
+
+        let window_start = start - range;
+        let schema = plan_builder.schema();
+        let by_exprs = by
+            .iter()
+            .map(|expr| self.log_expr_to_df_expr(expr, schema))
+            .try_collect::<Vec<_>>()?;
+        let by_names = by_exprs
+            .iter()
+            .map(|expr| expr.schema_name().to_string())
+            .collect::<Vec<_>>();
+
+        let ts_ms = cast(
+            cast(
+                col(timestamp_name),
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+            ),
+            DataType::Int64,
+        );
+        // The last step whose range still covers the row, clamped to the last step of the
+        // query so that rows are never expanded into steps past `end`.
+        let last_step = (end - start) / step;
+        let row_last_step = (ts_ms.clone() - lit(window_start) - lit(1i64)) / lit(step);
+        let row_last_step = when(row_last_step.clone().gt(lit(last_step)), lit(last_step))
+            .otherwise(row_last_step)
+            .context(DataFusionPlanningSnafu)?;
+        let mut projection = by_exprs.clone();
+        projection.push(row_last_step.alias(LAST_STEP));
+        projection.push(ts_ms.clone().alias(TS_MS));
+        let plan_builder = plan_builder
+            .filter(
+                ts_ms
+                    .clone()
+                    .gt(lit(window_start))
+                    .and(ts_ms.lt_eq(lit(end))),
+            )
+            .context(DataFusionPlanningSnafu)?
+            .project(projection)
+            .context(DataFusionPlanningSnafu)?;
+
+        let num_offsets = ((range + step - 1) / step).min(last_step + 1);
+        ensure!(
+            num_offsets <= MAX_RANGE_AGGR_OFFSETS,
+            InvalidRangeAggrSnafu {
+                reason: format!(
+                    "range {range}ms with step {step}ms spans more than {MAX_RANGE_AGGR_OFFSETS} steps, try increasing the step"
+                ),
+            }
+        );
+        let offsets =
+            LogicalPlanBuilder::values((0..num_offsets).map(|offset| vec![lit(offset)]).collect())
+                .context(DataFusionPlanningSnafu)?
+                .project([col("column1").alias(OFFSET)])
+                .context(DataFusionPlanningSnafu)?
+                .build()
+                .context(DataFusionPlanningSnafu)?;
+
+        let step_index = col(LAST_STEP) - col(OFFSET);
+        let mut group_exprs = by_names.iter().map(col).collect::<Vec<_>>();
+        group_exprs.push(step_index.clone().alias(STEP));
+        let plan_builder = plan_builder
+            .cross_join(offsets)
+            .context(DataFusionPlanningSnafu)?
+            .filter(
+                step_index
+                    .clone()
+                    .gt_eq(lit(0i64))
+                    .and(step_index.clone().lt_eq(lit(last_step)))
+                    .and((lit(start) + step_index * lit(step)).gt_eq(col(TS_MS))),
+            )
+            .context(DataFusionPlanningSnafu)?
+            .aggregate(group_exprs, [count(lit(1i64)).alias(COUNT)])
+            .context(DataFusionPlanningSnafu)?;
+
+        let value = cast(col(COUNT), DataType::Float64);
+        let value = if rate {
+            value / lit(range as f64 / 1000.0)
+        } else {
+            value
+        };
+        let mut projection = by_names.iter().map(col).collect::<Vec<_>>();
+        projection.push(
+            cast(
+                lit(start) + col(STEP) * lit(step),
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+            )
+            .alias(timestamp_name),
+        );
+        projection.push(value.alias(alias.as_deref().unwrap_or(name)));
+
+        plan_builder
+            .project(projection)
+            .context(DataFusionPlanningSnafu)
     }
 }
 
@@ -560,17 +815,22 @@ mod tests {
     use catalog::RegisterTableRequest;
     use catalog::memory::MemoryCatalogManager;
     use common_catalog::consts::DEFAULT_CATALOG_NAME;
+    use common_function::function::FunctionContext;
+    use common_function::function_registry::FUNCTION_REGISTRY;
     use common_query::test_util::DummyDecoder;
+    use datafusion::arrow::array::{Float64Array, TimestampMillisecondArray};
     use datafusion::execution::SessionStateBuilder;
+    use datafusion::prelude::SessionContext;
     use datatypes::prelude::ConcreteDataType;
     use datatypes::schema::{ColumnSchema, SchemaRef};
+    use datatypes::vectors::{BooleanVector, StringVector, TimestampMillisecondVector};
     use log_query::{
         ColumnFilters, ConjunctionOperator, ContentFilter, Context, Filters, Limit, LogExpr,
     };
     use session::context::QueryContext;
     use table::metadata::{TableInfoBuilder, TableMetaBuilder};
     use table::table_name::TableName;
-    use table::test_util::EmptyTable;
+    use table::test_util::{EmptyTable, MemTable};
 
     use super::*;
 
@@ -1551,4 +1811,357 @@ mod tests {
         let expected_expr_debug = r#"BinaryExpr(BinaryExpr { left: BinaryExpr(BinaryExpr { left: Column(Column { relation: None, name: "age" }), op: Plus, right: Literal(Int32(5), None) }), op: Gt, right: Literal(Int32(30), None) })"#;
         assert_eq!(format!("{:?}", expr), expected_expr_debug);
     }
+
+    #[tokio::test]
+    async fn test_build_regex_filter() {
+        let table_provider =
+            build_test_table_provider(&[("public".to_string(), "test_table".to_string())]).await;
+        let session_state = SessionStateBuilder::new().with_default_features().build();
+        let planner = LogQueryPlanner::new(table_provider, session_state);
+        let schema = mock_schema();
+
+        let column_filter = ColumnFilters {
+            expr: Box::new(LogExpr::NamedIdent("message".to_string())),
+            filters: vec![ContentFilter::Regex("^err(or)?".to_string())],
+        };
+        let expr = planner
+            .build_column_filter(&column_filter, schema.arrow_schema())
+            .unwrap()
+            .unwrap();
+
+        let expected_expr = Expr::BinaryExpr(BinaryExpr {
+            left: Box::new(col("message")),
+            op: Operator::RegexMatch,
+            right: Box::new(lit(ScalarValue::Utf8(Some("^err(or)?".to_string())))),
+        });
+        assert_eq!(format!("{:?}", expr), format!("{:?}", expected_expr));
+    }
+
+    #[tokio::test]
+    async fn test_query_to_plan_with_decompose() {
+        let table_provider =
+            build_test_table_provider(&[("public".to_string(), "test_table".to_string())]).await;
+        let json_field = FUNCTION_REGISTRY
+            .get_function("json_field")
+            .unwrap()
+            .provide(FunctionContext::default());
+        let session_state = SessionStateBuilder::new()
+            .with_default_features()
+            .with_scalar_functions(vec![Arc::new(json_field)])
+            .build();
+        let mut planner = LogQueryPlanner::new(table_provider, session_state);
+
+        let log_query = LogQuery {
+            table: TableName::new(DEFAULT_CATALOG_NAME, "public", "test_table"),
+            time_filter: TimeFilter {
+                start: Some("2021-01-01T00:00:00Z".to_string()),
+                end: Some("2021-01-02T00:00:00Z".to_string()),
+                span: None,
+            },
+            filters: Filters::And(vec![]),
+            limit: Limit::default(),
+            context: Context::None,
+            columns: vec![],
+            exprs: vec![
+                LogExpr::Decompose {
+                    expr: Box::new(LogExpr::NamedIdent("message".to_string())),
+                    schema: "json".to_string(),
+                    fields: vec![
+                        ("level".to_string(), "string".to_string()),
+                        ("host".to_string(), "string".to_string()),
+                    ],
+                },
+                LogExpr::Sort {
+                    by: vec![LogExpr::NamedIdent("timestamp".to_string())],
+                    descending: true,
+                },
+            ],
+        };
+
+        let plan = planner.query_to_plan(log_query).await.unwrap();
+        let LogicalPlan::Sort(sort) = &plan else {
+            panic!("expected sort, got {plan}");
+        };
+        assert!(!sort.expr[0].asc);
+        // The extracted `host` replaces the original column.
+        let fields = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| (field.name().as_str(), field.data_type().clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                ("message", DataType::Utf8),
+                (
+                    "timestamp",
+                    DataType::Timestamp(TimeUnit::Millisecond, None)
+                ),
+                ("is_active", DataType::Boolean),
+                ("level", DataType::LargeUtf8),
+                ("host", DataType::LargeUtf8),
+            ]
+        );
+
+        let mut planner = LogQueryPlanner::new(
+            build_test_table_provider(&[("public".to_string(), "test_table".to_string())]).await,
+            SessionStateBuilder::new().with_default_features().build(),
+        );
+        let log_query = LogQuery {
+            table: TableName::new(DEFAULT_CATALOG_NAME, "public", "test_table"),
+            exprs: vec![LogExpr::Decompose {
+                expr: Box::new(LogExpr::NamedIdent("message".to_string())),
+                schema: "csv".to_string(),
+                fields: vec![("level".to_string(), "string".to_string())],
+            }],
+            ..Default::default()
+        };
+        assert!(matches!(
+            planner.query_to_plan(log_query).await,
+            Err(crate::log_query::error::Error::Unimplemented { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_query_to_plan_with_consecutive_sorts() {
+        let table_provider =
+            build_test_table_provider(&[("public".to_string(), "test_table".to_string())]).await;
+        let session_state = SessionStateBuilder::new().with_default_features().build();
+        let mut planner = LogQueryPlanner::new(table_provider, session_state);
+
+        let log_query = LogQuery {
+            table: TableName::new(DEFAULT_CATALOG_NAME, "public", "test_table"),
+            exprs: vec![
+                LogExpr::Sort {
+                    by: vec![LogExpr::NamedIdent("timestamp".to_string())],
+                    descending: true,
+                },
+                LogExpr::Sort {
+                    by: vec![LogExpr::NamedIdent("message".to_string())],
+                    descending: false,
+                },
+            ],
+            ..Default::default()
+        };
+
+        let plan = planner.query_to_plan(log_query).await.unwrap();
+        let LogicalPlan::Sort(sort) = &plan else {
+            panic!("expected sort, got {plan}");
+        };
+        assert!(!matches!(sort.input.as_ref(), LogicalPlan::Sort(_)));
+        let keys = sort
+            .expr
+            .iter()
+            .map(|sort| match &sort.expr {
+                Expr::Column(column) => (column.name.clone(), sort.asc),
+                expr => panic!("expected column, got {expr}"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            keys,
+            vec![
+                ("timestamp".to_string(), false),
+                ("message".to_string(), true),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_query_to_plan_with_range_aggr() {
+        let table_provider =
+            build_test_table_provider(&[("public".to_string(), "test_table".to_string())]).await;
+        let session_state = SessionStateBuilder::new().with_default_features().build();
+        let mut planner = LogQueryPlanner::new(table_provider, session_state);
+
+        let log_query = LogQuery {
+            table: TableName::new(DEFAULT_CATALOG_NAME, "public", "test_table"),
+            time_filter: TimeFilter {
+                start: Some("2021-01-01T00:00:00Z".to_string()),
+                end: Some("2021-01-02T00:00:00Z".to_string()),
+                span: None,
+            },
+            filters: Filters::And(vec![]),
+            limit: Limit::default(),
+            context: Context::None,
+            columns: vec![],
+            exprs: vec![
+                LogExpr::RangeAggr {
+                    name: "rate".to_string(),
+                    timestamp: Box::new(LogExpr::NamedIdent("timestamp".to_string())),
+                    by: vec![LogExpr::NamedIdent("host".to_string())],
+                    start: 1_609_459_200_000,
+                    end: 1_609_462_800_000,
+                    step: 60_000,
+                    range: 300_000,
+                    alias: Some("value".to_string()),
+                },
+                LogExpr::AggrFunc {
+                    expr: vec![AggFunc::new(
+                        "sum".to_string(),
+                        vec![LogExpr::NamedIdent("value".to_string())],
+                        Some("value".to_string()),
+                    )],
+                    by: vec![LogExpr::NamedIdent("timestamp".to_string())],
+                },
+            ],
+        };
+
+        let plan = planner.query_to_plan(log_query).await.unwrap();
+        let fields = plan
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| (field.name().as_str(), field.data_type().clone()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                (
+                    "timestamp",
+                    DataType::Timestamp(TimeUnit::Millisecond, None)
+                ),
+                ("value", DataType::Float64),
+            ]
+        );
+
+        let invalid = LogQuery {
+            table: TableName::new(DEFAULT_CATALOG_NAME, "public", "test_table"),
+            exprs: vec![LogExpr::RangeAggr {
+                name: "count_over_time".to_string(),
+                timestamp: Box::new(LogExpr::NamedIdent("timestamp".to_string())),
+                by: vec![],
+                start: 0,
+                end: 60_000,
+                step: 0,
+                range: 60_000,
+                alias: None,
+            }],
+            ..Default::default()
+        };
+        assert!(planner.query_to_plan(invalid).await.is_err());
+
+        // Each row would be paired with 3600 offsets.
+        let too_many_steps = LogQuery {
+            table: TableName::new(DEFAULT_CATALOG_NAME, "public", "test_table"),
+            exprs: vec![LogExpr::RangeAggr {
+                name: "count_over_time".to_string(),
+                timestamp: Box::new(LogExpr::NamedIdent("timestamp".to_string())),
+                by: vec![],
+                start: 0,
+                end: 86_400_000,
+                step: 1_000,
+                range: 3_600_000,
+                alias: None,
+            }],
+            ..Default::default()
+        };
+        assert!(planner.query_to_plan(too_many_steps).await.is_err());
+
+        // The offsets are capped by the number of steps.
+        let few_steps = LogQuery {
+            table: TableName::new(DEFAULT_CATALOG_NAME, "public", "test_table"),
+            exprs: vec![LogExpr::RangeAggr {
+                name: "count_over_time".to_string(),
+                timestamp: Box::new(LogExpr::NamedIdent("timestamp".to_string())),
+                by: vec![],
+                start: 0,
+                end: 60_000,
+                step: 1_000,
+                range: 3_600_000,
+                alias: None,
+            }],
+            ..Default::default()
+        };
+        assert!(planner.query_to_plan(few_steps).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_range_aggr_with_range_larger_than_query() {
+        let schema = mock_schema();
+        let recordbatch = common_recordbatch::RecordBatch::new(
+            schema.clone(),
+            vec![
+                Arc::new(StringVector::from(vec!["error"])) as _,
+                Arc::new(TimestampMillisecondVector::from_slice([30_000_i64])) as _,
+                Arc::new(StringVector::from(vec!["host1"])) as _,
+                Arc::new(BooleanVector::from(vec![true])) as _,
+            ],
+        )
+        .unwrap();
+        let catalog_list = MemoryCatalogManager::with_default_setup();
+        catalog_list
+            .register_table_sync(RegisterTableRequest {
+                catalog: DEFAULT_CATALOG_NAME.to_string(),
+                schema: "public".to_string(),
+                table_name: "test_table".to_string(),
+                table_id: 1024,
+                table: MemTable::table("test_table", recordbatch),
+            })
+            .unwrap();
+        let table_provider = DfTableSourceProvider::new(
+            catalog_list,
+            false,
+            QueryContext::arc(),
+            DummyDecoder::arc(),
+            false,
+        );
+        let session_state = SessionStateBuilder::new().with_default_features().build();
+        let mut planner = LogQueryPlanner::new(table_provider, session_state.clone());
+
+        // The one hour range covers the row from its timestamp until the end of the query.
+        let log_query = LogQuery {
+            table: TableName::new(DEFAULT_CATALOG_NAME, "public", "test_table"),
+            time_filter: TimeFilter {
+                start: Some("1970-01-01T00:00:00Z".to_string()),
+                end: Some("1970-01-01T00:01:01Z".to_string()),
+                span: None,
+            },
+            exprs: vec![LogExpr::RangeAggr {
+                name: "count_over_time".to_string(),
+                timestamp: Box::new(LogExpr::NamedIdent("timestamp".to_string())),
+                by: vec![],
+                start: 0,
+                end: 60_000,
+                step: 1_000,
+                range: 3_600_000,
+                alias: Some("value".to_string()),
+            }],
+            ..Default::default()
+        };
+        let plan = planner.query_to_plan(log_query).await.unwrap();
+        let batches = SessionContext::new_with_state(session_state)
+            .execute_logical_plan(plan)
+            .await
+            .unwrap()
+            .collect()
+            .await
+            .unwrap();
+
+        let mut points = Vec::new();
+        for batch in &batches {
+            let timestamps = batch
+                .column_by_name("timestamp")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<TimestampMillisecondArray>()
+                .unwrap();
+            let values = batch
+                .column_by_name("value")
+                .unwrap()
+                .as_any()
+                .downcast_ref::<Float64Array>()
+                .unwrap();
+            points.extend(
+                timestamps
+                    .values()
+                    .iter()
+                    .copied()
+                    .zip(values.values().iter().copied()),
+            );
+        }
+        points.sort_by_key(|(ts, _)| *ts);
+        let expected = (30..=60).map(|s| (s * 1_000, 1.0)).collect::<Vec<_>>();
+        assert_eq!(points, expected);
+    }
 }
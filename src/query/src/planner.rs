@@ -51,6 +51,9 @@ use crate::error::{
     CteColumnSchemaMismatchSnafu, PlanSqlSnafu, QueryPlanSnafu, Result, SqlSnafu,
     UnimplementedSnafu,
 };
+use crate::influxql::InfluxqlPlan;
+use crate::influxql::ast::Statement as InfluxqlStatement;
+use crate::influxql::planner::InfluxqlPlanner;
 use crate::log_query::planner::LogQueryPlanner;
 use crate::parser::{DEFAULT_LOOKBACK_STRING, PromQuery, QueryLanguageParser, QueryStatement};
 use crate::promql::planner::PromPlanner;
@@ -68,6 +71,12 @@ pub trait LogicalPlanner: Send + Sync {
         query_ctx: QueryContextRef,
     ) -> Result<LogicalPlan>;
 
+    async fn plan_influxql(
+        &self,
+        stmt: InfluxqlStatement,
+        query_ctx: QueryContextRef,
+    ) -> Result<InfluxqlPlan>;
+
     fn optimize(&self, plan: LogicalPlan) -> Result<LogicalPlan>;
 
     fn as_any(&self) -> &dyn Any;
@@ -647,6 +656,34 @@ impl LogicalPlanner for DfLogicalPlanner {
             .context(QueryPlanSnafu)
     }
 
+    async fn plan_influxql(
+        &self,
+        stmt: InfluxqlStatement,
+        query_ctx: QueryContextRef,
+    ) -> Result<InfluxqlPlan> {
+        let plan_decoder = Arc::new(DefaultPlanDecoder::new(
+            self.session_state.clone(),
+            &query_ctx,
+        )?);
+        let table_provider = DfTableSourceProvider::new(
+            self.engine_state.catalog_manager().clone(),
+            self.engine_state.disallow_cross_catalog_query(),
+            query_ctx.clone(),
+            plan_decoder,
+            self.session_state
+                .config_options()
+                .sql_parser
+                .enable_ident_normalization,
+        );
+
+        let mut planner = InfluxqlPlanner::new(table_provider, query_ctx);
+        planner
+            .statement_to_plan(stmt)
+            .await
+            .map_err(BoxedError::new)
+            .context(QueryPlanSnafu)
+    }
+
     fn optimize(&self, plan: LogicalPlan) -> Result<LogicalPlan> {
         self.optimize_logical_plan(plan)
     }
@@ -36,7 +36,7 @@ use datafusion::functions_aggregate::sum::sum_udaf;
 use datafusion::functions_aggregate::variance::var_pop_udaf;
 use datafusion::functions_window::row_number::RowNumber;
 use datafusion::logical_expr::expr::{Alias, ScalarFunction, WindowFunction};
-use datafusion::logical_expr::expr_rewriter::normalize_cols;
+use datafusion::logical_expr::expr_rewriter::{normalize_col, normalize_cols};
 use datafusion::logical_expr::{
     BinaryExpr, Cast, Extension, LogicalPlan, LogicalPlanBuilder, Operator,
     ScalarUDF as ScalarUdfDef, WindowFrame, WindowFunctionDefinition,
@@ -54,6 +54,7 @@ use datafusion_expr::{
     ExprSchemable, Literal, Projection, SortExpr, TableScan, TableSource, col, lit,
 };
 use datafusion_functions::core::coalesce;
+use datafusion_functions::math::expr_fn::{floor, isnan};
 use datatypes::arrow::datatypes::{DataType as ArrowDataType, TimeUnit as ArrowTimeUnit};
 use datatypes::data_type::{ConcreteDataType, DataType as GreptimeDataType};
 use itertools::Itertools;
@@ -64,7 +65,7 @@ use promql::extension_plan::{
 };
 use promql::functions::{
     AbsentOverTime, AvgOverTime, Changes, CountOverTime, Delta, Deriv, DoubleExponentialSmoothing,
-    IDelta, Increase, LastOverTime, MaxOverTime, MinOverTime, MixedRange,
+    IDelta, Increase, LastOverTime, MatchGroupCheck, MaxOverTime, MinOverTime, MixedRange,
     NativeHistogramAbsentOverTime, NativeHistogramAdd, NativeHistogramAggAvg,
     NativeHistogramAggSum, NativeHistogramAvg, NativeHistogramAvgOverTime, NativeHistogramChanges,
     NativeHistogramCount, NativeHistogramCountOverTime, NativeHistogramDelta,
@@ -75,7 +76,8 @@ use promql::functions::{
     NativeHistogramRate, NativeHistogramResets, NativeHistogramScalarMul, NativeHistogramStddev,
     NativeHistogramStdvar, NativeHistogramSub, NativeHistogramSum, NativeHistogramSumOverTime,
     NativeHistogramToString, PredictLinear, PresentOverTime, PromqlFloatToString, QuantileOverTime,
-    Rate, Resets, Round, StddevOverTime, StdvarOverTime, SumOverTime, quantile_udaf,
+    Rate, Resets, Round, StddevOverTime, StdvarOverTime, SumOverTime, UniqueMatchGroup,
+    quantile_udaf,
 };
 use promql_parser::label::{METRIC_NAME, MatchOp, Matcher, Matchers};
 use promql_parser::parser::token::TokenType;
@@ -145,6 +147,9 @@ const BINARY_ISLAND_LEAF_ALIAS_PREFIX: &str = "__prom_v";
 const OR_FLOAT_FIELD_PREFIX: &str = "__promql_or_float_";
 const OR_HISTOGRAM_FIELD_PREFIX: &str = "__promql_or_histogram_";
 const TIMESTAMP_VALUE_PREFIX: &str = "__promql_timestamp_value_";
+/// Columns of a non-literal `k` parameter joined onto the input of `topk`/`bottomk`.
+const TOPK_PARAM_TIME_COLUMN: &str = "__promql_topk_param_time";
+const TOPK_PARAM_VALUE_COLUMN: &str = "__promql_topk_param";
 
 /// Threshold for scatter scan mode
 const MAX_SCATTER_POINTS: i64 = 400;
@@ -639,7 +644,8 @@ impl PromPlanner {
 
         match (*op).id() {
             token::T_TOPK | token::T_BOTTOMK => {
-                self.prom_topk_bottomk_to_plan(aggr_expr, input).await
+                self.prom_topk_bottomk_to_plan(aggr_expr, input, query_engine_state)
+                    .await
             }
             _ => {
                 // When `__tsid` is available, tag columns may have been pruned from the input plan.
@@ -793,6 +799,7 @@ impl PromPlanner {
         &mut self,
         aggr_expr: &AggregateExpr,
         input: LogicalPlan,
+        query_engine_state: &QueryEngineState,
     ) -> Result<LogicalPlan> {
         let AggregateExpr {
             op,
@@ -856,7 +863,18 @@ impl PromPlanner {
                 .context(DataFusionPlanningSnafu);
         }
 
-        let val = Self::get_param_as_literal_expr(param, Some(*op), Some(ArrowDataType::Float64))?;
+        // A non-literal `k` (e.g. `scalar(...)`) is evaluated at every step and joined onto
+        // the input by timestamp.
+        let (input, val) = match param.as_deref() {
+            Some(k) if Self::try_build_literal_expr(k).is_none() => {
+                self.join_topk_param(*op, input, k, query_engine_state)
+                    .await?
+            }
+            _ => (
+                input,
+                Self::get_param_as_literal_expr(param, Some(*op), Some(ArrowDataType::Float64))?,
+            ),
+        };
 
         // convert op and value columns to window exprs.
         let window_exprs = self.create_window_exprs(*op, group_exprs.clone(), &input)?;
@@ -924,6 +942,76 @@ impl PromPlanner {
             .context(DataFusionPlanningSnafu)
     }
 
+    /// Plan the non-literal `k` parameter of `topk`/`bottomk` and join it onto `input` by
+    /// timestamp. Returns the joined plan and the expression of the per-step `k`.
+    async fn join_topk_param(
+        &mut self,
+        op: TokenType,
+        input: LogicalPlan,
+        param: &PromExpr,
+        query_engine_state: &QueryEngineState,
+    ) -> Result<(LogicalPlan, DfExpr)> {
+        let input_ctx = self.ctx.clone();
+        let param_plan = self.prom_expr_to_plan(param, query_engine_state).await?;
+        let param_ctx = std::mem::replace(&mut self.ctx, input_ctx);
+
+        ensure!(
+            param_ctx.field_columns.len() == 1,
+            FunctionInvalidArgumentSnafu {
+                fn_name: op.to_string(),
+            }
+        );
+        let param_time_index =
+            param_ctx
+                .time_index_column
+                .clone()
+                .with_context(|| TimeIndexNotFoundSnafu {
+                    table: param_ctx.table_name.clone().unwrap_or_default(),
+                })?;
+        let time_index =
+            self.ctx
+                .time_index_column
+                .clone()
+                .with_context(|| TimeIndexNotFoundSnafu {
+                    table: self.ctx.table_name.clone().unwrap_or_default(),
+                })?;
+
+        let param_value = DfExpr::Cast(Cast {
+            expr: Box::new(DfExpr::Column(Column::from_name(
+                &param_ctx.field_columns[0],
+            ))),
+            data_type: ArrowDataType::Float64,
+        });
+        // `k` is truncated to an integer. A NaN `k` selects nothing, like a `k` below 1.
+        let param_value = when(isnan(param_value.clone()), lit(0.0_f64))
+            .otherwise(floor(param_value))
+            .context(DataFusionPlanningSnafu)?;
+        let param_plan = LogicalPlanBuilder::from(param_plan)
+            .project(vec![
+                DfExpr::Column(Column::from_name(param_time_index)).alias(TOPK_PARAM_TIME_COLUMN),
+                param_value.alias(TOPK_PARAM_VALUE_COLUMN),
+            ])
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)?;
+
+        let plan = LogicalPlanBuilder::from(input)
+            .join(
+                param_plan,
+                JoinType::Inner,
+                (
+                    vec![Column::from_name(time_index)],
+                    vec![Column::from_name(TOPK_PARAM_TIME_COLUMN)],
+                ),
+                None,
+            )
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)?;
+
+        Ok((plan, col(TOPK_PARAM_VALUE_COLUMN)))
+    }
+
     async fn prom_unary_expr_to_plan(
         &mut self,
         query_engine_state: &QueryEngineState,
@@ -1272,18 +1360,6 @@ impl PromPlanner {
         query_engine_state: &QueryEngineState,
         binary_expr: &PromBinaryExpr,
     ) -> Result<LogicalPlan> {
-        // promql-parser accepts fill modifiers, but Greptime does not implement the
-        // required outer joins and missing-value substitution. Reject them before the
-        // binary-island fast path so they cannot silently behave like normal inner joins.
-        if let Some(modifier) = &binary_expr.modifier {
-            ensure!(
-                modifier.fill_values.lhs.is_none() && modifier.fill_values.rhs.is_none(),
-                UnsupportedExprSnafu {
-                    name: "PromQL fill modifiers"
-                }
-            );
-        }
-
         if let Some(plan) = self.try_plan_binary_island(binary_expr).await? {
             return Ok(plan);
         }
@@ -1496,8 +1572,18 @@ impl PromPlanner {
 
                 // TODO(ruihang): avoid join if left and right are the same table
 
+                let has_fill_values = modifier.as_ref().is_some_and(|modifier| {
+                    modifier.fill_values.lhs.is_some() || modifier.fill_values.rhs.is_some()
+                });
+
                 // set op has "special" join semantics
                 if Self::is_token_a_set_op(*op) {
+                    ensure!(
+                        !has_fill_values,
+                        UnsupportedExprSnafu {
+                            name: "PromQL fill modifiers on set operators"
+                        }
+                    );
                     return self.set_op_on_non_field_columns(
                         left_input,
                         right_input,
@@ -1515,6 +1601,13 @@ impl PromPlanner {
                     right_input.schema(),
                     &right_field_columns,
                 );
+                // Fill values are floats and can't stand in for a missing histogram sample.
+                ensure!(
+                    !(has_fill_values && has_native_histogram),
+                    UnsupportedExprSnafu {
+                        name: "PromQL fill modifiers on native histograms"
+                    }
+                );
 
                 // normal join
                 if left_table_ref == right_table_ref {
@@ -1626,6 +1719,46 @@ impl PromPlanner {
                     join_plan
                 };
 
+                // `group_left`/`group_right` output the labels of the "many" side, plus the
+                // labels listed in the modifier copied from the "one" side.
+                let mut group_table_ref = None;
+                let join_plan = if let Some((many_is_left, include_labels)) =
+                    Self::vector_match_group_labels(modifier)
+                {
+                    let (many_table_ref, one_table_ref, many_context) = if many_is_left {
+                        (&left_table_ref, &right_table_ref, &left_context)
+                    } else {
+                        (&right_table_ref, &left_table_ref, &right_context)
+                    };
+                    group_table_ref = Some(many_table_ref.clone());
+                    let join_plan = self.project_group_labels(
+                        join_plan,
+                        many_table_ref,
+                        one_table_ref,
+                        many_context,
+                        include_labels,
+                    )?;
+                    // Labels copied over labels of the "many" side may merge its series.
+                    if include_labels
+                        .iter()
+                        .any(|label| many_context.tag_columns.contains(label))
+                    {
+                        let qualified =
+                            |column: &String| Column::new(Some(many_table_ref.clone()), column);
+                        self.ensure_unique_match_group(
+                            join_plan,
+                            self.ctx.tag_columns.iter().map(qualified).collect(),
+                            many_context.time_index_column.as_ref().map(qualified),
+                            MatchGroupCheck::GroupOutput,
+                        )?
+                    } else {
+                        join_plan
+                    }
+                } else {
+                    join_plan
+                };
+                let join_plan_schema = join_plan.schema().clone();
+
                 let bin_expr_builder = |_: &String| {
                     let (_, field_pairs) =
                         field_groups
@@ -1690,6 +1823,18 @@ impl PromPlanner {
                     // So we filter on the join result and then project only the side that should
                     // be preserved according to PromQL semantics.
                     let filtered = self.filter_on_field_column(join_plan, bin_expr_builder)?;
+                    if let Some(label_table_ref) = &group_table_ref {
+                        // Group modifiers keep the value of the left operand, but the labels
+                        // of the "many" side.
+                        let mut project_context = self.ctx.clone();
+                        project_context.field_columns = left_aligned_field_columns;
+                        return self.project_binary_join_side(
+                            filtered,
+                            &left_table_ref,
+                            label_table_ref,
+                            &project_context,
+                        );
+                    }
                     let (project_table_ref, mut project_context, project_field_columns) =
                         match (lhs.value_type(), rhs.value_type()) {
                             (ValueType::Scalar, ValueType::Vector) => (
@@ -1704,7 +1849,12 @@ impl PromPlanner {
                             ),
                         };
                     project_context.field_columns = project_field_columns;
-                    self.project_binary_join_side(filtered, project_table_ref, &project_context)
+                    self.project_binary_join_side(
+                        filtered,
+                        project_table_ref,
+                        project_table_ref,
+                        &project_context,
+                    )
                 } else {
                     let projected =
                         self.projection_for_each_field_column(join_plan, bin_expr_builder)?;
@@ -1790,10 +1940,13 @@ impl PromPlanner {
             .context(DataFusionPlanningSnafu)
     }
 
+    /// Project a comparison join result, taking field columns from `field_table_ref` and
+    /// the time index, tag columns and `__tsid` from `label_table_ref`.
     fn project_binary_join_side(
         &mut self,
         input: LogicalPlan,
-        table_ref: &TableReference,
+        field_table_ref: &TableReference,
+        label_table_ref: &TableReference,
         context: &PromPlannerContext,
     ) -> Result<LogicalPlan> {
         let schema = input.schema();
@@ -1804,7 +1957,7 @@ impl PromPlanner {
         // Project time index from the chosen side.
         if let Some(time_index_column) = &context.time_index_column {
             let time_index_col = schema
-                .qualified_field_with_name(Some(table_ref), time_index_column)
+                .qualified_field_with_name(Some(label_table_ref), time_index_column)
                 .context(DataFusionPlanningSnafu)?
                 .into();
             project_exprs.push(DfExpr::Column(time_index_col));
@@ -1813,7 +1966,7 @@ impl PromPlanner {
         // Project field columns from the chosen side.
         for field_column in &context.field_columns {
             let field_col = schema
-                .qualified_field_with_name(Some(table_ref), field_column)
+                .qualified_field_with_name(Some(field_table_ref), field_column)
                 .context(DataFusionPlanningSnafu)?
                 .into();
             project_exprs.push(DfExpr::Column(field_col));
@@ -1822,7 +1975,7 @@ impl PromPlanner {
         // Project tag columns from the chosen side.
         for tag_column in &context.tag_columns {
             let tag_col = schema
-                .qualified_field_with_name(Some(table_ref), tag_column)
+                .qualified_field_with_name(Some(label_table_ref), tag_column)
                 .context(DataFusionPlanningSnafu)?
                 .into();
             project_exprs.push(DfExpr::Column(tag_col));
@@ -1831,7 +1984,7 @@ impl PromPlanner {
         // Preserve `__tsid` if present, so it can still be used internally downstream. It's
         // stripped from the final output anyway.
         if let Some(tsid_col) =
-            Self::optional_tsid_projection(schema, Some(table_ref), context.use_tsid)
+            Self::optional_tsid_projection(schema, Some(label_table_ref), context.use_tsid)
         {
             project_exprs.push(tsid_col);
         }
@@ -4317,9 +4470,11 @@ impl PromPlanner {
             token::T_GROUP => max_udaf().call(vec![lit(1_f64)]),
             token::T_STDDEV => stddev_pop_udaf().call(vec![input]),
             token::T_STDVAR => var_pop_udaf().call(vec![input]),
+            // `topk` and `bottomk` keep the selected input series, so they are planned as
+            // window functions in `prom_topk_bottomk_to_plan` instead.
             token::T_TOPK | token::T_BOTTOMK => {
-                return UnsupportedExprSnafu {
-                    name: format!("{op:?}"),
+                return UnexpectedPlanExprSnafu {
+                    desc: format!("{op} should be planned as a window function"),
                 }
                 .fail();
             }
@@ -4408,8 +4563,8 @@ impl PromPlanner {
                 )
             }
             token::T_TOPK | token::T_BOTTOMK => {
-                return UnsupportedExprSnafu {
-                    name: format!("{op:?}"),
+                return UnexpectedPlanExprSnafu {
+                    desc: format!("{op} should be planned as a window function"),
                 }
                 .fail();
             }
@@ -5583,6 +5738,7 @@ impl PromPlanner {
     }
 
     /// Build a inner join on time index column and tag columns to concat two logical plans.
+    /// Fill modifiers make it a left, right or full outer join with the missing side filled.
     /// When `only_join_time_index == true` we only join on the time index, because these two plan may not have the same tag columns
     #[allow(clippy::too_many_arguments)]
     fn join_on_non_field_columns(
@@ -5608,6 +5764,31 @@ impl PromPlanner {
                 modifier,
             )?;
 
+        // `group_left`/`group_right` require the "one" side to be unique per match group.
+        let unique_side = |plan: LogicalPlan,
+                           tag_columns: &BTreeSet<String>,
+                           time_index_column: &Option<String>,
+                           side: &'static str| {
+            self.ensure_unique_match_group(
+                plan,
+                tag_columns.iter().map(Column::from_name).collect(),
+                time_index_column.as_ref().map(Column::from_name),
+                MatchGroupCheck::Side(side),
+            )
+        };
+        let (left, right) = match modifier.as_ref().map(|modifier| &modifier.card) {
+            Some(VectorMatchCardinality::ManyToOne(_)) if !force_empty_join => {
+                let right =
+                    unique_side(right, &right_tag_columns, &right_time_index_column, "right")?;
+                (left, right)
+            }
+            Some(VectorMatchCardinality::OneToMany(_)) if !force_empty_join => {
+                let left = unique_side(left, &left_tag_columns, &left_time_index_column, "left")?;
+                (left, right)
+            }
+            _ => (left, right),
+        };
+
         // push time index column if it exists
         if let (Some(left_time_index_column), Some(right_time_index_column)) =
             (left_time_index_column, right_time_index_column)
@@ -5617,18 +5798,20 @@ impl PromPlanner {
         }
 
         let right = LogicalPlanBuilder::from(right)
-            .alias(right_table_ref)
+            .alias(right_table_ref.clone())
             .context(DataFusionPlanningSnafu)?
             .build()
             .context(DataFusionPlanningSnafu)?;
 
-        // Inner Join on time index column to concat two operator
-        LogicalPlanBuilder::from(left)
-            .alias(left_table_ref)
+        // Join on time index column to concat two operator. Fill modifiers turn it into an
+        // outer join that keeps the unmatched samples of the filled side(s).
+        let join_type = Self::binary_join_type(modifier);
+        let join_plan = LogicalPlanBuilder::from(left)
+            .alias(left_table_ref.clone())
             .context(DataFusionPlanningSnafu)?
             .join_detailed(
                 right,
-                JoinType::Inner,
+                join_type,
                 (
                     left_tag_columns
                         .into_iter()
@@ -5644,9 +5827,266 @@ impl PromPlanner {
             )
             .context(DataFusionPlanningSnafu)?
             .build()
+            .context(DataFusionPlanningSnafu)?;
+
+        if join_type == JoinType::Inner {
+            return Ok(join_plan);
+        }
+        let fill_values = modifier
+            .as_ref()
+            .map(|modifier| (modifier.fill_values.lhs, modifier.fill_values.rhs))
+            .unwrap_or_default();
+        self.fill_missing_binary_side(
+            join_plan,
+            (&left_table_ref, left_context, fill_values.0),
+            (&right_table_ref, right_context, fill_values.1),
+        )
+    }
+
+    /// Returns the join type of a vector-vector binary operation: fill modifiers keep the
+    /// samples that only exist on the other side of the operation.
+    fn binary_join_type(modifier: &Option<BinModifier>) -> JoinType {
+        let Some(modifier) = modifier else {
+            return JoinType::Inner;
+        };
+        match (modifier.fill_values.lhs, modifier.fill_values.rhs) {
+            (Some(_), Some(_)) => JoinType::Full,
+            // missing left samples are filled, so keep every right sample
+            (Some(_), None) => JoinType::Right,
+            (None, Some(_)) => JoinType::Left,
+            (None, None) => JoinType::Inner,
+        }
+    }
+
+    /// Substitute the missing side of an outer binary join.
+    ///
+    /// Each side is given as its table reference, planner context and fill value. Where a
+    /// side has no sample, its field columns are replaced by its fill value and its time
+    /// index, tags and `__tsid` are taken from the other side. Columns keep their
+    /// qualifiers, so the result can be projected like an inner join result.
+    fn fill_missing_binary_side(
+        &self,
+        input: LogicalPlan,
+        left: (&TableReference, &PromPlannerContext, Option<f64>),
+        right: (&TableReference, &PromPlannerContext, Option<f64>),
+    ) -> Result<LogicalPlan> {
+        let schema = input.schema().clone();
+        let time_index_expr = |table_ref: &TableReference, context: &PromPlannerContext| {
+            context
+                .time_index_column
+                .as_ref()
+                .and_then(|time_index| {
+                    schema
+                        .qualified_field_with_name(Some(table_ref), time_index)
+                        .ok()
+                })
+                .map(|field| DfExpr::Column(field.into()))
+        };
+
+        let mut project_exprs = Vec::with_capacity(schema.fields().len());
+        for (qualifier, field) in schema.iter() {
+            let column = DfExpr::Column(Column::new(qualifier.cloned(), field.name().clone()));
+            let ((this_ref, this_context, fill_value), (other_ref, other_context, _)) =
+                if qualifier == Some(left.0) {
+                    (left, right)
+                } else if qualifier == Some(right.0) {
+                    (right, left)
+                } else {
+                    project_exprs.push(column);
+                    continue;
+                };
+            // A side without fill value is never missing from the join result.
+            let (Some(fill_value), Some(this_time_index)) =
+                (fill_value, time_index_expr(this_ref, this_context))
+            else {
+                project_exprs.push(column);
+                continue;
+            };
+            let missing = this_time_index.is_null();
+
+            let name = field.name();
+            let substitute = if this_context.time_index_column.as_ref() == Some(name) {
+                time_index_expr(other_ref, other_context)
+            } else if this_context.tag_columns.contains(name)
+                || name == DATA_SCHEMA_TSID_COLUMN_NAME
+            {
+                schema
+                    .qualified_field_with_name(Some(other_ref), name)
+                    .ok()
+                    .map(|field| DfExpr::Column(field.into()))
+            } else if this_context.field_columns.contains(name) {
+                Some(DfExpr::Cast(Cast {
+                    expr: Box::new(lit(fill_value)),
+                    data_type: field.data_type().clone(),
+                }))
+            } else {
+                None
+            };
+
+            let expr = match substitute {
+                Some(substitute) => DfExpr::Alias(Alias::new(
+                    when(missing, substitute)
+                        .otherwise(column)
+                        .context(DataFusionPlanningSnafu)?,
+                    qualifier.cloned(),
+                    name,
+                )),
+                None => column,
+            };
+            project_exprs.push(expr);
+        }
+
+        LogicalPlanBuilder::from(input)
+            .project(project_exprs)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)
+    }
+
+    /// Fail the query at execution time if more than one row of `input` is in the same
+    /// match group at the same timestamp.
+    fn ensure_unique_match_group(
+        &self,
+        input: LogicalPlan,
+        match_columns: Vec<Column>,
+        time_index_column: Option<Column>,
+        check: MatchGroupCheck,
+    ) -> Result<LogicalPlan> {
+        let output_exprs = input
+            .schema()
+            .columns()
+            .into_iter()
+            .map(DfExpr::Column)
+            .collect::<Vec<_>>();
+        let partition_by = match_columns
+            .iter()
+            .cloned()
+            .chain(time_index_column)
+            .map(DfExpr::Column)
+            .collect::<Vec<_>>();
+        let count_expr = DfExpr::WindowFunction(Box::new(WindowFunction {
+            fun: WindowFunctionDefinition::AggregateUDF(count_udaf()),
+            params: WindowFunctionParams {
+                args: vec![lit(1_i64)],
+                partition_by,
+                order_by: vec![],
+                window_frame: WindowFrame::new(None),
+                null_treatment: None,
+                distinct: false,
+                filter: None,
+            },
+        }));
+        // The window qualifies the partition columns, so its output column is named after
+        // the qualified expression.
+        let count_expr = normalize_col(count_expr, &input).context(DataFusionPlanningSnafu)?;
+        let count_column = DfExpr::Column(Column::from_name(count_expr.schema_name().to_string()));
+        let check_expr = DfExpr::ScalarFunction(ScalarFunction {
+            func: Arc::new(UniqueMatchGroup::scalar_udf(
+                check,
+                match_columns
+                    .iter()
+                    .map(|column| column.name.clone())
+                    .collect(),
+            )),
+            args: std::iter::once(count_column)
+                .chain(match_columns.into_iter().map(DfExpr::Column))
+                .collect(),
+        });
+
+        LogicalPlanBuilder::from(input)
+            .window(vec![count_expr])
+            .context(DataFusionPlanningSnafu)?
+            .filter(check_expr)
+            .context(DataFusionPlanningSnafu)?
+            .project(output_exprs)
+            .context(DataFusionPlanningSnafu)?
+            .build()
             .context(DataFusionPlanningSnafu)
     }
 
+    /// Returns whether the "many" side of a `group_left`/`group_right` match is the left
+    /// operand, together with the labels to copy from the "one" side.
+    fn vector_match_group_labels(modifier: &Option<BinModifier>) -> Option<(bool, &[String])> {
+        match modifier.as_ref().map(|modifier| &modifier.card) {
+            Some(VectorMatchCardinality::ManyToOne(labels)) => Some((true, &labels.labels)),
+            Some(VectorMatchCardinality::OneToMany(labels)) => Some((false, &labels.labels)),
+            _ => None,
+        }
+    }
+
+    /// Project the labels of a `group_left`/`group_right` join result.
+    ///
+    /// The result keeps every label of the "many" side. Labels listed in the group
+    /// modifier are taken from the "one" side instead, or dropped if the "one" side
+    /// doesn't have them. Updates the context to describe the "many" side.
+    fn project_group_labels(
+        &mut self,
+        input: LogicalPlan,
+        many_table_ref: &TableReference,
+        one_table_ref: &TableReference,
+        many_context: &PromPlannerContext,
+        include_labels: &[String],
+    ) -> Result<LogicalPlan> {
+        let schema = input.schema().clone();
+        let include = include_labels.iter().collect::<HashSet<_>>();
+
+        let mut project_exprs = schema
+            .iter()
+            .filter(|(qualifier, field)| {
+                *qualifier != Some(many_table_ref) || !include.contains(field.name())
+            })
+            .map(|(qualifier, field)| {
+                DfExpr::Column(Column::new(qualifier.cloned(), field.name().clone()))
+            })
+            .collect::<Vec<_>>();
+
+        let mut tag_columns = many_context
+            .tag_columns
+            .iter()
+            .filter(|tag| !include.contains(tag))
+            .cloned()
+            .collect::<Vec<_>>();
+        for label in include_labels {
+            let value = if let Ok(field) =
+                schema.qualified_field_with_name(Some(one_table_ref), label)
+            {
+                DfExpr::Column(field.into())
+            } else if let Ok((_, field)) =
+                schema.qualified_field_with_name(Some(many_table_ref), label)
+            {
+                DfExpr::Literal(
+                    ScalarValue::try_from(field.data_type()).context(DataFusionPlanningSnafu)?,
+                    None,
+                )
+            } else {
+                continue;
+            };
+            project_exprs.push(DfExpr::Alias(Alias::new(
+                value,
+                Some(many_table_ref.clone()),
+                label,
+            )));
+            tag_columns.push(label.clone());
+        }
+
+        let plan = LogicalPlanBuilder::from(input)
+            .project(project_exprs)
+            .context(DataFusionPlanningSnafu)?
+            .build()
+            .context(DataFusionPlanningSnafu)?;
+
+        let field_columns = std::mem::take(&mut self.ctx.field_columns);
+        self.ctx = many_context.clone();
+        self.ctx.field_columns = field_columns;
+        self.ctx.tag_columns = tag_columns;
+        self.ctx.table_name = Some(many_table_ref.table().to_string());
+        self.ctx.schema_name = many_table_ref.schema().map(str::to_string);
+        // Copied labels change the series identity of the "many" side.
+        self.ctx.use_tsid = many_context.use_tsid && include_labels.is_empty();
+
+        Ok(plan)
+    }
+
     fn is_zero_row_empty_relation(plan: &LogicalPlan) -> bool {
         // `produce_one_row` is used for input-free plans that still emit one row;
         // only the false case is a statically proven empty vector.
@@ -8457,32 +8897,86 @@ mod test {
     }
 
     #[tokio::test]
-    async fn reject_binary_fill_modifiers() {
+    async fn binary_fill_modifiers_use_outer_join() {
         let state = build_query_engine_state();
 
-        for query in [
-            "some_metric + fill(0) some_alt_metric",
-            "some_metric + fill_left(0) some_alt_metric",
-            "some_metric + fill_right(0) some_alt_metric",
-            "(some_metric + fill(0) some_alt_metric) + some_metric",
+        for (query, join) in [
+            ("some_metric + fill(0) some_alt_metric", "Full Join"),
+            ("some_metric + fill_left(0) some_alt_metric", "Right Join"),
+            ("some_metric + fill_right(0) some_alt_metric", "Left Join"),
+            (
+                "(some_metric + fill(0) some_alt_metric) + some_metric",
+                "Full Join",
+            ),
         ] {
             let eval_stmt = build_eval_stmt(query);
-            let table_provider = build_test_table_provider(&[], 0, 0).await;
-            let err = PromPlanner::stmt_to_plan(table_provider, &eval_stmt, &state)
+            let table_provider = build_test_table_provider(
+                &[
+                    (DEFAULT_SCHEMA_NAME.to_string(), "some_metric".to_string()),
+                    (
+                        DEFAULT_SCHEMA_NAME.to_string(),
+                        "some_alt_metric".to_string(),
+                    ),
+                ],
+                1,
+                1,
+            )
+            .await;
+            let plan = PromPlanner::stmt_to_plan(table_provider, &eval_stmt, &state)
                 .await
-                .unwrap_err();
+                .unwrap();
 
+            let plan_str = plan.display_indent_schema().to_string();
+            assert_eq!(plan_str.matches(join).count(), 1, "{plan_str}");
             assert!(
-                matches!(
-                    &err,
-                    crate::promql::error::Error::UnsupportedExpr { name, .. }
-                        if name == "PromQL fill modifiers"
-                ),
-                "{err}"
+                plan_str.contains("CASE WHEN some_metric.timestamp IS NULL")
+                    || plan_str.contains("CASE WHEN some_alt_metric.timestamp IS NULL"),
+                "{plan_str}"
             );
         }
     }
 
+    #[tokio::test]
+    async fn binary_fill_modifiers_substitute_missing_side() {
+        let eval_stmt = build_eval_stmt("some_metric + fill_right(42) some_alt_metric");
+        let table_provider = build_test_table_provider(
+            &[
+                (DEFAULT_SCHEMA_NAME.to_string(), "some_metric".to_string()),
+                (
+                    DEFAULT_SCHEMA_NAME.to_string(),
+                    "some_alt_metric".to_string(),
+                ),
+            ],
+            1,
+            1,
+        )
+        .await;
+        let plan =
+            PromPlanner::stmt_to_plan(table_provider, &eval_stmt, &build_query_engine_state())
+                .await
+                .unwrap();
+
+        let plan_str = plan.display_indent_schema().to_string();
+        // Only the right side is filled: its value defaults to 42 and its labels come
+        // from the left side.
+        assert!(
+            plan_str.contains(
+                "CASE WHEN some_alt_metric.timestamp IS NULL THEN CAST(Float64(42) AS Float64)"
+            ),
+            "{plan_str}"
+        );
+        assert!(
+            plan_str.contains(
+                "CASE WHEN some_alt_metric.timestamp IS NULL THEN some_metric.tag_0 ELSE some_alt_metric.tag_0 END"
+            ),
+            "{plan_str}"
+        );
+        assert!(
+            !plan_str.contains("CASE WHEN some_metric.timestamp IS NULL"),
+            "{plan_str}"
+        );
+    }
+
     #[tokio::test]
     async fn timestamp_binary_join_falls_back_when_tsid_is_projected_out() {
         for query in [
@@ -8897,6 +9391,172 @@ mod test {
         );
     }
 
+    async fn build_group_modifier_table_provider() -> DfTableSourceProvider {
+        build_test_table_provider_with_tsid_tag_fields(&[
+            (
+                (DEFAULT_SCHEMA_NAME.to_string(), "some_metric".to_string()),
+                2,
+                1,
+            ),
+            (
+                (
+                    DEFAULT_SCHEMA_NAME.to_string(),
+                    "some_alt_metric".to_string(),
+                ),
+                3,
+                1,
+            ),
+        ])
+        .await
+    }
+
+    fn output_column_names(plan: &LogicalPlan) -> Vec<String> {
+        plan.schema()
+            .fields()
+            .iter()
+            .map(|field| field.name().clone())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn group_left_copies_labels_from_one_side() {
+        let eval_stmt =
+            build_eval_stmt("some_metric * on(tag_0) group_left(tag_2) some_alt_metric");
+        let plan = PromPlanner::stmt_to_plan(
+            build_group_modifier_table_provider().await,
+            &eval_stmt,
+            &build_query_engine_state(),
+        )
+        .await
+        .unwrap();
+
+        let plan_str = plan.display_indent_schema().to_string();
+        assert!(!plan_str.contains("__tsid ="), "{plan_str}");
+        assert!(
+            plan_str.contains("some_metric.tag_0 = some_alt_metric.tag_0"),
+            "{plan_str}"
+        );
+        assert_eq!(
+            plan_str.matches("prom_unique_match_group(").count(),
+            1,
+            "{plan_str}"
+        );
+
+        let columns = output_column_names(&plan);
+        for column in ["tag_0", "tag_1", "tag_2", "timestamp"] {
+            assert!(columns.iter().any(|c| c == column), "{columns:?}");
+        }
+        assert!(
+            !columns.iter().any(|c| c == DATA_SCHEMA_TSID_COLUMN_NAME),
+            "{columns:?}"
+        );
+    }
+
+    #[tokio::test]
+    async fn group_right_keeps_labels_of_right_side() {
+        let eval_stmt =
+            build_eval_stmt("some_metric - on(tag_0) group_right(tag_1) some_alt_metric");
+        let plan = PromPlanner::stmt_to_plan(
+            build_group_modifier_table_provider().await,
+            &eval_stmt,
+            &build_query_engine_state(),
+        )
+        .await
+        .unwrap();
+
+        let plan_str = plan.display_indent_schema().to_string();
+        // The left ("one") side is unique per match group, and so are the output series
+        // as `tag_1` of the right side is replaced.
+        assert_eq!(
+            plan_str.matches("prom_unique_match_group(").count(),
+            2,
+            "{plan_str}"
+        );
+        // `tag_1` is copied from the left ("one") side, the others come from the right.
+        assert!(
+            plan_str.contains("some_metric.tag_1 AS some_alt_metric.tag_1"),
+            "{plan_str}"
+        );
+
+        let columns = output_column_names(&plan);
+        for column in ["tag_0", "tag_1", "tag_2", "timestamp"] {
+            assert!(columns.iter().any(|c| c == column), "{columns:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn only_group_modifiers_check_duplicate_series() {
+        // (query, expected number of checks)
+        let cases = [
+            // One-to-one matches don't check duplicate series.
+            ("some_metric / on(tag_0) some_alt_metric", 0),
+            ("some_metric / ignoring(tag_2) some_alt_metric", 0),
+            // New labels from the "one" side can't merge series of the "many" side, so
+            // only the "one" side is checked.
+            (
+                "some_metric / on(tag_0) group_left(tag_2) some_alt_metric",
+                1,
+            ),
+        ];
+        for (query, expected) in cases {
+            let plan = PromPlanner::stmt_to_plan(
+                build_group_modifier_table_provider().await,
+                &build_eval_stmt(query),
+                &build_query_engine_state(),
+            )
+            .await
+            .unwrap();
+
+            let plan_str = plan.display_indent_schema().to_string();
+            assert_eq!(
+                plan_str.matches("prom_unique_match_group(").count(),
+                expected,
+                "{query}: {plan_str}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn group_left_without_labels_keeps_many_side_labels() {
+        let eval_stmt = build_eval_stmt("some_metric / on(tag_0) group_left some_alt_metric");
+        let plan = PromPlanner::stmt_to_plan(
+            build_group_modifier_table_provider().await,
+            &eval_stmt,
+            &build_query_engine_state(),
+        )
+        .await
+        .unwrap();
+
+        let columns = output_column_names(&plan);
+        for column in ["tag_0", "tag_1", "timestamp"] {
+            assert!(columns.iter().any(|c| c == column), "{columns:?}");
+        }
+        assert!(!columns.iter().any(|c| c == "tag_2"), "{columns:?}");
+    }
+
+    #[tokio::test]
+    async fn group_left_comparison_keeps_left_value() {
+        let eval_stmt =
+            build_eval_stmt("some_metric > on(tag_0) group_left(tag_2) some_alt_metric");
+        let plan = PromPlanner::stmt_to_plan(
+            build_group_modifier_table_provider().await,
+            &eval_stmt,
+            &build_query_engine_state(),
+        )
+        .await
+        .unwrap();
+
+        let plan_str = plan.display_indent_schema().to_string();
+        assert!(
+            plan_str.contains("some_metric.field_0 > some_alt_metric.field_0"),
+            "{plan_str}"
+        );
+        let columns = output_column_names(&plan);
+        for column in ["field_0", "tag_0", "tag_1", "tag_2", "timestamp"] {
+            assert!(columns.iter().any(|c| c == column), "{columns:?}");
+        }
+    }
+
     #[tokio::test]
     async fn binary_island_falls_back_for_comparison_filter() {
         let eval_stmt = build_eval_stmt("(some_metric > some_alt_metric) / some_metric");
@@ -11593,6 +12253,60 @@ Filter: up.field_0 IS NOT NULL [timestamp:Timestamp(ms), field_0:Float64;N, foo:
         assert_eq!(plan.display_indent_schema().to_string(), expected);
     }
 
+    #[tokio::test]
+    async fn topk_with_non_literal_k() {
+        for query in [
+            "topk(scalar(some_alt_metric), some_metric)",
+            "bottomk by (tag_0) (scalar(some_alt_metric), some_metric)",
+        ] {
+            let eval_stmt = build_eval_stmt(query);
+            let table_provider = build_test_table_provider(
+                &[
+                    (DEFAULT_SCHEMA_NAME.to_string(), "some_metric".to_string()),
+                    (
+                        DEFAULT_SCHEMA_NAME.to_string(),
+                        "some_alt_metric".to_string(),
+                    ),
+                ],
+                1,
+                1,
+            )
+            .await;
+            let plan =
+                PromPlanner::stmt_to_plan(table_provider, &eval_stmt, &build_query_engine_state())
+                    .await
+                    .unwrap();
+
+            let plan_str = plan.display_indent_schema().to_string();
+            assert!(plan_str.contains("ScalarCalculate"), "{plan_str}");
+            assert!(
+                plan_str.contains(&format!("some_metric.timestamp = {TOPK_PARAM_TIME_COLUMN}")),
+                "{plan_str}"
+            );
+            assert!(
+                plan_str.contains(&format!("<= {TOPK_PARAM_VALUE_COLUMN}")),
+                "{plan_str}"
+            );
+            // `k` is floored and NaN selects nothing.
+            assert!(plan_str.contains("WHEN isnan("), "{plan_str}");
+            assert!(plan_str.contains("ELSE floor("), "{plan_str}");
+
+            let columns = plan
+                .schema()
+                .fields()
+                .iter()
+                .map(|field| field.name().clone())
+                .collect::<Vec<_>>();
+            assert_eq!(columns.len(), 3, "{columns:?}");
+            assert!(
+                !columns
+                    .iter()
+                    .any(|column| column.starts_with("__promql_topk_param")),
+                "{columns:?}"
+            );
+        }
+    }
+
     #[tokio::test]
     async fn test_count_values_expr() {
         let mut eval_stmt = EvalStmt {
@@ -49,6 +49,11 @@ use crate::metrics::{
     METRIC_ELASTICSEARCH_LOGS_DOCS_COUNT, METRIC_ELASTICSEARCH_LOGS_INGESTION_ELAPSED,
 };
 
+mod query_dsl;
+mod search;
+
+pub use search::{handle_count_api, handle_search_api};
+
 // The headers for every response of Elasticsearch API.
 static ELASTICSEARCH_HEADERS: Lazy<HeaderMap> = Lazy::new(|| {
     HeaderMap::from_iter([
@@ -0,0 +1,1402 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Translates the Elasticsearch [query DSL] into [`LogQuery`].
+//!
+//! Full text queries (`match`, `match_phrase` and `query_string`) on string
+//! columns use `matches_term`, and `term`/`terms` use equality, so both can be
+//! served by the fulltext and bloom filter indexes.
+//!
+//! [query DSL]: https://www.elastic.co/guide/en/elasticsearch/reference/current/query-dsl.html
+
+use std::iter::Peekable;
+use std::str::Chars;
+
+use chrono::{DateTime, SecondsFormat};
+use log_query::logql::parse_duration;
+use log_query::{
+    AggFunc, BinaryOperator, ColumnFilters, ContentFilter, Context, EqualValue, Filters, Limit,
+    LogExpr, LogQuery, TimeFilter,
+};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use snafu::{OptionExt, ensure};
+use table::table_name::TableName;
+
+use crate::error::{InvalidElasticsearchInputSnafu, NotSupportedSnafu, Result};
+
+/// Column of the bucket key in aggregation results.
+pub(crate) const KEY_COLUMN: &str = "key";
+/// Column of the document count in aggregation and count results.
+pub(crate) const DOC_COUNT_COLUMN: &str = "doc_count";
+
+const DEFAULT_SIZE: usize = 10;
+/// Upper bound of `from + size` and of the number of term buckets, like
+/// Elasticsearch's default `index.max_result_window`.
+const MAX_RESULT_WINDOW: usize = 10_000;
+/// Suffix of the keyword sub-field Elasticsearch creates for text fields.
+const KEYWORD_SUFFIX: &str = ".keyword";
+
+/// Body of a `_search` or `_count` request.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub(crate) struct SearchRequest {
+    pub query: Option<Value>,
+    pub size: Option<usize>,
+    pub from: Option<usize>,
+    pub sort: Option<Value>,
+    #[serde(alias = "aggregations")]
+    pub aggs: Option<Map<String, Value>>,
+    #[serde(rename = "_source")]
+    pub source: Option<Value>,
+}
+
+/// The table an index maps to.
+#[derive(Debug)]
+pub(crate) struct SearchTarget {
+    pub table: TableName,
+    pub timestamp_column: String,
+    /// All columns, with whether they are strings.
+    pub columns: Vec<(String, bool)>,
+    /// Columns searched by `query_string` terms without a field.
+    pub default_fields: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum Aggregation {
+    Terms {
+        field: String,
+        size: usize,
+        /// Order by key instead of document count.
+        by_key: bool,
+        ascending: bool,
+    },
+    DateHistogram {
+        field: String,
+        interval: HistogramInterval,
+    },
+}
+
+#[derive(Debug, PartialEq)]
+pub(crate) enum HistogramInterval {
+    /// Fixed interval in milliseconds.
+    Fixed(i64),
+    /// Calendar unit accepted by `date_trunc`, e.g. `month`.
+    Calendar(&'static str),
+}
+
+impl SearchTarget {
+    /// Resolves a field name to a column and whether it's a string column.
+    fn column(&self, field: &str) -> Result<(&str, bool)> {
+        let name = field.strip_suffix(KEYWORD_SUFFIX).unwrap_or(field);
+        self.columns
+            .iter()
+            .find(|(column, _)| column == name || column == field)
+            .map(|(column, is_string)| (column.as_str(), *is_string))
+            .with_context(|| InvalidElasticsearchInputSnafu {
+                reason: format!("unknown field `{field}`"),
+            })
+    }
+
+    /// Translates a query into filters. A missing query matches all documents.
+    pub fn filters(&self, query: Option<&Value>, now: i64) -> Result<Filters> {
+        match query {
+            Some(query) => self.query_filters(query, now),
+            None => Ok(Filters::And(vec![])),
+        }
+    }
+
+    /// Builds the query returning the hits of a search.
+    pub fn hits_query(&self, filters: Filters, request: &SearchRequest) -> Result<LogQuery> {
+        let size = request.size.unwrap_or(DEFAULT_SIZE);
+        let from = request.from.unwrap_or(0);
+        ensure!(
+            from.saturating_add(size) <= MAX_RESULT_WINDOW,
+            InvalidElasticsearchInputSnafu {
+                reason: format!("from + size must not exceed {MAX_RESULT_WINDOW}"),
+            }
+        );
+
+        // Consecutive sorts make up a single sort, so each run of keys in the
+        // same order becomes one sort.
+        let mut exprs: Vec<LogExpr> = vec![];
+        if let Some(sort) = &request.sort {
+            for (key, key_descending) in self.sort_keys(sort)? {
+                match exprs.last_mut() {
+                    Some(LogExpr::Sort { by, descending }) if *descending == key_descending => {
+                        by.push(key)
+                    }
+                    _ => exprs.push(LogExpr::Sort {
+                        by: vec![key],
+                        descending: key_descending,
+                    }),
+                }
+            }
+        }
+
+        Ok(LogQuery {
+            table: self.table.clone(),
+            time_filter: unbounded_time_filter(),
+            limit: Limit {
+                skip: request.from,
+                fetch: Some(size),
+            },
+            columns: vec![],
+            filters,
+            context: Context::None,
+            exprs,
+        })
+    }
+
+    /// Builds the query counting the matching documents into [`DOC_COUNT_COLUMN`].
+    pub fn count_query(&self, filters: Filters) -> LogQuery {
+        LogQuery {
+            table: self.table.clone(),
+            time_filter: unbounded_time_filter(),
+            limit: Limit::default(),
+            columns: vec![],
+            filters,
+            context: Context::None,
+            exprs: vec![LogExpr::AggrFunc {
+                expr: vec![AggFunc::new(
+                    "count".to_string(),
+                    vec![LogExpr::NamedIdent(self.timestamp_column.clone())],
+                    Some(DOC_COUNT_COLUMN.to_string()),
+                )],
+                by: vec![],
+            }],
+        }
+    }
+
+    /// Restricts the filters to the documents having the field of the aggregation,
+    /// documents without the field fall into no bucket.
+    pub fn aggregation_filters(&self, filters: Filters, aggregation: &Aggregation) -> Filters {
+        let (Aggregation::Terms { field, .. } | Aggregation::DateHistogram { field, .. }) =
+            aggregation;
+        Filters::And(vec![
+            filters,
+            Filters::Single(ColumnFilters {
+                expr: Box::new(LogExpr::NamedIdent(field.clone())),
+                filters: vec![ContentFilter::Exist],
+            }),
+        ])
+    }
+
+    /// Builds the query computing the buckets of an aggregation, with the bucket
+    /// key in [`KEY_COLUMN`] and its size in [`DOC_COUNT_COLUMN`].
+    pub fn aggregation_query(&self, filters: Filters, aggregation: &Aggregation) -> LogQuery {
+        let (exprs, fetch) = match aggregation {
+            Aggregation::Terms {
+                field,
+                size,
+                by_key,
+                ascending,
+            } => {
+                let key = LogExpr::NamedIdent(KEY_COLUMN.to_string());
+                let mut exprs = vec![
+                    LogExpr::AggrFunc {
+                        expr: vec![AggFunc::new(
+                            "count".to_string(),
+                            vec![LogExpr::NamedIdent(field.clone())],
+                            Some(DOC_COUNT_COLUMN.to_string()),
+                        )],
+                        by: vec![LogExpr::Alias {
+                            expr: Box::new(LogExpr::NamedIdent(field.clone())),
+                            alias: KEY_COLUMN.to_string(),
+                        }],
+                    },
+                    LogExpr::Sort {
+                        by: vec![if *by_key {
+                            key.clone()
+                        } else {
+                            LogExpr::NamedIdent(DOC_COUNT_COLUMN.to_string())
+                        }],
+                        descending: !ascending,
+                    },
+                ];
+                if !by_key {
+                    // Ties are broken by ascending key so the buckets are deterministic.
+                    exprs.push(LogExpr::Sort {
+                        by: vec![key],
+                        descending: false,
+                    });
+                }
+                (exprs, Some(*size))
+            }
+            Aggregation::DateHistogram { field, interval } => {
+                let bucket = match interval {
+                    HistogramInterval::Fixed(millis) => LogExpr::ScalarFunc {
+                        name: "date_bin".to_string(),
+                        args: vec![
+                            LogExpr::Literal(format!("{millis} milliseconds")),
+                            LogExpr::NamedIdent(field.clone()),
+                        ],
+                        alias: Some(KEY_COLUMN.to_string()),
+                    },
+                    HistogramInterval::Calendar(unit) => LogExpr::ScalarFunc {
+                        name: "date_trunc".to_string(),
+                        args: vec![
+                            LogExpr::Literal(unit.to_string()),
+                            LogExpr::NamedIdent(field.clone()),
+                        ],
+                        alias: Some(KEY_COLUMN.to_string()),
+                    },
+                };
+                let exprs = vec![
+                    bucket,
+                    LogExpr::AggrFunc {
+                        expr: vec![AggFunc::new(
+                            "count".to_string(),
+                            vec![LogExpr::NamedIdent(KEY_COLUMN.to_string())],
+                            Some(DOC_COUNT_COLUMN.to_string()),
+                        )],
+                        by: vec![LogExpr::NamedIdent(KEY_COLUMN.to_string())],
+                    },
+                    LogExpr::Sort {
+                        by: vec![LogExpr::NamedIdent(KEY_COLUMN.to_string())],
+                        descending: false,
+                    },
+                ];
+                (exprs, None)
+            }
+        };
+
+        LogQuery {
+            table: self.table.clone(),
+            time_filter: unbounded_time_filter(),
+            limit: Limit { skip: None, fetch },
+            columns: vec![],
+            filters,
+            context: Context::None,
+            exprs,
+        }
+    }
+
+    /// Parses the `aggs` of a search request.
+    pub fn aggregations(&self, aggs: &Map<String, Value>) -> Result<Vec<(String, Aggregation)>> {
+        aggs.iter()
+            .map(|(name, spec)| {
+                ensure!(
+                    spec.get("aggs").is_none() && spec.get("aggregations").is_none(),
+                    NotSupportedSnafu {
+                        feat: "Elasticsearch sub-aggregations",
+                    }
+                );
+                let (kind, body) = single_entry(spec, "aggregation")?;
+                let field = body.get("field").and_then(Value::as_str).with_context(|| {
+                    InvalidElasticsearchInputSnafu {
+                        reason: format!("aggregation `{name}` requires a field"),
+                    }
+                })?;
+                let field = self.column(field)?.0.to_string();
+
+                let aggregation = match kind {
+                    "terms" => {
+                        let size = body
+                            .get("size")
+                            .and_then(Value::as_u64)
+                            .map(|size| size as usize)
+                            .unwrap_or(DEFAULT_SIZE);
+                        ensure!(
+                            size <= MAX_RESULT_WINDOW,
+                            InvalidElasticsearchInputSnafu {
+                                reason: format!(
+                                    "aggregation `{name}` size must not exceed {MAX_RESULT_WINDOW}"
+                                ),
+                            }
+                        );
+                        let (by_key, ascending) = match body.get("order") {
+                            None => (false, false),
+                            Some(order) => {
+                                let (key, direction) = single_entry(order, "terms order")?;
+                                let by_key = match key {
+                                    "_count" => false,
+                                    "_key" => true,
+                                    _ => {
+                                        return NotSupportedSnafu {
+                                            feat: format!("terms aggregation order by `{key}`"),
+                                        }
+                                        .fail();
+                                    }
+                                };
+                                (by_key, parse_order(direction)?)
+                            }
+                        };
+                        Aggregation::Terms {
+                            field,
+                            size,
+                            by_key,
+                            ascending,
+                        }
+                    }
+                    "date_histogram" => Aggregation::DateHistogram {
+                        field,
+                        interval: histogram_interval(body)?,
+                    },
+                    _ => {
+                        return NotSupportedSnafu {
+                            feat: format!("Elasticsearch aggregation `{kind}`"),
+                        }
+                        .fail();
+                    }
+                };
+                Ok((name.clone(), aggregation))
+            })
+            .collect()
+    }
+
+    /// Returns the sort keys, each with whether it's sorted in descending order.
+    fn sort_keys(&self, sort: &Value) -> Result<Vec<(LogExpr, bool)>> {
+        let items = match sort {
+            Value::Array(items) => items.iter().collect(),
+            item => vec![item],
+        };
+
+        let mut keys = vec![];
+        for item in items {
+            let (field, descending) = match item {
+                Value::String(field) => (field.as_str(), false),
+                Value::Object(_) => {
+                    let (field, order) = single_entry(item, "sort")?;
+                    let order = match order {
+                        Value::Object(options) => options.get("order").unwrap_or(&Value::Null),
+                        order => order,
+                    };
+                    let descending = match order {
+                        Value::Null => false,
+                        order => !parse_order(order)?,
+                    };
+                    (field, descending)
+                }
+                _ => {
+                    return InvalidElasticsearchInputSnafu {
+                        reason: format!("invalid sort `{item}`"),
+                    }
+                    .fail();
+                }
+            };
+            // Relevance scores and index order have no equivalent, keep the natural order.
+            if field == "_score" || field == "_doc" {
+                continue;
+            }
+            let column = self.column(field)?.0;
+            keys.push((LogExpr::NamedIdent(column.to_string()), descending));
+        }
+        Ok(keys)
+    }
+
+    /// Filters matching no documents.
+    fn match_none(&self) -> Filters {
+        // `IN ()` never holds, even for null values.
+        Filters::Single(ColumnFilters {
+            expr: Box::new(LogExpr::NamedIdent(self.timestamp_column.clone())),
+            filters: vec![ContentFilter::In(vec![])],
+        })
+    }
+
+    fn query_filters(&self, query: &Value, now: i64) -> Result<Filters> {
+        let (kind, body) = single_entry(query, "query")?;
+        match kind {
+            "match_all" => Ok(Filters::And(vec![])),
+            "match_none" => Ok(self.match_none()),
+            "bool" => self.bool_filters(body, now),
+            "match" => {
+                let (field, body) = single_entry(body, "match query")?;
+                let (text, options) = query_value(body, "query")?;
+                let and = match options.and_then(|options| options.get("operator")) {
+                    Some(operator) => parse_operator(operator)?,
+                    None => false,
+                };
+                let (column, is_string) = self.column(field)?;
+                if !is_string {
+                    return Ok(equals(column, false, &scalar_to_string(text)?));
+                }
+                let text = scalar_to_string(text)?;
+                let terms = text
+                    .split_whitespace()
+                    .map(|term| matches_term(column, term))
+                    .collect::<Vec<_>>();
+                if terms.is_empty() {
+                    // The text has no terms, `zero_terms_query` decides what matches.
+                    let match_all =
+                        match options.and_then(|options| options.get("zero_terms_query")) {
+                            Some(zero_terms_query) => parse_zero_terms_query(zero_terms_query)?,
+                            None => false,
+                        };
+                    return Ok(if match_all {
+                        Filters::And(vec![])
+                    } else {
+                        self.match_none()
+                    });
+                }
+                Ok(if and {
+                    Filters::And(terms)
+                } else {
+                    Filters::Or(terms)
+                })
+            }
+            "match_phrase" => {
+                let (field, body) = single_entry(body, "match_phrase query")?;
+                let (text, _) = query_value(body, "query")?;
+                let (column, is_string) = self.column(field)?;
+                let text = scalar_to_string(text)?;
+                Ok(if is_string {
+                    matches_term(column, &text)
+                } else {
+                    equals(column, false, &text)
+                })
+            }
+            "term" => {
+                let (field, body) = single_entry(body, "term query")?;
+                let (value, _) = query_value(body, "value")?;
+                let (column, is_string) = self.column(field)?;
+                Ok(equals(column, is_string, &scalar_to_string(value)?))
+            }
+            "terms" => {
+                let (field, values) = single_entry(body, "terms query")?;
+                let values = values
+                    .as_array()
+                    .with_context(|| InvalidElasticsearchInputSnafu {
+                        reason: "terms query requires an array of values",
+                    })?
+                    .iter()
+                    .map(scalar_to_string)
+                    .collect::<Result<Vec<_>>>()?;
+                let (column, _) = self.column(field)?;
+                Ok(Filters::Single(ColumnFilters {
+                    expr: Box::new(LogExpr::NamedIdent(column.to_string())),
+                    filters: vec![ContentFilter::In(values)],
+                }))
+            }
+            "exists" => {
+                let field = body.get("field").and_then(Value::as_str).with_context(|| {
+                    InvalidElasticsearchInputSnafu {
+                        reason: "exists query requires a field",
+                    }
+                })?;
+                let (column, _) = self.column(field)?;
+                Ok(Filters::Single(ColumnFilters {
+                    expr: Box::new(LogExpr::NamedIdent(column.to_string())),
+                    filters: vec![ContentFilter::Exist],
+                }))
+            }
+            "range" => self.range_filters(body, now),
+            "query_string" => {
+                let query = body.get("query").and_then(Value::as_str).with_context(|| {
+                    InvalidElasticsearchInputSnafu {
+                        reason: "query_string query requires a query",
+                    }
+                })?;
+                let mut default_fields = vec![];
+                if let Some(field) = body.get("default_field").and_then(Value::as_str) {
+                    default_fields.push(field);
+                }
+                if let Some(fields) = body.get("fields").and_then(Value::as_array) {
+                    default_fields.extend(fields.iter().filter_map(Value::as_str));
+                }
+                let default_and = match body.get("default_operator") {
+                    Some(operator) => parse_operator(operator)?,
+                    None => false,
+                };
+                self.query_string_filters(query, &default_fields, default_and)
+            }
+            _ => NotSupportedSnafu {
+                feat: format!("Elasticsearch query `{kind}`"),
+            }
+            .fail(),
+        }
+    }
+
+    fn bool_filters(&self, body: &Value, now: i64) -> Result<Filters> {
+        let clauses = |name: &str| -> Result<Vec<Filters>> {
+            match body.get(name) {
+                None => Ok(vec![]),
+                Some(Value::Array(queries)) => queries
+                    .iter()
+                    .map(|query| self.query_filters(query, now))
+                    .collect(),
+                Some(query) => Ok(vec![self.query_filters(query, now)?]),
+            }
+        };
+
+        let mut filters = clauses("must")?;
+        filters.extend(clauses("filter")?);
+        let should = clauses("should")?;
+        let minimum_should_match = match body.get("minimum_should_match") {
+            None if filters.is_empty() => 1,
+            None => 0,
+            Some(value) => scalar_to_string(value)?.parse::<u64>().map_err(|_| {
+                NotSupportedSnafu {
+                    feat: format!("minimum_should_match `{value}`"),
+                }
+                .build()
+            })?,
+        };
+        ensure!(
+            minimum_should_match <= 1,
+            NotSupportedSnafu {
+                feat: "minimum_should_match greater than 1",
+            }
+        );
+        if !should.is_empty() && minimum_should_match == 1 {
+            filters.push(Filters::Or(should));
+        }
+        filters.extend(
+            clauses("must_not")?
+                .into_iter()
+                .map(|filter| Filters::Not(Box::new(filter))),
+        );
+        Ok(Filters::And(filters))
+    }
+
+    fn range_filters(&self, body: &Value, now: i64) -> Result<Filters> {
+        let (field, bounds) = single_entry(body, "range query")?;
+        let (column, _) = self.column(field)?;
+        let is_timestamp = column == self.timestamp_column;
+        let format = bounds.get("format").and_then(Value::as_str);
+
+        let mut filters = vec![];
+        for (bound, value) in bounds.as_object().into_iter().flatten() {
+            let (greater, inclusive) = match bound.as_str() {
+                "gt" => (true, false),
+                "gte" => (true, true),
+                "lt" => (false, false),
+                "lte" => (false, true),
+                "format" | "boost" => continue,
+                _ => {
+                    return NotSupportedSnafu {
+                        feat: format!("range query parameter `{bound}`"),
+                    }
+                    .fail();
+                }
+            };
+            let value = if is_timestamp {
+                date_value(value, format, now)?
+            } else {
+                scalar_to_string(value)?
+            };
+            filters.push(if greater {
+                ContentFilter::GreatThan { value, inclusive }
+            } else {
+                ContentFilter::LessThan { value, inclusive }
+            });
+        }
+
+        Ok(Filters::Single(ColumnFilters {
+            expr: Box::new(LogExpr::NamedIdent(column.to_string())),
+            filters,
+        }))
+    }
+
+    fn query_string_filters(
+        &self,
+        query: &str,
+        default_fields: &[&str],
+        default_and: bool,
+    ) -> Result<Filters> {
+        let default_fields = if default_fields.is_empty() || default_fields == ["*"] {
+            self.default_fields.clone()
+        } else {
+            default_fields
+                .iter()
+                .map(|field| self.column(field).map(|(column, _)| column.to_string()))
+                .collect::<Result<_>>()?
+        };
+        let mut parser = QueryStringParser {
+            target: self,
+            chars: query.chars().peekable(),
+            default_fields,
+            default_and,
+        };
+        let filters = parser.parse_or()?;
+        ensure!(
+            parser.chars.next().is_none(),
+            InvalidElasticsearchInputSnafu {
+                reason: format!("unbalanced parentheses in query_string `{query}`"),
+            }
+        );
+        Ok(filters)
+    }
+}
+
+/// Parser for the Lucene syntax of `query_string`: terms and phrases,
+/// optionally prefixed by `field:`, combined with `AND`, `OR`, `NOT`, `+`, `-`
+/// and parentheses.
+struct QueryStringParser<'a> {
+    target: &'a SearchTarget,
+    chars: Peekable<Chars<'a>>,
+    default_fields: Vec<String>,
+    default_and: bool,
+}
+
+impl QueryStringParser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+    }
+
+    /// Consumes the keyword if it's followed by whitespace.
+    fn eat_keyword(&mut self, keyword: &str) -> bool {
+        let mut lookahead = self.chars.clone();
+        for expected in keyword.chars() {
+            if lookahead.next() != Some(expected) {
+                return false;
+            }
+        }
+        if lookahead.peek().is_some_and(|c| !c.is_whitespace()) {
+            return false;
+        }
+        self.chars = lookahead;
+        true
+    }
+
+    /// Parses clauses up to the end or a closing parenthesis. `AND` binds
+    /// tighter than `OR`.
+    fn parse_or(&mut self) -> Result<Filters> {
+        let mut groups = vec![vec![]];
+        let mut explicit_and = false;
+        loop {
+            self.skip_whitespace();
+            match self.chars.peek() {
+                None | Some(')') => break,
+                _ => {}
+            }
+            if self.eat_keyword("OR") || self.eat_keyword("||") {
+                groups.push(vec![]);
+                explicit_and = false;
+                continue;
+            }
+            if self.eat_keyword("AND") || self.eat_keyword("&&") {
+                explicit_and = true;
+                continue;
+            }
+
+            let clause = self.parse_unary()?;
+            let current = groups.last_mut().unwrap();
+            if !current.is_empty() && !explicit_and && !self.default_and {
+                groups.push(vec![clause]);
+            } else {
+                current.push(clause);
+            }
+            explicit_and = false;
+        }
+
+        let mut groups = groups
+            .into_iter()
+            .filter(|group| !group.is_empty())
+            .map(|mut group| {
+                if group.len() == 1 {
+                    group.pop().unwrap()
+                } else {
+                    Filters::And(group)
+                }
+            })
+            .collect::<Vec<_>>();
+        Ok(match groups.len() {
+            0 => Filters::And(vec![]),
+            1 => groups.pop().unwrap(),
+            _ => Filters::Or(groups),
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<Filters> {
+        self.skip_whitespace();
+        if self.eat_keyword("NOT") || self.chars.next_if(|c| *c == '-' || *c == '!').is_some() {
+            return Ok(Filters::Not(Box::new(self.parse_unary()?)));
+        }
+        if self.chars.next_if_eq(&'+').is_some() {
+            return self.parse_unary();
+        }
+        if self.chars.next_if_eq(&'(').is_some() {
+            let filters = self.parse_or()?;
+            ensure!(
+                self.chars.next() == Some(')'),
+                InvalidElasticsearchInputSnafu {
+                    reason: "unbalanced parentheses in query_string",
+                }
+            );
+            return Ok(filters);
+        }
+
+        let (field, value) = if self.chars.peek() == Some(&'"') {
+            (None, self.parse_phrase()?)
+        } else {
+            let word = self.parse_word();
+            match word.split_once(':') {
+                Some((field, "")) => {
+                    let value = match self.chars.peek() {
+                        Some('"') => self.parse_phrase()?,
+                        Some('(' | '[' | '{') => {
+                            return NotSupportedSnafu {
+                                feat: "grouping and ranges on a field in query_string",
+                            }
+                            .fail();
+                        }
+                        _ => {
+                            return InvalidElasticsearchInputSnafu {
+                                reason: format!(
+                                    "missing value for field `{field}` in query_string"
+                                ),
+                            }
+                            .fail();
+                        }
+                    };
+                    (Some(field.to_string()), value)
+                }
+                Some((field, value)) => (Some(field.to_string()), value.to_string()),
+                None => (None, word),
+            }
+        };
+
+        if value == "*" {
+            return Ok(match field {
+                Some(field) => Filters::Single(ColumnFilters {
+                    expr: Box::new(LogExpr::NamedIdent(
+                        self.target.column(&field)?.0.to_string(),
+                    )),
+                    filters: vec![ContentFilter::Exist],
+                }),
+                None => Filters::And(vec![]),
+            });
+        }
+        ensure!(
+            !value.contains(['*', '?']),
+            NotSupportedSnafu {
+                feat: "wildcards in query_string",
+            }
+        );
+
+        match field {
+            Some(field) => {
+                let (column, is_string) = self.target.column(&field)?;
+                Ok(if is_string {
+                    matches_term(column, &value)
+                } else {
+                    equals(column, false, &value)
+                })
+            }
+            None => {
+                ensure!(
+                    !self.default_fields.is_empty(),
+                    InvalidElasticsearchInputSnafu {
+                        reason: "no field to search, specify `default_field` in the query_string query",
+                    }
+                );
+                Ok(Filters::Or(
+                    self.default_fields
+                        .iter()
+                        .map(|column| matches_term(column, &value))
+                        .collect(),
+                ))
+            }
+        }
+    }
+
+    /// Parses a bare term, or a field name up to the colon if a phrase or a
+    /// group follows it.
+    fn parse_word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(c) = self.chars.next_if(|c| !c.is_whitespace() && *c != ')') {
+            match c {
+                '\\' => {
+                    if let Some(escaped) = self.chars.next() {
+                        word.push(escaped);
+                    }
+                }
+                ':' => {
+                    word.push(c);
+                    if self
+                        .chars
+                        .peek()
+                        .is_some_and(|c| matches!(c, '"' | '(' | '[' | '{'))
+                    {
+                        break;
+                    }
+                }
+                c => word.push(c),
+            }
+        }
+        word
+    }
+
+    fn parse_phrase(&mut self) -> Result<String> {
+        self.chars.next();
+        let mut phrase = String::new();
+        loop {
+            match self.chars.next() {
+                Some('"') => return Ok(phrase),
+                Some('\\') => {
+                    if let Some(c) = self.chars.next() {
+                        phrase.push(c);
+                    }
+                }
+                Some(c) => phrase.push(c),
+                None => {
+                    return InvalidElasticsearchInputSnafu {
+                        reason: "unterminated phrase in query_string",
+                    }
+                    .fail();
+                }
+            }
+        }
+    }
+}
+
+/// The search covers all time. Range queries on the time index narrow it down.
+fn unbounded_time_filter() -> TimeFilter {
+    TimeFilter {
+        start: Some("1970-01-01T00:00:00Z".to_string()),
+        end: Some("9999-12-31T23:59:59Z".to_string()),
+        span: None,
+    }
+}
+
+fn matches_term(column: &str, term: &str) -> Filters {
+    Filters::Single(ColumnFilters {
+        expr: Box::new(LogExpr::ScalarFunc {
+            name: "matches_term".to_string(),
+            args: vec![
+                LogExpr::NamedIdent(column.to_string()),
+                LogExpr::Literal(term.to_string()),
+            ],
+            alias: None,
+        }),
+        filters: vec![ContentFilter::IsTrue],
+    })
+}
+
+/// Compares a column with a value. The value is cast to the column type for
+/// non-string columns.
+fn equals(column: &str, is_string: bool, value: &str) -> Filters {
+    if is_string {
+        Filters::Single(ColumnFilters {
+            expr: Box::new(LogExpr::NamedIdent(column.to_string())),
+            filters: vec![ContentFilter::Equal(EqualValue::String(value.to_string()))],
+        })
+    } else {
+        Filters::Single(ColumnFilters {
+            expr: Box::new(LogExpr::BinaryOp {
+                left: Box::new(LogExpr::NamedIdent(column.to_string())),
+                op: BinaryOperator::Eq,
+                right: Box::new(LogExpr::Literal(value.to_string())),
+            }),
+            filters: vec![],
+        })
+    }
+}
+
+/// Returns the only entry of a JSON object, like `{"match": {...}}`.
+fn single_entry<'a>(value: &'a Value, what: &str) -> Result<(&'a str, &'a Value)> {
+    match value.as_object() {
+        Some(object) if object.len() == 1 => {
+            let (key, value) = object.iter().next().unwrap();
+            Ok((key.as_str(), value))
+        }
+        _ => InvalidElasticsearchInputSnafu {
+            reason: format!("{what} must be an object with a single entry, found `{value}`"),
+        }
+        .fail(),
+    }
+}
+
+/// Returns the value of a leaf query in either the short form
+/// `{"field": value}` or the long form `{"field": {"<key>": value, ...}}`.
+fn query_value<'a>(
+    body: &'a Value,
+    key: &str,
+) -> Result<(&'a Value, Option<&'a Map<String, Value>>)> {
+    match body {
+        Value::Object(options) => {
+            let value = options
+                .get(key)
+                .with_context(|| InvalidElasticsearchInputSnafu {
+                    reason: format!("missing `{key}` in `{body}`"),
+                })?;
+            Ok((value, Some(options)))
+        }
+        value => Ok((value, None)),
+    }
+}
+
+fn scalar_to_string(value: &Value) -> Result<String> {
+    match value {
+        Value::String(s) => Ok(s.clone()),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        _ => InvalidElasticsearchInputSnafu {
+            reason: format!("expected a string, number or boolean, found `{value}`"),
+        }
+        .fail(),
+    }
+}
+
+/// Returns whether the order is ascending.
+fn parse_order(order: &Value) -> Result<bool> {
+    match order.as_str() {
+        Some("asc") => Ok(true),
+        Some("desc") => Ok(false),
+        _ => InvalidElasticsearchInputSnafu {
+            reason: format!("invalid order `{order}`"),
+        }
+        .fail(),
+    }
+}
+
+/// Returns whether the operator is `and`.
+fn parse_operator(operator: &Value) -> Result<bool> {
+    match operator.as_str().map(str::to_ascii_lowercase).as_deref() {
+        Some("and") => Ok(true),
+        Some("or") => Ok(false),
+        _ => InvalidElasticsearchInputSnafu {
+            reason: format!("invalid operator `{operator}`"),
+        }
+        .fail(),
+    }
+}
+
+/// Returns whether the `zero_terms_query` matches all documents.
+fn parse_zero_terms_query(zero_terms_query: &Value) -> Result<bool> {
+    match zero_terms_query
+        .as_str()
+        .map(str::to_ascii_lowercase)
+        .as_deref()
+    {
+        Some("all") => Ok(true),
+        Some("none") => Ok(false),
+        _ => InvalidElasticsearchInputSnafu {
+            reason: format!("invalid zero_terms_query `{zero_terms_query}`"),
+        }
+        .fail(),
+    }
+}
+
+fn histogram_interval(body: &Value) -> Result<HistogramInterval> {
+    if let Some(interval) = body.get("fixed_interval").and_then(Value::as_str) {
+        return fixed_interval(interval);
+    }
+    let interval = body
+        .get("calendar_interval")
+        .or_else(|| body.get("interval"))
+        .and_then(Value::as_str)
+        .context(InvalidElasticsearchInputSnafu {
+            reason: "date_histogram requires `fixed_interval` or `calendar_interval`",
+        })?;
+    let unit = match interval {
+        "minute" | "1m" => "minute",
+        "hour" | "1h" => "hour",
+        "day" | "1d" => "day",
+        "week" | "1w" => "week",
+        "month" | "1M" => "month",
+        "quarter" | "1q" => "quarter",
+        "year" | "1y" => "year",
+        // The deprecated `interval` also accepts fixed intervals.
+        _ => return fixed_interval(interval),
+    };
+    Ok(HistogramInterval::Calendar(unit))
+}
+
+fn fixed_interval(interval: &str) -> Result<HistogramInterval> {
+    match parse_duration(interval) {
+        Ok(millis) if millis > 0 => Ok(HistogramInterval::Fixed(millis)),
+        _ => InvalidElasticsearchInputSnafu {
+            reason: format!("invalid interval `{interval}`"),
+        }
+        .fail(),
+    }
+}
+
+/// Converts a date in a range query into an RFC3339 timestamp. `now` is in
+/// milliseconds.
+fn date_value(value: &Value, format: Option<&str>, now: i64) -> Result<String> {
+    let millis = match value {
+        Value::Number(n) => {
+            let n = n.as_i64().with_context(|| InvalidElasticsearchInputSnafu {
+                reason: format!("invalid date `{n}`"),
+            })?;
+            if format == Some("epoch_second") {
+                n * 1000
+            } else {
+                n
+            }
+        }
+        Value::String(s) if s.starts_with("now") => date_math(s, now)?,
+        Value::String(s) => match (format, s.parse::<i64>()) {
+            (Some("epoch_second"), Ok(n)) => n * 1000,
+            (Some("epoch_millis"), Ok(n)) => n,
+            _ => return Ok(s.clone()),
+        },
+        _ => {
+            return InvalidElasticsearchInputSnafu {
+                reason: format!("invalid date `{value}`"),
+            }
+            .fail();
+        }
+    };
+    DateTime::from_timestamp_millis(millis)
+        .map(|dt| dt.to_rfc3339_opts(SecondsFormat::Millis, true))
+        .with_context(|| InvalidElasticsearchInputSnafu {
+            reason: format!("date out of range `{value}`"),
+        })
+}
+
+/// Evaluates date math like `now-15m` or `now/d`.
+fn date_math(expr: &str, now: i64) -> Result<i64> {
+    let invalid = || {
+        InvalidElasticsearchInputSnafu {
+            reason: format!("invalid date math `{expr}`"),
+        }
+        .build()
+    };
+    let unit_millis = |unit: char| -> Result<i64> {
+        match unit {
+            's' => Ok(1_000),
+            'm' => Ok(60_000),
+            'h' | 'H' => Ok(3_600_000),
+            'd' => Ok(86_400_000),
+            'w' => Ok(604_800_000),
+            'M' | 'y' => NotSupportedSnafu {
+                feat: format!("date math unit `{unit}`"),
+            }
+            .fail(),
+            _ => Err(invalid()),
+        }
+    };
+
+    let mut chars = expr
+        .strip_prefix("now")
+        .ok_or_else(invalid)?
+        .chars()
+        .peekable();
+    let mut millis = now;
+    while let Some(op) = chars.next() {
+        match op {
+            '+' | '-' => {
+                let mut amount = String::new();
+                while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                    amount.push(c);
+                }
+                let amount = amount.parse::<i64>().map_err(|_| invalid())?;
+                let delta = amount * unit_millis(chars.next().ok_or_else(invalid)?)?;
+                millis = if op == '+' {
+                    millis + delta
+                } else {
+                    millis - delta
+                };
+            }
+            '/' => {
+                let unit = chars.next().ok_or_else(invalid)?;
+                ensure!(
+                    unit != 'w',
+                    NotSupportedSnafu {
+                        feat: "rounding date math to weeks",
+                    }
+                );
+                millis -= millis.rem_euclid(unit_millis(unit)?);
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    Ok(millis)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::error::Error;
+
+    fn target() -> SearchTarget {
+        SearchTarget {
+            table: TableName::new("greptime", "public", "logs"),
+            timestamp_column: "ts".to_string(),
+            columns: vec![
+                ("ts".to_string(), false),
+                ("message".to_string(), true),
+                ("level".to_string(), true),
+                ("status".to_string(), false),
+            ],
+            default_fields: vec!["message".to_string()],
+        }
+    }
+
+    fn debug(filters: &Filters) -> String {
+        format!("{filters:?}")
+    }
+
+    #[test]
+    fn test_bool_query() {
+        let query = json!({
+            "bool": {
+                "must": [{"match": {"message": "disk full"}}],
+                "filter": {"term": {"level.keyword": "error"}},
+                "should": [{"term": {"status": 500}}],
+                "must_not": [{"match_phrase": {"message": {"query": "retry later"}}}],
+            }
+        });
+        let Filters::And(filters) = target().filters(Some(&query), 0).unwrap() else {
+            panic!("expected conjunction");
+        };
+        // `should` is optional next to `must`.
+        assert_eq!(filters.len(), 3);
+        assert!(matches!(&filters[0], Filters::Or(terms) if terms.len() == 2));
+        assert!(debug(&filters[0]).contains("matches_term"));
+        assert!(debug(&filters[1]).contains(r#"Equal(String("error"))"#));
+        assert!(matches!(&filters[2], Filters::Not(_)));
+        assert!(debug(&filters[2]).contains("retry later"));
+
+        let query = json!({"bool": {"should": [{"term": {"status": 500}}]}});
+        let Filters::And(filters) = target().filters(Some(&query), 0).unwrap() else {
+            panic!("expected conjunction");
+        };
+        assert!(matches!(&filters[..], [Filters::Or(_)]));
+        assert!(debug(&filters[0]).contains("BinaryOp"));
+    }
+
+    #[test]
+    fn test_range_query() {
+        let query = json!({"range": {"ts": {"gte": "now-15m", "lt": 1_700_000_000_000_i64}}});
+        let Filters::Single(filter) = target().filters(Some(&query), 1_700_000_900_000).unwrap()
+        else {
+            panic!("expected single filter");
+        };
+        assert!(matches!(
+            &filter.filters[..],
+            [
+                ContentFilter::GreatThan { value: start, inclusive: true },
+                ContentFilter::LessThan { value: end, inclusive: false },
+            ] if start == "2023-11-14T22:13:20.000Z" && end == "2023-11-14T22:13:20.000Z"
+        ));
+
+        let query = json!({"range": {"status": {"gt": 499}}});
+        let Filters::Single(filter) = target().filters(Some(&query), 0).unwrap() else {
+            panic!("expected single filter");
+        };
+        assert!(matches!(
+            &filter.filters[..],
+            [ContentFilter::GreatThan { value, inclusive: false }] if value == "499"
+        ));
+    }
+
+    #[test]
+    fn test_date_math() {
+        let now = 1_700_000_123_456;
+        assert_eq!(date_math("now", now).unwrap(), now);
+        assert_eq!(date_math("now-1h", now).unwrap(), now - 3_600_000);
+        assert_eq!(date_math("now+2d/d", now).unwrap(), 1_700_092_800_000);
+        assert!(date_math("now-1x", now).is_err());
+        assert!(matches!(
+            date_math("now-1M", now),
+            Err(Error::NotSupported { .. })
+        ));
+    }
+
+    #[test]
+    fn test_query_string() {
+        let target = target();
+        let filters = target
+            .query_string_filters(r#"level:error AND "disk full" OR -status:200"#, &[], false)
+            .unwrap();
+        let Filters::Or(groups) = filters else {
+            panic!("expected disjunction");
+        };
+        assert_eq!(groups.len(), 2);
+        let Filters::And(first) = &groups[0] else {
+            panic!("expected conjunction");
+        };
+        assert_eq!(first.len(), 2);
+        assert!(debug(&first[0]).contains(r#"NamedIdent("level")"#));
+        assert!(debug(&first[1]).contains(r#"NamedIdent("message")"#));
+        assert!(matches!(&groups[1], Filters::Not(_)));
+
+        // The implicit operator is the default operator.
+        let filters = target
+            .query_string_filters("disk full", &["message"], true)
+            .unwrap();
+        assert!(matches!(filters, Filters::And(terms) if terms.len() == 2));
+
+        assert!(matches!(
+            target.query_string_filters("disk*", &[], false),
+            Err(Error::NotSupported { .. })
+        ));
+        assert!(matches!(
+            target.query_string_filters("(disk", &[], false),
+            Err(Error::InvalidElasticsearchInput { .. })
+        ));
+        assert!(matches!(
+            target.query_string_filters("host:web", &[], false),
+            Err(Error::InvalidElasticsearchInput { .. })
+        ));
+    }
+
+    #[test]
+    fn test_aggregations() {
+        let target = target();
+        let aggs = json!({
+            "levels": {"terms": {"field": "level.keyword", "size": 5, "order": {"_key": "asc"}}},
+            "over_time": {"date_histogram": {"field": "ts", "fixed_interval": "30s"}},
+            "per_month": {"date_histogram": {"field": "ts", "calendar_interval": "month"}},
+        });
+        let aggregations = target.aggregations(aggs.as_object().unwrap()).unwrap();
+        assert_eq!(
+            aggregations,
+            vec![
+                (
+                    "levels".to_string(),
+                    Aggregation::Terms {
+                        field: "level".to_string(),
+                        size: 5,
+                        by_key: true,
+                        ascending: true,
+                    }
+                ),
+                (
+                    "over_time".to_string(),
+                    Aggregation::DateHistogram {
+                        field: "ts".to_string(),
+                        interval: HistogramInterval::Fixed(30_000),
+                    }
+                ),
+                (
+                    "per_month".to_string(),
+                    Aggregation::DateHistogram {
+                        field: "ts".to_string(),
+                        interval: HistogramInterval::Calendar("month"),
+                    }
+                ),
+            ]
+        );
+
+        let aggs = json!({"levels": {"terms": {"field": "level", "size": 10_001}}});
+        assert!(matches!(
+            target.aggregations(aggs.as_object().unwrap()),
+            Err(Error::InvalidElasticsearchInput { .. })
+        ));
+
+        // Buckets are ordered by descending count, then by ascending key.
+        let query = target.aggregation_query(
+            Filters::And(vec![]),
+            &Aggregation::Terms {
+                field: "level".to_string(),
+                size: 10,
+                by_key: false,
+                ascending: false,
+            },
+        );
+        assert!(matches!(
+            &query.exprs[1..],
+            [
+                LogExpr::Sort {
+                    by: count,
+                    descending: true,
+                },
+                LogExpr::Sort {
+                    by: key,
+                    descending: false,
+                },
+            ] if matches!(&count[..], [LogExpr::NamedIdent(name)] if name == DOC_COUNT_COLUMN)
+                && matches!(&key[..], [LogExpr::NamedIdent(name)] if name == KEY_COLUMN)
+        ));
+
+        let aggs = json!({"avg_status": {"avg": {"field": "status"}}});
+        assert!(matches!(
+            target.aggregations(aggs.as_object().unwrap()),
+            Err(Error::NotSupported { .. })
+        ));
+
+        // Documents without the field are excluded from the buckets.
+        let filters = target.aggregation_filters(Filters::And(vec![]), &aggregations[0].1);
+        let Filters::And(filters) = filters else {
+            panic!("expected conjunction");
+        };
+        assert!(matches!(
+            &filters[1],
+            Filters::Single(ColumnFilters { expr, filters })
+                if matches!(expr.as_ref(), LogExpr::NamedIdent(name) if name == "level")
+                    && matches!(filters[..], [ContentFilter::Exist])
+        ));
+    }
+
+    #[test]
+    fn test_match_zero_terms() {
+        let target = target();
+        let none = debug(&target.match_none());
+        let query = json!({"match": {"message": "  "}});
+        assert_eq!(debug(&target.filters(Some(&query), 0).unwrap()), none);
+        let query = json!({"match": {"message": {"query": "", "zero_terms_query": "none"}}});
+        assert_eq!(debug(&target.filters(Some(&query), 0).unwrap()), none);
+        let query = json!({"match": {"message": {"query": "", "zero_terms_query": "all"}}});
+        assert!(matches!(
+            target.filters(Some(&query), 0).unwrap(),
+            Filters::And(filters) if filters.is_empty()
+        ));
+        let query = json!({"match": {"message": {"query": "", "zero_terms_query": "some"}}});
+        assert!(target.filters(Some(&query), 0).is_err());
+        let query = json!({"match_none": {}});
+        assert_eq!(debug(&target.filters(Some(&query), 0).unwrap()), none);
+    }
+
+    #[test]
+    fn test_hits_query() {
+        let request: SearchRequest = serde_json::from_value(json!({
+            "size": 20,
+            "from": 40,
+            "sort": [{"ts": {"order": "desc"}}, "_score", {"level": "desc"}],
+        }))
+        .unwrap();
+        let query = target().hits_query(Filters::And(vec![]), &request).unwrap();
+        assert_eq!(query.limit.skip, Some(40));
+        assert_eq!(query.limit.fetch, Some(20));
+        let [LogExpr::Sort { by, descending }] = &query.exprs[..] else {
+            panic!("expected sort");
+        };
+        assert_eq!(by.len(), 2);
+        assert!(*descending);
+
+        let request: SearchRequest = serde_json::from_value(json!({
+            "sort": [{"ts": {"order": "desc"}}, {"level": "asc"}, "status"],
+        }))
+        .unwrap();
+        let query = target().hits_query(Filters::And(vec![]), &request).unwrap();
+        let [
+            LogExpr::Sort {
+                by: desc_keys,
+                descending: true,
+            },
+            LogExpr::Sort {
+                by: asc_keys,
+                descending: false,
+            },
+        ] = &query.exprs[..]
+        else {
+            panic!("expected a descending and an ascending sort");
+        };
+        assert_eq!(desc_keys.len(), 1);
+        assert_eq!(asc_keys.len(), 2);
+
+        for request in [
+            json!({"size": 10_001}),
+            json!({"from": 9_995, "size": 10}),
+            json!({"from": 10_000}),
+        ] {
+            let request: SearchRequest = serde_json::from_value(request).unwrap();
+            assert!(matches!(
+                target().hits_query(Filters::And(vec![]), &request),
+                Err(Error::InvalidElasticsearchInput { .. })
+            ));
+        }
+        let request: SearchRequest =
+            serde_json::from_value(json!({"from": 9_990, "size": 10})).unwrap();
+        assert!(target().hits_query(Filters::And(vec![]), &request).is_ok());
+    }
+}
@@ -0,0 +1,622 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Elasticsearch [search] and [count] APIs. An index is a table of the
+//! current database.
+//!
+//! [search]: https://www.elastic.co/guide/en/elasticsearch/reference/current/search-search.html
+//! [count]: https://www.elastic.co/guide/en/elasticsearch/reference/current/search-count.html
+
+use std::sync::Arc;
+use std::time::Instant;
+
+use arrow::array::{Array, ArrayRef, AsArray, RecordBatch};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Float64Type, Int64Type, TimeUnit};
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Json};
+use bytes::Bytes;
+use chrono::{DateTime, SecondsFormat};
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode as ErrorCode;
+use common_telemetry::tracing;
+use common_time::util::current_time_millis;
+use log_query::LogQuery;
+use serde::Deserialize;
+use serde_json::{Map, Value, json};
+use session::context::{Channel, QueryContext, QueryContextRef};
+use snafu::{OptionExt, ResultExt, ensure};
+use table::table_name::TableName;
+
+use crate::elasticsearch::elasticsearch_headers;
+use crate::elasticsearch::query_dsl::{
+    Aggregation, DOC_COUNT_COLUMN, KEY_COLUMN, SearchRequest, SearchTarget,
+};
+use crate::error::{
+    ArrowSnafu, Error, InvalidElasticsearchInputSnafu, NotSupportedSnafu, Result,
+    TableNotFoundSnafu, UnexpectedResultSnafu, status_code_to_http_status,
+};
+use crate::http::logs::query_record_batches;
+use crate::metrics::METRIC_HTTP_LOGS_ELAPSED;
+use crate::query_handler::LogQueryHandlerRef;
+
+/// URL parameters of the search and count APIs. They take precedence over the
+/// request body.
+#[derive(Debug, Default, Deserialize)]
+pub struct SearchParams {
+    /// Query in the Lucene query string syntax.
+    q: Option<String>,
+    size: Option<usize>,
+    from: Option<usize>,
+}
+
+/// Handler for `/{index}/_search`.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "elasticsearch", request_type = "search"))]
+pub async fn handle_search_api(
+    State(handler): State<LogQueryHandlerRef>,
+    Extension(query_ctx): Extension<QueryContext>,
+    Path(index): Path<String>,
+    Query(params): Query<SearchParams>,
+    body: Bytes,
+) -> Response {
+    let start = Instant::now();
+    let query_ctx = search_query_context(query_ctx);
+    let _timer = METRIC_HTTP_LOGS_ELAPSED
+        .with_label_values(&[query_ctx.get_db_string().as_str()])
+        .start_timer();
+
+    match search(&handler, &query_ctx, &index, params, &body, start).await {
+        Ok(response) => (StatusCode::OK, elasticsearch_headers(), Json(response)).into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+/// Handler for `/{index}/_count`.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "elasticsearch", request_type = "count"))]
+pub async fn handle_count_api(
+    State(handler): State<LogQueryHandlerRef>,
+    Extension(query_ctx): Extension<QueryContext>,
+    Path(index): Path<String>,
+    Query(params): Query<SearchParams>,
+    body: Bytes,
+) -> Response {
+    let query_ctx = search_query_context(query_ctx);
+    let _timer = METRIC_HTTP_LOGS_ELAPSED
+        .with_label_values(&[query_ctx.get_db_string().as_str()])
+        .start_timer();
+
+    match count(&handler, &query_ctx, &index, params, &body).await {
+        Ok(count) => (
+            StatusCode::OK,
+            elasticsearch_headers(),
+            Json(json!({
+                "count": count,
+                "_shards": shards(),
+            })),
+        )
+            .into_response(),
+        Err(e) => error_response(e),
+    }
+}
+
+fn search_query_context(mut query_ctx: QueryContext) -> QueryContextRef {
+    query_ctx.set_channel(Channel::Elasticsearch);
+    Arc::new(query_ctx)
+}
+
+async fn search(
+    handler: &LogQueryHandlerRef,
+    query_ctx: &QueryContextRef,
+    index: &str,
+    params: SearchParams,
+    body: &[u8],
+    start: Instant,
+) -> Result<Value> {
+    let request = parse_request(params, body)?;
+    let target = resolve_target(handler, query_ctx, index).await?;
+    let filters = target.filters(request.query.as_ref(), current_time_millis())?;
+    let aggregations = match &request.aggs {
+        Some(aggs) => target.aggregations(aggs)?,
+        None => vec![],
+    };
+
+    let total = count_documents(handler, query_ctx, target.count_query(filters.clone())).await?;
+
+    let mut hits = vec![];
+    if request.size != Some(0) {
+        let query = target.hits_query(filters.clone(), &request)?;
+        let from = request.from.unwrap_or_default();
+        for batch in query_record_batches(handler, query, query_ctx).await? {
+            for source in batch_rows(&batch)? {
+                hits.push(json!({
+                    "_index": index,
+                    "_id": (from + hits.len()).to_string(),
+                    "_score": null,
+                    "_source": filter_source(source, request.source.as_ref())?,
+                }));
+            }
+        }
+        // `_source: false` omits the documents.
+        if request.source == Some(Value::Bool(false)) {
+            for hit in &mut hits {
+                hit.as_object_mut().unwrap().remove("_source");
+            }
+        }
+    }
+
+    let mut response = json!({
+        "took": start.elapsed().as_millis() as u64,
+        "timed_out": false,
+        "_shards": shards(),
+        "hits": {
+            "total": {"value": total, "relation": "eq"},
+            "max_score": null,
+            "hits": hits,
+        },
+    });
+
+    if !aggregations.is_empty() {
+        let mut results = Map::new();
+        for (name, aggregation) in aggregations {
+            let filters = target.aggregation_filters(filters.clone(), &aggregation);
+            // Only documents having the field are counted as other documents.
+            let total = match aggregation {
+                Aggregation::Terms { .. } => {
+                    count_documents(handler, query_ctx, target.count_query(filters.clone())).await?
+                }
+                Aggregation::DateHistogram { .. } => total,
+            };
+            let query = target.aggregation_query(filters, &aggregation);
+            let batches = query_record_batches(handler, query, query_ctx).await?;
+            results.insert(name, aggregation_result(&aggregation, &batches, total)?);
+        }
+        response["aggregations"] = Value::Object(results);
+    }
+
+    Ok(response)
+}
+
+async fn count(
+    handler: &LogQueryHandlerRef,
+    query_ctx: &QueryContextRef,
+    index: &str,
+    params: SearchParams,
+    body: &[u8],
+) -> Result<u64> {
+    let request = parse_request(params, body)?;
+    let target = resolve_target(handler, query_ctx, index).await?;
+    let filters = target.filters(request.query.as_ref(), current_time_millis())?;
+    count_documents(handler, query_ctx, target.count_query(filters)).await
+}
+
+/// Parses the request body. An empty body matches all documents.
+fn parse_request(params: SearchParams, body: &[u8]) -> Result<SearchRequest> {
+    let mut request = if body.iter().all(u8::is_ascii_whitespace) {
+        SearchRequest::default()
+    } else {
+        serde_json::from_slice::<SearchRequest>(body).map_err(|e| {
+            InvalidElasticsearchInputSnafu {
+                reason: format!("invalid search request: {e}"),
+            }
+            .build()
+        })?
+    };
+
+    if let Some(q) = params.q {
+        request.query = Some(json!({"query_string": {"query": q}}));
+    }
+    if params.size.is_some() {
+        request.size = params.size;
+    }
+    if params.from.is_some() {
+        request.from = params.from;
+    }
+    Ok(request)
+}
+
+async fn resolve_target(
+    handler: &LogQueryHandlerRef,
+    query_ctx: &QueryContextRef,
+    index: &str,
+) -> Result<SearchTarget> {
+    ensure!(
+        !index.contains(['*', ',']),
+        NotSupportedSnafu {
+            feat: "searching multiple Elasticsearch indices",
+        }
+    );
+
+    let catalog = query_ctx.current_catalog();
+    let schema = query_ctx.current_schema();
+    let table = handler
+        .catalog_manager(query_ctx)?
+        .table(catalog, &schema, index, Some(query_ctx))
+        .await?
+        .with_context(|| TableNotFoundSnafu {
+            catalog,
+            schema: &schema,
+            table: index,
+        })?;
+
+    let table_schema = table.schema();
+    let timestamp_column = table_schema
+        .timestamp_column()
+        .with_context(|| InvalidElasticsearchInputSnafu {
+            reason: format!("index `{index}` has no time index"),
+        })?
+        .name
+        .clone();
+    let columns = table_schema
+        .column_schemas()
+        .iter()
+        .map(|column| (column.name.clone(), column.data_type.is_string()))
+        .collect::<Vec<_>>();
+    // Prefer the columns with a fulltext index for unfielded terms.
+    let mut default_fields = table_schema
+        .column_schemas()
+        .iter()
+        .filter(|column| column.is_fulltext_indexed())
+        .map(|column| column.name.clone())
+        .collect::<Vec<_>>();
+    if default_fields.is_empty() {
+        default_fields = columns
+            .iter()
+            .filter(|(_, is_string)| *is_string)
+            .map(|(name, _)| name.clone())
+            .collect();
+    }
+
+    Ok(SearchTarget {
+        table: TableName::new(catalog, schema, index),
+        timestamp_column,
+        columns,
+        default_fields,
+    })
+}
+
+async fn count_documents(
+    handler: &LogQueryHandlerRef,
+    query_ctx: &QueryContextRef,
+    query: LogQuery,
+) -> Result<u64> {
+    let batches = query_record_batches(handler, query, query_ctx).await?;
+    let count = batches
+        .iter()
+        .find(|batch| batch.num_rows() > 0)
+        .map(|batch| column_values(batch.column(0), false))
+        .transpose()?
+        .and_then(|values| values.first().and_then(Value::as_u64))
+        .unwrap_or_default();
+    Ok(count)
+}
+
+fn shards() -> Value {
+    json!({"total": 1, "successful": 1, "skipped": 0, "failed": 0})
+}
+
+/// Converts a batch into one JSON object per row, omitting null values.
+fn batch_rows(batch: &RecordBatch) -> Result<Vec<Map<String, Value>>> {
+    let mut rows = vec![Map::new(); batch.num_rows()];
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        for (row, value) in column_values(column, true)?.into_iter().enumerate() {
+            if !value.is_null() {
+                rows[row].insert(field.name().clone(), value);
+            }
+        }
+    }
+    Ok(rows)
+}
+
+/// Converts a column into JSON values. Timestamps are RFC3339 strings if
+/// `iso_timestamps`, otherwise milliseconds since the epoch.
+fn column_values(column: &ArrayRef, iso_timestamps: bool) -> Result<Vec<Value>> {
+    let values = match column.data_type() {
+        DataType::Boolean => column
+            .as_boolean()
+            .iter()
+            .map(|v| v.map(Value::from).unwrap_or_default())
+            .collect(),
+        data_type if data_type.is_integer() => cast(column, &DataType::Int64)
+            .context(ArrowSnafu)?
+            .as_primitive::<Int64Type>()
+            .iter()
+            .map(|v| v.map(Value::from).unwrap_or_default())
+            .collect(),
+        data_type if data_type.is_floating() => cast(column, &DataType::Float64)
+            .context(ArrowSnafu)?
+            .as_primitive::<Float64Type>()
+            .iter()
+            .map(|v| v.map(Value::from).unwrap_or_default())
+            .collect(),
+        DataType::Timestamp(..) => {
+            let millis = cast(column, &DataType::Timestamp(TimeUnit::Millisecond, None))
+                .context(ArrowSnafu)?;
+            cast(&millis, &DataType::Int64)
+                .context(ArrowSnafu)?
+                .as_primitive::<Int64Type>()
+                .iter()
+                .map(|v| match v {
+                    Some(millis) if iso_timestamps => DateTime::from_timestamp_millis(millis)
+                        .map(|dt| Value::from(dt.to_rfc3339_opts(SecondsFormat::Millis, true)))
+                        .unwrap_or_default(),
+                    v => v.map(Value::from).unwrap_or_default(),
+                })
+                .collect()
+        }
+        _ => cast(column, &DataType::Utf8)
+            .context(ArrowSnafu)?
+            .as_string::<i32>()
+            .iter()
+            .map(|v| v.map(Value::from).unwrap_or_default())
+            .collect(),
+    };
+    Ok(values)
+}
+
+/// Applies [source filtering] to a document.
+///
+/// [source filtering]: https://www.elastic.co/guide/en/elasticsearch/reference/current/search-fields.html#source-filtering
+fn filter_source(mut source: Map<String, Value>, filter: Option<&Value>) -> Result<Value> {
+    let fields = |value: Option<&Value>| -> Option<Vec<String>> {
+        match value? {
+            Value::String(field) => Some(vec![field.clone()]),
+            Value::Array(fields) => Some(
+                fields
+                    .iter()
+                    .filter_map(|field| field.as_str().map(ToString::to_string))
+                    .collect(),
+            ),
+            _ => None,
+        }
+    };
+
+    let (includes, excludes) = match filter {
+        None | Some(Value::Bool(_)) => (None, None),
+        Some(Value::Object(options)) => (
+            fields(options.get("includes")),
+            fields(options.get("excludes")),
+        ),
+        Some(value @ (Value::String(_) | Value::Array(_))) => (fields(Some(value)), None),
+        Some(value) => {
+            return InvalidElasticsearchInputSnafu {
+                reason: format!("invalid _source `{value}`"),
+            }
+            .fail();
+        }
+    };
+
+    if let Some(includes) = includes {
+        source.retain(|field, _| includes.contains(field));
+    }
+    if let Some(excludes) = excludes {
+        source.retain(|field, _| !excludes.contains(field));
+    }
+    Ok(Value::Object(source))
+}
+
+fn aggregation_result(
+    aggregation: &Aggregation,
+    batches: &[RecordBatch],
+    total: u64,
+) -> Result<Value> {
+    let mut keys = vec![];
+    let mut counts = vec![];
+    for batch in batches {
+        let column = |name| {
+            batch
+                .column_by_name(name)
+                .with_context(|| UnexpectedResultSnafu {
+                    reason: format!("column `{name}` not found in aggregation result"),
+                })
+        };
+        keys.extend(column_values(column(KEY_COLUMN)?, false)?);
+        counts.extend(column_values(column(DOC_COUNT_COLUMN)?, false)?);
+    }
+    let buckets = keys
+        .into_iter()
+        .zip(counts)
+        .filter(|(key, _)| !key.is_null())
+        .map(|(key, count)| (key, count.as_u64().unwrap_or_default()));
+
+    Ok(match aggregation {
+        Aggregation::Terms { .. } => {
+            let buckets = buckets
+                .map(|(key, doc_count)| json!({"key": key, "doc_count": doc_count}))
+                .collect::<Vec<_>>();
+            let bucketed = buckets
+                .iter()
+                .filter_map(|bucket| bucket["doc_count"].as_u64())
+                .sum::<u64>();
+            json!({
+                "doc_count_error_upper_bound": 0,
+                "sum_other_doc_count": total.saturating_sub(bucketed),
+                "buckets": buckets,
+            })
+        }
+        Aggregation::DateHistogram { .. } => {
+            let buckets = buckets
+                .map(|(key, doc_count)| {
+                    let key_as_string = key
+                        .as_i64()
+                        .and_then(DateTime::from_timestamp_millis)
+                        .map(|dt| dt.to_rfc3339_opts(SecondsFormat::Millis, true));
+                    json!({"key_as_string": key_as_string, "key": key, "doc_count": doc_count})
+                })
+                .collect::<Vec<_>>();
+            json!({ "buckets": buckets })
+        }
+    })
+}
+
+/// Renders an error in the Elasticsearch error format.
+fn error_response(error: Error) -> Response {
+    let status_code = error.status_code();
+    let error_type = match status_code {
+        ErrorCode::TableNotFound => "index_not_found_exception",
+        ErrorCode::InvalidArguments | ErrorCode::Unsupported => "illegal_argument_exception",
+        _ => "exception",
+    };
+    let reason = error.output_msg();
+    let status = status_code_to_http_status(&status_code);
+    (
+        status,
+        elasticsearch_headers(),
+        Json(json!({
+            "error": {
+                "root_cause": [{"type": error_type, "reason": reason}],
+                "type": error_type,
+                "reason": reason,
+            },
+            "status": status.as_u16(),
+        })),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow::array::{Int64Array, StringArray, TimestampMillisecondArray};
+    use arrow::datatypes::{Field, Schema};
+
+    use super::*;
+    use crate::elasticsearch::query_dsl::HistogramInterval;
+
+    #[test]
+    fn test_parse_request() {
+        let request = parse_request(SearchParams::default(), b" ").unwrap();
+        assert!(request.query.is_none());
+
+        let params = SearchParams {
+            q: Some("level:error".to_string()),
+            size: Some(5),
+            from: None,
+        };
+        let request = parse_request(params, br#"{"size": 20, "from": 10}"#).unwrap();
+        assert_eq!(request.size, Some(5));
+        assert_eq!(request.from, Some(10));
+        assert_eq!(
+            request.query,
+            Some(json!({"query_string": {"query": "level:error"}}))
+        );
+
+        assert!(parse_request(SearchParams::default(), b"{").is_err());
+    }
+
+    #[test]
+    fn test_batch_rows() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                "ts",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+            Field::new("message", DataType::Utf8, true),
+            Field::new("status", DataType::Int64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(TimestampMillisecondArray::from(vec![0, 1_500])),
+                Arc::new(StringArray::from(vec![Some("hello"), None])),
+                Arc::new(Int64Array::from(vec![200, 500])),
+            ],
+        )
+        .unwrap();
+
+        let rows = batch_rows(&batch).unwrap();
+        assert_eq!(
+            Value::from(rows.clone()),
+            json!([
+                {"ts": "1970-01-01T00:00:00.000Z", "message": "hello", "status": 200},
+                {"ts": "1970-01-01T00:00:01.500Z", "status": 500},
+            ])
+        );
+
+        let source = filter_source(rows[0].clone(), Some(&json!(["message"]))).unwrap();
+        assert_eq!(source, json!({"message": "hello"}));
+        let source =
+            filter_source(rows[0].clone(), Some(&json!({"excludes": ["message"]}))).unwrap();
+        assert_eq!(
+            source,
+            json!({"ts": "1970-01-01T00:00:00.000Z", "status": 200})
+        );
+    }
+
+    #[test]
+    fn test_aggregation_result() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(KEY_COLUMN, DataType::Utf8, true),
+            Field::new(DOC_COUNT_COLUMN, DataType::Int64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec![Some("info"), None, Some("error")])),
+                Arc::new(Int64Array::from(vec![7, 4, 2])),
+            ],
+        )
+        .unwrap();
+        let terms = Aggregation::Terms {
+            field: "level".to_string(),
+            size: 10,
+            by_key: false,
+            ascending: false,
+        };
+        assert_eq!(
+            aggregation_result(&terms, &[batch], 13).unwrap(),
+            json!({
+                "doc_count_error_upper_bound": 0,
+                "sum_other_doc_count": 4,
+                "buckets": [
+                    {"key": "info", "doc_count": 7},
+                    {"key": "error", "doc_count": 2},
+                ],
+            })
+        );
+
+        let schema = Arc::new(Schema::new(vec![
+            Field::new(
+                KEY_COLUMN,
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                true,
+            ),
+            Field::new(DOC_COUNT_COLUMN, DataType::Int64, false),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(TimestampMillisecondArray::from(vec![0, 60_000])),
+                Arc::new(Int64Array::from(vec![3, 1])),
+            ],
+        )
+        .unwrap();
+        let histogram = Aggregation::DateHistogram {
+            field: "ts".to_string(),
+            interval: HistogramInterval::Fixed(60_000),
+        };
+        assert_eq!(
+            aggregation_result(&histogram, &[batch], 4).unwrap(),
+            json!({
+                "buckets": [
+                    {"key_as_string": "1970-01-01T00:00:00.000Z", "key": 0, "doc_count": 3},
+                    {"key_as_string": "1970-01-01T00:01:00.000Z", "key": 60_000, "doc_count": 1},
+                ],
+            })
+        );
+    }
+}
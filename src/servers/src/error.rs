@@ -177,6 +177,13 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid COPY data: {}", reason))]
+    InvalidCopyData {
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display(
         "Too many concurrent large requests, limit: {}, request size: {}",
         ReadableSize(*limit as u64),
@@ -276,6 +283,34 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid OTLP metric, msg: {}", msg))]
+    InvalidOtlpMetric {
+        msg: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Invalid Loki query, reason: {}", reason))]
+    InvalidLokiQuery {
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Failed to plan LogQL query"))]
+    LogQl {
+        source: log_query::error::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
+    #[snafu(display("Failed to parse InfluxQL query"))]
+    Influxql {
+        source: query::influxql::error::Error,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display("Common Meta error"))]
     CommonMeta {
         #[snafu(implicit)]
@@ -653,6 +688,13 @@ pub enum Error {
         location: Location,
     },
 
+    #[snafu(display("Invalid Tempo query, reason: {}", reason))]
+    InvalidTempoQuery {
+        reason: String,
+        #[snafu(implicit)]
+        location: Location,
+    },
+
     #[snafu(display("DataFusion error"))]
     DataFusion {
         #[snafu(source)]
@@ -755,11 +797,14 @@ impl ErrorExt for Error {
 
             NotSupported { .. }
             | InvalidParameter { .. }
+            | InvalidCopyData { .. }
             | InvalidQuery { .. }
             | InfluxdbLineProtocol { .. }
             | InvalidOpentsdbJsonRequest { .. }
             | DecodePromRemoteRequest { .. }
             | DecodeOtlpRequest { .. }
+            | InvalidOtlpMetric { .. }
+            | InvalidLokiQuery { .. }
             | DecodeLokiRequest { .. }
             | UnsupportedJsonContentType { .. }
             | CompressPromRemoteRequest { .. }
@@ -787,10 +832,13 @@ impl ErrorExt for Error {
             | FailedToParseQuery { .. }
             | InvalidElasticsearchInput { .. }
             | InvalidJaegerQuery { .. }
+            | InvalidTempoQuery { .. }
             | ParseTimestamp { .. }
             | UnknownHint { .. } => StatusCode::InvalidArguments,
 
             Catalog { source, .. } => source.status_code(),
+            LogQl { source, .. } => source.status_code(),
+            Influxql { source, .. } => source.status_code(),
             RowWriter { source, .. } => source.status_code(),
             DataTypes { source, .. } => source.status_code(),
 
@@ -12,6 +12,7 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
+mod sql;
 mod stream;
 
 use std::collections::HashMap;
@@ -53,6 +54,7 @@ use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status, Streaming};
 
 use crate::error::{InvalidParameterSnafu, Result, ToJsonSnafu};
+pub use crate::grpc::flight::sql::FlightSqlCraft;
 pub use crate::grpc::flight::stream::FlightRecordBatchStream;
 use crate::grpc::greptime_handler::{
     GreptimeRequestHandler, create_query_context, get_request_type,
@@ -79,6 +81,30 @@ pub trait FlightCraft: Send + Sync + 'static {
         let _ = request;
         Err(Status::unimplemented("Not yet implemented"))
     }
+
+    async fn handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> TonicResult<Response<TonicStream<HandshakeResponse>>> {
+        let _ = request;
+        Err(Status::unimplemented("Not yet implemented"))
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> TonicResult<Response<FlightInfo>> {
+        let _ = request;
+        Err(Status::unimplemented("Not yet implemented"))
+    }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<TonicStream<arrow_flight::Result>>> {
+        let _ = request;
+        Err(Status::unimplemented("Not yet implemented"))
+    }
 }
 
 pub type FlightCraftRef = Arc<dyn FlightCraft>;
@@ -106,6 +132,27 @@ impl FlightCraft for FlightCraftRef {
     ) -> TonicResult<Response<TonicStream<PutResult>>> {
         self.as_ref().do_put(request).await
     }
+
+    async fn handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> TonicResult<Response<TonicStream<HandshakeResponse>>> {
+        self.as_ref().handshake(request).await
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> TonicResult<Response<FlightInfo>> {
+        self.as_ref().get_flight_info(request).await
+    }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<TonicStream<arrow_flight::Result>>> {
+        self.as_ref().do_action(request).await
+    }
 }
 
 #[async_trait]
@@ -114,9 +161,9 @@ impl<T: FlightCraft> FlightService for FlightCraftWrapper<T> {
 
     async fn handshake(
         &self,
-        _: Request<Streaming<HandshakeRequest>>,
+        request: Request<Streaming<HandshakeRequest>>,
     ) -> TonicResult<Response<Self::HandshakeStream>> {
-        Err(Status::unimplemented("Not yet implemented"))
+        self.0.handshake(request).await
     }
 
     type ListFlightsStream = TonicStream<FlightInfo>;
@@ -130,9 +177,9 @@ impl<T: FlightCraft> FlightService for FlightCraftWrapper<T> {
 
     async fn get_flight_info(
         &self,
-        _: Request<FlightDescriptor>,
+        request: Request<FlightDescriptor>,
     ) -> TonicResult<Response<FlightInfo>> {
-        Err(Status::unimplemented("Not yet implemented"))
+        self.0.get_flight_info(request).await
     }
 
     async fn poll_flight_info(
@@ -175,8 +222,11 @@ impl<T: FlightCraft> FlightService for FlightCraftWrapper<T> {
 
     type DoActionStream = TonicStream<arrow_flight::Result>;
 
-    async fn do_action(&self, _: Request<Action>) -> TonicResult<Response<Self::DoActionStream>> {
-        Err(Status::unimplemented("Not yet implemented"))
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<Self::DoActionStream>> {
+        self.0.do_action(request).await
     }
 
     type ListActionsStream = TonicStream<ActionType>;
@@ -0,0 +1,636 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! [Arrow Flight SQL] on top of the Flight service.
+//!
+//! Flight SQL shares the gRPC service with GreptimeDB's own Flight protocol.
+//! Its commands are protobuf `Any` messages in the `arrow.flight.protocol.sql`
+//! package, so requests carrying them are served here and all other requests
+//! are passed to the wrapped [FlightCraft].
+//!
+//! Statement tickets carry the SQL text itself, so any frontend can serve them.
+//! Prepared statements are not supported, as `DoPut` is served by GreptimeDB's
+//! own Flight protocol, so there is no way to bind parameters, and statements
+//! with placeholders are rejected. Statements that don't return rows, like
+//! `INSERT`, run as queries returning the number of affected rows.
+//!
+//! The handshake authenticates the `Basic` credentials and returns an opaque
+//! `Bearer` session token for the following requests. Session tokens expire
+//! and are only known by the frontend that issued them, so clients handshake
+//! again when connecting to another frontend. Other `Bearer` tokens are
+//! authenticated by the user provider, like those of the HTTP API.
+//!
+//! [Arrow Flight SQL]: https://arrow.apache.org/docs/format/FlightSql.html
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::error::FlightError;
+use arrow_flight::sql::metadata::{SqlInfoData, SqlInfoDataBuilder};
+use arrow_flight::sql::{
+    Any, Command, CommandGetCatalogs, CommandGetDbSchemas, CommandGetSqlInfo, CommandGetTableTypes,
+    CommandGetTables, CommandStatementQuery, ProstMessageExt, SqlInfo, TicketStatementQuery,
+};
+use arrow_flight::{
+    Action, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo, HandshakeRequest,
+    HandshakeResponse, PutResult, Ticket,
+};
+use async_trait::async_trait;
+use auth::{UserInfoRef, UserProviderRef};
+use catalog::CatalogManagerRef;
+use common_error::ext::ErrorExt;
+use common_query::{Output, OutputData};
+use dashmap::DashMap;
+use datafusion_expr::LogicalPlan;
+use datatypes::arrow::array::{RecordBatch, StringArray, UInt64Array};
+use datatypes::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use futures::{StreamExt, TryStreamExt, stream};
+use prost::Message;
+use session::context::QueryContextRef;
+use snafu::{ResultExt, ensure};
+use sql::parser::{ParseOptions, ParserContext};
+use table::metadata::TableType;
+use tonic::metadata::{MetadataMap, MetadataValue};
+use tonic::{Request, Response, Status, Streaming};
+
+use crate::error::{
+    ArrowSnafu, DataFusionSnafu, FailedToParseQuerySnafu, NotSupportedSnafu, Result,
+};
+use crate::grpc::flight::{FlightCraft, FlightCraftRef, TonicStream};
+use crate::grpc::{TonicResult, context_auth};
+use crate::metrics::METRIC_AUTH_FAILURE;
+use crate::query_handler::sql::ServerSqlQueryHandlerRef;
+
+/// Prefix of the type URL of all Flight SQL messages.
+const FLIGHT_SQL_TYPE_URL_PREFIX: &str = "type.googleapis.com/arrow.flight.protocol.sql.";
+/// Column of the result of statements that don't return rows.
+const AFFECTED_ROWS_COLUMN: &str = "affected_rows";
+const TABLE_TYPES: [&str; 3] = ["TABLE", "VIEW", "LOCAL TEMPORARY"];
+const AUTHORIZATION: &str = "authorization";
+/// How long a session token returned by the handshake is valid.
+const SESSION_TOKEN_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// Session tokens returned by the handshake and the users they authenticate.
+#[derive(Default)]
+struct SessionTokens {
+    tokens: DashMap<String, (UserInfoRef, Instant)>,
+}
+
+impl SessionTokens {
+    /// Issues a new token for the user, removing the expired ones.
+    fn issue(&self, user_info: UserInfoRef) -> String {
+        let now = Instant::now();
+        self.tokens.retain(|_, (_, expires_at)| *expires_at > now);
+        let token = hex::encode(rand::random::<[u8; 32]>());
+        self.tokens
+            .insert(token.clone(), (user_info, now + SESSION_TOKEN_TTL));
+        token
+    }
+
+    /// Returns the user of the token, or `None` if the token is unknown or expired.
+    fn user_info(&self, token: &str) -> Option<UserInfoRef> {
+        let (user_info, expires_at) = self.tokens.get(token).map(|entry| entry.clone())?;
+        if expires_at <= Instant::now() {
+            self.tokens.remove(token);
+            return None;
+        }
+        Some(user_info)
+    }
+}
+
+/// A [FlightCraft] serving Flight SQL requests and passing the others to
+/// `inner`.
+pub struct FlightSqlCraft {
+    inner: FlightCraftRef,
+    query_handler: ServerSqlQueryHandlerRef,
+    catalog_manager: CatalogManagerRef,
+    user_provider: Option<UserProviderRef>,
+    session_tokens: SessionTokens,
+}
+
+impl FlightSqlCraft {
+    pub fn new(
+        inner: FlightCraftRef,
+        query_handler: ServerSqlQueryHandlerRef,
+        catalog_manager: CatalogManagerRef,
+        user_provider: Option<UserProviderRef>,
+    ) -> Self {
+        Self {
+            inner,
+            query_handler,
+            catalog_manager,
+            user_provider,
+            session_tokens: SessionTokens::default(),
+        }
+    }
+
+    /// Creates the query context of a request and authenticates its user.
+    ///
+    /// Clients send either the `Basic` credentials or a `Bearer` token, like
+    /// the session token returned by [FlightCraft::handshake].
+    async fn query_context(&self, metadata: &MetadataMap) -> TonicResult<QueryContextRef> {
+        let query_ctx = context_auth::create_query_context_from_grpc_metadata(metadata)?;
+        let (Some(user_provider), Some(token)) = (&self.user_provider, bearer_token(metadata))
+        else {
+            context_auth::check_auth(self.user_provider.clone(), metadata, query_ctx.clone())
+                .await?;
+            return Ok(query_ctx);
+        };
+
+        let catalog = query_ctx.current_catalog();
+        let schema = query_ctx.current_schema();
+        let result = match self.session_tokens.user_info(token) {
+            Some(user_info) => user_provider
+                .authorize(catalog, &schema, &user_info)
+                .await
+                .map(|_| user_info),
+            None => {
+                user_provider
+                    .auth_bearer_token(token, catalog, &schema)
+                    .await
+            }
+        };
+        let user_info = result
+            .inspect_err(|e| {
+                METRIC_AUTH_FAILURE
+                    .with_label_values(&[e.status_code().as_ref()])
+                    .inc();
+            })
+            .map_err(|_| Status::unauthenticated("auth failed"))?;
+        query_ctx.set_current_user(user_info);
+        Ok(query_ctx)
+    }
+
+    async fn flight_info(
+        &self,
+        descriptor: FlightDescriptor,
+        command: Command,
+        query_ctx: QueryContextRef,
+    ) -> TonicResult<FlightInfo> {
+        let (schema, ticket) = match command {
+            Command::CommandStatementQuery(CommandStatementQuery { query, .. }) => {
+                let schema = self.statement_schema(&query, query_ctx).await?;
+                let ticket = TicketStatementQuery {
+                    statement_handle: query.into(),
+                };
+                (schema, ticket.as_any())
+            }
+            Command::CommandGetCatalogs(command) => {
+                let schema = command.clone().into_builder().schema();
+                (schema, command.as_any())
+            }
+            Command::CommandGetDbSchemas(command) => {
+                let schema = command.clone().into_builder().schema();
+                (schema, command.as_any())
+            }
+            Command::CommandGetTables(command) => {
+                let schema = command.clone().into_builder().schema();
+                (schema, command.as_any())
+            }
+            Command::CommandGetTableTypes(command) => (table_types_schema(), command.as_any()),
+            Command::CommandGetSqlInfo(command) => {
+                let schema = Arc::new(sql_info_data()?.schema().clone());
+                (schema, command.as_any())
+            }
+            command => {
+                return Err(Status::unimplemented(format!(
+                    "Flight SQL command {} is not supported",
+                    command.type_url()
+                )));
+            }
+        };
+
+        let endpoint = FlightEndpoint::new().with_ticket(Ticket::new(ticket.encode_to_vec()));
+        let info = FlightInfo::new()
+            .try_with_schema(&schema)
+            .context(ArrowSnafu)?
+            .with_endpoint(endpoint)
+            .with_descriptor(descriptor);
+        Ok(info)
+    }
+
+    async fn data(
+        &self,
+        command: Command,
+        query_ctx: QueryContextRef,
+    ) -> TonicResult<TonicStream<FlightData>> {
+        let batch = match command {
+            Command::TicketStatementQuery(TicketStatementQuery { statement_handle }) => {
+                let query = handle_to_sql(&statement_handle)?;
+                let output = self.execute(&query, query_ctx).await?;
+                return Ok(output_to_flight_data(output));
+            }
+            Command::CommandGetCatalogs(command) => {
+                let mut builder = command.into_builder();
+                for catalog in self
+                    .catalog_manager
+                    .catalog_names()
+                    .await
+                    .map_err(to_status)?
+                {
+                    builder.append(catalog);
+                }
+                builder.build()
+            }
+            Command::CommandGetDbSchemas(command) => {
+                let catalogs = self.catalog_names(command.catalog.as_deref()).await?;
+                let mut builder = command.into_builder();
+                for catalog in catalogs {
+                    for schema in self
+                        .catalog_manager
+                        .schema_names(&catalog, Some(&query_ctx))
+                        .await
+                        .map_err(to_status)?
+                    {
+                        builder.append(&catalog, schema);
+                    }
+                }
+                builder.build()
+            }
+            Command::CommandGetTables(command) => {
+                let catalogs = self.catalog_names(command.catalog.as_deref()).await?;
+                let mut builder = command.into_builder();
+                for catalog in catalogs {
+                    for schema in self
+                        .catalog_manager
+                        .schema_names(&catalog, Some(&query_ctx))
+                        .await
+                        .map_err(to_status)?
+                    {
+                        let mut tables =
+                            self.catalog_manager
+                                .tables(&catalog, &schema, Some(&query_ctx));
+                        while let Some(table) = tables.try_next().await.map_err(to_status)? {
+                            let info = table.table_info();
+                            builder
+                                .append(
+                                    &catalog,
+                                    &schema,
+                                    &info.name,
+                                    table_type_name(info.table_type),
+                                    table.schema().arrow_schema(),
+                                )
+                                .context(ArrowSnafu)?;
+                        }
+                    }
+                }
+                builder.build()
+            }
+            Command::CommandGetTableTypes(CommandGetTableTypes {}) => RecordBatch::try_new(
+                table_types_schema(),
+                vec![Arc::new(StringArray::from(TABLE_TYPES.to_vec()))],
+            ),
+            Command::CommandGetSqlInfo(CommandGetSqlInfo { info }) => {
+                sql_info_data()?.record_batch(info)
+            }
+            command => {
+                return Err(Status::unimplemented(format!(
+                    "Flight SQL command {} is not supported",
+                    command.type_url()
+                )));
+            }
+        }
+        .context(ArrowSnafu)?;
+
+        Ok(encode_batches(
+            batch.schema(),
+            stream::once(async move { Ok(batch) }),
+        ))
+    }
+
+    /// Returns the catalog named in a request, or all catalogs.
+    async fn catalog_names(&self, catalog: Option<&str>) -> TonicResult<Vec<String>> {
+        match catalog {
+            Some(catalog) => Ok(vec![catalog.to_string()]),
+            None => self
+                .catalog_manager
+                .catalog_names()
+                .await
+                .map_err(to_status),
+        }
+    }
+
+    /// Returns the schema of the rows returned by a statement, without running
+    /// it. Statements that don't return rows have an empty schema.
+    async fn statement_schema(&self, query: &str, query_ctx: QueryContextRef) -> Result<SchemaRef> {
+        let mut statements = ParserContext::create_with_dialect(
+            query,
+            query_ctx.sql_dialect(),
+            ParseOptions::default(),
+        )
+        .context(FailedToParseQuerySnafu)?;
+        if statements.len() != 1 {
+            return Ok(Arc::new(Schema::empty()));
+        }
+
+        let Some(result) = self
+            .query_handler
+            .do_describe(statements.remove(0), query_ctx)
+            .await?
+        else {
+            return Ok(Arc::new(Schema::empty()));
+        };
+        ensure_no_parameters(&result.logical_plan)?;
+        Ok(Arc::new(result.logical_plan.schema().as_arrow().clone()))
+    }
+
+    /// Runs a statement, returning the output of its last statement if it
+    /// consists of several.
+    async fn execute(&self, query: &str, query_ctx: QueryContextRef) -> TonicResult<Output> {
+        let outputs = self.query_handler.do_query(query, query_ctx).await;
+        outputs
+            .into_iter()
+            .collect::<Result<Vec<_>>>()?
+            .pop()
+            .ok_or_else(|| Status::invalid_argument("empty statement"))
+    }
+}
+
+#[async_trait]
+impl FlightCraft for FlightSqlCraft {
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> TonicResult<Response<TonicStream<FlightData>>> {
+        let Some(command) = flight_sql_command(&request.get_ref().ticket) else {
+            return self.inner.do_get(request).await;
+        };
+        let query_ctx = self.query_context(request.metadata()).await?;
+        Ok(Response::new(self.data(command, query_ctx).await?))
+    }
+
+    async fn do_put(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> TonicResult<Response<TonicStream<PutResult>>> {
+        self.inner.do_put(request).await
+    }
+
+    async fn handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> TonicResult<Response<TonicStream<HandshakeResponse>>> {
+        // Checks the credentials and returns a session token for the following
+        // requests, so clients don't need to send the credentials again.
+        let query_ctx = self.query_context(request.metadata()).await?;
+
+        let output: TonicStream<HandshakeResponse> =
+            Box::pin(stream::once(async { Ok(HandshakeResponse::default()) }));
+        let mut response = Response::new(output);
+        if self.user_provider.is_some() {
+            let token = self.session_tokens.issue(query_ctx.current_user());
+            let token = MetadataValue::try_from(format!("Bearer {token}"))
+                .map_err(|_| Status::internal("invalid session token"))?;
+            response.metadata_mut().insert(AUTHORIZATION, token);
+        }
+        Ok(response)
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> TonicResult<Response<FlightInfo>> {
+        let Some(command) = flight_sql_command(&request.get_ref().cmd) else {
+            return self.inner.get_flight_info(request).await;
+        };
+        let query_ctx = self.query_context(request.metadata()).await?;
+        let info = self
+            .flight_info(request.into_inner(), command, query_ctx)
+            .await?;
+        Ok(Response::new(info))
+    }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> TonicResult<Response<TonicStream<arrow_flight::Result>>> {
+        self.inner.do_action(request).await
+    }
+}
+
+/// Decodes a Flight SQL command, returning `None` for other messages.
+fn flight_sql_command(bytes: &[u8]) -> Option<Command> {
+    let any = Any::decode(bytes).ok()?;
+    if !any.type_url.starts_with(FLIGHT_SQL_TYPE_URL_PREFIX) {
+        return None;
+    }
+    Command::try_from(any).ok()
+}
+
+/// Rejects plans with placeholders, as there is no way to bind their parameters.
+fn ensure_no_parameters(plan: &LogicalPlan) -> Result<()> {
+    let parameters = plan.get_parameter_types().context(DataFusionSnafu)?;
+    ensure!(
+        parameters.is_empty(),
+        NotSupportedSnafu {
+            feat: "parameters of Flight SQL statements",
+        }
+    );
+    Ok(())
+}
+
+/// Returns the token of the `Bearer` authorization header.
+fn bearer_token(metadata: &MetadataMap) -> Option<&str> {
+    let value = metadata.get(AUTHORIZATION)?.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+    let token = token.trim_start();
+    (!token.is_empty()).then_some(token)
+}
+
+fn handle_to_sql(handle: &[u8]) -> TonicResult<String> {
+    String::from_utf8(handle.to_vec())
+        .map_err(|_| Status::invalid_argument("invalid statement handle"))
+}
+
+fn to_status(error: catalog::error::Error) -> Status {
+    crate::error::Error::from(error).into()
+}
+
+fn table_type_name(table_type: TableType) -> &'static str {
+    match table_type {
+        TableType::Base => TABLE_TYPES[0],
+        TableType::View => TABLE_TYPES[1],
+        TableType::Temporary => TABLE_TYPES[2],
+    }
+}
+
+fn table_types_schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![Field::new(
+        "table_type",
+        DataType::Utf8,
+        false,
+    )]))
+}
+
+fn sql_info_data() -> Result<SqlInfoData> {
+    let mut builder = SqlInfoDataBuilder::new();
+    builder.append(SqlInfo::FlightSqlServerName, common_version::product_name());
+    builder.append(SqlInfo::FlightSqlServerVersion, common_version::version());
+    builder.append(SqlInfo::FlightSqlServerArrowVersion, "58");
+    builder.append(SqlInfo::FlightSqlServerReadOnly, false);
+    builder.append(SqlInfo::FlightSqlServerSql, true);
+    builder.append(SqlInfo::FlightSqlServerSubstrait, false);
+    builder.append(SqlInfo::FlightSqlServerTransaction, 0_i32);
+    builder.append(SqlInfo::FlightSqlServerCancel, false);
+    builder.append(SqlInfo::SqlIdentifierQuoteChar, "\"");
+    builder.build().context(ArrowSnafu)
+}
+
+/// Encodes the output of a statement as Flight data. Statements that don't
+/// return rows return the number of affected rows instead.
+fn output_to_flight_data(output: Output) -> TonicStream<FlightData> {
+    let stream = match output.data {
+        OutputData::Stream(stream) => stream,
+        OutputData::RecordBatches(batches) => batches.as_stream(),
+        OutputData::AffectedRows(rows) => {
+            let schema = Arc::new(Schema::new(vec![Field::new(
+                AFFECTED_ROWS_COLUMN,
+                DataType::UInt64,
+                false,
+            )]));
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(UInt64Array::from(vec![rows as u64]))],
+            )
+            .map_err(FlightError::from);
+            return encode_batches(schema, stream::once(async move { batch }));
+        }
+    };
+
+    let schema = stream.schema().arrow_schema().clone();
+    let batches = stream.map(|batch| {
+        batch
+            .map(|batch| batch.into_df_record_batch())
+            .map_err(|e| FlightError::ExternalError(Box::new(e)))
+    });
+    encode_batches(schema, batches)
+}
+
+fn encode_batches(
+    schema: SchemaRef,
+    batches: impl futures::Stream<Item = std::result::Result<RecordBatch, FlightError>> + Send + 'static,
+) -> TonicStream<FlightData> {
+    Box::pin(
+        FlightDataEncoderBuilder::new()
+            .with_schema(schema)
+            .build(batches)
+            .map_err(Status::from),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use auth::userinfo_by_name;
+    use bytes::Bytes;
+    use datafusion_expr::logical_plan::table_scan;
+    use datafusion_expr::{col, lit, placeholder};
+
+    use super::*;
+
+    #[test]
+    fn test_flight_sql_command() {
+        let query = CommandStatementQuery {
+            query: "SELECT 1".to_string(),
+            transaction_id: None,
+        };
+        assert!(matches!(
+            flight_sql_command(&query.as_any().encode_to_vec()),
+            Some(Command::CommandStatementQuery(command)) if command.query == "SELECT 1"
+        ));
+
+        let ticket = TicketStatementQuery {
+            statement_handle: Bytes::from_static(b"SELECT 1"),
+        };
+        assert!(matches!(
+            flight_sql_command(&ticket.as_any().encode_to_vec()),
+            Some(Command::TicketStatementQuery(_))
+        ));
+
+        // GreptimeDB's own tickets are passed to the inner service.
+        let request = api::v1::GreptimeRequest {
+            header: Some(api::v1::RequestHeader {
+                catalog: "greptime".to_string(),
+                schema: "public".to_string(),
+                ..Default::default()
+            }),
+            request: Some(api::v1::greptime_request::Request::Query(
+                api::v1::QueryRequest {
+                    query: Some(api::v1::query_request::Query::Sql("SELECT 1".to_string())),
+                },
+            )),
+        };
+        assert!(flight_sql_command(&request.encode_to_vec()).is_none());
+        assert!(flight_sql_command(b"").is_none());
+    }
+
+    #[test]
+    fn test_ensure_no_parameters() {
+        let schema = Schema::new(vec![Field::new("id", DataType::Int32, false)]);
+        let plan = table_scan(Some("t"), &schema, None)
+            .unwrap()
+            .filter(col("id").eq(lit(1)))
+            .unwrap()
+            .build()
+            .unwrap();
+        ensure_no_parameters(&plan).unwrap();
+
+        let plan = table_scan(Some("t"), &schema, None)
+            .unwrap()
+            .filter(col("id").eq(placeholder("$1")))
+            .unwrap()
+            .build()
+            .unwrap();
+        let err = ensure_no_parameters(&plan).unwrap_err();
+        assert!(matches!(err, crate::error::Error::NotSupported { .. }));
+    }
+
+    #[test]
+    fn test_session_tokens() {
+        let tokens = SessionTokens::default();
+        let token = tokens.issue(userinfo_by_name(Some("greptime".to_string())));
+        assert_eq!(64, token.len());
+        assert_eq!("greptime", tokens.user_info(&token).unwrap().username());
+        assert!(tokens.user_info("greptime:greptime").is_none());
+
+        tokens.tokens.get_mut(&token).unwrap().1 = Instant::now();
+        assert!(tokens.user_info(&token).is_none());
+        assert!(tokens.tokens.is_empty());
+    }
+
+    #[test]
+    fn test_bearer_token() {
+        let mut metadata = MetadataMap::new();
+        assert!(bearer_token(&metadata).is_none());
+        metadata.insert(
+            AUTHORIZATION,
+            "Basic Z3JlcHRpbWU6Z3JlcHRpbWU=".parse().unwrap(),
+        );
+        assert!(bearer_token(&metadata).is_none());
+        metadata.insert(AUTHORIZATION, "bearer abc".parse().unwrap());
+        assert_eq!(Some("abc"), bearer_token(&metadata));
+    }
+
+    #[test]
+    fn test_sql_info() {
+        let batch = sql_info_data()
+            .unwrap()
+            .record_batch(vec![SqlInfo::FlightSqlServerName as u32])
+            .unwrap();
+        assert_eq!(batch.num_rows(), 1);
+    }
+}
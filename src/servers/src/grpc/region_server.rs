@@ -15,7 +15,7 @@
 use std::sync::Arc;
 
 use api::v1::region::region_server::Region as RegionServer;
-use api::v1::region::{RegionRequest, RegionResponse, region_request};
+use api::v1::region::{RegionRequest, RegionRequestHeader, RegionResponse, region_request};
 use async_trait::async_trait;
 use common_error::ext::ErrorExt;
 use common_runtime::Runtime;
@@ -31,7 +31,11 @@ use crate::grpc::{TonicResult, cancellation};
 
 #[async_trait]
 pub trait RegionServerHandler: Send + Sync {
-    async fn handle(&self, request: region_request::Body) -> Result<RegionResponse>;
+    async fn handle(
+        &self,
+        header: RegionRequestHeader,
+        request: region_request::Body,
+    ) -> Result<RegionResponse>;
 }
 
 pub type RegionServerHandlerRef = Arc<dyn RegionServerHandler>;
@@ -48,14 +52,10 @@ impl RegionServerRequestHandler {
     }
 
     async fn handle(&self, request: RegionRequest) -> Result<RegionResponse> {
-        let tracing_context = TracingContext::from_w3c(
-            &request
-                .header
-                .context(InvalidQuerySnafu {
-                    reason: "Expecting non-empty region request header.",
-                })?
-                .tracing_context,
-        );
+        let header = request.header.context(InvalidQuerySnafu {
+            reason: "Expecting non-empty region request header.",
+        })?;
+        let tracing_context = TracingContext::from_w3c(&header.tracing_context);
         let query = request.body.context(InvalidQuerySnafu {
             reason: "Expecting non-empty region request body.",
         })?;
@@ -71,7 +71,7 @@ impl RegionServerRequestHandler {
         // 2. avoid the handler blocks the gRPC runtime incidentally.
         let handle = self.runtime.spawn(async move {
             handler
-                .handle(query)
+                .handle(header, query)
                 .trace(tracing_context.attach(info_span!("RegionServerRequestHandler::handle")))
                 .await
                 .map_err(|e| {
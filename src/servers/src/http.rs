@@ -57,6 +57,7 @@ use crate::error::{
     AddressBindSnafu, AlreadyStartedSnafu, Error, InternalIoSnafu, InvalidHeaderValueSnafu, Result,
 };
 use crate::http::influxdb::{influxdb_health, influxdb_ping, influxdb_write_v1, influxdb_write_v2};
+use crate::http::influxql::influxdb_query;
 use crate::http::otlp::OtlpState;
 use crate::http::prom_store::PromStoreState;
 use crate::http::prometheus::{
@@ -70,6 +71,7 @@ use crate::http::result::greptime_result_v1::GreptimedbV1Response;
 use crate::http::result::influxdb_result_v1::InfluxdbV1Response;
 use crate::http::result::json_result::JsonResponse;
 use crate::http::result::null_result::NullResponse;
+use crate::http::tempo::{TempoSearchLimits, TempoState};
 use crate::interceptor::LogIngestInterceptorRef;
 use crate::metrics::http_metrics_layer;
 use crate::metrics_handler::MetricsHandler;
@@ -77,9 +79,10 @@ use crate::pending_rows_batcher::PendingRowsBatcher;
 use crate::prometheus_handler::PrometheusHandlerRef;
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
 use crate::query_handler::{
-    DashboardHandlerRef, InfluxdbLineProtocolHandlerRef, JaegerQueryHandlerRef, LogQueryHandlerRef,
-    OpenTelemetryProtocolHandlerRef, OpentsdbProtocolHandlerRef, PipelineHandlerRef,
-    PromStoreProtocolHandlerRef,
+    DashboardHandlerRef, InfluxdbLineProtocolHandlerRef, InfluxqlQueryHandlerRef,
+    JaegerQueryHandlerRef, LogQueryHandlerRef, OpenTelemetryProtocolHandlerRef,
+    OpentsdbProtocolHandlerRef, OpentsdbQueryHandlerRef, PipelineHandlerRef,
+    PromStoreProtocolHandlerRef, TempoQueryHandlerRef,
 };
 use crate::request_memory_limiter::ServerMemoryLimiter;
 use crate::server::Server;
@@ -94,9 +97,11 @@ pub mod extractor;
 pub mod handler;
 pub mod header;
 pub mod influxdb;
+pub mod influxql;
 pub mod jaeger;
 pub mod logs;
 pub mod loki;
+pub mod loki_query;
 pub mod mem_prof;
 mod memory_limit;
 pub mod opentsdb;
@@ -106,6 +111,7 @@ pub mod prom_store;
 pub mod prometheus;
 pub mod result;
 pub mod splunk;
+pub mod tempo;
 mod timeout;
 pub mod utils;
 
@@ -653,12 +659,19 @@ impl HttpServerBuilder {
     }
 
     pub fn with_logs_handler(self, logs_handler: LogQueryHandlerRef) -> Self {
-        let logs_router = HttpServer::route_logs(logs_handler);
+        let logs_router = HttpServer::route_logs(logs_handler.clone());
+        let loki_query_router = HttpServer::route_loki_query(logs_handler.clone());
+        let elasticsearch_search_router = HttpServer::route_elasticsearch_search(logs_handler);
 
         Self {
             router: self
                 .router
-                .nest(&format!("/{HTTP_API_VERSION}"), logs_router),
+                .nest(&format!("/{HTTP_API_VERSION}"), logs_router)
+                .nest(&format!("/{HTTP_API_VERSION}/loki"), loki_query_router)
+                .nest(
+                    &format!("/{HTTP_API_VERSION}/elasticsearch"),
+                    elasticsearch_search_router,
+                ),
             ..self
         }
     }
@@ -673,6 +686,16 @@ impl HttpServerBuilder {
         }
     }
 
+    pub fn with_opentsdb_query_handler(self, handler: OpentsdbQueryHandlerRef) -> Self {
+        Self {
+            router: self.router.nest(
+                &format!("/{HTTP_API_VERSION}/opentsdb"),
+                HttpServer::route_opentsdb_query(handler),
+            ),
+            ..self
+        }
+    }
+
     pub fn with_influxdb_handler(self, handler: InfluxdbLineProtocolHandlerRef) -> Self {
         Self {
             router: self.router.nest(
@@ -683,6 +706,16 @@ impl HttpServerBuilder {
         }
     }
 
+    pub fn with_influxql_handler(self, handler: InfluxqlQueryHandlerRef) -> Self {
+        Self {
+            router: self.router.nest(
+                &format!("/{HTTP_API_VERSION}/influxdb"),
+                HttpServer::route_influxql(handler),
+            ),
+            ..self
+        }
+    }
+
     pub fn with_prom_handler(
         self,
         handler: PromStoreProtocolHandlerRef,
@@ -817,6 +850,20 @@ impl HttpServerBuilder {
         }
     }
 
+    pub fn with_tempo_handler(
+        self,
+        handler: TempoQueryHandlerRef,
+        limits: TempoSearchLimits,
+    ) -> Self {
+        Self {
+            router: self.router.nest(
+                &format!("/{HTTP_API_VERSION}/tempo"),
+                HttpServer::route_tempo(handler, limits),
+            ),
+            ..self
+        }
+    }
+
     pub fn with_dashboard_handler(self, handler: DashboardHandlerRef) -> Self {
         Self {
             router: self.router.nest(
@@ -1134,6 +1181,52 @@ impl HttpServer {
             .with_state(log_state)
     }
 
+    /// Route Loki [query API].
+    ///
+    /// [query API]: https://grafana.com/docs/loki/latest/reference/loki-http-api/#query-endpoints
+    fn route_loki_query<S>(log_handler: LogQueryHandlerRef) -> Router<S> {
+        Router::new()
+            .route(
+                "/api/v1/query_range",
+                routing::get(loki_query::loki_query_range).post(loki_query::loki_query_range),
+            )
+            .route(
+                "/api/v1/query",
+                routing::get(loki_query::loki_query).post(loki_query::loki_query),
+            )
+            .route(
+                "/api/v1/labels",
+                routing::get(loki_query::loki_labels).post(loki_query::loki_labels),
+            )
+            .route(
+                "/api/v1/label/{name}/values",
+                routing::get(loki_query::loki_label_values),
+            )
+            .route(
+                "/api/v1/series",
+                routing::get(loki_query::loki_series).post(loki_query::loki_series),
+            )
+            .with_state(log_handler)
+    }
+
+    fn route_elasticsearch_search<S>(log_handler: LogQueryHandlerRef) -> Router<S> {
+        Router::new()
+            .route(
+                "/{index}/_search",
+                routing::get(elasticsearch::handle_search_api)
+                    .post(elasticsearch::handle_search_api),
+            )
+            .route(
+                "/{index}/_count",
+                routing::get(elasticsearch::handle_count_api).post(elasticsearch::handle_count_api),
+            )
+            .layer(
+                ServiceBuilder::new()
+                    .layer(RequestDecompressionLayer::new().pass_through_unaccepted(true)),
+            )
+            .with_state(log_handler)
+    }
+
     fn route_splunk<S>(log_state: LogState) -> Router<S> {
         Router::new()
             .route(
@@ -1377,12 +1470,35 @@ impl HttpServer {
             .with_state(influxdb_handler)
     }
 
+    fn route_influxql<S>(influxql_handler: InfluxqlQueryHandlerRef) -> Router<S> {
+        Router::new()
+            .route("/query", routing::get(influxdb_query).post(influxdb_query))
+            .with_state(influxql_handler)
+    }
+
     fn route_opentsdb<S>(opentsdb_handler: OpentsdbProtocolHandlerRef) -> Router<S> {
         Router::new()
             .route("/api/put", routing::post(opentsdb::put))
             .with_state(opentsdb_handler)
     }
 
+    fn route_opentsdb_query<S>(opentsdb_query_handler: OpentsdbQueryHandlerRef) -> Router<S> {
+        Router::new()
+            .route(
+                "/api/query",
+                routing::get(opentsdb::query).post(opentsdb::query),
+            )
+            .route(
+                "/api/suggest",
+                routing::get(opentsdb::suggest).post(opentsdb::suggest),
+            )
+            .route(
+                "/api/search/lookup",
+                routing::get(opentsdb::lookup).post(opentsdb::lookup),
+            )
+            .with_state(opentsdb_query_handler)
+    }
+
     fn route_otlp<S>(
         otlp_handler: OpenTelemetryProtocolHandlerRef,
         with_metric_engine: bool,
@@ -1426,6 +1542,19 @@ impl HttpServer {
             .with_state(handler)
     }
 
+    fn route_tempo<S>(handler: TempoQueryHandlerRef, limits: TempoSearchLimits) -> Router<S> {
+        Router::new()
+            .route("/api/echo", routing::get(tempo::echo))
+            .route("/api/search", routing::get(tempo::search))
+            .route("/api/traces/{trace_id}", routing::get(tempo::get_trace))
+            .route("/api/search/tags", routing::get(tempo::search_tags))
+            .route(
+                "/api/search/tag/{tag}/values",
+                routing::get(tempo::search_tag_values),
+            )
+            .with_state(TempoState { handler, limits })
+    }
+
     #[cfg(feature = "dashboard")]
     fn route_dashboard<S>(handler: DashboardHandlerRef) -> Router<S> {
         use crate::http::dashboard::{add_dashboard, delete_dashboard, list_dashboards};
@@ -0,0 +1,377 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The InfluxDB v1 `/query` API, which executes InfluxQL statements.
+//!
+//! See <https://docs.influxdata.com/influxdb/v1/tools/api/#query-http-endpoint>.
+//!
+//! `SHOW TAG VALUES` requires a `FROM` clause. The form without `FROM`, which Grafana
+//! template variables send unless a measurement is given, is rejected as unimplemented.
+//! A statement reads from the database of its measurement (`db..m`), then the `ON`
+//! database, then the `db` parameter.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::{Extension, Form, Json};
+use chrono::SecondsFormat;
+use common_catalog::parse_catalog_and_schema_from_db_string;
+use common_error::ext::ErrorExt;
+use common_query::OutputData;
+use common_recordbatch::{RecordBatch, util};
+use common_telemetry::tracing;
+use datatypes::value::Value;
+use query::influxql::parser::parse_statements;
+use query::influxql::{SeriesLayout, SeriesName};
+use serde::{Deserialize, Serialize};
+use session::context::{Channel, QueryContext};
+use snafu::{OptionExt, ResultExt};
+
+use crate::error::{CollectRecordbatchSnafu, InfluxqlSnafu, Result, UnexpectedResultSnafu};
+use crate::http::Epoch;
+use crate::http::prometheus::try_update_catalog_schema;
+use crate::http::result::HttpOutputWriter;
+use crate::metrics::METRIC_HTTP_INFLUXDB_QUERY_ELAPSED;
+use crate::query_handler::{InfluxqlOutput, InfluxqlQueryHandlerRef};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct InfluxqlQuery {
+    pub db: Option<String>,
+    /// `;` separated InfluxQL statements.
+    pub q: Option<String>,
+    /// Returns epoch timestamps with the specified precision instead of RFC3339 strings.
+    pub epoch: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct InfluxqlResponse {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    results: Vec<StatementResult>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StatementResult {
+    statement_id: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    series: Vec<Series>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct Series {
+    name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tags: Option<BTreeMap<String, String>>,
+    columns: Vec<String>,
+    values: Vec<Vec<serde_json::Value>>,
+}
+
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "influxdb", request_type = "query"))]
+pub async fn influxdb_query(
+    State(handler): State<InfluxqlQueryHandlerRef>,
+    Query(params): Query<InfluxqlQuery>,
+    Extension(mut query_ctx): Extension<QueryContext>,
+    Form(form_params): Form<InfluxqlQuery>,
+) -> Response {
+    let db = params.db.or(form_params.db);
+    let query = params.q.or(form_params.q).unwrap_or_default();
+    let epoch = params
+        .epoch
+        .or(form_params.epoch)
+        .and_then(|epoch| Epoch::parse(&epoch));
+
+    query_ctx.set_channel(Channel::Influx);
+    if let Some(db) = &db {
+        let (catalog, schema) = parse_catalog_and_schema_from_db_string(db);
+        try_update_catalog_schema(&mut query_ctx, &catalog, &schema);
+    }
+    let query_ctx = Arc::new(query_ctx);
+    let _timer = METRIC_HTTP_INFLUXDB_QUERY_ELAPSED
+        .with_label_values(&[query_ctx.get_db_string().as_str()])
+        .start_timer();
+
+    if query.trim().is_empty() {
+        return bad_request("missing required parameter \"q\"".to_string());
+    }
+    let statements = match parse_statements(&query).context(InfluxqlSnafu) {
+        Ok(statements) => statements,
+        Err(err) => return bad_request(err.output_msg()),
+    };
+
+    let mut results = Vec::with_capacity(statements.len());
+    for (statement_id, stmt) in statements.into_iter().enumerate() {
+        let result = match handler.query(stmt, query_ctx.clone()).await {
+            Ok(output) => collect_series(output, epoch).await,
+            Err(err) => Err(err),
+        };
+        results.push(match result {
+            Ok(series) => StatementResult {
+                statement_id,
+                series,
+                error: None,
+            },
+            Err(err) => StatementResult {
+                statement_id,
+                series: vec![],
+                error: Some(err.output_msg()),
+            },
+        });
+    }
+
+    Json(InfluxqlResponse {
+        results,
+        error: None,
+    })
+    .into_response()
+}
+
+fn bad_request(error: String) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(InfluxqlResponse {
+            results: vec![],
+            error: Some(error),
+        }),
+    )
+        .into_response()
+}
+
+async fn collect_series(output: InfluxqlOutput, epoch: Option<Epoch>) -> Result<Vec<Series>> {
+    let batches = match output.output.data {
+        OutputData::AffectedRows(_) => return Ok(vec![]),
+        OutputData::RecordBatches(batches) => batches.take(),
+        OutputData::Stream(stream) => util::collect(stream)
+            .await
+            .context(CollectRecordbatchSnafu)?,
+    };
+    build_series(&output.layout, batches, epoch)
+}
+
+/// Splits the rows into series by the name and tag columns of the layout.
+/// Rows of the same series are expected to be adjacent.
+fn build_series(
+    layout: &SeriesLayout,
+    batches: Vec<RecordBatch>,
+    epoch: Option<Epoch>,
+) -> Result<Vec<Series>> {
+    let Some(first) = batches.first() else {
+        return Ok(vec![]);
+    };
+    let schema = first.schema.clone();
+    let column_names = schema
+        .column_schemas()
+        .iter()
+        .map(|column| column.name.clone())
+        .collect::<Vec<_>>();
+    let index_of = |name: &str| {
+        column_names
+            .iter()
+            .position(|column| column == name)
+            .with_context(|| UnexpectedResultSnafu {
+                reason: format!("column {name} not found in the InfluxQL result"),
+            })
+    };
+
+    let (fixed_name, name_index) = match &layout.name {
+        SeriesName::Fixed(name) => (name.clone(), None),
+        SeriesName::Column(column) => (String::new(), Some(index_of(column)?)),
+    };
+    let tag_indices = layout
+        .tag_columns
+        .iter()
+        .map(|tag| index_of(tag))
+        .collect::<Result<Vec<_>>>()?;
+    let value_indices = (0..column_names.len())
+        .filter(|i| Some(*i) != name_index && !tag_indices.contains(i))
+        .collect::<Vec<_>>();
+    let columns = value_indices
+        .iter()
+        .map(|i| column_names[*i].clone())
+        .collect::<Vec<_>>();
+
+    let mut rows = Vec::with_capacity(batches.iter().map(|batch| batch.num_rows()).sum());
+    for batch in batches {
+        let mut writer = HttpOutputWriter::new(
+            schema.num_columns(),
+            Some(Box::new(move |value: Value| {
+                convert_timestamp(value, epoch)
+            })),
+        );
+        writer.write(batch, &mut rows)?;
+    }
+
+    let mut series: Vec<Series> = vec![];
+    for row in rows {
+        let name = match name_index {
+            Some(i) => json_to_string(&row[i]),
+            None => fixed_name.clone(),
+        };
+        let tags = (!tag_indices.is_empty()).then(|| {
+            layout
+                .tag_columns
+                .iter()
+                .zip(&tag_indices)
+                .map(|(tag, i)| (tag.clone(), json_to_string(&row[*i])))
+                .collect::<BTreeMap<_, _>>()
+        });
+        let values = value_indices.iter().map(|i| row[*i].clone()).collect();
+
+        match series.last_mut() {
+            Some(last) if last.name == name && last.tags == tags => last.values.push(values),
+            _ => series.push(Series {
+                name,
+                tags,
+                columns: columns.clone(),
+                values: vec![values],
+            }),
+        }
+    }
+    Ok(series)
+}
+
+/// Converts timestamps to RFC3339 strings, or to integers of the epoch precision.
+fn convert_timestamp(value: Value, epoch: Option<Epoch>) -> Value {
+    let Value::Timestamp(ts) = value else {
+        return value;
+    };
+    let converted = match epoch {
+        Some(epoch) => epoch
+            .convert_timestamp(ts)
+            .map(|ts| Value::Int64(ts.value())),
+        None => ts.to_chrono_datetime().map(|datetime| {
+            Value::from(
+                datetime
+                    .and_utc()
+                    .to_rfc3339_opts(SecondsFormat::AutoSi, true),
+            )
+        }),
+    };
+    converted.unwrap_or(Value::Timestamp(ts))
+}
+
+fn json_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Null => String::new(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use common_recordbatch::RecordBatch;
+    use common_time::Timestamp;
+    use datatypes::prelude::ConcreteDataType;
+    use datatypes::schema::{ColumnSchema, Schema};
+    use datatypes::vectors::{Float64Vector, StringVector, TimestampNanosecondVector, VectorRef};
+    use serde_json::json;
+
+    use super::*;
+
+    fn test_batch() -> RecordBatch {
+        let schema = Arc::new(Schema::new(vec![
+            ColumnSchema::new("host", ConcreteDataType::string_datatype(), true),
+            ColumnSchema::new(
+                "time",
+                ConcreteDataType::timestamp_nanosecond_datatype(),
+                false,
+            ),
+            ColumnSchema::new("mean", ConcreteDataType::float64_datatype(), true),
+        ]));
+        let columns: Vec<VectorRef> = vec![
+            Arc::new(StringVector::from(vec!["a", "a", "b"])),
+            Arc::new(TimestampNanosecondVector::from_vec(vec![
+                0,
+                60_000_000_000,
+                1_500_000,
+            ])),
+            Arc::new(Float64Vector::from(vec![Some(1.0), None, Some(3.5)])),
+        ];
+        RecordBatch::new(schema, columns).unwrap()
+    }
+
+    #[test]
+    fn test_build_series() {
+        let layout = SeriesLayout {
+            name: SeriesName::Fixed("cpu".to_string()),
+            tag_columns: vec!["host".to_string()],
+        };
+        let series = build_series(&layout, vec![test_batch()], None).unwrap();
+        assert_eq!(
+            json!([
+                {
+                    "name": "cpu",
+                    "tags": {"host": "a"},
+                    "columns": ["time", "mean"],
+                    "values": [["1970-01-01T00:00:00Z", 1.0], ["1970-01-01T00:01:00Z", null]]
+                },
+                {
+                    "name": "cpu",
+                    "tags": {"host": "b"},
+                    "columns": ["time", "mean"],
+                    "values": [["1970-01-01T00:00:00.0015Z", 3.5]]
+                }
+            ]),
+            serde_json::to_value(series).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_build_series_with_epoch() {
+        let layout = SeriesLayout {
+            name: SeriesName::Column("host".to_string()),
+            tag_columns: vec![],
+        };
+        let series = build_series(&layout, vec![test_batch()], Some(Epoch::Millisecond)).unwrap();
+        assert_eq!(
+            json!([
+                {
+                    "name": "a",
+                    "columns": ["time", "mean"],
+                    "values": [[0, 1.0], [60000, null]]
+                },
+                {
+                    "name": "b",
+                    "columns": ["time", "mean"],
+                    "values": [[1, 3.5]]
+                }
+            ]),
+            serde_json::to_value(series).unwrap()
+        );
+        assert!(build_series(&layout, vec![], None).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_convert_timestamp() {
+        let ts = Timestamp::new_nanosecond(1_700_000_000_123_456_789);
+        assert_eq!(
+            Value::from("2023-11-14T22:13:20.123456789Z"),
+            convert_timestamp(Value::Timestamp(ts), None)
+        );
+        assert_eq!(
+            Value::Int64(1_700_000_000),
+            convert_timestamp(Value::Timestamp(ts), Some(Epoch::Second))
+        );
+        assert_eq!(Value::Int64(1), convert_timestamp(Value::Int64(1), None));
+    }
+}
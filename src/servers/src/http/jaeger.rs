@@ -709,7 +709,7 @@ pub async fn handle_get_operations_by_service(
     }
 }
 
-async fn covert_to_records(output: Output) -> Result<Option<HttpRecordsOutput>> {
+pub(crate) async fn covert_to_records(output: Output) -> Result<Option<HttpRecordsOutput>> {
     match output.data {
         OutputData::Stream(stream) => {
             let records = HttpRecordsOutput::try_new(
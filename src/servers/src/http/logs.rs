@@ -15,13 +15,18 @@
 use std::sync::Arc;
 use std::time::Instant;
 
+use arrow::array::RecordBatch;
 use axum::extract::State;
 use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json};
+use common_query::OutputData;
+use common_recordbatch::util;
 use common_telemetry::tracing;
 use log_query::{Limit, LogQuery};
-use session::context::{Channel, QueryContext};
+use session::context::{Channel, QueryContext, QueryContextRef};
+use snafu::ResultExt;
 
+use crate::error::{CollectRecordbatchSnafu, Result};
 use crate::http::result::greptime_result_v1::GreptimedbV1Response;
 use crate::query_handler::LogQueryHandlerRef;
 
@@ -56,6 +61,26 @@ pub async fn logs(
         .into_response()
 }
 
+/// Executes the log query and collects its output as arrow record batches.
+pub(crate) async fn query_record_batches(
+    handler: &LogQueryHandlerRef,
+    query: LogQuery,
+    query_ctx: &QueryContextRef,
+) -> Result<Vec<RecordBatch>> {
+    let output = handler.query(query, query_ctx.clone()).await?;
+    let batches = match output.data {
+        OutputData::Stream(stream) => util::collect(stream)
+            .await
+            .context(CollectRecordbatchSnafu)?,
+        OutputData::RecordBatches(batches) => batches.take(),
+        OutputData::AffectedRows(_) => vec![],
+    };
+    Ok(batches
+        .into_iter()
+        .map(|batch| batch.into_df_record_batch())
+        .collect())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
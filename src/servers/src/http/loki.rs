@@ -58,11 +58,11 @@ use crate::metrics::{METRIC_LOKI_LOGS_INGESTION_COUNTER, METRIC_LOKI_LOGS_INGEST
 use crate::pipeline::run_pipeline;
 use crate::query_handler::PipelineHandlerRef;
 
-const LOKI_TABLE_NAME: &str = "loki_logs";
-const LOKI_LINE_COLUMN: &str = "line";
+pub(crate) const LOKI_TABLE_NAME: &str = "loki_logs";
+pub(crate) const LOKI_LINE_COLUMN: &str = "line";
 const LOKI_STRUCTURED_METADATA_COLUMN: &str = "structured_metadata";
 
-const LOKI_LINE_COLUMN_NAME: &str = "loki_line";
+pub(crate) const LOKI_LINE_COLUMN_NAME: &str = "loki_line";
 
 const LOKI_PIPELINE_METADATA_PREFIX: &str = "loki_metadata_";
 const LOKI_PIPELINE_LABEL_PREFIX: &str = "loki_label_";
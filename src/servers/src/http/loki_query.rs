@@ -0,0 +1,722 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Loki [query API], planned through the log query model.
+//!
+//! Stream labels are the tag columns of the queried table, which defaults to
+//! the table written by the Loki push API.
+//!
+//! [query API]: https://grafana.com/docs/loki/latest/reference/loki-http-api/#query-endpoints
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Arc;
+
+use arrow::array::{Array, ArrayRef, AsArray, RecordBatch};
+use arrow::compute::cast;
+use arrow::datatypes::{DataType, Float64Type, Int64Type, TimeUnit};
+use axum::extract::{Path, Query, State};
+use axum::{Extension, Json};
+use chrono::DateTime;
+use common_telemetry::tracing;
+use common_time::util::current_time_millis;
+use log_query::logql::{self, LogQlExpr, LogQlTarget, LogSelector, METRIC_VALUE_COLUMN};
+use serde::{Deserialize, Serialize};
+use session::context::{Channel, QueryContext, QueryContextRef};
+use snafu::{OptionExt, ResultExt, ensure};
+use table::table_name::TableName;
+
+use crate::error::{
+    ArrowSnafu, InvalidLokiQuerySnafu, LogQlSnafu, Result, TableNotFoundSnafu,
+    UnexpectedResultSnafu,
+};
+use crate::http::extractor::LogTableName;
+use crate::http::logs::query_record_batches;
+use crate::http::loki::{LOKI_LINE_COLUMN, LOKI_LINE_COLUMN_NAME, LOKI_TABLE_NAME};
+use crate::http::prometheus::Matches;
+use crate::metrics::METRIC_HTTP_LOGS_ELAPSED;
+use crate::query_handler::LogQueryHandlerRef;
+
+const DEFAULT_LIMIT: usize = 100;
+/// Time range of queries without `start`.
+const DEFAULT_LOOKBACK_MILLIS: i64 = 60 * 60 * 1000;
+/// Time range of label and series queries without `start`.
+const DEFAULT_LABELS_LOOKBACK_MILLIS: i64 = 6 * 60 * 60 * 1000;
+/// The default step yields about this many points per series, as in Loki.
+const DEFAULT_POINTS: i64 = 250;
+const MAX_POINTS: i64 = 11_000;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LokiQueryParams {
+    query: Option<String>,
+    start: Option<String>,
+    end: Option<String>,
+    /// Evaluation time of instant queries.
+    time: Option<String>,
+    step: Option<String>,
+    limit: Option<usize>,
+    /// `backward` (default) or `forward`.
+    direction: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LokiSeriesParams {
+    start: Option<String>,
+    end: Option<String>,
+    #[serde(flatten)]
+    matches: Matches,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LokiJsonResponse<T> {
+    status: &'static str,
+    data: T,
+}
+
+impl<T> LokiJsonResponse<T> {
+    fn success(data: T) -> Json<Self> {
+        Json(Self {
+            status: "success",
+            data,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(tag = "resultType", content = "result", rename_all = "lowercase")]
+pub enum LokiQueryResult {
+    Streams(Vec<LokiStream>),
+    Matrix(Vec<LokiSeries>),
+    Vector(Vec<LokiSample>),
+}
+
+/// Log lines of a stream, as `[<unix nanos>, <line>]` pairs.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct LokiStream {
+    stream: BTreeMap<String, String>,
+    values: Vec<(String, String)>,
+}
+
+/// Samples of a series, as `[<unix seconds>, <value>]` pairs.
+#[derive(Debug, PartialEq, Serialize)]
+pub struct LokiSeries {
+    metric: BTreeMap<String, String>,
+    values: Vec<(f64, String)>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+pub struct LokiSample {
+    metric: BTreeMap<String, String>,
+    value: (f64, String),
+}
+
+/// Handler for `/loki/api/v1/query_range`.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "loki", request_type = "query_range"))]
+pub async fn loki_query_range(
+    State(handler): State<LogQueryHandlerRef>,
+    Extension(query_ctx): Extension<QueryContext>,
+    LogTableName(table_name): LogTableName,
+    Query(params): Query<LokiQueryParams>,
+) -> Result<Json<LokiJsonResponse<LokiQueryResult>>> {
+    let query_ctx = loki_query_context(query_ctx);
+    let _timer = METRIC_HTTP_LOGS_ELAPSED
+        .with_label_values(&[query_ctx.get_db_string().as_str()])
+        .start_timer();
+
+    let expr = parse_query(&params)?;
+    let (start, end) = time_range(
+        params.start.as_deref(),
+        params.end.as_deref(),
+        DEFAULT_LOOKBACK_MILLIS,
+    )?;
+    let target = resolve_target(&handler, &query_ctx, table_name).await?;
+
+    let result = match expr {
+        LogQlExpr::Log(selector) => {
+            query_streams(
+                &handler, &query_ctx, &target, &selector, start, end, &params,
+            )
+            .await?
+        }
+        LogQlExpr::Metric(metric) => {
+            let step = parse_step(params.step.as_deref(), start, end)?;
+            let query = metric
+                .to_log_query(&target, start, end, step)
+                .context(LogQlSnafu)?;
+            let batches = query_record_batches(&handler, query, &query_ctx).await?;
+            let series = series_from_batches(&batches, &target.timestamp_column)?;
+            LokiQueryResult::Matrix(
+                series
+                    .into_iter()
+                    .map(|(metric, samples)| LokiSeries {
+                        metric,
+                        values: samples.into_iter().map(format_sample).collect(),
+                    })
+                    .collect(),
+            )
+        }
+    };
+
+    Ok(LokiJsonResponse::success(result))
+}
+
+/// Handler for `/loki/api/v1/query`.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "loki", request_type = "query"))]
+pub async fn loki_query(
+    State(handler): State<LogQueryHandlerRef>,
+    Extension(query_ctx): Extension<QueryContext>,
+    LogTableName(table_name): LogTableName,
+    Query(params): Query<LokiQueryParams>,
+) -> Result<Json<LokiJsonResponse<LokiQueryResult>>> {
+    let query_ctx = loki_query_context(query_ctx);
+    let _timer = METRIC_HTTP_LOGS_ELAPSED
+        .with_label_values(&[query_ctx.get_db_string().as_str()])
+        .start_timer();
+
+    // Like Loki, only metric queries can be evaluated at an instant.
+    let LogQlExpr::Metric(metric) = parse_query(&params)? else {
+        return InvalidLokiQuerySnafu {
+            reason: "log queries are not supported as an instant query type, please change your query to a range query type",
+        }
+        .fail();
+    };
+    let time = params
+        .time
+        .as_deref()
+        .map(parse_loki_time)
+        .transpose()?
+        .unwrap_or_else(current_time_millis);
+    let target = resolve_target(&handler, &query_ctx, table_name).await?;
+
+    let query = metric
+        .to_log_query(&target, time, time, 0)
+        .context(LogQlSnafu)?;
+    let batches = query_record_batches(&handler, query, &query_ctx).await?;
+    let series = series_from_batches(&batches, &target.timestamp_column)?;
+    let result = LokiQueryResult::Vector(
+        series
+            .into_iter()
+            .filter_map(|(metric, samples)| {
+                let sample = samples.into_iter().next()?;
+                Some(LokiSample {
+                    metric,
+                    value: format_sample(sample),
+                })
+            })
+            .collect(),
+    );
+
+    Ok(LokiJsonResponse::success(result))
+}
+
+/// Handler for `/loki/api/v1/labels`.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "loki", request_type = "labels"))]
+pub async fn loki_labels(
+    State(handler): State<LogQueryHandlerRef>,
+    Extension(query_ctx): Extension<QueryContext>,
+    LogTableName(table_name): LogTableName,
+) -> Result<Json<LokiJsonResponse<Vec<String>>>> {
+    let query_ctx = loki_query_context(query_ctx);
+    let mut labels = resolve_target(&handler, &query_ctx, table_name)
+        .await?
+        .stream_labels;
+    labels.sort_unstable();
+
+    Ok(LokiJsonResponse::success(labels))
+}
+
+/// Handler for `/loki/api/v1/label/{name}/values`.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "loki", request_type = "label_values"))]
+pub async fn loki_label_values(
+    State(handler): State<LogQueryHandlerRef>,
+    Path(name): Path<String>,
+    Extension(query_ctx): Extension<QueryContext>,
+    LogTableName(table_name): LogTableName,
+    Query(params): Query<LokiQueryParams>,
+) -> Result<Json<LokiJsonResponse<Vec<String>>>> {
+    let query_ctx = loki_query_context(query_ctx);
+    let (start, end) = time_range(
+        params.start.as_deref(),
+        params.end.as_deref(),
+        DEFAULT_LABELS_LOOKBACK_MILLIS,
+    )?;
+    let target = resolve_target(&handler, &query_ctx, table_name).await?;
+    if !target.stream_labels.contains(&name) {
+        return Ok(LokiJsonResponse::success(vec![]));
+    }
+
+    let selector = match &params.query {
+        Some(_) => parse_query(&params)?.selector().clone(),
+        None => LogSelector {
+            matchers: vec![],
+            stages: vec![],
+        },
+    };
+    let series = query_series(&handler, &query_ctx, &target, &selector, start, end).await?;
+    let values = series
+        .into_iter()
+        .filter_map(|mut labels| labels.remove(&name))
+        .collect::<BTreeSet<_>>();
+
+    Ok(LokiJsonResponse::success(values.into_iter().collect()))
+}
+
+/// Handler for `/loki/api/v1/series`.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "loki", request_type = "series"))]
+pub async fn loki_series(
+    State(handler): State<LogQueryHandlerRef>,
+    Extension(query_ctx): Extension<QueryContext>,
+    LogTableName(table_name): LogTableName,
+    Query(params): Query<LokiSeriesParams>,
+) -> Result<Json<LokiJsonResponse<Vec<BTreeMap<String, String>>>>> {
+    let query_ctx = loki_query_context(query_ctx);
+    ensure!(
+        !params.matches.0.is_empty(),
+        InvalidLokiQuerySnafu {
+            reason: "match[] parameter is required",
+        }
+    );
+    let (start, end) = time_range(
+        params.start.as_deref(),
+        params.end.as_deref(),
+        DEFAULT_LABELS_LOOKBACK_MILLIS,
+    )?;
+    let target = resolve_target(&handler, &query_ctx, table_name).await?;
+
+    let mut series = BTreeSet::new();
+    for matcher in &params.matches.0 {
+        let expr = logql::parse(matcher).context(LogQlSnafu)?;
+        series.extend(
+            query_series(&handler, &query_ctx, &target, expr.selector(), start, end).await?,
+        );
+    }
+
+    Ok(LokiJsonResponse::success(series.into_iter().collect()))
+}
+
+fn loki_query_context(mut query_ctx: QueryContext) -> QueryContextRef {
+    query_ctx.set_channel(Channel::Loki);
+    Arc::new(query_ctx)
+}
+
+fn parse_query(params: &LokiQueryParams) -> Result<LogQlExpr> {
+    let query = params.query.as_deref().context(InvalidLokiQuerySnafu {
+        reason: "query parameter is required",
+    })?;
+    logql::parse(query).context(LogQlSnafu)
+}
+
+/// Parses a Loki timestamp into milliseconds.
+///
+/// Like Loki, this accepts a Unix epoch in nanoseconds (or in seconds if it has
+/// at most 10 digits or a fraction), or an RFC3339 timestamp.
+fn parse_loki_time(value: &str) -> Result<i64> {
+    if let Ok(epoch) = value.parse::<i64>() {
+        return Ok(if value.len() <= 10 {
+            epoch * 1000
+        } else {
+            epoch / 1_000_000
+        });
+    }
+    if let Ok(seconds) = value.parse::<f64>() {
+        return Ok((seconds * 1000.0) as i64);
+    }
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.timestamp_millis())
+        .map_err(|_| {
+            InvalidLokiQuerySnafu {
+                reason: format!("invalid timestamp `{value}`"),
+            }
+            .build()
+        })
+}
+
+fn time_range(start: Option<&str>, end: Option<&str>, lookback: i64) -> Result<(i64, i64)> {
+    let end = end
+        .map(parse_loki_time)
+        .transpose()?
+        .unwrap_or_else(current_time_millis);
+    let start = start
+        .map(parse_loki_time)
+        .transpose()?
+        .unwrap_or(end - lookback);
+    ensure!(
+        start < end,
+        InvalidLokiQuerySnafu {
+            reason: "end timestamp must be after start timestamp",
+        }
+    );
+    Ok((start, end))
+}
+
+/// Parses the step of a range query into milliseconds, either a duration like
+/// `1m` or a number of seconds.
+fn parse_step(step: Option<&str>, start: i64, end: i64) -> Result<i64> {
+    let step = match step {
+        Some(step) => match step.parse::<f64>() {
+            Ok(seconds) => (seconds * 1000.0) as i64,
+            Err(_) => logql::parse_duration(step).context(LogQlSnafu)?,
+        },
+        None => ((end - start) / 1000 / DEFAULT_POINTS).max(1) * 1000,
+    };
+    ensure!(
+        step > 0,
+        InvalidLokiQuerySnafu {
+            reason: "step must be positive",
+        }
+    );
+    ensure!(
+        (end - start) / step <= MAX_POINTS,
+        InvalidLokiQuerySnafu {
+            reason: format!(
+                "exceeded maximum resolution of {MAX_POINTS} points per time series, try increasing the step"
+            ),
+        }
+    );
+    Ok(step)
+}
+
+async fn resolve_target(
+    handler: &LogQueryHandlerRef,
+    query_ctx: &QueryContextRef,
+    table_name: Option<String>,
+) -> Result<LogQlTarget> {
+    let table_name = table_name.unwrap_or_else(|| LOKI_TABLE_NAME.to_string());
+    let catalog = query_ctx.current_catalog();
+    let schema = query_ctx.current_schema();
+    let table = handler
+        .catalog_manager(query_ctx)?
+        .table(catalog, &schema, &table_name, Some(query_ctx))
+        .await?
+        .with_context(|| TableNotFoundSnafu {
+            catalog,
+            schema: &schema,
+            table: &table_name,
+        })?;
+
+    let table_schema = table.schema();
+    let timestamp_column = table_schema
+        .timestamp_column()
+        .with_context(|| InvalidLokiQuerySnafu {
+            reason: format!("table `{table_name}` has no time index"),
+        })?
+        .name
+        .clone();
+    let line_column = [LOKI_LINE_COLUMN, LOKI_LINE_COLUMN_NAME]
+        .into_iter()
+        .find(|column| table_schema.column_schema_by_name(column).is_some())
+        .with_context(|| InvalidLokiQuerySnafu {
+            reason: format!("table `{table_name}` has no log line column"),
+        })?
+        .to_string();
+    let stream_labels = table
+        .table_info()
+        .meta
+        .primary_key_indices
+        .iter()
+        .map(|index| table_schema.column_schemas()[*index].name.clone())
+        .collect();
+
+    Ok(LogQlTarget {
+        table: TableName::new(catalog, schema, table_name),
+        timestamp_column,
+        line_column,
+        stream_labels,
+    })
+}
+
+async fn query_streams(
+    handler: &LogQueryHandlerRef,
+    query_ctx: &QueryContextRef,
+    target: &LogQlTarget,
+    selector: &LogSelector,
+    start: i64,
+    end: i64,
+    params: &LokiQueryParams,
+) -> Result<LokiQueryResult> {
+    let descending = match params.direction.as_deref() {
+        None | Some("backward") => true,
+        Some("forward") => false,
+        Some(direction) => {
+            return InvalidLokiQuerySnafu {
+                reason: format!("invalid direction `{direction}`"),
+            }
+            .fail();
+        }
+    };
+    let query = selector
+        .to_log_query(
+            target,
+            start,
+            end,
+            params.limit.unwrap_or(DEFAULT_LIMIT),
+            descending,
+        )
+        .context(LogQlSnafu)?;
+    let batches = query_record_batches(handler, query, query_ctx).await?;
+    Ok(LokiQueryResult::Streams(streams_from_batches(
+        &batches, target,
+    )?))
+}
+
+async fn query_series(
+    handler: &LogQueryHandlerRef,
+    query_ctx: &QueryContextRef,
+    target: &LogQlTarget,
+    selector: &LogSelector,
+    start: i64,
+    end: i64,
+) -> Result<Vec<BTreeMap<String, String>>> {
+    let query = selector
+        .to_series_query(target, start, end)
+        .context(LogQlSnafu)?;
+    let batches = query_record_batches(handler, query, query_ctx).await?;
+    let mut series = vec![];
+    for batch in &batches {
+        series.extend(row_labels(batch, &[])?);
+    }
+    Ok(series)
+}
+
+fn column_by_name<'a>(batch: &'a RecordBatch, name: &str) -> Result<&'a ArrayRef> {
+    batch
+        .column_by_name(name)
+        .with_context(|| UnexpectedResultSnafu {
+            reason: format!("column `{name}` is missing from the query result"),
+        })
+}
+
+/// Returns the labels of each row, taken from the non-empty values of all
+/// columns but `excluded`.
+fn row_labels(batch: &RecordBatch, excluded: &[&str]) -> Result<Vec<BTreeMap<String, String>>> {
+    let mut labels = vec![BTreeMap::new(); batch.num_rows()];
+    for (field, column) in batch.schema().fields().iter().zip(batch.columns()) {
+        if excluded.contains(&field.name().as_str()) {
+            continue;
+        }
+        let column = cast(column, &DataType::Utf8).context(ArrowSnafu)?;
+        for (row, value) in column.as_string::<i32>().iter().enumerate() {
+            if let Some(value) = value
+                && !value.is_empty()
+            {
+                labels[row].insert(field.name().clone(), value.to_string());
+            }
+        }
+    }
+    Ok(labels)
+}
+
+fn timestamps(column: &ArrayRef, unit: TimeUnit) -> Result<Vec<Option<i64>>> {
+    let column = cast(column, &DataType::Timestamp(unit, None)).context(ArrowSnafu)?;
+    let column = cast(&column, &DataType::Int64).context(ArrowSnafu)?;
+    Ok(column.as_primitive::<Int64Type>().iter().collect())
+}
+
+/// Groups log lines by stream, keeping their order in the query result.
+fn streams_from_batches(batches: &[RecordBatch], target: &LogQlTarget) -> Result<Vec<LokiStream>> {
+    let mut streams: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for batch in batches {
+        let labels = row_labels(
+            batch,
+            &[
+                target.timestamp_column.as_str(),
+                target.line_column.as_str(),
+            ],
+        )?;
+        let timestamps = timestamps(
+            column_by_name(batch, &target.timestamp_column)?,
+            TimeUnit::Nanosecond,
+        )?;
+        let lines = cast(column_by_name(batch, &target.line_column)?, &DataType::Utf8)
+            .context(ArrowSnafu)?;
+        let lines = lines.as_string::<i32>();
+
+        for ((labels, timestamp), line) in labels.into_iter().zip(timestamps).zip(lines.iter()) {
+            let Some(timestamp) = timestamp else {
+                continue;
+            };
+            streams
+                .entry(labels)
+                .or_default()
+                .push((timestamp.to_string(), line.unwrap_or_default().to_string()));
+        }
+    }
+
+    Ok(streams
+        .into_iter()
+        .map(|(stream, values)| LokiStream { stream, values })
+        .collect())
+}
+
+/// Groups the samples of a metric query by series, ordered by time. Sample
+/// times are in milliseconds.
+fn series_from_batches(
+    batches: &[RecordBatch],
+    timestamp_column: &str,
+) -> Result<BTreeMap<BTreeMap<String, String>, Vec<(i64, f64)>>> {
+    let mut series: BTreeMap<_, Vec<_>> = BTreeMap::new();
+    for batch in batches {
+        let labels = row_labels(batch, &[timestamp_column, METRIC_VALUE_COLUMN])?;
+        let timestamps = timestamps(
+            column_by_name(batch, timestamp_column)?,
+            TimeUnit::Millisecond,
+        )?;
+        let values = cast(
+            column_by_name(batch, METRIC_VALUE_COLUMN)?,
+            &DataType::Float64,
+        )
+        .context(ArrowSnafu)?;
+        let values = values.as_primitive::<Float64Type>();
+
+        for ((labels, timestamp), value) in labels.into_iter().zip(timestamps).zip(values.iter()) {
+            if let (Some(timestamp), Some(value)) = (timestamp, value) {
+                series.entry(labels).or_default().push((timestamp, value));
+            }
+        }
+    }
+
+    for samples in series.values_mut() {
+        samples.sort_unstable_by_key(|(timestamp, _)| *timestamp);
+    }
+    Ok(series)
+}
+
+fn format_sample((timestamp, value): (i64, f64)) -> (f64, String) {
+    (timestamp as f64 / 1000.0, value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::array::{Float64Array, StringArray, TimestampMillisecondArray};
+    use arrow::datatypes::{Field, Schema};
+
+    use super::*;
+
+    fn target() -> LogQlTarget {
+        LogQlTarget {
+            table: TableName::new("greptime", "public", LOKI_TABLE_NAME),
+            timestamp_column: "greptime_timestamp".to_string(),
+            line_column: LOKI_LINE_COLUMN.to_string(),
+            stream_labels: vec!["app".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_parse_loki_time() {
+        assert_eq!(parse_loki_time("1700000000").unwrap(), 1_700_000_000_000);
+        assert_eq!(
+            parse_loki_time("1700000000123456789").unwrap(),
+            1_700_000_000_123
+        );
+        assert_eq!(parse_loki_time("1700000000.5").unwrap(), 1_700_000_000_500);
+        assert_eq!(
+            parse_loki_time("2023-11-14T22:13:20.250Z").unwrap(),
+            1_700_000_000_250
+        );
+        assert!(parse_loki_time("yesterday").is_err());
+    }
+
+    #[test]
+    fn test_parse_step() {
+        assert_eq!(parse_step(Some("30s"), 0, 3_600_000).unwrap(), 30_000);
+        assert_eq!(parse_step(Some("15"), 0, 3_600_000).unwrap(), 15_000);
+        // 1h / 250 points, rounded down to whole seconds.
+        assert_eq!(parse_step(None, 0, 3_600_000).unwrap(), 14_000);
+        assert_eq!(parse_step(None, 0, 60_000).unwrap(), 1_000);
+        assert!(parse_step(Some("0"), 0, 3_600_000).is_err());
+        assert!(parse_step(Some("1ms"), 0, 3_600_000).is_err());
+    }
+
+    #[test]
+    fn test_streams_from_batches() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("app", DataType::Utf8, true),
+            Field::new(
+                "greptime_timestamp",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+            Field::new("line", DataType::Utf8, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec![Some("api"), None, Some("api")])),
+                Arc::new(TimestampMillisecondArray::from(vec![3, 2, 1])),
+                Arc::new(StringArray::from(vec!["c", "b", "a"])),
+            ],
+        )
+        .unwrap();
+
+        let streams = streams_from_batches(&[batch], &target()).unwrap();
+        assert_eq!(
+            serde_json::to_value(LokiQueryResult::Streams(streams)).unwrap(),
+            serde_json::json!({
+                "resultType": "streams",
+                "result": [
+                    {"stream": {}, "values": [["2000000", "b"]]},
+                    {"stream": {"app": "api"}, "values": [["3000000", "c"], ["1000000", "a"]]},
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn test_series_from_batches() {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("app", DataType::Utf8, true),
+            Field::new(
+                "greptime_timestamp",
+                DataType::Timestamp(TimeUnit::Millisecond, None),
+                false,
+            ),
+            Field::new(METRIC_VALUE_COLUMN, DataType::Float64, true),
+        ]));
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(StringArray::from(vec!["api", "api", "web"])),
+                Arc::new(TimestampMillisecondArray::from(vec![
+                    120_000, 60_000, 60_000,
+                ])),
+                Arc::new(Float64Array::from(vec![Some(2.0), Some(0.5), None])),
+            ],
+        )
+        .unwrap();
+
+        let series = series_from_batches(&[batch], "greptime_timestamp").unwrap();
+        let matrix = series
+            .into_iter()
+            .map(|(metric, samples)| LokiSeries {
+                metric,
+                values: samples.into_iter().map(format_sample).collect(),
+            })
+            .collect();
+        assert_eq!(
+            serde_json::to_value(LokiQueryResult::Matrix(matrix)).unwrap(),
+            serde_json::json!({
+                "resultType": "matrix",
+                "result": [
+                    {"metric": {"app": "api"}, "values": [[60.0, "0.5"], [120.0, "2"]]},
+                ],
+            })
+        );
+    }
+}
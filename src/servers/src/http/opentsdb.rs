@@ -12,21 +12,32 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
+use std::time::Instant;
 
 use axum::body::Bytes;
 use axum::extract::{Query, State};
 use axum::http::StatusCode as HttpStatusCode;
+use axum::response::{IntoResponse, Response};
 use axum::{Extension, Json};
 use common_error::ext::ErrorExt;
+use common_query::{Output, OutputData};
+use common_recordbatch::{RecordBatch, util};
+use common_telemetry::tracing;
+use common_time::util::current_time_millis;
 use serde::{Deserialize, Serialize};
-use session::context::{Channel, QueryContext};
-use snafu::ResultExt;
+use session::context::{Channel, QueryContext, QueryContextRef};
+use snafu::{OptionExt, ResultExt, ensure};
 
-use crate::error::{self, Result};
+use crate::error::{self, CollectRecordbatchSnafu, Result, status_code_to_http_status};
+use crate::metrics::METRIC_HTTP_OPENTSDB_QUERY_ELAPSED;
 use crate::opentsdb::codec::DataPoint;
-use crate::query_handler::OpentsdbProtocolHandlerRef;
+use crate::opentsdb::query::{
+    Aggregator, Downsample, MetricQuery, QueryResult, RateOptions, SubQuery, SuggestKind,
+    TagFilter, TagFilterKind, collect_series, collect_tag_sets, parse_time,
+};
+use crate::query_handler::{OpentsdbProtocolHandlerRef, OpentsdbQueryHandlerRef};
 
 #[derive(Serialize, Deserialize)]
 #[serde(untagged)]
@@ -169,6 +180,436 @@ impl OpentsdbDebuggingResponse {
     }
 }
 
+const DEFAULT_SUGGEST_MAX: usize = 25;
+const DEFAULT_LOOKUP_LIMIT: usize = 25;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryRequest {
+    /// An absolute or relative time, either a number or a string.
+    start: serde_json::Value,
+    #[serde(default)]
+    end: Option<serde_json::Value>,
+    queries: Vec<SubQueryRequest>,
+    #[serde(default)]
+    ms_resolution: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SubQueryRequest {
+    aggregator: String,
+    metric: String,
+    #[serde(default)]
+    rate: bool,
+    #[serde(default)]
+    rate_options: Option<RateOptions>,
+    #[serde(default)]
+    downsample: Option<String>,
+    /// Tags that group the series, the same as filters with `groupBy` enabled.
+    #[serde(default)]
+    tags: HashMap<String, String>,
+    #[serde(default)]
+    filters: Vec<FilterRequest>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FilterRequest {
+    #[serde(rename = "type")]
+    filter_type: String,
+    tagk: String,
+    filter: String,
+    #[serde(default)]
+    group_by: bool,
+}
+
+impl QueryRequest {
+    fn into_sub_queries(self, now_millis: i64) -> Result<Vec<SubQuery>> {
+        let (start_millis, end_millis) =
+            parse_time_range(Some(&self.start), self.end.as_ref(), now_millis)?;
+
+        self.queries
+            .into_iter()
+            .map(|query| {
+                let mut filters = query
+                    .tags
+                    .into_iter()
+                    .map(|(tagk, filter)| TagFilter::parse_inline(tagk, &filter, true))
+                    .collect::<Result<Vec<_>>>()?;
+                for filter in query.filters {
+                    filters.push(TagFilter::try_new(
+                        &filter.filter_type,
+                        filter.tagk,
+                        &filter.filter,
+                        filter.group_by,
+                    )?);
+                }
+
+                Ok(SubQuery {
+                    aggregator: Aggregator::try_from_str(&query.aggregator)?,
+                    rate: query.rate.then(|| query.rate_options.unwrap_or_default()),
+                    query: MetricQuery {
+                        metric: query.metric,
+                        start_millis,
+                        end_millis,
+                        filters,
+                        downsample: query
+                            .downsample
+                            .as_deref()
+                            .map(Downsample::try_from_str)
+                            .transpose()?,
+                    },
+                })
+            })
+            .collect()
+    }
+}
+
+fn parse_time_range(
+    start: Option<&serde_json::Value>,
+    end: Option<&serde_json::Value>,
+    now_millis: i64,
+) -> Result<(i64, i64)> {
+    let parse = |time: &serde_json::Value| match time {
+        serde_json::Value::String(time) => parse_time(time, now_millis),
+        time => parse_time(&time.to_string(), now_millis),
+    };
+    let start = start.context(error::InvalidQuerySnafu {
+        reason: "missing start time",
+    })?;
+    let start_millis = parse(start)?;
+    let end_millis = end.map(parse).transpose()?.unwrap_or(now_millis);
+    ensure!(
+        start_millis <= end_millis,
+        error::InvalidQuerySnafu {
+            reason: "start time must not be after end time",
+        }
+    );
+    Ok((start_millis, end_millis))
+}
+
+/// Parses the sub queries from the `start`, `end`, `m` and `ms` parameters of a GET request.
+fn parse_query_params(
+    params: &[(String, String)],
+    now_millis: i64,
+) -> Result<(Vec<SubQuery>, bool)> {
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| serde_json::Value::String(value.clone()))
+    };
+    let (start_millis, end_millis) =
+        parse_time_range(param("start").as_ref(), param("end").as_ref(), now_millis)?;
+    let ms_resolution = params
+        .iter()
+        .any(|(key, value)| key == "ms" && value != "false");
+
+    let sub_queries = params
+        .iter()
+        .filter(|(key, _)| key == "m")
+        .map(|(_, m)| SubQuery::parse_m_param(m, start_millis, end_millis))
+        .collect::<Result<Vec<_>>>()?;
+    ensure!(
+        !sub_queries.is_empty(),
+        error::InvalidQuerySnafu {
+            reason: "missing sub queries",
+        }
+    );
+    Ok((sub_queries, ms_resolution))
+}
+
+#[derive(Debug, Serialize)]
+struct OpentsdbErrorResponse {
+    error: OpentsdbError,
+}
+
+#[derive(Debug, Serialize)]
+struct OpentsdbError {
+    code: u16,
+    message: String,
+}
+
+fn error_response(err: impl ErrorExt) -> Response {
+    let status = status_code_to_http_status(&err.status_code());
+    let body = OpentsdbErrorResponse {
+        error: OpentsdbError {
+            code: status.as_u16(),
+            message: err.output_msg(),
+        },
+    };
+    (status, Json(body)).into_response()
+}
+
+async fn collect_batches(output: Output) -> Result<Vec<RecordBatch>> {
+    match output.data {
+        OutputData::AffectedRows(_) => Ok(vec![]),
+        OutputData::RecordBatches(batches) => Ok(batches.take()),
+        OutputData::Stream(stream) => util::collect(stream).await.context(CollectRecordbatchSnafu),
+    }
+}
+
+fn opentsdb_query_context(mut ctx: QueryContext) -> QueryContextRef {
+    ctx.set_channel(Channel::Opentsdb);
+    Arc::new(ctx)
+}
+
+// Please refer to the OpenTSDB documents of ["api/query"](http://opentsdb.net/docs/build/html/api_http/query/index.html)
+// for more details.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "opentsdb", request_type = "query"))]
+pub async fn query(
+    State(handler): State<OpentsdbQueryHandlerRef>,
+    Query(params): Query<Vec<(String, String)>>,
+    Extension(ctx): Extension<QueryContext>,
+    body: Bytes,
+) -> Response {
+    let ctx = opentsdb_query_context(ctx);
+    let _timer = METRIC_HTTP_OPENTSDB_QUERY_ELAPSED
+        .with_label_values(&[ctx.get_db_string().as_str(), "query"])
+        .start_timer();
+
+    match do_query(handler, params, body, ctx).await {
+        Ok(results) => Json(results).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+async fn do_query(
+    handler: OpentsdbQueryHandlerRef,
+    params: Vec<(String, String)>,
+    body: Bytes,
+    ctx: QueryContextRef,
+) -> Result<Vec<QueryResult>> {
+    let now_millis = current_time_millis();
+    let (sub_queries, ms_resolution) = if body.is_empty() {
+        parse_query_params(&params, now_millis)?
+    } else {
+        let request = serde_json::from_slice::<QueryRequest>(&body)
+            .context(error::InvalidOpentsdbJsonRequestSnafu)?;
+        let ms_resolution = request.ms_resolution;
+        (request.into_sub_queries(now_millis)?, ms_resolution)
+    };
+
+    let mut results = vec![];
+    for sub_query in sub_queries {
+        let output = handler.query(&sub_query.query, ctx.clone()).await?;
+        let series = collect_series(&collect_batches(output).await?)?;
+        results.extend(sub_query.build_results(series, ms_resolution));
+    }
+    Ok(results)
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct SuggestRequest {
+    /// One of `metrics`, `tagk` or `tagv`.
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    /// The prefix to match.
+    q: Option<String>,
+    max: Option<usize>,
+}
+
+// Please refer to the OpenTSDB documents of ["api/suggest"](http://opentsdb.net/docs/build/html/api_http/suggest.html)
+// for more details.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "opentsdb", request_type = "suggest"))]
+pub async fn suggest(
+    State(handler): State<OpentsdbQueryHandlerRef>,
+    Query(params): Query<SuggestRequest>,
+    Extension(ctx): Extension<QueryContext>,
+    body: Bytes,
+) -> Response {
+    let ctx = opentsdb_query_context(ctx);
+    let _timer = METRIC_HTTP_OPENTSDB_QUERY_ELAPSED
+        .with_label_values(&[ctx.get_db_string().as_str(), "suggest"])
+        .start_timer();
+
+    match do_suggest(handler, params, body, ctx).await {
+        Ok(suggestions) => Json(suggestions).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+async fn do_suggest(
+    handler: OpentsdbQueryHandlerRef,
+    params: SuggestRequest,
+    body: Bytes,
+    ctx: QueryContextRef,
+) -> Result<Vec<String>> {
+    let request = if body.is_empty() {
+        params
+    } else {
+        serde_json::from_slice::<SuggestRequest>(&body)
+            .context(error::InvalidOpentsdbJsonRequestSnafu)?
+    };
+    let kind = request.kind.context(error::InvalidQuerySnafu {
+        reason: "missing suggest type",
+    })?;
+    handler
+        .suggest(
+            SuggestKind::try_from_str(&kind)?,
+            request.q.as_deref().unwrap_or_default(),
+            request.max.unwrap_or(DEFAULT_SUGGEST_MAX),
+            ctx,
+        )
+        .await
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LookupTag {
+    key: String,
+    /// The tag value, or `*` for any value.
+    value: String,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LookupRequest {
+    /// The metric and tags like `sys.cpu.user{host=*}`, only in GET requests.
+    m: Option<String>,
+    metric: Option<String>,
+    #[serde(default)]
+    tags: Vec<LookupTag>,
+    limit: Option<usize>,
+}
+
+impl LookupRequest {
+    fn into_metric_and_tags(self) -> Result<(String, Vec<LookupTag>)> {
+        let Some(m) = self.m else {
+            let metric = self.metric.context(error::InvalidQuerySnafu {
+                reason: "missing metric",
+            })?;
+            return Ok((metric, self.tags));
+        };
+
+        let invalid = || error::InvalidQuerySnafu {
+            reason: format!("invalid m parameter: {m}"),
+        };
+        let (metric, tags) = match m.split_once('{') {
+            Some((metric, tags)) => (metric, tags.strip_suffix('}').with_context(invalid)?),
+            None => (m.as_str(), ""),
+        };
+        let tags = tags
+            .split(',')
+            .filter(|tag| !tag.is_empty())
+            .map(|tag| {
+                tag.split_once('=')
+                    .map(|(key, value)| LookupTag {
+                        key: key.to_string(),
+                        value: value.to_string(),
+                    })
+                    .with_context(invalid)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok((metric.to_string(), tags))
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LookupResponse {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    metric: String,
+    tags: Vec<LookupTag>,
+    limit: usize,
+    /// Milliseconds spent on the lookup.
+    time: u64,
+    results: Vec<LookupResult>,
+    start_index: usize,
+    total_results: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LookupResult {
+    /// Always empty as series have no UIDs here.
+    tsuid: String,
+    metric: String,
+    tags: BTreeMap<String, String>,
+}
+
+// Please refer to the OpenTSDB documents of ["api/search/lookup"](http://opentsdb.net/docs/build/html/api_http/search/lookup.html)
+// for more details.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "opentsdb", request_type = "lookup"))]
+pub async fn lookup(
+    State(handler): State<OpentsdbQueryHandlerRef>,
+    Query(params): Query<LookupRequest>,
+    Extension(ctx): Extension<QueryContext>,
+    body: Bytes,
+) -> Response {
+    let ctx = opentsdb_query_context(ctx);
+    let _timer = METRIC_HTTP_OPENTSDB_QUERY_ELAPSED
+        .with_label_values(&[ctx.get_db_string().as_str(), "lookup"])
+        .start_timer();
+
+    match do_lookup(handler, params, body, ctx).await {
+        Ok(response) => Json(response).into_response(),
+        Err(err) => error_response(err),
+    }
+}
+
+async fn do_lookup(
+    handler: OpentsdbQueryHandlerRef,
+    params: LookupRequest,
+    body: Bytes,
+    ctx: QueryContextRef,
+) -> Result<LookupResponse> {
+    let start = Instant::now();
+    let request = if body.is_empty() {
+        params
+    } else {
+        serde_json::from_slice::<LookupRequest>(&body)
+            .context(error::InvalidOpentsdbJsonRequestSnafu)?
+    };
+    let limit = request.limit.unwrap_or(DEFAULT_LOOKUP_LIMIT);
+    let (metric, tags) = request.into_metric_and_tags()?;
+
+    let filters = tags
+        .iter()
+        .map(|tag| {
+            ensure!(
+                tag.key != "*",
+                error::InvalidQuerySnafu {
+                    reason: "wildcard tag keys are not supported",
+                }
+            );
+            let kind = if tag.value == "*" {
+                TagFilterKind::Wildcard(tag.value.clone())
+            } else {
+                TagFilterKind::LiteralOr(vec![tag.value.clone()])
+            };
+            Ok(TagFilter {
+                tagk: tag.key.clone(),
+                kind,
+                group_by: false,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let output = handler.lookup(&metric, &filters, limit, ctx).await?;
+    let results = collect_tag_sets(&collect_batches(output).await?)
+        .into_iter()
+        .map(|tags| LookupResult {
+            tsuid: String::new(),
+            metric: metric.clone(),
+            tags,
+        })
+        .collect::<Vec<_>>();
+
+    Ok(LookupResponse {
+        kind: "LOOKUP",
+        metric,
+        tags,
+        limit,
+        time: start.elapsed().as_millis() as u64,
+        total_results: results.len(),
+        results,
+        start_index: 0,
+    })
+}
+
 #[cfg(test)]
 mod test {
 
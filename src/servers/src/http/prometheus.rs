@@ -632,7 +632,7 @@ async fn do_range_query(
 }
 
 #[derive(Debug, Default, Serialize)]
-struct Matches(Vec<String>);
+pub(crate) struct Matches(pub(crate) Vec<String>);
 
 #[derive(Debug, Default, Serialize, Deserialize)]
 pub struct LabelsQuery {
@@ -0,0 +1,1080 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Grafana Tempo compatible trace query HTTP API.
+//!
+//! See <https://grafana.com/docs/tempo/latest/api_docs/>.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Arc;
+
+use axum::Extension;
+use axum::extract::{Path, Query, State};
+use axum::http::StatusCode as HttpStatusCode;
+use axum::response::{IntoResponse, Response};
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use common_error::ext::ErrorExt;
+use common_error::status_code::StatusCode;
+use common_query::Output;
+use common_telemetry::{debug, error, tracing};
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+use session::context::{QueryContext, QueryContextRef};
+use snafu::OptionExt;
+
+use crate::error::{Error, InvalidTempoQuerySnafu, Result, status_code_to_http_status};
+use crate::http::HttpRecordsOutput;
+use crate::http::extractor::TraceTableName;
+use crate::http::jaeger::covert_to_records;
+use crate::metrics::METRIC_TEMPO_QUERY_ELAPSED;
+use crate::otlp::trace::{
+    DURATION_NANO_COLUMN, KEY_SERVICE_NAME, PARENT_SPAN_ID_COLUMN, SCOPE_NAME_COLUMN,
+    SCOPE_VERSION_COLUMN, SERVICE_NAME_COLUMN, SPAN_ID_COLUMN, SPAN_KIND_COLUMN, SPAN_NAME_COLUMN,
+    SPAN_STATUS_CODE, SPAN_STATUS_MESSAGE_COLUMN, TIMESTAMP_COLUMN, TRACE_ID_COLUMN,
+    TRACE_STATE_COLUMN,
+};
+use crate::query_handler::TempoQueryHandlerRef;
+use crate::tempo::traceql::{
+    self, AttributeScope, CompareOp, Field, FieldExpr, Spanset, Static, TraceQuery,
+};
+use crate::tempo::{
+    RESOURCE_ATTRIBUTE_COLUMN_PREFIX, SPAN_ATTRIBUTE_COLUMN_PREFIX, SpanRow, evaluate_descendants,
+};
+
+pub const TEMPO_QUERY_TABLE_NAME_KEY: &str = "tempo_query_table_name";
+
+const DEFAULT_SEARCH_LIMIT: usize = 20;
+const DEFAULT_SPANS_PER_SPAN_SET: usize = 3;
+const DEFAULT_TAG_VALUES_LIMIT: usize = 1000;
+/// Structural queries filter out candidate traces after fetching them, so fetch more of them.
+const STRUCTURAL_CANDIDATE_FACTOR: usize = 5;
+/// Max rounds of fetching candidate traces for a structural query.
+const MAX_STRUCTURAL_SEARCH_ROUNDS: usize = 10;
+const ROOT_SPAN_NOT_YET_RECEIVED: &str = "<root span not yet received>";
+const TRACE_NOT_FOUND: &str = "trace not found";
+
+const INTRINSIC_TAGS: [&str; 5] = ["duration", "kind", "name", "status", "statusMessage"];
+const STATUS_VALUES: [&str; 3] = ["error", "ok", "unset"];
+const KIND_VALUES: [&str; 6] = [
+    "client",
+    "consumer",
+    "internal",
+    "producer",
+    "server",
+    "unspecified",
+];
+
+/// Upper bounds of the `limit` and `spss` params of `/api/search`.
+#[derive(Debug, Clone, Copy)]
+pub struct TempoSearchLimits {
+    pub max_search_limit: usize,
+    pub max_spans_per_span_set: usize,
+}
+
+impl Default for TempoSearchLimits {
+    fn default() -> Self {
+        Self {
+            max_search_limit: 1000,
+            max_spans_per_span_set: 100,
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct TempoState {
+    pub handler: TempoQueryHandlerRef,
+    pub limits: TempoSearchLimits,
+}
+
+/// The query params of `/api/search`. `start` and `end` are unix epoch seconds.
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchParams {
+    pub q: Option<String>,
+    /// logfmt encoded attributes like `service.name=api http.method=GET`.
+    pub tags: Option<String>,
+    pub min_duration: Option<String>,
+    pub max_duration: Option<String>,
+    pub limit: Option<usize>,
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    /// Spans per span set.
+    pub spss: Option<usize>,
+}
+
+/// The query params of `/api/traces/{trace_id}` and `/api/search/tag/{tag}/values`.
+#[derive(Debug, Default, Deserialize)]
+pub struct TimeRangeParams {
+    pub start: Option<i64>,
+    pub end: Option<i64>,
+    pub limit: Option<usize>,
+}
+
+/// The query params of `/api/search/tags`.
+#[derive(Debug, Default, Deserialize)]
+pub struct TagsParams {
+    /// One of `span`, `resource`, `intrinsic` or `all`.
+    pub scope: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct SearchResponse {
+    pub traces: Vec<TraceSearchMetadata>,
+    pub metrics: SearchMetrics,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchMetrics {
+    pub inspected_traces: usize,
+    pub inspected_spans: usize,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TraceSearchMetadata {
+    #[serde(rename = "traceID")]
+    pub trace_id: String,
+    pub root_service_name: String,
+    pub root_trace_name: String,
+    pub start_time_unix_nano: String,
+    pub duration_ms: u64,
+    pub span_set: SpanSet,
+    pub span_sets: Vec<SpanSet>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SpanSet {
+    pub spans: Vec<SearchSpan>,
+    pub matched: usize,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchSpan {
+    #[serde(rename = "spanID")]
+    pub span_id: String,
+    pub name: String,
+    pub start_time_unix_nano: String,
+    pub duration_nanos: String,
+    pub attributes: Vec<KeyValue>,
+}
+
+/// The OTLP JSON encoded trace, like the response of Tempo.
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct TraceByIdResponse {
+    pub batches: Vec<ResourceSpans>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceSpans {
+    pub resource: Resource,
+    pub scope_spans: Vec<ScopeSpans>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct Resource {
+    pub attributes: Vec<KeyValue>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct ScopeSpans {
+    pub scope: Scope,
+    pub spans: Vec<Span>,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Scope {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub version: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Span {
+    pub trace_id: String,
+    pub span_id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub parent_span_id: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub trace_state: String,
+    pub name: String,
+    pub kind: String,
+    pub start_time_unix_nano: String,
+    pub end_time_unix_nano: String,
+    pub attributes: Vec<KeyValue>,
+    pub status: SpanStatus,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+pub struct SpanStatus {
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub code: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeyValue {
+    pub key: String,
+    pub value: AnyValue,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum AnyValue {
+    StringValue(String),
+    /// 64-bit integers are strings in OTLP JSON.
+    IntValue(String),
+    DoubleValue(f64),
+    BoolValue(bool),
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TagNamesResponse {
+    pub tag_names: Vec<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TagValuesResponse {
+    pub tag_values: Vec<String>,
+}
+
+/// Handle the GET `/api/echo` request, which Grafana uses to test the data source.
+pub async fn echo() -> &'static str {
+    "echo"
+}
+
+/// Handle the GET `/api/search` request.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "tempo", request_type = "search"))]
+pub async fn search(
+    State(TempoState { handler, limits }): State<TempoState>,
+    Query(params): Query<SearchParams>,
+    Extension(mut query_ctx): Extension<QueryContext>,
+    TraceTableName(table_name): TraceTableName,
+) -> Response {
+    debug!("Received Tempo '/api/search' request, params: {:?}", params);
+
+    update_query_context(&mut query_ctx, table_name);
+    let query_ctx = Arc::new(query_ctx);
+    let db = query_ctx.get_db_string();
+    let _timer = METRIC_TEMPO_QUERY_ELAPSED
+        .with_label_values(&[db.as_str(), "/api/search"])
+        .start_timer();
+
+    json_response(do_search(handler, limits, params, query_ctx).await)
+}
+
+/// Handle the GET `/api/traces/{trace_id}` request.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "tempo", request_type = "get_trace"))]
+pub async fn get_trace(
+    State(TempoState { handler, .. }): State<TempoState>,
+    Path(trace_id): Path<String>,
+    Query(params): Query<TimeRangeParams>,
+    Extension(mut query_ctx): Extension<QueryContext>,
+    TraceTableName(table_name): TraceTableName,
+) -> Response {
+    debug!(
+        "Received Tempo '/api/traces/{}' request, params: {:?}",
+        trace_id, params
+    );
+
+    update_query_context(&mut query_ctx, table_name);
+    let query_ctx = Arc::new(query_ctx);
+    let db = query_ctx.get_db_string();
+    let _timer = METRIC_TEMPO_QUERY_ELAPSED
+        .with_label_values(&[db.as_str(), "/api/traces"])
+        .start_timer();
+
+    let result: Result<TraceByIdResponse> = async {
+        let output = handler
+            .get_trace(
+                query_ctx,
+                &trace_id.to_lowercase(),
+                params.start.map(seconds_to_nanos),
+                params.end.map(seconds_to_nanos),
+            )
+            .await?;
+        match covert_to_records(output).await? {
+            Some(records) => Ok(trace_from_records(records)),
+            None => Ok(TraceByIdResponse::default()),
+        }
+    }
+    .await;
+
+    match result {
+        Ok(trace) if !trace.batches.is_empty() => axum::Json(trace).into_response(),
+        Ok(_) => (HttpStatusCode::NOT_FOUND, TRACE_NOT_FOUND).into_response(),
+        Err(err) if err.status_code() == StatusCode::TableNotFound => {
+            (HttpStatusCode::NOT_FOUND, TRACE_NOT_FOUND).into_response()
+        }
+        Err(err) => error_response(err),
+    }
+}
+
+/// Handle the GET `/api/search/tags` request.
+#[axum_macros::debug_handler]
+#[tracing::instrument(skip_all, fields(protocol = "tempo", request_type = "search_tags"))]
+pub async fn search_tags(
+    State(TempoState { handler, .. }): State<TempoState>,
+    Query(params): Query<TagsParams>,
+    Extension(mut query_ctx): Extension<QueryContext>,
+    TraceTableName(table_name): TraceTableName,
+) -> Response {
+    update_query_context(&mut query_ctx, table_name);
+    let query_ctx = Arc::new(query_ctx);
+    let db = query_ctx.get_db_string();
+    let _timer = METRIC_TEMPO_QUERY_ELAPSED
+        .with_label_values(&[db.as_str(), "/api/search/tags"])
+        .start_timer();
+
+    let result: Result<TagNamesResponse> = async {
+        let tag_names = match params.scope.as_deref().unwrap_or("all") {
+            "intrinsic" => INTRINSIC_TAGS.iter().map(|s| s.to_string()).collect(),
+            "span" => {
+                handler
+                    .tag_names(query_ctx, Some(AttributeScope::Span))
+                    .await?
+            }
+            "resource" => {
+                handler
+                    .tag_names(query_ctx, Some(AttributeScope::Resource))
+                    .await?
+            }
+            "all" | "" => handler.tag_names(query_ctx, None).await?,
+            scope => {
+                return InvalidTempoQuerySnafu {
+                    reason: format!("invalid scope {scope:?}"),
+                }
+                .fail();
+            }
+        };
+        Ok(TagNamesResponse { tag_names })
+    }
+    .await;
+
+    json_response(result)
+}
+
+/// Handle the GET `/api/search/tag/{tag}/values` request.
+#[axum_macros::debug_handler]
+#[tracing::instrument(
+    skip_all,
+    fields(protocol = "tempo", request_type = "search_tag_values")
+)]
+pub async fn search_tag_values(
+    State(TempoState { handler, .. }): State<TempoState>,
+    Path(tag): Path<String>,
+    Query(params): Query<TimeRangeParams>,
+    Extension(mut query_ctx): Extension<QueryContext>,
+    TraceTableName(table_name): TraceTableName,
+) -> Response {
+    update_query_context(&mut query_ctx, table_name);
+    let query_ctx = Arc::new(query_ctx);
+    let db = query_ctx.get_db_string();
+    let _timer = METRIC_TEMPO_QUERY_ELAPSED
+        .with_label_values(&[db.as_str(), "/api/search/tag/values"])
+        .start_timer();
+
+    let result: Result<TagValuesResponse> = async {
+        // Tags are attribute names like `http.method` in the v1 API, and may be scoped like
+        // `span.http.method` or intrinsics in TraceQL.
+        let field = Field::parse(&tag).unwrap_or_else(|_| Field::Attribute {
+            scope: AttributeScope::Any,
+            name: tag.clone(),
+        });
+        let tag_values = match field {
+            Field::Status => STATUS_VALUES.iter().map(|s| s.to_string()).collect(),
+            Field::Kind => KIND_VALUES.iter().map(|s| s.to_string()).collect(),
+            Field::Duration => vec![],
+            field => {
+                handler
+                    .tag_values(
+                        query_ctx,
+                        &field,
+                        params.start.map(seconds_to_nanos),
+                        params.end.map(seconds_to_nanos),
+                        params.limit.unwrap_or(DEFAULT_TAG_VALUES_LIMIT),
+                    )
+                    .await?
+            }
+        };
+        Ok(TagValuesResponse { tag_values })
+    }
+    .await;
+
+    json_response(result)
+}
+
+async fn do_search(
+    handler: TempoQueryHandlerRef,
+    limits: TempoSearchLimits,
+    params: SearchParams,
+    ctx: QueryContextRef,
+) -> Result<SearchResponse> {
+    let query = build_query(&params)?;
+    let limit = match params.limit {
+        Some(limit) if limit > 0 => limit.min(limits.max_search_limit),
+        _ => DEFAULT_SEARCH_LIMIT,
+    };
+    let spans_per_span_set = params
+        .spss
+        .unwrap_or(DEFAULT_SPANS_PER_SPAN_SET)
+        .min(limits.max_spans_per_span_set);
+    // Structural queries may reject the most recent candidates, so page through older
+    // candidates until enough traces are found, within a bounded number of rounds.
+    let (candidate_limit, max_rounds) = if query.spansets.len() > 1 {
+        (
+            limit.saturating_mul(STRUCTURAL_CANDIDATE_FACTOR),
+            MAX_STRUCTURAL_SEARCH_ROUNDS,
+        )
+    } else {
+        (limit, 1)
+    };
+
+    // Every result span matches the last spanset, so only traces with such spans are candidates.
+    let last_filter = query.spansets.last().and_then(|s| s.filter.as_ref());
+    let mut response = SearchResponse::default();
+    let mut offset = 0;
+    for _ in 0..max_rounds {
+        let trace_ids = handler
+            .find_trace_ids(
+                ctx.clone(),
+                last_filter,
+                params.start.map(seconds_to_nanos),
+                params.end.map(seconds_to_nanos),
+                offset,
+                candidate_limit,
+            )
+            .await?;
+        if trace_ids.is_empty() {
+            break;
+        }
+        offset += trace_ids.len();
+        let exhausted = trace_ids.len() < candidate_limit;
+
+        search_candidates(
+            &handler,
+            &ctx,
+            &query,
+            &trace_ids,
+            limit,
+            spans_per_span_set,
+            &mut response,
+        )
+        .await?;
+        if exhausted || response.traces.len() >= limit {
+            break;
+        }
+    }
+
+    Ok(response)
+}
+
+/// Evaluates the query on the candidate traces and appends matched traces to the response
+/// until it contains `limit` traces.
+async fn search_candidates(
+    handler: &TempoQueryHandlerRef,
+    ctx: &QueryContextRef,
+    query: &TraceQuery,
+    trace_ids: &[String],
+    limit: usize,
+    spans_per_span_set: usize,
+    response: &mut SearchResponse,
+) -> Result<()> {
+    // All spans of the candidates, to find the root spans and the ancestors of spans.
+    let spans = spans_from_output(handler.find_spans(ctx.clone(), trace_ids, None).await?).await?;
+    let mut matched = Vec::with_capacity(query.spansets.len());
+    for spanset in &query.spansets {
+        let matched_spans = match &spanset.filter {
+            Some(filter) => {
+                spans_from_output(
+                    handler
+                        .find_spans(ctx.clone(), trace_ids, Some(filter))
+                        .await?,
+                )
+                .await?
+            }
+            None => spans.clone(),
+        };
+        let mut span_ids: HashMap<String, HashSet<String>> = HashMap::new();
+        for span in matched_spans {
+            span_ids
+                .entry(span.trace_id)
+                .or_default()
+                .insert(span.span_id);
+        }
+        matched.push(span_ids);
+    }
+
+    let mut spans_by_trace: HashMap<&str, Vec<SpanRow>> = HashMap::new();
+    for span in &spans {
+        spans_by_trace
+            .entry(span.trace_id.as_str())
+            .or_default()
+            .push(span.clone());
+    }
+
+    response.metrics.inspected_traces += trace_ids.len();
+    response.metrics.inspected_spans += spans.len();
+    for trace_id in trace_ids {
+        if response.traces.len() >= limit {
+            break;
+        }
+        let Some(trace_spans) = spans_by_trace.get(trace_id.as_str()) else {
+            continue;
+        };
+        let matched_ids: Vec<HashSet<String>> = matched
+            .iter()
+            .map(|span_ids| span_ids.get(trace_id).cloned().unwrap_or_default())
+            .collect();
+        let result = evaluate_descendants(trace_spans, &matched_ids);
+        if result.is_empty() {
+            continue;
+        }
+        response.traces.push(trace_search_metadata(
+            trace_id,
+            trace_spans,
+            &result,
+            spans_per_span_set,
+        ));
+    }
+
+    Ok(())
+}
+
+/// Builds the TraceQL query from `q`, or from the `tags`, `minDuration` and `maxDuration`
+/// params of the v1 search API.
+fn build_query(params: &SearchParams) -> Result<TraceQuery> {
+    if let Some(q) = params.q.as_deref()
+        && !q.trim().is_empty()
+    {
+        return traceql::parse(q);
+    }
+
+    let mut filters = vec![];
+    if let Some(tags) = params.tags.as_deref() {
+        for (key, value) in parse_logfmt(tags)? {
+            filters.push(FieldExpr::Compare {
+                field: Field::Attribute {
+                    scope: AttributeScope::Any,
+                    name: key,
+                },
+                op: CompareOp::Eq,
+                value: tag_value_to_static(value),
+            });
+        }
+    }
+    for (duration, op) in [
+        (&params.min_duration, CompareOp::Gte),
+        (&params.max_duration, CompareOp::Lte),
+    ] {
+        if let Some(duration) = duration.as_deref()
+            && !duration.is_empty()
+        {
+            filters.push(FieldExpr::Compare {
+                field: Field::Duration,
+                op,
+                value: Static::Duration(traceql::parse_duration(duration)?),
+            });
+        }
+    }
+
+    let filter = filters.into_iter().reduce(FieldExpr::and);
+    Ok(TraceQuery {
+        spansets: vec![Spanset { filter }],
+    })
+}
+
+fn tag_value_to_static(value: String) -> Static {
+    if let Ok(int) = value.parse::<i64>() {
+        Static::Int(int)
+    } else if let Ok(float) = value.parse::<f64>() {
+        Static::Float(float)
+    } else if let Ok(bool) = value.parse::<bool>() {
+        Static::Bool(bool)
+    } else {
+        Static::String(value)
+    }
+}
+
+/// Parses logfmt like `a=b c="d e"` into key-value pairs.
+fn parse_logfmt(input: &str) -> Result<Vec<(String, String)>> {
+    let mut pairs = vec![];
+    let mut chars = input.chars().peekable();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        if chars.peek().is_none() {
+            return Ok(pairs);
+        }
+
+        let mut key = String::new();
+        while let Some(c) = chars.next_if(|c| *c != '=' && !c.is_whitespace()) {
+            key.push(c);
+        }
+        let invalid = || InvalidTempoQuerySnafu {
+            reason: format!("invalid tags {input:?}"),
+        };
+        chars.next_if_eq(&'=').with_context(invalid)?;
+
+        let mut value = String::new();
+        if chars.next_if_eq(&'"').is_some() {
+            loop {
+                match chars.next().with_context(invalid)? {
+                    '"' => break,
+                    '\\' => value.push(chars.next().with_context(invalid)?),
+                    c => value.push(c),
+                }
+            }
+        } else {
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                value.push(c);
+            }
+        }
+        pairs.push((key, value));
+    }
+}
+
+fn trace_search_metadata(
+    trace_id: &str,
+    spans: &[SpanRow],
+    matched: &HashSet<String>,
+    spans_per_span_set: usize,
+) -> TraceSearchMetadata {
+    let span_ids: HashSet<&str> = spans.iter().map(|s| s.span_id.as_str()).collect();
+    let root = spans.iter().find(|s| match &s.parent_span_id {
+        Some(parent) => !span_ids.contains(parent.as_str()),
+        None => true,
+    });
+    let start = spans
+        .iter()
+        .map(|s| s.start_time_unix_nano)
+        .min()
+        .unwrap_or_default();
+    let end = spans
+        .iter()
+        .map(|s| s.start_time_unix_nano + s.duration_nano)
+        .max()
+        .unwrap_or_default();
+
+    let mut matched_spans: Vec<&SpanRow> = spans
+        .iter()
+        .filter(|s| matched.contains(&s.span_id))
+        .collect();
+    matched_spans.sort_by_key(|s| s.start_time_unix_nano);
+    let span_set = SpanSet {
+        spans: matched_spans
+            .iter()
+            .take(spans_per_span_set)
+            .map(|s| SearchSpan {
+                span_id: s.span_id.clone(),
+                name: s.name.clone(),
+                start_time_unix_nano: s.start_time_unix_nano.to_string(),
+                duration_nanos: s.duration_nano.to_string(),
+                attributes: vec![KeyValue {
+                    key: KEY_SERVICE_NAME.to_string(),
+                    value: AnyValue::StringValue(s.service_name.clone()),
+                }],
+            })
+            .collect(),
+        matched: matched_spans.len(),
+    };
+
+    TraceSearchMetadata {
+        trace_id: trace_id.to_string(),
+        root_service_name: root
+            .map(|s| s.service_name.clone())
+            .unwrap_or_else(|| ROOT_SPAN_NOT_YET_RECEIVED.to_string()),
+        root_trace_name: root.map(|s| s.name.clone()).unwrap_or_default(),
+        start_time_unix_nano: start.to_string(),
+        duration_ms: end.saturating_sub(start) / 1_000_000,
+        span_sets: vec![span_set.clone()],
+        span_set,
+    }
+}
+
+async fn spans_from_output(output: Output) -> Result<Vec<SpanRow>> {
+    Ok(covert_to_records(output)
+        .await?
+        .map(spans_from_records)
+        .unwrap_or_default())
+}
+
+fn spans_from_records(records: HttpRecordsOutput) -> Vec<SpanRow> {
+    let mut spans = Vec::with_capacity(records.total_rows);
+    for row in records.rows {
+        let mut span = SpanRow::default();
+        for (idx, cell) in row.into_iter().enumerate() {
+            match records.schema.column_schemas[idx].name.as_str() {
+                TRACE_ID_COLUMN => span.trace_id = string_cell(cell),
+                SPAN_ID_COLUMN => span.span_id = string_cell(cell),
+                PARENT_SPAN_ID_COLUMN => {
+                    span.parent_span_id = Some(string_cell(cell)).filter(|s| !s.is_empty())
+                }
+                SPAN_NAME_COLUMN => span.name = string_cell(cell),
+                SERVICE_NAME_COLUMN => span.service_name = string_cell(cell),
+                TIMESTAMP_COLUMN => span.start_time_unix_nano = cell.as_u64().unwrap_or_default(),
+                DURATION_NANO_COLUMN => span.duration_nano = cell.as_u64().unwrap_or_default(),
+                _ => {}
+            }
+        }
+        spans.push(span);
+    }
+    spans
+}
+
+/// Converts the rows of a trace into OTLP JSON, grouping spans by service and scope.
+fn trace_from_records(records: HttpRecordsOutput) -> TraceByIdResponse {
+    // service name -> (resource attributes, scope -> spans)
+    let mut services: BTreeMap<String, (Vec<KeyValue>, BTreeMap<Scope, Vec<Span>>)> =
+        BTreeMap::new();
+
+    for row in records.rows {
+        let mut span = Span::default();
+        let mut service_name = String::new();
+        let mut scope = Scope::default();
+        let mut resource_attributes = vec![];
+        let mut start = 0;
+        let mut duration = 0;
+
+        for (idx, cell) in row.into_iter().enumerate() {
+            let column_name = records.schema.column_schemas[idx].name.as_str();
+            match column_name {
+                TRACE_ID_COLUMN => span.trace_id = encode_id(&string_cell(cell)),
+                SPAN_ID_COLUMN => span.span_id = encode_id(&string_cell(cell)),
+                PARENT_SPAN_ID_COLUMN => span.parent_span_id = encode_id(&string_cell(cell)),
+                TRACE_STATE_COLUMN => span.trace_state = string_cell(cell),
+                SPAN_NAME_COLUMN => span.name = string_cell(cell),
+                SPAN_KIND_COLUMN => span.kind = string_cell(cell),
+                SPAN_STATUS_CODE => span.status.code = string_cell(cell),
+                SPAN_STATUS_MESSAGE_COLUMN => span.status.message = string_cell(cell),
+                SERVICE_NAME_COLUMN => service_name = string_cell(cell),
+                SCOPE_NAME_COLUMN => scope.name = string_cell(cell),
+                SCOPE_VERSION_COLUMN => scope.version = string_cell(cell),
+                TIMESTAMP_COLUMN => start = cell.as_u64().unwrap_or_default(),
+                DURATION_NANO_COLUMN => duration = cell.as_u64().unwrap_or_default(),
+                _ => {
+                    let attributes =
+                        if let Some(key) = column_name.strip_prefix(SPAN_ATTRIBUTE_COLUMN_PREFIX) {
+                            Some((key, &mut span.attributes))
+                        } else {
+                            column_name
+                                .strip_prefix(RESOURCE_ATTRIBUTE_COLUMN_PREFIX)
+                                .map(|key| (key, &mut resource_attributes))
+                        };
+                    if let Some((key, attributes)) = attributes
+                        && let Some(value) = to_any_value(cell)
+                    {
+                        attributes.push(KeyValue {
+                            key: key.to_string(),
+                            value,
+                        });
+                    }
+                }
+            }
+        }
+        span.start_time_unix_nano = start.to_string();
+        span.end_time_unix_nano = (start + duration).to_string();
+
+        let (attributes, scopes) = services.entry(service_name.clone()).or_insert_with(|| {
+            resource_attributes.push(KeyValue {
+                key: KEY_SERVICE_NAME.to_string(),
+                value: AnyValue::StringValue(service_name),
+            });
+            (resource_attributes, BTreeMap::new())
+        });
+        attributes.sort_by(|a, b| a.key.cmp(&b.key));
+        scopes.entry(scope).or_default().push(span);
+    }
+
+    TraceByIdResponse {
+        batches: services
+            .into_values()
+            .map(|(attributes, scopes)| ResourceSpans {
+                resource: Resource { attributes },
+                scope_spans: scopes
+                    .into_iter()
+                    .map(|(scope, spans)| ScopeSpans { scope, spans })
+                    .collect(),
+            })
+            .collect(),
+    }
+}
+
+fn string_cell(cell: JsonValue) -> String {
+    match cell {
+        JsonValue::String(s) => s,
+        JsonValue::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+/// Ids are hex strings in the trace table and base64 encoded bytes in OTLP JSON.
+fn encode_id(id: &str) -> String {
+    match hex::decode(id) {
+        Ok(bytes) => BASE64.encode(bytes),
+        Err(_) => id.to_string(),
+    }
+}
+
+fn to_any_value(cell: JsonValue) -> Option<AnyValue> {
+    match cell {
+        JsonValue::Null => None,
+        JsonValue::String(s) => Some(AnyValue::StringValue(s)),
+        JsonValue::Bool(b) => Some(AnyValue::BoolValue(b)),
+        JsonValue::Number(n) => match n.as_i64() {
+            Some(i) => Some(AnyValue::IntValue(i.to_string())),
+            None => n.as_f64().map(AnyValue::DoubleValue),
+        },
+        other => Some(AnyValue::StringValue(other.to_string())),
+    }
+}
+
+fn seconds_to_nanos(seconds: i64) -> i64 {
+    seconds.saturating_mul(1_000_000_000)
+}
+
+fn update_query_context(query_ctx: &mut QueryContext, table_name: Option<String>) {
+    if let Some(table) = table_name {
+        query_ctx.set_extension(TEMPO_QUERY_TABLE_NAME_KEY, table);
+    }
+}
+
+fn json_response<T: Serialize + Default>(result: Result<T>) -> Response {
+    match result {
+        Ok(body) => axum::Json(body).into_response(),
+        // Like Tempo, return an empty result if there is no trace table.
+        Err(err) if err.status_code() == StatusCode::TableNotFound => {
+            axum::Json(T::default()).into_response()
+        }
+        Err(err) => error_response(err),
+    }
+}
+
+fn error_response(err: Error) -> Response {
+    error!(err; "Failed to handle Tempo query");
+    (
+        status_code_to_http_status(&err.status_code()),
+        err.output_msg(),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::{ColumnSchema, OutputSchema};
+
+    fn records(columns: &[(&str, &str)], rows: Vec<Vec<JsonValue>>) -> HttpRecordsOutput {
+        HttpRecordsOutput {
+            schema: OutputSchema::new(
+                columns
+                    .iter()
+                    .map(|(name, data_type)| {
+                        ColumnSchema::new(name.to_string(), data_type.to_string())
+                    })
+                    .collect(),
+            ),
+            total_rows: rows.len(),
+            rows,
+            metrics: Default::default(),
+        }
+    }
+
+    #[test]
+    fn test_build_query() {
+        let query = build_query(&SearchParams {
+            tags: Some(r#"service.name=api http.status_code=500 error="a \"b\"""#.to_string()),
+            min_duration: Some("100ms".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        let attribute = |name: &str| Field::Attribute {
+            scope: AttributeScope::Any,
+            name: name.to_string(),
+        };
+        let compare = |field, op, value| FieldExpr::Compare { field, op, value };
+        assert_eq!(
+            Some(
+                compare(
+                    attribute("service.name"),
+                    CompareOp::Eq,
+                    Static::String("api".to_string())
+                )
+                .and(compare(
+                    attribute("http.status_code"),
+                    CompareOp::Eq,
+                    Static::Int(500)
+                ))
+                .and(compare(
+                    attribute("error"),
+                    CompareOp::Eq,
+                    Static::String("a \"b\"".to_string())
+                ))
+                .and(compare(
+                    Field::Duration,
+                    CompareOp::Gte,
+                    Static::Duration(100_000_000)
+                ))
+            ),
+            query.spansets[0].filter
+        );
+
+        let query = build_query(&SearchParams {
+            q: Some("{} >> { name = \"a\" }".to_string()),
+            tags: Some("ignored=true".to_string()),
+            ..Default::default()
+        })
+        .unwrap();
+        assert_eq!(2, query.spansets.len());
+
+        assert_eq!(
+            None,
+            build_query(&SearchParams::default()).unwrap().spansets[0].filter
+        );
+        assert!(
+            build_query(&SearchParams {
+                tags: Some("a=\"b".to_string()),
+                ..Default::default()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_trace_search_metadata() {
+        let span = |span_id: &str, parent: Option<&str>, start, duration| SpanRow {
+            trace_id: "t1".to_string(),
+            span_id: span_id.to_string(),
+            parent_span_id: parent.map(|s| s.to_string()),
+            name: format!("op-{span_id}"),
+            service_name: "api".to_string(),
+            start_time_unix_nano: start,
+            duration_nano: duration,
+        };
+        let spans = vec![
+            span("b", Some("a"), 2_000_000, 1_000_000),
+            span("a", None, 1_000_000, 5_000_000),
+            span("c", Some("b"), 3_000_000, 8_000_000),
+        ];
+        let matched = ["b", "c"].iter().map(|s| s.to_string()).collect();
+
+        let metadata = trace_search_metadata("t1", &spans, &matched, 1);
+        assert_eq!("api", metadata.root_service_name);
+        assert_eq!("op-a", metadata.root_trace_name);
+        assert_eq!("1000000", metadata.start_time_unix_nano);
+        assert_eq!(10, metadata.duration_ms);
+        assert_eq!(2, metadata.span_set.matched);
+        assert_eq!(1, metadata.span_set.spans.len());
+        assert_eq!("b", metadata.span_set.spans[0].span_id);
+        assert_eq!(vec![metadata.span_set.clone()], metadata.span_sets);
+
+        let metadata = trace_search_metadata("t1", &spans[2..], &matched, 3);
+        assert_eq!(ROOT_SPAN_NOT_YET_RECEIVED, metadata.root_service_name);
+    }
+
+    #[test]
+    fn test_trace_from_records() {
+        let records = records(
+            &[
+                (TIMESTAMP_COLUMN, "TimestampNanosecond"),
+                (DURATION_NANO_COLUMN, "UInt64"),
+                (TRACE_ID_COLUMN, "String"),
+                (SPAN_ID_COLUMN, "String"),
+                (PARENT_SPAN_ID_COLUMN, "String"),
+                (SPAN_NAME_COLUMN, "String"),
+                (SPAN_KIND_COLUMN, "String"),
+                (SPAN_STATUS_CODE, "String"),
+                (SERVICE_NAME_COLUMN, "String"),
+                (SCOPE_NAME_COLUMN, "String"),
+                ("span_attributes.http.status_code", "Int64"),
+                ("resource_attributes.host", "String"),
+            ],
+            vec![
+                vec![
+                    JsonValue::from(1000),
+                    JsonValue::from(500),
+                    JsonValue::from("0102"),
+                    JsonValue::from("03"),
+                    JsonValue::from(""),
+                    JsonValue::from("GET"),
+                    JsonValue::from("SPAN_KIND_SERVER"),
+                    JsonValue::from("STATUS_CODE_OK"),
+                    JsonValue::from("api"),
+                    JsonValue::from("scope"),
+                    JsonValue::from(200),
+                    JsonValue::from("h1"),
+                ],
+                vec![
+                    JsonValue::from(1100),
+                    JsonValue::from(100),
+                    JsonValue::from("0102"),
+                    JsonValue::from("04"),
+                    JsonValue::from("03"),
+                    JsonValue::from("SELECT"),
+                    JsonValue::from("SPAN_KIND_CLIENT"),
+                    JsonValue::from("STATUS_CODE_UNSET"),
+                    JsonValue::from("api"),
+                    JsonValue::from("scope"),
+                    JsonValue::Null,
+                    JsonValue::from("h1"),
+                ],
+            ],
+        );
+
+        let trace = trace_from_records(records);
+        assert_eq!(1, trace.batches.len());
+        let batch = &trace.batches[0];
+        assert_eq!(
+            vec![
+                KeyValue {
+                    key: "host".to_string(),
+                    value: AnyValue::StringValue("h1".to_string()),
+                },
+                KeyValue {
+                    key: KEY_SERVICE_NAME.to_string(),
+                    value: AnyValue::StringValue("api".to_string()),
+                },
+            ],
+            batch.resource.attributes
+        );
+        assert_eq!(1, batch.scope_spans.len());
+        let spans = &batch.scope_spans[0].spans;
+        assert_eq!(2, spans.len());
+        assert_eq!("AQI=", spans[0].trace_id);
+        assert_eq!("", spans[0].parent_span_id);
+        assert_eq!("1500", spans[0].end_time_unix_nano);
+        assert_eq!(
+            vec![KeyValue {
+                key: "http.status_code".to_string(),
+                value: AnyValue::IntValue("200".to_string()),
+            }],
+            spans[0].attributes
+        );
+        assert_eq!("Aw==", spans[1].parent_span_id);
+        assert!(spans[1].attributes.is_empty());
+
+        let json = serde_json::to_value(&trace).unwrap();
+        assert_eq!(
+            json["batches"][0]["scopeSpans"][0]["spans"][0]["attributes"][0]["value"],
+            serde_json::json!({"intValue": "200"})
+        );
+    }
+}
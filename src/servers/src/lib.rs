@@ -52,6 +52,7 @@ pub mod request_memory_metrics;
 mod row_writer;
 pub mod semantic;
 pub mod server;
+pub mod tempo;
 pub mod tls;
 
 /// Cached sql plan or statement for database interfaces
@@ -101,6 +101,22 @@ lazy_static! {
         vec![0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 60.0, 300.0]
     )
     .unwrap();
+    /// Http influxdb query duration per database.
+    pub static ref METRIC_HTTP_INFLUXDB_QUERY_ELAPSED: HistogramVec = register_histogram_vec!(
+        "greptime_servers_http_influxdb_query_elapsed",
+        "servers http influxdb query elapsed",
+        &[METRIC_DB_LABEL],
+        vec![0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 60.0, 300.0]
+    )
+    .unwrap();
+    /// Http opentsdb query duration per database and request type.
+    pub static ref METRIC_HTTP_OPENTSDB_QUERY_ELAPSED: HistogramVec = register_histogram_vec!(
+        "greptime_servers_http_opentsdb_query_elapsed",
+        "servers http opentsdb query elapsed",
+        &[METRIC_DB_LABEL, METRIC_TYPE_LABEL],
+        vec![0.005, 0.01, 0.05, 0.1, 0.5, 1.0, 5.0, 10.0, 60.0, 300.0]
+    )
+    .unwrap();
     /// Http prometheus write duration per database and remote write protocol version.
     pub static ref METRIC_HTTP_PROM_STORE_WRITE_ELAPSED: HistogramVec = register_histogram_vec!(
         "greptime_servers_http_prometheus_write_elapsed",
@@ -357,6 +373,11 @@ lazy_static! {
         "servers jaeger query elapsed",
         &[METRIC_DB_LABEL, METRIC_PATH_LABEL]
     ).unwrap();
+    pub static ref METRIC_TEMPO_QUERY_ELAPSED: HistogramVec = register_histogram_vec!(
+        "greptime_servers_tempo_query_elapsed",
+        "servers tempo query elapsed",
+        &[METRIC_DB_LABEL, METRIC_PATH_LABEL]
+    ).unwrap();
 
     pub static ref GRPC_BULK_INSERT_ELAPSED: Histogram = register_histogram!(
         "greptime_servers_bulk_insert_elapsed",
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 pub mod codec;
+pub mod query;
 
 use api::v1::RowInsertRequests;
 use common_grpc::precision::Precision;
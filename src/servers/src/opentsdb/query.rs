@@ -0,0 +1,880 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The query side of the OpenTSDB HTTP API.
+//!
+//! Tag filters and downsampling of a sub query are pushed down to the query engine as a
+//! [MetricQuery], which returns the points of every matched series. Like OpenTSDB, the rate
+//! conversion and the aggregation across series are applied to the downsampled series afterwards.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use arrow::array::{Array, AsArray};
+use arrow::datatypes::{Float64Type, Int64Type};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use common_query::prelude::{greptime_timestamp, greptime_value};
+use common_recordbatch::RecordBatch;
+use serde::{Deserialize, Serialize};
+use snafu::{OptionExt, ensure};
+
+use crate::error::{self, Result};
+use crate::opentsdb::codec::DataPoint;
+
+/// Aggregators of downsampling and of aggregating across series.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregator {
+    Sum,
+    Avg,
+    Min,
+    Max,
+    Count,
+    Dev,
+    First,
+    Last,
+    /// Skips the aggregation across series. Not allowed in downsampling.
+    None,
+}
+
+impl Aggregator {
+    pub fn try_from_str(name: &str) -> Result<Self> {
+        // We don't interpolate missing points across series, so the `zim*` and `mim*` variants
+        // are the same as the plain ones.
+        let aggregator = match name {
+            "sum" | "zimsum" => Self::Sum,
+            "avg" => Self::Avg,
+            "min" | "mimmin" => Self::Min,
+            "max" | "mimmax" => Self::Max,
+            "count" => Self::Count,
+            "dev" => Self::Dev,
+            "first" => Self::First,
+            "last" => Self::Last,
+            "none" => Self::None,
+            _ => {
+                return error::InvalidQuerySnafu {
+                    reason: format!("unsupported aggregator: {name}"),
+                }
+                .fail();
+            }
+        };
+        Ok(aggregator)
+    }
+
+    /// Aggregates the values, which are in time order.
+    fn aggregate(&self, values: &[f64]) -> Option<f64> {
+        if values.is_empty() {
+            return None;
+        }
+        let count = values.len() as f64;
+        let sum = values.iter().sum::<f64>();
+        let value = match self {
+            Self::Sum => sum,
+            Self::Avg => sum / count,
+            Self::Min => values.iter().copied().fold(f64::INFINITY, f64::min),
+            Self::Max => values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+            Self::Count => count,
+            Self::Dev => {
+                let mean = sum / count;
+                let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / count;
+                variance.sqrt()
+            }
+            Self::First => values[0],
+            Self::Last => values[values.len() - 1],
+            Self::None => return None,
+        };
+        Some(value)
+    }
+}
+
+/// How to fill the intervals without any points in downsampling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FillPolicy {
+    /// Skips the missing intervals.
+    None,
+    /// Emits `null` for missing intervals, the same as [FillPolicy::Null] in JSON.
+    Nan,
+    Null,
+    Zero,
+}
+
+/// A downsample specification like `1m-avg` or `1m-avg-zero`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Downsample {
+    pub interval_millis: i64,
+    pub aggregator: Aggregator,
+    pub fill: FillPolicy,
+}
+
+impl Downsample {
+    pub fn try_from_str(downsample: &str) -> Result<Self> {
+        let mut parts = downsample.splitn(3, '-');
+        let (Some(interval), Some(aggregator)) = (parts.next(), parts.next()) else {
+            return error::InvalidQuerySnafu {
+                reason: format!("invalid downsample: {downsample}"),
+            }
+            .fail();
+        };
+        let fill = match parts.next() {
+            None | Some("none") => FillPolicy::None,
+            Some("nan") => FillPolicy::Nan,
+            Some("null") => FillPolicy::Null,
+            Some("zero") => FillPolicy::Zero,
+            Some(fill) => {
+                return error::InvalidQuerySnafu {
+                    reason: format!("unsupported fill policy: {fill}"),
+                }
+                .fail();
+            }
+        };
+        let aggregator = Aggregator::try_from_str(aggregator)?;
+        ensure!(
+            aggregator != Aggregator::None,
+            error::InvalidQuerySnafu {
+                reason: "aggregator none is not allowed in downsample",
+            }
+        );
+
+        Ok(Self {
+            interval_millis: parse_duration_millis(interval)?,
+            aggregator,
+            fill,
+        })
+    }
+}
+
+/// Options of converting counters or gauges to per second rates.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RateOptions {
+    /// Whether the values are monotonically increasing counters that may roll over or reset.
+    pub counter: bool,
+    pub counter_max: f64,
+    /// Rates above this value are treated as counter resets and reported as 0. `0` disables it.
+    pub reset_value: f64,
+    /// Drops the points of counter resets instead of computing them with `counter_max`.
+    pub drop_resets: bool,
+}
+
+impl Default for RateOptions {
+    fn default() -> Self {
+        Self {
+            counter: false,
+            counter_max: i64::MAX as f64,
+            reset_value: 0.0,
+            drop_resets: false,
+        }
+    }
+}
+
+/// The filter of a tag value.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagFilterKind {
+    LiteralOr(Vec<String>),
+    /// Case insensitive [TagFilterKind::LiteralOr].
+    ILiteralOr(Vec<String>),
+    NotLiteralOr(Vec<String>),
+    NotILiteralOr(Vec<String>),
+    /// A pattern where `*` matches any characters.
+    Wildcard(String),
+    IWildcard(String),
+    Regexp(String),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TagFilter {
+    pub tagk: String,
+    pub kind: TagFilterKind,
+    /// Whether to split the series into groups by the values of the tag.
+    pub group_by: bool,
+}
+
+impl TagFilter {
+    pub fn try_new(filter_type: &str, tagk: String, filter: &str, group_by: bool) -> Result<Self> {
+        let values = || filter.split('|').map(ToString::to_string).collect();
+        let kind = match filter_type {
+            "literal_or" => TagFilterKind::LiteralOr(values()),
+            "iliteral_or" => TagFilterKind::ILiteralOr(values()),
+            "not_literal_or" => TagFilterKind::NotLiteralOr(values()),
+            "not_iliteral_or" => TagFilterKind::NotILiteralOr(values()),
+            "wildcard" => TagFilterKind::Wildcard(filter.to_string()),
+            "iwildcard" => TagFilterKind::IWildcard(filter.to_string()),
+            "regexp" => TagFilterKind::Regexp(filter.to_string()),
+            _ => {
+                return error::InvalidQuerySnafu {
+                    reason: format!("unsupported tag filter type: {filter_type}"),
+                }
+                .fail();
+            }
+        };
+        Ok(Self {
+            tagk,
+            kind,
+            group_by,
+        })
+    }
+
+    /// Parses the filter of the `tagk=filter` form, like `web01|web02`, `web*` or
+    /// `regexp(web.*)`.
+    pub fn parse_inline(tagk: String, filter: &str, group_by: bool) -> Result<Self> {
+        if let Some((filter_type, inner)) = filter.split_once('(')
+            && let Some(inner) = inner.strip_suffix(')')
+            && filter_type
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c == '_')
+        {
+            return Self::try_new(filter_type, tagk, inner, group_by);
+        }
+
+        let filter_type = if filter.contains('*') {
+            "wildcard"
+        } else {
+            "literal_or"
+        };
+        Self::try_new(filter_type, tagk, filter, group_by)
+    }
+}
+
+/// Reads the points of the series of a metric in `[start_millis, end_millis]`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricQuery {
+    pub metric: String,
+    pub start_millis: i64,
+    pub end_millis: i64,
+    pub filters: Vec<TagFilter>,
+    pub downsample: Option<Downsample>,
+}
+
+/// One sub query of the `/api/query` request.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SubQuery {
+    pub aggregator: Aggregator,
+    pub rate: Option<RateOptions>,
+    pub query: MetricQuery,
+}
+
+/// One result series of the `/api/query` response.
+#[derive(Debug, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryResult {
+    pub metric: String,
+    /// Tags whose values are the same in all the aggregated series.
+    pub tags: BTreeMap<String, String>,
+    /// Tags whose values differ in the aggregated series.
+    pub aggregate_tags: Vec<String>,
+    pub dps: BTreeMap<i64, Option<f64>>,
+}
+
+/// The tags and points of a series, in time order.
+pub type SeriesMap = BTreeMap<Vec<(String, String)>, Vec<(i64, Option<f64>)>>;
+
+impl SubQuery {
+    /// Parses the `m` parameter of `/api/query`, which is in the form of
+    /// `aggregator:[downsample:][rate[{counter[,counterMax[,resetValue]]}]:]metric[{group by
+    /// filters}][{filters}]`.
+    pub fn parse_m_param(m: &str, start_millis: i64, end_millis: i64) -> Result<Self> {
+        let parts = split_outside_braces(m, ':');
+        let invalid = || error::InvalidQuerySnafu {
+            reason: format!("invalid m parameter: {m}"),
+        };
+        let (Some((metric, options)), true) = (parts.split_last(), parts.len() >= 2) else {
+            return invalid().fail();
+        };
+
+        let aggregator = Aggregator::try_from_str(options[0])?;
+        let mut rate = None;
+        let mut downsample = None;
+        for option in &options[1..] {
+            if let Some(rate_options) = option.strip_prefix("rate") {
+                rate = Some(parse_rate_options(rate_options).with_context(invalid)?);
+            } else {
+                downsample = Some(Downsample::try_from_str(option)?);
+            }
+        }
+
+        let (metric, filter_groups) = match metric.split_once('{') {
+            Some((metric, filters)) => (metric, format!("{{{filters}")),
+            None => (*metric, String::new()),
+        };
+        let mut filters = vec![];
+        for (i, group) in split_filter_groups(&filter_groups)
+            .with_context(invalid)?
+            .into_iter()
+            .enumerate()
+        {
+            // Filters in the first braces group the series, and those in the second don't.
+            ensure!(i < 2, invalid());
+            for filter in group.split(',').filter(|filter| !filter.is_empty()) {
+                let (tagk, filter) = filter.split_once('=').with_context(invalid)?;
+                filters.push(TagFilter::parse_inline(tagk.to_string(), filter, i == 0)?);
+            }
+        }
+        ensure!(!metric.is_empty(), invalid());
+
+        Ok(Self {
+            aggregator,
+            rate,
+            query: MetricQuery {
+                metric: metric.to_string(),
+                start_millis,
+                end_millis,
+                filters,
+                downsample,
+            },
+        })
+    }
+
+    /// Applies the fill policy and the rate conversion to the series, then aggregates them by the
+    /// group by tags.
+    pub fn build_results(&self, series: SeriesMap, ms_resolution: bool) -> Vec<QueryResult> {
+        let series = series.into_iter().map(|(tags, points)| {
+            let points = self.fill(points);
+            let points = match &self.rate {
+                Some(options) => to_rates(points, options),
+                None => points,
+            };
+            (tags, points)
+        });
+
+        let group_by_tags = self
+            .query
+            .filters
+            .iter()
+            .filter(|filter| filter.group_by)
+            .map(|filter| filter.tagk.as_str())
+            .collect::<BTreeSet<_>>();
+        let mut groups: BTreeMap<Vec<(String, String)>, Vec<_>> = BTreeMap::new();
+        for (tags, points) in series {
+            let key = if self.aggregator == Aggregator::None {
+                tags.clone()
+            } else {
+                tags.iter()
+                    .filter(|(tagk, _)| group_by_tags.contains(tagk.as_str()))
+                    .cloned()
+                    .collect()
+            };
+            groups.entry(key).or_default().push((tags, points));
+        }
+
+        groups
+            .into_values()
+            .map(|members| self.aggregate_group(members, ms_resolution))
+            .collect()
+    }
+
+    fn fill(&self, points: Vec<(i64, Option<f64>)>) -> Vec<(i64, Option<f64>)> {
+        let Some(downsample) = &self.query.downsample else {
+            return points;
+        };
+        let fill_value = match downsample.fill {
+            FillPolicy::None => return points,
+            FillPolicy::Nan | FillPolicy::Null => None,
+            FillPolicy::Zero => Some(0.0),
+        };
+
+        let interval = downsample.interval_millis;
+        let start = self.query.start_millis - self.query.start_millis.rem_euclid(interval);
+        let mut points = points.into_iter().peekable();
+        let mut filled = vec![];
+        let mut ts = start;
+        while ts <= self.query.end_millis {
+            match points.next_if(|(point_ts, _)| *point_ts == ts) {
+                Some(point) => filled.push(point),
+                None => filled.push((ts, fill_value)),
+            }
+            ts += interval;
+        }
+        filled
+    }
+
+    fn aggregate_group(
+        &self,
+        members: Vec<(Vec<(String, String)>, Vec<(i64, Option<f64>)>)>,
+        ms_resolution: bool,
+    ) -> QueryResult {
+        let mut tags = members[0].0.iter().cloned().collect::<BTreeMap<_, _>>();
+        let mut aggregate_tags = BTreeSet::new();
+        for (member_tags, _) in &members {
+            let member_tags = member_tags.iter().cloned().collect::<BTreeMap<_, _>>();
+            tags.retain(|tagk, tagv| {
+                let same = member_tags.get(tagk) == Some(tagv);
+                if !same {
+                    aggregate_tags.insert(tagk.clone());
+                }
+                same
+            });
+            aggregate_tags.extend(
+                member_tags
+                    .into_keys()
+                    .filter(|tagk| !tags.contains_key(tagk)),
+            );
+        }
+
+        let mut values: BTreeMap<i64, Vec<f64>> = BTreeMap::new();
+        for (_, points) in members {
+            for (ts, value) in points {
+                let ts = if ms_resolution { ts } else { ts / 1000 };
+                let entry = values.entry(ts).or_default();
+                entry.extend(value);
+            }
+        }
+        let dps = values
+            .into_iter()
+            .map(|(ts, values)| {
+                let value = if self.aggregator == Aggregator::None {
+                    values.last().copied()
+                } else {
+                    self.aggregator.aggregate(&values)
+                };
+                (ts, value)
+            })
+            .collect();
+
+        QueryResult {
+            metric: self.query.metric.clone(),
+            tags,
+            aggregate_tags: aggregate_tags.into_iter().collect(),
+            dps,
+        }
+    }
+}
+
+/// Converts the points into the per second rates of their adjacent points.
+fn to_rates(points: Vec<(i64, Option<f64>)>, options: &RateOptions) -> Vec<(i64, Option<f64>)> {
+    let mut rates = Vec::with_capacity(points.len());
+    let mut prev: Option<(i64, f64)> = None;
+    for (ts, value) in points {
+        let Some(value) = value else {
+            continue;
+        };
+        if let Some((prev_ts, prev_value)) = prev.replace((ts, value)) {
+            let seconds = (ts - prev_ts) as f64 / 1000.0;
+            if seconds <= 0.0 {
+                continue;
+            }
+            let mut delta = value - prev_value;
+            if options.counter && delta < 0.0 {
+                if options.drop_resets {
+                    continue;
+                }
+                delta = options.counter_max - prev_value + value;
+            }
+            let mut rate = delta / seconds;
+            if options.counter && options.reset_value > 0.0 && rate > options.reset_value {
+                rate = 0.0;
+            }
+            rates.push((ts, Some(rate)));
+        }
+    }
+    rates
+}
+
+/// Parses the `{counter[,counterMax[,resetValue]]}` options after `rate`.
+fn parse_rate_options(options: &str) -> Option<RateOptions> {
+    let mut rate_options = RateOptions::default();
+    if options.is_empty() {
+        return Some(rate_options);
+    }
+
+    let options = options.strip_prefix('{')?.strip_suffix('}')?;
+    let mut options = options.split(',');
+    match options.next()? {
+        "counter" => rate_options.counter = true,
+        "" => {}
+        _ => return None,
+    }
+    if let Some(counter_max) = options.next().filter(|s| !s.is_empty()) {
+        rate_options.counter_max = counter_max.parse().ok()?;
+    }
+    if let Some(reset_value) = options.next().filter(|s| !s.is_empty()) {
+        rate_options.reset_value = reset_value.parse().ok()?;
+    }
+    options.next().is_none().then_some(rate_options)
+}
+
+fn split_outside_braces(s: &str, separator: char) -> Vec<&str> {
+    let mut parts = vec![];
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth = depth.saturating_sub(1),
+            c if c == separator && depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + c.len_utf8();
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
+/// Splits `{a=b,c=d}{e=f}` into `["a=b,c=d", "e=f"]`.
+fn split_filter_groups(s: &str) -> Option<Vec<&str>> {
+    let mut groups = vec![];
+    let mut rest = s;
+    while !rest.is_empty() {
+        let (group, remaining) = rest.strip_prefix('{')?.split_once('}')?;
+        groups.push(group);
+        rest = remaining;
+    }
+    Some(groups)
+}
+
+/// Parses a duration like `30s` or `1h` into milliseconds.
+pub fn parse_duration_millis(duration: &str) -> Result<i64> {
+    let split = duration
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(duration.len());
+    let (value, unit) = duration.split_at(split);
+    let unit_millis = match unit {
+        "ms" => 1,
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        "d" => 86_400_000,
+        "w" => 604_800_000,
+        _ => 0,
+    };
+    value
+        .parse::<i64>()
+        .ok()
+        .and_then(|value| value.checked_mul(unit_millis))
+        .filter(|millis| *millis > 0)
+        .with_context(|| error::InvalidQuerySnafu {
+            reason: format!("invalid duration: {duration}"),
+        })
+}
+
+/// Parses an absolute time or a relative time like `1h-ago` into Unix milliseconds.
+pub fn parse_time(time: &str, now_millis: i64) -> Result<i64> {
+    let time = time.trim();
+    if time == "now" {
+        return Ok(now_millis);
+    }
+    if let Some(duration) = time.strip_suffix("-ago") {
+        return Ok(now_millis - parse_duration_millis(duration)?);
+    }
+    if let Ok(ts) = time.parse::<i64>() {
+        return Ok(DataPoint::timestamp_to_millis(ts));
+    }
+    // Seconds with a fraction of milliseconds, like `1356998400.500`.
+    if let Ok(seconds) = time.parse::<f64>() {
+        return Ok((seconds * 1000.0) as i64);
+    }
+    for format in [
+        "%Y/%m/%d-%H:%M:%S",
+        "%Y/%m/%d %H:%M:%S",
+        "%Y/%m/%d-%H:%M",
+        "%Y/%m/%d %H:%M",
+    ] {
+        if let Ok(datetime) = NaiveDateTime::parse_from_str(time, format) {
+            return Ok(datetime.and_utc().timestamp_millis());
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(time, "%Y/%m/%d") {
+        return Ok(date.and_time(NaiveTime::MIN).and_utc().timestamp_millis());
+    }
+
+    error::InvalidQuerySnafu {
+        reason: format!("invalid time: {time}"),
+    }
+    .fail()
+}
+
+/// Collects the output of a [MetricQuery] into series. Columns other than the timestamp and
+/// value columns are tags.
+pub fn collect_series(batches: &[RecordBatch]) -> Result<SeriesMap> {
+    let mut series = SeriesMap::new();
+    for batch in batches {
+        let column_names = batch
+            .schema
+            .column_schemas()
+            .iter()
+            .map(|column| column.name.as_str())
+            .collect::<Vec<_>>();
+        let index_of = |name: &str| {
+            column_names
+                .iter()
+                .position(|column| *column == name)
+                .with_context(|| error::UnexpectedResultSnafu {
+                    reason: format!("column {name} not found in the OpenTSDB query result"),
+                })
+        };
+        let ts_index = index_of(greptime_timestamp())?;
+        let value_index = index_of(greptime_value())?;
+        let timestamps = batch
+            .column(ts_index)
+            .as_primitive_opt::<Int64Type>()
+            .context(error::UnexpectedResultSnafu {
+                reason: "timestamps of the OpenTSDB query result are not Int64",
+            })?;
+        let values = batch
+            .column(value_index)
+            .as_primitive_opt::<Float64Type>()
+            .context(error::UnexpectedResultSnafu {
+                reason: "values of the OpenTSDB query result are not Float64",
+            })?;
+        let tag_columns = (0..column_names.len())
+            .filter(|i| *i != ts_index && *i != value_index)
+            .map(|i| {
+                (
+                    column_names[i],
+                    batch.iter_column_as_string(i).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+
+        for row in 0..batch.num_rows() {
+            if timestamps.is_null(row) {
+                continue;
+            }
+            let tags = tag_columns
+                .iter()
+                .filter_map(|(tagk, tagvs)| {
+                    tagvs[row]
+                        .as_ref()
+                        .map(|tagv| (tagk.to_string(), tagv.clone()))
+                })
+                .collect();
+            let value = (!values.is_null(row)).then(|| values.value(row));
+            series
+                .entry(tags)
+                .or_default()
+                .push((timestamps.value(row), value));
+        }
+    }
+
+    for points in series.values_mut() {
+        points.sort_by_key(|(ts, _)| *ts);
+    }
+    Ok(series)
+}
+
+/// Collects the distinct tag sets of series from the output of a lookup.
+pub fn collect_tag_sets(batches: &[RecordBatch]) -> Vec<BTreeMap<String, String>> {
+    let mut tag_sets = BTreeSet::new();
+    for batch in batches {
+        let tag_columns = batch
+            .schema
+            .column_schemas()
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                (
+                    column.name.clone(),
+                    batch.iter_column_as_string(i).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        for row in 0..batch.num_rows() {
+            let tags = tag_columns
+                .iter()
+                .filter_map(|(tagk, tagvs)| tagvs[row].clone().map(|tagv| (tagk.clone(), tagv)))
+                .collect::<BTreeMap<_, _>>();
+            tag_sets.insert(tags);
+        }
+    }
+    tag_sets.into_iter().collect()
+}
+
+/// What the `/api/suggest` API suggests.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SuggestKind {
+    Metrics,
+    TagKeys,
+    TagValues,
+}
+
+impl SuggestKind {
+    pub fn try_from_str(kind: &str) -> Result<Self> {
+        match kind {
+            "metrics" => Ok(Self::Metrics),
+            "tagk" => Ok(Self::TagKeys),
+            "tagv" => Ok(Self::TagValues),
+            _ => error::InvalidQuerySnafu {
+                reason: format!("invalid suggest type: {kind}"),
+            }
+            .fail(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tags(tags: &[(&str, &str)]) -> Vec<(String, String)> {
+        tags.iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_m_param() {
+        let query = SubQuery::parse_m_param(
+            "sum:1m-avg-zero:rate{counter,,1000}:sys.cpu.user{host=web*}{dc=lga|lgb}",
+            0,
+            60_000,
+        )
+        .unwrap();
+        assert_eq!(Aggregator::Sum, query.aggregator);
+        assert_eq!(
+            Some(RateOptions {
+                counter: true,
+                reset_value: 1000.0,
+                ..Default::default()
+            }),
+            query.rate
+        );
+        assert_eq!(
+            MetricQuery {
+                metric: "sys.cpu.user".to_string(),
+                start_millis: 0,
+                end_millis: 60_000,
+                filters: vec![
+                    TagFilter {
+                        tagk: "host".to_string(),
+                        kind: TagFilterKind::Wildcard("web*".to_string()),
+                        group_by: true,
+                    },
+                    TagFilter {
+                        tagk: "dc".to_string(),
+                        kind: TagFilterKind::LiteralOr(vec!["lga".to_string(), "lgb".to_string()]),
+                        group_by: false,
+                    },
+                ],
+                downsample: Some(Downsample {
+                    interval_millis: 60_000,
+                    aggregator: Aggregator::Avg,
+                    fill: FillPolicy::Zero,
+                }),
+            },
+            query.query
+        );
+
+        let query = SubQuery::parse_m_param("max:cpu{host=regexp(web.*)}", 0, 0).unwrap();
+        assert_eq!(Aggregator::Max, query.aggregator);
+        assert_eq!(
+            TagFilterKind::Regexp("web.*".to_string()),
+            query.query.filters[0].kind
+        );
+
+        assert!(SubQuery::parse_m_param("cpu", 0, 0).is_err());
+        assert!(SubQuery::parse_m_param("foo:cpu", 0, 0).is_err());
+        assert!(SubQuery::parse_m_param("sum:1m-none:cpu", 0, 0).is_err());
+        assert!(SubQuery::parse_m_param("sum:cpu{host}", 0, 0).is_err());
+        assert!(SubQuery::parse_m_param("sum:rate{foo}:cpu", 0, 0).is_err());
+    }
+
+    #[test]
+    fn test_parse_time() {
+        let now = 1_700_000_000_000;
+        assert_eq!(now, parse_time("now", now).unwrap());
+        assert_eq!(now - 3_600_000, parse_time("1h-ago", now).unwrap());
+        assert_eq!(1_356_998_400_000, parse_time("1356998400", now).unwrap());
+        assert_eq!(1_356_998_400_500, parse_time("1356998400500", now).unwrap());
+        assert_eq!(
+            1_356_998_400_500,
+            parse_time("1356998400.500", now).unwrap()
+        );
+        assert_eq!(
+            1_356_998_400_000,
+            parse_time("2013/01/01-00:00:00", now).unwrap()
+        );
+        assert_eq!(1_356_998_400_000, parse_time("2013/01/01", now).unwrap());
+        assert!(parse_time("1x-ago", now).is_err());
+        assert!(parse_time("yesterday", now).is_err());
+    }
+
+    #[test]
+    fn test_build_results() {
+        let query = SubQuery::parse_m_param("sum:cpu{host=*}", 0, 10_000).unwrap();
+        let series = SeriesMap::from([
+            (
+                tags(&[("dc", "lga"), ("host", "web01")]),
+                vec![(1_000, Some(1.0)), (2_000, Some(2.0))],
+            ),
+            (
+                tags(&[("dc", "lga"), ("host", "web02")]),
+                vec![(1_000, Some(3.0))],
+            ),
+        ]);
+        let results = query.build_results(series.clone(), false);
+        assert_eq!(2, results.len());
+        assert_eq!(
+            BTreeMap::from([
+                ("dc".to_string(), "lga".to_string()),
+                ("host".to_string(), "web01".to_string()),
+            ]),
+            results[0].tags
+        );
+        assert!(results[0].aggregate_tags.is_empty());
+        assert_eq!(
+            BTreeMap::from([(1, Some(1.0)), (2, Some(2.0))]),
+            results[0].dps
+        );
+
+        let query = SubQuery::parse_m_param("sum:cpu", 0, 10_000).unwrap();
+        let results = query.build_results(series.clone(), true);
+        assert_eq!(1, results.len());
+        assert_eq!(
+            BTreeMap::from([("dc".to_string(), "lga".to_string())]),
+            results[0].tags
+        );
+        assert_eq!(vec!["host".to_string()], results[0].aggregate_tags);
+        assert_eq!(
+            BTreeMap::from([(1_000, Some(4.0)), (2_000, Some(2.0))]),
+            results[0].dps
+        );
+
+        let query = SubQuery::parse_m_param("sum:rate:cpu{host=web01}", 0, 10_000).unwrap();
+        let results = query.build_results(series.clone(), false);
+        assert_eq!(BTreeMap::from([(2, Some(1.0))]), results[0].dps);
+
+        let query = SubQuery::parse_m_param("sum:2s-sum-zero:cpu{host=web02}", 0, 5_000).unwrap();
+        let series = SeriesMap::from([(tags(&[("host", "web02")]), vec![(2_000, Some(3.0))])]);
+        let results = query.build_results(series, false);
+        assert_eq!(
+            BTreeMap::from([(0, Some(0.0)), (2, Some(3.0)), (4, Some(0.0))]),
+            results[0].dps
+        );
+    }
+
+    #[test]
+    fn test_to_rates() {
+        let points = vec![(0, Some(10.0)), (1_000, Some(20.0)), (2_000, Some(5.0))];
+        assert_eq!(
+            vec![(1_000, Some(10.0)), (2_000, Some(-15.0))],
+            to_rates(points.clone(), &RateOptions::default())
+        );
+
+        let options = RateOptions {
+            counter: true,
+            counter_max: 25.0,
+            ..Default::default()
+        };
+        assert_eq!(
+            vec![(1_000, Some(10.0)), (2_000, Some(10.0))],
+            to_rates(points.clone(), &options)
+        );
+
+        let options = RateOptions {
+            counter: true,
+            drop_resets: true,
+            ..Default::default()
+        };
+        assert_eq!(vec![(1_000, Some(10.0))], to_rates(points, &options));
+    }
+}
@@ -13,21 +13,31 @@
 // limitations under the License.
 
 use ahash::HashSet;
+use api::greptime_proto::io::prometheus::write::v2::histogram::{Count, ZeroCount};
+use api::greptime_proto::io::prometheus::write::v2::{
+    BucketSpan, Histogram as NativeHistogramPoint,
+};
 use api::v1::{RowInsertRequests, Value};
 use common_grpc::precision::Precision;
+use common_query::native_histogram::{
+    CounterResetHint, GAUGE_RESET_HINT, UNKNOWN_COUNTER_RESET_HINT,
+    exponential_overflow_bucket_index,
+};
 use common_query::prelude::{GREPTIME_COUNT, greptime_timestamp, greptime_value};
 use lazy_static::lazy_static;
 use otel_arrow_rust::proto::opentelemetry::collector::metrics::v1::ExportMetricsServiceRequest;
 use otel_arrow_rust::proto::opentelemetry::common::v1::{AnyValue, KeyValue, any_value};
 use otel_arrow_rust::proto::opentelemetry::metrics::v1::{metric, number_data_point, *};
 use session::protocol_ctx::{MetricType, OtlpMetricCtx};
+use snafu::{OptionExt, ensure};
 use table::requests::{
     METADATA_QUALITY_DECLARED, SEMANTIC_METRIC_METADATA_QUALITY, SEMANTIC_METRIC_ORIGINAL_NAME,
     SEMANTIC_METRIC_TEMPORALITY, SEMANTIC_METRIC_TYPE, SEMANTIC_METRIC_UNIT,
 };
 
-use crate::error::Result;
+use crate::error::{InvalidOtlpMetricSnafu, Result};
 use crate::otlp::trace::{KEY_SERVICE_INSTANCE_ID, KEY_SERVICE_NAME};
+use crate::prom_remote_write::v2::write_native_histogram_value;
 use crate::row_writer::{self, MultiTableData, TableData};
 pub use crate::semantic::SemanticIndex;
 use crate::semantic::{
@@ -159,8 +169,9 @@ fn emitted_semantic_tables(
             (format!("{base}{COUNT_TABLE_SUFFIX}"), METRIC_TYPE_COUNTER),
             (format!("{base}{SUM_TABLE_SUFFIX}"), METRIC_TYPE_COUNTER),
         ],
-        // ExponentialHistogram is a no-op today; Init never reaches encoding.
-        MetricType::ExponentialHistogram | MetricType::Init => vec![],
+        MetricType::ExponentialHistogram => vec![(base.to_string(), METRIC_TYPE_HISTOGRAM)],
+        // Init never reaches encoding.
+        MetricType::Init => vec![],
     }
 }
 
@@ -170,6 +181,7 @@ fn temporality_value(data: &metric::Data) -> Option<&'static str> {
     let raw = match data {
         metric::Data::Sum(sum) => sum.aggregation_temporality,
         metric::Data::Histogram(hist) => hist.aggregation_temporality,
+        metric::Data::ExponentialHistogram(hist) => hist.aggregation_temporality,
         _ => return None,
     };
     match AggregationTemporality::try_from(raw) {
@@ -369,9 +381,16 @@ fn encode_metrics(
                     metric_ctx,
                 )?;
             }
-            // TODO: Convert OTLP exponential histograms into the canonical native-histogram
-            // Struct. See docs/rfcs/2026-08-04-native-histograms.md.
-            metric::Data::ExponentialHistogram(_hist) => {}
+            metric::Data::ExponentialHistogram(hist) => {
+                encode_exponential_histogram(
+                    table_writer,
+                    &name,
+                    hist,
+                    resource_attrs,
+                    scope_attrs,
+                    metric_ctx,
+                )?;
+            }
         }
     }
 
@@ -710,6 +729,191 @@ fn encode_histogram(
     Ok(())
 }
 
+/// Highest native histogram schema, OTLP data points with a larger scale are
+/// downscaled to it by merging adjacent buckets.
+const MAX_NATIVE_HISTOGRAM_SCHEMA: i32 = 8;
+/// Lowest native histogram schema, OTLP data points with a smaller scale can't
+/// be represented and are rejected.
+const MIN_NATIVE_HISTOGRAM_SCHEMA: i32 = -4;
+
+/// Encode exponential histogram data as native histograms.
+///
+/// Each data point is stored as one row in the `%metric%` table, with the
+/// histogram persisted in the `greptime_native_histogram` struct field, so that
+/// PromQL native histogram functions like `histogram_quantile` and
+/// `histogram_count` can be used on it directly.
+///
+/// The conversion follows the Prometheus OTLP receiver:
+///
+/// - OTLP `scale` becomes the schema. Scales above 8 are downscaled, and scales
+///   below -4 are rejected.
+/// - OTLP bucket `i` covers `(base^i, base^(i+1)]` while Prometheus bucket `i`
+///   covers `(base^(i-1), base^i]`, so bucket indexes are shifted by one.
+/// - Delta temporality is stored as a gauge histogram, as delta points don't
+///   carry counter reset semantics.
+fn encode_exponential_histogram(
+    table_writer: &mut MultiTableData,
+    name: &str,
+    hist: &ExponentialHistogram,
+    resource_attrs: Option<&Vec<KeyValue>>,
+    scope_attrs: Option<&Vec<KeyValue>>,
+    metric_ctx: &OtlpMetricCtx,
+) -> Result<()> {
+    let reset_hint = if hist.aggregation_temporality == AggregationTemporality::Delta as i32 {
+        GAUGE_RESET_HINT
+    } else {
+        UNKNOWN_COUNTER_RESET_HINT
+    };
+
+    let table = table_writer.get_or_default_table_data(
+        name,
+        APPROXIMATE_COLUMN_COUNT,
+        hist.data_points.len(),
+    );
+
+    for data_point in &hist.data_points {
+        let histogram = exponential_data_point_to_native_histogram(name, data_point, reset_hint)?;
+
+        let mut row = table.alloc_one_row();
+        write_tags_and_timestamp(
+            table,
+            &mut row,
+            resource_attrs,
+            scope_attrs,
+            Some(data_point.attributes.as_ref()),
+            data_point.time_unix_nano as i64,
+            metric_ctx,
+        )?;
+        write_native_histogram_value(table, &histogram, &mut row)?;
+        table.add_row(row);
+    }
+
+    Ok(())
+}
+
+fn exponential_data_point_to_native_histogram(
+    name: &str,
+    data_point: &ExponentialHistogramDataPoint,
+    reset_hint: CounterResetHint,
+) -> Result<NativeHistogramPoint> {
+    ensure!(
+        data_point.scale >= MIN_NATIVE_HISTOGRAM_SCHEMA,
+        InvalidOtlpMetricSnafu {
+            msg: format!(
+                "exponential histogram `{name}` has scale {}, the minimum supported scale is {MIN_NATIVE_HISTOGRAM_SCHEMA}",
+                data_point.scale
+            ),
+        }
+    );
+    let schema = data_point.scale.min(MAX_NATIVE_HISTOGRAM_SCHEMA);
+    let scale_down = (data_point.scale - schema) as u32;
+
+    let (positive_spans, positive_deltas) =
+        exponential_buckets_to_native(name, data_point.positive.as_ref(), schema, scale_down)?;
+    let (negative_spans, negative_deltas) =
+        exponential_buckets_to_native(name, data_point.negative.as_ref(), schema, scale_down)?;
+
+    let bucket_count = data_point
+        .positive
+        .iter()
+        .chain(data_point.negative.iter())
+        .flat_map(|buckets| buckets.bucket_counts.iter())
+        .try_fold(data_point.zero_count, |total, count| {
+            total.checked_add(*count)
+        })
+        .with_context(|| InvalidOtlpMetricSnafu {
+            msg: format!("exponential histogram `{name}` bucket total overflows u64"),
+        })?;
+    let sum = if data_point.flags & DataPointFlags::NoRecordedValueMask as u32 != 0 {
+        f64::NAN
+    } else {
+        data_point.sum.unwrap_or_default()
+    };
+    ensure!(
+        if sum.is_nan() {
+            bucket_count <= data_point.count
+        } else {
+            bucket_count == data_point.count
+        },
+        InvalidOtlpMetricSnafu {
+            msg: format!(
+                "exponential histogram `{name}` has {bucket_count} observations in buckets, count is {}",
+                data_point.count
+            ),
+        }
+    );
+
+    Ok(NativeHistogramPoint {
+        count: Some(Count::CountInt(data_point.count)),
+        sum,
+        schema,
+        zero_threshold: data_point.zero_threshold,
+        zero_count: Some(ZeroCount::ZeroCountInt(data_point.zero_count)),
+        negative_spans,
+        negative_deltas,
+        positive_spans,
+        positive_deltas,
+        reset_hint: reset_hint.into(),
+        timestamp: (data_point.time_unix_nano / 1_000_000) as i64,
+        start_timestamp: (data_point.start_time_unix_nano / 1_000_000) as i64,
+        ..Default::default()
+    })
+}
+
+/// Converts dense OTLP buckets into a single native histogram span and its
+/// delta encoded bucket counts, merging `2^scale_down` adjacent buckets into one.
+fn exponential_buckets_to_native(
+    name: &str,
+    buckets: Option<&exponential_histogram_data_point::Buckets>,
+    schema: i32,
+    scale_down: u32,
+) -> Result<(Vec<BucketSpan>, Vec<i64>)> {
+    let Some(buckets) = buckets.filter(|buckets| !buckets.bucket_counts.is_empty()) else {
+        return Ok((vec![], vec![]));
+    };
+
+    // Shifting a negative index rounds towards negative infinity, which is what
+    // merging buckets requires.
+    let native_index = |otlp_index: i64| (otlp_index >> scale_down) + 1;
+    let first_index = native_index(buckets.offset as i64);
+    let last_index = native_index(buckets.offset as i64 + buckets.bucket_counts.len() as i64 - 1);
+    // `exponential_overflow_bucket_index` never fails for a schema in range.
+    let overflow_index = exponential_overflow_bucket_index(schema).unwrap_or(i32::MAX) as i64;
+    ensure!(
+        first_index > i32::MIN as i64 && last_index <= overflow_index,
+        InvalidOtlpMetricSnafu {
+            msg: format!("exponential histogram `{name}` bucket index is out of range"),
+        }
+    );
+
+    let mut counts = vec![0_i64; (last_index - first_index + 1) as usize];
+    for (i, count) in buckets.bucket_counts.iter().enumerate() {
+        let index = (native_index(buckets.offset as i64 + i as i64) - first_index) as usize;
+        counts[index] = i64::try_from(*count)
+            .ok()
+            .and_then(|count| counts[index].checked_add(count))
+            .with_context(|| InvalidOtlpMetricSnafu {
+                msg: format!("exponential histogram `{name}` bucket count overflows i64"),
+            })?;
+    }
+
+    let span = BucketSpan {
+        offset: first_index as i32,
+        length: counts.len() as u32,
+    };
+    let mut previous = 0;
+    let deltas = counts
+        .into_iter()
+        .map(|count| {
+            let delta = count - previous;
+            previous = count;
+            delta
+        })
+        .collect();
+
+    Ok((vec![span], deltas))
+}
+
 fn encode_summary(
     table_writer: &mut MultiTableData,
     name: &str,
@@ -843,9 +1047,10 @@ fn encode_summary(
 
 #[cfg(test)]
 mod tests {
-    use common_query::prelude::set_default_prefix;
+    use common_query::prelude::{greptime_native_histogram, set_default_prefix};
     use otel_arrow_rust::proto::opentelemetry::common::v1::AnyValue;
     use otel_arrow_rust::proto::opentelemetry::common::v1::any_value::Value as Val;
+    use otel_arrow_rust::proto::opentelemetry::metrics::v1::exponential_histogram_data_point::Buckets;
     use otel_arrow_rust::proto::opentelemetry::metrics::v1::number_data_point::Value;
     use otel_arrow_rust::proto::opentelemetry::metrics::v1::summary_data_point::ValueAtQuantile;
     use otel_arrow_rust::proto::opentelemetry::metrics::v1::{
@@ -1173,6 +1378,154 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_encode_exponential_histogram() {
+        let mut tables = MultiTableData::default();
+
+        let histogram = ExponentialHistogram {
+            data_points: vec![ExponentialHistogramDataPoint {
+                attributes: vec![keyvalue("host", "testserver")],
+                time_unix_nano: 100_000_000,
+                start_time_unix_nano: 23_000_000,
+                count: 10,
+                sum: Some(42.),
+                scale: 1,
+                zero_count: 1,
+                zero_threshold: 0.001,
+                positive: Some(Buckets {
+                    offset: -1,
+                    bucket_counts: vec![2, 0, 3],
+                }),
+                negative: Some(Buckets {
+                    offset: 2,
+                    bucket_counts: vec![4],
+                }),
+                ..Default::default()
+            }],
+            aggregation_temporality: AggregationTemporality::Cumulative.into(),
+        };
+        encode_exponential_histogram(
+            &mut tables,
+            "latency",
+            &histogram,
+            Some(&vec![]),
+            Some(&vec![keyvalue("scope", "otel")]),
+            &OtlpMetricCtx::default(),
+        )
+        .unwrap();
+
+        assert_eq!(1, tables.num_tables());
+        let table = tables.get_or_default_table_data("latency", 0, 0);
+        assert_eq!(table.num_rows(), 1);
+        assert_eq!(
+            table
+                .columns()
+                .iter()
+                .map(|c| &c.column_name)
+                .collect::<Vec<&String>>(),
+            vec![
+                "otel_scope_scope",
+                "host",
+                greptime_timestamp(),
+                greptime_native_histogram(),
+            ]
+        );
+
+        let histogram = exponential_data_point_to_native_histogram(
+            "latency",
+            &histogram.data_points[0],
+            UNKNOWN_COUNTER_RESET_HINT,
+        )
+        .unwrap();
+        assert_eq!(histogram.schema, 1);
+        assert_eq!(histogram.count, Some(Count::CountInt(10)));
+        assert_eq!(histogram.zero_count, Some(ZeroCount::ZeroCountInt(1)));
+        assert_eq!(histogram.zero_threshold, 0.001);
+        assert_eq!(histogram.sum, 42.);
+        assert_eq!(histogram.start_timestamp, 23);
+        // OTLP bucket indexes are shifted by one in the native histogram layout.
+        assert_eq!(
+            histogram.positive_spans,
+            vec![BucketSpan {
+                offset: 0,
+                length: 3
+            }]
+        );
+        assert_eq!(histogram.positive_deltas, vec![2, -2, 3]);
+        assert_eq!(
+            histogram.negative_spans,
+            vec![BucketSpan {
+                offset: 3,
+                length: 1
+            }]
+        );
+        assert_eq!(histogram.negative_deltas, vec![4]);
+    }
+
+    #[test]
+    fn test_exponential_histogram_downscales_large_scale() {
+        let data_point = ExponentialHistogramDataPoint {
+            count: 10,
+            sum: Some(10.),
+            scale: 10,
+            positive: Some(Buckets {
+                offset: -3,
+                bucket_counts: vec![1, 2, 3, 4],
+            }),
+            ..Default::default()
+        };
+
+        let histogram =
+            exponential_data_point_to_native_histogram("latency", &data_point, GAUGE_RESET_HINT)
+                .unwrap();
+        assert_eq!(histogram.schema, 8);
+        assert_eq!(histogram.reset_hint, i32::from(GAUGE_RESET_HINT));
+        // OTLP indexes -3 to -1 merge into bucket 0, index 0 becomes bucket 1.
+        assert_eq!(
+            histogram.positive_spans,
+            vec![BucketSpan {
+                offset: 0,
+                length: 2
+            }]
+        );
+        assert_eq!(histogram.positive_deltas, vec![6, -2]);
+    }
+
+    #[test]
+    fn test_exponential_histogram_rejects_invalid_points() {
+        let too_small_scale = ExponentialHistogramDataPoint {
+            scale: -5,
+            ..Default::default()
+        };
+        let err = exponential_data_point_to_native_histogram(
+            "latency",
+            &too_small_scale,
+            UNKNOWN_COUNTER_RESET_HINT,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("minimum supported scale is -4"));
+
+        let count_mismatch = ExponentialHistogramDataPoint {
+            count: 5,
+            sum: Some(1.),
+            positive: Some(Buckets {
+                offset: 0,
+                bucket_counts: vec![1, 1],
+            }),
+            ..Default::default()
+        };
+        let err = exponential_data_point_to_native_histogram(
+            "latency",
+            &count_mismatch,
+            UNKNOWN_COUNTER_RESET_HINT,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("has 2 observations in buckets, count is 5")
+        );
+    }
+
     use std::collections::BTreeMap;
 
     use table::requests::validate_semantic_option;
@@ -1331,6 +1684,36 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_record_exponential_histogram_single_table() {
+        let metric = Metric {
+            name: "request.duration".to_string(),
+            unit: "s".to_string(),
+            data: Some(metric::Data::ExponentialHistogram(ExponentialHistogram {
+                aggregation_temporality: AggregationTemporality::Delta as i32,
+                ..Default::default()
+            })),
+            ..Default::default()
+        };
+        let index = record(
+            &metric,
+            MetricType::ExponentialHistogram,
+            "request_duration",
+        );
+        let decoded = decode(&index);
+
+        assert_eq!(decoded.len(), 1);
+        let table = &decoded["request_duration"];
+        assert_eq!(
+            table.get(SEMANTIC_METRIC_TYPE).map(String::as_str),
+            Some("histogram")
+        );
+        assert_eq!(
+            table.get(SEMANTIC_METRIC_TEMPORALITY).map(String::as_str),
+            Some("delta")
+        );
+    }
+
     #[test]
     fn test_record_summary_fans_out() {
         let metric = Metric {
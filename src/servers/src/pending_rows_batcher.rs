@@ -40,6 +40,7 @@ use dashmap::DashMap;
 use dashmap::mapref::entry::Entry;
 use futures::StreamExt;
 use metric_engine::batch_modifier::{TagColumnInfo, modify_batch_sparse};
+use operator::insert::InsertListenerRef;
 use partition::manager::PartitionRuleManagerRef;
 use partition::partition::PartitionRuleRef;
 use session::context::QueryContextRef;
@@ -47,6 +48,7 @@ use smallvec::SmallVec;
 use snafu::{OptionExt, ResultExt, ensure};
 use store_api::storage::{RegionId, TableId};
 use table::metadata::{TableInfo, TableInfoRef};
+use table::table_reference::TableReference;
 use tokio::sync::{OwnedSemaphorePermit, Semaphore, broadcast, mpsc, oneshot};
 
 use crate::error;
@@ -349,6 +351,8 @@ pub struct PendingRowsBatcher {
     node_manager: NodeManagerRef,
     catalog_manager: CatalogManagerRef,
     flow_notification_tx: mpsc::Sender<FlowNotification>,
+    /// Listener notified after rows are flushed, like the [`Inserter`](operator::insert::Inserter)'s.
+    insert_listener: Option<InsertListenerRef>,
     flush_semaphore: Arc<Semaphore>,
     inflight_semaphore: Arc<Semaphore>,
     worker_channel_capacity: usize,
@@ -365,6 +369,7 @@ impl PendingRowsBatcher {
         node_manager: NodeManagerRef,
         catalog_manager: CatalogManagerRef,
         table_flownode_set_cache: TableFlownodeSetCacheRef,
+        insert_listener: Option<InsertListenerRef>,
         prom_store_with_metric_engine: bool,
         schema_alterer: PendingRowsSchemaAltererRef,
         flush_interval: Duration,
@@ -406,6 +411,7 @@ impl PendingRowsBatcher {
             node_manager,
             catalog_manager,
             flow_notification_tx,
+            insert_listener,
             prom_store_with_metric_engine,
             schema_alterer,
             flush_semaphore: Arc::new(Semaphore::new(max_concurrent_flushes)),
@@ -878,6 +884,7 @@ impl PendingRowsBatcher {
             self.node_manager.clone(),
             self.catalog_manager.clone(),
             self.flow_notification_tx.clone(),
+            self.insert_listener.clone(),
             self.flush_interval,
             worker_idle_timeout,
             self.max_batch_rows,
@@ -935,6 +942,7 @@ fn start_worker(
     node_manager: NodeManagerRef,
     catalog_manager: CatalogManagerRef,
     flow_notification_tx: mpsc::Sender<FlowNotification>,
+    insert_listener: Option<InsertListenerRef>,
     flush_interval: Duration,
     worker_idle_timeout: Duration,
     max_batch_rows: usize,
@@ -977,6 +985,7 @@ fn start_worker(
                                         node_manager.clone(),
                                         catalog_manager.clone(),
                                         flow_notification_tx.clone(),
+                                        insert_listener.clone(),
                                         flush_semaphore.clone(),
                                     ).await;
                             }
@@ -989,6 +998,7 @@ fn start_worker(
                                     node_manager.clone(),
                                     catalog_manager.clone(),
                                     flow_notification_tx.clone(),
+                                    insert_listener.clone(),
                                 ).await;
                             }
                             break;
@@ -1025,6 +1035,7 @@ fn start_worker(
                                 node_manager.clone(),
                                 catalog_manager.clone(),
                                 flow_notification_tx.clone(),
+                                insert_listener.clone(),
                                 flush_semaphore.clone(),
                             ).await;
                     }
@@ -1037,6 +1048,7 @@ fn start_worker(
                             node_manager.clone(),
                             catalog_manager.clone(),
                             flow_notification_tx.clone(),
+                            insert_listener.clone(),
                         ).await;
                     }
                     break;
@@ -1098,6 +1110,7 @@ async fn spawn_flush(
     node_manager: NodeManagerRef,
     catalog_manager: CatalogManagerRef,
     flow_notification_tx: mpsc::Sender<FlowNotification>,
+    insert_listener: Option<InsertListenerRef>,
     semaphore: Arc<Semaphore>,
 ) {
     match semaphore.acquire_owned().await {
@@ -1110,6 +1123,7 @@ async fn spawn_flush(
                     node_manager,
                     catalog_manager,
                     flow_notification_tx,
+                    insert_listener,
                 )
                 .await;
             });
@@ -1122,6 +1136,7 @@ async fn spawn_flush(
                 node_manager,
                 catalog_manager,
                 flow_notification_tx,
+                insert_listener,
             )
             .await;
         }
@@ -1314,6 +1329,7 @@ async fn flush_batch_with_managers(
     node_manager: NodeManagerRef,
     catalog_manager: CatalogManagerRef,
     flow_notification_tx: mpsc::Sender<FlowNotification>,
+    insert_listener: Option<InsertListenerRef>,
 ) {
     let partition_provider = PartitionManagerPhysicalFlushAdapter { partition_manager };
     let node_requester = NodeManagerPhysicalFlushAdapter {
@@ -1326,6 +1342,7 @@ async fn flush_batch_with_managers(
         &node_requester,
         &catalog_provider,
         flow_notification_tx,
+        insert_listener,
     )
     .await;
 }
@@ -1336,6 +1353,7 @@ async fn flush_batch(
     node_manager: &(impl PhysicalFlushNodeRequester + ?Sized),
     catalog_manager: &(impl PhysicalFlushCatalogProvider + ?Sized),
     flow_notification_tx: mpsc::Sender<FlowNotification>,
+    insert_listener: Option<InsertListenerRef>,
 ) {
     let FlushBatch {
         table_batches,
@@ -1378,6 +1396,10 @@ async fn flush_batch(
                 .with_label_values(&[db_string.as_str()])
                 .inc_by(affected_rows as u64);
 
+            // Invalidates caches before replying, so the writer reads its own writes.
+            if let Some(listener) = &insert_listener {
+                notify_inserted(listener, &table_batches, &ctx);
+            }
             notify_waiters(waiters, Ok(()));
             enqueue_flow_notifications(table_batches, &flow_notification_tx);
         }
@@ -1389,6 +1411,35 @@ async fn flush_batch(
     }
 }
 
+/// Notifies the insert listener of the minimal timestamp flushed to each table.
+fn notify_inserted(
+    listener: &InsertListenerRef,
+    table_batches: &[TableBatch],
+    ctx: &QueryContextRef,
+) {
+    let catalog = ctx.current_catalog();
+    let schema = ctx.current_schema();
+    for table_batch in table_batches {
+        // Aligned batches always have millisecond timestamps, see `columns_taxonomy`.
+        let min_timestamp = table_batch
+            .batches
+            .iter()
+            .filter_map(|batch| {
+                let timestamp_column = batch.batch.column(batch.timestamp_index);
+                let (timestamp_values, _) =
+                    datatypes::timestamp::timestamp_array_to_primitive(timestamp_column)?;
+                arrow::compute::min(&timestamp_values)
+            })
+            .min();
+        if let Some(min_timestamp) = min_timestamp {
+            listener.on_inserted(
+                TableReference::full(catalog, &schema, &table_batch.table_name),
+                min_timestamp,
+            );
+        }
+    }
+}
+
 fn extract_timestamps(table_batch: &TableBatch) -> Vec<i64> {
     let mut timestamps = Vec::with_capacity(table_batch.row_count);
     for batch in &table_batch.batches {
@@ -1900,12 +1951,14 @@ mod tests {
     use dashmap::DashMap;
     use datatypes::schema::{ColumnSchema as DtColumnSchema, Schema as DtSchema};
     use moka::future::CacheBuilder;
+    use operator::insert::{InsertListener, InsertListenerRef};
     use partition::error::Result as PartitionResult;
     use partition::partition::{PartitionRule, PartitionRuleRef, RegionMask};
     use smallvec::SmallVec;
     use snafu::ResultExt;
     use store_api::storage::RegionId;
     use table::metadata::TableId;
+    use table::table_reference::TableReference;
     use table::test_util::table_info::test_table_info;
     use tokio::sync::{Notify, Semaphore, mpsc, oneshot};
     use tokio::time::sleep;
@@ -2801,6 +2854,7 @@ mod tests {
                 table: Some(mock_physical_table_metadata(1024)),
             },
             flow_notification_tx,
+            None,
         )
         .await;
 
@@ -2862,6 +2916,7 @@ mod tests {
                 table: Some(mock_physical_table_metadata(1024)),
             },
             flow_notification_tx,
+            None,
         )
         .await;
 
@@ -2873,6 +2928,70 @@ mod tests {
         );
     }
 
+    struct RecordingInsertListener {
+        inserted: Mutex<Vec<(String, i64)>>,
+    }
+
+    impl InsertListener for RecordingInsertListener {
+        fn on_inserted(&self, table: TableReference, min_timestamp_millis: i64) {
+            self.inserted
+                .lock()
+                .unwrap()
+                .push((table.to_string(), min_timestamp_millis));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flush_batch_notifies_insert_listener_after_successful_physical_write() {
+        for fail in [false, true] {
+            let listener = Arc::new(RecordingInsertListener {
+                inserted: Mutex::new(Vec::new()),
+            });
+            let (flow_notification_tx, _flow_notification_rx) = mpsc::channel(8);
+            let ctx = session::context::QueryContext::arc();
+            let table_batches = vec![TableBatch {
+                table_name: "cpu".to_string(),
+                table_id: 42,
+                batches: vec![
+                    mock_aligned_tag_batch("tag1", "host-1", 2000, 1.0),
+                    mock_aligned_tag_batch("tag1", "host-2", 1000, 2.0),
+                ],
+                row_count: 2,
+            }];
+
+            flush_batch(
+                FlushBatch {
+                    table_batches,
+                    total_row_count: 2,
+                    db_string: ctx.get_db_string(),
+                    ctx,
+                    waiters: Vec::new(),
+                },
+                &MockFlushPartitionProvider {
+                    partition_rule_calls: Arc::new(AtomicUsize::new(0)),
+                    region_leader_calls: Arc::new(AtomicUsize::new(0)),
+                },
+                &MockFlushNodeRequester {
+                    writes: Arc::new(AtomicUsize::new(0)),
+                    fail,
+                },
+                &MockFlushCatalogProvider {
+                    table: Some(mock_physical_table_metadata(1024)),
+                },
+                flow_notification_tx,
+                Some(listener.clone() as InsertListenerRef),
+            )
+            .await;
+
+            let inserted = listener.inserted.lock().unwrap().clone();
+            if fail {
+                assert!(inserted.is_empty());
+            } else {
+                assert_eq!(vec![("greptime.public.cpu".to_string(), 1000)], inserted);
+            }
+        }
+    }
+
     #[async_trait]
     impl PhysicalFlushNodeRequester for ConcurrentMockNodeManager {
         async fn handle(
@@ -13,6 +13,7 @@
 // limitations under the License.
 
 mod auth_handler;
+mod copy;
 mod fixtures;
 mod handler;
 mod server;
@@ -33,13 +34,16 @@ use std::sync::Arc;
 use ::auth::UserProviderRef;
 use derive_builder::Builder;
 use pgwire::api::auth::{ServerParameterProvider, StartupHandler};
+use pgwire::api::copy::CopyHandler;
 use pgwire::api::query::{ExtendedQueryHandler, SimpleQueryHandler};
 use pgwire::api::{ClientInfo, ErrorHandler, PgWireServerHandlers};
 pub use server::PostgresServer;
 use session::Session;
 use session::context::Channel;
+use tokio::sync::Mutex;
 
 use self::auth_handler::PgLoginVerifier;
+use self::copy::CopyInState;
 use self::handler::DefaultQueryParser;
 use crate::query_handler::sql::ServerSqlQueryHandlerRef;
 
@@ -82,6 +86,8 @@ pub struct PostgresServerHandlerInner {
 
     session: Arc<Session>,
     query_parser: Arc<DefaultQueryParser>,
+    /// The in-progress `COPY ... FROM STDIN` of this connection, if any.
+    copy_in: Mutex<Option<CopyInState>>,
 }
 
 #[derive(Builder)]
@@ -108,6 +114,10 @@ impl PgWireServerHandlers for PostgresServerHandler {
         self.0.clone()
     }
 
+    fn copy_handler(&self) -> Arc<impl CopyHandler> {
+        self.0.clone()
+    }
+
     fn error_handler(&self) -> Arc<impl ErrorHandler> {
         self.0.clone()
     }
@@ -129,6 +139,7 @@ impl MakePostgresServerHandler {
 
             session: session.clone(),
             query_parser: Arc::new(DefaultQueryParser::new(self.query_handler.clone(), session)),
+            copy_in: Mutex::new(None),
         };
         PostgresServerHandler(Arc::new(handler))
     }
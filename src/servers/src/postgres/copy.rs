@@ -0,0 +1,962 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! `COPY ... FROM STDIN` support.
+//!
+//! The client streams `CopyData` messages after we answer with `CopyInResponse`.
+//! Rows are decoded incrementally in the text, CSV or binary format and handed
+//! to the table inserter in batches of at most [COPY_IN_BATCH_SIZE] rows.
+//!
+//! A copy is not atomic: the batches inserted before a failure stay in the
+//! table, and the error reports how many rows were inserted.
+
+use std::collections::HashMap;
+
+use arrow::compute::{CastOptions, cast_with_options};
+use arrow::datatypes::DataType;
+use arrow::util::display::FormatOptions;
+use bytes::{Buf, BytesMut};
+use datafusion::sql::sqlparser::ast::{
+    CopyLegacyCsvOption, CopyLegacyOption, CopyOption, CopySource, CopyTarget, Ident, ObjectName,
+    Statement as SqlParserStatement,
+};
+use datafusion_common::ScalarValue;
+use datatypes::vectors::Helper;
+use session::context::QueryContextRef;
+use snafu::{ResultExt, ensure};
+use table::requests::InsertRequest;
+
+use crate::error::{ArrowSnafu, DataFusionSnafu, InvalidCopyDataSnafu, Result};
+
+/// Max number of rows buffered before they are flushed to the table.
+pub(crate) const COPY_IN_BATCH_SIZE: usize = 4096;
+/// Max size of a single row, so an unterminated row can't buffer without bound.
+const MAX_COPY_ROW_SIZE: usize = 16 * 1024 * 1024;
+
+const BINARY_SIGNATURE: &[u8] = b"PGCOPY\n\xff\r\n\0";
+/// Microseconds between the unix epoch and the postgres epoch (2000-01-01).
+const PG_EPOCH_OFFSET_MICROS: i64 = 946_684_800_000_000;
+/// Days between the unix epoch and the postgres epoch (2000-01-01).
+const PG_EPOCH_OFFSET_DAYS: i32 = 10_957;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum CopyInFormat {
+    Text,
+    Csv,
+    Binary,
+}
+
+impl CopyInFormat {
+    /// The overall format code sent in `CopyInResponse`.
+    pub(crate) fn format_code(&self) -> i8 {
+        match self {
+            CopyInFormat::Binary => 1,
+            CopyInFormat::Text | CopyInFormat::Csv => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct CopyInOptions {
+    pub(crate) format: CopyInFormat,
+    pub(crate) delimiter: u8,
+    pub(crate) null: String,
+    pub(crate) header: bool,
+    pub(crate) quote: u8,
+    pub(crate) escape: Option<u8>,
+}
+
+impl Default for CopyInOptions {
+    fn default() -> Self {
+        Self {
+            format: CopyInFormat::Text,
+            delimiter: b'\t',
+            null: "\\N".to_string(),
+            header: false,
+            quote: b'"',
+            escape: None,
+        }
+    }
+}
+
+/// A parsed `COPY <table> [(columns)] FROM STDIN` statement.
+#[derive(Debug, Clone)]
+pub(crate) struct CopyFromStdin {
+    pub(crate) table_name: ObjectName,
+    pub(crate) columns: Vec<Ident>,
+    pub(crate) options: CopyInOptions,
+}
+
+/// Returns the copy target if the statement is a `COPY ... FROM STDIN`.
+pub(crate) fn check_copy_from_stdin(
+    statement: &SqlParserStatement,
+) -> Option<Result<CopyFromStdin>> {
+    let SqlParserStatement::Copy {
+        source: CopySource::Table {
+            table_name,
+            columns,
+        },
+        to: false,
+        target: CopyTarget::Stdin,
+        options,
+        legacy_options,
+        ..
+    } = statement
+    else {
+        return None;
+    };
+
+    Some(
+        parse_options(options, legacy_options).map(|options| CopyFromStdin {
+            table_name: table_name.clone(),
+            columns: columns.clone(),
+            options,
+        }),
+    )
+}
+
+fn parse_options(
+    options: &[CopyOption],
+    legacy_options: &[CopyLegacyOption],
+) -> Result<CopyInOptions> {
+    let mut result = CopyInOptions::default();
+    let mut delimiter = None;
+    let mut null = None;
+
+    for option in options {
+        match option {
+            CopyOption::Format(format) => {
+                result.format = match format.value.to_lowercase().as_str() {
+                    "text" => CopyInFormat::Text,
+                    "csv" => CopyInFormat::Csv,
+                    "binary" => CopyInFormat::Binary,
+                    other => {
+                        return InvalidCopyDataSnafu {
+                            reason: format!("unsupported COPY format: {other}"),
+                        }
+                        .fail();
+                    }
+                }
+            }
+            CopyOption::Delimiter(c) => delimiter = Some(single_byte(*c, "DELIMITER")?),
+            CopyOption::Null(s) => null = Some(s.clone()),
+            CopyOption::Header(header) => result.header = *header,
+            CopyOption::Quote(c) => result.quote = single_byte(*c, "QUOTE")?,
+            CopyOption::Escape(c) => result.escape = Some(single_byte(*c, "ESCAPE")?),
+            _ => {}
+        }
+    }
+
+    for option in legacy_options {
+        match option {
+            CopyLegacyOption::Binary => result.format = CopyInFormat::Binary,
+            CopyLegacyOption::Delimiter(c) => delimiter = Some(single_byte(*c, "DELIMITER")?),
+            CopyLegacyOption::Null(s) => null = Some(s.clone()),
+            CopyLegacyOption::Csv(csv_options) => {
+                result.format = CopyInFormat::Csv;
+                for csv_option in csv_options {
+                    match csv_option {
+                        CopyLegacyCsvOption::Header => result.header = true,
+                        CopyLegacyCsvOption::Quote(c) => result.quote = single_byte(*c, "QUOTE")?,
+                        CopyLegacyCsvOption::Escape(c) => {
+                            result.escape = Some(single_byte(*c, "ESCAPE")?)
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // CSV has different defaults than the text format.
+    if result.format == CopyInFormat::Csv {
+        result.delimiter = b',';
+        result.null = String::new();
+    }
+    if let Some(delimiter) = delimiter {
+        result.delimiter = delimiter;
+    }
+    if let Some(null) = null {
+        result.null = null;
+    }
+    ensure!(
+        result.format != CopyInFormat::Text || !result.header,
+        InvalidCopyDataSnafu {
+            reason: "HEADER is only supported in CSV mode",
+        }
+    );
+
+    Ok(result)
+}
+
+fn single_byte(c: char, option: &str) -> Result<u8> {
+    ensure!(
+        c.is_ascii(),
+        InvalidCopyDataSnafu {
+            reason: format!("COPY {option} must be a single one-byte character"),
+        }
+    );
+    Ok(c as u8)
+}
+
+/// State of an in-progress `COPY ... FROM STDIN`.
+pub(crate) struct CopyInState {
+    catalog: String,
+    schema: String,
+    table: String,
+    columns: Vec<(String, DataType)>,
+    options: CopyInOptions,
+    query_ctx: QueryContextRef,
+
+    /// Received bytes that do not form a complete row yet.
+    buffer: BytesMut,
+    /// Decoded cells of the pending rows, one vector per column.
+    values: Vec<Vec<ScalarValue>>,
+    pending_rows: usize,
+    /// Whether the CSV header or the binary file header has been consumed.
+    header_consumed: bool,
+    /// Whether the end-of-data marker has been seen.
+    finished: bool,
+    inserted_rows: usize,
+}
+
+impl CopyInState {
+    pub(crate) fn new(
+        (catalog, schema, table): (String, String, String),
+        columns: Vec<(String, DataType)>,
+        options: CopyInOptions,
+        query_ctx: QueryContextRef,
+    ) -> Self {
+        let header_consumed = !options.header && options.format != CopyInFormat::Binary;
+        let values = vec![Vec::new(); columns.len()];
+        Self {
+            catalog,
+            schema,
+            table,
+            columns,
+            options,
+            query_ctx,
+            buffer: BytesMut::new(),
+            values,
+            pending_rows: 0,
+            header_consumed,
+            finished: false,
+            inserted_rows: 0,
+        }
+    }
+
+    pub(crate) fn format(&self) -> CopyInFormat {
+        self.options.format
+    }
+
+    pub(crate) fn num_columns(&self) -> usize {
+        self.columns.len()
+    }
+
+    pub(crate) fn query_ctx(&self) -> &QueryContextRef {
+        &self.query_ctx
+    }
+
+    pub(crate) fn inserted_rows(&self) -> usize {
+        self.inserted_rows
+    }
+
+    pub(crate) fn add_inserted_rows(&mut self, rows: usize) {
+        self.inserted_rows += rows;
+    }
+
+    /// Appends a chunk of `CopyData` payload.
+    pub(crate) fn extend(&mut self, data: &[u8]) {
+        if !self.finished {
+            self.buffer.extend_from_slice(data);
+        }
+    }
+
+    /// Decodes buffered rows and returns an insert request once a full batch
+    /// is available. When `eof` is set, the trailing incomplete row (if any) is
+    /// decoded and the remaining rows are returned even if the batch is not full.
+    pub(crate) fn next_batch(&mut self, eof: bool) -> Result<Option<InsertRequest>> {
+        while self.pending_rows < COPY_IN_BATCH_SIZE && !self.finished {
+            let decoded = match self.options.format {
+                CopyInFormat::Text => self.decode_text_row(eof)?,
+                CopyInFormat::Csv => self.decode_csv_row(eof)?,
+                CopyInFormat::Binary => self.decode_binary_row()?,
+            };
+            if !decoded {
+                // What's left in the buffer is part of a single row.
+                ensure!(
+                    self.buffer.len() <= MAX_COPY_ROW_SIZE,
+                    InvalidCopyDataSnafu {
+                        reason: format!("COPY row exceeds {MAX_COPY_ROW_SIZE} bytes"),
+                    }
+                );
+                break;
+            }
+        }
+
+        if self.pending_rows >= COPY_IN_BATCH_SIZE || (eof && self.pending_rows > 0) {
+            return self.take_request().map(Some);
+        }
+        if eof {
+            ensure!(
+                self.buffer.is_empty() || self.finished,
+                InvalidCopyDataSnafu {
+                    reason: "unexpected end of COPY data",
+                }
+            );
+        }
+        Ok(None)
+    }
+
+    fn push_row(&mut self, row: Vec<ScalarValue>) {
+        for (column, value) in self.values.iter_mut().zip(row) {
+            column.push(value);
+        }
+        self.pending_rows += 1;
+    }
+
+    /// Splits the next line (without the line terminator) off the buffer.
+    fn next_line(&mut self, eof: bool) -> Option<BytesMut> {
+        match self.buffer.iter().position(|b| *b == b'\n') {
+            Some(pos) => {
+                let mut line = self.buffer.split_to(pos + 1);
+                line.truncate(pos);
+                if line.last() == Some(&b'\r') {
+                    line.truncate(pos - 1);
+                }
+                Some(line)
+            }
+            None if eof && !self.buffer.is_empty() => Some(self.buffer.split()),
+            None => None,
+        }
+    }
+
+    fn decode_text_row(&mut self, eof: bool) -> Result<bool> {
+        let Some(line) = self.next_line(eof) else {
+            return Ok(false);
+        };
+        if &line[..] == b"\\." {
+            self.finished = true;
+            self.buffer.clear();
+            return Ok(true);
+        }
+
+        let fields = split_text_line(&line, self.options.delimiter);
+        ensure_field_count(fields.len(), self.columns.len())?;
+        let row = fields
+            .into_iter()
+            .zip(self.columns.iter())
+            .map(|(field, (_, data_type))| {
+                if field == self.options.null.as_bytes() {
+                    Ok(null_text_cell(data_type))
+                } else {
+                    text_cell(&unescape_text(field), data_type)
+                }
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.push_row(row);
+        Ok(true)
+    }
+
+    fn decode_csv_row(&mut self, eof: bool) -> Result<bool> {
+        let Some(len) = csv_record_len(&self.buffer, self.options.quote, eof) else {
+            return Ok(false);
+        };
+        let mut record = self.buffer.split_to(len);
+        while matches!(record.last(), Some(b'\n' | b'\r')) {
+            record.truncate(record.len() - 1);
+        }
+        if &record[..] == b"\\." {
+            self.finished = true;
+            self.buffer.clear();
+            return Ok(true);
+        }
+        if !self.header_consumed {
+            self.header_consumed = true;
+            return Ok(true);
+        }
+
+        let fields = split_csv_record(&record, &self.options)?;
+        ensure_field_count(fields.len(), self.columns.len())?;
+        let row = fields
+            .into_iter()
+            .zip(self.columns.iter())
+            .map(|(field, (_, data_type))| match field {
+                Some(field) => text_cell(&field, data_type),
+                None => Ok(null_text_cell(data_type)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.push_row(row);
+        Ok(true)
+    }
+
+    fn decode_binary_row(&mut self) -> Result<bool> {
+        if !self.header_consumed {
+            // signature, flags and header extension length
+            let fixed_len = BINARY_SIGNATURE.len() + 8;
+            if self.buffer.len() < fixed_len {
+                return Ok(false);
+            }
+            ensure!(
+                self.buffer.starts_with(BINARY_SIGNATURE),
+                InvalidCopyDataSnafu {
+                    reason: "COPY file signature not recognized",
+                }
+            );
+            let ext_len =
+                i32::from_be_bytes(self.buffer[fixed_len - 4..fixed_len].try_into().unwrap());
+            ensure!(
+                ext_len >= 0,
+                InvalidCopyDataSnafu {
+                    reason: "invalid COPY file header",
+                }
+            );
+            if self.buffer.len() < fixed_len + ext_len as usize {
+                return Ok(false);
+            }
+            self.buffer.advance(fixed_len + ext_len as usize);
+            self.header_consumed = true;
+        }
+
+        let Some((tuple_len, fields)) = binary_tuple(&self.buffer)? else {
+            return Ok(false);
+        };
+        let Some(fields) = fields else {
+            // file trailer
+            self.finished = true;
+            self.buffer.clear();
+            return Ok(true);
+        };
+
+        ensure_field_count(fields.len(), self.columns.len())?;
+        let row = fields
+            .into_iter()
+            .zip(self.columns.iter())
+            .map(|(field, (_, data_type))| match field {
+                Some(field) => binary_cell(field, data_type),
+                None => ScalarValue::try_from(data_type).context(DataFusionSnafu),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        self.buffer.advance(tuple_len);
+        self.push_row(row);
+        Ok(true)
+    }
+
+    fn take_request(&mut self) -> Result<InsertRequest> {
+        let mut columns_values = HashMap::with_capacity(self.columns.len());
+        for ((name, data_type), values) in self.columns.iter().zip(self.values.iter_mut()) {
+            let values = std::mem::take(values);
+            let array = ScalarValue::iter_to_array(values).context(DataFusionSnafu)?;
+            let array = if array.data_type() == data_type {
+                array
+            } else {
+                let options = CastOptions {
+                    safe: false,
+                    format_options: FormatOptions::default(),
+                };
+                cast_with_options(&array, data_type, &options).context(ArrowSnafu)?
+            };
+            columns_values.insert(name.clone(), Helper::try_into_vector(array)?);
+        }
+        self.pending_rows = 0;
+
+        Ok(InsertRequest {
+            catalog_name: self.catalog.clone(),
+            schema_name: self.schema.clone(),
+            table_name: self.table.clone(),
+            columns_values,
+        })
+    }
+}
+
+fn ensure_field_count(actual: usize, expected: usize) -> Result<()> {
+    ensure!(
+        actual == expected,
+        InvalidCopyDataSnafu {
+            reason: format!("expected {expected} columns, got {actual}"),
+        }
+    );
+    Ok(())
+}
+
+fn null_text_cell(data_type: &DataType) -> ScalarValue {
+    if matches!(data_type, DataType::Binary | DataType::LargeBinary) {
+        ScalarValue::Binary(None)
+    } else {
+        ScalarValue::Utf8(None)
+    }
+}
+
+/// Text and CSV cells are kept as strings and cast to the column type when the
+/// batch is flushed, except binary columns which are written as `\x` hex.
+fn text_cell(field: &[u8], data_type: &DataType) -> Result<ScalarValue> {
+    if matches!(data_type, DataType::Binary | DataType::LargeBinary) {
+        let bytes = match field.strip_prefix(b"\\x") {
+            Some(hex_str) => hex::decode(hex_str).map_err(|e| {
+                InvalidCopyDataSnafu {
+                    reason: format!("invalid bytea value: {e}"),
+                }
+                .build()
+            })?,
+            None => field.to_vec(),
+        };
+        return Ok(ScalarValue::Binary(Some(bytes)));
+    }
+
+    let value = String::from_utf8(field.to_vec()).map_err(|e| {
+        InvalidCopyDataSnafu {
+            reason: format!("invalid UTF-8 in COPY data: {e}"),
+        }
+        .build()
+    })?;
+    Ok(ScalarValue::Utf8(Some(value)))
+}
+
+/// Decodes a field of the binary format. Integers and floats are decoded by
+/// their length and then cast to the column type.
+fn binary_cell(field: &[u8], data_type: &DataType) -> Result<ScalarValue> {
+    let invalid = || {
+        InvalidCopyDataSnafu {
+            reason: format!(
+                "invalid binary field of {} bytes for type {data_type}",
+                field.len()
+            ),
+        }
+        .build()
+    };
+
+    let value = match data_type {
+        DataType::Utf8 | DataType::LargeUtf8 | DataType::Utf8View => ScalarValue::Utf8(Some(
+            String::from_utf8(field.to_vec()).map_err(|_| invalid())?,
+        )),
+        DataType::Binary | DataType::LargeBinary => ScalarValue::Binary(Some(field.to_vec())),
+        DataType::Boolean => match field {
+            [b] => ScalarValue::Boolean(Some(*b != 0)),
+            _ => return Err(invalid()),
+        },
+        DataType::Float32 | DataType::Float64 => match field.len() {
+            4 => ScalarValue::Float32(Some(f32::from_be_bytes(field.try_into().unwrap()))),
+            8 => ScalarValue::Float64(Some(f64::from_be_bytes(field.try_into().unwrap()))),
+            _ => return Err(invalid()),
+        },
+        DataType::Timestamp(_, _) => {
+            let micros = i64::from_be_bytes(field.try_into().map_err(|_| invalid())?);
+            ScalarValue::TimestampMicrosecond(Some(micros + PG_EPOCH_OFFSET_MICROS), None)
+        }
+        DataType::Date32 => {
+            let days = i32::from_be_bytes(field.try_into().map_err(|_| invalid())?);
+            ScalarValue::Date32(Some(days + PG_EPOCH_OFFSET_DAYS))
+        }
+        data_type if data_type.is_integer() => {
+            let value = match field.len() {
+                1 => field[0] as i8 as i64,
+                2 => i16::from_be_bytes(field.try_into().unwrap()) as i64,
+                4 => i32::from_be_bytes(field.try_into().unwrap()) as i64,
+                8 => i64::from_be_bytes(field.try_into().unwrap()),
+                _ => return Err(invalid()),
+            };
+            ScalarValue::Int64(Some(value))
+        }
+        _ => {
+            return InvalidCopyDataSnafu {
+                reason: format!("binary COPY is not supported for type {data_type}"),
+            }
+            .fail();
+        }
+    };
+
+    // Keep every cell of a column in the column type, so that the batch is
+    // built without another cast.
+    if value.data_type() == *data_type {
+        Ok(value)
+    } else {
+        value.cast_to(data_type).context(DataFusionSnafu)
+    }
+}
+
+/// Splits a text format line into raw (still escaped) fields.
+fn split_text_line(line: &[u8], delimiter: u8) -> Vec<&[u8]> {
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+    while i < line.len() {
+        if line[i] == b'\\' {
+            i += 2;
+            continue;
+        }
+        if line[i] == delimiter {
+            fields.push(&line[start..i]);
+            start = i + 1;
+        }
+        i += 1;
+    }
+    fields.push(&line[start.min(line.len())..]);
+    fields
+}
+
+/// Resolves the backslash escapes of the text format.
+fn unescape_text(field: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(field.len());
+    let mut i = 0;
+    while i < field.len() {
+        if field[i] != b'\\' || i + 1 == field.len() {
+            result.push(field[i]);
+            i += 1;
+            continue;
+        }
+
+        i += 1;
+        match field[i] {
+            b'b' => result.push(0x08),
+            b'f' => result.push(0x0c),
+            b'n' => result.push(b'\n'),
+            b'r' => result.push(b'\r'),
+            b't' => result.push(b'\t'),
+            b'v' => result.push(0x0b),
+            b'0'..=b'7' => {
+                let end = (i + 3).min(field.len());
+                let digits = field[i..end]
+                    .iter()
+                    .take_while(|b| (b'0'..=b'7').contains(*b))
+                    .count();
+                let value = field[i..i + digits]
+                    .iter()
+                    .fold(0u32, |acc, b| acc * 8 + (b - b'0') as u32);
+                result.push(value as u8);
+                i += digits;
+                continue;
+            }
+            b'x' if field.get(i + 1).is_some_and(u8::is_ascii_hexdigit) => {
+                let end = (i + 3).min(field.len());
+                let digits = field[i + 1..end]
+                    .iter()
+                    .take_while(|b| b.is_ascii_hexdigit())
+                    .count();
+                let hex_str = std::str::from_utf8(&field[i + 1..i + 1 + digits]).unwrap();
+                result.push(u8::from_str_radix(hex_str, 16).unwrap());
+                i += 1 + digits;
+                continue;
+            }
+            other => result.push(other),
+        }
+        i += 1;
+    }
+    result
+}
+
+/// Returns the length of the next complete CSV record, including its line
+/// terminator. Newlines inside quoted fields do not end a record.
+fn csv_record_len(buffer: &[u8], quote: u8, eof: bool) -> Option<usize> {
+    let mut in_quotes = false;
+    for (i, b) in buffer.iter().enumerate() {
+        if *b == quote {
+            in_quotes = !in_quotes;
+        } else if *b == b'\n' && !in_quotes {
+            return Some(i + 1);
+        }
+    }
+    (eof && !buffer.is_empty()).then_some(buffer.len())
+}
+
+/// Splits a CSV record into fields. Unquoted fields equal to the null string
+/// are `None`; quoted fields are never null.
+fn split_csv_record(record: &[u8], options: &CopyInOptions) -> Result<Vec<Option<Vec<u8>>>> {
+    let quote = options.quote;
+    let escape = options.escape.unwrap_or(quote);
+    let mut fields = Vec::new();
+    let mut field = Vec::new();
+    let mut quoted = false;
+    let mut in_quotes = false;
+    let mut i = 0;
+
+    while i < record.len() {
+        let b = record[i];
+        if in_quotes {
+            if b == escape && record.get(i + 1) == Some(&quote) {
+                field.push(quote);
+                i += 2;
+                continue;
+            }
+            if b == quote {
+                in_quotes = false;
+            } else {
+                field.push(b);
+            }
+        } else if b == quote {
+            in_quotes = true;
+            quoted = true;
+        } else if b == options.delimiter {
+            fields.push(finish_csv_field(&mut field, quoted, options));
+            quoted = false;
+        } else {
+            field.push(b);
+        }
+        i += 1;
+    }
+    ensure!(
+        !in_quotes,
+        InvalidCopyDataSnafu {
+            reason: "unterminated CSV quoted field",
+        }
+    );
+    fields.push(finish_csv_field(&mut field, quoted, options));
+    Ok(fields)
+}
+
+fn finish_csv_field(field: &mut Vec<u8>, quoted: bool, options: &CopyInOptions) -> Option<Vec<u8>> {
+    let field = std::mem::take(field);
+    if !quoted && field == options.null.as_bytes() {
+        None
+    } else {
+        Some(field)
+    }
+}
+
+type BinaryTuple<'a> = (usize, Option<Vec<Option<&'a [u8]>>>);
+
+/// Reads the next binary tuple from the buffer. Returns `None` if the tuple is
+/// incomplete, and a `None` field list for the file trailer.
+fn binary_tuple(buffer: &[u8]) -> Result<Option<BinaryTuple<'_>>> {
+    if buffer.len() < 2 {
+        return Ok(None);
+    }
+    let num_fields = i16::from_be_bytes([buffer[0], buffer[1]]);
+    if num_fields == -1 {
+        return Ok(Some((2, None)));
+    }
+    ensure!(
+        num_fields >= 0,
+        InvalidCopyDataSnafu {
+            reason: format!("invalid field count {num_fields}"),
+        }
+    );
+
+    let mut offset = 2;
+    let mut fields = Vec::with_capacity(num_fields as usize);
+    for _ in 0..num_fields {
+        if buffer.len() < offset + 4 {
+            return Ok(None);
+        }
+        let len = i32::from_be_bytes(buffer[offset..offset + 4].try_into().unwrap());
+        offset += 4;
+        if len < 0 {
+            fields.push(None);
+            continue;
+        }
+        let len = len as usize;
+        if buffer.len() < offset + len {
+            return Ok(None);
+        }
+        fields.push(Some(&buffer[offset..offset + len]));
+        offset += len;
+    }
+    Ok(Some((offset, Some(fields))))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::datatypes::TimeUnit;
+    use datafusion::sql::sqlparser::dialect::PostgreSqlDialect;
+    use datafusion::sql::sqlparser::parser::Parser;
+    use datatypes::value::Value;
+    use session::context::QueryContext;
+
+    use super::*;
+
+    fn parse(sql: &str) -> CopyFromStdin {
+        let statements = Parser::parse_sql(&PostgreSqlDialect {}, sql).unwrap();
+        check_copy_from_stdin(&statements[0]).unwrap().unwrap()
+    }
+
+    fn new_state(options: CopyInOptions) -> CopyInState {
+        CopyInState::new(
+            (
+                "greptime".to_string(),
+                "public".to_string(),
+                "t".to_string(),
+            ),
+            vec![
+                ("host".to_string(), DataType::Utf8),
+                ("val".to_string(), DataType::Float64),
+                (
+                    "ts".to_string(),
+                    DataType::Timestamp(TimeUnit::Millisecond, None),
+                ),
+            ],
+            options,
+            Arc::new(QueryContext::with("greptime", "public")),
+        )
+    }
+
+    fn column(request: &InsertRequest, name: &str) -> Vec<Value> {
+        let vector = &request.columns_values[name];
+        (0..vector.len()).map(|i| vector.get(i)).collect()
+    }
+
+    #[test]
+    fn test_check_copy_from_stdin() {
+        let copy = parse("COPY t (host, val) FROM STDIN");
+        assert_eq!(copy.table_name.to_string(), "t");
+        assert_eq!(copy.columns.len(), 2);
+        assert_eq!(copy.options, CopyInOptions::default());
+
+        let copy = parse("COPY t FROM STDIN WITH (FORMAT csv, HEADER true, DELIMITER ';')");
+        assert_eq!(copy.options.format, CopyInFormat::Csv);
+        assert_eq!(copy.options.delimiter, b';');
+        assert_eq!(copy.options.null, "");
+        assert!(copy.options.header);
+
+        let copy = parse("COPY t FROM STDIN BINARY");
+        assert_eq!(copy.options.format, CopyInFormat::Binary);
+
+        let statements = Parser::parse_sql(&PostgreSqlDialect {}, "COPY t TO STDOUT").unwrap();
+        assert!(check_copy_from_stdin(&statements[0]).is_none());
+    }
+
+    #[test]
+    fn test_copy_in_text() {
+        let mut state = new_state(CopyInOptions::default());
+        state.extend(b"host\\ta\t1.5\t2024-01-01 00:00:00\nhost_b\t\\N\t");
+        assert!(state.next_batch(false).unwrap().is_none());
+        state.extend(b"2024-01-01 00:00:01\n\\.\n");
+        let request = state.next_batch(true).unwrap().unwrap();
+
+        assert_eq!(
+            column(&request, "host"),
+            vec![Value::from("host\ta"), Value::from("host_b")]
+        );
+        assert_eq!(
+            column(&request, "val"),
+            vec![Value::from(1.5f64), Value::Null]
+        );
+        assert_eq!(
+            column(&request, "ts"),
+            vec![
+                Value::Timestamp(common_time::Timestamp::new_millisecond(1_704_067_200_000)),
+                Value::Timestamp(common_time::Timestamp::new_millisecond(1_704_067_201_000)),
+            ]
+        );
+        assert!(state.next_batch(true).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_copy_in_text_column_mismatch() {
+        let mut state = new_state(CopyInOptions::default());
+        state.extend(b"a\t1\n");
+        assert!(state.next_batch(true).is_err());
+    }
+
+    #[test]
+    fn test_copy_in_csv() {
+        let options = parse("COPY t FROM STDIN WITH (FORMAT csv, HEADER true)").options;
+        let mut state = new_state(options);
+        state.extend(b"host,val,ts\n\"a,\"\"b\"\"\n\",,2024-01-01T00:00:00Z\n");
+        state.extend(b"\"\",2,2024-01-01T00:00:01Z");
+        let request = state.next_batch(true).unwrap().unwrap();
+
+        assert_eq!(
+            column(&request, "host"),
+            vec![Value::from("a,\"b\"\n"), Value::from("")]
+        );
+        assert_eq!(
+            column(&request, "val"),
+            vec![Value::Null, Value::from(2.0f64)]
+        );
+    }
+
+    #[test]
+    fn test_copy_in_binary() {
+        let mut data = BINARY_SIGNATURE.to_vec();
+        data.extend(0i32.to_be_bytes());
+        data.extend(0i32.to_be_bytes());
+        // one tuple: 'a', 1.5, 2000-01-01 00:00:01
+        data.extend(3i16.to_be_bytes());
+        data.extend(1i32.to_be_bytes());
+        data.extend(b"a");
+        data.extend(8i32.to_be_bytes());
+        data.extend(1.5f64.to_be_bytes());
+        data.extend(8i32.to_be_bytes());
+        data.extend(1_000_000i64.to_be_bytes());
+        // one tuple with nulls
+        data.extend(3i16.to_be_bytes());
+        data.extend(1i32.to_be_bytes());
+        data.extend(b"b");
+        data.extend((-1i32).to_be_bytes());
+        data.extend(8i32.to_be_bytes());
+        data.extend(0i64.to_be_bytes());
+        data.extend((-1i16).to_be_bytes());
+
+        let options = parse("COPY t FROM STDIN WITH (FORMAT binary)").options;
+        let mut state = new_state(options);
+        // feed the data in small chunks to exercise partial tuples
+        for chunk in data.chunks(5) {
+            state.extend(chunk);
+            assert!(state.next_batch(false).unwrap().is_none());
+        }
+        let request = state.next_batch(true).unwrap().unwrap();
+
+        assert_eq!(
+            column(&request, "host"),
+            vec![Value::from("a"), Value::from("b")]
+        );
+        assert_eq!(
+            column(&request, "val"),
+            vec![Value::from(1.5f64), Value::Null]
+        );
+        assert_eq!(
+            column(&request, "ts"),
+            vec![
+                Value::Timestamp(common_time::Timestamp::new_millisecond(946_684_801_000)),
+                Value::Timestamp(common_time::Timestamp::new_millisecond(946_684_800_000)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_copy_in_batches() {
+        let mut state = new_state(CopyInOptions::default());
+        let line = b"a\t1\t2024-01-01 00:00:00\n";
+        for _ in 0..COPY_IN_BATCH_SIZE + 1 {
+            state.extend(line);
+        }
+        let request = state.next_batch(false).unwrap().unwrap();
+        assert_eq!(request.columns_values["host"].len(), COPY_IN_BATCH_SIZE);
+        assert!(state.next_batch(false).unwrap().is_none());
+        let request = state.next_batch(true).unwrap().unwrap();
+        assert_eq!(request.columns_values["host"].len(), 1);
+    }
+
+    #[test]
+    fn test_copy_in_row_too_large() {
+        let mut state = new_state(CopyInOptions::default());
+        state.extend(b"a\t1\t2024-01-01 00:00:00\n");
+        state.extend(&vec![b'a'; MAX_COPY_ROW_SIZE]);
+        assert!(state.next_batch(false).unwrap().is_none());
+        state.extend(b"a");
+        assert!(matches!(
+            state.next_batch(false),
+            Err(crate::error::Error::InvalidCopyData { .. })
+        ));
+    }
+
+    #[test]
+    fn test_unescape_text() {
+        assert_eq!(unescape_text(b"a\\tb\\\\c"), b"a\tb\\c");
+        assert_eq!(unescape_text(b"\\101\\x42"), b"AB");
+        assert_eq!(unescape_text(b"\\N"), b"N");
+    }
+}
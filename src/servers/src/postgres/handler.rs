@@ -17,10 +17,11 @@ use std::pin::Pin;
 use std::sync::Arc;
 
 use async_trait::async_trait;
+use common_error::ext::ErrorExt;
 use common_query::{Output, OutputData};
 use common_recordbatch::RecordBatch;
 use common_recordbatch::error::Result as RecordBatchResult;
-use common_telemetry::{debug, info, tracing};
+use common_telemetry::{debug, error, info, tracing};
 use datafusion::sql::sqlparser::ast::{CopyOption, CopyTarget, Statement as SqlParserStatement};
 use datafusion_common::ParamValues;
 use datafusion_expr::LogicalPlan;
@@ -28,6 +29,7 @@ use datafusion_pg_catalog::sql::PostgresCompatibilityParser;
 use datatypes::prelude::ConcreteDataType;
 use datatypes::schema::{Schema, SchemaRef};
 use futures::{Sink, SinkExt, Stream, StreamExt, future, stream};
+use pgwire::api::copy::CopyHandler;
 use pgwire::api::portal::{Format, Portal};
 use pgwire::api::query::{ExtendedQueryHandler, SimpleQueryHandler};
 use pgwire::api::results::{
@@ -38,19 +40,23 @@ use pgwire::api::stmt::{QueryParser, StoredStatement};
 use pgwire::api::{ClientInfo, ErrorHandler, Type};
 use pgwire::error::{ErrorInfo, PgWireError, PgWireResult};
 use pgwire::messages::PgWireBackendMessage;
-use pgwire::messages::copy::CopyData;
+use pgwire::messages::copy::{CopyData, CopyDone, CopyFail};
 use pgwire::messages::data::DataRow;
 use query::planner::DfLogicalPlanner;
 use query::query_engine::DescribeResult;
 use session::Session;
 use session::context::QueryContextRef;
-use snafu::ResultExt;
+use session::table_name::table_idents_to_full_name;
+use snafu::{OptionExt, ResultExt};
 use sql::dialect::PostgreSqlDialect;
 use sql::parser::{ParseOptions, ParserContext};
 use sql::statements::statement::Statement;
 
 use crate::SqlPlan;
-use crate::error::{DataFusionSnafu, InferParameterTypesSnafu, Result};
+use crate::error::{
+    DataFusionSnafu, InferParameterTypesSnafu, InvalidCopyDataSnafu, Result, TableNotFoundSnafu,
+};
+use crate::postgres::copy::{CopyFromStdin, CopyInState, check_copy_from_stdin};
 use crate::postgres::types::*;
 use crate::postgres::utils::convert_err;
 use crate::postgres::{PostgresServerHandlerInner, fixtures};
@@ -88,6 +94,16 @@ impl SimpleQueryHandler for PostgresServerHandlerInner {
             query.to_string()
         };
 
+        if let Ok(statements) = &parsed_query
+            && let [statement] = &statements[..]
+            && let Some(copy) = check_copy_from_stdin(statement)
+        {
+            let copy = copy.map_err(convert_err)?;
+            let resp = self.start_copy_in(copy, query_ctx.clone()).await?;
+            send_warning_opt(client, query_ctx).await?;
+            return Ok(vec![resp]);
+        }
+
         if let Some(resps) = fixtures::process(&query, query_ctx.clone()) {
             send_warning_opt(client, query_ctx).await?;
             Ok(resps)
@@ -303,6 +319,7 @@ impl DefaultQueryParser {
 pub struct PgSqlPlan {
     pub(crate) plan: SqlPlan,
     pub(crate) copy_to_stdout_format: Option<String>,
+    pub(crate) copy_from_stdin: Option<CopyFromStdin>,
 }
 
 #[async_trait]
@@ -323,6 +340,7 @@ impl QueryParser for DefaultQueryParser {
             return Ok(PgSqlPlan {
                 plan: SqlPlan::Empty,
                 copy_to_stdout_format: None,
+                copy_from_stdin: None,
             });
         }
 
@@ -330,12 +348,21 @@ impl QueryParser for DefaultQueryParser {
             return Ok(PgSqlPlan {
                 plan: SqlPlan::Shortcut(sql.to_string()),
                 copy_to_stdout_format: None,
+                copy_from_stdin: None,
             });
         }
 
         let parsed_statements = self.compatibility_parser.parse(sql);
         let (sql, copy_to_stdout_format) = if let Ok(mut statements) = parsed_statements {
             let first_stmt = statements.remove(0);
+            // The data of a `COPY ... FROM STDIN` is received once the portal is executed.
+            if let Some(copy) = check_copy_from_stdin(&first_stmt) {
+                return Ok(PgSqlPlan {
+                    plan: SqlPlan::Empty,
+                    copy_to_stdout_format: None,
+                    copy_from_stdin: Some(copy.map_err(convert_err)?),
+                });
+            }
             let format = check_copy_to_stdout(&first_stmt);
             (first_stmt.to_string(), format)
         } else {
@@ -367,11 +394,13 @@ impl QueryParser for DefaultQueryParser {
                 Ok(PgSqlPlan {
                     plan: SqlPlan::Plan(logical_plan, stmt),
                     copy_to_stdout_format,
+                    copy_from_stdin: None,
                 })
             } else {
                 Ok(PgSqlPlan {
                     plan: SqlPlan::Statement(stmt, sql),
                     copy_to_stdout_format,
+                    copy_from_stdin: None,
                 })
             }
         }
@@ -427,6 +456,12 @@ impl ExtendedQueryHandler for PostgresServerHandlerInner {
         let pg_sql_plan = &portal.statement.statement;
         let sql_plan = &pg_sql_plan.plan;
 
+        if let Some(copy) = &pg_sql_plan.copy_from_stdin {
+            let resp = self.start_copy_in(copy.clone(), query_ctx.clone()).await?;
+            send_warning_opt(client, query_ctx).await?;
+            return Ok(resp);
+        }
+
         let output = match sql_plan {
             SqlPlan::Empty => {
                 // early return if query is empty
@@ -698,6 +733,159 @@ impl ErrorHandler for PostgresServerHandlerInner {
     }
 }
 
+impl PostgresServerHandlerInner {
+    /// Resolves the target columns of a `COPY ... FROM STDIN` and keeps the copy
+    /// state until the client finishes sending data.
+    async fn start_copy_in(
+        &self,
+        copy: CopyFromStdin,
+        query_ctx: QueryContextRef,
+    ) -> PgWireResult<Response> {
+        let (catalog, schema, table_name) =
+            table_idents_to_full_name(&copy.table_name, &query_ctx).map_err(convert_err)?;
+        let table = self
+            .query_handler
+            .insert_target_table(&catalog, &schema, &table_name, query_ctx.clone())
+            .await
+            .and_then(|table| {
+                table.with_context(|| TableNotFoundSnafu {
+                    catalog: &catalog,
+                    schema: &schema,
+                    table: &table_name,
+                })
+            })
+            .map_err(convert_err)?;
+
+        let table_schema = table.schema();
+        let columns = if copy.columns.is_empty() {
+            table_schema
+                .column_schemas()
+                .iter()
+                .map(|column| (column.name.clone(), column.data_type.as_arrow_type()))
+                .collect::<Vec<_>>()
+        } else {
+            copy.columns
+                .iter()
+                .map(|ident| {
+                    table_schema
+                        .column_schema_by_name(&ident.value)
+                        .map(|column| (column.name.clone(), column.data_type.as_arrow_type()))
+                        .with_context(|| InvalidCopyDataSnafu {
+                            reason: format!(
+                                "column \"{}\" of table {} does not exist",
+                                ident.value, copy.table_name
+                            ),
+                        })
+                })
+                .collect::<Result<Vec<_>>>()
+                .map_err(convert_err)?
+        };
+
+        let table = (catalog, schema, table_name);
+        let state = CopyInState::new(table, columns, copy.options, query_ctx);
+        let resp = Response::CopyIn(CopyResponse::new(
+            state.format().format_code(),
+            state.num_columns(),
+            stream::empty::<PgWireResult<CopyData>>(),
+        ));
+        *self.copy_in.lock().await = Some(state);
+        Ok(resp)
+    }
+
+    /// Inserts every complete batch of the copy. When `eof` is set, the
+    /// remaining rows are inserted as well.
+    ///
+    /// Batches inserted before a failure are not rolled back, so the error
+    /// tells the client how many rows were inserted.
+    async fn flush_copy_in(&self, state: &mut CopyInState, eof: bool) -> PgWireResult<()> {
+        let result = self.try_flush_copy_in(state, eof).await;
+        result.map_err(|e| {
+            let inserted_rows = state.inserted_rows();
+            if inserted_rows == 0 {
+                return convert_err(e);
+            }
+
+            let status_code = e.status_code();
+            if status_code.should_log_error() {
+                error!(e; "Failed to handle postgres COPY, code: {}", status_code);
+            }
+            PgWireError::UserError(Box::new(PgErrorCode::from(status_code).to_err_info(
+                format!(
+                    "{}, {inserted_rows} rows were inserted before the failure",
+                    e.output_msg()
+                ),
+            )))
+        })
+    }
+
+    async fn try_flush_copy_in(&self, state: &mut CopyInState, eof: bool) -> Result<()> {
+        while let Some(request) = state.next_batch(eof)? {
+            let output = self
+                .query_handler
+                .do_table_insert(request, state.query_ctx().clone())
+                .await?;
+            if let OutputData::AffectedRows(rows) = output.data {
+                state.add_inserted_rows(rows);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CopyHandler for PostgresServerHandlerInner {
+    async fn on_copy_data<C>(&self, _client: &mut C, copy_data: CopyData) -> PgWireResult<()>
+    where
+        C: ClientInfo + Sink<PgWireBackendMessage> + Unpin + Send + Sync,
+        C::Error: Debug,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        let mut copy_in = self.copy_in.lock().await;
+        // The copy has already failed, discard the rest of the data.
+        let Some(state) = copy_in.as_mut() else {
+            return Ok(());
+        };
+
+        state.extend(&copy_data.data);
+        let result = self.flush_copy_in(state, false).await;
+        if result.is_err() {
+            *copy_in = None;
+        }
+        result
+    }
+
+    async fn on_copy_done<C>(&self, client: &mut C, _done: CopyDone) -> PgWireResult<()>
+    where
+        C: ClientInfo + Sink<PgWireBackendMessage> + Unpin + Send + Sync,
+        C::Error: Debug,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        let Some(mut state) = self.copy_in.lock().await.take() else {
+            return Ok(());
+        };
+
+        self.flush_copy_in(&mut state, true).await?;
+        client
+            .send(PgWireBackendMessage::CommandComplete(
+                Tag::new("COPY").with_rows(state.inserted_rows()).into(),
+            ))
+            .await?;
+        Ok(())
+    }
+
+    async fn on_copy_fail<C>(&self, _client: &mut C, fail: CopyFail) -> PgWireError
+    where
+        C: ClientInfo + Sink<PgWireBackendMessage> + Unpin + Send + Sync,
+        C::Error: Debug,
+        PgWireError: From<<C as Sink<PgWireBackendMessage>>::Error>,
+    {
+        self.copy_in.lock().await.take();
+        PgWireError::UserError(Box::new(
+            PgErrorCode::Ec57000.to_err_info(format!("COPY from stdin failed: {}", fail.message)),
+        ))
+    }
+}
+
 fn check_copy_to_stdout(statement: &SqlParserStatement) -> Option<String> {
     if let SqlParserStatement::Copy {
         target, options, ..
@@ -617,7 +617,7 @@ fn write_native_histogram<'a>(
     Ok(())
 }
 
-fn write_native_histogram_value(
+pub(crate) fn write_native_histogram_value(
     table_data: &mut TableData,
     histogram: &Histogram,
     row: &mut Vec<Value>,
@@ -41,6 +41,8 @@ use opentelemetry_proto::tonic::collector::logs::v1::ExportLogsServiceRequest;
 use opentelemetry_proto::tonic::collector::trace::v1::ExportTraceServiceRequest;
 use otel_arrow_rust::proto::opentelemetry::collector::metrics::v1::ExportMetricsServiceRequest;
 use pipeline::{GreptimePipelineParams, Pipeline, PipelineInfo, PipelineVersion, PipelineWay};
+use query::influxql::SeriesLayout;
+use query::influxql::ast::Statement;
 use serde_json::Value;
 use session::context::{QueryContext, QueryContextRef};
 
@@ -54,13 +56,18 @@ use crate::error::Result;
 use crate::http::jaeger::QueryTraceParams;
 use crate::influxdb::InfluxdbRequest;
 use crate::opentsdb::codec::DataPoint;
+use crate::opentsdb::query::{MetricQuery, SuggestKind, TagFilter};
+use crate::tempo::traceql::{AttributeScope, Field, FieldExpr};
 pub type OpentsdbProtocolHandlerRef = Arc<dyn OpentsdbProtocolHandler + Send + Sync>;
+pub type OpentsdbQueryHandlerRef = Arc<dyn OpentsdbQueryHandler + Send + Sync>;
 pub type InfluxdbLineProtocolHandlerRef = Arc<dyn InfluxdbLineProtocolHandler + Send + Sync>;
+pub type InfluxqlQueryHandlerRef = Arc<dyn InfluxqlQueryHandler + Send + Sync>;
 pub type PromStoreProtocolHandlerRef = Arc<dyn PromStoreProtocolHandler + Send + Sync>;
 pub type OpenTelemetryProtocolHandlerRef = Arc<dyn OpenTelemetryProtocolHandler + Send + Sync>;
 pub type PipelineHandlerRef = Arc<dyn PipelineHandler + Send + Sync>;
 pub type LogQueryHandlerRef = Arc<dyn LogQueryHandler + Send + Sync>;
 pub type JaegerQueryHandlerRef = Arc<dyn JaegerQueryHandler + Send + Sync>;
+pub type TempoQueryHandlerRef = Arc<dyn TempoQueryHandler + Send + Sync>;
 
 #[derive(Debug, Default, Clone)]
 pub struct TraceIngestOutcome {
@@ -77,6 +84,19 @@ pub trait InfluxdbLineProtocolHandler {
     async fn exec(&self, request: InfluxdbRequest, ctx: QueryContextRef) -> Result<Output>;
 }
 
+/// The output of an InfluxQL statement and how to split it into series.
+pub struct InfluxqlOutput {
+    pub layout: SeriesLayout,
+    pub output: Output,
+}
+
+/// Handle InfluxQL query requests of the InfluxDB v1 `/query` API.
+#[async_trait]
+pub trait InfluxqlQueryHandler {
+    /// Execute a parsed InfluxQL statement.
+    async fn query(&self, stmt: Statement, ctx: QueryContextRef) -> Result<InfluxqlOutput>;
+}
+
 #[async_trait]
 pub trait OpentsdbProtocolHandler {
     /// Checks all points in one external request before per-point debug execution.
@@ -87,6 +107,33 @@ pub trait OpentsdbProtocolHandler {
     async fn exec(&self, data_points: Vec<DataPoint>, ctx: QueryContextRef) -> Result<usize>;
 }
 
+/// Handle the OpenTSDB HTTP query APIs.
+#[async_trait]
+pub trait OpentsdbQueryHandler {
+    /// Reads the points of the series matched by the query. The output has the tag columns, the
+    /// timestamp column in milliseconds and the value column in `f64`.
+    async fn query(&self, query: &MetricQuery, ctx: QueryContextRef) -> Result<Output>;
+
+    /// Suggests at most `max` metric names, tag keys or tag values starting with the prefix.
+    async fn suggest(
+        &self,
+        kind: SuggestKind,
+        prefix: &str,
+        max: usize,
+        ctx: QueryContextRef,
+    ) -> Result<Vec<String>>;
+
+    /// Returns at most `limit` distinct tag sets of the series of the metric matched by the
+    /// filters.
+    async fn lookup(
+        &self,
+        metric: &str,
+        filters: &[TagFilter],
+        limit: usize,
+        ctx: QueryContextRef,
+    ) -> Result<Output>;
+}
+
 pub struct PromStoreResponse {
     pub content_type: HeaderValue,
     pub content_encoding: HeaderValue,
@@ -278,3 +325,58 @@ pub trait JaegerQueryHandler {
         query_params: QueryTraceParams,
     ) -> Result<Output>;
 }
+
+/// Handle Grafana Tempo query requests.
+///
+/// Time ranges are in nanoseconds and only the v1 trace table schema is supported.
+#[async_trait]
+pub trait TempoQueryHandler {
+    /// Finds the ids of at most `limit` traces that have spans matching the `filter`, most recent
+    /// first, skipping the first `offset` traces.
+    async fn find_trace_ids(
+        &self,
+        ctx: QueryContextRef,
+        filter: Option<&FieldExpr>,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+        offset: usize,
+        limit: usize,
+    ) -> Result<Vec<String>>;
+
+    /// Finds the spans of the traces that match the `filter`, with the
+    /// [`SPAN_SET_COLUMNS`](crate::tempo::SPAN_SET_COLUMNS).
+    async fn find_spans(
+        &self,
+        ctx: QueryContextRef,
+        trace_ids: &[String],
+        filter: Option<&FieldExpr>,
+    ) -> Result<Output>;
+
+    /// Retrieves all columns of the spans of a trace. It's used for `/api/traces/{trace_id}` API.
+    async fn get_trace(
+        &self,
+        ctx: QueryContextRef,
+        trace_id: &str,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+    ) -> Result<Output>;
+
+    /// Lists the attribute names of the `scope`, or of all scopes if it's `None`. It's used for
+    /// `/api/search/tags` API.
+    async fn tag_names(
+        &self,
+        ctx: QueryContextRef,
+        scope: Option<AttributeScope>,
+    ) -> Result<Vec<String>>;
+
+    /// Lists at most `limit` distinct values of a field. It's used for
+    /// `/api/search/tag/{tag}/values` API.
+    async fn tag_values(
+        &self,
+        ctx: QueryContextRef,
+        field: &Field,
+        start_time: Option<i64>,
+        end_time: Option<i64>,
+        limit: usize,
+    ) -> Result<Vec<String>>;
+}
@@ -21,8 +21,10 @@ use query::parser::PromQuery;
 use query::query_engine::DescribeResult;
 use session::context::QueryContextRef;
 use sql::statements::statement::Statement;
+use table::TableRef;
+use table::requests::InsertRequest;
 
-use crate::error::Result;
+use crate::error::{NotSupportedSnafu, Result};
 
 pub type ServerSqlQueryHandlerRef = Arc<dyn SqlQueryHandler + Send + Sync>;
 
@@ -65,4 +67,32 @@ pub trait SqlQueryHandler {
     ) -> Result<Option<DescribeResult>>;
 
     async fn is_valid_schema(&self, catalog: &str, schema: &str) -> Result<bool>;
+
+    /// Inserts rows that were decoded by the protocol layer (e.g. Postgres
+    /// `COPY ... FROM STDIN`) directly into a table, without going through SQL.
+    async fn do_table_insert(
+        &self,
+        _request: InsertRequest,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Output> {
+        NotSupportedSnafu {
+            feat: "table insert",
+        }
+        .fail()
+    }
+
+    /// Returns the table [`SqlQueryHandler::do_table_insert`] writes into, if it
+    /// exists and the user is allowed to insert into it.
+    async fn insert_target_table(
+        &self,
+        _catalog: &str,
+        _schema: &str,
+        _table: &str,
+        _query_ctx: QueryContextRef,
+    ) -> Result<Option<TableRef>> {
+        NotSupportedSnafu {
+            feat: "table insert",
+        }
+        .fail()
+    }
 }
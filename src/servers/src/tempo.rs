@@ -0,0 +1,146 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+//! Grafana Tempo compatible trace query API, see [`crate::http::tempo`].
+
+pub mod traceql;
+
+use std::collections::{HashMap, HashSet};
+
+use crate::otlp::trace::{
+    DURATION_NANO_COLUMN, PARENT_SPAN_ID_COLUMN, SERVICE_NAME_COLUMN, SPAN_ID_COLUMN,
+    SPAN_NAME_COLUMN, TIMESTAMP_COLUMN, TRACE_ID_COLUMN,
+};
+
+/// The prefix of the flattened span attribute columns in the v1 trace table.
+pub const SPAN_ATTRIBUTE_COLUMN_PREFIX: &str = "span_attributes.";
+/// The prefix of the flattened resource attribute columns in the v1 trace table.
+pub const RESOURCE_ATTRIBUTE_COLUMN_PREFIX: &str = "resource_attributes.";
+
+/// The columns of the spans returned by
+/// [`TempoQueryHandler::find_spans`](crate::query_handler::TempoQueryHandler::find_spans).
+pub const SPAN_SET_COLUMNS: [&str; 7] = [
+    TRACE_ID_COLUMN,
+    SPAN_ID_COLUMN,
+    PARENT_SPAN_ID_COLUMN,
+    SPAN_NAME_COLUMN,
+    SERVICE_NAME_COLUMN,
+    TIMESTAMP_COLUMN,
+    DURATION_NANO_COLUMN,
+];
+
+/// A span of a trace with the [`SPAN_SET_COLUMNS`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SpanRow {
+    pub trace_id: String,
+    pub span_id: String,
+    pub parent_span_id: Option<String>,
+    pub name: String,
+    pub service_name: String,
+    pub start_time_unix_nano: u64,
+    pub duration_nano: u64,
+}
+
+/// Evaluates spansets chained by `>>` within a trace.
+///
+/// `spans` are all spans of the trace and `matched[k]` are the ids of the spans matching the
+/// `k`-th spanset. Returns the ids of the spans matching the last spanset that have an ancestor
+/// in the result of the spansets before it.
+pub fn evaluate_descendants(spans: &[SpanRow], matched: &[HashSet<String>]) -> HashSet<String> {
+    let parents: HashMap<&str, &str> = spans
+        .iter()
+        .filter_map(|span| {
+            span.parent_span_id
+                .as_deref()
+                .map(|parent| (span.span_id.as_str(), parent))
+        })
+        .collect();
+
+    let Some((first, rest)) = matched.split_first() else {
+        return HashSet::new();
+    };
+    let mut result = first.clone();
+    for spanset in rest {
+        if result.is_empty() {
+            break;
+        }
+        result = spanset
+            .iter()
+            .filter(|span_id| {
+                let mut current = span_id.as_str();
+                // Bound the walk in case the parent links of a broken trace form a cycle.
+                for _ in 0..parents.len() {
+                    let Some(parent) = parents.get(current) else {
+                        return false;
+                    };
+                    if result.contains(*parent) {
+                        return true;
+                    }
+                    current = *parent;
+                }
+                false
+            })
+            .cloned()
+            .collect();
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn span(span_id: &str, parent_span_id: Option<&str>) -> SpanRow {
+        SpanRow {
+            trace_id: "t1".to_string(),
+            span_id: span_id.to_string(),
+            parent_span_id: parent_span_id.map(|s| s.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn ids(ids: &[&str]) -> HashSet<String> {
+        ids.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_evaluate_descendants() {
+        // a -> b -> c -> d, a -> e
+        let spans = vec![
+            span("a", None),
+            span("b", Some("a")),
+            span("c", Some("b")),
+            span("d", Some("c")),
+            span("e", Some("a")),
+        ];
+
+        assert_eq!(ids(&["b"]), evaluate_descendants(&spans, &[ids(&["b"])]));
+        assert_eq!(
+            ids(&["d", "e"]),
+            evaluate_descendants(&spans, &[ids(&["a"]), ids(&["d", "e"])])
+        );
+        assert_eq!(
+            ids(&["d"]),
+            evaluate_descendants(&spans, &[ids(&["b"]), ids(&["d", "e"])])
+        );
+        assert_eq!(
+            ids(&["d"]),
+            evaluate_descendants(&spans, &[ids(&["a"]), ids(&["c"]), ids(&["a", "d"])])
+        );
+        assert!(evaluate_descendants(&spans, &[ids(&["d"]), ids(&["a", "b"])]).is_empty());
+
+        // A cycle must not hang.
+        let spans = vec![span("x", Some("y")), span("y", Some("x"))];
+        assert!(evaluate_descendants(&spans, &[ids(&["z"]), ids(&["x"])]).is_empty());
+    }
+}
@@ -0,0 +1,626 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A subset of [TraceQL].
+//!
+//! Supported are spansets of span and resource attribute predicates and the `name`, `status`,
+//! `statusMessage`, `kind` and `duration` intrinsics, combined with `&&` and `||`, and spansets
+//! chained by the `>>` descendant operator, like
+//! `{ resource.service.name = "api" } >> { span.http.status_code >= 500 && duration > 1s }`.
+//!
+//! [TraceQL]: https://grafana.com/docs/tempo/latest/traceql/
+
+use std::iter::Peekable;
+use std::str::CharIndices;
+
+use snafu::ensure;
+
+use crate::error::{InvalidTempoQuerySnafu, Result};
+
+/// Spansets chained by `>>`. Results are the spans of the last spanset that are descendants of
+/// the results of the spansets before it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceQuery {
+    pub spansets: Vec<Spanset>,
+}
+
+/// A spanset like `{ name = "GET" }`. `{}` matches all spans.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spanset {
+    pub filter: Option<FieldExpr>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FieldExpr {
+    And(Box<FieldExpr>, Box<FieldExpr>),
+    Or(Box<FieldExpr>, Box<FieldExpr>),
+    Compare {
+        field: Field,
+        op: CompareOp,
+        value: Static,
+    },
+}
+
+impl FieldExpr {
+    pub fn and(self, other: FieldExpr) -> FieldExpr {
+        FieldExpr::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn or(self, other: FieldExpr) -> FieldExpr {
+        FieldExpr::Or(Box::new(self), Box::new(other))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AttributeScope {
+    Span,
+    Resource,
+    /// `.name` matches either span or resource attributes.
+    Any,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Field {
+    Name,
+    Status,
+    StatusMessage,
+    Kind,
+    Duration,
+    Attribute { scope: AttributeScope, name: String },
+}
+
+impl Field {
+    /// Parses a field like `name`, `span.http.method` or `.http.method`.
+    pub fn parse(field: &str) -> Result<Field> {
+        let mut parser = Parser::new(field);
+        let parsed = parser.parse_field()?;
+        parser.expect_end()?;
+        Ok(parsed)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompareOp {
+    Eq,
+    NotEq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    Regex,
+    NotRegex,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    Ok,
+    Error,
+    Unset,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Unspecified,
+    Internal,
+    Server,
+    Client,
+    Producer,
+    Consumer,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Static {
+    String(String),
+    Int(i64),
+    Float(f64),
+    Bool(bool),
+    /// A duration in nanoseconds.
+    Duration(i64),
+    Status(Status),
+    Kind(Kind),
+}
+
+impl Status {
+    /// The status code stored in the trace table.
+    pub fn as_column_value(&self) -> &'static str {
+        match self {
+            Status::Ok => "STATUS_CODE_OK",
+            Status::Error => "STATUS_CODE_ERROR",
+            Status::Unset => "STATUS_CODE_UNSET",
+        }
+    }
+}
+
+impl Kind {
+    /// The span kind stored in the trace table.
+    pub fn as_column_value(&self) -> &'static str {
+        match self {
+            Kind::Unspecified => "SPAN_KIND_UNSPECIFIED",
+            Kind::Internal => "SPAN_KIND_INTERNAL",
+            Kind::Server => "SPAN_KIND_SERVER",
+            Kind::Client => "SPAN_KIND_CLIENT",
+            Kind::Producer => "SPAN_KIND_PRODUCER",
+            Kind::Consumer => "SPAN_KIND_CONSUMER",
+        }
+    }
+}
+
+/// Parses a TraceQL query.
+pub fn parse(query: &str) -> Result<TraceQuery> {
+    let mut parser = Parser::new(query);
+    let mut spansets = vec![parser.parse_spanset()?];
+    loop {
+        parser.skip_whitespace();
+        if parser.eat(">>") {
+            spansets.push(parser.parse_spanset()?);
+        } else {
+            break;
+        }
+    }
+    parser.expect_end()?;
+    Ok(TraceQuery { spansets })
+}
+
+/// Parses a duration like `100ms` or `1.5s` into nanoseconds.
+pub fn parse_duration(duration: &str) -> Result<i64> {
+    let mut parser = Parser::new(duration);
+    let value = parser.parse_static()?;
+    parser.expect_end()?;
+    match value {
+        Static::Duration(nanos) => Ok(nanos),
+        _ => InvalidTempoQuerySnafu {
+            reason: format!("invalid duration {duration:?}"),
+        }
+        .fail(),
+    }
+}
+
+/// Max depth of nested parentheses in a spanset, which bounds the recursion of the parser.
+const MAX_NESTING_DEPTH: usize = 64;
+
+struct Parser<'a> {
+    input: &'a str,
+    chars: Peekable<CharIndices<'a>>,
+    /// Depth of the parentheses being parsed.
+    depth: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Self {
+            input,
+            chars: input.char_indices().peekable(),
+            depth: 0,
+        }
+    }
+
+    fn pos(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map(|(pos, _)| *pos)
+            .unwrap_or(self.input.len())
+    }
+
+    fn rest(&mut self) -> &'a str {
+        let pos = self.pos();
+        &self.input[pos..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.chars.next_if(|(_, c)| c.is_whitespace()).is_some() {}
+    }
+
+    /// Consumes `token` if the remaining input starts with it.
+    fn eat(&mut self, token: &str) -> bool {
+        if self.rest().starts_with(token) {
+            for _ in token.chars() {
+                self.chars.next();
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        self.skip_whitespace();
+        ensure!(self.eat(token), self.error(&format!("expected {token}")));
+        Ok(())
+    }
+
+    fn expect_end(&mut self) -> Result<()> {
+        self.skip_whitespace();
+        ensure!(
+            self.chars.peek().is_none(),
+            self.error("unexpected trailing input")
+        );
+        Ok(())
+    }
+
+    fn error(&mut self, reason: &str) -> InvalidTempoQuerySnafu<String> {
+        let pos = self.pos();
+        InvalidTempoQuerySnafu {
+            reason: format!("{reason} at position {pos} of TraceQL: {}", self.input),
+        }
+    }
+
+    fn take_while(&mut self, f: impl Fn(char) -> bool) -> &'a str {
+        let start = self.pos();
+        while self.chars.next_if(|(_, c)| f(*c)).is_some() {}
+        let end = self.pos();
+        &self.input[start..end]
+    }
+
+    fn parse_spanset(&mut self) -> Result<Spanset> {
+        self.expect("{")?;
+        self.skip_whitespace();
+        if self.eat("}") {
+            return Ok(Spanset { filter: None });
+        }
+        let filter = self.parse_or()?;
+        self.expect("}")?;
+        Ok(Spanset {
+            filter: Some(filter),
+        })
+    }
+
+    fn parse_or(&mut self) -> Result<FieldExpr> {
+        let mut expr = self.parse_and()?;
+        loop {
+            self.skip_whitespace();
+            if self.eat("||") {
+                expr = expr.or(self.parse_and()?);
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn parse_and(&mut self) -> Result<FieldExpr> {
+        let mut expr = self.parse_primary()?;
+        loop {
+            self.skip_whitespace();
+            if self.eat("&&") {
+                expr = expr.and(self.parse_primary()?);
+            } else {
+                return Ok(expr);
+            }
+        }
+    }
+
+    fn parse_primary(&mut self) -> Result<FieldExpr> {
+        self.skip_whitespace();
+        if self.eat("(") {
+            self.depth += 1;
+            ensure!(
+                self.depth <= MAX_NESTING_DEPTH,
+                self.error(&format!(
+                    "parentheses nested deeper than {MAX_NESTING_DEPTH}"
+                ))
+            );
+            let expr = self.parse_or()?;
+            self.expect(")")?;
+            self.depth -= 1;
+            return Ok(expr);
+        }
+
+        let field = self.parse_field()?;
+        let op = self.parse_op()?;
+        let value = self.parse_static()?;
+        validate_comparison(&field, op, &value)?;
+        Ok(FieldExpr::Compare { field, op, value })
+    }
+
+    fn parse_field(&mut self) -> Result<Field> {
+        self.skip_whitespace();
+        let scope = if self.eat("span.") {
+            AttributeScope::Span
+        } else if self.eat("resource.") {
+            AttributeScope::Resource
+        } else if self.eat(".") {
+            AttributeScope::Any
+        } else {
+            let intrinsic = self.take_while(|c| c.is_ascii_alphanumeric() || c == ':');
+            return match intrinsic {
+                "name" | "span:name" => Ok(Field::Name),
+                "status" | "span:status" => Ok(Field::Status),
+                "statusMessage" | "span:statusMessage" => Ok(Field::StatusMessage),
+                "kind" | "span:kind" => Ok(Field::Kind),
+                "duration" | "span:duration" => Ok(Field::Duration),
+                _ => self
+                    .error(&format!("unsupported intrinsic {intrinsic:?}"))
+                    .fail(),
+            };
+        };
+
+        let name = if self.rest().starts_with('"') {
+            self.parse_string()?
+        } else {
+            self.take_while(|c| c.is_ascii_alphanumeric() || "._-/:".contains(c))
+                .to_string()
+        };
+        ensure!(!name.is_empty(), self.error("expected attribute name"));
+        Ok(Field::Attribute { scope, name })
+    }
+
+    fn parse_op(&mut self) -> Result<CompareOp> {
+        self.skip_whitespace();
+        // Longer operators first.
+        for (token, op) in [
+            ("!=", CompareOp::NotEq),
+            ("!~", CompareOp::NotRegex),
+            ("=~", CompareOp::Regex),
+            (">=", CompareOp::Gte),
+            ("<=", CompareOp::Lte),
+            ("=", CompareOp::Eq),
+            (">", CompareOp::Gt),
+            ("<", CompareOp::Lt),
+        ] {
+            if self.eat(token) {
+                return Ok(op);
+            }
+        }
+        self.error("expected comparison operator").fail()
+    }
+
+    fn parse_string(&mut self) -> Result<String> {
+        ensure!(self.eat("\""), self.error("expected string"));
+        let mut value = String::new();
+        loop {
+            match self.chars.next() {
+                Some((_, '"')) => return Ok(value),
+                Some((_, '\\')) => match self.chars.next() {
+                    Some((_, 'n')) => value.push('\n'),
+                    Some((_, 't')) => value.push('\t'),
+                    Some((_, c)) => value.push(c),
+                    None => break,
+                },
+                Some((_, c)) => value.push(c),
+                None => break,
+            }
+        }
+        self.error("unterminated string").fail()
+    }
+
+    fn parse_static(&mut self) -> Result<Static> {
+        self.skip_whitespace();
+        if self.rest().starts_with('"') {
+            return self.parse_string().map(Static::String);
+        }
+
+        let negative = self.eat("-");
+        let token = self.take_while(|c| c.is_ascii_alphanumeric() || c == '.' || c == 'µ');
+        if token.starts_with(|c: char| c.is_ascii_digit()) {
+            let split = token
+                .find(|c: char| !c.is_ascii_digit() && c != '.')
+                .unwrap_or(token.len());
+            let (number, unit) = token.split_at(split);
+            let sign = if negative { -1.0 } else { 1.0 };
+            if !unit.is_empty() {
+                let unit_nanos = match unit {
+                    "ns" => 1.0,
+                    "us" | "µs" => 1e3,
+                    "ms" => 1e6,
+                    "s" => 1e9,
+                    "m" => 60e9,
+                    "h" => 3600e9,
+                    _ => return self.error(&format!("invalid duration {token}")).fail(),
+                };
+                let Ok(number) = number.parse::<f64>() else {
+                    return self.error(&format!("invalid duration {token}")).fail();
+                };
+                return Ok(Static::Duration((sign * number * unit_nanos) as i64));
+            }
+            if let Ok(int) = number.parse::<i64>() {
+                return Ok(Static::Int(if negative { -int } else { int }));
+            }
+            if let Ok(float) = number.parse::<f64>() {
+                return Ok(Static::Float(sign * float));
+            }
+            return self.error(&format!("invalid number {token}")).fail();
+        }
+        ensure!(!negative, self.error("expected number"));
+
+        let value = match token {
+            "true" => Static::Bool(true),
+            "false" => Static::Bool(false),
+            "ok" => Static::Status(Status::Ok),
+            "error" => Static::Status(Status::Error),
+            "unset" => Static::Status(Status::Unset),
+            "unspecified" => Static::Kind(Kind::Unspecified),
+            "internal" => Static::Kind(Kind::Internal),
+            "server" => Static::Kind(Kind::Server),
+            "client" => Static::Kind(Kind::Client),
+            "producer" => Static::Kind(Kind::Producer),
+            "consumer" => Static::Kind(Kind::Consumer),
+            _ => return self.error(&format!("invalid value {token:?}")).fail(),
+        };
+        Ok(value)
+    }
+}
+
+fn validate_comparison(field: &Field, op: CompareOp, value: &Static) -> Result<()> {
+    let is_equality = matches!(op, CompareOp::Eq | CompareOp::NotEq);
+    let valid = match (field, value) {
+        (_, Static::String(_)) if matches!(op, CompareOp::Regex | CompareOp::NotRegex) => {
+            matches!(
+                field,
+                Field::Name | Field::StatusMessage | Field::Attribute { .. }
+            )
+        }
+        (_, _) if matches!(op, CompareOp::Regex | CompareOp::NotRegex) => false,
+        (Field::Status, Static::Status(_)) | (Field::Kind, Static::Kind(_)) => is_equality,
+        (Field::Duration, Static::Duration(_) | Static::Int(_)) => true,
+        (Field::Name | Field::StatusMessage, Static::String(_)) => true,
+        (Field::Attribute { .. }, Static::String(_) | Static::Bool(_)) => is_equality,
+        (Field::Attribute { .. }, Static::Int(_) | Static::Float(_) | Static::Duration(_)) => true,
+        _ => false,
+    };
+    ensure!(
+        valid,
+        InvalidTempoQuerySnafu {
+            reason: format!("invalid comparison {field:?} {op:?} {value:?}"),
+        }
+    );
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compare(field: Field, op: CompareOp, value: Static) -> FieldExpr {
+        FieldExpr::Compare { field, op, value }
+    }
+
+    fn attribute(scope: AttributeScope, name: &str) -> Field {
+        Field::Attribute {
+            scope,
+            name: name.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            TraceQuery {
+                spansets: vec![Spanset { filter: None }]
+            },
+            parse("{}").unwrap()
+        );
+
+        let query = parse(
+            r#"{ resource.service.name = "api" } >> { (span.http.status_code >= 500 || status = error) && duration > 1.5s }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            TraceQuery {
+                spansets: vec![
+                    Spanset {
+                        filter: Some(compare(
+                            attribute(AttributeScope::Resource, "service.name"),
+                            CompareOp::Eq,
+                            Static::String("api".to_string()),
+                        )),
+                    },
+                    Spanset {
+                        filter: Some(
+                            compare(
+                                attribute(AttributeScope::Span, "http.status_code"),
+                                CompareOp::Gte,
+                                Static::Int(500),
+                            )
+                            .or(compare(
+                                Field::Status,
+                                CompareOp::Eq,
+                                Static::Status(Status::Error),
+                            ))
+                            .and(compare(
+                                Field::Duration,
+                                CompareOp::Gt,
+                                Static::Duration(1_500_000_000),
+                            )),
+                        ),
+                    },
+                ]
+            },
+            query
+        );
+
+        let query = parse(r#"{ .foo =~ "ba.*" && kind != server && name = "GET \"/\"" }"#).unwrap();
+        assert_eq!(
+            Some(
+                compare(
+                    attribute(AttributeScope::Any, "foo"),
+                    CompareOp::Regex,
+                    Static::String("ba.*".to_string()),
+                )
+                .and(compare(
+                    Field::Kind,
+                    CompareOp::NotEq,
+                    Static::Kind(Kind::Server),
+                ))
+                .and(compare(
+                    Field::Name,
+                    CompareOp::Eq,
+                    Static::String("GET \"/\"".to_string()),
+                ))
+            ),
+            query.spansets[0].filter
+        );
+    }
+
+    #[test]
+    fn test_parse_error() {
+        for query in [
+            "",
+            "{",
+            "{ name }",
+            "{ name = }",
+            "{ name = \"a }",
+            "{ foo = 1 }",
+            "{ status > ok }",
+            "{ duration = \"1s\" }",
+            "{ .a = 1x }",
+            "{ .a = 1 } >",
+            "{ .a = 1 } && { .b = 2 }",
+        ] {
+            assert!(parse(query).is_err(), "{query}");
+        }
+    }
+
+    #[test]
+    fn test_parse_nesting_depth() {
+        let nested =
+            |depth: usize| format!("{{ {}.a = 1{} }}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(
+            TraceQuery {
+                spansets: vec![Spanset {
+                    filter: Some(compare(
+                        attribute(AttributeScope::Any, "a"),
+                        CompareOp::Eq,
+                        Static::Int(1)
+                    )),
+                }],
+            },
+            parse(&nested(MAX_NESTING_DEPTH)).unwrap()
+        );
+
+        let err = parse(&nested(MAX_NESTING_DEPTH + 1)).unwrap_err();
+        assert!(
+            matches!(err, crate::error::Error::InvalidTempoQuery { .. }),
+            "unexpected error: {err:?}"
+        );
+        // Doesn't overflow the stack.
+        assert!(parse(&nested(100_000)).is_err());
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(100_000_000, parse_duration("100ms").unwrap());
+        assert_eq!(90_000_000_000, parse_duration("1.5m").unwrap());
+        assert!(parse_duration("100").is_err());
+        assert!(parse_duration("1s2").is_err());
+    }
+
+    #[test]
+    fn test_parse_field() {
+        assert_eq!(Field::Name, Field::parse("name").unwrap());
+        assert_eq!(
+            attribute(AttributeScope::Resource, "service.name"),
+            Field::parse("resource.service.name").unwrap()
+        );
+        assert!(Field::parse("foo bar").is_err());
+    }
+}
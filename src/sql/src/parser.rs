@@ -159,6 +159,8 @@ impl ParserContext<'_> {
 
                 Keyword::DELETE => self.parse_delete(),
 
+                Keyword::UPDATE => self.parse_update(),
+
                 Keyword::DESCRIBE | Keyword::DESC => {
                     let _ = self.parser.next_token();
                     self.parse_describe()
@@ -186,6 +188,10 @@ impl ParserContext<'_> {
 
                 Keyword::ADMIN => self.parse_admin_command(),
 
+                Keyword::GRANT => self.parse_grant(),
+
+                Keyword::REVOKE => self.parse_revoke(),
+
                 Keyword::NoKeyword
                     if w.quote_style.is_none() && w.value.to_uppercase() == tql_parser::TQL =>
                 {
@@ -32,5 +32,7 @@ pub(crate) mod set_var_parser;
 pub(crate) mod show_parser;
 pub(crate) mod tql_parser;
 pub(crate) mod truncate_parser;
+pub(crate) mod update_parser;
+pub(crate) mod user_parser;
 pub mod utils;
 pub mod with_tql_parser;
@@ -50,12 +50,16 @@ impl ParserContext<'_> {
             Token::Word(w) => match w.keyword {
                 Keyword::DATABASE => self.parse_alter_database().map(Statement::AlterDatabase),
                 Keyword::TABLE => self.parse_alter_table().map(Statement::AlterTable),
+                Keyword::USER => self.parse_alter_user(),
                 #[cfg(feature = "enterprise")]
                 Keyword::TRIGGER => {
                     self.parser.next_token();
                     self.parse_alter_trigger()
                 }
-                _ => self.expected("DATABASE or TABLE after ALTER", self.parser.peek_token()),
+                _ => self.expected(
+                    "DATABASE, TABLE or USER after ALTER",
+                    self.parser.peek_token(),
+                ),
             },
             unexpected => self.unsupported(unexpected.to_string()),
         }
@@ -26,8 +26,9 @@ use datatypes::data_type::ConcreteDataType;
 use itertools::Itertools;
 use snafu::{OptionExt, ResultExt, ensure};
 use sqlparser::ast::{
-    ColumnOption, ColumnOptionDef, DataType, Expr, KeyOrIndexDisplay, NullsDistinctOption,
-    PrimaryKeyConstraint, UniqueConstraint,
+    ColumnOption, ColumnOptionDef, DataType, Expr, Function, FunctionArg, FunctionArgExpr,
+    FunctionArguments, KeyOrIndexDisplay, NullsDistinctOption, PrimaryKeyConstraint,
+    UniqueConstraint,
 };
 use sqlparser::dialect::keywords::Keyword;
 use sqlparser::keywords::ALL_KEYWORDS;
@@ -50,7 +51,8 @@ use crate::parsers::utils::{
 };
 use crate::statements::create::{
     Column, ColumnExtensions, CreateDatabase, CreateExternalTable, CreateFlow, CreateTable,
-    CreateTableLike, CreateView, Partitions, SqlOrTql, TableConstraint, VECTOR_OPT_DIM,
+    CreateTableLike, CreateView, HASH_BUCKET_FUNCTION, MAX_HASH_PARTITIONS, Partitions, SqlOrTql,
+    TableConstraint, VECTOR_OPT_DIM,
 };
 use crate::statements::statement::Statement;
 use crate::statements::transform::type_alias::get_data_type_by_alias_name;
@@ -90,6 +92,8 @@ impl<'a> ParserContext<'a> {
 
                 Keyword::EXTERNAL => self.parse_create_external_table(),
 
+                Keyword::USER => self.parse_create_user(),
+
                 Keyword::OR => {
                     let _ = self.parser.next_token();
                     self.parser
@@ -506,8 +510,12 @@ impl<'a> ParserContext<'a> {
         self.parse_partition_on_columns().map(Some)
     }
 
-    /// Parses the "ON COLUMNS (...) (...)" part after "PARTITION".
+    /// Parses the "ON COLUMNS (...) (...)" or "BY HASH (...) PARTITIONS n" part after "PARTITION".
     pub(crate) fn parse_partition_on_columns(&mut self) -> Result<Partitions> {
+        if self.parser.parse_keyword(Keyword::BY) {
+            return self.parse_partition_by_hash();
+        }
+
         self.parser
             .expect_keywords(&[Keyword::ON, Keyword::COLUMNS])
             .context(error::UnexpectedSnafu {
@@ -529,6 +537,40 @@ impl<'a> ParserContext<'a> {
         Ok(Partitions { column_list, exprs })
     }
 
+    /// Parses the "HASH (...) PARTITIONS n" part after "PARTITION BY".
+    fn parse_partition_by_hash(&mut self) -> Result<Partitions> {
+        if !self.consume_token("HASH") {
+            return self.expected("HASH", self.parser.peek_token());
+        }
+
+        let raw_column_list = self
+            .parser
+            .parse_parenthesized_column_list(Mandatory, false)
+            .context(error::SyntaxSnafu)?;
+        let column_list = raw_column_list
+            .into_iter()
+            .map(Self::canonicalize_identifier)
+            .collect::<Vec<_>>();
+
+        if !self.consume_token("PARTITIONS") {
+            return self.expected("PARTITIONS", self.parser.peek_token());
+        }
+        let num_partitions = self
+            .parser
+            .parse_literal_uint()
+            .context(error::SyntaxSnafu)?;
+        ensure!(
+            (1..=MAX_HASH_PARTITIONS as u64).contains(&num_partitions),
+            InvalidSqlSnafu {
+                msg: format!(
+                    "number of hash partitions must be between 1 and {MAX_HASH_PARTITIONS}, got {num_partitions}"
+                ),
+            }
+        );
+
+        Ok(Partitions::new_hash(column_list, num_partitions as u32))
+    }
+
     fn parse_partition_entry(&mut self) -> Result<Expr> {
         self.parser.parse_expr().context(error::SyntaxSnafu)
     }
@@ -1238,6 +1280,22 @@ fn ensure_one_expr(expr: &Expr, columns: &[&Column]) -> Result<()> {
             ensure_one_expr(expr, columns)?;
             Ok(())
         }
+        Expr::Function(Function {
+            name,
+            args: FunctionArguments::List(arg_list),
+            ..
+        }) if name.to_string().eq_ignore_ascii_case(HASH_BUCKET_FUNCTION) => {
+            for arg in &arg_list.args {
+                let FunctionArg::Unnamed(FunctionArgExpr::Expr(arg)) = arg else {
+                    return error::InvalidSqlSnafu {
+                        msg: format!("Invalid argument {arg} of {HASH_BUCKET_FUNCTION}"),
+                    }
+                    .fail();
+                };
+                ensure_one_expr(arg, columns)?;
+            }
+            Ok(())
+        }
         _ => error::InvalidSqlSnafu {
             msg: format!("Partition rule expr {:?} is not a binary expr", expr),
         }
@@ -2567,6 +2625,82 @@ ENGINE=mito";
         );
     }
 
+    #[test]
+    fn test_parse_partition_by_hash() {
+        let sql = r"
+CREATE TABLE rcx ( ts TIMESTAMP TIME INDEX, a INT, b STRING, c INT )
+PARTITION BY HASH (b, A) PARTITIONS 3
+ENGINE=mito";
+        let result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        let Statement::CreateTable(create_table) = &result[0] else {
+            unreachable!()
+        };
+        let partitions = create_table.partitions.as_ref().unwrap();
+        assert_eq!(
+            partitions.to_string(),
+            r#"PARTITION ON COLUMNS (b, a) (
+  hash_bucket(b, a, 3) = 0,
+  hash_bucket(b, a, 3) = 1,
+  hash_bucket(b, a, 3) = 2
+)"#
+        );
+
+        // The displayed partitions can be parsed again.
+        let sql = format!(
+            "CREATE TABLE rcx ( ts TIMESTAMP TIME INDEX, a INT, b STRING, c INT ) {partitions} ENGINE=mito"
+        );
+        let result = ParserContext::create_with_dialect(
+            &sql,
+            &GreptimeDbDialect {},
+            ParseOptions::default(),
+        )
+        .unwrap();
+        let Statement::CreateTable(reparsed) = &result[0] else {
+            unreachable!()
+        };
+        assert_eq!(
+            reparsed.partitions.as_ref().unwrap().to_string(),
+            partitions.to_string()
+        );
+    }
+
+    #[test]
+    fn test_parse_partition_by_hash_error() {
+        let sql = r"
+CREATE TABLE rcx ( ts TIMESTAMP TIME INDEX, a INT, b STRING, c INT )
+PARTITION BY HASH (b) PARTITIONS 0
+ENGINE=mito";
+        let result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default());
+        assert_eq!(
+            result.unwrap_err().output_msg(),
+            "Invalid SQL, error: number of hash partitions must be between 1 and 1024, got 0"
+        );
+
+        let sql = r"
+CREATE TABLE rcx ( ts TIMESTAMP TIME INDEX, a INT, b STRING, c INT )
+PARTITION BY HASH (d) PARTITIONS 2
+ENGINE=mito";
+        let result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default());
+        assert!(result.is_err());
+
+        let sql = r"
+CREATE TABLE rcx ( ts TIMESTAMP TIME INDEX, a INT, b STRING, c INT )
+PARTITION BY RANGE (b)
+ENGINE=mito";
+        let result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default());
+        assert!(
+            result
+                .unwrap_err()
+                .output_msg()
+                .contains("Expected HASH, found: RANGE")
+        );
+    }
+
     fn assert_column_def(column: &ColumnDef, name: &str, data_type: &str) {
         assert_eq!(column.name.to_string(), name);
         assert_eq!(column.data_type.to_string(), data_type);
@@ -60,6 +60,7 @@ impl ParserContext<'_> {
                 #[cfg(feature = "enterprise")]
                 Keyword::TRIGGER => self.parse_drop_trigger(),
                 Keyword::SCHEMA | Keyword::DATABASE => self.parse_drop_database(),
+                Keyword::USER => self.parse_drop_user(),
                 Keyword::NoKeyword => {
                     let uppercase = w.value.to_uppercase();
                     match uppercase.as_str() {
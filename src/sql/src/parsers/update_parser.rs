@@ -0,0 +1,73 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::ResultExt;
+use sqlparser::ast::Statement as SpStatement;
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::statement::Statement;
+use crate::statements::update::Update;
+
+/// UPDATE statement parser implementation
+impl ParserContext<'_> {
+    pub(crate) fn parse_update(&mut self) -> Result<Statement> {
+        let token = self.parser.next_token();
+        let spstatement = self
+            .parser
+            .parse_update(token)
+            .context(error::SyntaxSnafu)?;
+
+        match spstatement {
+            SpStatement::Update { .. } => {
+                Ok(Statement::Update(Box::new(Update { inner: spstatement })))
+            }
+            unexp => error::UnsupportedSnafu {
+                keyword: unexp.to_string(),
+            }
+            .fail(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::assert_matches;
+
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+    use crate::parser::ParseOptions;
+
+    #[test]
+    pub fn test_parse_update() {
+        let sql = r"update my_table set cpu = cpu * 2, memory = NULL where host = 'a' and ts > 0;";
+        let result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(1, result.len());
+        assert_matches!(result[0], Statement::Update { .. });
+        assert_eq!(
+            "UPDATE my_table SET cpu = cpu * 2, memory = NULL WHERE host = 'a' AND ts > 0",
+            result[0].to_string()
+        );
+    }
+
+    #[test]
+    pub fn test_parse_invalid_update() {
+        let sql = r"update my_table where "; // intentionally a bad sql
+        let result =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default());
+        assert!(result.is_err(), "result is: {result:?}");
+    }
+}
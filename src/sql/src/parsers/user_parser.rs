@@ -0,0 +1,328 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use snafu::ResultExt;
+use sqlparser::dialect::keywords::Keyword;
+use sqlparser::tokenizer::Token;
+
+use crate::error::{self, Result};
+use crate::parser::ParserContext;
+use crate::statements::statement::Statement;
+use crate::statements::user::{
+    AlterUser, CreateUser, DropUser, Grant, GrantTarget, Privilege, Revoke, UserPassword,
+};
+
+/// User management statements parser implementation
+impl ParserContext<'_> {
+    /// Parses `CREATE USER [IF NOT EXISTS] <name> IDENTIFIED BY '<password>'`.
+    pub(crate) fn parse_create_user(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let if_not_exists =
+            self.parser
+                .parse_keywords(&[Keyword::IF, Keyword::NOT, Keyword::EXISTS]);
+        let name = self.parse_user_name()?;
+        let password = self.parse_user_password()?;
+
+        Ok(Statement::CreateUser(CreateUser {
+            name,
+            password,
+            if_not_exists,
+        }))
+    }
+
+    /// Parses `ALTER USER <name> IDENTIFIED BY '<password>'`.
+    pub(crate) fn parse_alter_user(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let name = self.parse_user_name()?;
+        let password = self.parse_user_password()?;
+
+        Ok(Statement::AlterUser(AlterUser { name, password }))
+    }
+
+    /// Parses `DROP USER [IF EXISTS] <name>`.
+    pub(crate) fn parse_drop_user(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let if_exists = self.parser.parse_keywords(&[Keyword::IF, Keyword::EXISTS]);
+        let name = self.parse_user_name()?;
+
+        Ok(Statement::DropUser(DropUser { name, if_exists }))
+    }
+
+    /// Parses `GRANT <privileges> ON <target> TO <user>`.
+    pub(crate) fn parse_grant(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let privileges = self.parse_privileges()?;
+        self.parser
+            .expect_keyword(Keyword::ON)
+            .context(error::SyntaxSnafu)?;
+        let target = self.parse_grant_target()?;
+        self.parser
+            .expect_keyword(Keyword::TO)
+            .context(error::SyntaxSnafu)?;
+        let user = self.parse_user_name()?;
+
+        Ok(Statement::Grant(Grant {
+            privileges,
+            target,
+            user,
+        }))
+    }
+
+    /// Parses `REVOKE <privileges> ON <target> FROM <user>`.
+    pub(crate) fn parse_revoke(&mut self) -> Result<Statement> {
+        let _ = self.parser.next_token();
+        let privileges = self.parse_privileges()?;
+        self.parser
+            .expect_keyword(Keyword::ON)
+            .context(error::SyntaxSnafu)?;
+        let target = self.parse_grant_target()?;
+        self.parser
+            .expect_keyword(Keyword::FROM)
+            .context(error::SyntaxSnafu)?;
+        let user = self.parse_user_name()?;
+
+        Ok(Statement::Revoke(Revoke {
+            privileges,
+            target,
+            user,
+        }))
+    }
+
+    fn parse_user_name(&mut self) -> Result<String> {
+        // User names are case-sensitive, so they are not canonicalized.
+        self.parser
+            .parse_identifier()
+            .map(|ident| ident.value)
+            .with_context(|_| error::UnexpectedSnafu {
+                expected: "a user name",
+                actual: self.peek_token_as_string(),
+            })
+    }
+
+    /// Parses `IDENTIFIED BY '<password>'` or `[WITH] PASSWORD [=] '<password>'`.
+    fn parse_user_password(&mut self) -> Result<UserPassword> {
+        if self.consume_token("IDENTIFIED") {
+            self.parser
+                .expect_keyword(Keyword::BY)
+                .context(error::SyntaxSnafu)?;
+        } else {
+            let _ = self.parser.parse_keyword(Keyword::WITH);
+            if !self.consume_token("PASSWORD") {
+                return self.expected("IDENTIFIED BY or PASSWORD", self.parser.peek_token());
+            }
+            let _ = self.parser.consume_token(&Token::Eq);
+        }
+
+        let password = self
+            .parser
+            .parse_literal_string()
+            .context(error::SyntaxSnafu)?;
+        Ok(UserPassword::new(password))
+    }
+
+    fn parse_privileges(&mut self) -> Result<Vec<Privilege>> {
+        if self.parser.parse_keyword(Keyword::ALL) {
+            let _ = self.parser.parse_keyword(Keyword::PRIVILEGES);
+            return Ok(vec![Privilege::All]);
+        }
+
+        let mut privileges = Vec::with_capacity(1);
+        loop {
+            let privilege = match self.parser.next_token().token {
+                Token::Word(w) if w.keyword == Keyword::SELECT => Privilege::Select,
+                Token::Word(w) if w.keyword == Keyword::INSERT => Privilege::Insert,
+                Token::Word(w) if w.keyword == Keyword::DELETE => Privilege::Delete,
+                Token::Word(w) if w.keyword == Keyword::ALTER => Privilege::Alter,
+                Token::Word(w) if w.keyword == Keyword::DROP => Privilege::Drop,
+                unexpected => {
+                    return self.unsupported(format!("privilege {unexpected}"));
+                }
+            };
+            if !privileges.contains(&privilege) {
+                privileges.push(privilege);
+            }
+            if !self.parser.consume_token(&Token::Comma) {
+                break;
+            }
+        }
+
+        Ok(privileges)
+    }
+
+    /// Parses `*`, `<table>`, `<database>.*` or `<database>.<table>`.
+    fn parse_grant_target(&mut self) -> Result<GrantTarget> {
+        let first = self.parse_grant_target_part()?;
+        if !self.parser.consume_token(&Token::Period) {
+            return Ok(GrantTarget {
+                database: None,
+                table: first,
+            });
+        }
+
+        let Some(database) = first else {
+            return self.expected("a database name", self.parser.peek_token());
+        };
+        let table = self.parse_grant_target_part()?;
+        Ok(GrantTarget {
+            database: Some(database),
+            table,
+        })
+    }
+
+    /// Parses an identifier, or `*` which returns `None`.
+    fn parse_grant_target_part(&mut self) -> Result<Option<String>> {
+        if self.parser.consume_token(&Token::Mul) {
+            return Ok(None);
+        }
+
+        let ident = self
+            .parser
+            .parse_identifier()
+            .with_context(|_| error::UnexpectedSnafu {
+                expected: "a database or table name",
+                actual: self.peek_token_as_string(),
+            })?;
+        Ok(Some(Self::canonicalize_identifier(ident).value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dialect::GreptimeDbDialect;
+    use crate::parser::ParseOptions;
+
+    fn parse(sql: &str) -> Statement {
+        let mut stmts =
+            ParserContext::create_with_dialect(sql, &GreptimeDbDialect {}, ParseOptions::default())
+                .unwrap();
+        assert_eq!(stmts.len(), 1);
+        stmts.pop().unwrap()
+    }
+
+    #[test]
+    fn test_parse_create_user() {
+        for sql in [
+            "CREATE USER IF NOT EXISTS alice IDENTIFIED BY 'secret'",
+            "create user if not exists alice with password 'secret'",
+            "CREATE USER IF NOT EXISTS 'alice' PASSWORD = 'secret'",
+        ] {
+            let stmt = parse(sql);
+            let Statement::CreateUser(create_user) = &stmt else {
+                unreachable!()
+            };
+            assert_eq!(create_user.name, "alice");
+            assert_eq!(create_user.password.expose(), "secret");
+            assert!(create_user.if_not_exists);
+            assert!(!stmt.is_readonly());
+            // The password is redacted.
+            assert_eq!(
+                "CREATE USER IF NOT EXISTS alice IDENTIFIED BY '******'",
+                stmt.to_string()
+            );
+            assert!(!serde_json::to_string(&stmt).unwrap().contains("secret"));
+        }
+
+        assert!(
+            ParserContext::create_with_dialect(
+                "CREATE USER alice",
+                &GreptimeDbDialect {},
+                ParseOptions::default()
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_alter_and_drop_user() {
+        let stmt = parse("ALTER USER alice IDENTIFIED BY 'new_secret'");
+        let Statement::AlterUser(alter_user) = &stmt else {
+            unreachable!()
+        };
+        assert_eq!(alter_user.name, "alice");
+        assert_eq!(alter_user.password.expose(), "new_secret");
+
+        let stmt = parse("DROP USER IF EXISTS alice");
+        assert_eq!(
+            stmt,
+            Statement::DropUser(DropUser {
+                name: "alice".to_string(),
+                if_exists: true,
+            })
+        );
+        assert_eq!("DROP USER IF EXISTS alice", stmt.to_string());
+    }
+
+    #[test]
+    fn test_parse_grant_and_revoke() {
+        let stmt = parse("GRANT SELECT, INSERT ON Metrics.CPU TO alice");
+        assert_eq!(
+            stmt,
+            Statement::Grant(Grant {
+                privileges: vec![Privilege::Select, Privilege::Insert],
+                target: GrantTarget {
+                    database: Some("metrics".to_string()),
+                    table: Some("cpu".to_string()),
+                },
+                user: "alice".to_string(),
+            })
+        );
+        assert_eq!(
+            "GRANT SELECT, INSERT ON metrics.cpu TO alice",
+            stmt.to_string()
+        );
+
+        let stmt = parse("GRANT ALL PRIVILEGES ON metrics.* TO alice");
+        assert_eq!(
+            "GRANT ALL PRIVILEGES ON metrics.* TO alice",
+            stmt.to_string()
+        );
+
+        let stmt = parse("REVOKE INSERT ON * FROM alice");
+        assert_eq!(
+            stmt,
+            Statement::Revoke(Revoke {
+                privileges: vec![Privilege::Insert],
+                target: GrantTarget {
+                    database: None,
+                    table: None,
+                },
+                user: "alice".to_string(),
+            })
+        );
+        assert_eq!("REVOKE INSERT ON * FROM alice", stmt.to_string());
+
+        let stmt = parse("GRANT DELETE, ALTER, DROP ON metrics.cpu TO alice");
+        assert_eq!(
+            "GRANT DELETE, ALTER, DROP ON metrics.cpu TO alice",
+            stmt.to_string()
+        );
+
+        for sql in [
+            "GRANT UPDATE ON metrics.cpu TO alice",
+            "GRANT SELECT ON *.cpu TO alice",
+            "REVOKE SELECT ON metrics.cpu TO alice",
+        ] {
+            assert!(
+                ParserContext::create_with_dialect(
+                    sql,
+                    &GreptimeDbDialect {},
+                    ParseOptions::default()
+                )
+                .is_err(),
+                "{sql}"
+            );
+        }
+    }
+}
@@ -32,6 +32,8 @@ pub mod statement;
 pub mod tql;
 pub(crate) mod transform;
 pub mod truncate;
+pub mod update;
+pub mod user;
 
 use std::sync::Arc;
 
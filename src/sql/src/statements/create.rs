@@ -26,7 +26,10 @@ use datatypes::schema::{
 use itertools::Itertools;
 use serde::Serialize;
 use snafu::ResultExt;
-use sqlparser::ast::{ColumnOption, ColumnOptionDef, DataType, Expr};
+use sqlparser::ast::{
+    BinaryOperator, ColumnOption, ColumnOptionDef, DataType, Expr, Function, FunctionArg,
+    FunctionArgExpr, FunctionArgumentList, FunctionArguments,
+};
 use sqlparser_derive::{Visit, VisitMut};
 
 use crate::ast::{ColumnDef, Ident, ObjectName, Value as SqlValue};
@@ -527,6 +530,22 @@ pub struct Partitions {
 }
 
 impl Partitions {
+    /// Creates the partitions of `PARTITION BY HASH (columns) PARTITIONS n`.
+    ///
+    /// The i-th partition is `hash_bucket(columns, n) = i`.
+    pub fn new_hash(column_list: Vec<Ident>, num_partitions: u32) -> Self {
+        let exprs = (0..num_partitions)
+            .map(|bucket| Expr::BinaryOp {
+                left: Box::new(hash_bucket_expr(&column_list, num_partitions)),
+                op: BinaryOperator::Eq,
+                right: Box::new(Expr::Value(
+                    SqlValue::Number(bucket.to_string(), false).into(),
+                )),
+            })
+            .collect();
+        Self { column_list, exprs }
+    }
+
     /// set quotes to all [Ident]s from column list
     pub fn set_quote(&mut self, quote_style: char) {
         self.column_list
@@ -535,6 +554,39 @@ impl Partitions {
     }
 }
 
+/// Name of the function that computes the hash partition of a row.
+pub const HASH_BUCKET_FUNCTION: &str = "hash_bucket";
+
+/// The maximum number of partitions of `PARTITION BY HASH`.
+pub const MAX_HASH_PARTITIONS: u32 = 1024;
+
+/// Builds the `hash_bucket(columns, num_buckets)` expr.
+pub fn hash_bucket_expr(columns: &[Ident], num_buckets: u32) -> Expr {
+    let args = columns
+        .iter()
+        .map(|column| Expr::Identifier(column.clone()))
+        .chain(std::iter::once(Expr::Value(
+            SqlValue::Number(num_buckets.to_string(), false).into(),
+        )))
+        .map(|expr| FunctionArg::Unnamed(FunctionArgExpr::Expr(expr)))
+        .collect();
+
+    Expr::Function(Function {
+        name: ObjectName::from(vec![Ident::new(HASH_BUCKET_FUNCTION)]),
+        args: FunctionArguments::List(FunctionArgumentList {
+            args,
+            duplicate_treatment: None,
+            clauses: vec![],
+        }),
+        filter: None,
+        null_treatment: None,
+        over: None,
+        parameters: FunctionArguments::None,
+        within_group: vec![],
+        uses_odbc_syntax: false,
+    })
+}
+
 #[derive(Debug, PartialEq, Eq, Clone, Visit, VisitMut)]
 pub struct PartitionEntry {
     pub name: Ident,
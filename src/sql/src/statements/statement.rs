@@ -45,6 +45,8 @@ use crate::statements::show::{
 };
 use crate::statements::tql::Tql;
 use crate::statements::truncate::TruncateTable;
+use crate::statements::update::Update;
+use crate::statements::user::{AlterUser, CreateUser, DropUser, Grant, Revoke};
 
 /// Tokens parsed by `DFParser` are converted into these values.
 #[allow(clippy::large_enum_variant)]
@@ -56,6 +58,8 @@ pub enum Statement {
     Insert(Box<Insert>),
     // Delete
     Delete(Box<Delete>),
+    // Update
+    Update(Box<Update>),
     /// CREATE TABLE
     CreateTable(CreateTable),
     // CREATE EXTERNAL TABLE
@@ -161,6 +165,16 @@ pub enum Statement {
     Kill(Kill),
     // SHOW PROCESSLIST
     ShowProcesslist(ShowProcessList),
+    // CREATE USER
+    CreateUser(CreateUser),
+    // ALTER USER
+    AlterUser(AlterUser),
+    // DROP USER
+    DropUser(DropUser),
+    // GRANT ... ON ... TO
+    Grant(Grant),
+    // REVOKE ... ON ... FROM
+    Revoke(Revoke),
 }
 
 impl Statement {
@@ -201,6 +215,7 @@ impl Statement {
             // Write operations
             Statement::Insert(_)
             | Statement::Delete(_)
+            | Statement::Update(_)
             | Statement::CreateTable(_)
             | Statement::CreateExternalTable(_)
             | Statement::CreateTableLike(_)
@@ -221,7 +236,12 @@ impl Statement {
             | Statement::DeclareCursor(_)
             | Statement::CloseCursor(_)
             | Statement::Kill(_)
-            | Statement::Admin(_) => false,
+            | Statement::Admin(_)
+            | Statement::CreateUser(_)
+            | Statement::AlterUser(_)
+            | Statement::DropUser(_)
+            | Statement::Grant(_)
+            | Statement::Revoke(_) => false,
 
             #[cfg(feature = "enterprise")]
             Statement::UndropTable(_) => false,
@@ -241,6 +261,7 @@ impl Display for Statement {
             Statement::Query(s) => s.inner.fmt(f),
             Statement::Insert(s) => s.inner.fmt(f),
             Statement::Delete(s) => s.inner.fmt(f),
+            Statement::Update(s) => s.inner.fmt(f),
             Statement::CreateTable(s) => s.fmt(f),
             Statement::CreateExternalTable(s) => s.fmt(f),
             Statement::CreateTableLike(s) => s.fmt(f),
@@ -301,6 +322,11 @@ impl Display for Statement {
             Statement::CloseCursor(s) => s.fmt(f),
             Statement::Kill(k) => k.fmt(f),
             Statement::ShowProcesslist(s) => s.fmt(f),
+            Statement::CreateUser(s) => s.fmt(f),
+            Statement::AlterUser(s) => s.fmt(f),
+            Statement::DropUser(s) => s.fmt(f),
+            Statement::Grant(s) => s.fmt(f),
+            Statement::Revoke(s) => s.fmt(f),
         }
     }
 }
@@ -323,6 +349,7 @@ impl TryFrom<&Statement> for DfStatement {
             Statement::Query(query) => SpStatement::Query(Box::new(query.inner.clone())),
             Statement::Insert(insert) => insert.inner.clone(),
             Statement::Delete(delete) => delete.inner.clone(),
+            Statement::Update(update) => update.inner.clone(),
             _ => {
                 return ConvertToDfStatementSnafu {
                     statement: format!("{s:?}"),
@@ -0,0 +1,214 @@
+// Copyright 2023 Greptime Team
+//
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+//     http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use std::fmt::Display;
+use std::ops::ControlFlow;
+
+use common_base::secrets::{ExposeSecret, SecretString};
+use serde::{Serialize, Serializer};
+use sqlparser::ast::{Visit, VisitMut, Visitor, VisitorMut};
+use sqlparser_derive::{Visit, VisitMut};
+
+/// A password in user management statements.
+///
+/// It's redacted when displayed or serialized.
+#[derive(Clone, Debug)]
+pub struct UserPassword(SecretString);
+
+impl UserPassword {
+    pub fn new(password: String) -> Self {
+        Self(SecretString::new(Box::new(password)))
+    }
+
+    pub fn expose(&self) -> &str {
+        self.0.expose_secret()
+    }
+}
+
+impl PartialEq for UserPassword {
+    fn eq(&self, other: &Self) -> bool {
+        self.expose() == other.expose()
+    }
+}
+
+impl Eq for UserPassword {}
+
+impl Display for UserPassword {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("'******'")
+    }
+}
+
+impl Serialize for UserPassword {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str("******")
+    }
+}
+
+impl Visit for UserPassword {
+    fn visit<V: Visitor>(&self, _visitor: &mut V) -> ControlFlow<V::Break> {
+        ControlFlow::Continue(())
+    }
+}
+
+impl VisitMut for UserPassword {
+    fn visit<V: VisitorMut>(&mut self, _visitor: &mut V) -> ControlFlow<V::Break> {
+        ControlFlow::Continue(())
+    }
+}
+
+/// CREATE USER statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct CreateUser {
+    pub name: String,
+    pub password: UserPassword,
+    pub if_not_exists: bool,
+}
+
+impl Display for CreateUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CREATE USER ")?;
+        if self.if_not_exists {
+            write!(f, "IF NOT EXISTS ")?;
+        }
+        write!(f, "{} IDENTIFIED BY {}", self.name, self.password)
+    }
+}
+
+/// ALTER USER ... PASSWORD statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct AlterUser {
+    pub name: String,
+    pub password: UserPassword,
+}
+
+impl Display for AlterUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "ALTER USER {} IDENTIFIED BY {}",
+            self.name, self.password
+        )
+    }
+}
+
+/// DROP USER statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct DropUser {
+    pub name: String,
+    pub if_exists: bool,
+}
+
+impl Display for DropUser {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "DROP USER ")?;
+        if self.if_exists {
+            write!(f, "IF EXISTS ")?;
+        }
+        write!(f, "{}", self.name)
+    }
+}
+
+/// A privilege in GRANT and REVOKE statements.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub enum Privilege {
+    Select,
+    Insert,
+    Delete,
+    Alter,
+    Drop,
+    All,
+}
+
+impl Display for Privilege {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Privilege::Select => f.write_str("SELECT"),
+            Privilege::Insert => f.write_str("INSERT"),
+            Privilege::Delete => f.write_str("DELETE"),
+            Privilege::Alter => f.write_str("ALTER"),
+            Privilege::Drop => f.write_str("DROP"),
+            Privilege::All => f.write_str("ALL PRIVILEGES"),
+        }
+    }
+}
+
+/// The target of GRANT and REVOKE statements, like `db.table` or `db.*`.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct GrantTarget {
+    /// The database, or the current database if `None`.
+    pub database: Option<String>,
+    /// The table, or every table of the database if `None`.
+    pub table: Option<String>,
+}
+
+impl Display for GrantTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(database) = &self.database {
+            write!(f, "{database}.")?;
+        }
+        match &self.table {
+            Some(table) => write!(f, "{table}"),
+            None => write!(f, "*"),
+        }
+    }
+}
+
+fn format_privileges(privileges: &[Privilege]) -> String {
+    privileges
+        .iter()
+        .map(|privilege| privilege.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// GRANT statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct Grant {
+    pub privileges: Vec<Privilege>,
+    pub target: GrantTarget,
+    pub user: String,
+}
+
+impl Display for Grant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "GRANT {} ON {} TO {}",
+            format_privileges(&self.privileges),
+            self.target,
+            self.user
+        )
+    }
+}
+
+/// REVOKE statement.
+#[derive(Debug, Clone, PartialEq, Eq, Visit, VisitMut, Serialize)]
+pub struct Revoke {
+    pub privileges: Vec<Privilege>,
+    pub target: GrantTarget,
+    pub user: String,
+}
+
+impl Display for Revoke {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "REVOKE {} ON {} FROM {}",
+            format_privileges(&self.privileges),
+            self.target,
+            self.user
+        )
+    }
+}
@@ -219,6 +219,10 @@ fn extract_tables_from_statement(stmt: &Statement, names: &mut HashSet<ObjectNam
             collect_relations(&delete.inner, names);
             true
         }
+        Statement::Update(update) => {
+            collect_relations(&update.inner, names);
+            true
+        }
         Statement::Query(query) => extract_tables_from_query_ast(query, names),
         Statement::CreateTable(create) => {
             names.insert(create.name.clone());
@@ -346,7 +350,12 @@ fn extract_tables_from_statement(stmt: &Statement, names: &mut HashSet<ObjectNam
         | Statement::FetchCursor(_)
         | Statement::CloseCursor(_)
         | Statement::Kill(_)
-        | Statement::ShowProcesslist(_) => true,
+        | Statement::ShowProcesslist(_)
+        | Statement::CreateUser(_)
+        | Statement::AlterUser(_)
+        | Statement::DropUser(_)
+        | Statement::Grant(_)
+        | Statement::Revoke(_) => true,
         #[cfg(feature = "enterprise")]
         Statement::DropTrigger(_)
         | Statement::ShowCreateTrigger(_)
@@ -69,7 +69,7 @@ impl RegionInvoker {
         })?;
 
         self.region_server
-            .handle(body)
+            .handle(request.header.unwrap_or_default(), body)
             .await
             .context(InvokeRegionServerSnafu)
     }
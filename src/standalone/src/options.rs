@@ -23,9 +23,10 @@ use datanode::config::{DatanodeOptions, ProcedureConfig, RegionEngineConfig, Sto
 use file_engine::config::EngineConfig as FileEngineConfig;
 use flow::FlowConfig;
 use frontend::frontend::FrontendOptions;
+use frontend::promql_cache::PromqlCacheOptions;
 use frontend::service_config::{
     InfluxdbOptions, JaegerOptions, MysqlOptions, OpentsdbOptions, PostgresOptions,
-    PromStoreOptions,
+    PromStoreOptions, TempoOptions,
 };
 use mito2::config::MitoConfig;
 use query::options::QueryOptions;
@@ -56,6 +57,7 @@ pub struct StandaloneOptions {
     pub opentsdb: OpentsdbOptions,
     pub influxdb: InfluxdbOptions,
     pub jaeger: JaegerOptions,
+    pub tempo: TempoOptions,
     pub prom_store: PromStoreOptions,
     pub wal: DatanodeWalConfig,
     pub storage: StorageConfig,
@@ -64,12 +66,19 @@ pub struct StandaloneOptions {
     pub flow: FlowConfig,
     pub logging: LoggingOptions,
     pub user_provider: Option<String>,
+    /// Users allowed to run `CREATE USER`, `ALTER USER`, `DROP USER`, `GRANT` and `REVOKE`.
+    pub admin_users: Vec<String>,
+    /// The directory the `geoip` pipeline processor reads databases from. The
+    /// processor is disabled if it's not set.
+    pub geoip_database_dir: Option<String>,
     /// Options for different store engines.
     pub region_engine: Vec<RegionEngineConfig>,
     pub tracing: TracingOptions,
     pub init_regions_in_background: bool,
     pub init_regions_parallelism: usize,
     pub slow_query: SlowQueryOptions,
+    /// The PromQL range query results cache options.
+    pub promql_cache: PromqlCacheOptions,
     pub query: QueryOptions,
     pub memory: MemoryOptions,
     /// The event recorder options.
@@ -94,6 +103,7 @@ impl Default for StandaloneOptions {
             opentsdb: OpentsdbOptions::default(),
             influxdb: InfluxdbOptions::default(),
             jaeger: JaegerOptions::default(),
+            tempo: TempoOptions::default(),
             prom_store: PromStoreOptions::default(),
             wal: DatanodeWalConfig::default(),
             storage: StorageConfig::default(),
@@ -102,6 +112,8 @@ impl Default for StandaloneOptions {
             flow: FlowConfig::default(),
             logging: LoggingOptions::default(),
             user_provider: None,
+            admin_users: vec![],
+            geoip_database_dir: None,
             region_engine: vec![
                 RegionEngineConfig::Mito(MitoConfig::default()),
                 RegionEngineConfig::File(FileEngineConfig::default()),
@@ -110,6 +122,7 @@ impl Default for StandaloneOptions {
             init_regions_in_background: false,
             init_regions_parallelism: 16,
             slow_query: SlowQueryOptions::default(),
+            promql_cache: PromqlCacheOptions::default(),
             query: QueryOptions::default(),
             memory: MemoryOptions::default(),
             event_recorder: EventRecorderOptions::default(),
@@ -153,12 +166,16 @@ impl StandaloneOptions {
             opentsdb: cloned_opts.opentsdb,
             influxdb: cloned_opts.influxdb,
             jaeger: cloned_opts.jaeger,
+            tempo: cloned_opts.tempo,
             prom_store: cloned_opts.prom_store,
             meta_client: None,
             logging: cloned_opts.logging,
             user_provider: cloned_opts.user_provider,
+            admin_users: cloned_opts.admin_users,
+            geoip_database_dir: cloned_opts.geoip_database_dir,
             query: cloned_opts.query,
             slow_query: cloned_opts.slow_query,
+            promql_cache: cloned_opts.promql_cache,
             event_recorder: cloned_opts.event_recorder,
             heartbeat_env_vars: cloned_opts.heartbeat_env_vars.clone(),
             ..Default::default()
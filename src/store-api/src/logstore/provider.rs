@@ -58,11 +58,29 @@ impl RaftEngineProvider {
     }
 }
 
+// The Provider of object store log store
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ObjectStoreProvider {
+    pub id: u64,
+}
+
+impl ObjectStoreProvider {
+    pub fn new(id: u64) -> Self {
+        Self { id }
+    }
+
+    /// Returns the type name.
+    pub fn type_name() -> &'static str {
+        "ObjectStoreProvider"
+    }
+}
+
 /// The Provider of LogStore
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum Provider {
     RaftEngine(RaftEngineProvider),
     Kafka(Arc<KafkaProvider>),
+    ObjectStore(ObjectStoreProvider),
     Noop,
 }
 
@@ -73,6 +91,9 @@ impl Display for Provider {
                 write!(f, "RaftEngine(region={})", RegionId::from_u64(provider.id))
             }
             Provider::Kafka(provider) => write!(f, "Kafka(topic={})", provider.topic),
+            Provider::ObjectStore(provider) => {
+                write!(f, "ObjectStore(region={})", RegionId::from_u64(provider.id))
+            }
             Provider::Noop => write!(f, "Noop"),
         }
     }
@@ -99,13 +120,17 @@ impl Provider {
         Provider::Kafka(Arc::new(KafkaProvider { topic }))
     }
 
+    pub fn object_store_provider(id: u64) -> Provider {
+        Provider::ObjectStore(ObjectStoreProvider { id })
+    }
+
     pub fn noop_provider() -> Provider {
         Provider::Noop
     }
 
     /// Returns true if it's remote WAL.
     pub fn is_remote_wal(&self) -> bool {
-        matches!(self, Provider::Kafka(_))
+        matches!(self, Provider::Kafka(_) | Provider::ObjectStore(_))
     }
 
     /// Returns the type name.
@@ -113,6 +138,7 @@ impl Provider {
         match self {
             Provider::RaftEngine(_) => RaftEngineProvider::type_name(),
             Provider::Kafka(_) => KafkaProvider::type_name(),
+            Provider::ObjectStore(_) => ObjectStoreProvider::type_name(),
             Provider::Noop => "Noop",
         }
     }
@@ -132,4 +158,12 @@ impl Provider {
         }
         None
     }
+
+    /// Returns the reference of [`ObjectStoreProvider`] if it's the type of [`LogStoreProvider::ObjectStore`].
+    pub fn as_object_store_provider(&self) -> Option<&ObjectStoreProvider> {
+        if let Provider::ObjectStore(ns) = self {
+            return Some(ns);
+        }
+        None
+    }
 }
@@ -23,6 +23,8 @@ pub const APPEND_MODE_KEY: &str = "append_mode";
 pub const WRITE_BUFFER_SIZE_KEY: &str = "write_buffer_size";
 /// Option key for merge mode.
 pub const MERGE_MODE_KEY: &str = "merge_mode";
+/// Option key for merge functions of fields in the aggregate merge mode.
+pub const MERGE_FUNCTIONS_KEY: &str = "merge_functions";
 /// Option key for TTL(time-to-live)
 pub const TTL_KEY: &str = "ttl";
 /// Option key for the per-table auto flush interval.
@@ -45,6 +47,28 @@ pub const TWCS_TIME_WINDOW: &str = "compaction.twcs.time_window";
 pub const TWCS_REMOTE_COMPACTION: &str = "compaction.twcs.remote_compaction";
 /// Option key for twcs fallback to local.
 pub const TWCS_FALLBACK_TO_LOCAL: &str = "compaction.twcs.fallback_to_local";
+/// Option key for the age after which twcs downsamples SSTs.
+pub const TWCS_DOWNSAMPLE_AFTER: &str = "compaction.twcs.downsample_after";
+/// Option key for the time resolution of downsampled SSTs.
+pub const TWCS_DOWNSAMPLE_INTERVAL: &str = "compaction.twcs.downsample_interval";
+/// Option key for the functions to aggregate fields while downsampling.
+pub const TWCS_DOWNSAMPLE_FUNCTIONS: &str = "compaction.twcs.downsample_functions";
+/// Size-tiered compaction strategy.
+pub const COMPACTION_TYPE_SIZE_TIERED: &str = "size_tiered";
+/// Option key for size-tiered min file num in a tier to trigger a compaction.
+pub const SIZE_TIERED_TRIGGER_FILE_NUM: &str = "compaction.size_tiered.trigger_file_num";
+/// Option key for the size under which files are put into the lowest tier.
+pub const SIZE_TIERED_MIN_FILE_SIZE: &str = "compaction.size_tiered.min_file_size";
+/// Option key for size-tiered max output file size.
+pub const SIZE_TIERED_MAX_OUTPUT_FILE_SIZE: &str = "compaction.size_tiered.max_output_file_size";
+/// Option key for size-tiered remote compaction.
+pub const SIZE_TIERED_REMOTE_COMPACTION: &str = "compaction.size_tiered.remote_compaction";
+/// Option key for size-tiered fallback to local.
+pub const SIZE_TIERED_FALLBACK_TO_LOCAL: &str = "compaction.size_tiered.fallback_to_local";
+/// Option key for the name of the object store that aged SSTs move to.
+pub const STORAGE_COLD_KEY: &str = "storage.cold";
+/// Option key for the age after which SSTs move to the cold object store.
+pub const STORAGE_COLD_AFTER_KEY: &str = "storage.cold_after";
 /// Option key for memtable type.
 pub const MEMTABLE_TYPE: &str = "memtable.type";
 /// Option key for bulk memtable merge threshold.
@@ -87,7 +111,17 @@ pub fn is_mito_engine_option_key(key: &str) -> bool {
         TWCS_TIME_WINDOW,
         TWCS_REMOTE_COMPACTION,
         TWCS_FALLBACK_TO_LOCAL,
+        TWCS_DOWNSAMPLE_AFTER,
+        TWCS_DOWNSAMPLE_INTERVAL,
+        TWCS_DOWNSAMPLE_FUNCTIONS,
+        SIZE_TIERED_TRIGGER_FILE_NUM,
+        SIZE_TIERED_MIN_FILE_SIZE,
+        SIZE_TIERED_MAX_OUTPUT_FILE_SIZE,
+        SIZE_TIERED_REMOTE_COMPACTION,
+        SIZE_TIERED_FALLBACK_TO_LOCAL,
         "storage",
+        STORAGE_COLD_KEY,
+        STORAGE_COLD_AFTER_KEY,
         "index.inverted_index.ignore_column_ids",
         "index.inverted_index.segment_row_count",
         WAL_OPTIONS_KEY,
@@ -102,6 +136,7 @@ pub fn is_mito_engine_option_key(key: &str) -> bool {
         // We don't allow to create a mito table with sparse primary key encoding directly.
         APPEND_MODE_KEY,
         MERGE_MODE_KEY,
+        MERGE_FUNCTIONS_KEY,
         SST_FORMAT_KEY,
         MAX_ROW_GROUP_ROW_COUNT,
     ]
@@ -123,7 +158,24 @@ mod tests {
             "compaction.twcs.trigger_file_num"
         ));
         assert!(is_mito_engine_option_key("compaction.twcs.time_window"));
+        assert!(is_mito_engine_option_key(
+            "compaction.twcs.downsample_after"
+        ));
+        assert!(is_mito_engine_option_key(
+            "compaction.twcs.downsample_interval"
+        ));
+        assert!(is_mito_engine_option_key(
+            "compaction.twcs.downsample_functions"
+        ));
+        assert!(is_mito_engine_option_key(
+            "compaction.size_tiered.trigger_file_num"
+        ));
+        assert!(is_mito_engine_option_key(
+            "compaction.size_tiered.min_file_size"
+        ));
         assert!(is_mito_engine_option_key("storage"));
+        assert!(is_mito_engine_option_key("storage.cold"));
+        assert!(is_mito_engine_option_key("storage.cold_after"));
         assert!(is_mito_engine_option_key(
             "index.inverted_index.ignore_column_ids"
         ));
@@ -150,6 +202,7 @@ mod tests {
             "memtable.partition_tree.fork_dictionary_bytes"
         ));
         assert!(is_mito_engine_option_key("append_mode"));
+        assert!(is_mito_engine_option_key("merge_functions"));
         assert!(is_mito_engine_option_key("max_row_group_row_count"));
         assert!(!is_mito_engine_option_key("foo"));
     }
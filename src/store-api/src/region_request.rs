@@ -26,7 +26,8 @@ use api::v1::region::{
     AlterRequest, AlterRequests, BuildIndexRequest, BulkInsertRequest,
     CleanUpRequest as PbCleanUpRequest, CloseRequest, CompactRequest, CreateRequest,
     CreateRequests, DeleteRequests, DropRequest, DropRequests, FlushRequest, InsertRequests,
-    OpenRequest, TruncateRequest, alter_request, compact_request, region_request, truncate_request,
+    OpenRequest, RegionRequestHeader, TruncateRequest, alter_request, compact_request,
+    region_request, truncate_request,
 };
 use api::v1::{
     self, Analyzer, ArrowIpc, FulltextBackend as PbFulltextBackend, Option as PbOption, Rows,
@@ -63,7 +64,7 @@ use crate::path_utils::table_dir;
 use crate::storage::{ColumnId, RegionId, ScanRequest};
 
 /// The type of path to generate.
-#[derive(Debug, Clone, Copy, PartialEq, TryFromPrimitive)]
+#[derive(Debug, Clone, Copy, PartialEq, TryFromPrimitive, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum PathType {
     /// A bare path - the original path of an engine.
@@ -219,6 +220,7 @@ fn make_region_puts(inserts: InsertRequests) -> Result<Vec<(RegionId, RegionRequ
                         rows,
                         hint: None,
                         partition_expr_version: r.partition_expr_version.map(|v| v.value),
+                        replace_existing: false,
                     }),
                 )
             })
@@ -523,6 +525,38 @@ pub struct RegionPutRequest {
     pub hint: Option<WriteHint>,
     /// Partition expression version for the region.
     pub partition_expr_version: Option<u64>,
+    /// Whether the rows replace the existing rows with the same key instead of being
+    /// merged with them, see [REPLACE_EXISTING_ROWS_EXTENSION].
+    pub replace_existing: bool,
+}
+
+/// Query context extension of a [RegionRequestHeader] marking the put requests in the
+/// body as replacing the existing rows with the same key.
+///
+/// Only the frontend sets it for `UPDATE`, it's never copied from the query context
+/// of a client.
+pub const REPLACE_EXISTING_ROWS_EXTENSION: &str = "replace_existing_rows";
+
+/// Returns the header query context marking the put requests as replacing the
+/// existing rows, see [REPLACE_EXISTING_ROWS_EXTENSION].
+pub fn replace_existing_rows_context() -> v1::QueryContext {
+    v1::QueryContext {
+        extensions: HashMap::from([(
+            REPLACE_EXISTING_ROWS_EXTENSION.to_string(),
+            "true".to_string(),
+        )]),
+        ..Default::default()
+    }
+}
+
+/// Returns whether the put requests under `header` replace the existing rows, see
+/// [REPLACE_EXISTING_ROWS_EXTENSION].
+pub fn is_replacing_existing_rows(header: &RegionRequestHeader) -> bool {
+    header
+        .query_context
+        .as_ref()
+        .and_then(|ctx| ctx.extensions.get(REPLACE_EXISTING_ROWS_EXTENSION))
+        .is_some_and(|value| value == "true")
 }
 
 #[derive(Debug)]
@@ -30,7 +30,7 @@ use snafu::{OptionExt, ResultExt, ensure};
 use store_api::metric_engine_consts::PHYSICAL_TABLE_METADATA_KEY;
 use store_api::mito_engine_options::{
     APPEND_MODE_KEY, AUTO_FLUSH_INTERVAL_KEY, COMPACTION_TYPE, COMPACTION_TYPE_TWCS,
-    MAX_ROW_GROUP_ROW_COUNT, MERGE_MODE_KEY, SKIP_WAL_KEY, SST_FORMAT_KEY,
+    MAX_ROW_GROUP_ROW_COUNT, MERGE_FUNCTIONS_KEY, MERGE_MODE_KEY, SKIP_WAL_KEY, SST_FORMAT_KEY,
 };
 use store_api::region_request::{SetRegionOption, UnsetRegionOption};
 use store_api::storage::{ColumnDescriptor, ColumnDescriptorBuilder, ColumnId};
@@ -384,6 +384,7 @@ impl TableMeta {
                         .insert(APPEND_MODE_KEY.to_string(), value.to_string());
                     if *value {
                         new_options.extra_options.remove(MERGE_MODE_KEY);
+                        new_options.extra_options.remove(MERGE_FUNCTIONS_KEY);
                     }
                 }
                 SetRegionOption::AutoFlushInterval(new_interval) => {
@@ -1697,6 +1698,9 @@ mod tests {
         table_options
             .extra_options
             .insert(MERGE_MODE_KEY.to_string(), "last_non_null".to_string());
+        table_options
+            .extra_options
+            .insert(MERGE_FUNCTIONS_KEY.to_string(), "requests:sum".to_string());
         let meta = TableMetaBuilder::empty()
             .schema(schema)
             .primary_key_indices(vec![0])
@@ -1724,6 +1728,12 @@ mod tests {
                 .map(String::as_str)
         );
         assert!(!new_meta.options.extra_options.contains_key(MERGE_MODE_KEY));
+        assert!(
+            !new_meta
+                .options
+                .extra_options
+                .contains_key(MERGE_FUNCTIONS_KEY)
+        );
     }
 
     #[test]
@@ -691,4 +691,92 @@ mod tests {
             "denied source leaked into entities:\n{pretty_print}"
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_influxql_checks_permission_of_target_database() {
+        use query::influxql::parser::parse_statements;
+        use servers::query_handler::InfluxqlQueryHandler;
+
+        /// Denies InfluxQL queries on the `secret` schema only.
+        struct DenySecretSchema;
+
+        impl auth::PermissionChecker for DenySecretSchema {
+            fn check_permission(
+                &self,
+                _user_info: auth::UserInfoRef,
+                _req: auth::PermissionReq,
+            ) -> auth::error::Result<auth::PermissionResp> {
+                Ok(auth::PermissionResp::Allow)
+            }
+
+            fn check_permission_with_table_targets(
+                &self,
+                _user_info: auth::UserInfoRef,
+                req: auth::PermissionReq,
+                targets: auth::PermissionTableTargets,
+            ) -> auth::error::Result<auth::PermissionResp> {
+                if matches!(req, auth::PermissionReq::Action(auth::INFLUXDB_QUERY))
+                    && let auth::PermissionTableTargets::Resolved(targets) = targets
+                    && targets.iter().any(|target| target.schema == "secret")
+                {
+                    return Ok(auth::PermissionResp::Reject);
+                }
+                Ok(auth::PermissionResp::Allow)
+            }
+        }
+
+        let plugins = Plugins::new();
+        plugins.insert::<auth::PermissionCheckerRef>(Arc::new(DenySecretSchema));
+
+        let standalone =
+            GreptimeDbStandaloneBuilder::new("test_influxql_checks_permission_of_target_database")
+                .with_plugin(plugins)
+                .build()
+                .await;
+        let instance = standalone.fe_instance().clone();
+
+        let _ = query(&instance, "create database secret").await;
+        for table in ["public.m", "secret.m"] {
+            create_table(
+                &instance,
+                &format!(
+                    "create table {table} (ts timestamp time index, host string primary key, v double)"
+                ),
+            )
+            .await;
+            let _ = query(
+                &instance,
+                &format!("insert into {table} values (0, '{table}', 1.0)"),
+            )
+            .await;
+        }
+
+        async fn influxql(instance: &Instance, q: &str) -> servers::error::Result<()> {
+            let stmt = parse_statements(q).unwrap().remove(0);
+            InfluxqlQueryHandler::query(instance, stmt, QueryContext::arc())
+                .await
+                .map(|_| ())
+        }
+
+        // The database of the measurement takes precedence over the `ON` database.
+        influxql(
+            &instance,
+            "SHOW TAG VALUES ON secret FROM public..m WITH KEY = host",
+        )
+        .await
+        .unwrap();
+        for q in [
+            "SHOW TAG VALUES ON public FROM secret..m WITH KEY = host",
+            "SHOW TAG VALUES ON secret FROM m WITH KEY = host",
+            "SHOW TAG KEYS ON public FROM secret..m",
+            "SELECT v FROM secret..m",
+        ] {
+            let err = influxql(&instance, q).await.unwrap_err();
+            assert_eq!(
+                StatusCode::PermissionDenied,
+                err.status_code(),
+                "{q}: {err}"
+            );
+        }
+    }
 }
@@ -14,6 +14,7 @@
 
 #[cfg(test)]
 mod tests {
+    use std::collections::BTreeMap;
     use std::sync::Arc;
 
     use client::OutputData;
@@ -21,8 +22,12 @@ mod tests {
     use frontend::instance::Instance;
     use itertools::Itertools;
     use servers::opentsdb::codec::DataPoint;
-    use servers::query_handler::OpentsdbProtocolHandler;
+    use servers::opentsdb::query::{
+        MetricQuery, SubQuery, SuggestKind, TagFilter, TagFilterKind, collect_series,
+        collect_tag_sets,
+    };
     use servers::query_handler::sql::SqlQueryHandler;
+    use servers::query_handler::{OpentsdbProtocolHandler, OpentsdbQueryHandler};
     use session::context::QueryContext;
 
     use crate::standalone::GreptimeDbStandaloneBuilder;
@@ -44,6 +49,130 @@ mod tests {
         test_exec(&distributed.frontend()).await;
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_standalone_query() {
+        let standalone = GreptimeDbStandaloneBuilder::new("test_standalone_query")
+            .build()
+            .await;
+        let instance = standalone.fe_instance();
+
+        test_query(instance).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_distributed_query() {
+        let distributed = tests::create_distributed_instance("test_distributed_query").await;
+        test_query(&distributed.frontend()).await;
+    }
+
+    async fn collect(output: client::Output) -> Vec<common_recordbatch::RecordBatch> {
+        match output.data {
+            OutputData::Stream(stream) => RecordBatches::try_collect(stream).await.unwrap().take(),
+            OutputData::RecordBatches(batches) => batches.take(),
+            _ => unreachable!(),
+        }
+    }
+
+    async fn test_query(instance: &Arc<Instance>) {
+        let ctx = QueryContext::arc();
+
+        let data_points = [
+            ("web01", 1000, 1.0),
+            ("web01", 2000, 2.0),
+            ("web01", 61000, 4.0),
+            ("web02", 1000, 10.0),
+        ]
+        .into_iter()
+        .map(|(host, ts, value)| {
+            DataPoint::new(
+                "my_metric_2".to_string(),
+                ts,
+                value,
+                vec![("host".to_string(), host.to_string())],
+            )
+        })
+        .collect();
+        instance.exec(data_points, ctx.clone()).await.unwrap();
+
+        // Sums the series at every timestamp.
+        let sub_query = SubQuery::parse_m_param("sum:my_metric_2", 0, 120_000).unwrap();
+        let output = instance.query(&sub_query.query, ctx.clone()).await.unwrap();
+        let series = collect_series(&collect(output).await).unwrap();
+        assert_eq!(2, series.len());
+        let results = sub_query.build_results(series, true);
+        assert_eq!(1, results.len());
+        assert_eq!(vec!["host".to_string()], results[0].aggregate_tags);
+        assert_eq!(
+            BTreeMap::from([(1000, Some(11.0)), (2000, Some(2.0)), (61000, Some(4.0))]),
+            results[0].dps
+        );
+
+        // Downsamples every series into 1 minute averages.
+        let sub_query =
+            SubQuery::parse_m_param("sum:1m-avg:my_metric_2{host=web01}", 0, 120_000).unwrap();
+        let output = instance.query(&sub_query.query, ctx.clone()).await.unwrap();
+        let series = collect_series(&collect(output).await).unwrap();
+        let results = sub_query.build_results(series, false);
+        assert_eq!(1, results.len());
+        assert_eq!(
+            BTreeMap::from([("host".to_string(), "web01".to_string())]),
+            results[0].tags
+        );
+        assert_eq!(
+            BTreeMap::from([(0, Some(1.5)), (60, Some(4.0))]),
+            results[0].dps
+        );
+
+        // Unknown tag keys are rejected.
+        let query = MetricQuery {
+            metric: "my_metric_2".to_string(),
+            start_millis: 0,
+            end_millis: 120_000,
+            filters: vec![TagFilter::parse_inline("dc".to_string(), "lga", false).unwrap()],
+            downsample: None,
+        };
+        assert!(instance.query(&query, ctx.clone()).await.is_err());
+
+        let filters = [TagFilter {
+            tagk: "host".to_string(),
+            kind: TagFilterKind::Wildcard("*".to_string()),
+            group_by: false,
+        }];
+        let output = instance
+            .lookup("my_metric_2", &filters, 25, ctx.clone())
+            .await
+            .unwrap();
+        assert_eq!(
+            vec![
+                BTreeMap::from([("host".to_string(), "web01".to_string())]),
+                BTreeMap::from([("host".to_string(), "web02".to_string())]),
+            ],
+            collect_tag_sets(&collect(output).await)
+        );
+
+        assert_eq!(
+            vec!["my_metric_2".to_string()],
+            instance
+                .suggest(SuggestKind::Metrics, "my_metric", 25, ctx.clone())
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            vec!["host".to_string()],
+            instance
+                .suggest(SuggestKind::TagKeys, "ho", 25, ctx.clone())
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            vec!["web02".to_string()],
+            instance
+                .suggest(SuggestKind::TagValues, "web02", 25, ctx)
+                .await
+                .unwrap()
+        );
+    }
+
     async fn test_exec(instance: &Arc<Instance>) {
         let ctx = QueryContext::arc();
 
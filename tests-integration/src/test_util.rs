@@ -50,6 +50,7 @@ use object_store::{AzblobConnection, GcsConnection, ObjectStore, OssConnection,
 use servers::grpc::builder::GrpcServerBuilder;
 use servers::grpc::greptime_handler::GreptimeRequestHandler;
 use servers::grpc::{FlightCompression, GrpcOptions, GrpcServer, GrpcServerConfig};
+use servers::http::tempo::TempoSearchLimits;
 use servers::http::{HttpOptions, HttpServerBuilder};
 use servers::metrics_handler::MetricsHandler;
 use servers::mysql::server::{MysqlServer, MysqlSpawnConfig, MysqlSpawnRef};
@@ -490,8 +491,10 @@ pub async fn setup_test_http_app_with_frontend_and_slow_query_threshold(
         .with_log_ingest_handler(instance.fe_instance().clone(), None, None)
         .with_logs_handler(instance.fe_instance().clone())
         .with_influxdb_handler(instance.fe_instance().clone())
+        .with_influxql_handler(instance.fe_instance().clone())
         .with_otlp_handler(instance.fe_instance().clone(), true)
         .with_jaeger_handler(instance.fe_instance().clone())
+        .with_tempo_handler(instance.fe_instance().clone(), TempoSearchLimits::default())
         .with_greptime_config_options(instance.opts.to_toml().unwrap())
         .build();
 
@@ -541,8 +544,10 @@ pub async fn setup_test_http_app_with_frontend_and_custom_options(
         .with_log_ingest_handler(instance.fe_instance().clone(), None, None)
         .with_logs_handler(instance.fe_instance().clone())
         .with_influxdb_handler(instance.fe_instance().clone())
+        .with_influxql_handler(instance.fe_instance().clone())
         .with_otlp_handler(instance.fe_instance().clone(), true)
         .with_jaeger_handler(instance.fe_instance().clone())
+        .with_tempo_handler(instance.fe_instance().clone(), TempoSearchLimits::default())
         .with_dashboard_handler(instance.fe_instance().clone())
         .with_greptime_config_options(instance.opts.to_toml().unwrap());
 
@@ -658,6 +663,7 @@ async fn setup_test_prom_app_with_frontend_inner(
             frontend_ref.node_manager().clone(),
             frontend_ref.catalog_manager().clone(),
             frontend_ref.table_flownode_set_cache().clone(),
+            frontend_ref.insert_listener().cloned(),
             true,
             frontend_ref.clone(),
             Duration::from_millis(50),
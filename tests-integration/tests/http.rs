@@ -54,6 +54,11 @@ use opentelemetry_proto::tonic::collector::metrics::v1::ExportMetricsServiceRequ
 use opentelemetry_proto::tonic::collector::trace::v1::{
     ExportTraceServiceRequest, ExportTraceServiceResponse,
 };
+use opentelemetry_proto::tonic::metrics::v1::exponential_histogram_data_point::Buckets;
+use opentelemetry_proto::tonic::metrics::v1::{
+    AggregationTemporality, ExponentialHistogram, ExponentialHistogramDataPoint, Metric,
+    ResourceMetrics, ScopeMetrics, metric,
+};
 use pipeline::GREPTIME_INTERNAL_TRACE_PIPELINE_V1_NAME;
 use prost::Message;
 use serde_json::{Value, json};
@@ -161,6 +166,7 @@ macro_rules! http_tests {
 
                 test_otlp_metrics_new,
                 test_otlp_metric_translation_strategies,
+                test_otlp_exponential_histogram,
                 test_otlp_traces_v0,
                 test_otlp_traces_v1,
                 test_otlp_traces_v1_entity_graph,
@@ -178,10 +184,13 @@ macro_rules! http_tests {
                 test_log_query,
                 test_jaeger_query_api,
                 test_jaeger_query_api_for_trace_v1,
+                test_tempo_query_api,
+                test_tempo_structural_search_paging,
 
                 test_influxdb_write,
                 test_influxdb_write_with_hints,
                 test_influxdb_write_with_append_mode_hint,
+                test_influxdb_query,
                 test_http_memory_limit,
             );
         )*
@@ -2133,6 +2142,7 @@ enable_telemetry = true
 auto_create_table = true
 max_in_flight_write_bytes = "0KiB"
 write_bytes_exhausted_policy = "wait"
+admin_users = []
 init_regions_in_background = false
 init_regions_parallelism = 16
 heartbeat_env_vars = []
@@ -2201,6 +2211,11 @@ default_merge_mode = "last_non_null"
 [jaeger]
 enable = true
 
+[tempo]
+enable = true
+max_search_limit = 1000
+max_spans_per_span_set = 100
+
 [prom_store]
 enable = true
 with_metric_engine = true
@@ -2331,6 +2346,13 @@ threshold = "1s"
 sample_ratio = 1.0
 ttl = "2months 29days 2h 52m 48s"
 
+[promql_cache]
+enable = false
+split_interval = "1h"
+max_freshness = "10m"
+capacity = "256MiB"
+ttl = "5m"
+
 [query]
 parallelism = 0
 allow_query_fallback = false
@@ -6790,6 +6812,85 @@ pub async fn test_otlp_metric_translation_strategies(store_type: StorageType) {
     guard.remove_all().await;
 }
 
+pub async fn test_otlp_exponential_histogram(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (app, mut guard) =
+        setup_test_http_app_with_frontend(store_type, "test_otlp_exponential_histogram").await;
+    let client = TestClient::new(app).await;
+
+    // Scale 0 buckets: 2 observations in (1, 2] and 2 in (2, 4].
+    let req = ExportMetricsServiceRequest {
+        resource_metrics: vec![ResourceMetrics {
+            scope_metrics: vec![ScopeMetrics {
+                metrics: vec![Metric {
+                    name: "otlp_exp_latency".to_string(),
+                    data: Some(metric::Data::ExponentialHistogram(ExponentialHistogram {
+                        data_points: vec![ExponentialHistogramDataPoint {
+                            start_time_unix_nano: 1_000_000_000,
+                            time_unix_nano: 10_000_000_000,
+                            count: 4,
+                            sum: Some(10.0),
+                            scale: 0,
+                            positive: Some(Buckets {
+                                offset: 0,
+                                bucket_counts: vec![2, 2],
+                            }),
+                            ..Default::default()
+                        }],
+                        aggregation_temporality: AggregationTemporality::Cumulative as i32,
+                    })),
+                    ..Default::default()
+                }],
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+    };
+
+    let res = send_req(
+        &client,
+        vec![(
+            HeaderName::from_static("content-type"),
+            HeaderValue::from_static("application/x-protobuf"),
+        )],
+        "/v1/otlp/v1/metrics",
+        req.encode_to_vec(),
+        false,
+    )
+    .await;
+    assert_eq!(StatusCode::OK, res.status());
+
+    validate_data(
+        "otlp_exponential_histogram_rows",
+        &client,
+        "select greptime_timestamp from otlp_exp_latency;",
+        "[[10000]]",
+    )
+    .await;
+
+    // The median is the upper bound of the first bucket, the max the upper bound of the last.
+    for (quantile, expected) in [(0.5, 2.0), (1.0, 4.0)] {
+        let query = encode(&format!("histogram_quantile({quantile}, otlp_exp_latency)"));
+        let res = client
+            .get(&format!("/v1/promql?query={query}&start=10&end=10&step=1s"))
+            .send()
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+        let body = serde_json::from_str::<GreptimedbV1Response>(&res.text().await).unwrap();
+        let Some(GreptimeQueryOutput::Records(records)) = body.output().first() else {
+            panic!("expected records, got {body:?}");
+        };
+        let rows = records.rows();
+        assert_eq!(rows.len(), 1, "{rows:?}");
+        assert!(
+            rows[0].iter().any(|value| value.as_f64() == Some(expected)),
+            "quantile {quantile}: {rows:?}"
+        );
+    }
+
+    guard.remove_all().await;
+}
+
 pub async fn test_otlp_traces_v0(store_type: StorageType) {
     // init
     common_telemetry::init_default_ut_logging();
@@ -10247,6 +10348,364 @@ pub async fn test_jaeger_query_api_for_trace_v1(store_type: StorageType) {
 
     guard.remove_all().await;
 }
+pub async fn test_tempo_query_api(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (app, mut guard) =
+        setup_test_http_app_with_frontend(store_type, "test_tempo_query_api").await;
+
+    let client = TestClient::new(app).await;
+
+    // Test empty response before writing any traces.
+    let res = client.get("/v1/tempo/api/search").send().await;
+    assert_eq!(StatusCode::OK, res.status());
+    let resp: Value = serde_json::from_str(&res.text().await).unwrap();
+    assert_eq!(
+        json!({"traces": [], "metrics": {"inspectedTraces": 0, "inspectedSpans": 0}}),
+        resp
+    );
+
+    let content = r#"
+    {
+        "resourceSpans": [
+            {
+                "resource": {
+                    "attributes": [
+                        {
+                            "key": "service.name",
+                            "value": {
+                                "stringValue": "test-tempo-query-api"
+                            }
+                        }
+                    ]
+                },
+                "scopeSpans": [
+                    {
+                        "scope": {
+                            "name": "test-tempo",
+                            "version": "1.0.0"
+                        },
+                        "spans": [
+                            {
+                                "traceId": "5611dce1bc9ebed65352d99a027b0a01",
+                                "spanId": "0000000000000001",
+                                "name": "GET /api",
+                                "kind": 2,
+                                "startTimeUnixNano": "0",
+                                "endTimeUnixNano": "0",
+                                "attributes": [
+                                    {
+                                        "key": "http.method",
+                                        "value": {
+                                            "stringValue": "GET"
+                                        }
+                                    },
+                                    {
+                                        "key": "http.status_code",
+                                        "value": {
+                                            "intValue": "500"
+                                        }
+                                    }
+                                ],
+                                "status": {
+                                    "code": 0
+                                }
+                            },
+                            {
+                                "traceId": "5611dce1bc9ebed65352d99a027b0a01",
+                                "spanId": "0000000000000002",
+                                "parentSpanId": "0000000000000001",
+                                "name": "query-db",
+                                "kind": 3,
+                                "startTimeUnixNano": "0",
+                                "endTimeUnixNano": "0",
+                                "attributes": [],
+                                "status": {
+                                    "message": "timeout",
+                                    "code": 2
+                                }
+                            },
+                            {
+                                "traceId": "5611dce1bc9ebed65352d99a027b0a02",
+                                "spanId": "0000000000000003",
+                                "name": "GET /health",
+                                "kind": 2,
+                                "startTimeUnixNano": "0",
+                                "endTimeUnixNano": "0",
+                                "attributes": [
+                                    {
+                                        "key": "http.method",
+                                        "value": {
+                                            "stringValue": "GET"
+                                        }
+                                    },
+                                    {
+                                        "key": "http.status_code",
+                                        "value": {
+                                            "intValue": "200"
+                                        }
+                                    }
+                                ],
+                                "status": {
+                                    "code": 1
+                                }
+                            }
+                        ]
+                    }
+                ]
+            }
+        ]
+    }
+    "#;
+
+    let mut req: ExportTraceServiceRequest = serde_json::from_str(content).unwrap();
+    let now = Utc::now().timestamp_nanos_opt().unwrap() as u64;
+    for resource_span in req.resource_spans.iter_mut() {
+        for scope_span in resource_span.scope_spans.iter_mut() {
+            for (i, span) in scope_span.spans.iter_mut().enumerate() {
+                span.start_time_unix_nano = now - 5_000_000_000 + i as u64 * 1_000_000;
+                span.end_time_unix_nano = span.start_time_unix_nano + 100_000_000;
+            }
+        }
+    }
+    let res = send_req(
+        &client,
+        vec![
+            (
+                HeaderName::from_static("content-type"),
+                HeaderValue::from_static("application/x-protobuf"),
+            ),
+            (
+                HeaderName::from_static("x-greptime-log-pipeline-name"),
+                HeaderValue::from_static(GREPTIME_INTERNAL_TRACE_PIPELINE_V1_NAME),
+            ),
+        ],
+        "/v1/otlp/v1/traces",
+        req.encode_to_vec(),
+        false,
+    )
+    .await;
+    assert_eq!(StatusCode::OK, res.status());
+
+    let search = |q: &str| {
+        let url = format!(
+            "/v1/tempo/api/search?q={}",
+            url::form_urlencoded::byte_serialize(q.as_bytes()).collect::<String>()
+        );
+        client.get(&url)
+    };
+
+    // Test `/api/search` API with TraceQL.
+    let res = search("{ status = error }").send().await;
+    assert_eq!(StatusCode::OK, res.status());
+    let resp: Value = serde_json::from_str(&res.text().await).unwrap();
+    let traces = resp["traces"].as_array().unwrap();
+    assert_eq!(1, traces.len());
+    assert_eq!("5611dce1bc9ebed65352d99a027b0a01", traces[0]["traceID"]);
+    assert_eq!("test-tempo-query-api", traces[0]["rootServiceName"]);
+    assert_eq!("GET /api", traces[0]["rootTraceName"]);
+    assert_eq!(1, traces[0]["spanSet"]["matched"]);
+    assert_eq!(
+        "0000000000000002",
+        traces[0]["spanSet"]["spans"][0]["spanID"]
+    );
+
+    let res = search("{ span.http.status_code >= 500 && kind = server } >> { status = error }")
+        .send()
+        .await;
+    let resp: Value = serde_json::from_str(&res.text().await).unwrap();
+    assert_eq!(1, resp["traces"].as_array().unwrap().len());
+
+    let res = search(r#"{ name = "query-db" } >> { kind = server }"#)
+        .send()
+        .await;
+    let resp: Value = serde_json::from_str(&res.text().await).unwrap();
+    assert!(resp["traces"].as_array().unwrap().is_empty());
+
+    let res = search(r#"{ .service.name =~ "test-tempo.*" && name != "query-db" }"#)
+        .send()
+        .await;
+    let resp: Value = serde_json::from_str(&res.text().await).unwrap();
+    assert_eq!(2, resp["traces"].as_array().unwrap().len());
+
+    let res = search("{ name = }").send().await;
+    assert_eq!(StatusCode::BAD_REQUEST, res.status());
+
+    // Test `/api/search` API with tags.
+    let res = client
+        .get("/v1/tempo/api/search?tags=http.method%3DGET%20http.status_code%3D200")
+        .send()
+        .await;
+    let resp: Value = serde_json::from_str(&res.text().await).unwrap();
+    let traces = resp["traces"].as_array().unwrap();
+    assert_eq!(1, traces.len());
+    assert_eq!("5611dce1bc9ebed65352d99a027b0a02", traces[0]["traceID"]);
+
+    // Test `/api/traces/{trace_id}` API.
+    let res = client
+        .get("/v1/tempo/api/traces/5611dce1bc9ebed65352d99a027b0a01")
+        .send()
+        .await;
+    assert_eq!(StatusCode::OK, res.status());
+    let resp: Value = serde_json::from_str(&res.text().await).unwrap();
+    let batches = resp["batches"].as_array().unwrap();
+    assert_eq!(1, batches.len());
+    assert_eq!(
+        json!([{"key": "service.name", "value": {"stringValue": "test-tempo-query-api"}}]),
+        batches[0]["resource"]["attributes"]
+    );
+    let spans = batches[0]["scopeSpans"][0]["spans"].as_array().unwrap();
+    assert_eq!(2, spans.len());
+    assert_eq!("VhHc4byevtZTUtmaAnsKAQ==", spans[0]["traceId"]);
+    assert_eq!("SPAN_KIND_SERVER", spans[0]["kind"]);
+    assert_eq!("AAAAAAAAAAE=", spans[1]["parentSpanId"]);
+    assert_eq!("STATUS_CODE_ERROR", spans[1]["status"]["code"]);
+
+    let res = client
+        .get("/v1/tempo/api/traces/5611dce1bc9ebed65352d99a027b0aff")
+        .send()
+        .await;
+    assert_eq!(StatusCode::NOT_FOUND, res.status());
+
+    // Test `/api/search/tags` API.
+    let res = client.get("/v1/tempo/api/search/tags").send().await;
+    assert_eq!(StatusCode::OK, res.status());
+    let resp: Value = serde_json::from_str(&res.text().await).unwrap();
+    assert_eq!(
+        json!({"tagNames": ["http.method", "http.status_code", "service.name"]}),
+        resp
+    );
+
+    // Test `/api/search/tag/{tag}/values` API.
+    let res = client
+        .get("/v1/tempo/api/search/tag/service.name/values")
+        .send()
+        .await;
+    assert_eq!(StatusCode::OK, res.status());
+    let resp: Value = serde_json::from_str(&res.text().await).unwrap();
+    assert_eq!(json!({"tagValues": ["test-tempo-query-api"]}), resp);
+
+    let res = client
+        .get("/v1/tempo/api/search/tag/span.http.status_code/values")
+        .send()
+        .await;
+    let resp: Value = serde_json::from_str(&res.text().await).unwrap();
+    assert_eq!(json!({"tagValues": ["200", "500"]}), resp);
+
+    guard.remove_all().await;
+}
+
+pub async fn test_tempo_structural_search_paging(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (app, mut guard) =
+        setup_test_http_app_with_frontend(store_type, "test_tempo_structural_search_paging").await;
+
+    let client = TestClient::new(app).await;
+
+    let now = Utc::now().timestamp_nanos_opt().unwrap() as u64;
+    let span =
+        |trace_id: String, span_id: &str, parent_span_id: &str, kind: i32, code: i32, ts: u64| {
+            json!({
+                "traceId": trace_id,
+                "spanId": span_id,
+                "parentSpanId": parent_span_id,
+                "name": "span",
+                "kind": kind,
+                "startTimeUnixNano": ts.to_string(),
+                "endTimeUnixNano": (ts + 1_000_000).to_string(),
+                "attributes": [],
+                "status": {"code": code}
+            })
+        };
+    // The oldest trace has an error span under a server span.
+    let mut spans = vec![
+        span(
+            "5611dce1bc9ebed65352d99a027b0b00".to_string(),
+            "0000000000000001",
+            "",
+            2,
+            0,
+            now - 10_000_000_000,
+        ),
+        span(
+            "5611dce1bc9ebed65352d99a027b0b00".to_string(),
+            "0000000000000002",
+            "0000000000000001",
+            3,
+            2,
+            now - 10_000_000_000,
+        ),
+    ];
+    // More recent traces have error spans without server spans, so they are candidates that
+    // fail `{ kind = server } >> { status = error }`. There are more of them than the
+    // candidates fetched in the first round for `limit=1`.
+    for i in 1..=6u64 {
+        spans.push(span(
+            format!("5611dce1bc9ebed65352d99a027b0b{i:02}"),
+            "0000000000000003",
+            "",
+            3,
+            2,
+            now - 5_000_000_000 + i * 1_000_000,
+        ));
+    }
+    let req: ExportTraceServiceRequest = serde_json::from_value(json!({
+        "resourceSpans": [{
+            "resource": {
+                "attributes": [{"key": "service.name", "value": {"stringValue": "test-tempo-paging"}}]
+            },
+            "scopeSpans": [{"scope": {"name": "test-tempo", "version": "1.0.0"}, "spans": spans}]
+        }]
+    }))
+    .unwrap();
+    let res = send_req(
+        &client,
+        vec![
+            (
+                HeaderName::from_static("content-type"),
+                HeaderValue::from_static("application/x-protobuf"),
+            ),
+            (
+                HeaderName::from_static("x-greptime-log-pipeline-name"),
+                HeaderValue::from_static(GREPTIME_INTERNAL_TRACE_PIPELINE_V1_NAME),
+            ),
+        ],
+        "/v1/otlp/v1/traces",
+        req.encode_to_vec(),
+        false,
+    )
+    .await;
+    assert_eq!(StatusCode::OK, res.status());
+
+    let q =
+        url::form_urlencoded::byte_serialize("{ kind = server } >> { status = error }".as_bytes())
+            .collect::<String>();
+    let res = client
+        .get(&format!("/v1/tempo/api/search?q={q}&limit=1"))
+        .send()
+        .await;
+    assert_eq!(StatusCode::OK, res.status());
+    let resp: Value = serde_json::from_str(&res.text().await).unwrap();
+    let traces = resp["traces"].as_array().unwrap();
+    assert_eq!(1, traces.len());
+    assert_eq!("5611dce1bc9ebed65352d99a027b0b00", traces[0]["traceID"]);
+    // Inspects all candidates in two rounds.
+    assert_eq!(7, resp["metrics"]["inspectedTraces"]);
+
+    // `limit` and `spss` are clamped instead of overflowing.
+    let res = client
+        .get(&format!(
+            "/v1/tempo/api/search?q={q}&limit={}&spss={}",
+            usize::MAX,
+            usize::MAX
+        ))
+        .send()
+        .await;
+    assert_eq!(StatusCode::OK, res.status());
+    let resp: Value = serde_json::from_str(&res.text().await).unwrap();
+    assert_eq!(1, resp["traces"].as_array().unwrap().len());
+
+    guard.remove_all().await;
+}
 
 pub async fn test_influxdb_write(store_type: StorageType) {
     common_telemetry::init_default_ut_logging();
@@ -10294,6 +10753,140 @@ pub async fn test_influxdb_write(store_type: StorageType) {
     guard.remove_all().await;
 }
 
+pub async fn test_influxdb_query(store_type: StorageType) {
+    common_telemetry::init_default_ut_logging();
+    let (app, mut guard) =
+        setup_test_http_app_with_frontend(store_type, "test_influxdb_query").await;
+
+    let client = TestClient::new(app).await;
+    let result = client
+        .post("/v1/influxdb/write?db=public")
+        .body(
+            "influxql_cpu,host=a,region=us usage=1.0 1000000000\n\
+             influxql_cpu,host=a,region=us usage=3.0 61000000000\n\
+             influxql_cpu,host=b,region=eu usage=5.0 2000000000",
+        )
+        .send()
+        .await;
+    assert_eq!(result.status(), 204);
+
+    async fn query(client: &TestClient, q: &str, epoch: Option<&str>) -> (StatusCode, Value) {
+        let mut url = format!("/v1/influxdb/query?db=public&q={}", encode(q));
+        if let Some(epoch) = epoch {
+            url.push_str(&format!("&epoch={epoch}"));
+        }
+        let res = client.get(&url).send().await;
+        let status = res.status();
+        (status, serde_json::from_str(&res.text().await).unwrap())
+    }
+
+    let (status, body) = query(
+        &client,
+        "SELECT usage FROM influxql_cpu WHERE host = 'a'",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        json!({"results": [{"statement_id": 0, "series": [{
+            "name": "influxql_cpu",
+            "columns": ["time", "usage"],
+            "values": [["1970-01-01T00:00:01Z", 1.0], ["1970-01-01T00:01:01Z", 3.0]]
+        }]}]}),
+        body
+    );
+
+    let (status, body) = query(
+        &client,
+        "SELECT mean(usage) FROM influxql_cpu GROUP BY time(1m), host fill(none)",
+        Some("s"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        json!({"results": [{"statement_id": 0, "series": [
+            {
+                "name": "influxql_cpu",
+                "tags": {"host": "a"},
+                "columns": ["time", "mean"],
+                "values": [[0, 1.0], [60, 3.0]]
+            },
+            {
+                "name": "influxql_cpu",
+                "tags": {"host": "b"},
+                "columns": ["time", "mean"],
+                "values": [[0, 5.0]]
+            }
+        ]}]}),
+        body
+    );
+
+    let (status, body) = query(
+        &client,
+        "SHOW MEASUREMENTS WITH MEASUREMENT = influxql_cpu; \
+         SHOW TAG KEYS FROM influxql_cpu; \
+         SHOW FIELD KEYS FROM influxql_cpu; \
+         SHOW TAG VALUES FROM influxql_cpu WITH KEY = host",
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        json!({"results": [
+            {"statement_id": 0, "series": [{
+                "name": "measurements",
+                "columns": ["name"],
+                "values": [["influxql_cpu"]]
+            }]},
+            {"statement_id": 1, "series": [{
+                "name": "influxql_cpu",
+                "columns": ["tagKey"],
+                "values": [["host"], ["region"]]
+            }]},
+            {"statement_id": 2, "series": [{
+                "name": "influxql_cpu",
+                "columns": ["fieldKey", "fieldType"],
+                "values": [["usage", "float"]]
+            }]},
+            {"statement_id": 3, "series": [{
+                "name": "influxql_cpu",
+                "columns": ["key", "value"],
+                "values": [["host", "a"], ["host", "b"]]
+            }]}
+        ]}),
+        body
+    );
+
+    // POST with form parameters.
+    let res = client
+        .post("/v1/influxdb/query")
+        .header("Content-Type", "application/x-www-form-urlencoded")
+        .body(format!(
+            "db=public&q={}",
+            encode("SELECT count(usage) FROM influxql_cpu WHERE region = 'eu'")
+        ))
+        .send()
+        .await;
+    assert_eq!(res.status(), StatusCode::OK);
+    let body: Value = serde_json::from_str(&res.text().await).unwrap();
+    assert_eq!(
+        json!([["1970-01-01T00:00:00Z", 1]]),
+        body["results"][0]["series"][0]["values"]
+    );
+
+    // Errors of a statement are reported in its result.
+    let (status, body) = query(&client, "SELECT usage FROM not_exist", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["results"][0]["error"].is_string(), "{body}");
+
+    // Invalid queries are rejected.
+    let (status, body) = query(&client, "SELECT FROM", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].is_string(), "{body}");
+
+    guard.remove_all().await;
+}
+
 async fn validate_data(test_name: &str, client: &TestClient, sql: &str, expected: &str) {
     let res = client
         .get(format!("/v1/sql?sql={sql}").as_str())
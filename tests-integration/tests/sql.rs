@@ -24,6 +24,7 @@ use common_frontend::slow_query_event::{
     SLOW_QUERY_TABLE_NAME, SLOW_QUERY_TABLE_QUERY_COLUMN_NAME,
     SLOW_QUERY_TABLE_SCHEMA_NAME_COLUMN_NAME, SLOW_QUERY_TABLE_THRESHOLD_COLUMN_NAME,
 };
+use futures::SinkExt;
 use sqlx::mysql::{MySqlConnection, MySqlDatabaseError, MySqlPoolOptions};
 use sqlx::postgres::{PgDatabaseError, PgPoolOptions};
 use sqlx::types::Decimal;
@@ -81,6 +82,7 @@ macro_rules! sql_tests {
                 test_postgres_crud,
                 test_postgres_timezone,
                 test_postgres_bytea,
+                test_postgres_copy_from_stdin,
                 test_postgres_slow_query,
                 test_postgres_datestyle,
                 test_postgres_intervalstyle,
@@ -875,6 +877,62 @@ pub async fn test_postgres_bytea(store_type: StorageType) {
     guard.remove_all().await;
 }
 
+pub async fn test_postgres_copy_from_stdin(store_type: StorageType) {
+    let (mut guard, fe_pg_server) =
+        setup_pg_server(store_type, "test_postgres_copy_from_stdin").await;
+    let addr = fe_pg_server.bind_addr().unwrap().to_string();
+
+    let (client, connection) = tokio_postgres::connect(&format!("postgres://{addr}/public"), NoTls)
+        .await
+        .unwrap();
+    let (tx, rx) = tokio::sync::oneshot::channel();
+    tokio::spawn(async move {
+        connection.await.unwrap();
+        tx.send(()).unwrap();
+    });
+    let _ = client
+        .simple_query("CREATE TABLE copy_test(host STRING, val DOUBLE, ts TIMESTAMP TIME INDEX)")
+        .await
+        .unwrap();
+
+    // `copy_in` goes through the extended query protocol.
+    let sink = client
+        .copy_in::<_, &'static [u8]>("COPY copy_test (host, val, ts) FROM STDIN")
+        .await
+        .unwrap();
+    let mut sink = std::pin::pin!(sink);
+    sink.send(&b"a\t1.5\t2024-01-01 00:00:00\nb\t\\N\t"[..])
+        .await
+        .unwrap();
+    sink.send(&b"2024-01-01 00:00:01\n"[..]).await.unwrap();
+    assert_eq!(sink.as_mut().finish().await.unwrap(), 2);
+
+    let rows = client
+        .query("SELECT host, val FROM copy_test ORDER BY ts", &[])
+        .await
+        .unwrap();
+    let rows = rows
+        .iter()
+        .map(|row| (row.get::<_, String>(0), row.get::<_, Option<f64>>(1)))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        vec![("a".to_string(), Some(1.5)), ("b".to_string(), None)]
+    );
+
+    let error = client
+        .copy_in::<_, &'static [u8]>("COPY copy_test (host, unknown) FROM STDIN")
+        .await
+        .unwrap_err();
+    assert!(error.as_db_error().unwrap().message().contains("unknown"));
+
+    drop(client);
+    rx.await.unwrap();
+
+    let _ = fe_pg_server.shutdown().await;
+    guard.remove_all().await;
+}
+
 pub async fn test_postgres_slow_query(store_type: StorageType) {
     let (mut guard, fe_pg_server) = setup_pg_server_with_slow_query_threshold(
         store_type,
@@ -0,0 +1,63 @@
+CREATE TABLE host_metrics (
+    host STRING,
+    val DOUBLE,
+    ts TIMESTAMP TIME INDEX,
+    PRIMARY KEY(host)
+);
+
+CREATE TABLE host_regions (
+    host STRING,
+    region STRING,
+    ts TIMESTAMP TIME INDEX,
+    PRIMARY KEY(host)
+);
+
+CREATE TABLE host_metrics_with_region (
+    host STRING,
+    region STRING,
+    val DOUBLE,
+    ts TIMESTAMP TIME INDEX,
+    PRIMARY KEY(host)
+);
+
+-- inner equi-join without aggregation runs in streaming mode
+CREATE FLOW enrich_host_metrics
+SINK TO host_metrics_with_region
+AS
+SELECT m.host, r.region, m.val, m.ts
+FROM host_metrics m
+JOIN host_regions r ON m.host = r.host;
+
+SELECT options FROM INFORMATION_SCHEMA.FLOWS WHERE flow_name = 'enrich_host_metrics';
+
+INSERT INTO host_metrics VALUES
+('h1', 1.0, '2023-01-01 00:00:00'),
+('h2', 2.0, '2023-01-01 00:00:01'),
+('h3', 3.0, '2023-01-01 00:00:02');
+
+INSERT INTO host_regions VALUES
+('h1', 'us', '2023-01-01 00:00:00'),
+('h2', 'eu', '2023-01-01 00:00:00');
+
+-- SQLNESS REPLACE (ADMIN\sFLUSH_FLOW\('\w+'\)\s+\|\n\+-+\+\n\|\s+)[0-9]+\s+\| $1 FLOW_FLUSHED  |
+ADMIN FLUSH_FLOW('enrich_host_metrics');
+
+-- h3 has no region yet so it is not joined
+SELECT * FROM host_metrics_with_region ORDER BY host, ts;
+
+-- a late dimension row joins with the metrics that arrived before it
+INSERT INTO host_regions VALUES
+('h3', 'ap', '2023-01-01 00:00:00');
+
+INSERT INTO host_metrics VALUES
+('h1', 4.0, '2023-01-01 00:00:03');
+
+-- SQLNESS REPLACE (ADMIN\sFLUSH_FLOW\('\w+'\)\s+\|\n\+-+\+\n\|\s+)[0-9]+\s+\| $1 FLOW_FLUSHED  |
+ADMIN FLUSH_FLOW('enrich_host_metrics');
+
+SELECT * FROM host_metrics_with_region ORDER BY host, ts;
+
+DROP FLOW enrich_host_metrics;
+DROP TABLE host_metrics;
+DROP TABLE host_regions;
+DROP TABLE host_metrics_with_region;
@@ -37,6 +37,24 @@ TQL EVAL (0, 15, '5s') bottomk(1, sum(test) by (idc));
 
 TQL EVAL (0, 15, '5s') bottomk(2, sum(test) by (idc));
 
+-- k from a scalar expression --
+TQL EVAL (0, 15, '5s') topk(scalar(count(test)) - 1, test);
+
+TQL EVAL (0, 15, '5s') bottomk(scalar(count(test)) - 1, sum(test) by (idc));
+
+-- fractional k is truncated --
+TQL EVAL (0, 15, '5s') topk(1.5, test);
+
+TQL EVAL (0, 15, '5s') bottomk(scalar(count(test)) / 2, test);
+
+-- NaN k selects nothing --
+TQL EVAL (0, 15, '5s') topk(scalar(test), test);
+
+-- k larger than the group size selects the whole group --
+TQL EVAL (0, 15, '5s') topk(5, test);
+
+TQL EVAL (0, 15, '5s') bottomk(scalar(count(test)) + 2, test);
+
 
 DROP table test;
 
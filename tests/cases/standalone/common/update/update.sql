@@ -0,0 +1,61 @@
+CREATE TABLE monitor (host STRING, ts TIMESTAMP, cpu DOUBLE DEFAULT 0, memory DOUBLE, TIME INDEX (ts), PRIMARY KEY(host));
+
+INSERT INTO monitor(ts, host, cpu, memory) VALUES
+(1655276557000, 'host1', 66.6, 1024),
+(1655276557000, 'host2', 66.6, 1024),
+(1655276558000, 'host1', 77.7, 2048),
+(1655276558000, 'host2', 77.7, 2048);
+
+UPDATE monitor SET cpu = cpu * 2 WHERE host = 'host1';
+
+SELECT ts, host, cpu, memory FROM monitor ORDER BY host, ts;
+
+UPDATE monitor SET memory = NULL, cpu = 0 WHERE ts = 1655276558000::timestamp;
+
+SELECT ts, host, cpu, memory FROM monitor ORDER BY host, ts;
+
+UPDATE monitor SET host = 'host3';
+
+UPDATE monitor SET ts = 0 WHERE host = 'host1';
+
+DROP TABLE monitor;
+
+CREATE TABLE monitor_non_null (host STRING, ts TIMESTAMP, cpu DOUBLE, memory DOUBLE, TIME INDEX (ts), PRIMARY KEY(host)) WITH ('merge_mode'='last_non_null');
+
+INSERT INTO monitor_non_null(ts, host, cpu, memory) VALUES
+(1655276557000, 'host1', 66.6, 1024),
+(1655276557000, 'host2', 66.6, 1024);
+
+UPDATE monitor_non_null SET memory = NULL WHERE host = 'host1';
+
+SELECT ts, host, cpu, memory FROM monitor_non_null ORDER BY host, ts;
+
+ADMIN flush_table('monitor_non_null');
+
+SELECT ts, host, cpu, memory FROM monitor_non_null ORDER BY host, ts;
+
+DROP TABLE monitor_non_null;
+
+CREATE TABLE monitor_not_null (host STRING, ts TIMESTAMP, cpu DOUBLE NOT NULL, memory DOUBLE, TIME INDEX (ts), PRIMARY KEY(host)) WITH ('merge_mode'='last_non_null');
+
+INSERT INTO monitor_not_null(ts, host, cpu, memory) VALUES
+(1655276557000, 'host1', 66.6, 1024),
+(1655276557000, 'host2', 66.6, 1024);
+
+-- The rows assigning NULL replace the existing rows in the same write, so a failed write keeps them.
+-- SQLNESS REPLACE (region\s\d+\(\d+\,\s\d+\)) region
+UPDATE monitor_not_null SET cpu = NULL, memory = NULL WHERE host = 'host1';
+
+SELECT ts, host, cpu, memory FROM monitor_not_null ORDER BY host, ts;
+
+UPDATE monitor_not_null SET cpu = 0 WHERE host = 'host1';
+
+SELECT ts, host, cpu, memory FROM monitor_not_null ORDER BY host, ts;
+
+DROP TABLE monitor_not_null;
+
+CREATE TABLE monitor_append (host STRING, ts TIMESTAMP, cpu DOUBLE, TIME INDEX (ts)) WITH ('append_mode'='true');
+
+UPDATE monitor_append SET cpu = 0;
+
+DROP TABLE monitor_append;